@@ -25,7 +25,9 @@ use chainx_primitives::{AccountId, AssetId, Balance, ReferralId, Signature};
 use chainx_runtime::constants::{currency::DOLLARS, time::DAYS};
 use xp_assets_registrar::Chain;
 use xp_protocol::{NetworkType, PCX, PCX_DECIMALS, X_BTC};
-use xpallet_gateway_bitcoin::{BtcParams, BtcTxVerifier};
+use xpallet_gateway_bitcoin::{
+    types::DEFAULT_COINBASE_DEPOSIT_CONFIRMATIONS, BtcParams, BtcTxVerifier,
+};
 use xpallet_gateway_common::types::TrusteeInfoConfig;
 
 use crate::genesis::assets::{genesis_assets, init_assets, pcx, AssetParams};
@@ -509,6 +511,7 @@ fn mainnet_genesis(
             genesis_trustees: btc_genesis_trustees,
             network_id: bitcoin.network,
             confirmation_number: bitcoin.confirmation_number,
+            coinbase_deposit_confirmations: DEFAULT_COINBASE_DEPOSIT_CONFIRMATIONS,
             genesis_hash: bitcoin.hash(),
             genesis_info: (bitcoin.header(), bitcoin.height),
             params_info: BtcParams::new(
@@ -556,6 +559,7 @@ fn mainnet_genesis(
         ),
         x_assets_bridge: chainx::XAssetsBridgeConfig { admin_key: None },
         x_btc_ledger: Default::default(),
+        x_multisig: Default::default(),
     }
 }
 
@@ -763,6 +767,7 @@ fn malan_genesis(
             genesis_trustees: btc_genesis_trustees,
             network_id: bitcoin.network,
             confirmation_number: bitcoin.confirmation_number,
+            coinbase_deposit_confirmations: DEFAULT_COINBASE_DEPOSIT_CONFIRMATIONS,
             genesis_hash: bitcoin.hash(),
             genesis_info: (bitcoin.header(), bitcoin.height),
             params_info: BtcParams::new(
@@ -811,6 +816,7 @@ fn malan_genesis(
         ),
         x_assets_bridge: malan::XAssetsBridgeConfig { admin_key: None },
         x_btc_ledger: Default::default(),
+        x_multisig: Default::default(),
     }
 }
 
@@ -942,6 +948,7 @@ fn build_dev_genesis(
             genesis_trustees: btc_genesis_trustees,
             network_id: bitcoin.network,
             confirmation_number: bitcoin.confirmation_number,
+            coinbase_deposit_confirmations: DEFAULT_COINBASE_DEPOSIT_CONFIRMATIONS,
             genesis_hash: bitcoin.hash(),
             genesis_info: (bitcoin.header(), bitcoin.height),
             params_info: BtcParams::new(
@@ -989,5 +996,6 @@ fn build_dev_genesis(
         ),
         x_assets_bridge: dev::XAssetsBridgeConfig { admin_key: None },
         x_btc_ledger: Default::default(),
+        x_multisig: Default::default(),
     }
 }
@@ -58,6 +58,9 @@ pub enum Subcommand {
 
     /// Revert the chain to a previous state.
     Revert(sc_cli::RevertCmd),
+
+    /// Build the genesis block from the chain spec and verify its hash.
+    VerifyGenesis(crate::verify_genesis::VerifyGenesisCmd),
 }
 
 #[allow(missing_docs)]
@@ -76,6 +79,20 @@ pub struct RunCmd {
     #[clap(long = "config", value_name = "PATH", parse(from_os_str))]
     pub config_file: Option<std::path::PathBuf>,
 
+    /// Skip checking the genesis block hash of mainnet on startup.
+    ///
+    /// The node refuses to start if the genesis block built from the embedded mainnet
+    /// chain spec is not the published one, unless this flag is specified.
+    #[clap(long)]
+    pub skip_genesis_check: bool,
+
+    /// Keep the bridge records pruned from the chain state in the local database.
+    ///
+    /// The withdrawal records and bitcoin headers removed in the finalized blocks are
+    /// then served by `chainx_getArchivedWithdrawal` and `chainx_getArchivedBtcHeader`.
+    #[clap(long = "chainx-archive")]
+    pub chainx_archive: bool,
+
     #[clap(flatten)]
     pub logger: crate::logger::LoggerParams,
 }
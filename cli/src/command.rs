@@ -16,6 +16,7 @@ use chainx_service::{self as service, new_partial, IdentifyVariant};
 
 use crate::chain_spec;
 use crate::cli::{Cli, Subcommand};
+use crate::verify_genesis;
 
 impl DefaultConfigurationValues for Cli {
     fn p2p_listen_port() -> u16 {
@@ -301,8 +302,19 @@ pub fn run() -> sc_cli::Result<()> {
         None => {
             let runner = cli.create_runner(&cli.run.base)?;
 
+            let shared_params = cli.run.base.shared_params();
+            let chain = shared_params.chain_id(shared_params.is_dev());
+            if verify_genesis::is_mainnet(&chain) && !cli.run.skip_genesis_check {
+                let chain_spec = &runner.config().chain_spec;
+                let state_version = Cli::native_runtime_version(chain_spec).state_version();
+                let genesis = verify_genesis::genesis_header(chain_spec.as_ref(), state_version)?;
+                verify_genesis::ensure_genesis_hash(&genesis, verify_genesis::MAINNET_GENESIS_HASH)
+                    .map_err(|e| format!("{}, use --skip-genesis-check to start anyway", e))?;
+            }
+
+            let archive = cli.run.chainx_archive;
             runner.run_node_until_exit(|config| async move {
-                service::build_full(config).map_err(sc_cli::Error::Service)
+                service::build_full(config, archive).map_err(sc_cli::Error::Service)
             })
         }
         Some(Subcommand::Benchmark(cmd)) => {
@@ -367,6 +379,11 @@ pub fn run() -> sc_cli::Result<()> {
                 Ok(cmd.run(components.client, components.backend))
             })
         }
+        Some(Subcommand::VerifyGenesis(cmd)) => {
+            let chain_spec = load_spec(&cmd.chain)?;
+            let state_version = Cli::native_runtime_version(&chain_spec).state_version();
+            cmd.run(chain_spec.as_ref(), state_version)
+        }
         #[cfg(feature = "try-runtime")]
         Some(Subcommand::TryRuntime(cmd)) => {
             let runner = cli.create_runner(cmd)?;
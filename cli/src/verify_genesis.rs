@@ -0,0 +1,147 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Verification of the genesis block built from the chain spec.
+//!
+//! A chain spec that differs from the published one in any storage item results in
+//! a different genesis block, the node will then fork at genesis silently.
+
+use hex_literal::hex;
+
+use sc_service::ChainSpec;
+use sp_core::H256;
+use sp_runtime::{
+    traits::{BlakeTwo256, Hash, Header as HeaderT},
+    StateVersion,
+};
+
+use chainx_primitives::Header;
+
+/// The genesis block hash of ChainX mainnet.
+pub const MAINNET_GENESIS_HASH: H256 = H256(hex!(
+    "6ac13efb5b368b97b4934cef6edfdd99c2af51ba5109bfb8dacc116f9c584c10"
+));
+
+/// The `verify-genesis` command used to check the genesis block built from the chain spec.
+#[derive(Debug, clap::Parser)]
+pub struct VerifyGenesisCmd {
+    /// Specify the chain specification, the embedded mainnet spec is used by default.
+    #[clap(long, value_name = "CHAIN_SPEC", default_value = "mainnet")]
+    pub chain: String,
+
+    /// The expected genesis block hash.
+    ///
+    /// The built-in genesis hash is used when verifying the mainnet spec.
+    #[clap(long, value_name = "HASH")]
+    pub expect: Option<H256>,
+}
+
+impl VerifyGenesisCmd {
+    /// Runs the command, returns an error if the genesis hash is not the expected one.
+    pub fn run(
+        &self,
+        chain_spec: &dyn ChainSpec,
+        state_version: StateVersion,
+    ) -> sc_cli::Result<()> {
+        let genesis = genesis_header(chain_spec, state_version)?;
+        println!("Genesis state root: {:?}", genesis.state_root());
+        println!("Genesis block hash: {:?}", genesis.hash());
+
+        let expected = match self.expect {
+            Some(expected) => expected,
+            None if is_mainnet(&self.chain) => MAINNET_GENESIS_HASH,
+            None => return Ok(()),
+        };
+        ensure_genesis_hash(&genesis, expected)?;
+        println!("Genesis block hash matches the expected {:?}", expected);
+        Ok(())
+    }
+}
+
+/// Returns true if `chain` refers to the embedded mainnet chain spec.
+pub fn is_mainnet(chain: &str) -> bool {
+    matches!(chain, "" | "mainnet")
+}
+
+/// Builds the genesis block header of `chain_spec`.
+///
+/// This is equivalent to what the client does for building the genesis block at startup.
+pub fn genesis_header(
+    chain_spec: &dyn ChainSpec,
+    state_version: StateVersion,
+) -> Result<Header, String> {
+    let storage = chain_spec.build_storage()?;
+
+    let mut top = storage.top.into_iter().collect::<Vec<_>>();
+    for child in storage.children_default.into_values() {
+        if child.data.is_empty() {
+            continue;
+        }
+        let child_root = BlakeTwo256::trie_root(child.data.into_iter().collect(), state_version);
+        top.push((
+            child.child_info.prefixed_storage_key().into_inner(),
+            child_root.as_ref().to_vec(),
+        ));
+    }
+
+    let state_root = BlakeTwo256::trie_root(top, state_version);
+    let extrinsics_root = BlakeTwo256::trie_root(Vec::new(), state_version);
+
+    Ok(Header::new(
+        0,
+        extrinsics_root,
+        state_root,
+        Default::default(),
+        Default::default(),
+    ))
+}
+
+/// Returns an error if the hash of `genesis` is not `expected`.
+pub fn ensure_genesis_hash(genesis: &Header, expected: H256) -> Result<(), String> {
+    let actual = genesis.hash();
+    if actual != expected {
+        return Err(format!(
+            "Genesis block hash mismatch, expected: {:?}, actual: {:?}",
+            expected, actual
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain_spec::{development_config, DevChainSpec};
+
+    const DEV_STATE_VERSION: StateVersion = StateVersion::V0;
+
+    #[test]
+    fn dev_genesis_should_survive_raw_spec_round_trip() {
+        let spec = development_config().unwrap();
+        let genesis = genesis_header(&spec, DEV_STATE_VERSION).unwrap();
+
+        let raw = spec.as_json(true).unwrap();
+        let reloaded = DevChainSpec::from_json_bytes(raw.into_bytes()).unwrap();
+        let reloaded_genesis = genesis_header(&reloaded, DEV_STATE_VERSION).unwrap();
+
+        assert_eq!(reloaded_genesis, genesis);
+        assert!(ensure_genesis_hash(&reloaded_genesis, genesis.hash()).is_ok());
+    }
+
+    #[test]
+    fn perturbed_spec_should_fail_the_check() {
+        let spec = development_config().unwrap();
+        let expected = genesis_header(&spec, DEV_STATE_VERSION).unwrap().hash();
+
+        let mut raw: serde_json::Value =
+            serde_json::from_str(&spec.as_json(true).unwrap()).unwrap();
+        let top = raw["genesis"]["raw"]["top"].as_object_mut().unwrap();
+        let (_, value) = top.iter_mut().next().unwrap();
+        *value = serde_json::Value::String(format!("{}00", value.as_str().unwrap()));
+
+        let perturbed = DevChainSpec::from_json_bytes(raw.to_string().into_bytes()).unwrap();
+        let genesis = genesis_header(&perturbed, DEV_STATE_VERSION).unwrap();
+
+        assert_ne!(genesis.hash(), expected);
+        assert!(ensure_genesis_hash(&genesis, expected).is_err());
+    }
+}
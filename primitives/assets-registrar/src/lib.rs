@@ -72,6 +72,17 @@ pub trait RegistrarHandler {
     fn on_deregister(_asset_id: &AssetId) -> DispatchResult {
         Ok(())
     }
+
+    /// Called when a new asset is added with a request to create its trading pair against
+    /// the native asset.
+    fn on_register_trading_pair(
+        _asset_id: &AssetId,
+        _pip_decimals: u32,
+        _tick_decimals: u32,
+        _initial_price: u128,
+    ) -> DispatchResult {
+        Ok(())
+    }
 }
 
 #[impl_trait_for_tuples::impl_for_tuples(30)]
@@ -85,4 +96,14 @@ impl RegistrarHandler for Tuple {
         for_tuples!( #( Tuple::on_deregister(asset_id)?; )* );
         Ok(())
     }
+
+    fn on_register_trading_pair(
+        asset_id: &AssetId,
+        pip_decimals: u32,
+        tick_decimals: u32,
+        initial_price: u128,
+    ) -> DispatchResult {
+        for_tuples!( #( Tuple::on_register_trading_pair(asset_id, pip_decimals, tick_decimals, initial_price)?; )* );
+        Ok(())
+    }
 }
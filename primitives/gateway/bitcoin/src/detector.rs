@@ -13,7 +13,11 @@ use light_bitcoin::{
 };
 
 use crate::{
-    types::{BtcDepositInfo, BtcTxMetaType, OpReturnAccount, TrusteePair},
+    extractor::extract_channel,
+    types::{
+        BtcDepositInfo, BtcTxEvidence, BtcTxMetaType, OpReturnAccount, OpReturnEvidence,
+        TrusteeAddrKind, TrusteePair,
+    },
     utils::{
         extract_addr_from_transaction, extract_opreturn_data, extract_output_addr, is_trustee_addr,
     },
@@ -64,12 +68,74 @@ impl BtcTxTypeDetector {
         AccountId: Debug,
         Extractor: Fn(&[u8]) -> Option<(OpReturnAccount<AccountId>, Option<ReferralId>)>,
     {
-        // extract input addr from the output of previous transaction
-        let input_addr = prev_tx.and_then(|prev_tx| {
-            let outpoint = &tx.inputs[0].previous_output;
+        let input_addr = self.extract_input_addr(tx, prev_tx);
+        self.detect_transaction_type_by_input_addr(
+            tx,
+            input_addr,
+            extract_account,
+            current_trustee_pair,
+            prev_trustee_pair,
+        )
+    }
+
+    /// Detect X-BTC transaction type like `detect_transaction_type`, along with the evidence
+    /// for detecting the type, which is used for debugging the misclassification.
+    ///
+    /// The input address is resolved only once and shared by the detection and the evidence.
+    pub fn detect_transaction_type_with_evidence<AccountId, Extractor>(
+        &self,
+        tx: &Transaction,
+        prev_tx: Option<&Transaction>,
+        extract_account: Extractor,
+        current_trustee_pair: TrusteePair,
+        prev_trustee_pair: Option<TrusteePair>,
+    ) -> (BtcTxMetaType<AccountId>, BtcTxEvidence)
+    where
+        AccountId: Debug,
+        Extractor: Fn(&[u8]) -> Option<(OpReturnAccount<AccountId>, Option<ReferralId>)>,
+    {
+        let input_addr = self.extract_input_addr(tx, prev_tx);
+        let evidence = self.collect_evidence(
+            tx,
+            input_addr,
+            &extract_account,
+            current_trustee_pair,
+            prev_trustee_pair,
+        );
+        let meta_type = self.detect_transaction_type_by_input_addr(
+            tx,
+            input_addr,
+            extract_account,
+            current_trustee_pair,
+            prev_trustee_pair,
+        );
+        (meta_type, evidence)
+    }
+
+    // extract input addr from the output of previous transaction
+    fn extract_input_addr(
+        &self,
+        tx: &Transaction,
+        prev_tx: Option<&Transaction>,
+    ) -> Option<Address> {
+        prev_tx.zip(tx.inputs.first()).and_then(|(prev_tx, input)| {
+            let outpoint = &input.previous_output;
             extract_addr_from_transaction(prev_tx, outpoint.index as usize, self.network)
-        });
+        })
+    }
 
+    fn detect_transaction_type_by_input_addr<AccountId, Extractor>(
+        &self,
+        tx: &Transaction,
+        input_addr: Option<Address>,
+        extract_account: Extractor,
+        current_trustee_pair: TrusteePair,
+        prev_trustee_pair: Option<TrusteePair>,
+    ) -> BtcTxMetaType<AccountId>
+    where
+        AccountId: Debug,
+        Extractor: Fn(&[u8]) -> Option<(OpReturnAccount<AccountId>, Option<ReferralId>)>,
+    {
         // detect X-BTC `Withdrawal`/`HotAndCold`/`TrusteeTransition` transaction
         if let Some(input_addr) = input_addr {
             let all_outputs_is_trustee = tx
@@ -158,6 +224,11 @@ impl BtcTxTypeDetector {
     {
         let (op_return, deposit_value) =
             self.parse_deposit_transaction_outputs(tx, extract_account, current_trustee_pair);
+        let channel = if op_return.is_none() {
+            Self::parse_deposit_channel(tx)
+        } else {
+            None
+        };
         // check if deposit value is greater than minimum deposit value.
         if deposit_value >= self.min_deposit {
             // if opreturn.is_none() && input_addr.is_none()
@@ -165,6 +236,7 @@ impl BtcTxTypeDetector {
             BtcTxMetaType::Deposit(BtcDepositInfo {
                 deposit_value,
                 op_return,
+                channel,
                 input_addr,
             })
         } else {
@@ -176,6 +248,71 @@ impl BtcTxTypeDetector {
         }
     }
 
+    // Collect the evidence for detecting the type of transaction from the resolved input addr.
+    fn collect_evidence<AccountId, Extractor>(
+        &self,
+        tx: &Transaction,
+        input_addr: Option<Address>,
+        extract_account: Extractor,
+        current_trustee_pair: TrusteePair,
+        prev_trustee_pair: Option<TrusteePair>,
+    ) -> BtcTxEvidence
+    where
+        AccountId: Debug,
+        Extractor: Fn(&[u8]) -> Option<(OpReturnAccount<AccountId>, Option<ReferralId>)>,
+    {
+        let mut evidence = BtcTxEvidence::default();
+
+        if let Some(input_addr) = input_addr {
+            evidence.input_addr_resolved = true;
+            let (hot_addr, cold_addr) = current_trustee_pair;
+            evidence.input_trustee = if input_addr.hash == hot_addr.hash {
+                Some(TrusteeAddrKind::Hot)
+            } else if input_addr.hash == cold_addr.hash {
+                Some(TrusteeAddrKind::Cold)
+            } else {
+                prev_trustee_pair.and_then(|(last_hot_addr, last_cold_addr)| {
+                    if input_addr.hash == last_hot_addr.hash {
+                        Some(TrusteeAddrKind::LastHot)
+                    } else if input_addr.hash == last_cold_addr.hash {
+                        Some(TrusteeAddrKind::LastCold)
+                    } else {
+                        None
+                    }
+                })
+            };
+        }
+
+        let (hot_addr, cold_addr) = current_trustee_pair;
+        for output in &tx.outputs {
+            let script = Script::new(output.script_pubkey.clone());
+            if script.is_null_data_script() {
+                let valid = extract_opreturn_data(&script)
+                    .filter(|opreturn| {
+                        extract_account(opreturn).is_some() || extract_channel(opreturn).is_some()
+                    })
+                    .is_some();
+                if valid {
+                    evidence.op_return = OpReturnEvidence::Valid;
+                } else if evidence.op_return == OpReturnEvidence::Absent {
+                    evidence.op_return = OpReturnEvidence::Invalid;
+                }
+                continue;
+            }
+            match extract_output_addr(output, self.network) {
+                Some(addr) if addr.hash == hot_addr.hash => {
+                    evidence.hot_outputs = evidence.hot_outputs.saturating_add(1)
+                }
+                Some(addr) if addr.hash == cold_addr.hash => {
+                    evidence.cold_outputs = evidence.cold_outputs.saturating_add(1)
+                }
+                _ => evidence.other_outputs = evidence.other_outputs.saturating_add(1),
+            }
+        }
+
+        evidence
+    }
+
     /// Parse the outputs of X-BTC `Deposit` transaction.
     /// Return the account info that extracted from OP_RETURN data and the deposit value.
     pub fn parse_deposit_transaction_outputs<AccountId, Extractor>(
@@ -228,6 +365,18 @@ impl BtcTxTypeDetector {
         );
         (account_info, deposit_value)
     }
+
+    /// Parse the channel of the first channel-only OP_RETURN in the outputs of X-BTC
+    /// `Deposit` transaction.
+    pub fn parse_deposit_channel(tx: &Transaction) -> Option<ReferralId> {
+        tx.outputs
+            .iter()
+            .map(|output| Script::new(output.script_pubkey.clone()))
+            .filter(|script| script.is_null_data_script())
+            .find_map(|script| {
+                extract_opreturn_data(&script).and_then(|data| extract_channel(&data))
+            })
+    }
 }
 
 #[cfg(test)]
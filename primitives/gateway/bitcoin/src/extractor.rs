@@ -12,14 +12,41 @@ use xp_gateway_common::{
 
 pub use xp_gateway_common::AccountExtractor;
 
+/// The length limits of the channel name in a channel-only OP_RETURN, which are the
+/// same as the limits of the referral id of a validator.
+const MIN_CHANNEL_LEN: usize = 2;
+const MAX_CHANNEL_LEN: usize = 12;
+
 /// A helper struct that implements the `AccountExtractor` trait for Bitcoin OP_RETURN data.
 ///
 /// OP_RETURN data format:
 /// - `account`, e.g. 5VEW3R1T4LR3kDhYwXeeCnYrHRwRaH7E9V1KprypBe68XmY4
 /// - `account@referral`, e.g. 5VEW3R1T4LR3kDhYwXeeCnYrHRwRaH7E9V1KprypBe68XmY4@referral1
+///
+/// The channel-only format `@referral` has no account, see [`extract_channel`].
 #[derive(PartialEq, Eq, Clone)]
 pub struct OpReturnExtractor;
 
+/// Extracts the channel from the channel-only OP_RETURN data `@channel`, e.g. `@polkaworld`.
+///
+/// The depositor of such data is expected to have bound its address already. The channel
+/// name must consist of 2 to 12 ASCII letters, digits, `-` or `_`.
+pub fn extract_channel(data: &[u8]) -> Option<ReferralId> {
+    let channel = data.strip_prefix(b"@")?;
+    let valid = (MIN_CHANNEL_LEN..=MAX_CHANNEL_LEN).contains(&channel.len())
+        && channel
+            .iter()
+            .all(|c| c.is_ascii_alphanumeric() || *c == b'-' || *c == b'_');
+    if !valid {
+        debug!(
+            "[extract_channel] Malformed channel-only data:{:?}",
+            hex::encode(data)
+        );
+        return None;
+    }
+    Some(channel.to_vec())
+}
+
 impl AccountExtractor<AccountId32, ReferralId> for OpReturnExtractor {
     fn extract_account(data: &[u8]) -> Option<(OpReturnAccount<AccountId32>, Option<ReferralId>)> {
         let account_and_referral = data
@@ -62,6 +89,31 @@ impl AccountExtractor<AccountId32, ReferralId> for OpReturnExtractor {
     }
 }
 
+#[test]
+fn test_channel_extractor() {
+    assert_eq!(
+        extract_channel(b"@polkaworld"),
+        Some(b"polkaworld".to_vec())
+    );
+    assert_eq!(extract_channel(b"@pw_2-x"), Some(b"pw_2-x".to_vec()));
+
+    // the account part must be empty
+    assert_eq!(
+        extract_channel(b"5VEW3R1T4LR3kDhYwXeeCnYrHRwRaH7E9V1KprypBe68XmY4@polkaworld"),
+        None
+    );
+    assert_eq!(extract_channel(b"polkaworld"), None);
+    // malformed channel names
+    assert_eq!(extract_channel(b"@"), None);
+    assert_eq!(extract_channel(b"@p"), None);
+    assert_eq!(extract_channel(b"@polkaworld123"), None);
+    assert_eq!(extract_channel(b"@polka@world"), None);
+    assert_eq!(extract_channel(b"@<script>"), None);
+
+    // the channel-only data carries no account
+    assert_eq!(OpReturnExtractor::extract_account(b"@polkaworld"), None);
+}
+
 #[test]
 fn test_opreturn_extractor() {
     use sp_core::{
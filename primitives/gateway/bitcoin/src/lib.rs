@@ -12,6 +12,9 @@ mod types;
 mod utils;
 
 pub use self::detector::BtcTxTypeDetector;
-pub use self::extractor::{AccountExtractor, OpReturnExtractor};
-pub use self::types::{BtcDepositInfo, BtcTxMetaType, BtcTxType, OpReturnAccount};
+pub use self::extractor::{extract_channel, AccountExtractor, OpReturnExtractor};
+pub use self::types::{
+    BtcDepositInfo, BtcTxEvidence, BtcTxMetaType, BtcTxType, OpReturnAccount, OpReturnEvidence,
+    TrusteeAddrKind,
+};
 pub use self::utils::*;
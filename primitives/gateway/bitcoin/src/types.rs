@@ -64,6 +64,61 @@ pub struct BtcDepositInfo<AccountId> {
     pub deposit_value: u64,
     /// The parsed op_return data.
     pub op_return: Option<(OpReturnAccount<AccountId>, Option<ReferralId>)>,
+    /// The channel of the channel-only op_return data, if there is no valid `op_return`.
+    pub channel: Option<ReferralId>,
     /// The input address of deposit transaction.
     pub input_addr: Option<Address>,
 }
+
+/// The trustee address that the first input of transaction spent from.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum TrusteeAddrKind {
+    /// The current hot trustee address.
+    Hot,
+    /// The current cold trustee address.
+    Cold,
+    /// The hot trustee address of last trustee session.
+    LastHot,
+    /// The cold trustee address of last trustee session.
+    LastCold,
+}
+
+/// The state of OP_RETURN outputs of transaction.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum OpReturnEvidence {
+    /// No OP_RETURN output.
+    Absent,
+    /// There are OP_RETURN outputs, but none of them contains valid account info.
+    Invalid,
+    /// An OP_RETURN output contains valid account info.
+    Valid,
+}
+
+impl Default for OpReturnEvidence {
+    fn default() -> Self {
+        OpReturnEvidence::Absent
+    }
+}
+
+/// The evidence collected when detecting the type of transaction.
+///
+/// It's fixed-size, the output counters are saturated at `u8::MAX`.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct BtcTxEvidence {
+    /// Whether the address of first input is resolved from the previous transaction.
+    pub input_addr_resolved: bool,
+    /// The trustee address that the first input spent from, if any.
+    pub input_trustee: Option<TrusteeAddrKind>,
+    /// The number of outputs to the current hot trustee address.
+    pub hot_outputs: u8,
+    /// The number of outputs to the current cold trustee address.
+    pub cold_outputs: u8,
+    /// The number of outputs to the other addresses.
+    pub other_outputs: u8,
+    /// The state of OP_RETURN outputs.
+    pub op_return: OpReturnEvidence,
+}
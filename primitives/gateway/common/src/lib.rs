@@ -10,6 +10,7 @@ use scale_info::{prelude::vec::Vec, TypeInfo};
 use sp_core::{crypto::AccountId32, RuntimeDebug, H160, H256};
 
 use frame_support::log::error;
+use light_bitcoin::keys::Address;
 
 /// OpReturn supports evm and substrate addresses
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
@@ -26,6 +27,16 @@ pub enum OpReturnAccount<AccountId> {
     Named(Vec<u8>, Vec<u8>),
 }
 
+/// The address of a withdrawal on the target chain.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub enum ExternalAddress {
+    /// Bitcoin address validated when the withdrawal was applied.
+    Btc(Address),
+    /// Raw address bytes, e.g. the address of the withdrawals applied before
+    /// the addresses were typed.
+    Named(Vec<u8>),
+}
+
 /// The tokens may not be issued in Chainx, but issued to other chains
 #[derive(PartialEq, Eq, Ord, PartialOrd, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub enum DstChain {
@@ -151,36 +162,163 @@ pub fn from_ss58_check(raw_account: &[u8]) -> Option<AccountId32> {
     }
 
     // Due to current parachain do not allow custom runtime-interface, thus we just could
-    // impl ss58 check in runtime, and ignore ss58 address version and hash checksum checking.
-    // Same as `substrate/primitives/core/src/crypto.rs:trait Ss58Codec`
+    // impl ss58 check in runtime.
+    // Same as `substrate/primitives/core/src/crypto.rs:trait Ss58Codec`, the checksum is
+    // always verified. The addresses of any one byte address type are accepted as before,
+    // besides the ones of the ChainX address type.
     #[cfg(not(feature = "ss58check"))]
     {
-        let mut res: [u8; 32] = Default::default();
-        let len = res.as_mut().len();
-        let d = bs58::decode(raw_account)
-            .into_vec()
-            .map_err(|err| {
+        let chainx_address_type = u16::from(xp_protocol::MAINNET_ADDRESS_FORMAT_ID);
+        match ss58_decode_with_type(raw_account) {
+            Some((address_type, account))
+                if address_type == chainx_address_type || address_type < 64 =>
+            {
+                Some(account)
+            }
+            Some((address_type, _)) => {
                 error!(
-                    "[from_ss58_check] Base58 decode {} error:{}",
-                    hex::encode(raw_account),
-                    err
+                    "[from_ss58_check] Unsupported ss58 address type:{}, data:{:?}",
+                    address_type,
+                    hex::encode(raw_account)
                 );
-                err
-            })
-            .ok()?;
-        if d.len() != len + 3 {
-            // Invalid length.
-            error!(
-                "[from_ss58_check] Bad length, data len:{}, len:{}",
-                d.len(),
-                len
-            );
-            return None;
+                None
+            }
+            None => {
+                error!(
+                    "[from_ss58_check] Bad ss58 address, data:{:?}",
+                    hex::encode(raw_account)
+                );
+                None
+            }
         }
+    }
+}
+
+/// Decode the raw account as a SS58Check address of the `address_type`,
+/// the address type of both one byte and two bytes are supported.
+///
+/// Same as `substrate/primitives/core/src/crypto.rs:trait Ss58Codec`, the checksum is verified.
+pub fn ss58_decode(raw_account: &[u8], address_type: u16) -> Option<AccountId32> {
+    ss58_decode_with_type(raw_account)
+        .filter(|(ident, _)| *ident == address_type)
+        .map(|(_, account)| account)
+}
+
+/// Decode the raw account as a SS58Check address, returns the address type along with
+/// the account, the checksum is verified.
+pub fn ss58_decode_with_type(raw_account: &[u8]) -> Option<(u16, AccountId32)> {
+    const CHECKSUM_LEN: usize = 2;
+    const BODY_LEN: usize = 32;
+
+    let data = bs58::decode(raw_account).into_vec().ok()?;
+    let (prefix_len, ident) = match *data.first()? {
+        ident @ 0..=63 => (1, u16::from(ident)),
+        64..=127 => {
+            // weird bit manipulation owing to the combination of LE encoding and missing two
+            // bits from the left.
+            let (first, second) = (data[0], *data.get(1)?);
+            let lower = (first << 2) | (second >> 6);
+            let upper = second & 0b0011_1111;
+            (2, u16::from(lower) | (u16::from(upper) << 8))
+        }
+        _ => return None,
+    };
+    if data.len() != prefix_len + BODY_LEN + CHECKSUM_LEN {
+        return None;
+    }
+
+    let (payload, checksum) = data.split_at(prefix_len + BODY_LEN);
+    if ss58hash(payload)[0..CHECKSUM_LEN] != *checksum {
+        return None;
+    }
+
+    let mut res = [0u8; BODY_LEN];
+    res.copy_from_slice(&payload[prefix_len..]);
+    Some((ident, res.into()))
+}
+
+fn ss58hash(data: &[u8]) -> [u8; 64] {
+    const PREFIX: &[u8] = b"SS58PRE";
+    let mut input = Vec::with_capacity(PREFIX.len() + data.len());
+    input.extend_from_slice(PREFIX);
+    input.extend_from_slice(data);
+    sp_core_hashing::blake2_512(&input)
+}
 
-        // ignore the ss58 address version and hash checksum checking
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account() -> AccountId32 {
+        let mut key = [0u8; 32];
+        key.copy_from_slice(
+            &hex::decode("f778a69d4166401048acb0f7b2625e9680609f8859c78e3d28e2549f84f0269a")
+                .unwrap(),
+        );
+        key.into()
+    }
+
+    #[test]
+    fn test_ss58_decode() {
+        // mainnet address type
+        assert_eq!(
+            ss58_decode(b"5VEW3R1T4LR3kDhYwXeeCnYrHRwRaH7E9V1KprypBe68XmY4", 44),
+            Some(account())
+        );
+        // two bytes address type
+        assert_eq!(
+            ss58_decode(b"Vdw819AMcGFfAZgdcz3swqZYDTdtwhk9wc9kzJgT9kpGxbPp5", 1284),
+            Some(account())
+        );
+        // wrong address type
+        assert_eq!(
+            ss58_decode(b"5HfBXRveHkfbS6td8TtbSz1zzTeqTurBmbu4bVhDFvu9xwLK", 44),
+            None
+        );
+        assert_eq!(
+            ss58_decode(b"Vdw819AMcGFfAZgdcz3swqZYDTdtwhk9wc9kzJgT9kpGxbPp5", 44),
+            None
+        );
+        // bad checksum
+        assert_eq!(
+            ss58_decode(b"5VEW3R1T4LR3kDhYwXeeCnYrHRwRaH7E9V1KprypBe68XmY5", 44),
+            None
+        );
+        // truncated
+        assert_eq!(
+            ss58_decode(b"5VEW3R1T4LR3kDhYwXeeCnYrHRwRaH7E9V1K", 44),
+            None
+        );
+        assert_eq!(ss58_decode(b"V", 1284), None);
+        assert_eq!(ss58_decode(b"", 44), None);
+    }
 
-        res.as_mut().copy_from_slice(&d[1..len + 1]);
-        Some(res.into())
+    #[cfg(not(feature = "ss58check"))]
+    #[test]
+    fn test_from_ss58_check() {
+        // legacy layout
+        assert_eq!(
+            from_ss58_check(b"5VEW3R1T4LR3kDhYwXeeCnYrHRwRaH7E9V1KprypBe68XmY4"),
+            Some(account())
+        );
+        assert_eq!(
+            from_ss58_check(b"5HfBXRveHkfbS6td8TtbSz1zzTeqTurBmbu4bVhDFvu9xwLK"),
+            Some(account())
+        );
+        // bad checksum
+        assert_eq!(
+            from_ss58_check(b"5VEW3R1T4LR3kDhYwXeeCnYrHRwRaH7E9V1KprypBe68XmY5"),
+            None
+        );
+        // the two bytes address type which is not the ChainX one
+        assert_eq!(
+            from_ss58_check(b"Vdw819AMcGFfAZgdcz3swqZYDTdtwhk9wc9kzJgT9kpGxbPp5"),
+            None
+        );
+        // truncated
+        assert_eq!(from_ss58_check(b"5VEW3R1T4LR3kDhYwXeeCnYrHRwRa"), None);
+        assert_eq!(from_ss58_check(b"5"), None);
+        assert_eq!(from_ss58_check(b""), None);
+        assert_eq!(from_ss58_check(b"0OIl"), None);
     }
 }
@@ -20,5 +20,8 @@ pub const ASSET_TOKEN_NAME_MAX_LEN: usize = 48;
 /// The maximum length of asset description
 pub const ASSET_DESC_MAX_LEN: usize = 128;
 
+/// The maximum length of the source reference of a bridged asset
+pub const ASSET_SOURCE_REF_MAX_LEN: usize = 128;
+
 /// The maximum length of memo
 pub const MEMO_MAX_LEN: usize = 80;
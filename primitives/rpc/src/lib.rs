@@ -1,5 +1,7 @@
 // Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
 
+mod page;
+
 use std::{
     fmt::{Debug, Display},
     result::Result as StdResult,
@@ -9,6 +11,8 @@ use std::{
 pub use jsonrpc_core::{Error, ErrorCode, Result};
 use serde::{de, ser, Deserialize, Serialize};
 
+pub use self::page::{paginate, Page};
+
 /// The call to runtime failed.
 pub const RUNTIME_ERROR: i64 = 1;
 
@@ -24,6 +28,12 @@ pub const RUNTIME_TRUSTEE_DECODE_ERROR: i64 = RUNTIME_TRUSTEE_ERROR + 1;
 /// The trustees are inexistent.
 pub const RUNTIME_TRUSTEE_INEXISTENT_ERROR: i64 = RUNTIME_TRUSTEE_ERROR + 2;
 
+/// The dry run of a runtime upgrade failed.
+pub const DRY_RUN_ERROR: i64 = RUNTIME_ERROR + 200;
+
+/// The runtime at the requested block does not provide the required runtime api version.
+pub const UNSUPPORTED_API_ERROR: i64 = RUNTIME_ERROR + 300;
+
 /// The transaction was not decodable.
 pub const DECODE_ERROR: i64 = 10000;
 
@@ -57,6 +67,33 @@ pub fn trustee_inexistent_rpc_err() -> Error {
     }
 }
 
+/// Converts a failure of the runtime upgrade dry run into an RPC error.
+pub fn dry_run_error_into_rpc_err(err: impl Debug) -> Error {
+    Error {
+        code: ErrorCode::ServerError(DRY_RUN_ERROR),
+        message: "Runtime upgrade dry run failed".into(),
+        data: Some(format!("{:?}", err).into()),
+    }
+}
+
+/// Returns the RPC error of a runtime api older than `version` at the requested block.
+pub fn unsupported_api_rpc_err(api: &str, version: u32) -> Error {
+    Error {
+        code: ErrorCode::ServerError(UNSUPPORTED_API_ERROR),
+        message: "The runtime api is not supported at the requested block".into(),
+        data: Some(format!("{} version {} is required", api, version).into()),
+    }
+}
+
+/// Converts a codec decode error into an RPC error.
+pub fn decode_error_into_rpc_err(err: impl Debug) -> Error {
+    Error {
+        code: ErrorCode::ServerError(DECODE_ERROR),
+        message: "Failed to decode the runtime result".into(),
+        data: Some(format!("{:?}", err).into()),
+    }
+}
+
 /// Converts a hex decode error into an RPC error.
 pub fn hex_decode_error_into_rpc_err(err: impl Debug) -> Error {
     Error {
@@ -89,6 +126,22 @@ impl<T: Display + FromStr> From<T> for RpcU128<T> {
     }
 }
 
+/// Renders the `amount` measured by the smallest unit with the `decimals` precision,
+/// e.g., `123456789` with 8 decimals is rendered as `1.23456789`.
+///
+/// The rendering is done on the decimal string instead of the floating point, so
+/// it's still exact for the amounts that can not be represented by `f64`.
+pub fn display_amount(amount: impl Display, decimals: u8) -> String {
+    let raw = amount.to_string();
+    let decimals = decimals as usize;
+    if decimals == 0 {
+        return raw;
+    }
+    let padded = format!("{:0>width$}", raw, width = decimals + 1);
+    let (integer, fraction) = padded.split_at(padded.len() - decimals);
+    format!("{}.{}", integer, fraction)
+}
+
 /// Number string serialization/deserialization
 pub mod serde_num_str {
     use super::*;
@@ -167,6 +220,31 @@ pub mod serde_text {
     }
 }
 
+/// Optional text serialization/deserialization
+pub mod serde_text_opt {
+    use super::*;
+
+    /// A serializer that encodes the bytes as a string, `None` as null
+    pub fn serialize<S>(value: &Option<Vec<u8>>, serializer: S) -> StdResult<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        match value {
+            Some(value) => super::serde_text::serialize(value, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    /// A deserializer that decodes the string to the bytes (Vec<u8>), null to `None`
+    pub fn deserialize<'de, D>(deserializer: D) -> StdResult<Option<Vec<u8>>, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let data = Option::<String>::deserialize(deserializer)?;
+        Ok(data.map(String::into_bytes))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use serde::{Deserialize, Serialize};
@@ -182,6 +260,29 @@ mod tests {
         assert_eq!(de, test);
     }
 
+    #[test]
+    fn test_display_amount() {
+        use super::display_amount;
+
+        assert_eq!(display_amount(0u128, 0), "0");
+        assert_eq!(display_amount(123_456_789u128, 0), "123456789");
+
+        assert_eq!(display_amount(0u128, 8), "0.00000000");
+        assert_eq!(display_amount(1u128, 8), "0.00000001");
+        assert_eq!(display_amount(123_456_789u128, 8), "1.23456789");
+        assert_eq!(display_amount(100_000_000u128, 8), "1.00000000");
+
+        // 2^53 + 1 can not be represented by f64 exactly.
+        assert_eq!(
+            display_amount(9_007_199_254_740_993u128, 8),
+            "90071992.54740993"
+        );
+        assert_eq!(
+            display_amount(u128::max_value(), 8),
+            "3402823669209384634633746074317.68211455"
+        );
+    }
+
     #[test]
     fn test_serde_hex_attr() {
         #[derive(PartialEq, Debug, Serialize, Deserialize)]
@@ -208,4 +309,22 @@ mod tests {
         let de = serde_json::from_str::<TextTest>(&ser).unwrap();
         assert_eq!(de, test);
     }
+
+    #[test]
+    fn test_serde_text_opt_attr() {
+        #[derive(PartialEq, Debug, Serialize, Deserialize)]
+        struct TextOptTest(#[serde(with = "super::serde_text_opt")] Option<Vec<u8>>);
+
+        let test = TextOptTest(Some(b"bitcoin:mainnet".to_vec()));
+        let ser = serde_json::to_string(&test).unwrap();
+        assert_eq!(ser, "\"bitcoin:mainnet\"");
+        let de = serde_json::from_str::<TextOptTest>(&ser).unwrap();
+        assert_eq!(de, test);
+
+        let test = TextOptTest(None);
+        let ser = serde_json::to_string(&test).unwrap();
+        assert_eq!(ser, "null");
+        let de = serde_json::from_str::<TextOptTest>(&ser).unwrap();
+        assert_eq!(de, test);
+    }
 }
@@ -0,0 +1,100 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+use serde::{Deserialize, Serialize};
+
+/// A page of the list RPC.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<T> {
+    pub page_index: u32,
+    pub page_size: u32,
+    /// The total count of the non-empty pages.
+    pub page_total: u32,
+    pub data: T,
+}
+
+/// Returns the page `page_index` of `items`.
+///
+/// `items` must be sorted by a stable key by the caller, otherwise an item may
+/// jump between the pages across calls.
+pub fn paginate<T>(items: Vec<T>, page_index: u32, page_size: u32) -> Page<Vec<T>> {
+    let page_total = page_total(items.len(), page_size);
+    let data = if page_size == 0 {
+        Vec::new()
+    } else {
+        items
+            .into_iter()
+            .skip((page_index as usize).saturating_mul(page_size as usize))
+            .take(page_size as usize)
+            .collect()
+    };
+    Page {
+        page_index,
+        page_size,
+        page_total,
+        data,
+    }
+}
+
+/// Returns the count of the non-empty pages, an exact multiple of `page_size`
+/// has no trailing empty page.
+fn page_total(total: usize, page_size: u32) -> u32 {
+    if page_size == 0 {
+        return 0;
+    }
+    let page_size = page_size as usize;
+    let pages = total / page_size + usize::from(total % page_size != 0);
+    pages as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paginate_empty_items() {
+        let page = paginate(Vec::<u32>::new(), 0, 10);
+        assert_eq!(page.page_total, 0);
+        assert!(page.data.is_empty());
+
+        let page = paginate(vec![1, 2, 3], 0, 0);
+        assert_eq!(page.page_total, 0);
+        assert!(page.data.is_empty());
+    }
+
+    #[test]
+    fn test_paginate_exact_multiple() {
+        let items = (0..20).collect::<Vec<u32>>();
+        let page = paginate(items.clone(), 1, 10);
+        assert_eq!(page.page_total, 2);
+        assert_eq!(page.data, (10..20).collect::<Vec<_>>());
+
+        // Regression: `total / page_size + 1` used to report a trailing empty page.
+        let page = paginate(items, 2, 10);
+        assert_eq!(page.page_total, 2);
+        assert!(page.data.is_empty());
+    }
+
+    #[test]
+    fn test_paginate_final_partial_page() {
+        let items = (0..25).collect::<Vec<u32>>();
+        let page = paginate(items.clone(), 2, 10);
+        assert_eq!(page.page_total, 3);
+        assert_eq!(page.data, (20..25).collect::<Vec<_>>());
+
+        // Out of range.
+        let page = paginate(items, u32::MAX, u32::MAX);
+        assert_eq!(page.page_total, 1);
+        assert!(page.data.is_empty());
+    }
+
+    #[test]
+    fn test_serde_page() {
+        let page = paginate(vec![1u32, 2, 3], 1, 2);
+        let ser = serde_json::to_string(&page).unwrap();
+        assert_eq!(
+            ser,
+            r#"{"pageIndex":1,"pageSize":2,"pageTotal":2,"data":[3]}"#
+        );
+    }
+}
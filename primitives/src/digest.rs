@@ -0,0 +1,53 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Header digest of the asset total issuance changes.
+//!
+//! The light clients can track the total issuance of the assets by decoding
+//! this digest item from the block headers without executing the blocks.
+
+use codec::{Decode, Encode};
+use sp_runtime::{generic::Digest, RuntimeDebug};
+use sp_std::prelude::Vec;
+
+use crate::{AssetId, DigestItem};
+
+/// Prefix of the `DigestItem::Other` payload carrying the asset totals.
+pub const ASSET_TOTALS_DIGEST_ID: [u8; 4] = *b"xast";
+
+/// The maximum number of entries in one asset totals digest item.
+pub const MAX_ASSET_TOTALS_DIGEST_ENTRIES: usize = 32;
+
+/// The new total issuance of the assets changed in a block.
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug)]
+pub struct AssetTotalsDigest<Balance> {
+    /// List of (asset_id, new_total_issuance), sorted by asset id.
+    pub entries: Vec<(AssetId, Balance)>,
+    /// Whether some changed assets were left out due to the entries cap.
+    pub overflow: bool,
+}
+
+impl<Balance: Encode> AssetTotalsDigest<Balance> {
+    /// Converts into the digest item that is deposited in the header.
+    pub fn to_digest_item(&self) -> DigestItem {
+        let mut data = ASSET_TOTALS_DIGEST_ID.to_vec();
+        self.encode_to(&mut data);
+        DigestItem::Other(data)
+    }
+}
+
+impl<Balance: Decode> AssetTotalsDigest<Balance> {
+    /// Decodes the asset totals from the digest item, if any.
+    pub fn from_digest_item(item: &DigestItem) -> Option<Self> {
+        match item {
+            DigestItem::Other(data) => data
+                .strip_prefix(&ASSET_TOTALS_DIGEST_ID[..])
+                .and_then(|mut payload| Self::decode(&mut payload).ok()),
+            _ => None,
+        }
+    }
+
+    /// Finds and decodes the asset totals from the header digest, if any.
+    pub fn from_digest(digest: &Digest) -> Option<Self> {
+        digest.logs().iter().find_map(Self::from_digest_item)
+    }
+}
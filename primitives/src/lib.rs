@@ -2,9 +2,12 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub mod digest;
+
+use codec::Encode;
 use sp_runtime::{
     generic,
-    traits::{BlakeTwo256, IdentifyAccount, Verify},
+    traits::{BlakeTwo256, Hash as HashT, IdentifyAccount, Verify},
     MultiSignature, OpaqueExtrinsic,
 };
 use sp_std::prelude::Vec;
@@ -13,6 +16,9 @@ use sp_std::prelude::Vec;
 pub type BlockNumber = u32;
 
 /// Alias to 512-bit hash when used in the context of a transaction signature on the chain.
+///
+/// `MultiSignature` accepts the sr25519, ed25519 and ecdsa schemes, the signer is
+/// always identified by the 32-byte `AccountId` whatever the scheme is.
 pub type Signature = MultiSignature;
 
 /// Some way of identifying an account on the chain. We intentionally make it equivalent
@@ -78,3 +84,83 @@ pub type ReferralId = Vec<u8>;
 
 pub type AddrStr = Vec<u8>;
 pub type ChainAddress = Vec<u8>;
+
+// ============================================================================
+// Event topics
+// ============================================================================
+
+/// Returns the topic under which the events of the asset `id` are indexed.
+///
+/// The topic is the hash of the SCALE-encoded asset id, i.e., `blake2_256(id.encode())`
+/// with the `BlakeTwo256` hasher of ChainX, so that the clients could reproduce it to
+/// subscribe the events of a single asset.
+pub fn asset_event_topic<H: HashT>(id: AssetId) -> H::Output {
+    H::hash_of(&id)
+}
+
+/// Returns the topic under which the events related to the account `who` are indexed,
+/// i.e., `blake2_256(who.encode())` with the `BlakeTwo256` hasher of ChainX.
+pub fn account_event_topic<H: HashT, AccountId: Encode>(who: &AccountId) -> H::Output {
+    H::hash_of(who)
+}
+
+#[cfg(test)]
+mod tests {
+    use sp_core::{ed25519, sr25519, Pair};
+    use sp_runtime::traits::{IdentifyAccount, Verify};
+
+    use super::{account_event_topic, asset_event_topic, AccountId, BlakeTwo256, Signature};
+
+    const MSG: &[u8] = b"chainx extrinsic payload";
+
+    fn account_of<P: Pair>(pair: &P) -> AccountId
+    where
+        P::Public: Into<<Signature as Verify>::Signer>,
+    {
+        pair.public().into().into_account()
+    }
+
+    #[test]
+    fn signature_of_each_scheme_should_verify() {
+        let sr = sr25519::Pair::from_string("//Alice", None).unwrap();
+        let sr_sig: Signature = sr.sign(MSG).into();
+        assert!(sr_sig.verify(MSG, &account_of(&sr)));
+
+        let ed = ed25519::Pair::from_string("//Alice", None).unwrap();
+        let ed_sig: Signature = ed.sign(MSG).into();
+        assert!(ed_sig.verify(MSG, &account_of(&ed)));
+
+        // The AccountId is the raw 32-byte public key for both schemes.
+        assert_eq!(AsRef::<[u8; 32]>::as_ref(&account_of(&sr)), &sr.public().0);
+        assert_eq!(AsRef::<[u8; 32]>::as_ref(&account_of(&ed)), &ed.public().0);
+    }
+
+    #[test]
+    fn signature_of_wrong_scheme_should_be_rejected() {
+        let sr = sr25519::Pair::from_string("//Alice", None).unwrap();
+        let ed = ed25519::Pair::from_string("//Alice", None).unwrap();
+
+        // An ed25519 signature can not be verified against a sr25519 account and vice versa.
+        let ed_sig: Signature = ed.sign(MSG).into();
+        assert!(!ed_sig.verify(MSG, &account_of(&sr)));
+
+        let sr_sig: Signature = sr.sign(MSG).into();
+        assert!(!sr_sig.verify(MSG, &account_of(&ed)));
+    }
+
+    #[test]
+    fn event_topic_should_be_blake2_of_encoded_key() {
+        use codec::Encode;
+        use sp_core::{hashing::blake2_256, H256};
+
+        assert_eq!(
+            asset_event_topic::<BlakeTwo256>(1),
+            H256(blake2_256(&1u32.encode()))
+        );
+        let who = AccountId::new([1; 32]);
+        assert_eq!(
+            account_event_topic::<BlakeTwo256, _>(&who),
+            H256(blake2_256(&[1; 32]))
+        );
+    }
+}
@@ -0,0 +1,359 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Hand-maintained registry of the ChainX specific types.
+//!
+//! The client SDKs have to know the field layout of the custom structs and enums
+//! in order to decode the storage items and RPC results. This registry describes
+//! these types in a versioned manner so that the SDKs can generate the type
+//! definitions instead of maintaining them by hand.
+//!
+//! Whenever the shape of a registered type changes, the entry here must be updated
+//! and [`REGISTRY_VERSION`] must be bumped, which is enforced by the unit tests.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+use sp_runtime::RuntimeDebug;
+use sp_std::prelude::Vec;
+
+/// Version of the type registry.
+pub const REGISTRY_VERSION: u32 = 3;
+
+/// Static shape of a registered type.
+#[derive(Copy, Clone, PartialEq, Eq, RuntimeDebug)]
+pub enum TypeShape {
+    /// A struct with the list of (field_name, field_type_name).
+    Struct(&'static [(&'static str, &'static str)]),
+    /// An enum with the list of variant names.
+    Enum(&'static [&'static str]),
+}
+
+/// Static entry of the type registry.
+#[derive(Copy, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct RegisteredType {
+    /// Name of the type, without the generic parameters.
+    pub name: &'static str,
+    /// Shape of the type.
+    pub shape: TypeShape,
+}
+
+/// All the ChainX specific types exposed to the client SDKs.
+pub const REGISTERED_TYPES: &[RegisteredType] = &[
+    // Assets
+    RegisteredType {
+        name: "Chain",
+        shape: TypeShape::Enum(&["ChainX", "Bitcoin", "Ethereum", "Polkadot"]),
+    },
+    RegisteredType {
+        name: "AssetInfo",
+        shape: TypeShape::Struct(&[
+            ("token", "Token"),
+            ("token_name", "Token"),
+            ("chain", "Chain"),
+            ("decimals", "Decimals"),
+            ("desc", "Desc"),
+        ]),
+    },
+    RegisteredType {
+        name: "AssetType",
+        shape: TypeShape::Enum(&[
+            "Usable",
+            "Locked",
+            "Reserved",
+            "ReservedWithdrawal",
+            "ReservedDexSpot",
+            "ReservedScheduled",
+        ]),
+    },
+    RegisteredType {
+        name: "AssetRestrictions",
+        shape: TypeShape::Struct(&[("bits", "u32")]),
+    },
+    RegisteredType {
+        name: "TotalAssetInfo",
+        shape: TypeShape::Struct(&[
+            ("info", "AssetInfo"),
+            ("balance", "BTreeMap<AssetType, Balance>"),
+            ("is_online", "bool"),
+            ("restrictions", "AssetRestrictions"),
+            ("source_ref", "Option<Vec<u8>>"),
+        ]),
+    },
+    // Staking
+    RegisteredType {
+        name: "ValidatorProfile",
+        shape: TypeShape::Struct(&[
+            ("registered_at", "BlockNumber"),
+            ("is_chilled", "bool"),
+            ("last_chilled", "Option<BlockNumber>"),
+            ("referral_id", "ReferralId"),
+        ]),
+    },
+    RegisteredType {
+        name: "ValidatorLedger",
+        shape: TypeShape::Struct(&[
+            ("total_nomination", "Balance"),
+            ("last_total_vote_weight", "VoteWeight"),
+            ("last_total_vote_weight_update", "BlockNumber"),
+        ]),
+    },
+    RegisteredType {
+        name: "NominatorLedger",
+        shape: TypeShape::Struct(&[
+            ("nomination", "Balance"),
+            ("last_vote_weight", "VoteWeight"),
+            ("last_vote_weight_update", "BlockNumber"),
+            ("unbonded_chunks", "Vec<Unbonded<Balance, BlockNumber>>"),
+        ]),
+    },
+    RegisteredType {
+        name: "Unbonded",
+        shape: TypeShape::Struct(&[("value", "Balance"), ("locked_until", "BlockNumber")]),
+    },
+    // Gateway records
+    RegisteredType {
+        name: "WithdrawalState",
+        shape: TypeShape::Enum(&[
+            "Applying",
+            "Processing",
+            "NormalFinish",
+            "RootFinish",
+            "NormalCancel",
+            "RootCancel",
+        ]),
+    },
+    RegisteredType {
+        name: "WithdrawalRecord",
+        shape: TypeShape::Struct(&[
+            ("asset_id", "AssetId"),
+            ("applicant", "AccountId"),
+            ("balance", "Balance"),
+            ("addr", "AddrStr"),
+            ("ext", "Memo"),
+            ("height", "BlockNumber"),
+        ]),
+    },
+    // Gateway bitcoin
+    RegisteredType {
+        name: "BtcTxType",
+        shape: TypeShape::Enum(&[
+            "Withdrawal",
+            "Deposit",
+            "HotAndCold",
+            "TrusteeTransition",
+            "Irrelevance",
+        ]),
+    },
+    RegisteredType {
+        name: "BtcTxResult",
+        shape: TypeShape::Enum(&["Success", "Failure"]),
+    },
+    RegisteredType {
+        name: "BtcTxState",
+        shape: TypeShape::Struct(&[("tx_type", "BtcTxType"), ("result", "BtcTxResult")]),
+    },
+    // Spot
+    RegisteredType {
+        name: "OrderType",
+        shape: TypeShape::Enum(&["Limit", "Market"]),
+    },
+    RegisteredType {
+        name: "Side",
+        shape: TypeShape::Enum(&["Buy", "Sell"]),
+    },
+    RegisteredType {
+        name: "OrderStatus",
+        shape: TypeShape::Enum(&[
+            "Created",
+            "PartialFill",
+            "Filled",
+            "PartialFillAndCanceled",
+            "Canceled",
+        ]),
+    },
+    RegisteredType {
+        name: "CurrencyPair",
+        shape: TypeShape::Struct(&[("base", "AssetId"), ("quote", "AssetId")]),
+    },
+    RegisteredType {
+        name: "TradingPairProfile",
+        shape: TypeShape::Struct(&[
+            ("id", "TradingPairId"),
+            ("currency_pair", "CurrencyPair"),
+            ("pip_decimals", "u32"),
+            ("tick_decimals", "u32"),
+            ("tradable", "bool"),
+        ]),
+    },
+    RegisteredType {
+        name: "OrderProperty",
+        shape: TypeShape::Struct(&[
+            ("id", "OrderId"),
+            ("side", "Side"),
+            ("price", "Price"),
+            ("amount", "Amount"),
+            ("pair_id", "PairId"),
+            ("submitter", "AccountId"),
+            ("order_type", "OrderType"),
+            ("created_at", "BlockNumber"),
+        ]),
+    },
+    RegisteredType {
+        name: "Order",
+        shape: TypeShape::Struct(&[
+            (
+                "props",
+                "OrderProperty<PairId, AccountId, Balance, Price, BlockNumber>",
+            ),
+            ("status", "OrderStatus"),
+            ("remaining", "Balance"),
+            ("executed_indices", "Vec<TradingHistoryIndex>"),
+            ("already_filled", "Balance"),
+            ("last_update_at", "BlockNumber"),
+        ]),
+    },
+];
+
+/// Field of a struct in the type registry.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct FieldDefinition {
+    #[cfg_attr(feature = "std", serde(with = "xp_rpc::serde_text"))]
+    pub name: Vec<u8>,
+    #[cfg_attr(feature = "std", serde(with = "xp_rpc::serde_text"))]
+    pub type_name: Vec<u8>,
+}
+
+/// Variant of an enum in the type registry.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct VariantDefinition {
+    #[cfg_attr(feature = "std", serde(with = "xp_rpc::serde_text"))]
+    pub name: Vec<u8>,
+}
+
+/// Encodable shape of a type in the type registry.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum TypeDefinitionShape {
+    Struct(Vec<FieldDefinition>),
+    Enum(Vec<VariantDefinition>),
+}
+
+/// Encodable entry of the type registry.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct TypeDefinition {
+    #[cfg_attr(feature = "std", serde(with = "xp_rpc::serde_text"))]
+    pub name: Vec<u8>,
+    pub shape: TypeDefinitionShape,
+}
+
+/// The whole type registry returned by the runtime api.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct TypeRegistry {
+    /// Version of the registry, bumped on every shape change.
+    pub version: u32,
+    /// Fingerprint of the registered shapes, see [`fingerprint`].
+    pub fingerprint: u64,
+    /// Definitions of all the registered types.
+    pub types: Vec<TypeDefinition>,
+}
+
+impl From<&RegisteredType> for TypeDefinition {
+    fn from(ty: &RegisteredType) -> Self {
+        let shape = match ty.shape {
+            TypeShape::Struct(fields) => TypeDefinitionShape::Struct(
+                fields
+                    .iter()
+                    .map(|(name, type_name)| FieldDefinition {
+                        name: name.as_bytes().to_vec(),
+                        type_name: type_name.as_bytes().to_vec(),
+                    })
+                    .collect(),
+            ),
+            TypeShape::Enum(variants) => TypeDefinitionShape::Enum(
+                variants
+                    .iter()
+                    .map(|name| VariantDefinition {
+                        name: name.as_bytes().to_vec(),
+                    })
+                    .collect(),
+            ),
+        };
+        Self {
+            name: ty.name.as_bytes().to_vec(),
+            shape,
+        }
+    }
+}
+
+/// Returns the current type registry.
+pub fn type_registry() -> TypeRegistry {
+    TypeRegistry {
+        version: REGISTRY_VERSION,
+        fingerprint: fingerprint(),
+        types: REGISTERED_TYPES.iter().map(Into::into).collect(),
+    }
+}
+
+/// Returns the SCALE encoded type registry.
+pub fn encoded_type_registry() -> Vec<u8> {
+    type_registry().encode()
+}
+
+/// Returns the FNV-1a hash of the canonical form of all the registered shapes.
+///
+/// Canonical form of a struct: `Name{field:Type;...}`,
+/// canonical form of an enum: `Name(Variant;...)`.
+pub fn fingerprint() -> u64 {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let mut hash = FNV_OFFSET_BASIS;
+    let mut feed = |bytes: &[u8]| {
+        for byte in bytes {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    };
+
+    for ty in REGISTERED_TYPES {
+        feed(ty.name.as_bytes());
+        match ty.shape {
+            TypeShape::Struct(fields) => {
+                feed(b"{");
+                for (name, type_name) in fields {
+                    feed(name.as_bytes());
+                    feed(b":");
+                    feed(type_name.as_bytes());
+                    feed(b";");
+                }
+                feed(b"}");
+            }
+            TypeShape::Enum(variants) => {
+                feed(b"(");
+                for name in variants {
+                    feed(name.as_bytes());
+                    feed(b";");
+                }
+                feed(b")");
+            }
+        }
+    }
+
+    hash
+}
+
+#[cfg(test)]
+mod tests;
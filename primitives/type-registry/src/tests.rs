@@ -0,0 +1,170 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+use codec::Decode;
+use scale_info::{form::MetaForm, TypeDef, TypeInfo};
+
+use super::*;
+
+/// Fingerprint of each released registry version.
+///
+/// Append the new `(version, fingerprint)` pair here once `REGISTRY_VERSION` is bumped.
+const RELEASED_FINGERPRINTS: &[(u32, u64)] = &[
+    (1, 0x4aa7_a6c8_d070_c0cb),
+    (2, 0x4922_f4ef_56bf_2007),
+    (3, 0x1484_f015_e319_973b),
+];
+
+type AccountId = u64;
+type Balance = u128;
+type BlockNumber = u32;
+
+fn shape_of<T: TypeInfo + 'static>() -> (String, Vec<(String, String)>, Vec<String>) {
+    let info = T::type_info();
+    let name = info.path().ident().unwrap_or_default().to_string();
+    match info.type_def() {
+        TypeDef::<MetaForm>::Composite(composite) => {
+            let fields = composite
+                .fields()
+                .iter()
+                .map(|field| {
+                    (
+                        field.name().copied().unwrap_or_default().to_string(),
+                        field.type_name().copied().unwrap_or_default().to_string(),
+                    )
+                })
+                .collect();
+            (name, fields, Vec::new())
+        }
+        TypeDef::<MetaForm>::Variant(variant) => {
+            let variants = variant
+                .variants()
+                .iter()
+                .map(|v| v.name().to_string())
+                .collect();
+            (name, Vec::new(), variants)
+        }
+        _ => unreachable!("only structs and enums are registered"),
+    }
+}
+
+fn registered_shape_of(name: &str) -> (String, Vec<(String, String)>, Vec<String>) {
+    let ty = REGISTERED_TYPES
+        .iter()
+        .find(|ty| ty.name == name)
+        .unwrap_or_else(|| panic!("{} is not in the type registry", name));
+    match ty.shape {
+        TypeShape::Struct(fields) => (
+            ty.name.to_string(),
+            fields
+                .iter()
+                .map(|(n, t)| (n.to_string(), t.to_string()))
+                .collect(),
+            Vec::new(),
+        ),
+        TypeShape::Enum(variants) => (
+            ty.name.to_string(),
+            Vec::new(),
+            variants.iter().map(|v| v.to_string()).collect(),
+        ),
+    }
+}
+
+macro_rules! assert_registered {
+    ( $( $ty:ty ),* $(,)? ) => {
+        let mut checked = 0;
+        $(
+            let actual = shape_of::<$ty>();
+            assert_eq!(
+                registered_shape_of(&actual.0),
+                actual,
+                "The shape of `{}` has changed, update the registry entry and bump REGISTRY_VERSION",
+                actual.0
+            );
+            checked += 1;
+        )*
+        assert_eq!(
+            checked,
+            REGISTERED_TYPES.len(),
+            "Every registered type must be checked against its definition"
+        );
+    };
+}
+
+#[test]
+fn registered_types_should_match_the_definitions() {
+    use xpallet_dex_spot::{
+        CurrencyPair, Order, OrderProperty, OrderStatus, OrderType, Side, TradingPairProfile,
+    };
+    use xpallet_mining_staking::{
+        NominatorLedger, Unbonded, ValidatorLedger, ValidatorProfile, VoteWeight,
+    };
+
+    assert_registered!(
+        xpallet_assets::Chain,
+        xpallet_assets::AssetInfo,
+        xpallet_assets::AssetType,
+        xpallet_assets::AssetRestrictions,
+        xpallet_assets::TotalAssetInfo<Balance>,
+        ValidatorProfile<BlockNumber>,
+        ValidatorLedger<Balance, VoteWeight, BlockNumber>,
+        NominatorLedger<Balance, VoteWeight, BlockNumber>,
+        Unbonded<Balance, BlockNumber>,
+        xpallet_gateway_records::WithdrawalState,
+        xpallet_gateway_records::WithdrawalRecord<AccountId, Balance, BlockNumber>,
+        xp_gateway_bitcoin::BtcTxType,
+        xpallet_gateway_bitcoin::types::BtcTxResult,
+        xpallet_gateway_bitcoin::types::BtcTxState,
+        OrderType,
+        Side,
+        OrderStatus,
+        CurrencyPair,
+        TradingPairProfile,
+        OrderProperty<u32, AccountId, Balance, Balance, BlockNumber>,
+        Order<u32, AccountId, Balance, Balance, BlockNumber>,
+    );
+}
+
+#[test]
+fn registry_version_should_be_bumped_on_shape_change() {
+    assert_eq!(
+        RELEASED_FINGERPRINTS.last(),
+        Some(&(REGISTRY_VERSION, fingerprint())),
+        "The type registry has changed, bump REGISTRY_VERSION and append the new fingerprint"
+    );
+}
+
+#[test]
+fn encoded_type_registry_should_decode() {
+    let registry = TypeRegistry::decode(&mut encoded_type_registry().as_slice()).unwrap();
+    assert_eq!(registry, type_registry());
+    assert_eq!(registry.version, REGISTRY_VERSION);
+    assert_eq!(registry.types.len(), REGISTERED_TYPES.len());
+    assert_eq!(
+        registry.types[1],
+        TypeDefinition {
+            name: b"AssetInfo".to_vec(),
+            shape: TypeDefinitionShape::Struct(vec![
+                FieldDefinition {
+                    name: b"token".to_vec(),
+                    type_name: b"Token".to_vec(),
+                },
+                FieldDefinition {
+                    name: b"token_name".to_vec(),
+                    type_name: b"Token".to_vec(),
+                },
+                FieldDefinition {
+                    name: b"chain".to_vec(),
+                    type_name: b"Chain".to_vec(),
+                },
+                FieldDefinition {
+                    name: b"decimals".to_vec(),
+                    type_name: b"Decimals".to_vec(),
+                },
+                FieldDefinition {
+                    name: b"desc".to_vec(),
+                    type_name: b"Desc".to_vec(),
+                },
+            ]),
+        }
+    );
+}
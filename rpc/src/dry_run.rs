@@ -0,0 +1,369 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! RPC for dry running a runtime upgrade against the state of a block.
+//!
+//! The `Core_initialize_block` of the next block is executed twice on top of the state,
+//! once with the current runtime and once with the new one, the executive runs the
+//! `on_runtime_upgrade` hooks in the latter if the spec version is bumped. The storage
+//! changes of the two executions are kept in memory overlays, the difference between
+//! them is what the upgrade would do to the state, which is never written back.
+//!
+//! The child tries are not inspected.
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use codec::Encode;
+use jsonrpc_derive::rpc;
+use serde::{Deserialize, Serialize};
+
+use sc_executor::{WasmExecutionMethod, WasmExecutor};
+use sc_rpc_api::DenyUnsafe;
+use sp_api::CallApiAt;
+use sp_blockchain::HeaderBackend;
+use sp_core::{
+    hashing::blake2_256,
+    storage::well_known_keys,
+    traits::{RuntimeCode, WrappedRuntimeCode},
+    Bytes,
+};
+use sp_externalities::Extensions;
+use sp_runtime::{
+    generic::BlockId,
+    traits::{BlakeTwo256, Header as HeaderT},
+    Digest, DigestItem,
+};
+use sp_state_machine::{
+    Backend as StateBackend, ExecutionStrategy, OverlayedChanges, StateMachine,
+};
+
+use chainx_primitives::{Block, Hash, Header};
+use xp_rpc::{dry_run_error_into_rpc_err, Result};
+
+/// The host functions available to the runtimes of ChainX.
+type HostFunctions = (
+    sp_io::SubstrateHostFunctions,
+    frame_benchmarking::benchmarking::HostFunctions,
+    xp_io::ss_58_codec::HostFunctions,
+);
+
+/// The length of the module prefix of a storage key, i.e., the twox128 hash of the pallet name.
+const MODULE_PREFIX_LEN: usize = 16;
+
+/// The storage changes of a module made by the runtime upgrade.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModuleStorageDiff {
+    /// The first 16 bytes of the changed keys, or the whole key if it's shorter,
+    /// e.g. the well known key `:code`.
+    pub prefix: Bytes,
+    /// The number of the changed keys.
+    pub changed_keys: u32,
+    /// The total length of the new values, the removed keys count as zero.
+    pub total_bytes: u64,
+}
+
+/// The storage change of a single key made by the runtime upgrade.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyStorageDiff {
+    pub key: Bytes,
+    /// The new value, `None` if the key is removed.
+    pub value: Option<Bytes>,
+}
+
+/// The summary of the storage changes made by the runtime upgrade.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpgradeDryRunReport {
+    /// The block whose state the upgrade was executed against.
+    pub at: Hash,
+    /// The changes grouped by the module prefix.
+    pub modules: Vec<ModuleStorageDiff>,
+    /// The changed keys under the requested prefix, if any.
+    pub keys: Option<Vec<KeyStorageDiff>>,
+}
+
+/// Dry run RPC methods of the runtime upgrade.
+#[rpc]
+pub trait UpgradeDryRunApi<BlockHash> {
+    /// Execute the runtime `wasm` on top of the state at block `at` as if it was upgraded to,
+    /// and return the storage changes it would make.
+    ///
+    /// The changed keys under `prefix` are listed as well if it's specified.
+    ///
+    /// This is an unsafe RPC, the execution might be expensive.
+    #[rpc(name = "chainx_dryRunUpgrade")]
+    fn dry_run_upgrade(
+        &self,
+        wasm: Bytes,
+        at: Option<BlockHash>,
+        prefix: Option<Bytes>,
+    ) -> Result<UpgradeDryRunReport>;
+}
+
+/// A struct that implements the [`UpgradeDryRunApi`].
+pub struct UpgradeDryRun<C> {
+    client: Arc<C>,
+    deny_unsafe: DenyUnsafe,
+}
+
+impl<C> UpgradeDryRun<C> {
+    /// Create new `UpgradeDryRun` with the given reference to the client.
+    pub fn new(client: Arc<C>, deny_unsafe: DenyUnsafe) -> Self {
+        Self {
+            client,
+            deny_unsafe,
+        }
+    }
+}
+
+impl<C> UpgradeDryRunApi<Hash> for UpgradeDryRun<C>
+where
+    C: HeaderBackend<Block> + CallApiAt<Block> + Send + Sync + 'static,
+    C::StateBackend: StateBackend<BlakeTwo256>,
+{
+    fn dry_run_upgrade(
+        &self,
+        wasm: Bytes,
+        at: Option<Hash>,
+        prefix: Option<Bytes>,
+    ) -> Result<UpgradeDryRunReport> {
+        self.deny_unsafe.check_if_safe()?;
+
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+        let parent = self
+            .client
+            .header(BlockId::Hash(at))
+            .map_err(dry_run_error_into_rpc_err)?
+            .ok_or_else(|| dry_run_error_into_rpc_err(format!("Unknown block {:?}", at)))?;
+        let state = self
+            .client
+            .state_at(&BlockId::Hash(at))
+            .map_err(dry_run_error_into_rpc_err)?;
+
+        let (modules, keys) =
+            dry_run_upgrade(&state, &next_header(&parent), &wasm, prefix.as_deref())
+                .map_err(dry_run_error_into_rpc_err)?;
+        Ok(UpgradeDryRunReport { at, modules, keys })
+    }
+}
+
+/// Returns the header of the block after `parent`.
+///
+/// The pre-runtime digests of the parent are reused since the consensus pallets expect them
+/// in `on_initialize`.
+fn next_header(parent: &Header) -> Header {
+    let digest = Digest {
+        logs: parent
+            .digest()
+            .logs()
+            .iter()
+            .filter(|item| matches!(item, DigestItem::PreRuntime(..)))
+            .cloned()
+            .collect(),
+    };
+    Header::new(
+        parent.number() + 1,
+        Default::default(),
+        Default::default(),
+        parent.hash(),
+        digest,
+    )
+}
+
+/// Executes `Core_initialize_block` of `code` on top of `state`, returns the changed keys
+/// and their new values.
+fn initialize_block<S: StateBackend<BlakeTwo256>>(
+    state: &S,
+    header: &Header,
+    code: &[u8],
+) -> std::result::Result<BTreeMap<Vec<u8>, Option<Vec<u8>>>, String> {
+    let mut overlay = OverlayedChanges::default();
+    let code_fetcher = WrappedRuntimeCode(code.into());
+    let runtime_code = RuntimeCode {
+        code_fetcher: &code_fetcher,
+        heap_pages: None,
+        hash: blake2_256(code).to_vec(),
+    };
+    let executor =
+        WasmExecutor::<HostFunctions>::new(WasmExecutionMethod::Interpreted, None, 1, None, 1);
+
+    StateMachine::new(
+        state,
+        &mut overlay,
+        &executor,
+        "Core_initialize_block",
+        &header.encode(),
+        Extensions::default(),
+        &runtime_code,
+        sp_core::testing::TaskExecutor::new(),
+    )
+    .execute(ExecutionStrategy::AlwaysWasm)
+    .map_err(|e| format!("Failed to execute Core_initialize_block: {:?}", e))?;
+
+    Ok(overlay
+        .changes()
+        .map(|(key, value)| (key.clone(), value.value().cloned()))
+        .collect())
+}
+
+/// Dry runs the upgrade to the runtime `wasm` on top of `state`, returns the changes grouped
+/// by the module prefix, and the changed keys under `prefix` if specified.
+fn dry_run_upgrade<S: StateBackend<BlakeTwo256>>(
+    state: &S,
+    header: &Header,
+    wasm: &[u8],
+    prefix: Option<&[u8]>,
+) -> std::result::Result<(Vec<ModuleStorageDiff>, Option<Vec<KeyStorageDiff>>), String> {
+    let current_code = state
+        .storage(well_known_keys::CODE)
+        .map_err(|e| format!("Failed to read the current runtime: {}", e))?
+        .ok_or("The current runtime is missing")?;
+
+    let current = initialize_block(state, header, &current_code)?;
+    let upgraded = initialize_block(state, header, wasm)?;
+
+    let value_of = |changes: &BTreeMap<Vec<u8>, Option<Vec<u8>>>, key: &[u8]| match changes.get(key)
+    {
+        Some(value) => Ok(value.clone()),
+        None => state
+            .storage(key)
+            .map_err(|e| format!("Failed to read the storage: {}", e)),
+    };
+
+    let mut modules = BTreeMap::<Vec<u8>, ModuleStorageDiff>::new();
+    let mut keys = prefix.map(|_| Vec::new());
+    for key in current
+        .keys()
+        .chain(upgraded.keys())
+        .collect::<std::collections::BTreeSet<_>>()
+    {
+        let new_value = value_of(&upgraded, key)?;
+        if value_of(&current, key)? == new_value {
+            continue;
+        }
+
+        let module_prefix = &key[..key.len().min(MODULE_PREFIX_LEN)];
+        let module = modules
+            .entry(module_prefix.to_vec())
+            .or_insert_with(|| ModuleStorageDiff {
+                prefix: module_prefix.to_vec().into(),
+                changed_keys: 0,
+                total_bytes: 0,
+            });
+        module.changed_keys += 1;
+        module.total_bytes += new_value.as_ref().map_or(0, |v| v.len() as u64);
+
+        if let (Some(keys), Some(prefix)) = (keys.as_mut(), prefix) {
+            if key.starts_with(prefix) {
+                keys.push(KeyStorageDiff {
+                    key: key.clone().into(),
+                    value: new_value.map(Into::into),
+                });
+            }
+        }
+    }
+
+    Ok((modules.into_values().collect(), keys))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sp_core::{storage::Storage, twox_128};
+    use sp_state_machine::InMemoryBackend;
+
+    /// Returns a runtime whose `Core_initialize_block` sets `key` to `value` if any.
+    fn runtime(write: Option<(&[u8], &[u8])>) -> Vec<u8> {
+        let escape = |bytes: &[u8]| {
+            bytes
+                .iter()
+                .map(|b| format!("\\{:02x}", b))
+                .collect::<String>()
+        };
+        let (data, body) = match write {
+            Some((key, value)) => (
+                format!(
+                    r#"(data (i32.const 0) "{}") (data (i32.const 256) "{}")"#,
+                    escape(key),
+                    escape(value)
+                ),
+                format!(
+                    "(call $set (i64.const {}) (i64.const {}))",
+                    (key.len() as u64) << 32,
+                    ((value.len() as u64) << 32) | 256
+                ),
+            ),
+            None => (String::new(), String::new()),
+        };
+        let wat = format!(
+            r#"(module
+                (import "env" "ext_storage_set_version_1" (func $set (param i64 i64)))
+                (memory (export "memory") 17)
+                (global (export "__heap_base") i32 (i32.const 1024))
+                {}
+                (func (export "Core_initialize_block") (param i32 i32) (result i64)
+                    {}
+                    (i64.const 0)))"#,
+            data, body
+        );
+        wat::parse_str(wat).unwrap()
+    }
+
+    fn state_of(code: Vec<u8>) -> InMemoryBackend<BlakeTwo256> {
+        let mut storage = Storage::default();
+        storage.top.insert(well_known_keys::CODE.to_vec(), code);
+        storage.into()
+    }
+
+    fn header() -> Header {
+        next_header(&Header::new(
+            1,
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        ))
+    }
+
+    #[test]
+    fn dry_run_current_runtime_should_change_nothing() {
+        let key = [twox_128(b"DryRun"), twox_128(b"Key")].concat();
+        let code = runtime(Some((&key, b"value")));
+        let state = state_of(code.clone());
+
+        let (modules, keys) = dry_run_upgrade(&state, &header(), &code, Some(&key[..])).unwrap();
+        assert!(modules.is_empty());
+        assert_eq!(keys, Some(vec![]));
+    }
+
+    #[test]
+    fn dry_run_should_report_the_written_key() {
+        let key = [twox_128(b"DryRun"), twox_128(b"Key")].concat();
+        let state = state_of(runtime(None));
+
+        let upgraded = runtime(Some((&key, b"value")));
+        let (modules, keys) =
+            dry_run_upgrade(&state, &header(), &upgraded, Some(&twox_128(b"DryRun")[..])).unwrap();
+        assert_eq!(
+            modules,
+            vec![ModuleStorageDiff {
+                prefix: twox_128(b"DryRun").to_vec().into(),
+                changed_keys: 1,
+                total_bytes: 5,
+            }]
+        );
+        assert_eq!(
+            keys,
+            Some(vec![KeyStorageDiff {
+                key: key.clone().into(),
+                value: Some(b"value".to_vec().into()),
+            }])
+        );
+
+        // the state is untouched
+        assert_eq!(state.storage(&key).unwrap(), None);
+        let (modules, _) = dry_run_upgrade(&state, &header(), &upgraded, None).unwrap();
+        assert_eq!(modules.len(), 1);
+    }
+}
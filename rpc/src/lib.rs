@@ -1,5 +1,7 @@
 // Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
 
+mod dry_run;
+
 use std::sync::Arc;
 
 use sc_client_api::AuxStore;
@@ -34,6 +36,7 @@ use jsonrpc_pubsub::manager::SubscriptionManager;
 use sc_client_api::{
     backend::{Backend, StateBackend, StorageProvider},
     client::BlockchainEvents,
+    BlockBackend, ProofProvider,
 };
 use sc_network::NetworkService;
 use sc_transaction_pool::{ChainApi, Pool};
@@ -158,7 +161,10 @@ where
         + HeaderBackend<Block>
         + HeaderMetadata<Block, Error = BlockChainError>
         + StorageProvider<Block, B>
+        + ProofProvider<Block>
+        + BlockBackend<Block>
         + BlockchainEvents<Block>
+        + sp_api::CallApiAt<Block>
         + Send
         + Sync
         + 'static,
@@ -197,6 +203,7 @@ where
         BlockNumber,
     >,
     C::Api: xpallet_btc_ledger_runtime_api::BtcLedgerApi<Block, AccountId, Balance>,
+    C::Api: xpallet_system_rpc_runtime_api::XSystemApi<Block> + sp_api::Core<Block>,
     C::Api: xpallet_transaction_fee_rpc_runtime_api::XTransactionFeeApi<Block, Balance>,
     C::Api: fp_rpc::EthereumRuntimeRPCApi<Block>,
     C::Api: fp_rpc::ConvertTransactionRuntimeApi<Block>,
@@ -206,6 +213,7 @@ where
     B::State: sc_client_api::backend::StateBackend<sp_runtime::traits::HashFor<Block>>,
     A: ChainApi<Block = Block> + 'static,
 {
+    use dry_run::{UpgradeDryRun, UpgradeDryRunApi};
     use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
     use substrate_frame_rpc_system::{FullSystem, SystemApi};
     use xpallet_assets_rpc::{Assets, XAssetsApi};
@@ -216,6 +224,7 @@ where
     use xpallet_gateway_records_rpc::{XGatewayRecords, XGatewayRecordsApi};
     use xpallet_mining_asset_rpc::{XMiningAsset, XMiningAssetApi};
     use xpallet_mining_staking_rpc::{XStaking, XStakingApi};
+    use xpallet_system_rpc::{XSystem, XSystemApi};
     use xpallet_transaction_fee_rpc::{XTransactionFee, XTransactionFeeApi};
 
     let mut io = jsonrpc_core::IoHandler::default();
@@ -270,6 +279,7 @@ where
             finality_provider,
         ),
     ));
+    let properties = chain_spec.properties();
     io.extend_with(sc_sync_state_rpc::SyncStateRpcApi::to_delegate(
         sc_sync_state_rpc::SyncStateRpcHandler::new(
             chain_spec,
@@ -298,6 +308,15 @@ where
         client.clone(),
     )));
     io.extend_with(BtcLedgerApi::to_delegate(BtcLedger::new(client.clone())));
+    io.extend_with(XSystemApi::to_delegate(XSystem::new(
+        client.clone(),
+        properties,
+        deny_unsafe,
+    )));
+    io.extend_with(UpgradeDryRunApi::to_delegate(UpgradeDryRun::new(
+        client.clone(),
+        deny_unsafe,
+    )));
 
     // EVM
     {
@@ -172,7 +172,7 @@ impl<
             }
         })?;
 
-        xpallet_gateway_common::Pallet::<T>::verify_withdrawal(
+        let address = xpallet_gateway_common::Pallet::<T>::verify_withdrawal_address(
             1,
             balance.unique_saturated_into(),
             btc_addr,
@@ -188,11 +188,12 @@ impl<
             }
         })?;
 
-        xpallet_gateway_records::Pallet::<T>::withdraw(
+        xpallet_gateway_records::Pallet::<T>::withdraw_to_address(
             &from,
             1,
             balance.unique_saturated_into(),
             btc_addr.to_vec(),
+            address,
             Default::default(),
         )
         .map_err(|err| {
@@ -205,6 +206,18 @@ impl<
             }
         })?;
 
+        xpallet_gateway_common::Pallet::<T>::on_withdrawal_applied(&from, 1, btc_addr).map_err(
+            |err| {
+                log::debug!(target: "evm-withdraw", "on_withdrawal_applied: {:?}", err);
+
+                PrecompileFailure::Revert {
+                    exit_status: ExitRevert::Reverted,
+                    output: "xbtc withdraw failed".into(),
+                    cost: BASE_GAS_COST,
+                }
+            },
+        )?;
+
         Ok(())
     }
 }
@@ -39,6 +39,38 @@ impl frame_support::traits::OnRuntimeUpgrade for PhragmenElectionDepositRuntimeU
     }
 }
 
+/// Records the reward pots and the trustee multisig accounts created before
+/// `DerivedAccountOf` of XAssets existed.
+pub struct DerivedAccountsMigration;
+impl xpallet_assets::migrations::DerivedAccountSource<AccountId> for DerivedAccountsMigration {
+    fn derived_accounts() -> Vec<(AccountId, xpallet_assets::DerivedAccountKind<AccountId>)> {
+        use xpallet_assets::DerivedAccountKind;
+
+        let validators = xpallet_mining_staking::Validators::<Runtime>::iter_keys().map(|who| {
+            (
+                XStaking::reward_pot_for(&who),
+                DerivedAccountKind::IntentionJackpot(who),
+            )
+        });
+        let assets = XMiningAsset::mining_previleged_assets()
+            .into_iter()
+            .map(|id| {
+                (
+                    XMiningAsset::reward_pot_for(&id),
+                    DerivedAccountKind::TokenJackpot(id),
+                )
+            });
+        let trustees = xpallet_gateway_common::TrusteeMultiSigAddr::<Runtime>::iter()
+            .map(|(chain, multi_addr)| (multi_addr, DerivedAccountKind::TrusteePot(chain)));
+        validators.chain(assets).chain(trustees).collect()
+    }
+}
+impl frame_support::traits::OnRuntimeUpgrade for DerivedAccountsMigration {
+    fn on_runtime_upgrade() -> frame_support::weights::Weight {
+        xpallet_assets::migrations::migrate::<Runtime, Self>()
+    }
+}
+
 // pub struct SystemToTripleRefCount;
 // impl frame_support::traits::OnRuntimeUpgrade for SystemToTripleRefCount {
 //     fn on_runtime_upgrade() -> frame_support::weights::Weight {
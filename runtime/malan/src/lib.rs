@@ -21,12 +21,11 @@ use sp_runtime::RuntimeString;
 use sp_runtime::{
     create_runtime_str, generic, impl_opaque_keys,
     traits::{
-        self, AccountIdConversion, BlakeTwo256, Block as BlockT, Convert, DispatchInfoOf,
-        NumberFor, OpaqueKeys, SaturatedConversion, Saturating, SignedExtension, StaticLookup,
+        self, AccountIdConversion, BlakeTwo256, Block as BlockT, Convert, NumberFor, OpaqueKeys,
+        SaturatedConversion, Saturating, StaticLookup,
     },
     transaction_validity::{
-        InvalidTransaction, TransactionPriority, TransactionSource, TransactionValidity,
-        TransactionValidityError, ValidTransaction,
+        TransactionPriority, TransactionSource, TransactionValidity, TransactionValidityError,
     },
     ApplyExtrinsicResult, DispatchError, Perbill, Percent, Permill, RuntimeDebug,
 };
@@ -45,9 +44,15 @@ use sp_authority_discovery::AuthorityId as AuthorityDiscoveryId;
 use sp_consensus_babe::AllowedSlots::PrimaryAndSecondaryPlainSlots;
 
 use chainx_runtime_common::{BlockLength, BlockWeights, BASE_FEE};
-use xpallet_dex_spot::{Depth, FullPairInfo, RpcOrder, TradingPairId};
-use xpallet_mining_asset::{MinerLedger, MiningAssetInfo, MiningDividendInfo};
-use xpallet_mining_staking::{NominatorInfo, NominatorLedger, ValidatorInfo};
+use xpallet_dex_spot::{CompetitionId, Depth, FullPairInfo, OrderBook, RpcOrder, TradingPairId};
+use xpallet_mining_asset::{
+    InvariantCursor, InvariantReport, MinerLedger, MiningAssetInfo, MiningDividendInfo,
+    PseduClaimable,
+};
+use xpallet_mining_staking::{
+    EraProgress, JackpotAccounting, NominatorInfo, NominatorLedger, TreasuryAccounting,
+    UnbondedInfo, ValidatorInfo,
+};
 use xpallet_support::traits::MultisigAddressFor;
 
 // A few exports that help ease life for downstream crates.
@@ -81,8 +86,11 @@ pub use xpallet_assets::{
 #[cfg(feature = "std")]
 pub use xpallet_gateway_bitcoin::h256_rev;
 pub use xpallet_gateway_bitcoin::{
-    hash_rev, types::BtcHeaderInfo, BtcHeader, BtcNetwork, BtcParams, BtcTxVerifier,
-    BtcWithdrawalProposal, Compact, H256,
+    hash_rev,
+    types::{
+        BtcBridgeStatus, BtcHeaderInfo, BtcTxInfo, BtcWithdrawalNetworkFee, BtcWithdrawalTxInfo,
+    },
+    BtcHeader, BtcNetwork, BtcParams, BtcTxVerifier, BtcWithdrawalProposal, Compact, H256,
 };
 pub use xpallet_gateway_common::{
     trustees,
@@ -90,7 +98,9 @@ pub use xpallet_gateway_common::{
         GenericTrusteeIntentionProps, GenericTrusteeSessionInfo, ScriptInfo, TrusteeInfoConfig,
     },
 };
-pub use xpallet_gateway_records::{Withdrawal, WithdrawalRecordId};
+pub use xpallet_gateway_records::{
+    Withdrawal, WithdrawalAllowance, WithdrawalCost, WithdrawalEstimate, WithdrawalRecordId,
+};
 pub use xpallet_mining_asset::MiningWeight;
 pub use xpallet_mining_staking::VoteWeight;
 
@@ -125,10 +135,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     spec_name: create_runtime_str!("chainx"),
     impl_name: create_runtime_str!("chainx-malan"),
     authoring_version: 1,
-    spec_version: 32,
+    spec_version: 33,
     impl_version: 1,
     apis: RUNTIME_API_VERSIONS,
-    transaction_version: 7,
+    transaction_version: 8,
     state_version: 0,
 };
 
@@ -156,56 +166,9 @@ pub const BABE_GENESIS_EPOCH_CONFIG: sp_consensus_babe::BabeEpochConfiguration =
         allowed_slots: PrimaryAndSecondaryPlainSlots,
     };
 
-#[derive(Debug, Clone, Eq, PartialEq, codec::Encode, codec::Decode, MaxEncodedLen, TypeInfo)]
-pub struct BaseFilter;
-impl Contains<Call> for BaseFilter {
-    fn contains(call: &Call) -> bool {
-        use frame_support::dispatch::GetCallMetadata;
-
-        let metadata = call.get_call_metadata();
-        !XSystem::is_paused(metadata)
-    }
-}
-
-pub const FORBIDDEN_CALL: u8 = 255;
-pub const FORBIDDEN_ACCOUNT: u8 = 254;
-
-impl SignedExtension for BaseFilter {
-    const IDENTIFIER: &'static str = "BaseFilter";
-    type AccountId = AccountId;
-    type Call = Call;
-    type AdditionalSigned = ();
-    type Pre = ();
-    fn additional_signed(&self) -> sp_std::result::Result<(), TransactionValidityError> {
-        Ok(())
-    }
-
-    fn pre_dispatch(
-        self,
-        who: &Self::AccountId,
-        call: &Self::Call,
-        info: &DispatchInfoOf<Self::Call>,
-        len: usize,
-    ) -> Result<Self::Pre, TransactionValidityError> {
-        self.validate(who, call, info, len).map(|_| ())
-    }
-
-    fn validate(
-        &self,
-        who: &Self::AccountId,
-        call: &Self::Call,
-        _info: &DispatchInfoOf<Self::Call>,
-        _len: usize,
-    ) -> TransactionValidity {
-        if !Self::contains(call) {
-            return Err(InvalidTransaction::Custom(FORBIDDEN_CALL).into());
-        }
-        if XSystem::blacklist(who) {
-            return Err(InvalidTransaction::Custom(FORBIDDEN_ACCOUNT).into());
-        }
-        Ok(ValidTransaction::default())
-    }
-}
+/// Applies the paused calls, the blacklist, the extrinsic quota and the burn account.
+pub type BaseFilter = xpallet_system::BaseFilter<Runtime>;
+pub use xpallet_system::{FORBIDDEN_ACCOUNT, FORBIDDEN_CALL};
 
 const AVERAGE_ON_INITIALIZE_WEIGHT: Perbill = Perbill::from_percent(10);
 parameter_types! {
@@ -312,7 +275,7 @@ impl pallet_authorship::Config for Runtime {
     type FindAuthor = pallet_session::FindAccountFromAuthorIndex<Self, Babe>;
     type UncleGenerations = UncleGenerations;
     type FilterUncle = ();
-    type EventHandler = ImOnline;
+    type EventHandler = (ImOnline, XStaking);
 }
 
 parameter_types! {
@@ -440,7 +403,7 @@ parameter_types! {
 }
 
 impl pallet_transaction_payment::Config for Runtime {
-    type OnChargeTransaction = pallet_transaction_payment::CurrencyAdapter<Balances, DealWithFees>;
+    type OnChargeTransaction = xpallet_transaction_fee::FeeCreditAdapter<DealWithFees>;
     type TransactionByteFee = TransactionByteFee;
     type OperationalFeeMultiplier = OperationalFeeMultiplier;
     type WeightToFee = self::constants::fee::WeightToFee;
@@ -449,6 +412,8 @@ impl pallet_transaction_payment::Config for Runtime {
 
 impl xpallet_transaction_fee::Config for Runtime {
     type Event = Event;
+    type Currency = Balances;
+    type WeightInfo = xpallet_transaction_fee::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
@@ -534,7 +499,7 @@ where
             frame_system::CheckNonce::<Runtime>::from(nonce),
             frame_system::CheckWeight::<Runtime>::new(),
             pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(tip),
-            BaseFilter,
+            BaseFilter::new(),
             ChargeExtraFee,
         );
         let raw_payload = SignedPayload::new(call, extra)
@@ -988,9 +953,38 @@ impl pallet_proxy::Config for Runtime {
 ///////////////////////////////////////////
 // Chainx pallets
 ///////////////////////////////////////////
+/// Counts the inner calls of the batches and the proxied calls in the extrinsic quota.
+pub struct QuotaCalls;
+impl xpallet_system::QuotaCalls<Call> for QuotaCalls {
+    fn quota_calls(call: &Call) -> u32 {
+        match call {
+            // Submitted by the relayers and trustees of the bridge.
+            Call::XGatewayBitcoin(..) => 0,
+            // An empty batch still counts as one call.
+            Call::Utility(pallet_utility::Call::batch { calls })
+            | Call::Utility(pallet_utility::Call::batch_all { calls })
+                if !calls.is_empty() =>
+            {
+                calls
+                    .iter()
+                    .map(Self::quota_calls)
+                    .fold(0, u32::saturating_add)
+            }
+            Call::Utility(pallet_utility::Call::as_derivative { call, .. })
+            | Call::Proxy(pallet_proxy::Call::proxy { call, .. })
+            | Call::Proxy(pallet_proxy::Call::proxy_announced { call, .. }) => {
+                Self::quota_calls(call)
+            }
+            _ => 1,
+        }
+    }
+}
+
 impl xpallet_system::Config for Runtime {
     type Event = Event;
     type Currency = Balances;
+    type MaxExtrinsicsPerSenderPerBlock = ConstU32<32>;
+    type QuotaCalls = QuotaCalls;
 }
 
 parameter_types! {
@@ -1000,7 +994,7 @@ parameter_types! {
 impl xpallet_assets_registrar::Config for Runtime {
     type Event = Event;
     type NativeAssetId = ChainXAssetId;
-    type RegistrarHandler = XMiningAsset;
+    type RegistrarHandler = (XMiningAsset, XSpot);
     type WeightInfo = xpallet_assets_registrar::weights::SubstrateWeight<Runtime>;
 }
 
@@ -1009,7 +1003,11 @@ impl xpallet_assets::Config for Runtime {
     type Currency = Balances;
     type TreasuryAccount = SimpleTreasuryAccount;
     type OnCreatedAccount = frame_system::Provider<Runtime>;
-    type OnAssetChanged = XMiningAsset;
+    type OnAssetChanged = (XMiningAsset, XMonitor);
+    type OnReceived = XSystem;
+    type MoveGuard = ReservedAssetTypesGuard;
+    type QuarantineOrigin =
+        pallet_collective::EnsureProportionAtLeast<AccountId, CouncilCollective, 2, 3>;
     type WeightInfo = xpallet_assets::weights::SubstrateWeight<Runtime>;
 }
 
@@ -1036,6 +1034,8 @@ impl xpallet_gateway_common::Config for Runtime {
     type BitcoinTrusteeSessionProvider = trustees::bitcoin::BtcTrusteeSessionManager<Runtime>;
     type BitcoinTotalSupply = XGatewayBitcoin;
     type BitcoinWithdrawalProposal = XGatewayBitcoin;
+    type BitcoinWithdrawalHook = XGatewayBitcoin;
+    type DerivedAccounts = XAssets;
     type WeightInfo = xpallet_gateway_common::weights::SubstrateWeight<Runtime>;
 }
 
@@ -1052,9 +1052,18 @@ impl xpallet_gateway_bitcoin::Config for Runtime {
     type WeightInfo = xpallet_gateway_bitcoin::weights::SubstrateWeight<Runtime>;
 }
 
+parameter_types! {
+    pub const AutoPauseOnImbalance: bool = true;
+    pub const AutoCancelDustOrders: bool = true;
+    pub const DelistNoticePeriod: BlockNumber = 7 * DAYS;
+}
+
 impl xpallet_dex_spot::Config for Runtime {
     type Event = Event;
     type Price = Balance;
+    type AutoPauseOnImbalance = AutoPauseOnImbalance;
+    type AutoCancelDustOrders = AutoCancelDustOrders;
+    type DelistNoticePeriod = DelistNoticePeriod;
     type WeightInfo = xpallet_dex_spot::weights::SubstrateWeight<Runtime>;
 }
 
@@ -1065,6 +1074,93 @@ impl xpallet_support::traits::TreasuryAccount<AccountId> for SimpleTreasuryAccou
     }
 }
 
+/// Restricts the moves into and out of the reserved asset types to the modules owning them,
+/// and the outflows of the reward pots to their claim/reward/slash paths.
+pub struct ReservedAssetTypesGuard;
+impl xpallet_assets::MoveGuard for ReservedAssetTypesGuard {
+    fn can_move(
+        _id: &AssetId,
+        from_type: AssetType,
+        to_type: AssetType,
+        caller: xpallet_assets::MoveCaller,
+    ) -> bool {
+        use xpallet_assets::MoveCaller;
+        let owner = |asset_type| match asset_type {
+            AssetType::Reserved => Some(MoveCaller::Gateway),
+            AssetType::ReservedWithdrawal => Some(MoveCaller::Gateway),
+            AssetType::ReservedDexSpot => Some(MoveCaller::DexSpot),
+            AssetType::ReservedScheduled => Some(MoveCaller::Assets),
+            _ => None,
+        };
+        [from_type, to_type]
+            .into_iter()
+            .all(|asset_type| owner(asset_type).map_or(true, |owner| owner == caller))
+    }
+
+    fn can_move_out<AccountId>(
+        kind: &xpallet_assets::DerivedAccountKind<AccountId>,
+        caller: xpallet_assets::MoveCaller,
+    ) -> bool {
+        use xpallet_assets::{DerivedAccountKind, MoveCaller};
+        match kind {
+            DerivedAccountKind::TokenJackpot(_) | DerivedAccountKind::IntentionJackpot(_) => {
+                caller == MoveCaller::Jackpot
+            }
+            _ => true,
+        }
+    }
+}
+
+/// Halts the bitcoin bridge by pausing all the calls of `XGatewayBitcoin`.
+pub struct PauseBridgeOnSupplyAnomaly;
+impl xpallet_monitor::HaltBridge for PauseBridgeOnSupplyAnomaly {
+    fn halt(token: &AssetId) {
+        if *token == X_BTC {
+            XSystem::modify_paused_impl(b"XGatewayBitcoin".to_vec(), None, true);
+        }
+    }
+}
+
+parameter_types! {
+    pub const SupplyWindowBlocks: BlockNumber = 600;
+}
+
+impl xpallet_monitor::Config for Runtime {
+    type Event = Event;
+    type WindowBlocks = SupplyWindowBlocks;
+    type HaltBridge = PauseBridgeOnSupplyAnomaly;
+}
+
+parameter_types! {
+    pub const MaxPriceFeeders: u32 = 16;
+    pub const PriceStaleBlocks: BlockNumber = 600;
+    pub const PriceObservationRetention: BlockNumber = 7 * DAYS;
+    pub const PriceChangeThreshold: Percent = Percent::from_percent(5);
+}
+
+impl xpallet_oracle::Config for Runtime {
+    type Event = Event;
+    type MaxFeeders = MaxPriceFeeders;
+    type StaleBlocks = PriceStaleBlocks;
+    type ObservationRetention = PriceObservationRetention;
+    type PriceChangeThreshold = PriceChangeThreshold;
+}
+
+parameter_types! {
+    pub const MaxMultisigOwners: u32 = 100;
+    pub const MultisigOwnerChangeDelay: BlockNumber = 2 * DAYS;
+    pub const MaxMultisigProposals: u32 = 100;
+}
+
+impl xpallet_multisig::Config for Runtime {
+    type Event = Event;
+    type Call = Call;
+    type MaxOwners = MaxMultisigOwners;
+    type OwnerChangeDelay = MultisigOwnerChangeDelay;
+    type MaxProposals = MaxMultisigProposals;
+    type WeightInfo = xpallet_multisig::weights::SubstrateWeight<Runtime>;
+}
+
 parameter_types! {
     // Total issuance is 7723350PCX by the end of ChainX 1.0.
     // 210000 - (7723350 / 50) = 55533
@@ -1081,28 +1177,51 @@ impl xpallet_mining_staking::Config for Runtime {
     type MaximumReferralId = MaximumReferralId;
     type SessionInterface = Self;
     type TreasuryAccount = SimpleTreasuryAccount;
+    type CouncilOrigin =
+        pallet_collective::EnsureProportionAtLeast<AccountId, CouncilCollective, 2, 3>;
     type AssetMining = XMiningAsset;
+    type NativeHolds = XAssets;
+    type DerivedAccounts = XAssets;
     type DetermineRewardPotAccount =
         xpallet_mining_staking::SimpleValidatorRewardPotAccountDeterminer<Runtime>;
     type ValidatorRegistration = Session;
     type WeightInfo = xpallet_mining_staking::weights::SubstrateWeight<Runtime>;
 }
 
+/// Provides the oracle prices of the mining assets without a fixed mining power.
+pub struct OraclePriceProvider;
+impl xpallet_mining_asset::AssetPriceProvider for OraclePriceProvider {
+    fn price_of(asset_id: &AssetId) -> Option<u128> {
+        XOracle::oracle_price(*asset_id)
+    }
+}
+
 pub struct ReferralGetter;
 impl xpallet_mining_asset::GatewayInterface<AccountId> for ReferralGetter {
     fn referral_of(who: &AccountId, asset_id: AssetId) -> Option<AccountId> {
         use xpallet_gateway_common::traits::ReferralBinding;
         XGatewayCommon::referral(&asset_id, who)
     }
+
+    fn minimal_deposit_of(asset_id: AssetId) -> u128 {
+        if asset_id == X_BTC {
+            XGatewayBitcoin::btc_min_deposit().into()
+        } else {
+            0
+        }
+    }
 }
 
 impl xpallet_mining_asset::Config for Runtime {
     type Event = Event;
     type StakingInterface = Self;
     type GatewayInterface = ReferralGetter;
+    type PriceProvider = OraclePriceProvider;
     type TreasuryAccount = SimpleTreasuryAccount;
     type DetermineRewardPotAccount =
         xpallet_mining_asset::SimpleAssetRewardPotAccountDeterminer<Runtime>;
+    type DerivedAccounts = XAssets;
+    type InvariantAssertionEnabled = ConstBool<true>;
     type WeightInfo = xpallet_mining_asset::weights::SubstrateWeight<Runtime>;
 }
 
@@ -1273,7 +1392,7 @@ construct_runtime!(
 
         // It might be possible to merge this module into pallet_transaction_payment in future, thus
         // we put it at the end for keeping the extrinsic ordering.
-        XTransactionFee: xpallet_transaction_fee::{Pallet, Event<T>} = 35,
+        XTransactionFee: xpallet_transaction_fee::{Pallet, Call, Storage, Event<T>} = 35,
 
         Proxy: pallet_proxy::{Pallet, Call, Storage, Event<T>} = 36,
 
@@ -1293,6 +1412,13 @@ construct_runtime!(
         XAssetsBridge: xpallet_assets_bridge::{Pallet, Call, Storage, Config<T>, Event<T>} = 45,
 
         XBtcLedger: xpallet_btc_ledger::{Pallet, Call, Storage, Config<T>, Event<T>} = 46,
+
+        // Dependency on xpallet_assets and xpallet_system
+        XMonitor: xpallet_monitor::{Pallet, Call, Storage, Event<T>} = 47,
+
+        XOracle: xpallet_oracle::{Pallet, Call, Storage, Event<T>} = 48,
+
+        XMultisig: xpallet_multisig::{Pallet, Call, Storage, Config<T>, Event<T>} = 49,
     }
 );
 
@@ -1334,6 +1460,7 @@ pub type Executive = frame_executive::Executive<
     frame_system::ChainContext<Runtime>,
     Runtime,
     AllPalletsWithSystem,
+    migrations::DerivedAccountsMigration,
 >;
 
 pub struct TransactionConverter;
@@ -1592,6 +1719,60 @@ impl_runtime_apis! {
         }
     }
 
+    impl xpallet_system_rpc_runtime_api::XSystemApi<Block> for Runtime {
+        fn chainx_type_registry() -> Vec<u8> {
+            xp_type_registry::encoded_type_registry()
+        }
+
+        fn chain_properties() -> xpallet_system_rpc_runtime_api::RuntimeProperties {
+            xpallet_system_rpc_runtime_api::RuntimeProperties {
+                pcx_precision: XAssetsRegistrar::asset_info_of(PCX)
+                    .map(|info| info.decimals())
+                    .unwrap_or(PCX_DECIMALS),
+                chains: Chain::iter()
+                    .map(|chain| (*chain, XAssetsRegistrar::asset_ids_of(chain).len() as u32))
+                    .filter(|(_, count)| *count > 0)
+                    .collect(),
+                bitcoin_network: match XGatewayBitcoin::network_id() {
+                    BtcNetwork::Mainnet => NetworkType::Mainnet,
+                    _ => NetworkType::Testnet,
+                },
+                bitcoin_confirmation_number: XGatewayBitcoin::confirmation_number(),
+            }
+        }
+
+        fn docs_registry() -> Vec<xpallet_system_rpc_runtime_api::ModuleDocs> {
+            vec![
+                xpallet_assets::PALLET_DOCS.into(),
+                xpallet_mining_staking::PALLET_DOCS.into(),
+                xpallet_mining_asset::PALLET_DOCS.into(),
+                xpallet_gateway_bitcoin::PALLET_DOCS.into(),
+            ]
+        }
+
+        fn pcx_supply_breakdown() -> xpallet_system_rpc_runtime_api::SupplyBreakdown {
+            let locked = XStaking::total_locked();
+            let locked_of = |ty| locked.get(&ty).copied().unwrap_or_default();
+            xpallet_system_rpc_runtime_api::SupplyBreakdown::new(
+                Balances::total_issuance(),
+                locked_of(xpallet_mining_staking::LockedType::Bonded),
+                locked_of(xpallet_mining_staking::LockedType::BondedWithdrawal),
+                XSpot::total_native_reserves(),
+                XAssets::total_asset_balance_of(&PCX, AssetType::ReservedWithdrawal),
+                XStaking::total_reward_pot_balance()
+                    .saturating_add(XMiningAsset::total_reward_pot_balance()),
+                XStaking::treasury_accounting().balance,
+            )
+        }
+
+        fn account_activity(
+            who: AccountId,
+            extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+        ) -> Vec<xpallet_system_rpc_runtime_api::AccountActivity> {
+            XSystem::account_activity(&who, extrinsics.into_iter().map(|xt| xt.0).collect())
+        }
+    }
+
     impl xpallet_transaction_fee_rpc_runtime_api::XTransactionFeeApi<Block, Balance> for Runtime {
         fn query_fee_details(
             uxt: <Block as BlockT>::Extrinsic,
@@ -1611,6 +1792,20 @@ impl_runtime_apis! {
         fn assets() -> BTreeMap<AssetId, TotalAssetInfo<Balance>> {
             XAssets::total_asset_infos()
         }
+
+        fn asset_balance_storage_key(who: AccountId, asset_id: AssetId) -> Vec<u8> {
+            XAssets::asset_balance_key(&who, &asset_id)
+        }
+
+        fn account_status(who: AccountId) -> xpallet_assets::AccountStatus<Balance> {
+            XAssets::account_status(&who)
+        }
+
+        fn identify_account(
+            who: AccountId,
+        ) -> Option<xpallet_assets::DerivedAccountKind<AccountId>> {
+            XAssets::derived_account_of(&who)
+        }
     }
 
     impl xpallet_mining_staking_rpc_runtime_api::XStakingApi<Block, AccountId, Balance, VoteWeight, BlockNumber> for Runtime {
@@ -1629,11 +1824,40 @@ impl_runtime_apis! {
         fn nominator_info_of(who: AccountId) -> NominatorInfo<BlockNumber> {
             XStaking::nominator_info_of(who)
         }
+        fn era_progress() -> EraProgress<BlockNumber> {
+            XStaking::era_progress()
+        }
+
+        fn treasury_accounting() -> TreasuryAccounting<AccountId, Balance, BlockNumber> {
+            XStaking::treasury_accounting()
+        }
+
+        fn unbonded_chunks_of(who: AccountId) -> BTreeMap<AccountId, Vec<UnbondedInfo<Balance, BlockNumber>>> {
+            XStaking::unbonded_chunks_of(who)
+        }
+
+        fn nomination_proof(
+            era: xpallet_mining_staking::EraIndex,
+            validator: AccountId,
+            nominator: AccountId,
+        ) -> Option<xpallet_mining_staking::NominationProof<VoteWeight, Hash>> {
+            XStaking::nomination_proof(era, &validator, &nominator)
+        }
+
+        fn nomination_records_detailed(
+            who: AccountId,
+        ) -> Vec<(AccountId, NominatorLedger<Balance, VoteWeight, BlockNumber>, VoteWeight, VoteWeight)> {
+            XStaking::nomination_records_detailed(who)
+        }
+
+        fn jackpot_accounting() -> JackpotAccounting<Balance> {
+            XStaking::jackpot_accounting()
+        }
     }
 
     impl xpallet_dex_spot_rpc_runtime_api::XSpotApi<Block, AccountId, Balance, BlockNumber, Balance> for Runtime {
-        fn trading_pairs() -> Vec<FullPairInfo<Balance, BlockNumber>> {
-            XSpot::trading_pairs()
+        fn trading_pairs(include_retired: bool) -> Vec<FullPairInfo<Balance, Balance, BlockNumber>> {
+            XSpot::trading_pairs(include_retired)
         }
 
         fn orders(who: AccountId, page_index: u32, page_size: u32) -> Vec<RpcOrder<TradingPairId, AccountId, Balance, Balance, BlockNumber>> {
@@ -1643,6 +1867,22 @@ impl_runtime_apis! {
         fn depth(pair_id: TradingPairId, depth_size: u32) -> Option<Depth<Balance, Balance>> {
             XSpot::depth(pair_id, depth_size)
         }
+
+        fn order_book(pair_id: TradingPairId, depth: u32) -> Option<OrderBook<Balance, Balance, BlockNumber>> {
+            XSpot::order_book(pair_id, depth)
+        }
+
+        fn min_notional(pair_id: TradingPairId) -> Option<Balance> {
+            XSpot::min_notional(pair_id)
+        }
+
+        fn competition_leaderboard(id: CompetitionId, page_index: u32, page_size: u32) -> Vec<(AccountId, Balance)> {
+            XSpot::competition_leaderboard(id, page_index, page_size)
+        }
+
+        fn order_sequence(who: AccountId) -> u64 {
+            XSpot::order_sequence_of(who)
+        }
     }
 
     impl xpallet_mining_asset_rpc_runtime_api::XMiningAssetApi<Block, AccountId, Balance, MiningWeight, BlockNumber> for Runtime {
@@ -1657,6 +1897,18 @@ impl_runtime_apis! {
         fn miner_ledger(who: AccountId) -> BTreeMap<AssetId, MinerLedger<MiningWeight, BlockNumber>> {
             XMiningAsset::miner_ledger(who)
         }
+
+        fn check_invariants(
+            asset_id: AssetId,
+            max_accounts: u32,
+            cursor: Option<InvariantCursor<Balance, BlockNumber>>,
+        ) -> InvariantReport<Balance, BlockNumber> {
+            XMiningAsset::check_invariants(asset_id, max_accounts, cursor)
+        }
+
+        fn psedu_claimable(who: AccountId) -> Vec<PseduClaimable<Balance, MiningWeight>> {
+            XMiningAsset::psedu_claimable(who)
+        }
     }
 
     impl xpallet_gateway_records_rpc_runtime_api::XGatewayRecordsApi<Block, AccountId, Balance, BlockNumber> for Runtime {
@@ -1667,6 +1919,22 @@ impl_runtime_apis! {
         fn withdrawal_list_by_chain(chain: Chain) -> BTreeMap<u32, Withdrawal<AccountId, Balance, BlockNumber>> {
             XGatewayRecords::withdrawals_list_by_chain(chain)
         }
+
+        fn archived_withdrawals() -> Vec<(WithdrawalRecordId, Withdrawal<AccountId, Balance, BlockNumber>)> {
+            System::read_events_no_consensus()
+                .into_iter()
+                .filter_map(|record| match record.event {
+                    Event::XGatewayRecords(xpallet_gateway_records::Event::WithdrawalArchived(id, record, state)) => {
+                        Some((id, Withdrawal::new(record, state)))
+                    }
+                    _ => None,
+                })
+                .collect()
+        }
+
+        fn withdrawal_estimates() -> BTreeMap<WithdrawalRecordId, WithdrawalEstimate> {
+            XGatewayBitcoin::withdrawal_estimates()
+        }
     }
 
     impl xpallet_gateway_bitcoin_rpc_runtime_api::XGatewayBitcoinApi<Block, AccountId> for Runtime {
@@ -1689,6 +1957,38 @@ impl_runtime_apis! {
         fn get_btc_block_header(txid: H256) -> Option<BtcHeaderInfo> {
             XGatewayBitcoin::get_btc_block_header(txid)
         }
+
+        fn get_btc_tx_info(txid: H256) -> Option<BtcTxInfo> {
+            XGatewayBitcoin::get_btc_tx_info(txid)
+        }
+
+        fn get_bridge_status() -> BtcBridgeStatus {
+            XGatewayBitcoin::get_bridge_status()
+        }
+
+        fn btc_relay_stats(last_n: u32) -> Vec<(u32, u32, u32, AccountId)> {
+            XGatewayBitcoin::btc_relay_stats(last_n)
+        }
+
+        fn archived_btc_headers() -> Vec<(H256, BtcHeaderInfo)> {
+            System::read_events_no_consensus()
+                .into_iter()
+                .filter_map(|record| match record.event {
+                    Event::XGatewayBitcoin(xpallet_gateway_bitcoin::Event::BtcHeaderArchived(hash, header_info)) => {
+                        Some((hash, header_info))
+                    }
+                    _ => None,
+                })
+                .collect()
+        }
+
+        fn withdrawal_network_fee(addr: Vec<u8>) -> Result<BtcWithdrawalNetworkFee, DispatchError> {
+            XGatewayBitcoin::withdrawal_network_fee(&addr)
+        }
+
+        fn get_withdrawal_tx_info() -> Option<BtcWithdrawalTxInfo<AccountId>> {
+            XGatewayBitcoin::get_withdrawal_tx_info()
+        }
     }
 
     impl xpallet_btc_ledger_runtime_api::BtcLedgerApi<Block, AccountId, Balance> for Runtime {
@@ -1737,22 +2037,13 @@ impl_runtime_apis! {
         }
 
         fn trustee_session_info(chain: Chain, session_number: i32) -> Option<GenericTrusteeSessionInfo<AccountId, BlockNumber>> {
-            if session_number < 0 {
-                let number = match session_number {
-                    -1i32 => Some(XGatewayCommon::trustee_session_info_len(chain)),
-                    -2i32 => XGatewayCommon::trustee_session_info_len(chain).checked_sub(1),
-                    _ => None
-                };
-                if let Some(number) = number {
-                    XGatewayCommon::trustee_session_info_of(chain, number)
-                }else{
-                    None
-                }
-            }else{
-                let number = session_number as u32;
-                XGatewayCommon::trustee_session_info_of(chain, number)
-            }
-
+            let session = match session_number {
+                -1i32 => None,
+                -2i32 => Some(XGatewayCommon::trustee_session_info_len(chain).checked_sub(1)?),
+                number if number >= 0 => Some(number as u32),
+                _ => return None,
+            };
+            XGatewayCommon::trustee_session(chain, session)
         }
 
         fn generate_trustee_session_info(chain: Chain, candidates: Vec<AccountId>) -> Result<(GenericTrusteeSessionInfo<AccountId, BlockNumber>, ScriptInfo<AccountId>), DispatchError> {
@@ -1761,6 +2052,38 @@ impl_runtime_apis! {
             let _ = XGatewayCommon::generate_multisig_addr(chain, &info.0)?;
             Ok(info)
         }
+
+        fn withdrawal_limit_of(asset_id: AssetId, who: AccountId) -> Result<(WithdrawalLimit<Balance>, Option<WithdrawalAllowance<Balance>>), DispatchError> {
+            XGatewayCommon::withdrawal_limit_of(&asset_id, &who)
+        }
+
+        fn estimate_withdrawal_cost(asset_id: AssetId, value: Balance, addr: AddrStr, memo: Memo) -> Result<WithdrawalCost<Balance>, DispatchError> {
+            use frame_support::weights::GetDispatchInfo;
+
+            XGatewayCommon::verify_withdrawal(asset_id, value, &addr, &memo)?;
+            let withdrawal_fee = XGatewayCommon::withdrawal_limit(&asset_id)?.fee;
+            let network_fee = match XAssetsRegistrar::chain_of(&asset_id)? {
+                Chain::Bitcoin => XGatewayBitcoin::withdrawal_network_fee(&addr)?.fee.into(),
+                _ => 0,
+            };
+            // The same fee as `query_info`, the signature and the extensions excluded.
+            let call = Call::XGatewayCommon(xpallet_gateway_common::Call::withdraw {
+                asset_id,
+                value,
+                addr,
+                ext: memo,
+            });
+            let chainx_fee = TransactionPayment::compute_fee(
+                call.encoded_size() as u32,
+                &call.get_dispatch_info(),
+                0,
+            ) + ChargeExtraFee::has_extra_fee(&call).unwrap_or_default();
+            Ok(WithdrawalCost {
+                chainx_fee,
+                withdrawal_fee,
+                network_fee,
+            })
+        }
     }
 
     impl fp_rpc::ConvertTransactionRuntimeApi<Block> for Runtime {
@@ -1942,6 +2265,7 @@ impl_runtime_apis! {
             list_benchmark!(list, extra, xpallet_gateway_common, XGatewayCommon);
             list_benchmark!(list, extra, xpallet_gateway_bitcoin, XGatewayBitcoin);
             list_benchmark!(list, extra, xpallet_dex_spot, XSpot);
+            list_benchmark!(list, extra, xpallet_multisig, XMultisig);
 
             let storage_info = AllPalletsWithSystem::storage_info();
 
@@ -2002,5 +2326,6 @@ mod benches {
         [xpallet_gateway_common,  XGatewayCommon]
         [xpallet_gateway_bitcoin, XGatewayBitcoin]
         [xpallet_dex_spot, XSpot]
+        [xpallet_multisig, XMultisig]
     );
 }
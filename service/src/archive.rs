@@ -0,0 +1,293 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Archive of the bridge records pruned from the on-chain storage.
+//!
+//! The runtime removes the finished withdrawal records and the old bitcoin headers
+//! from the storage, emitting their full content in the events. On the nodes started
+//! with `--chainx-archive`, this task follows the finalized blocks and copies them
+//! into the auxiliary storage of the node, which are then served by the RPCs
+//! `chainx_getArchivedWithdrawal` and `chainx_getArchivedBtcHeader`.
+//!
+//! The archive starts from the first block whose runtime provides the archive APIs,
+//! the blocks whose state has been pruned are skipped.
+
+use std::sync::Arc;
+
+use codec::{Decode, Encode};
+use futures::StreamExt;
+use sc_client_api::{AuxStore, BlockchainEvents};
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::generic::BlockId;
+
+use chainx_primitives::{AccountId, Balance, Block, BlockNumber};
+use xpallet_gateway_bitcoin_rpc::archived_btc_header_key;
+use xpallet_gateway_bitcoin_rpc_runtime_api::{BtcHeaderInfo, XGatewayBitcoinApi, H256};
+use xpallet_gateway_records_rpc::archived_withdrawal_key;
+use xpallet_gateway_records_rpc_runtime_api::{Withdrawal, WithdrawalRecordId, XGatewayRecordsApi};
+
+const LOG_TARGET: &str = "archive";
+
+/// Auxiliary storage key of the number of the last archived block.
+const LAST_ARCHIVED_BLOCK_KEY: &[u8] = b"chainx_archive_last_block";
+
+/// The version of `XGatewayRecordsApi` and `XGatewayBitcoinApi` adding the archive APIs.
+const ARCHIVE_API_VERSION: u32 = 2;
+
+type ArchivedWithdrawal = (
+    WithdrawalRecordId,
+    Withdrawal<AccountId, Balance, BlockNumber>,
+);
+
+/// Archives the records pruned in the finalized blocks, from the last archived block on.
+pub async fn archive_task<C>(client: Arc<C>)
+where
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + BlockchainEvents<Block> + AuxStore,
+    C::Api: XGatewayRecordsApi<Block, AccountId, Balance, BlockNumber>
+        + XGatewayBitcoinApi<Block, AccountId>,
+{
+    let mut finality_notifications = client.finality_notification_stream();
+    archive_finalized_blocks(&*client);
+    while finality_notifications.next().await.is_some() {
+        archive_finalized_blocks(&*client);
+    }
+}
+
+fn archive_finalized_blocks<C>(client: &C)
+where
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore,
+    C::Api: XGatewayRecordsApi<Block, AccountId, Balance, BlockNumber>
+        + XGatewayBitcoinApi<Block, AccountId>,
+{
+    let finalized = client.info().finalized_number;
+    let mut number = match last_archived_block(client) {
+        Ok(Some(last)) => last + 1,
+        Ok(None) => match first_archivable_block(client, finalized) {
+            Some(first) => first,
+            // Not upgraded to the runtime with the archive APIs yet.
+            None => return,
+        },
+        Err(err) => {
+            log::error!(target: LOG_TARGET, "Failed to read the last archived block: {}", err);
+            return;
+        }
+    };
+
+    while number <= finalized {
+        let result = match archive_apis_available(client, number) {
+            Ok(true) => archive_block(client, number),
+            Ok(false) => write_archive(client, number, &[], &[]).map_err(|err| err.to_string()),
+            Err(err) => {
+                log::warn!(
+                    target: LOG_TARGET,
+                    "Skip block #{} whose state is unavailable: {}",
+                    number,
+                    err
+                );
+                write_archive(client, number, &[], &[]).map_err(|err| err.to_string())
+            }
+        };
+        // Retry from the same block on the next finality notification.
+        if let Err(err) = result {
+            log::error!(target: LOG_TARGET, "Failed to archive block #{}: {}", number, err);
+            return;
+        }
+        number += 1;
+    }
+}
+
+/// Returns the first block up to `finalized` whose runtime provides the archive APIs.
+///
+/// The blocks whose state is unavailable are treated as not providing the APIs, the
+/// runtime upgrades being irreversible, the first block is found by a binary search.
+fn first_archivable_block<C>(client: &C, finalized: BlockNumber) -> Option<BlockNumber>
+where
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    C::Api: XGatewayRecordsApi<Block, AccountId, Balance, BlockNumber>
+        + XGatewayBitcoinApi<Block, AccountId>,
+{
+    let available = |number| archive_apis_available(client, number).unwrap_or(false);
+    if finalized == 0 || !available(finalized) {
+        return None;
+    }
+    let (mut low, mut high) = (1, finalized);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if available(mid) {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+    log::info!(target: LOG_TARGET, "Start archiving from block #{}", low);
+    Some(low)
+}
+
+/// Returns true if the runtime of block `number` provides the archive APIs.
+fn archive_apis_available<C>(client: &C, number: BlockNumber) -> Result<bool, String>
+where
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    C::Api: XGatewayRecordsApi<Block, AccountId, Balance, BlockNumber>
+        + XGatewayBitcoinApi<Block, AccountId>,
+{
+    let hash = client
+        .hash(number)
+        .map_err(|err| err.to_string())?
+        .ok_or_else(|| "unknown block".to_string())?;
+    let at = BlockId::hash(hash);
+    let api = client.runtime_api();
+    let records = api
+        .has_api_with::<dyn XGatewayRecordsApi<Block, AccountId, Balance, BlockNumber>, _>(
+            &at,
+            |version| version >= ARCHIVE_API_VERSION,
+        )
+        .map_err(|err| err.to_string())?;
+    let bitcoin = api
+        .has_api_with::<dyn XGatewayBitcoinApi<Block, AccountId>, _>(&at, |version| {
+            version >= ARCHIVE_API_VERSION
+        })
+        .map_err(|err| err.to_string())?;
+    Ok(records && bitcoin)
+}
+
+fn archive_block<C>(client: &C, number: BlockNumber) -> Result<(), String>
+where
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore,
+    C::Api: XGatewayRecordsApi<Block, AccountId, Balance, BlockNumber>
+        + XGatewayBitcoinApi<Block, AccountId>,
+{
+    let hash = client
+        .hash(number)
+        .map_err(|err| err.to_string())?
+        .ok_or_else(|| "unknown block".to_string())?;
+    let at = BlockId::hash(hash);
+    let api = client.runtime_api();
+    let withdrawals = api
+        .archived_withdrawals(&at)
+        .map_err(|err| err.to_string())?;
+    let headers = api
+        .archived_btc_headers(&at)
+        .map_err(|err| err.to_string())?;
+
+    if !withdrawals.is_empty() || !headers.is_empty() {
+        log::debug!(
+            target: LOG_TARGET,
+            "Archive {} withdrawal records and {} bitcoin headers of block #{}",
+            withdrawals.len(),
+            headers.len(),
+            number
+        );
+    }
+    write_archive(client, number, &withdrawals, &headers).map_err(|err| err.to_string())
+}
+
+/// Writes the archived records of block `number` along with the archive progress.
+fn write_archive<A: AuxStore>(
+    aux: &A,
+    number: BlockNumber,
+    withdrawals: &[ArchivedWithdrawal],
+    headers: &[(H256, BtcHeaderInfo)],
+) -> sp_blockchain::Result<()> {
+    let mut entries = withdrawals
+        .iter()
+        .map(|(id, withdrawal)| (archived_withdrawal_key(*id), withdrawal.encode()))
+        .chain(
+            headers
+                .iter()
+                .map(|(hash, header_info)| (archived_btc_header_key(hash), header_info.encode())),
+        )
+        .collect::<Vec<_>>();
+    entries.push((LAST_ARCHIVED_BLOCK_KEY.to_vec(), number.encode()));
+
+    let insert = entries
+        .iter()
+        .map(|(key, value)| (key.as_slice(), value.as_slice()))
+        .collect::<Vec<_>>();
+    aux.insert_aux(&insert, &[])
+}
+
+fn last_archived_block<A: AuxStore>(aux: &A) -> sp_blockchain::Result<Option<BlockNumber>> {
+    match aux.get_aux(LAST_ARCHIVED_BLOCK_KEY)? {
+        Some(encoded) => BlockNumber::decode(&mut encoded.as_slice())
+            .map(Some)
+            .map_err(|err| sp_blockchain::Error::Backend(err.to_string())),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use xpallet_gateway_records_rpc_runtime_api::WithdrawalState;
+
+    #[derive(Default)]
+    struct MemoryAux(Mutex<HashMap<Vec<u8>, Vec<u8>>>);
+
+    impl AuxStore for MemoryAux {
+        fn insert_aux<
+            'a,
+            'b: 'a,
+            'c: 'a,
+            I: IntoIterator<Item = &'a (&'c [u8], &'c [u8])>,
+            D: IntoIterator<Item = &'a &'b [u8]>,
+        >(
+            &self,
+            insert: I,
+            delete: D,
+        ) -> sp_blockchain::Result<()> {
+            let mut storage = self.0.lock().unwrap();
+            for (key, value) in insert {
+                storage.insert(key.to_vec(), value.to_vec());
+            }
+            for key in delete {
+                storage.remove(*key);
+            }
+            Ok(())
+        }
+
+        fn get_aux(&self, key: &[u8]) -> sp_blockchain::Result<Option<Vec<u8>>> {
+            Ok(self.0.lock().unwrap().get(key).cloned())
+        }
+    }
+
+    #[test]
+    fn archived_records_should_be_retrievable() {
+        let aux = MemoryAux::default();
+        assert_eq!(last_archived_block(&aux).unwrap(), None);
+
+        let withdrawal = Withdrawal {
+            asset_id: 1,
+            applicant: AccountId::new([1u8; 32]),
+            balance: 100,
+            addr: b"addr".to_vec(),
+            ext: b"ext".to_vec().into(),
+            height: 3,
+            state: WithdrawalState::NormalFinish,
+        };
+        let header_hash = H256::repeat_byte(1);
+        let header_info = BtcHeaderInfo {
+            header: Default::default(),
+            height: 63290,
+        };
+
+        // Block #5 prunes a withdrawal record, block #6 prunes a header.
+        write_archive(&aux, 5, &[(7, withdrawal.clone())], &[]).unwrap();
+        write_archive(&aux, 6, &[], &[(header_hash, header_info.clone())]).unwrap();
+        assert_eq!(last_archived_block(&aux).unwrap(), Some(6));
+
+        let archived_withdrawal = aux.get_aux(&archived_withdrawal_key(7)).unwrap().unwrap();
+        assert!(Withdrawal::decode(&mut archived_withdrawal.as_slice()).unwrap() == withdrawal);
+        let archived_header = aux
+            .get_aux(&archived_btc_header_key(&header_hash))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            BtcHeaderInfo::decode(&mut archived_header.as_slice()).unwrap(),
+            header_info
+        );
+        assert!(aux.get_aux(&archived_withdrawal_key(8)).unwrap().is_none());
+    }
+}
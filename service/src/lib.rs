@@ -16,7 +16,9 @@ use std::time::Duration;
 
 use chainx_primitives::Block;
 
+mod archive;
 mod client;
+mod metrics;
 use client::RuntimeApiCollection;
 
 // EVM
@@ -279,6 +281,7 @@ fn remote_keystore(_url: &str) -> Result<Arc<LocalKeystore>, &'static str> {
 /// Creates a full service from the configuration.
 pub fn new_full_base<RuntimeApi, Executor>(
     mut config: Configuration,
+    archive: bool,
 ) -> Result<NewFullBase<RuntimeApi, Executor>, ServiceError>
 where
     RuntimeApi:
@@ -501,6 +504,18 @@ where
         EthTask::ethereum_schema_cache_task(Arc::clone(&client), Arc::clone(&frontier_backend)),
     );
 
+    // Spawn the task updating the metrics of the ChainX subsystems.
+    if let Some(registry) = prometheus_registry.as_ref() {
+        match metrics::Metrics::register(registry) {
+            Ok(metrics) => task_manager.spawn_handle().spawn(
+                "chainx-metrics",
+                None,
+                metrics::metrics_task(client.clone(), transaction_pool.clone(), metrics),
+            ),
+            Err(err) => log::warn!("Failed to register the ChainX metrics: {}", err),
+        }
+    }
+
     if let sc_service::config::Role::Authority { .. } = &role {
         let proposer = sc_basic_authorship::ProposerFactory::new(
             task_manager.spawn_handle(),
@@ -637,6 +652,14 @@ where
         );
     }
 
+    if archive {
+        task_manager.spawn_handle().spawn(
+            "chainx-archive",
+            None,
+            archive::archive_task(client.clone()),
+        );
+    }
+
     network_starter.start_network();
 
     Ok(NewFullBase {
@@ -649,7 +672,10 @@ where
 }
 
 /// Builds a new service for a full client.
-pub fn new_full<RuntimeApi, Executor>(config: Configuration) -> Result<TaskManager, ServiceError>
+pub fn new_full<RuntimeApi, Executor>(
+    config: Configuration,
+    archive: bool,
+) -> Result<TaskManager, ServiceError>
 where
     RuntimeApi:
         ConstructRuntimeApi<Block, FullClient<RuntimeApi, Executor>> + Send + Sync + 'static,
@@ -657,7 +683,7 @@ where
         RuntimeApiCollection<StateBackend = sc_client_api::StateBackendFor<FullBackend, Block>>,
     Executor: NativeExecutionDispatch + 'static,
 {
-    new_full_base(config).map(|base: NewFullBase<RuntimeApi, Executor>| base.task_manager)
+    new_full_base(config, archive).map(|base: NewFullBase<RuntimeApi, Executor>| base.task_manager)
 }
 
 /// Can be called for a `Configuration` to check if it is a configuration for the `ChainX` network.
@@ -684,12 +710,12 @@ impl IdentifyVariant for Box<dyn sc_service::ChainSpec> {
     }
 }
 
-pub fn build_full(config: Configuration) -> Result<TaskManager, ServiceError> {
+pub fn build_full(config: Configuration, archive: bool) -> Result<TaskManager, ServiceError> {
     if config.chain_spec.is_chainx() {
-        new_full::<chainx_runtime::RuntimeApi, chainx_executor::ChainXExecutor>(config)
+        new_full::<chainx_runtime::RuntimeApi, chainx_executor::ChainXExecutor>(config, archive)
     } else if config.chain_spec.is_malan() {
-        new_full::<malan_runtime::RuntimeApi, chainx_executor::MalanExecutor>(config)
+        new_full::<malan_runtime::RuntimeApi, chainx_executor::MalanExecutor>(config, archive)
     } else {
-        new_full::<dev_runtime::RuntimeApi, chainx_executor::DevExecutor>(config)
+        new_full::<dev_runtime::RuntimeApi, chainx_executor::DevExecutor>(config, archive)
     }
 }
@@ -0,0 +1,574 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Prometheus metrics of the ChainX specific subsystems.
+//!
+//! The metrics are registered into the prometheus registry of the node, and served by the
+//! same exporter as the substrate metrics (`--prometheus-port`), with the registry prefix
+//! of the node prepended to the names below. They are updated on the import of each new
+//! best block, from the runtime APIs at that block and the extrinsics it contains.
+//!
+//! | Name                                  | Type    | Labels    |
+//! |---------------------------------------|---------|-----------|
+//! | `chainx_btc_header_lag`               | gauge   |           |
+//! | `chainx_btc_relay_lag_seconds`        | gauge   |           |
+//! | `chainx_btc_bridge_halted`            | gauge   |           |
+//! | `chainx_pending_withdrawals`          | gauge   | `state`   |
+//! | `chainx_btc_proposal_age_blocks`      | gauge   |           |
+//! | `chainx_spot_pair_latest_price`       | gauge   | `pair_id` |
+//! | `chainx_spot_orders_per_block`        | gauge   |           |
+//! | `chainx_extrinsics_total`             | counter | `pallet`  |
+//! | `chainx_txpool_ready`                 | gauge   |           |
+//! | `chainx_txpool_xassets_transfers`     | gauge   |           |
+//!
+//! The names and the labels are part of the interface for the operators, do not change
+//! them once released.
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use codec::{Decode, Encode};
+use frame_metadata::{RuntimeMetadata, RuntimeMetadataPrefixed};
+use futures::StreamExt;
+use sc_client_api::{BlockBackend, BlockchainEvents};
+use sc_transaction_pool_api::{InPoolTransaction, TransactionPool};
+use scale_info::TypeDef;
+use sp_api::{ApiExt, Core, Metadata, ProvideRuntimeApi};
+use sp_runtime::{generic::BlockId, traits::Header as _, OpaqueExtrinsic};
+use substrate_prometheus_endpoint::{
+    register, CounterVec, Gauge, GaugeVec, Opts, PrometheusError, Registry, F64, U64,
+};
+
+use chainx_primitives::{AccountId, Balance, Block, BlockNumber, Signature};
+use xpallet_dex_spot_rpc_runtime_api::{FullPairInfo, XSpotApi};
+#[cfg(test)]
+use xpallet_gateway_bitcoin_rpc_runtime_api::VoteResult;
+use xpallet_gateway_bitcoin_rpc_runtime_api::{
+    BtcBridgeStatus, BtcWithdrawalProposal, XGatewayBitcoinApi,
+};
+use xpallet_gateway_records_rpc_runtime_api::{
+    Withdrawal, WithdrawalRecordId, WithdrawalState, XGatewayRecordsApi,
+};
+
+const LOG_TARGET: &str = "metrics";
+
+/// The pallet and call indices of a call, i.e., the first two bytes of an encoded call.
+type CallIndex = (u8, u8);
+
+/// The signature part of a signed extrinsic of the ChainX runtimes.
+///
+/// All the ChainX runtimes share the same address, signature and signed extensions.
+type ExtrinsicSignature = (
+    chainx_runtime::Address,
+    Signature,
+    chainx_runtime::SignedExtra,
+);
+
+/// The extrinsic format version of the ChainX runtimes.
+const EXTRINSIC_FORMAT_VERSION: u8 = 4;
+
+/// The calls counted as the transfers of xassets.
+const XASSETS_TRANSFERS: &[(&str, &str)] = &[("XAssets", "transfer"), ("XAssets", "transfer_v2")];
+
+/// The call counted as a new spot order.
+const SPOT_PUT_ORDER: (&str, &str) = ("XSpot", "put_order");
+
+/// Returns the call index of an extrinsic, `None` if it's not a ChainX extrinsic.
+///
+/// The extrinsic is decoded by hand instead of `UncheckedExtrinsic::decode`, which rejects
+/// the extrinsic whose call arguments are left undecoded in the length check.
+fn call_index_of(extrinsic: &OpaqueExtrinsic) -> Option<CallIndex> {
+    let encoded = extrinsic.encode();
+    let input = &mut encoded.as_slice();
+    // The length prefix of the extrinsic.
+    codec::Compact::<u32>::decode(input).ok()?;
+    let version = u8::decode(input).ok()?;
+    if version & 0b0111_1111 != EXTRINSIC_FORMAT_VERSION {
+        return None;
+    }
+    if version & 0b1000_0000 != 0 {
+        ExtrinsicSignature::decode(input).ok()?;
+    }
+    CallIndex::decode(input).ok()
+}
+
+/// The names of the pallets and the calls of a runtime, read from its metadata.
+#[derive(Debug, Default)]
+pub struct CallNames(BTreeMap<CallIndex, (String, String)>);
+
+impl CallNames {
+    /// Collects the call names from the encoded runtime metadata.
+    pub fn from_metadata(mut metadata: &[u8]) -> Result<Self, String> {
+        let prefixed = RuntimeMetadataPrefixed::decode(&mut metadata)
+            .map_err(|err| format!("Invalid metadata: {}", err))?;
+        let metadata = match prefixed.1 {
+            RuntimeMetadata::V14(metadata) => metadata,
+            _ => return Err("Unsupported metadata version".into()),
+        };
+
+        let mut names = BTreeMap::new();
+        for pallet in &metadata.pallets {
+            let calls = match pallet
+                .calls
+                .as_ref()
+                .and_then(|calls| metadata.types.resolve(calls.ty.id()))
+            {
+                Some(ty) => ty,
+                None => continue,
+            };
+            if let TypeDef::Variant(variants) = calls.type_def() {
+                for call in variants.variants() {
+                    names.insert(
+                        (pallet.index, call.index()),
+                        (pallet.name.clone(), call.name().clone()),
+                    );
+                }
+            }
+        }
+        Ok(Self(names))
+    }
+
+    /// Returns the (pallet, call) names of an extrinsic.
+    fn names_of(&self, extrinsic: &OpaqueExtrinsic) -> Option<(&str, &str)> {
+        call_index_of(extrinsic)
+            .and_then(|index| self.0.get(&index))
+            .map(|(pallet, call)| (pallet.as_str(), call.as_str()))
+    }
+}
+
+/// The ChainX specific metrics.
+#[derive(Clone)]
+pub struct Metrics {
+    /// The bitcoin blocks between the best header and the confirmed header.
+    btc_header_lag: Gauge<U64>,
+    /// The average seconds between the bitcoin timestamp and the relay of the recent headers.
+    btc_relay_lag_seconds: Gauge<U64>,
+    /// 1 if the bitcoin bridge is halted, 0 otherwise.
+    btc_bridge_halted: Gauge<U64>,
+    /// The withdrawal records not finished yet, by `state` (`applying` or `processing`).
+    pending_withdrawals: GaugeVec<U64>,
+    /// The ChainX blocks since the oldest withdrawal of the current bitcoin withdrawal
+    /// proposal was applied, 0 if there is no proposal.
+    btc_proposal_age_blocks: Gauge<U64>,
+    /// The latest executed price of each spot trading pair, by `pair_id`.
+    spot_pair_latest_price: GaugeVec<F64>,
+    /// The `put_order` calls in the latest best block.
+    spot_orders_per_block: Gauge<U64>,
+    /// The extrinsics in the imported best blocks, by the name of the `pallet` called.
+    extrinsics_total: CounterVec<U64>,
+    /// The ready transactions in the transaction pool.
+    txpool_ready: Gauge<U64>,
+    /// The ready transactions in the transaction pool transferring xassets.
+    txpool_xassets_transfers: Gauge<U64>,
+}
+
+impl Metrics {
+    /// Registers the metrics into `registry`.
+    pub fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+        Ok(Self {
+            btc_header_lag: register(
+                Gauge::new(
+                    "chainx_btc_header_lag",
+                    "Bitcoin blocks between the best and the confirmed header",
+                )?,
+                registry,
+            )?,
+            btc_relay_lag_seconds: register(
+                Gauge::new(
+                    "chainx_btc_relay_lag_seconds",
+                    "Average seconds between the bitcoin timestamp and the relay of the recent headers",
+                )?,
+                registry,
+            )?,
+            btc_bridge_halted: register(
+                Gauge::new(
+                    "chainx_btc_bridge_halted",
+                    "1 if the bitcoin bridge is halted, 0 otherwise",
+                )?,
+                registry,
+            )?,
+            pending_withdrawals: register(
+                GaugeVec::new(
+                    Opts::new(
+                        "chainx_pending_withdrawals",
+                        "Withdrawal records not finished yet",
+                    ),
+                    &["state"],
+                )?,
+                registry,
+            )?,
+            btc_proposal_age_blocks: register(
+                Gauge::new(
+                    "chainx_btc_proposal_age_blocks",
+                    "Blocks since the oldest withdrawal of the current bitcoin proposal was applied",
+                )?,
+                registry,
+            )?,
+            spot_pair_latest_price: register(
+                GaugeVec::new(
+                    Opts::new(
+                        "chainx_spot_pair_latest_price",
+                        "Latest executed price of the spot trading pair",
+                    ),
+                    &["pair_id"],
+                )?,
+                registry,
+            )?,
+            spot_orders_per_block: register(
+                Gauge::new(
+                    "chainx_spot_orders_per_block",
+                    "Spot orders put in the latest best block",
+                )?,
+                registry,
+            )?,
+            extrinsics_total: register(
+                CounterVec::new(
+                    Opts::new(
+                        "chainx_extrinsics_total",
+                        "Extrinsics in the imported best blocks by pallet",
+                    ),
+                    &["pallet"],
+                )?,
+                registry,
+            )?,
+            txpool_ready: register(
+                Gauge::new("chainx_txpool_ready", "Ready transactions in the pool")?,
+                registry,
+            )?,
+            txpool_xassets_transfers: register(
+                Gauge::new(
+                    "chainx_txpool_xassets_transfers",
+                    "Ready transactions in the pool transferring xassets",
+                )?,
+                registry,
+            )?,
+        })
+    }
+
+    /// Counts the calls of the extrinsics in a new best block.
+    fn note_block_extrinsics(&self, names: &CallNames, extrinsics: &[OpaqueExtrinsic]) {
+        let mut spot_orders = 0;
+        for extrinsic in extrinsics {
+            let (pallet, call) = names.names_of(extrinsic).unwrap_or(("unknown", "unknown"));
+            self.extrinsics_total.with_label_values(&[pallet]).inc();
+            if (pallet, call) == SPOT_PUT_ORDER {
+                spot_orders += 1;
+            }
+        }
+        self.spot_orders_per_block.set(spot_orders);
+    }
+
+    /// Counts the ready transactions in the transaction pool.
+    fn note_pool<'a>(&self, names: &CallNames, ready: impl Iterator<Item = &'a OpaqueExtrinsic>) {
+        let (mut total, mut transfers) = (0, 0);
+        for extrinsic in ready {
+            total += 1;
+            if names
+                .names_of(extrinsic)
+                .map_or(false, |names| XASSETS_TRANSFERS.contains(&names))
+            {
+                transfers += 1;
+            }
+        }
+        self.txpool_ready.set(total);
+        self.txpool_xassets_transfers.set(transfers);
+    }
+
+    fn note_bridge_status(&self, status: &BtcBridgeStatus) {
+        let confirmed = status.confirmed_height.unwrap_or_default();
+        self.btc_header_lag
+            .set(status.best_height.saturating_sub(confirmed).into());
+        self.btc_relay_lag_seconds
+            .set(status.average_relay_lag.unwrap_or_default());
+        self.btc_bridge_halted.set(status.halted.into());
+    }
+
+    fn note_withdrawals(
+        &self,
+        withdrawals: &BTreeMap<WithdrawalRecordId, Withdrawal<AccountId, Balance, BlockNumber>>,
+        proposal: Option<&BtcWithdrawalProposal<AccountId>>,
+        now: BlockNumber,
+    ) {
+        let count_of = |state: WithdrawalState| {
+            withdrawals
+                .values()
+                .filter(|withdrawal| withdrawal.state == state)
+                .count() as u64
+        };
+        self.pending_withdrawals
+            .with_label_values(&["applying"])
+            .set(count_of(WithdrawalState::Applying));
+        self.pending_withdrawals
+            .with_label_values(&["processing"])
+            .set(count_of(WithdrawalState::Processing));
+
+        let oldest = proposal.and_then(|proposal| {
+            proposal
+                .withdrawal_id_list
+                .iter()
+                .filter_map(|id| withdrawals.get(id))
+                .map(|withdrawal| withdrawal.height)
+                .min()
+        });
+        self.btc_proposal_age_blocks
+            .set(oldest.map_or(0, |height| now.saturating_sub(height).into()));
+    }
+
+    fn note_pairs(&self, pairs: &[FullPairInfo<Balance, Balance, BlockNumber>]) {
+        for pair in pairs {
+            self.spot_pair_latest_price
+                .with_label_values(&[&pair.profile.id.to_string()])
+                .set(pair.pair_info.latest_price as f64);
+        }
+    }
+}
+
+/// Updates the metrics on the import of each new best block.
+pub async fn metrics_task<C, P>(client: Arc<C>, pool: Arc<P>, metrics: Metrics)
+where
+    C: ProvideRuntimeApi<Block> + BlockBackend<Block> + BlockchainEvents<Block>,
+    C::Api: Core<Block>
+        + Metadata<Block>
+        + XGatewayBitcoinApi<Block, AccountId>
+        + XGatewayRecordsApi<Block, AccountId, Balance, BlockNumber>
+        + XSpotApi<Block, AccountId, Balance, BlockNumber, Balance>,
+    P: TransactionPool<Block = Block>,
+{
+    // The call names are cached by the spec version of the runtime.
+    let mut names: Option<(u32, CallNames)> = None;
+    let mut import_notifications = client.import_notification_stream();
+    while let Some(notification) = import_notifications.next().await {
+        if !notification.is_new_best {
+            continue;
+        }
+        let number = *notification.header.number();
+        if let Err(err) = note_best_block(
+            &*client,
+            &*pool,
+            &metrics,
+            &mut names,
+            notification.hash,
+            number,
+        ) {
+            log::warn!(target: LOG_TARGET, "Failed to update the metrics of block #{}: {}", number, err);
+        }
+    }
+}
+
+fn note_best_block<C, P>(
+    client: &C,
+    pool: &P,
+    metrics: &Metrics,
+    names: &mut Option<(u32, CallNames)>,
+    hash: <Block as sp_runtime::traits::Block>::Hash,
+    number: BlockNumber,
+) -> Result<(), String>
+where
+    C: ProvideRuntimeApi<Block> + BlockBackend<Block>,
+    C::Api: Core<Block>
+        + Metadata<Block>
+        + XGatewayBitcoinApi<Block, AccountId>
+        + XGatewayRecordsApi<Block, AccountId, Balance, BlockNumber>
+        + XSpotApi<Block, AccountId, Balance, BlockNumber, Balance>,
+    P: TransactionPool<Block = Block>,
+{
+    let at = BlockId::hash(hash);
+    let api = client.runtime_api();
+
+    let spec_version = api
+        .version(&at)
+        .map_err(|err| err.to_string())?
+        .spec_version;
+    if names.as_ref().map(|(version, _)| *version) != Some(spec_version) {
+        let metadata = api.metadata(&at).map_err(|err| err.to_string())?;
+        *names = Some((spec_version, CallNames::from_metadata(&metadata)?));
+    }
+    let names = &names.as_ref().expect("Call names are set above; qed").1;
+
+    let extrinsics = client
+        .block_body(&at)
+        .map_err(|err| err.to_string())?
+        .unwrap_or_default();
+    metrics.note_block_extrinsics(names, &extrinsics);
+    let ready = pool.ready().map(|tx| tx.data().clone()).collect::<Vec<_>>();
+    metrics.note_pool(names, ready.iter());
+
+    // The runtimes of the old blocks imported during the major sync may lack the APIs,
+    // the metrics of those are left as is.
+    let has_bitcoin_api = api
+        .has_api::<dyn XGatewayBitcoinApi<Block, AccountId>>(&at)
+        .map_err(|err| err.to_string())?;
+    if has_bitcoin_api {
+        let status = api.get_bridge_status(&at).map_err(|err| err.to_string())?;
+        metrics.note_bridge_status(&status);
+    }
+
+    let has_records_api = api
+        .has_api::<dyn XGatewayRecordsApi<Block, AccountId, Balance, BlockNumber>>(&at)
+        .map_err(|err| err.to_string())?;
+    if has_records_api && has_bitcoin_api {
+        let withdrawals = api.withdrawal_list(&at).map_err(|err| err.to_string())?;
+        let proposal = api
+            .get_withdrawal_proposal(&at)
+            .map_err(|err| err.to_string())?;
+        metrics.note_withdrawals(&withdrawals, proposal.as_ref(), number);
+    }
+
+    let spot_api_version = api
+        .api_version::<dyn XSpotApi<Block, AccountId, Balance, BlockNumber, Balance>>(&at)
+        .map_err(|err| err.to_string())?;
+    match spot_api_version {
+        Some(version) if version >= 3 => {
+            let pairs = api
+                .trading_pairs(&at, false)
+                .map_err(|err| err.to_string())?;
+            metrics.note_pairs(&pairs);
+        }
+        Some(_) => {
+            #[allow(deprecated)]
+            let pairs = api
+                .trading_pairs_before_version_3(&at)
+                .map_err(|err| err.to_string())?;
+            let pairs = pairs.into_iter().map(Into::into).collect::<Vec<_>>();
+            metrics.note_pairs(&pairs);
+        }
+        None => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use substrate_prometheus_endpoint::prometheus::{Encoder, TextEncoder};
+
+    fn dev_call_names() -> CallNames {
+        CallNames::from_metadata(&dev_runtime::Runtime::metadata().encode()).unwrap()
+    }
+
+    /// Returns an unsigned extrinsic calling `pallet::call` with the encoded `args`.
+    fn unsigned_extrinsic(
+        names: &CallNames,
+        pallet: &str,
+        call: &str,
+        args: &[u8],
+    ) -> OpaqueExtrinsic {
+        let (index, _) = names
+            .0
+            .iter()
+            .find(|(_, names)| names.0 == pallet && names.1 == call)
+            .unwrap();
+        // The unsigned extrinsic of version 4.
+        let mut body = vec![4u8, index.0, index.1];
+        body.extend_from_slice(args);
+        OpaqueExtrinsic::from_bytes(&body.encode()).unwrap()
+    }
+
+    /// Parses the text exposition format into (name with labels, value).
+    fn parse_payload(payload: &str) -> BTreeMap<String, f64> {
+        payload
+            .lines()
+            .filter(|line| !line.starts_with('#') && !line.is_empty())
+            .map(|line| {
+                let (name, value) = line.rsplit_once(' ').expect("sample has a value; qed");
+                (
+                    name.to_string(),
+                    value.parse().expect("value is a number; qed"),
+                )
+            })
+            .collect()
+    }
+
+    fn gather(registry: &Registry) -> BTreeMap<String, f64> {
+        let mut buffer = vec![];
+        TextEncoder::new()
+            .encode(&registry.gather(), &mut buffer)
+            .unwrap();
+        parse_payload(&String::from_utf8(buffer).unwrap())
+    }
+
+    #[test]
+    fn block_extrinsics_should_move_the_counters() {
+        let registry = Registry::new();
+        let metrics = Metrics::register(&registry).unwrap();
+        let names = dev_call_names();
+
+        let timestamp =
+            unsigned_extrinsic(&names, "Timestamp", "set", &codec::Compact(1u64).encode());
+        let transfer = unsigned_extrinsic(&names, "XAssets", "transfer", &[0u8; 8]);
+        let put_order = unsigned_extrinsic(&names, "XSpot", "put_order", &[0u8; 8]);
+
+        metrics.note_block_extrinsics(&names, &[timestamp.clone(), put_order.clone(), put_order]);
+        let samples = gather(&registry);
+        assert_eq!(
+            samples["chainx_extrinsics_total{pallet=\"Timestamp\"}"],
+            1.0
+        );
+        assert_eq!(samples["chainx_extrinsics_total{pallet=\"XSpot\"}"], 2.0);
+        assert_eq!(samples["chainx_spot_orders_per_block"], 2.0);
+
+        metrics.note_block_extrinsics(&names, &[timestamp, transfer.clone()]);
+        metrics.note_pool(&names, [transfer.clone(), transfer].iter());
+
+        let samples = gather(&registry);
+        assert_eq!(
+            samples["chainx_extrinsics_total{pallet=\"Timestamp\"}"],
+            2.0
+        );
+        assert_eq!(samples["chainx_extrinsics_total{pallet=\"XSpot\"}"], 2.0);
+        assert_eq!(samples["chainx_extrinsics_total{pallet=\"XAssets\"}"], 1.0);
+        assert_eq!(samples["chainx_spot_orders_per_block"], 0.0);
+        assert_eq!(samples["chainx_txpool_ready"], 2.0);
+        assert_eq!(samples["chainx_txpool_xassets_transfers"], 2.0);
+    }
+
+    #[test]
+    fn exporter_should_serve_parseable_payload() {
+        let registry = Registry::new();
+        let metrics = Metrics::register(&registry).unwrap();
+
+        metrics.note_bridge_status(&BtcBridgeStatus {
+            best_height: 700_010,
+            confirmed_height: Some(700_006),
+            halted: true,
+            relayer_whitelist_enabled: false,
+            relayer_count: 3,
+            average_relay_lag: Some(600),
+        });
+        let withdrawal = |height, state| Withdrawal {
+            asset_id: 1,
+            applicant: AccountId::new([1u8; 32]),
+            balance: 100,
+            addr: b"addr".to_vec(),
+            ext: b"ext".to_vec().into(),
+            height,
+            state,
+        };
+        let withdrawals = vec![
+            (0, withdrawal(10, WithdrawalState::Processing)),
+            (1, withdrawal(12, WithdrawalState::Processing)),
+            (2, withdrawal(15, WithdrawalState::Applying)),
+        ]
+        .into_iter()
+        .collect();
+        let proposal = BtcWithdrawalProposal {
+            sig_state: VoteResult::Unfinish,
+            withdrawal_id_list: vec![0, 1],
+            tx: Default::default(),
+            trustee_list: vec![],
+        };
+        metrics.note_withdrawals(&withdrawals, Some(&proposal), 20);
+
+        let samples = gather(&registry);
+        assert_eq!(samples["chainx_btc_header_lag"], 4.0);
+        assert_eq!(samples["chainx_btc_relay_lag_seconds"], 600.0);
+        assert_eq!(samples["chainx_btc_bridge_halted"], 1.0);
+        assert_eq!(
+            samples["chainx_pending_withdrawals{state=\"applying\"}"],
+            1.0
+        );
+        assert_eq!(
+            samples["chainx_pending_withdrawals{state=\"processing\"}"],
+            2.0
+        );
+        assert_eq!(samples["chainx_btc_proposal_age_blocks"], 10.0);
+    }
+}
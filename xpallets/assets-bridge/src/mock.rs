@@ -118,6 +118,9 @@ impl xpallet_assets::Config for Test {
     type TreasuryAccount = ();
     type OnCreatedAccount = frame_system::Provider<Test>;
     type OnAssetChanged = ();
+    type OnReceived = ();
+    type MoveGuard = ();
+    type QuarantineOrigin = frame_system::EnsureRoot<AccountId32>;
     type WeightInfo = ();
 }
 
@@ -5,7 +5,7 @@ use frame_system::RawOrigin;
 
 use chainx_primitives::AssetId;
 
-use crate::{AssetInfo, AssetInfoOf, AssetOnline, Call, Chain, Config, Pallet};
+use crate::{AssetInfo, AssetInfoOf, AssetOnline, AssetSourceRefOf, Call, Chain, Config, Pallet};
 
 const ASSET_ID: AssetId = 8888;
 
@@ -23,14 +23,14 @@ fn b_asset_info_test_data<T: Config>() -> AssetInfo {
 benchmarks! {
     register {
         let asset_info = b_asset_info_test_data::<T>();
-    }: _(RawOrigin::Root, ASSET_ID, asset_info.clone(), true, true)
+    }: _(RawOrigin::Root, ASSET_ID, asset_info.clone(), true, true, None, None)
     verify {
         assert_eq!(AssetInfoOf::<T>::get(ASSET_ID), Some(asset_info));
     }
 
     deregister {
         let asset_info = b_asset_info_test_data::<T>();
-        Pallet::<T>::register(RawOrigin::Root.into(), ASSET_ID, asset_info, true, true)?;
+        Pallet::<T>::register(RawOrigin::Root.into(), ASSET_ID, asset_info, true, true, None, None)?;
     }: _(RawOrigin::Root, ASSET_ID)
     verify {
         assert!(!AssetOnline::<T>::get(ASSET_ID));
@@ -38,7 +38,7 @@ benchmarks! {
 
     recover {
         let asset_info = b_asset_info_test_data::<T>();
-        Pallet::<T>::register(RawOrigin::Root.into(), ASSET_ID, asset_info, true, true)?;
+        Pallet::<T>::register(RawOrigin::Root.into(), ASSET_ID, asset_info, true, true, None, None)?;
         Pallet::<T>::deregister(RawOrigin::Root.into(), ASSET_ID)?;
     }: _(RawOrigin::Root, ASSET_ID, true)
     verify {
@@ -47,7 +47,7 @@ benchmarks! {
 
     update_asset_info {
         let asset_info = b_asset_info_test_data::<T>();
-        Pallet::<T>::register(RawOrigin::Root.into(), ASSET_ID, asset_info.clone(), true, true)?;
+        Pallet::<T>::register(RawOrigin::Root.into(), ASSET_ID, asset_info.clone(), true, true, None, None)?;
     }: _(
         RawOrigin::Root,
         ASSET_ID,
@@ -62,6 +62,14 @@ benchmarks! {
         new_asset_info.set_desc(b"new_desc".to_vec());
         assert_eq!(AssetInfoOf::<T>::get(ASSET_ID).unwrap(), new_asset_info);
     }
+
+    set_asset_source_ref {
+        let asset_info = b_asset_info_test_data::<T>();
+        Pallet::<T>::register(RawOrigin::Root.into(), ASSET_ID, asset_info, true, true, None, None)?;
+    }: _(RawOrigin::Root, ASSET_ID, Some(b"bitcoin:mainnet".to_vec()))
+    verify {
+        assert_eq!(AssetSourceRefOf::<T>::get(ASSET_ID), Some(b"bitcoin:mainnet".to_vec()));
+    }
 }
 
 impl_benchmark_test_suite!(
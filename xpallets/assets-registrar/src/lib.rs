@@ -35,7 +35,7 @@ pub use pallet::*;
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
-    use frame_support::pallet_prelude::*;
+    use frame_support::{pallet_prelude::*, transactional};
     use frame_system::pallet_prelude::*;
 
     /// The pallet's config trait.
@@ -65,36 +65,38 @@ pub mod pallet {
     impl<T: Config> Pallet<T> {
         /// Register a new foreign asset.
         ///
+        /// If `auto_pair` is given as `(pip_decimals, tick_decimals, initial_price)`,
+        /// the trading pair of the native asset against the new asset is created as well,
+        /// the whole registration fails if the trading pair can not be created.
+        ///
+        /// `source_ref` references the source of a bridged asset, e.g., the network and the
+        /// contract address on the original chain.
+        ///
         /// This is a root-only operation.
-        #[pallet::weight(T::WeightInfo::register())]
+        #[pallet::weight(T::WeightInfo::register().saturating_add(
+            if auto_pair.is_some() { T::DbWeight::get().reads_writes(2, 3) } else { 0 }
+        ).saturating_add(
+            if source_ref.is_some() { T::DbWeight::get().writes(1) } else { 0 }
+        ))]
+        #[transactional]
         pub fn register(
             origin: OriginFor<T>,
             #[pallet::compact] asset_id: AssetId,
             asset: AssetInfo,
             is_online: bool,
             has_mining_rights: bool,
+            auto_pair: Option<(u32, u32, u128)>,
+            source_ref: Option<Vec<u8>>,
         ) -> DispatchResult {
             ensure_root(origin)?;
-
-            asset.is_valid::<T>()?;
-            ensure!(!Self::exists(&asset_id), Error::<T>::AssetAlreadyExists);
-
-            info!(
-                target: "runtime::assets-registrar",
-                "[register_asset] id:{}, info:{:?}, is_online:{}, has_mining_rights:{}",
-                asset_id, asset, is_online, has_mining_rights
-            );
-
-            Self::apply_register(asset_id, asset)?;
-
-            Self::deposit_event(Event::Registered(asset_id, has_mining_rights));
-            T::RegistrarHandler::on_register(&asset_id, has_mining_rights)?;
-
-            if !is_online {
-                let _ = Self::deregister(frame_system::RawOrigin::Root.into(), asset_id);
-            }
-
-            Ok(())
+            Self::do_register(
+                asset_id,
+                asset,
+                is_online,
+                has_mining_rights,
+                auto_pair,
+                source_ref,
+            )
         }
 
         /// Deregister an asset with given `id`.
@@ -164,6 +166,26 @@ pub mod pallet {
             AssetInfoOf::<T>::insert(id, info);
             Ok(())
         }
+
+        /// Set or clear the source reference of an asset.
+        ///
+        /// This is a root-only operation.
+        #[pallet::weight(T::WeightInfo::set_asset_source_ref())]
+        pub fn set_asset_source_ref(
+            origin: OriginFor<T>,
+            #[pallet::compact] id: AssetId,
+            source_ref: Option<Vec<u8>>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            ensure!(Self::exists(&id), Error::<T>::AssetDoesNotExist);
+            if let Some(source_ref) = &source_ref {
+                verifier::is_valid_source_ref::<T>(source_ref)?;
+            }
+
+            Self::apply_source_ref(id, source_ref);
+            Ok(())
+        }
     }
 
     /// Event for the XAssetRegistrar Pallet
@@ -176,6 +198,8 @@ pub mod pallet {
         Recovered(AssetId, bool),
         /// An asset was deregistered. [asset_id]
         Deregistered(AssetId),
+        /// The source reference of an asset was set or cleared. [asset_id, source_ref]
+        AssetSourceRefSet(AssetId, Option<Vec<u8>>),
     }
 
     /// Error for the XAssetRegistrar Pallet
@@ -199,6 +223,8 @@ pub mod pallet {
         AssetAlreadyValid,
         /// The asset is invalid (not online).
         AssetIsInvalid,
+        /// Source reference length is zero or too long
+        InvalidAssetSourceRefLength,
     }
 
     /// Asset id list for each Chain.
@@ -223,6 +249,14 @@ pub mod pallet {
     pub(super) type RegisteredAt<T: Config> =
         StorageMap<_, Twox64Concat, AssetId, T::BlockNumber, ValueQuery>;
 
+    /// The source reference of each bridged asset, e.g., the network and the contract
+    /// address on the original chain.
+    ///
+    /// Kept apart from `AssetInfoOf` so that the encoding of `AssetInfo` stays unchanged.
+    #[pallet::storage]
+    #[pallet::getter(fn asset_source_ref_of)]
+    pub(super) type AssetSourceRefOf<T: Config> = StorageMap<_, Twox64Concat, AssetId, Vec<u8>>;
+
     /// add_extra_genesis
     #[pallet::genesis_config]
     #[cfg_attr(feature = "std", derive(Default))]
@@ -235,12 +269,13 @@ pub mod pallet {
         fn build(&self) {
             let extra_genesis_builder: fn(&Self) = |config| {
                 for (id, asset, is_online, has_mining_rights) in &config.assets {
-                    Pallet::<T>::register(
-                        frame_system::RawOrigin::Root.into(),
+                    Pallet::<T>::do_register(
                         *id,
                         asset.clone(),
                         *is_online,
                         *has_mining_rights,
+                        None,
+                        None,
                     )
                     .expect("asset registeration during the genesis can not fail");
                 }
@@ -322,6 +357,53 @@ impl<T: Config> Pallet<T> {
         Ok(())
     }
 
+    /// Registers an asset and creates its trading pair if `auto_pair` is given.
+    ///
+    /// Split from `register` as the genesis build does not support storage transactions.
+    fn do_register(
+        asset_id: AssetId,
+        asset: AssetInfo,
+        is_online: bool,
+        has_mining_rights: bool,
+        auto_pair: Option<(u32, u32, u128)>,
+        source_ref: Option<Vec<u8>>,
+    ) -> DispatchResult {
+        asset.is_valid::<T>()?;
+        if let Some(source_ref) = &source_ref {
+            verifier::is_valid_source_ref::<T>(source_ref)?;
+        }
+        ensure!(!Self::exists(&asset_id), Error::<T>::AssetAlreadyExists);
+
+        info!(
+            target: "runtime::assets-registrar",
+            "[register_asset] id:{}, info:{:?}, is_online:{}, has_mining_rights:{}",
+            asset_id, asset, is_online, has_mining_rights
+        );
+
+        Self::apply_register(asset_id, asset)?;
+
+        Self::deposit_event(Event::Registered(asset_id, has_mining_rights));
+        if source_ref.is_some() {
+            Self::apply_source_ref(asset_id, source_ref);
+        }
+        T::RegistrarHandler::on_register(&asset_id, has_mining_rights)?;
+
+        if let Some((pip_decimals, tick_decimals, initial_price)) = auto_pair {
+            T::RegistrarHandler::on_register_trading_pair(
+                &asset_id,
+                pip_decimals,
+                tick_decimals,
+                initial_price,
+            )?;
+        }
+
+        if !is_online {
+            let _ = Self::deregister(frame_system::RawOrigin::Root.into(), asset_id);
+        }
+
+        Ok(())
+    }
+
     /// Actually register an asset.
     fn apply_register(id: AssetId, asset: AssetInfo) -> DispatchResult {
         let chain = asset.chain();
@@ -338,4 +420,13 @@ impl<T: Config> Pallet<T> {
 
         Ok(())
     }
+
+    /// Sets the source reference of asset `id`, clears it if `source_ref` is `None`.
+    fn apply_source_ref(id: AssetId, source_ref: Option<Vec<u8>>) {
+        match &source_ref {
+            Some(source_ref) => AssetSourceRefOf::<T>::insert(id, source_ref),
+            None => AssetSourceRefOf::<T>::remove(id),
+        }
+        Self::deposit_event(Event::AssetSourceRefSet(id, source_ref));
+    }
 }
@@ -1,5 +1,6 @@
 // Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
 
+use codec::{Decode, Encode};
 use frame_support::{assert_noop, assert_ok, parameter_types, sp_io, traits::GenesisBuild};
 use sp_core::H256;
 use sp_runtime::{
@@ -145,10 +146,20 @@ fn test_register() {
             abc_assets.0,
             abc_assets.1.clone(),
             false,
-            false
+            false,
+            None,
+            None
         ));
         assert_noop!(
-            XAssetsRegistrar::register(Origin::root(), abc_assets.0, abc_assets.1, false, false),
+            XAssetsRegistrar::register(
+                Origin::root(),
+                abc_assets.0,
+                abc_assets.1,
+                false,
+                false,
+                None,
+                None
+            ),
             Err::AssetAlreadyExists
         );
 
@@ -176,3 +187,136 @@ fn test_register() {
         );
     })
 }
+
+#[test]
+fn set_asset_source_ref_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        let abc_id = 100;
+        let abc_info = AssetInfo::new::<Test>(
+            b"ABC".to_vec(),
+            b"ABC".to_vec(),
+            Chain::Ethereum,
+            18,
+            b"abc".to_vec(),
+        )
+        .unwrap();
+        let source_ref = b"ethereum:0xdac17f958d2ee523a2206206994597c13d831ec7".to_vec();
+        assert_ok!(XAssetsRegistrar::register(
+            Origin::root(),
+            abc_id,
+            abc_info,
+            true,
+            false,
+            None,
+            Some(source_ref.clone())
+        ));
+        assert_eq!(
+            XAssetsRegistrar::asset_source_ref_of(abc_id),
+            Some(source_ref)
+        );
+
+        assert_ok!(XAssetsRegistrar::set_asset_source_ref(
+            Origin::root(),
+            X_BTC,
+            Some(b"bitcoin:mainnet".to_vec())
+        ));
+        assert_eq!(
+            XAssetsRegistrar::asset_source_ref_of(X_BTC),
+            Some(b"bitcoin:mainnet".to_vec())
+        );
+        assert_ok!(XAssetsRegistrar::set_asset_source_ref(
+            Origin::root(),
+            X_BTC,
+            Some(b"bitcoin:testnet".to_vec())
+        ));
+        assert_eq!(
+            XAssetsRegistrar::asset_source_ref_of(X_BTC),
+            Some(b"bitcoin:testnet".to_vec())
+        );
+        assert_ok!(XAssetsRegistrar::set_asset_source_ref(
+            Origin::root(),
+            X_BTC,
+            None
+        ));
+        assert_eq!(XAssetsRegistrar::asset_source_ref_of(X_BTC), None);
+
+        assert_noop!(
+            XAssetsRegistrar::set_asset_source_ref(
+                Origin::signed(1),
+                X_BTC,
+                Some(b"bitcoin:mainnet".to_vec())
+            ),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            XAssetsRegistrar::set_asset_source_ref(
+                Origin::root(),
+                10000,
+                Some(b"bitcoin:mainnet".to_vec())
+            ),
+            Err::AssetDoesNotExist
+        );
+    })
+}
+
+#[test]
+fn invalid_asset_source_ref_should_be_rejected() {
+    ExtBuilder::default().build_and_execute(|| {
+        let set = |source_ref: &[u8]| {
+            XAssetsRegistrar::set_asset_source_ref(Origin::root(), X_BTC, Some(source_ref.to_vec()))
+        };
+        assert_noop!(set(b""), Err::InvalidAssetSourceRefLength);
+        assert_noop!(
+            set(&[b'a'; xp_protocol::ASSET_SOURCE_REF_MAX_LEN + 1]),
+            Err::InvalidAssetSourceRefLength
+        );
+        assert_noop!(set(b"bitcoin mainnet"), Err::InvalidAscii);
+        assert_noop!(set(b"bitcoin:\nmainnet"), Err::InvalidAscii);
+        assert_noop!(set("bitcoin:主网".as_bytes()), Err::InvalidAscii);
+        assert!(set(b"<script>").is_err());
+        assert_ok!(set(&[b'a'; xp_protocol::ASSET_SOURCE_REF_MAX_LEN]));
+
+        let abc_info = AssetInfo::new::<Test>(
+            b"ABC".to_vec(),
+            b"ABC".to_vec(),
+            Chain::Ethereum,
+            18,
+            b"abc".to_vec(),
+        )
+        .unwrap();
+        assert_noop!(
+            XAssetsRegistrar::register(
+                Origin::root(),
+                100,
+                abc_info,
+                true,
+                false,
+                None,
+                Some(b"ethereum: 0x00".to_vec())
+            ),
+            Err::InvalidAscii
+        );
+        assert!(!XAssetsRegistrar::exists(&100));
+    })
+}
+
+#[test]
+fn legacy_asset_info_should_decode_without_source_ref() {
+    ExtBuilder::default().build_and_execute(|| {
+        // The encoding of `AssetInfo` before the source reference was introduced.
+        let legacy = (
+            b"X-BTC".to_vec(),
+            b"X-BTC".to_vec(),
+            Chain::Bitcoin,
+            8u8,
+            b"ChainX's cross-chain Bitcoin".to_vec(),
+        )
+            .encode();
+        let (_, btc_info) = btc();
+        assert_eq!(btc_info.encode(), legacy);
+        assert_eq!(AssetInfo::decode(&mut legacy.as_slice()).unwrap(), btc_info);
+
+        assert_eq!(XAssetsRegistrar::asset_info_of(X_BTC), Some(btc_info));
+        assert_eq!(XAssetsRegistrar::asset_source_ref_of(X_BTC), None);
+    })
+}
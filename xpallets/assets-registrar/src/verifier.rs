@@ -1,6 +1,9 @@
 // Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
 
-use xp_protocol::{ASSET_DESC_MAX_LEN, ASSET_TOKEN_NAME_MAX_LEN, ASSET_TOKEN_SYMBOL_MAX_LEN};
+use xp_protocol::{
+    ASSET_DESC_MAX_LEN, ASSET_SOURCE_REF_MAX_LEN, ASSET_TOKEN_NAME_MAX_LEN,
+    ASSET_TOKEN_SYMBOL_MAX_LEN,
+};
 
 use super::*;
 
@@ -46,6 +49,21 @@ pub fn is_valid_desc<T: Config>(desc: &[u8]) -> DispatchResult {
     Ok(())
 }
 
+/// A valid source reference should have a legal length and be visible ASCII chars without
+/// the space only, e.g., `bitcoin:mainnet` or `ethereum:0x<contract address>`.
+pub fn is_valid_source_ref<T: Config>(source_ref: &[u8]) -> DispatchResult {
+    if source_ref.len() > ASSET_SOURCE_REF_MAX_LEN || source_ref.is_empty() {
+        return Err(Error::<T>::InvalidAssetSourceRefLength.into());
+    }
+    xp_runtime::xss_check(source_ref)?;
+    for c in source_ref {
+        if !c.is_ascii_graphic() {
+            return Err(Error::<T>::InvalidAscii.into());
+        }
+    }
+    Ok(())
+}
+
 /// Visible ASCII char [0x20, 0x7E]
 #[inline]
 fn is_ascii_visible(c: &u8) -> bool {
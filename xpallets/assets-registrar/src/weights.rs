@@ -35,6 +35,7 @@ pub trait WeightInfo {
     fn deregister() -> Weight;
     fn recover() -> Weight;
     fn update_asset_info() -> Weight;
+    fn set_asset_source_ref() -> Weight;
 }
 
 /// Weights for xpallet_assets_registrar using the Substrate node and recommended hardware.
@@ -60,6 +61,13 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
             .saturating_add(T::DbWeight::get().reads(1 as Weight))
             .saturating_add(T::DbWeight::get().writes(1 as Weight))
     }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_asset_source_ref` benchmark.
+    fn set_asset_source_ref() -> Weight {
+        (12_954_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
 }
 
 // For backwards compatibility and tests
@@ -84,4 +92,11 @@ impl WeightInfo for () {
             .saturating_add(RocksDbWeight::get().reads(1 as Weight))
             .saturating_add(RocksDbWeight::get().writes(1 as Weight))
     }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_asset_source_ref` benchmark.
+    fn set_asset_source_ref() -> Weight {
+        (12_954_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
 }
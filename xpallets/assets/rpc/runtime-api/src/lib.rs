@@ -3,14 +3,17 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::too_many_arguments, clippy::unnecessary_mut_passed)]
 
-use sp_std::collections::btree_map::BTreeMap;
+use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
 
 use codec::Codec;
 
 pub use chainx_primitives::AssetId;
-pub use xpallet_assets::{AssetType, TotalAssetInfo};
+pub use xpallet_assets::{AccountStatus, AssetType, DerivedAccountKind, TotalAssetInfo};
 
 sp_api::decl_runtime_apis! {
+    /// Version 2 adds `asset_balance_storage_key` and `account_status`.
+    /// Version 3 adds `identify_account`.
+    #[api_version(3)]
     pub trait XAssetsApi<AccountId, Balance>
     where
         AccountId: Codec,
@@ -19,5 +22,11 @@ sp_api::decl_runtime_apis! {
         fn assets_for_account(who: AccountId) -> BTreeMap<AssetId, BTreeMap<AssetType, Balance>>;
 
         fn assets() -> BTreeMap<AssetId, TotalAssetInfo<Balance>>;
+
+        fn asset_balance_storage_key(who: AccountId, asset_id: AssetId) -> Vec<u8>;
+
+        fn account_status(who: AccountId) -> AccountStatus<Balance>;
+
+        fn identify_account(who: AccountId) -> Option<DerivedAccountKind<AccountId>>;
     }
 }
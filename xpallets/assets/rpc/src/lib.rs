@@ -7,18 +7,24 @@ use std::sync::Arc;
 
 use codec::Codec;
 use jsonrpc_derive::rpc;
+use serde::{Deserialize, Serialize};
 
-use sp_api::ProvideRuntimeApi;
+use sc_client_api::ProofProvider;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
 use sp_runtime::{
     generic::BlockId,
-    traits::{Block as BlockT, Zero},
+    traits::{Block as BlockT, HashFor, Header as HeaderT, Zero},
 };
 
-use xp_rpc::{runtime_error_into_rpc_err, Result, RpcBalance};
+use xp_rpc::{
+    display_amount, runtime_error_into_rpc_err, unsupported_api_rpc_err, Result, RpcBalance,
+};
 
 use xpallet_assets_rpc_runtime_api::{
-    AssetId, AssetType, TotalAssetInfo, XAssetsApi as XAssetsRuntimeApi,
+    AccountStatus, AssetId, AssetType, DerivedAccountKind, TotalAssetInfo,
+    XAssetsApi as XAssetsRuntimeApi,
 };
 
 pub struct Assets<C, B> {
@@ -36,6 +42,37 @@ impl<C, B> Assets<C, B> {
     }
 }
 
+impl<C, Block> Assets<C, Block>
+where
+    Block: BlockT,
+    C: ProvideRuntimeApi<Block>,
+{
+    /// Returns an error if the runtime at `at` provides an `XAssetsApi` older than `version`.
+    fn ensure_api_version<AccountId, Balance>(
+        &self,
+        at: &BlockId<Block>,
+        version: u32,
+    ) -> Result<()>
+    where
+        C::Api: XAssetsRuntimeApi<Block, AccountId, Balance>,
+        AccountId: Codec,
+        Balance: Codec,
+    {
+        let supported = self
+            .client
+            .runtime_api()
+            .has_api_with::<dyn XAssetsRuntimeApi<Block, AccountId, Balance>, _>(at, |v| {
+                v >= version
+            })
+            .map_err(runtime_error_into_rpc_err)?;
+        if supported {
+            Ok(())
+        } else {
+            Err(unsupported_api_rpc_err("XAssetsApi", version))
+        }
+    }
+}
+
 #[rpc]
 pub trait XAssetsApi<BlockHash, AccountId, Balance>
 where
@@ -54,13 +91,46 @@ where
     fn assets(
         &self,
         at: Option<BlockHash>,
-    ) -> Result<BTreeMap<AssetId, TotalAssetInfo<RpcBalance<Balance>>>>;
+    ) -> Result<BTreeMap<AssetId, RpcTotalAssetInfo<Balance>>>;
+
+    /// Return a combined storage proof of the asset balances of the (account, asset) pairs,
+    /// which could be checked against the state root by the light clients of other chains.
+    /// The proof is generated at the latest finalized block if `at` is not given.
+    #[rpc(name = "chainx_proveAssetBalance")]
+    fn prove_asset_balance(
+        &self,
+        keys: Vec<(AccountId, AssetId)>,
+        at: Option<BlockHash>,
+    ) -> Result<AssetBalanceProof<BlockHash>>;
+
+    /// Return whether the account exists, its free PCX and the number of the foreign assets
+    /// it holds, a PCX transfer below the existential deposit to a non-existent account fails.
+    #[rpc(name = "chainx_getAccountStatus")]
+    fn account_status(
+        &self,
+        who: AccountId,
+        at: Option<BlockHash>,
+    ) -> Result<AccountStatus<RpcBalance<Balance>>>;
+
+    /// Return what a derived account belongs to, e.g., the asset of a token jackpot or the
+    /// validator of an intention jackpot, `null` for a normal account.
+    #[rpc(name = "chainx_identifyAccount")]
+    fn identify_account(
+        &self,
+        who: AccountId,
+        at: Option<BlockHash>,
+    ) -> Result<Option<DerivedAccountKind<AccountId>>>;
 }
 
 impl<C, Block, AccountId, Balance> XAssetsApi<<Block as BlockT>::Hash, AccountId, Balance>
     for Assets<C, Block>
 where
-    C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    C: Send
+        + Sync
+        + 'static
+        + ProvideRuntimeApi<Block>
+        + HeaderBackend<Block>
+        + ProofProvider<Block>,
     C::Api: XAssetsRuntimeApi<Block, AccountId, Balance>,
     Block: BlockT,
     AccountId: Clone + Display + Codec,
@@ -93,32 +163,41 @@ where
     fn assets(
         &self,
         at: Option<<Block as BlockT>::Hash>,
-    ) -> Result<BTreeMap<AssetId, TotalAssetInfo<RpcBalance<Balance>>>> {
+    ) -> Result<BTreeMap<AssetId, RpcTotalAssetInfo<Balance>>> {
         let api = self.client.runtime_api();
         let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
         api.assets(&at)
             .map(|map| {
                 map.into_iter()
                     .map(|(id, info)| {
-                        let balance = AssetType::iter()
+                        let decimals = info.info.decimals();
+                        let raw_balance = AssetType::iter()
                             .map(|ty| {
                                 (
                                     *ty,
-                                    info.balance
-                                        .get(ty)
-                                        .copied()
-                                        .unwrap_or_else(Balance::zero)
-                                        .into(),
+                                    info.balance.get(ty).copied().unwrap_or_else(Balance::zero),
                                 )
                             })
                             .collect::<BTreeMap<_, _>>();
+                        let balance_display = raw_balance
+                            .iter()
+                            .map(|(ty, balance)| (*ty, display_amount(balance, decimals)))
+                            .collect();
+                        let balance = raw_balance
+                            .into_iter()
+                            .map(|(ty, balance)| (ty, balance.into()))
+                            .collect();
                         (
                             id,
-                            TotalAssetInfo::<RpcBalance<Balance>> {
-                                info: info.info,
-                                balance,
-                                is_online: info.is_online,
-                                restrictions: info.restrictions,
+                            RpcTotalAssetInfo {
+                                total: TotalAssetInfo::<RpcBalance<Balance>> {
+                                    info: info.info,
+                                    balance,
+                                    is_online: info.is_online,
+                                    restrictions: info.restrictions,
+                                    source_ref: info.source_ref,
+                                },
+                                balance_display,
                             },
                         )
                     })
@@ -126,4 +205,120 @@ where
             })
             .map_err(runtime_error_into_rpc_err)
     }
+
+    fn prove_asset_balance(
+        &self,
+        keys: Vec<(AccountId, AssetId)>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<AssetBalanceProof<<Block as BlockT>::Hash>> {
+        let api = self.client.runtime_api();
+        let block_hash = at.unwrap_or_else(|| self.client.info().finalized_hash);
+        let at = BlockId::hash(block_hash);
+        self.ensure_api_version::<AccountId, Balance>(&at, 2)?;
+
+        let storage_keys = keys
+            .into_iter()
+            .map(|(who, asset_id)| api.asset_balance_storage_key(&at, who, asset_id))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(runtime_error_into_rpc_err)?;
+
+        let state_root = *self
+            .client
+            .header(at)
+            .map_err(runtime_error_into_rpc_err)?
+            .ok_or_else(|| runtime_error_into_rpc_err("Header not found"))?
+            .state_root();
+        let proof = self
+            .client
+            .read_proof(&at, &mut storage_keys.iter().map(|key| key.as_slice()))
+            .map_err(runtime_error_into_rpc_err)?;
+
+        // Read the values back from the proof, exactly what the external verifiers would get.
+        let values = sp_state_machine::read_proof_check::<HashFor<Block>, _>(
+            state_root,
+            proof.clone(),
+            &storage_keys,
+        )
+        .map_err(runtime_error_into_rpc_err)?;
+        let entries = storage_keys
+            .into_iter()
+            .map(|key| {
+                let value = values.get(&key).cloned().flatten().map(Into::into);
+                AssetBalanceEntry {
+                    key: key.into(),
+                    value,
+                }
+            })
+            .collect();
+
+        Ok(AssetBalanceProof {
+            block_hash,
+            state_root,
+            entries,
+            proof: proof.iter_nodes().map(Into::into).collect(),
+        })
+    }
+
+    fn account_status(
+        &self,
+        who: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<AccountStatus<RpcBalance<Balance>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        self.ensure_api_version::<AccountId, Balance>(&at, 2)?;
+        api.account_status(&at, who)
+            .map(|status| AccountStatus {
+                exists: status.exists,
+                pcx_free: status.pcx_free.into(),
+                pcx_locked: status.pcx_locked.into(),
+                lock_count: status.lock_count,
+                has_foreign_assets: status.has_foreign_assets,
+                asset_count: status.asset_count,
+                reapable: status.reapable,
+            })
+            .map_err(runtime_error_into_rpc_err)
+    }
+
+    fn identify_account(
+        &self,
+        who: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<DerivedAccountKind<AccountId>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        self.ensure_api_version::<AccountId, Balance>(&at, 3)?;
+        api.identify_account(&at, who)
+            .map_err(runtime_error_into_rpc_err)
+    }
+}
+
+/// The storage proof of some asset balances at a block.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetBalanceProof<Hash> {
+    pub block_hash: Hash,
+    pub state_root: Hash,
+    /// The requested balances, in the order of the requested pairs.
+    pub entries: Vec<AssetBalanceEntry>,
+    /// The trie nodes of the combined proof.
+    pub proof: Vec<Bytes>,
+}
+
+/// The storage key and the SCALE-encoded `AssetBalance` value, `None` if the
+/// account has never held the asset.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetBalanceEntry {
+    pub key: Bytes,
+    pub value: Option<Bytes>,
+}
+
+/// `TotalAssetInfo` with the balances rendered by the asset precision.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcTotalAssetInfo<Balance: Display + FromStr> {
+    #[serde(flatten)]
+    pub total: TotalAssetInfo<RpcBalance<Balance>>,
+    pub balance_display: BTreeMap<AssetType, String>,
 }
@@ -49,6 +49,7 @@ benchmarks! {
         balances.insert(AssetType::Reserved, 1000u32.into());
         balances.insert(AssetType::ReservedWithdrawal, 1000u32.into());
         balances.insert(AssetType::ReservedDexSpot, 1000u32.into());
+        balances.insert(AssetType::ReservedScheduled, 1000u32.into());
     }: set_balance(RawOrigin::Root, user_lookup, ASSET_ID, balances.clone())
     verify {
         assert_eq!(XAssets::<T>::asset_balance(&user, &ASSET_ID), balances);
@@ -60,6 +61,170 @@ benchmarks! {
     verify {
         assert_eq!(XAssets::<T>::asset_restrictions_of(&ASSET_ID), res);
     }
+
+    schedule_transfer {
+        let caller = whitelisted_caller();
+        let transfer_amount: BalanceOf<T> = (100000000 * 10_u32).into(); // e.g. 10 btc
+        XAssets::<T>::issue(&ASSET_ID, &caller, transfer_amount, true).unwrap();
+
+        let recipient: T::AccountId = account("recipient", 0, SEED);
+        let recipient_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(recipient);
+        let unlock_block = frame_system::Pallet::<T>::block_number() + 10u32.into();
+        let memo: Memo = b"vesting".to_vec().into();
+    }: _(RawOrigin::Signed(caller.clone()), recipient_lookup, ASSET_ID, transfer_amount, unlock_block, memo)
+    verify {
+        assert_eq!(XAssets::<T>::usable_balance(&caller, &ASSET_ID), Zero::zero());
+        assert_eq!(XAssets::<T>::asset_balance_of(&caller, &ASSET_ID, AssetType::ReservedScheduled), transfer_amount);
+    }
+
+    execute_scheduled {
+        let caller: T::AccountId = whitelisted_caller();
+        let sender: T::AccountId = account("sender", 0, SEED);
+        let transfer_amount: BalanceOf<T> = (100000000 * 10_u32).into(); // e.g. 10 btc
+        XAssets::<T>::issue(&ASSET_ID, &sender, transfer_amount, true).unwrap();
+
+        let recipient: T::AccountId = account("recipient", 0, SEED);
+        let recipient_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(recipient.clone());
+        let unlock_block = frame_system::Pallet::<T>::block_number() + 10u32.into();
+        XAssets::<T>::schedule_transfer(
+            RawOrigin::Signed(sender.clone()).into(),
+            recipient_lookup,
+            ASSET_ID,
+            transfer_amount,
+            unlock_block,
+            b"vesting".to_vec().into(),
+        )?;
+        frame_system::Pallet::<T>::set_block_number(unlock_block);
+    }: _(RawOrigin::Signed(caller), 0)
+    verify {
+        assert_eq!(XAssets::<T>::all_type_asset_balance(&sender, &ASSET_ID), Zero::zero());
+        assert_eq!(XAssets::<T>::usable_balance(&recipient, &ASSET_ID), transfer_amount);
+    }
+
+    cancel_scheduled {
+        let caller = whitelisted_caller();
+        let transfer_amount: BalanceOf<T> = (100000000 * 10_u32).into(); // e.g. 10 btc
+        XAssets::<T>::issue(&ASSET_ID, &caller, transfer_amount, true).unwrap();
+
+        let recipient: T::AccountId = account("recipient", 0, SEED);
+        let recipient_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(recipient);
+        let unlock_block = frame_system::Pallet::<T>::block_number() + 10u32.into();
+        XAssets::<T>::schedule_transfer(
+            RawOrigin::Signed(caller.clone()).into(),
+            recipient_lookup,
+            ASSET_ID,
+            transfer_amount,
+            unlock_block,
+            b"vesting".to_vec().into(),
+        )?;
+    }: _(RawOrigin::Signed(caller.clone()), 0)
+    verify {
+        assert_eq!(XAssets::<T>::usable_balance(&caller, &ASSET_ID), transfer_amount);
+        assert!(XAssets::<T>::scheduled_transfers(0).is_none());
+    }
+
+    set_transfer_notify {
+        let receiver: T::AccountId = account("receiver", 0, SEED);
+        let receiver_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(receiver.clone());
+    }: _(RawOrigin::Root, receiver_lookup, true)
+    verify {
+        assert!(XAssets::<T>::transfer_notify_of(&receiver));
+    }
+
+    prune_unknown_asset_types {
+        let user: T::AccountId = account("user", 0, SEED);
+        let user_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(user.clone());
+        XAssets::<T>::issue(&ASSET_ID, &user, 1000u32.into(), true).unwrap();
+        AssetBalance::<T>::mutate(&user, &ASSET_ID, |balances| {
+            balances.insert(AssetType::Locked, Zero::zero());
+        });
+    }: _(RawOrigin::Root, ASSET_ID, user_lookup)
+    verify {
+        assert_eq!(XAssets::<T>::asset_balance(&user, &ASSET_ID).len(), 1);
+    }
+
+    set_max_issuance_per_window {
+        let cap = IssuanceCap { cap: 1000u32.into(), window_blocks: 100 };
+    }: _(RawOrigin::Root, ASSET_ID, Some(cap))
+    verify {
+        assert_eq!(XAssets::<T>::max_issuance_per_window(ASSET_ID), Some(cap));
+    }
+
+    release_quarantined {
+        let n in 1 .. 100;
+        let cap = IssuanceCap { cap: Zero::zero(), window_blocks: 100 };
+        XAssets::<T>::set_max_issuance_per_window(RawOrigin::Root.into(), ASSET_ID, Some(cap))?;
+        for i in 0..n {
+            let user: T::AccountId = account("user", i, SEED);
+            XAssets::<T>::issue(&ASSET_ID, &user, 1000u32.into(), true).unwrap();
+        }
+        let origin = T::QuarantineOrigin::successful_origin();
+    }: _<T::Origin>(origin, n)
+    verify {
+        assert!(XAssets::<T>::quarantined_issuances().is_empty());
+    }
+
+    reject_quarantined {
+        let n in 1 .. 100;
+        let cap = IssuanceCap { cap: Zero::zero(), window_blocks: 100 };
+        XAssets::<T>::set_max_issuance_per_window(RawOrigin::Root.into(), ASSET_ID, Some(cap))?;
+        for i in 0..n {
+            let user: T::AccountId = account("user", i, SEED);
+            XAssets::<T>::issue(&ASSET_ID, &user, 1000u32.into(), true).unwrap();
+        }
+        let ids = (0..n).collect::<Vec<_>>();
+        let origin = T::QuarantineOrigin::successful_origin();
+    }: _<T::Origin>(origin, ids)
+    verify {
+        assert!(XAssets::<T>::quarantined_issuances().is_empty());
+    }
+
+    impose_lock {
+        let user: T::AccountId = account("user", 0, SEED);
+        let user_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(user.clone());
+        let lock = vesting_lock::<T>();
+        for _ in 1..MAX_VESTING_LOCKS {
+            XAssets::<T>::impose_lock(RawOrigin::Root.into(), user_lookup.clone(), lock.clone())?;
+        }
+    }: _(RawOrigin::Root, user_lookup, lock)
+    verify {
+        assert_eq!(XAssets::<T>::locks_of(&user).len(), MAX_VESTING_LOCKS);
+    }
+
+    remove_lock {
+        let user: T::AccountId = account("user", 0, SEED);
+        let user_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(user.clone());
+        let lock = vesting_lock::<T>();
+        for _ in 0..MAX_VESTING_LOCKS {
+            XAssets::<T>::impose_lock(RawOrigin::Root.into(), user_lookup.clone(), lock.clone())?;
+        }
+    }: _(RawOrigin::Root, user_lookup, 0)
+    verify {
+        assert_eq!(XAssets::<T>::locks_of(&user).len(), MAX_VESTING_LOCKS - 1);
+    }
+
+    unlock_vested {
+        let caller: T::AccountId = whitelisted_caller();
+        let caller_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(caller.clone());
+        let lock = vesting_lock::<T>();
+        for _ in 0..MAX_VESTING_LOCKS {
+            XAssets::<T>::impose_lock(RawOrigin::Root.into(), caller_lookup.clone(), lock.clone())?;
+        }
+        frame_system::Pallet::<T>::set_block_number(1000u32.into());
+    }: _(RawOrigin::Signed(caller.clone()))
+    verify {
+        assert!(XAssets::<T>::locks_of(&caller).is_empty());
+    }
+}
+
+fn vesting_lock<T: Config>() -> LockInfo<BalanceOf<T>, T::BlockNumber> {
+    LockInfo {
+        asset_id: T::NativeAssetId::get(),
+        amount: 1000u32.into(),
+        unlock_start_block: Zero::zero(),
+        per_block_release: 10u32.into(),
+        nominatable: false,
+    }
 }
 
 #[cfg(test)]
@@ -75,6 +240,17 @@ mod tests {
             assert_ok!(Pallet::<Test>::test_benchmark_force_transfer());
             assert_ok!(Pallet::<Test>::test_benchmark_set_balance());
             assert_ok!(Pallet::<Test>::test_benchmark_set_asset_limit());
+            assert_ok!(Pallet::<Test>::test_benchmark_schedule_transfer());
+            assert_ok!(Pallet::<Test>::test_benchmark_execute_scheduled());
+            assert_ok!(Pallet::<Test>::test_benchmark_cancel_scheduled());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_transfer_notify());
+            assert_ok!(Pallet::<Test>::test_benchmark_prune_unknown_asset_types());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_max_issuance_per_window());
+            assert_ok!(Pallet::<Test>::test_benchmark_release_quarantined());
+            assert_ok!(Pallet::<Test>::test_benchmark_reject_quarantined());
+            assert_ok!(Pallet::<Test>::test_benchmark_impose_lock());
+            assert_ok!(Pallet::<Test>::test_benchmark_remove_lock());
+            assert_ok!(Pallet::<Test>::test_benchmark_unlock_vested());
         });
     }
 }
@@ -0,0 +1,57 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Documentation of the events and errors, served by `chainx_getRuntimeDocs`.
+
+xpallet_support::pallet_docs! {
+    "XAssets",
+    events {
+        Moved => "Some balances of an asset was moved from one to another. [asset_id, from, from_type, to, to_type, amount]",
+        Issued => "New balances of an asset were issued. [asset_id, receiver, amount]",
+        Destroyed => "Some balances of an asset were destoryed. [asset_id, who, amount]",
+        BalanceSet => "Set asset balance of an account by root. [asset_id, who, asset_type, amount]",
+        TransferScheduled => "A transfer was scheduled. [schedule_id, asset_id, from, dest, amount, unlock_block]",
+        ScheduledTransferExecuted => "A scheduled transfer was executed. [schedule_id]",
+        ScheduledTransferCancelled => "A scheduled transfer was cancelled by its transactor. [schedule_id]",
+        TransferNotifySet => "The transfer notification of an account was changed. [who, notify]",
+        ReceiveHookFailed => "The `OnReceived` hook failed, the move was kept. [asset_id, from, to, amount]",
+        AssetTypesPruned => "Stray entries were removed from the balance maps, keyed by the raw asset type index. [asset_id, who, account_entries, total_entries]",
+        MoveForbidden => "A move was rejected by the move guard. [asset_id, from_type, to_type, caller]",
+        OutflowForbidden => "An outflow of a derived account was rejected by the move guard. [who, caller]",
+        MaxIssuancePerWindowUpdated => "The issuance cap of an asset was updated. [asset_id, cap]",
+        IssuanceQuarantined => "An issuance beyond the cap was quarantined. [quarantine_id, asset_id, who, amount]",
+        QuarantinedIssuanceReleased => "A quarantined issuance was credited. [quarantine_id]",
+        QuarantinedIssuanceRejected => "A quarantined issuance was dropped. [quarantine_id]",
+        BalanceHeld => "Some balance of an account was held by a module. [asset_id, who, amount]",
+        HoldReleased => "Some held balance of an account was released. [asset_id, who, amount]",
+        LockImposed => "A vesting lock was imposed on an account. [who, lock]",
+        LockRemoved => "A vesting lock of an account was removed. [who, lock]",
+    }
+    errors {
+        InvalidAsset => "Got and Invalid Asset",
+        Overflow => "Got an overflow after adding",
+        InsufficientBalance => "Balance too low to send value",
+        LiquidityRestrictions => "Failed because liquidity restrictions due to locking",
+        AmountIntoBalanceFailed => "Cannot convert Amount into Balance type",
+        TotalAssetOverflow => "Got an overflow after adding",
+        TotalAssetInsufficientBalance => "Balance too low to send value",
+        DenyNativeAsset => "Not Allow native asset,",
+        ActionNotAllowed => "Action is not allowed.",
+        StillHasActiveReserved => "Account still has active reserved",
+        NoProvider => "Unable to increment the consumer reference counters on the account. Either no provider reference exists to allow a non-zero balance of a non-self-sufficient asset, or the maximum number of consumers has been reached.",
+        ZeroBalance => "The value of the scheduled transfer is zero",
+        InvalidUnlockBlock => "The unlock block of the scheduled transfer is not in the future",
+        ScheduledTransferNotFound => "The scheduled transfer does not exist",
+        ScheduledTransferLocked => "The scheduled transfer is still locked",
+        ScheduledTransferUnlocked => "The scheduled transfer has been unlocked and can not be cancelled",
+        NotScheduledTransferOwner => "Only the transactor is allowed to cancel the scheduled transfer",
+        UnknownAssetType => "The balance map holds the asset types unknown to this runtime, prune them first",
+        MoveForbidden => "The move between the asset types is forbidden for the caller",
+        InvalidIssuanceCap => "The window of the issuance cap can not be zero",
+        QuarantinedIssuanceNotFound => "The quarantined issuance does not exist",
+        InvalidLock => "The amount and the release per block of the vesting lock can not be zero",
+        TooManyLocks => "The account already has the most vesting locks",
+        LockNotFound => "The vesting lock does not exist",
+        TooManyQuarantinedIssuances => "Too many quarantined issuances are pending, release or reject them first",
+        WouldKill => "Transfer would drop the balance of transactor below the minimum balance",
+    }
+}
@@ -8,6 +8,8 @@
 
 #[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
+mod docs;
+pub mod migrations;
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
@@ -18,9 +20,12 @@ mod trigger;
 pub mod types;
 pub mod weights;
 
-use sp_std::collections::btree_map::{
-    BTreeMap,
-    Entry::{Occupied, Vacant},
+use sp_std::collections::{
+    btree_map::{
+        BTreeMap,
+        Entry::{Occupied, Vacant},
+    },
+    btree_set::BTreeSet,
 };
 
 use frame_support::{
@@ -28,19 +33,34 @@ use frame_support::{
     ensure,
     inherent::Vec,
     log::{debug, error, info},
-    traits::{Currency, Get, HandleLifetime, LockableCurrency, ReservableCurrency},
+    storage::{unhashed, with_transaction, TransactionOutcome},
+    traits::{
+        Currency, ExistenceRequirement, Get, HandleLifetime, LockableCurrency, ReservableCurrency,
+        WithdrawReasons,
+    },
+    weights::DispatchClass,
 };
 
 use frame_system::{ensure_root, ensure_signed, AccountInfo};
 use sp_runtime::traits::{CheckedAdd, CheckedSub, Saturating, StaticLookup, Zero};
 
 use self::trigger::AssetChangedTrigger;
-use chainx_primitives::AssetId;
-use xpallet_support::traits::TreasuryAccount;
+use self::types::decode_raw_balance_map;
+use chainx_primitives::{
+    asset_event_topic,
+    digest::{AssetTotalsDigest, MAX_ASSET_TOTALS_DIGEST_ENTRIES},
+    AssetId,
+};
+use xp_runtime::Memo;
+use xpallet_support::traits::{BalanceMap, NativeHolds, TreasuryAccount};
 
-pub use self::traits::{ChainT, OnAssetChanged};
+pub use self::docs::PALLET_DOCS;
+pub use self::traits::{ChainT, DerivedAccountRegistry, MoveGuard, OnAssetChanged, OnReceived};
 pub use self::types::{
-    AssetErr, AssetRestrictions, AssetType, BalanceLock, TotalAssetInfo, WithdrawalLimit,
+    AccountStatus, AssetErr, AssetRestrictions, AssetType, BalanceLock, DerivedAccountKind,
+    IssuanceCap, IssuanceWindow, LockInfo, MoveCaller, QuarantineId, QuarantinedIssuance,
+    ScheduledTransfer, ScheduledTransferId, TotalAssetInfo, WithdrawalLimit, MAX_ASSET_TYPES,
+    MAX_QUARANTINED_ISSUANCES, MAX_VESTING_LOCKS, VESTING_LOCK_ID,
 };
 pub use self::weights::WeightInfo;
 pub use xpallet_assets_registrar::{AssetInfo, Chain};
@@ -53,7 +73,7 @@ pub use pallet::*;
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
-    use frame_support::pallet_prelude::*;
+    use frame_support::{pallet_prelude::*, transactional};
     use frame_system::pallet_prelude::*;
 
     /// The pallet's config trait.
@@ -77,15 +97,40 @@ pub mod pallet {
         /// The hook triggered whenever the asset balance of an account is changed.
         type OnAssetChanged: OnAssetChanged<Self::AccountId, BalanceOf<Self>>;
 
+        /// The hook triggered when the assets are moved into an account registered
+        /// in `TransferNotifyOf`.
+        type OnReceived: OnReceived<Self::AccountId, BalanceOf<Self>>;
+
+        /// The guard deciding which callers may move the assets between which asset types.
+        type MoveGuard: MoveGuard;
+
+        /// The origin which can release or reject the quarantined issuances.
+        type QuarantineOrigin: EnsureOrigin<Self::Origin>;
+
         /// Weight information for extrinsics in this pallet.
         type WeightInfo: WeightInfo;
     }
 
     #[pallet::pallet]
     #[pallet::generate_store(pub(super) trait Store)]
+    #[pallet::storage_version(migrations::STORAGE_VERSION)]
     #[pallet::without_storage_info]
     pub struct Pallet<T>(PhantomData<T>);
 
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
+            // Reserve the worst case of depositing the asset totals digest in `on_finalize`:
+            // taking the changed assets, reading the totals of at most
+            // `MAX_ASSET_TOTALS_DIGEST_ENTRIES` of them and appending to the header digest.
+            T::DbWeight::get().reads_writes(2 + MAX_ASSET_TOTALS_DIGEST_ENTRIES as u64, 2)
+        }
+
+        fn on_finalize(_n: BlockNumberFor<T>) {
+            Self::deposit_asset_totals_digest();
+        }
+    }
+
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         /// transfer between two accounts
@@ -100,13 +145,40 @@ pub mod pallet {
             let dest = T::Lookup::lookup(dest)?;
             debug!(target: "runtime::assets", "[transfer] from:{:?}, to:{:?}, id:{}, value:{:?}", transactor, dest, id, value);
             Self::can_transfer(&id)?;
+            Self::ensure_not_restricted(&transactor, &id, value)?;
 
-            Self::move_usable_balance(&id, &transactor, &dest, value)
+            Self::move_usable_balance(&id, &transactor, &dest, value, MoveCaller::User)
                 .map_err::<Error<T>, _>(Into::into)?;
 
             Ok(())
         }
 
+        /// transfer between two accounts, the usable balance of transactor is not allowed to
+        /// drop below the minimum balance of the asset if `keep_alive` is true
+        #[pallet::weight(0)]
+        pub fn transfer_v2(
+            origin: OriginFor<T>,
+            dest: <T::Lookup as StaticLookup>::Source,
+            #[pallet::compact] id: AssetId,
+            #[pallet::compact] value: BalanceOf<T>,
+            keep_alive: bool,
+        ) -> DispatchResult {
+            let transactor = ensure_signed(origin)?;
+            let dest = T::Lookup::lookup(dest)?;
+            debug!(target: "runtime::assets", "[transfer_v2] from:{:?}, to:{:?}, id:{}, value:{:?}, keep_alive:{}", transactor, dest, id, value, keep_alive);
+            Self::can_transfer(&id)?;
+            Self::ensure_not_restricted(&transactor, &id, value)?;
+
+            if keep_alive {
+                Self::move_balance_keep_alive(&id, &transactor, &dest, value, MoveCaller::User)
+            } else {
+                Self::move_usable_balance(&id, &transactor, &dest, value, MoveCaller::User)
+            }
+            .map_err::<Error<T>, _>(Into::into)?;
+
+            Ok(())
+        }
+
         /// transfer method reserved for root(sudo)
         #[pallet::weight(0)]
         pub fn force_transfer(
@@ -122,13 +194,22 @@ pub mod pallet {
             let dest = T::Lookup::lookup(dest)?;
             debug!(target: "runtime::assets", "[force_transfer] from:{:?}, to:{:?}, id:{}, value:{:?}", transactor, dest, id, value);
             Self::can_transfer(&id)?;
-            Self::move_usable_balance(&id, &transactor, &dest, value)
+            Self::move_usable_balance(&id, &transactor, &dest, value, MoveCaller::Root)
                 .map_err::<Error<T>, _>(Into::into)?;
             Ok(())
         }
 
         /// set free token for an account
-        #[pallet::weight(0)]
+        ///
+        /// The weight covers the balances, the total issuance and the mining weights updated
+        /// for each asset type.
+        #[pallet::weight(
+            10_000_000 + T::DbWeight::get().reads(4).saturating_add(
+                T::DbWeight::get()
+                    .reads_writes(6, 6)
+                    .saturating_mul(balances.len() as Weight)
+            )
+        )]
         pub fn set_balance(
             origin: OriginFor<T>,
             who: <T::Lookup as StaticLookup>::Source,
@@ -139,10 +220,27 @@ pub mod pallet {
 
             let who = T::Lookup::lookup(who)?;
             info!(target: "runtime::assets", "[set_balance] Set balance by root, who:{:?}, id:{}, balances:{:?}", who, id, balances);
+            Self::ensure_outflow_allowed(&who, MoveCaller::Root)
+                .map_err::<Error<T>, _>(Into::into)?;
             Self::set_balance_impl(&who, &id, balances)?;
             Ok(())
         }
 
+        /// set the minimum usable balance of an asset kept by the keep-alive transfer
+        #[pallet::weight(0)]
+        pub fn set_asset_min_balance(
+            origin: OriginFor<T>,
+            #[pallet::compact] id: AssetId,
+            #[pallet::compact] min_balance: BalanceOf<T>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            Self::ensure_not_native_asset(&id)?;
+            xpallet_assets_registrar::Pallet::<T>::ensure_asset_exists(&id)?;
+
+            AssetMinBalance::<T>::insert(id, min_balance);
+            Ok(())
+        }
+
         /// asset restriction method reserved for root
         #[pallet::weight(<T as Config>::WeightInfo::set_asset_limit())]
         pub fn set_asset_limit(
@@ -153,6 +251,301 @@ pub mod pallet {
             ensure_root(origin)?;
             Self::set_asset_restrictions(id, restrictions)
         }
+
+        /// Schedule a transfer to `dest` which can be executed by anyone since `unlock_block`.
+        ///
+        /// The value is moved to the `ReservedScheduled` balance of the transactor until the
+        /// transfer is executed or cancelled.
+        #[pallet::weight(<T as Config>::WeightInfo::schedule_transfer())]
+        pub fn schedule_transfer(
+            origin: OriginFor<T>,
+            dest: <T::Lookup as StaticLookup>::Source,
+            #[pallet::compact] id: AssetId,
+            #[pallet::compact] value: BalanceOf<T>,
+            unlock_block: T::BlockNumber,
+            memo: Memo,
+        ) -> DispatchResult {
+            let transactor = ensure_signed(origin)?;
+            let dest = T::Lookup::lookup(dest)?;
+            debug!(target: "runtime::assets", "[schedule_transfer] from:{:?}, to:{:?}, id:{}, value:{:?}, unlock_block:{:?}", transactor, dest, id, value, unlock_block);
+            Self::can_transfer(&id)?;
+            Self::ensure_not_restricted(&transactor, &id, value)?;
+            memo.check_validity()?;
+            ensure!(!value.is_zero(), Error::<T>::ZeroBalance);
+            ensure!(
+                unlock_block > frame_system::Pallet::<T>::block_number(),
+                Error::<T>::InvalidUnlockBlock
+            );
+
+            let schedule_id = Self::next_scheduled_transfer_id();
+            let next_id = schedule_id.checked_add(1).ok_or(Error::<T>::Overflow)?;
+
+            Self::move_balance(
+                &id,
+                &transactor,
+                AssetType::Usable,
+                &transactor,
+                AssetType::ReservedScheduled,
+                value,
+                MoveCaller::Assets,
+            )
+            .map_err::<Error<T>, _>(Into::into)?;
+
+            NextScheduledTransferId::<T>::put(next_id);
+            ScheduledTransfers::<T>::insert(
+                schedule_id,
+                ScheduledTransfer {
+                    asset_id: id,
+                    from: transactor.clone(),
+                    dest: dest.clone(),
+                    value,
+                    unlock_block,
+                    memo,
+                },
+            );
+            Self::deposit_event(Event::<T>::TransferScheduled(
+                schedule_id,
+                id,
+                transactor,
+                dest,
+                value,
+                unlock_block,
+            ));
+            Ok(())
+        }
+
+        /// Execute the scheduled transfer `schedule_id` whose unlock block has been reached.
+        #[pallet::weight(<T as Config>::WeightInfo::execute_scheduled())]
+        pub fn execute_scheduled(
+            origin: OriginFor<T>,
+            #[pallet::compact] schedule_id: ScheduledTransferId,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+
+            let transfer = Self::scheduled_transfers(schedule_id)
+                .ok_or(Error::<T>::ScheduledTransferNotFound)?;
+            ensure!(
+                frame_system::Pallet::<T>::block_number() >= transfer.unlock_block,
+                Error::<T>::ScheduledTransferLocked
+            );
+
+            Self::move_balance(
+                &transfer.asset_id,
+                &transfer.from,
+                AssetType::ReservedScheduled,
+                &transfer.dest,
+                AssetType::Usable,
+                transfer.value,
+                MoveCaller::Assets,
+            )
+            .map_err::<Error<T>, _>(Into::into)?;
+
+            ScheduledTransfers::<T>::remove(schedule_id);
+            Self::deposit_event(Event::<T>::ScheduledTransferExecuted(schedule_id));
+            Ok(())
+        }
+
+        /// Cancel the scheduled transfer `schedule_id` before its unlock block.
+        ///
+        /// Only the transactor of the scheduled transfer is allowed to cancel it.
+        #[pallet::weight(<T as Config>::WeightInfo::cancel_scheduled())]
+        pub fn cancel_scheduled(
+            origin: OriginFor<T>,
+            #[pallet::compact] schedule_id: ScheduledTransferId,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let transfer = Self::scheduled_transfers(schedule_id)
+                .ok_or(Error::<T>::ScheduledTransferNotFound)?;
+            ensure!(transfer.from == who, Error::<T>::NotScheduledTransferOwner);
+            ensure!(
+                frame_system::Pallet::<T>::block_number() < transfer.unlock_block,
+                Error::<T>::ScheduledTransferUnlocked
+            );
+
+            Self::move_balance(
+                &transfer.asset_id,
+                &who,
+                AssetType::ReservedScheduled,
+                &who,
+                AssetType::Usable,
+                transfer.value,
+                MoveCaller::Assets,
+            )
+            .map_err::<Error<T>, _>(Into::into)?;
+
+            ScheduledTransfers::<T>::remove(schedule_id);
+            Self::deposit_event(Event::<T>::ScheduledTransferCancelled(schedule_id));
+            Ok(())
+        }
+
+        /// Set whether the `OnReceived` hook is invoked for the assets moved into `who`.
+        #[pallet::weight(<T as Config>::WeightInfo::set_transfer_notify())]
+        pub fn set_transfer_notify(
+            origin: OriginFor<T>,
+            who: <T::Lookup as StaticLookup>::Source,
+            notify: bool,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            let who = T::Lookup::lookup(who)?;
+            Self::set_transfer_notify_of(&who, notify);
+            Ok(())
+        }
+
+        /// Remove the stray entries from the balance map of `who` and the total balance map
+        /// of asset `id`, i.e., the zero balances and the unknown asset types.
+        #[pallet::weight(<T as Config>::WeightInfo::prune_unknown_asset_types())]
+        pub fn prune_unknown_asset_types(
+            origin: OriginFor<T>,
+            #[pallet::compact] id: AssetId,
+            who: <T::Lookup as StaticLookup>::Source,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            let who = T::Lookup::lookup(who)?;
+            Self::prune_stray_asset_types(&id, &who);
+            Ok(())
+        }
+
+        /// Set the most balance of asset `id` allowed to be issued within a window of blocks.
+        ///
+        /// The issuance beyond the cap is quarantined until released by the council.
+        /// `None` removes the cap.
+        #[pallet::weight(<T as Config>::WeightInfo::set_max_issuance_per_window())]
+        pub fn set_max_issuance_per_window(
+            origin: OriginFor<T>,
+            #[pallet::compact] id: AssetId,
+            cap: Option<IssuanceCap<BalanceOf<T>>>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            Self::ensure_not_native_asset(&id)?;
+            if let Some(cap) = cap {
+                ensure!(cap.window_blocks > 0, Error::<T>::InvalidIssuanceCap);
+                MaxIssuancePerWindow::<T>::insert(id, cap);
+            } else {
+                MaxIssuancePerWindow::<T>::remove(id);
+                IssuanceWindowOf::<T>::remove(id);
+            }
+            Self::deposit_event(Event::<T>::MaxIssuancePerWindowUpdated(id, cap));
+            Ok(())
+        }
+
+        /// Credit at most `limit` of the oldest quarantined issuances.
+        ///
+        /// The released issuances do not count towards the cap.
+        #[pallet::weight(<T as Config>::WeightInfo::release_quarantined(*limit))]
+        #[transactional]
+        pub fn release_quarantined(origin: OriginFor<T>, limit: u32) -> DispatchResult {
+            T::QuarantineOrigin::ensure_origin(origin)?;
+
+            let mut quarantined = Self::quarantined_issuances();
+            let count = quarantined.len().min(limit as usize);
+            for issuance in quarantined.drain(..count) {
+                Self::inner_issue(
+                    &issuance.asset_id,
+                    &issuance.who,
+                    AssetType::Usable,
+                    issuance.value,
+                    issuance.reward_pcx,
+                )?;
+                Self::deposit_event(Event::<T>::QuarantinedIssuanceReleased(issuance.id));
+            }
+            QuarantinedIssuances::<T>::put(quarantined);
+            Ok(())
+        }
+
+        /// Drop the quarantined issuances `ids` without crediting them.
+        #[pallet::weight(<T as Config>::WeightInfo::reject_quarantined(ids.len() as u32))]
+        pub fn reject_quarantined(origin: OriginFor<T>, ids: Vec<QuarantineId>) -> DispatchResult {
+            T::QuarantineOrigin::ensure_origin(origin)?;
+
+            let mut quarantined = Self::quarantined_issuances();
+            ensure!(
+                ids.iter()
+                    .all(|id| quarantined.iter().any(|issuance| issuance.id == *id)),
+                Error::<T>::QuarantinedIssuanceNotFound
+            );
+            quarantined.retain(|issuance| !ids.contains(&issuance.id));
+            QuarantinedIssuances::<T>::put(quarantined);
+            for id in ids {
+                Self::deposit_event(Event::<T>::QuarantinedIssuanceRejected(id));
+            }
+            Ok(())
+        }
+
+        /// Impose a vesting lock on `who`, the unvested balance of the lock is not spendable.
+        #[pallet::weight(<T as Config>::WeightInfo::impose_lock())]
+        pub fn impose_lock(
+            origin: OriginFor<T>,
+            who: <T::Lookup as StaticLookup>::Source,
+            lock: LockInfo<BalanceOf<T>, T::BlockNumber>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            let who = T::Lookup::lookup(who)?;
+            ensure!(
+                !lock.amount.is_zero() && !lock.per_block_release.is_zero(),
+                Error::<T>::InvalidLock
+            );
+            if lock.asset_id != T::NativeAssetId::get() {
+                xpallet_assets_registrar::Pallet::<T>::ensure_asset_exists(&lock.asset_id)?;
+            }
+
+            Locks::<T>::try_mutate(&who, |locks| -> DispatchResult {
+                ensure!(locks.len() < MAX_VESTING_LOCKS, Error::<T>::TooManyLocks);
+                locks.push(lock.clone());
+                Ok(())
+            })?;
+            Self::update_vesting_lock(&who);
+
+            Self::deposit_event(Event::<T>::LockImposed(who, lock));
+            Ok(())
+        }
+
+        /// Remove the vesting lock at `index` of `who`.
+        #[pallet::weight(<T as Config>::WeightInfo::remove_lock())]
+        pub fn remove_lock(
+            origin: OriginFor<T>,
+            who: <T::Lookup as StaticLookup>::Source,
+            #[pallet::compact] index: u32,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            let who = T::Lookup::lookup(who)?;
+
+            let lock = Locks::<T>::try_mutate_exists(&who, |maybe_locks| {
+                let locks = maybe_locks.as_mut().ok_or(Error::<T>::LockNotFound)?;
+                let index = index as usize;
+                ensure!(index < locks.len(), Error::<T>::LockNotFound);
+                let lock = locks.remove(index);
+                if locks.is_empty() {
+                    *maybe_locks = None;
+                }
+                Ok::<_, Error<T>>(lock)
+            })?;
+            Self::update_vesting_lock(&who);
+
+            Self::deposit_event(Event::<T>::LockRemoved(who, lock));
+            Ok(())
+        }
+
+        /// Release the vested PCX of the sender from the native currency lock and drop the
+        /// vesting locks that have been fully released.
+        ///
+        /// The vested balances of the other assets are spendable without this call.
+        #[pallet::weight(<T as Config>::WeightInfo::unlock_vested())]
+        pub fn unlock_vested(origin: OriginFor<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+
+            let now = frame_system::Pallet::<T>::block_number();
+            Locks::<T>::mutate_exists(&who, |maybe_locks| {
+                if let Some(locks) = maybe_locks {
+                    locks.retain(|lock| !lock.locked_at(now).is_zero());
+                    if locks.is_empty() {
+                        *maybe_locks = None;
+                    }
+                }
+            });
+            Self::update_vesting_lock(&who);
+            Ok(())
+        }
     }
 
     /// Event for the Assets Pallet
@@ -174,6 +567,51 @@ pub mod pallet {
         Destroyed(AssetId, T::AccountId, BalanceOf<T>),
         /// Set asset balance of an account by root. [asset_id, who, asset_type, amount]
         BalanceSet(AssetId, T::AccountId, AssetType, BalanceOf<T>),
+        /// A transfer was scheduled. [schedule_id, asset_id, from, dest, amount, unlock_block]
+        TransferScheduled(
+            ScheduledTransferId,
+            AssetId,
+            T::AccountId,
+            T::AccountId,
+            BalanceOf<T>,
+            T::BlockNumber,
+        ),
+        /// A scheduled transfer was executed. [schedule_id]
+        ScheduledTransferExecuted(ScheduledTransferId),
+        /// A scheduled transfer was cancelled by its transactor. [schedule_id]
+        ScheduledTransferCancelled(ScheduledTransferId),
+        /// The transfer notification of an account was changed. [who, notify]
+        TransferNotifySet(T::AccountId, bool),
+        /// The `OnReceived` hook failed, the move was kept. [asset_id, from, to, amount]
+        ReceiveHookFailed(AssetId, T::AccountId, T::AccountId, BalanceOf<T>),
+        /// Stray entries were removed from the balance maps, keyed by the raw asset type index.
+        /// [asset_id, who, account_entries, total_entries]
+        AssetTypesPruned(
+            AssetId,
+            T::AccountId,
+            Vec<(u8, BalanceOf<T>)>,
+            Vec<(u8, BalanceOf<T>)>,
+        ),
+        /// A move was rejected by the move guard. [asset_id, from_type, to_type, caller]
+        MoveForbidden(AssetId, AssetType, AssetType, MoveCaller),
+        /// An outflow of a derived account was rejected by the move guard. [who, caller]
+        OutflowForbidden(T::AccountId, MoveCaller),
+        /// The issuance cap of an asset was updated. [asset_id, cap]
+        MaxIssuancePerWindowUpdated(AssetId, Option<IssuanceCap<BalanceOf<T>>>),
+        /// An issuance beyond the cap was quarantined. [quarantine_id, asset_id, who, amount]
+        IssuanceQuarantined(QuarantineId, AssetId, T::AccountId, BalanceOf<T>),
+        /// A quarantined issuance was credited. [quarantine_id]
+        QuarantinedIssuanceReleased(QuarantineId),
+        /// A quarantined issuance was dropped. [quarantine_id]
+        QuarantinedIssuanceRejected(QuarantineId),
+        /// Some balance of an account was held by a module. [asset_id, who, amount]
+        BalanceHeld(AssetId, T::AccountId, BalanceOf<T>),
+        /// Some held balance of an account was released. [asset_id, who, amount]
+        HoldReleased(AssetId, T::AccountId, BalanceOf<T>),
+        /// A vesting lock was imposed on an account. [who, lock]
+        LockImposed(T::AccountId, LockInfo<BalanceOf<T>, T::BlockNumber>),
+        /// A vesting lock of an account was removed. [who, lock]
+        LockRemoved(T::AccountId, LockInfo<BalanceOf<T>, T::BlockNumber>),
     }
 
     /// Error for the Assets Pallet
@@ -203,6 +641,36 @@ pub mod pallet {
         /// reference exists to allow a non-zero balance of a non-self-sufficient asset, or the
         /// maximum number of consumers has been reached.
         NoProvider,
+        /// The value of the scheduled transfer is zero
+        ZeroBalance,
+        /// The unlock block of the scheduled transfer is not in the future
+        InvalidUnlockBlock,
+        /// The scheduled transfer does not exist
+        ScheduledTransferNotFound,
+        /// The scheduled transfer is still locked
+        ScheduledTransferLocked,
+        /// The scheduled transfer has been unlocked and can not be cancelled
+        ScheduledTransferUnlocked,
+        /// Only the transactor is allowed to cancel the scheduled transfer
+        NotScheduledTransferOwner,
+        /// The balance map holds the asset types unknown to this runtime, prune them first
+        UnknownAssetType,
+        /// The move between the asset types is forbidden for the caller
+        MoveForbidden,
+        /// The window of the issuance cap can not be zero
+        InvalidIssuanceCap,
+        /// The quarantined issuance does not exist
+        QuarantinedIssuanceNotFound,
+        /// The amount and the release per block of the vesting lock can not be zero
+        InvalidLock,
+        /// The account already has the most vesting locks
+        TooManyLocks,
+        /// The vesting lock does not exist
+        LockNotFound,
+        /// Too many quarantined issuances are pending, release or reject them first
+        TooManyQuarantinedIssuances,
+        /// Transfer would drop the balance of transactor below the minimum balance
+        WouldKill,
     }
 
     /// asset extend limit properties, set asset "can do", example, `CanTransfer`, `CanDestroyWithdrawal`
@@ -232,6 +700,108 @@ pub mod pallet {
     pub type TotalAssetBalance<T: Config> =
         StorageMap<_, Twox64Concat, AssetId, BTreeMap<AssetType, BalanceOf<T>>, ValueQuery>;
 
+    /// The minimum usable balance that should be kept when moving the asset with
+    /// keep-alive semantics.
+    #[pallet::storage]
+    #[pallet::getter(fn asset_min_balance)]
+    pub type AssetMinBalance<T: Config> =
+        StorageMap<_, Twox64Concat, AssetId, BalanceOf<T>, ValueQuery>;
+
+    /// The assets whose total issuance has been changed in the current block.
+    #[pallet::storage]
+    pub(crate) type TotalIssuanceChanged<T: Config> =
+        StorageValue<_, BTreeSet<AssetId>, ValueQuery>;
+
+    /// The id of the next scheduled transfer.
+    #[pallet::storage]
+    #[pallet::getter(fn next_scheduled_transfer_id)]
+    pub type NextScheduledTransferId<T: Config> = StorageValue<_, ScheduledTransferId, ValueQuery>;
+
+    /// The pending scheduled transfers.
+    #[pallet::storage]
+    #[pallet::getter(fn scheduled_transfers)]
+    pub type ScheduledTransfers<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        ScheduledTransferId,
+        ScheduledTransfer<T::AccountId, BalanceOf<T>, T::BlockNumber>,
+    >;
+
+    /// The accounts for which the `OnReceived` hook is invoked when receiving assets.
+    #[pallet::storage]
+    #[pallet::getter(fn transfer_notify_of)]
+    pub type TransferNotifyOf<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, bool, ValueQuery>;
+
+    /// The most balance of an asset allowed to be issued within a window of blocks.
+    #[pallet::storage]
+    #[pallet::getter(fn max_issuance_per_window)]
+    pub type MaxIssuancePerWindow<T: Config> =
+        StorageMap<_, Twox64Concat, AssetId, IssuanceCap<BalanceOf<T>>>;
+
+    /// The balance of an asset issued in the current window of its issuance cap.
+    #[pallet::storage]
+    #[pallet::getter(fn issuance_window_of)]
+    pub type IssuanceWindowOf<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        AssetId,
+        IssuanceWindow<BalanceOf<T>, T::BlockNumber>,
+        ValueQuery,
+    >;
+
+    /// The issuances beyond the cap waiting to be released or rejected by the council,
+    /// oldest first.
+    #[pallet::storage]
+    #[pallet::getter(fn quarantined_issuances)]
+    pub type QuarantinedIssuances<T: Config> = StorageValue<
+        _,
+        Vec<QuarantinedIssuance<T::AccountId, BalanceOf<T>, T::BlockNumber>>,
+        ValueQuery,
+    >;
+
+    /// The id of the next quarantined issuance.
+    #[pallet::storage]
+    #[pallet::getter(fn next_quarantine_id)]
+    pub type NextQuarantineId<T: Config> = StorageValue<_, QuarantineId, ValueQuery>;
+
+    /// The balances of an account placed on hold by the modules, which are not spendable
+    /// although they are still in the free balance.
+    #[pallet::storage]
+    #[pallet::getter(fn held_balance)]
+    pub type Holds<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Twox64Concat,
+        AssetId,
+        BalanceOf<T>,
+        ValueQuery,
+    >;
+
+    /// The vesting locks of an account, whose unvested balances are not spendable.
+    #[pallet::storage]
+    #[pallet::getter(fn locks_of)]
+    pub type Locks<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Vec<LockInfo<BalanceOf<T>, T::BlockNumber>>,
+        ValueQuery,
+    >;
+
+    /// The owners of the accounts derived by the modules, e.g., the reward pots, which
+    /// are otherwise anonymous addresses.
+    #[pallet::storage]
+    #[pallet::getter(fn derived_account_of)]
+    pub type DerivedAccountOf<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        DerivedAccountKind<T::AccountId>,
+        OptionQuery,
+    >;
+
     #[pallet::genesis_config]
     pub struct GenesisConfig<T: Config> {
         pub assets_restrictions: Vec<(AssetId, AssetRestrictions)>,
@@ -282,6 +852,138 @@ impl<T: Config> Pallet<T> {
         Ok(())
     }
 
+    /// Registers or deregisters `who` for the `OnReceived` hook.
+    ///
+    /// This is meant for the modules owning the account, e.g. a contract module.
+    pub fn set_transfer_notify_of(who: &T::AccountId, notify: bool) {
+        if notify {
+            TransferNotifyOf::<T>::insert(who, true);
+        } else {
+            TransferNotifyOf::<T>::remove(who);
+        }
+        Self::deposit_event(Event::<T>::TransferNotifySet(who.clone(), notify));
+    }
+
+    /// Returns true if the stored balance map of `who` holds any asset type unknown to
+    /// this runtime, which would be dropped by decoding and rewriting the map.
+    pub fn has_unknown_asset_types(who: &T::AccountId, id: &AssetId) -> bool {
+        unhashed::get_raw(&AssetBalance::<T>::hashed_key_for(who, id))
+            .map(|raw| match decode_raw_balance_map::<BalanceOf<T>>(&raw) {
+                Some((_, unknown)) => !unknown.is_empty(),
+                None => true,
+            })
+            .unwrap_or(false)
+    }
+
+    /// Removes the zero balances and the unknown asset types from the balance map of `who`
+    /// and the total balance map of asset `id`, which are left by the older runtimes.
+    ///
+    /// The maps are read raw from the storage, since the entries of the asset types unknown
+    /// to this runtime fail the decoding of the whole map. The non-zero balances removed from
+    /// the account are also removed from the totals.
+    pub fn prune_stray_asset_types(id: &AssetId, who: &T::AccountId) {
+        let account_key = AssetBalance::<T>::hashed_key_for(who, id);
+        let mut account_pruned = Vec::new();
+        if let Some((mut balances, unknown)) = unhashed::get_raw(&account_key)
+            .and_then(|raw| decode_raw_balance_map::<BalanceOf<T>>(&raw))
+        {
+            account_pruned = unknown;
+            account_pruned.extend(
+                balances
+                    .iter()
+                    .filter(|(_, balance)| balance.is_zero())
+                    .map(|(ty, balance)| (ty.index(), *balance)),
+            );
+            if !account_pruned.is_empty() {
+                balances.retain(|_, balance| !balance.is_zero());
+                if balances.is_empty() {
+                    AssetBalance::<T>::remove(who, id);
+                    frame_system::Pallet::<T>::dec_consumers(who);
+                } else {
+                    AssetBalance::<T>::insert(who, id, balances);
+                }
+            }
+        }
+
+        let total_key = TotalAssetBalance::<T>::hashed_key_for(id);
+        let mut total_pruned = Vec::new();
+        if let Some((mut total, mut unknown)) = unhashed::get_raw(&total_key)
+            .and_then(|raw| decode_raw_balance_map::<BalanceOf<T>>(&raw))
+        {
+            for (index, balance) in &account_pruned {
+                if let Some(ty) = AssetType::from_index(*index) {
+                    if let Some(total_balance) = total.get_mut(&ty) {
+                        *total_balance = total_balance.saturating_sub(*balance);
+                    }
+                } else if let Some((_, total_balance)) =
+                    unknown.iter_mut().find(|(i, _)| i == index)
+                {
+                    *total_balance = total_balance.saturating_sub(*balance);
+                }
+            }
+            total_pruned = unknown;
+            total_pruned.extend(
+                total
+                    .iter()
+                    .filter(|(_, balance)| balance.is_zero())
+                    .map(|(ty, balance)| (ty.index(), *balance)),
+            );
+            if !total_pruned.is_empty() {
+                total.retain(|_, balance| !balance.is_zero());
+                TotalAssetBalance::<T>::insert(id, total);
+            }
+        }
+
+        if !account_pruned.is_empty() || !total_pruned.is_empty() {
+            Self::deposit_event(Event::<T>::AssetTypesPruned(
+                *id,
+                who.clone(),
+                account_pruned,
+                total_pruned,
+            ));
+        }
+    }
+
+    /// Invokes the `OnReceived` hook for the assets moved into `to`.
+    ///
+    /// The receiver must not be able to block the senders, so a failed hook only
+    /// has its own changes reverted, the move itself is kept.
+    fn notify_received(id: &AssetId, from: &T::AccountId, to: &T::AccountId, value: BalanceOf<T>) {
+        let result = with_transaction(|| match T::OnReceived::on_received(id, from, to, value) {
+            Ok(()) => TransactionOutcome::Commit(Ok(())),
+            Err(err) => TransactionOutcome::Rollback(Err(err)),
+        });
+        if let Err(err) = result {
+            error!(
+                target: "runtime::assets",
+                "[notify_received] OnReceived hook failed, id:{}, from:{:?}, to:{:?}, value:{:?}, err:{:?}",
+                id, from, to, value, err
+            );
+            Self::deposit_event(Event::<T>::ReceiveHookFailed(
+                *id,
+                from.clone(),
+                to.clone(),
+                value,
+            ));
+        }
+    }
+
+    /// Deposits the event indexed by the topic of asset `id`, see `asset_event_topic`.
+    ///
+    /// The write of the topic index is registered as extra weight, since the event is
+    /// deposited by many calls.
+    pub(crate) fn deposit_asset_event(id: AssetId, event: Event<T>) {
+        let event = <T as Config>::Event::from(event);
+        frame_system::Pallet::<T>::deposit_event_indexed(
+            &[asset_event_topic::<T::Hashing>(id)],
+            event.into(),
+        );
+        frame_system::Pallet::<T>::register_extra_weight_unchecked(
+            T::DbWeight::get().writes(1),
+            DispatchClass::Normal,
+        );
+    }
+
     pub fn ensure_not_native_asset(asset_id: &AssetId) -> DispatchResult {
         ensure!(
             *asset_id != T::NativeAssetId::get(),
@@ -306,6 +1008,9 @@ impl<T: Config> Pallet<T> {
                             balance: Self::total_asset_balance(id),
                             is_online: xpallet_assets_registrar::Pallet::<T>::is_online(&id),
                             restrictions: Self::asset_restrictions_of(id),
+                            source_ref: xpallet_assets_registrar::Pallet::<T>::asset_source_ref_of(
+                                id,
+                            ),
                         },
                     );
                     Some(data)
@@ -323,6 +1028,31 @@ impl<T: Config> Pallet<T> {
             .collect()
     }
 
+    /// Returns the storage key of the `AssetBalance` of `who` for asset `id`.
+    pub fn asset_balance_key(who: &T::AccountId, id: &AssetId) -> Vec<u8> {
+        AssetBalance::<T>::hashed_key_for(who, id)
+    }
+
+    /// Returns the existence of `who` along with its PCX and the foreign assets it holds.
+    pub fn account_status(who: &T::AccountId) -> AccountStatus<BalanceOf<T>> {
+        let AccountInfo {
+            providers,
+            consumers,
+            sufficients,
+            ..
+        } = frame_system::pallet::Account::<T>::get(who);
+        let asset_count = AssetBalance::<T>::iter_key_prefix(who).count() as u32;
+        AccountStatus {
+            exists: providers > 0,
+            pcx_free: T::Currency::free_balance(who),
+            pcx_locked: Self::vesting_locked_balance(who, &T::NativeAssetId::get()),
+            lock_count: Self::locks_of(who).len() as u32,
+            has_foreign_assets: asset_count > 0,
+            asset_count,
+            reapable: providers == 1 && consumers == 0 && sufficients == 0,
+        }
+    }
+
     /// Returns whether `restriction` is applied for given asset `id`.
     pub fn can_do(id: &AssetId, restriction: AssetRestrictions) -> bool {
         !Self::asset_restrictions_of(id).contains(restriction)
@@ -374,10 +1104,7 @@ impl<T: Config> Pallet<T> {
 
     /// Returns the total balance of asset `id` given the specific asset type `ty`.
     pub fn total_asset_balance_of(id: &AssetId, ty: AssetType) -> BalanceOf<T> {
-        Self::total_asset_balance(id)
-            .get(&ty)
-            .copied()
-            .unwrap_or_default()
+        Self::total_asset_balance(id).get_or_zero(&ty)
     }
 
     /// Returns the sum of all kinds of `who`'s balances given asset `id`.
@@ -396,17 +1123,113 @@ impl<T: Config> Pallet<T> {
         Self::asset_typed_balance(who, id, AssetType::Usable)
     }
 
+    /// Returns the balance of `who` for asset `id` that is free to use, i.e., the free balance
+    /// minus the holds and the unvested balance of the vesting locks.
+    ///
+    /// The free balance of the native asset is the one in `Currency`.
+    pub fn spendable_balance(who: &T::AccountId, id: &AssetId) -> BalanceOf<T> {
+        let free = if *id == T::NativeAssetId::get() {
+            T::Currency::free_balance(who)
+        } else {
+            Self::usable_balance(who, id)
+        };
+        free.saturating_sub(Self::held_balance(who, id))
+            .saturating_sub(Self::vesting_locked_balance(who, id))
+    }
+
+    /// Returns the balance of `who` for asset `id` still locked by the vesting locks at the
+    /// current block.
+    pub fn vesting_locked_balance(who: &T::AccountId, id: &AssetId) -> BalanceOf<T> {
+        Self::unvested_balance(who, id, |_| true)
+    }
+
+    fn unvested_balance(
+        who: &T::AccountId,
+        id: &AssetId,
+        filter: impl Fn(&LockInfo<BalanceOf<T>, T::BlockNumber>) -> bool,
+    ) -> BalanceOf<T> {
+        let now = frame_system::Pallet::<T>::block_number();
+        Self::locks_of(who)
+            .iter()
+            .filter(|lock| lock.asset_id == *id && filter(lock))
+            .fold(Zero::zero(), |acc, lock| {
+                acc.saturating_add(lock.locked_at(now))
+            })
+    }
+
+    /// Locks the unvested PCX of `who` in the native currency, which handles the PCX transfers.
+    fn update_vesting_lock(who: &T::AccountId) {
+        let locked = Self::vesting_locked_balance(who, &T::NativeAssetId::get());
+        if locked.is_zero() {
+            T::Currency::remove_lock(VESTING_LOCK_ID, who);
+        } else {
+            let reasons = WithdrawReasons::TRANSFER | WithdrawReasons::RESERVE;
+            T::Currency::set_lock(VESTING_LOCK_ID, who, locked, reasons);
+        }
+    }
+
+    /// Ensures the `value` of asset `id` moved out of the usable balance of `who` is neither
+    /// held nor locked, the balance beyond the usable one is left to the move itself.
+    fn ensure_not_restricted(
+        who: &T::AccountId,
+        id: &AssetId,
+        value: BalanceOf<T>,
+    ) -> DispatchResult {
+        if value <= Self::usable_balance(who, id) {
+            ensure!(
+                value <= Self::spendable_balance(who, id),
+                Error::<T>::LiquidityRestrictions
+            );
+        }
+        Ok(())
+    }
+
+    /// Places `value` of the spendable balance of `who` for asset `id` on hold.
+    pub fn hold(id: &AssetId, who: &T::AccountId, value: BalanceOf<T>) -> DispatchResult {
+        ensure!(
+            Self::spendable_balance(who, id) >= value,
+            Error::<T>::InsufficientBalance
+        );
+        Holds::<T>::mutate(who, id, |held| *held = held.saturating_add(value));
+        Self::deposit_event(Event::<T>::BalanceHeld(*id, who.clone(), value));
+        Ok(())
+    }
+
+    /// Releases at most `value` of the held balance of `who` for asset `id`.
+    ///
+    /// Returns the balance actually released.
+    pub fn release_hold(id: &AssetId, who: &T::AccountId, value: BalanceOf<T>) -> BalanceOf<T> {
+        let released = Self::take_hold(id, who, value);
+        if !released.is_zero() {
+            Self::deposit_event(Event::<T>::HoldReleased(*id, who.clone(), released));
+        }
+        released
+    }
+
+    fn take_hold(id: &AssetId, who: &T::AccountId, value: BalanceOf<T>) -> BalanceOf<T> {
+        Holds::<T>::mutate_exists(who, id, |held| {
+            let current = held.unwrap_or_else(Zero::zero);
+            let released = current.min(value);
+            let rest = current - released;
+            *held = if rest.is_zero() { None } else { Some(rest) };
+            released
+        })
+    }
+
     pub fn locked_balance(who: &T::AccountId, id: &AssetId) -> BalanceOf<T> {
         Self::asset_typed_balance(who, id, AssetType::Locked)
     }
 
     pub fn total_reserved_balance(who: &T::AccountId, id: &AssetId) -> BalanceOf<T> {
-        use AssetType::{Reserved, ReservedDexSpot, ReservedWithdrawal};
+        use AssetType::{Reserved, ReservedDexSpot, ReservedScheduled, ReservedWithdrawal};
 
         let total_balances = Self::asset_balance(who, id);
-        let balance_for = |ty: AssetType| total_balances.get(&ty).copied().unwrap_or_default();
+        let balance_for = |ty: AssetType| total_balances.get_or_zero(&ty);
 
-        balance_for(Reserved) + balance_for(ReservedWithdrawal) + balance_for(ReservedDexSpot)
+        balance_for(Reserved)
+            + balance_for(ReservedWithdrawal)
+            + balance_for(ReservedDexSpot)
+            + balance_for(ReservedScheduled)
     }
 
     /// Sets the free balance of `who` without sanity checks and triggering the asset changed hook.
@@ -425,10 +1248,70 @@ impl<T: Config> Pallet<T> {
         Self::ensure_not_native_asset(id)?;
         xpallet_assets_registrar::Pallet::<T>::ensure_asset_is_valid(id)?;
 
-        let _imbalance = Self::inner_issue(id, who, AssetType::Usable, value, reward_pcx)?;
+        let value = Self::quarantine_beyond_cap(id, who, value, reward_pcx)?;
+        if !value.is_zero() {
+            Self::inner_issue(id, who, AssetType::Usable, value, reward_pcx)?;
+        }
         Ok(())
     }
 
+    /// Counts `value` towards the issuance cap of asset `id`, quarantines the part beyond
+    /// the cap and returns the rest.
+    ///
+    /// Fails without any change if the part beyond the cap can not be quarantined since
+    /// `MAX_QUARANTINED_ISSUANCES` issuances are pending already.
+    fn quarantine_beyond_cap(
+        id: &AssetId,
+        who: &T::AccountId,
+        value: BalanceOf<T>,
+        reward_pcx: bool,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let cap = match Self::max_issuance_per_window(id) {
+            Some(cap) => cap,
+            None => return Ok(value),
+        };
+
+        let now = frame_system::Pallet::<T>::block_number();
+        let mut window = Self::issuance_window_of(id);
+        if now >= window.started_at.saturating_add(cap.window_blocks.into())
+            || window.issued.is_zero()
+        {
+            window = IssuanceWindow {
+                started_at: now,
+                issued: Zero::zero(),
+            };
+        }
+        let allowed = cap.cap.saturating_sub(window.issued).min(value);
+        window.issued = window.issued.saturating_add(allowed);
+
+        let excess = value - allowed;
+        if !excess.is_zero() {
+            ensure!(
+                QuarantinedIssuances::<T>::decode_len().unwrap_or_default()
+                    < MAX_QUARANTINED_ISSUANCES,
+                Error::<T>::TooManyQuarantinedIssuances
+            );
+            let quarantine_id = Self::next_quarantine_id();
+            NextQuarantineId::<T>::put(quarantine_id.wrapping_add(1));
+            QuarantinedIssuances::<T>::append(QuarantinedIssuance {
+                id: quarantine_id,
+                asset_id: *id,
+                who: who.clone(),
+                value: excess,
+                reward_pcx,
+                block: now,
+            });
+            Self::deposit_event(Event::<T>::IssuanceQuarantined(
+                quarantine_id,
+                *id,
+                who.clone(),
+                excess,
+            ));
+        }
+        IssuanceWindowOf::<T>::insert(id, window);
+        Ok(allowed)
+    }
+
     pub fn destroy_reserved_withdrawal(
         id: &AssetId,
         who: &T::AccountId,
@@ -458,12 +1341,101 @@ impl<T: Config> Pallet<T> {
         to: &T::AccountId,
         to_type: AssetType,
         value: BalanceOf<T>,
+        caller: MoveCaller,
     ) -> Result<(), AssetErr> {
         Self::ensure_not_native_asset(id).map_err(|_| AssetErr::InvalidAsset)?;
         xpallet_assets_registrar::Pallet::<T>::ensure_asset_is_valid(id)
             .map_err(|_| AssetErr::InvalidAsset)?;
         Self::can_move(id).map_err(|_| AssetErr::NotAllow)?;
+        Self::ensure_move_allowed(id, from_type, to_type, caller)?;
+        if from != to {
+            Self::ensure_outflow_allowed(from, caller)?;
+        }
+
+        Self::inner_move_balance(id, from, from_type, to, to_type, value)
+    }
+
+    /// Moves the `ReservedDexSpot` balance of `who` back to `Usable`.
+    ///
+    /// Unlike `move_balance`, the asset is not required to be valid, so that the
+    /// balances locked by the open orders can still be released after the asset
+    /// has been deregistered.
+    pub fn unreserve_dex_spot(
+        id: &AssetId,
+        who: &T::AccountId,
+        value: BalanceOf<T>,
+    ) -> Result<(), AssetErr> {
+        Self::ensure_not_native_asset(id).map_err(|_| AssetErr::InvalidAsset)?;
+        Self::can_move(id).map_err(|_| AssetErr::NotAllow)?;
+        Self::ensure_move_allowed(
+            id,
+            AssetType::ReservedDexSpot,
+            AssetType::Usable,
+            MoveCaller::DexSpot,
+        )?;
 
+        Self::inner_move_balance(
+            id,
+            who,
+            AssetType::ReservedDexSpot,
+            who,
+            AssetType::Usable,
+            value,
+        )
+    }
+
+    /// Ensures `caller` is allowed to move asset `id` from `from_type` to `to_type` by
+    /// `T::MoveGuard`.
+    fn ensure_move_allowed(
+        id: &AssetId,
+        from_type: AssetType,
+        to_type: AssetType,
+        caller: MoveCaller,
+    ) -> Result<(), AssetErr> {
+        if T::MoveGuard::can_move(id, from_type, to_type, caller) {
+            return Ok(());
+        }
+        error!(
+            target: "runtime::assets",
+            "[ensure_move_allowed] Forbidden move, id:{}, from_type:{:?}, to_type:{:?}, caller:{:?}",
+            id, from_type, to_type, caller
+        );
+        Self::deposit_event(Event::<T>::MoveForbidden(*id, from_type, to_type, caller));
+        Err(AssetErr::Forbidden)
+    }
+
+    /// Ensures `caller` is allowed to move the balances out of `who` by `T::MoveGuard`,
+    /// if `who` is a registered derived account, e.g., a reward pot.
+    ///
+    /// The read of `DerivedAccountOf` is registered as extra weight, since the outflows
+    /// are checked by many calls.
+    fn ensure_outflow_allowed(who: &T::AccountId, caller: MoveCaller) -> Result<(), AssetErr> {
+        frame_system::Pallet::<T>::register_extra_weight_unchecked(
+            T::DbWeight::get().reads(1),
+            DispatchClass::Normal,
+        );
+        match Self::derived_account_of(who) {
+            Some(kind) if !T::MoveGuard::can_move_out(&kind, caller) => {
+                error!(
+                    target: "runtime::assets",
+                    "[ensure_outflow_allowed] Forbidden outflow, who:{:?}, kind:{:?}, caller:{:?}",
+                    who, kind, caller
+                );
+                Self::deposit_event(Event::<T>::OutflowForbidden(who.clone(), caller));
+                Err(AssetErr::Forbidden)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn inner_move_balance(
+        id: &AssetId,
+        from: &T::AccountId,
+        from_type: AssetType,
+        to: &T::AccountId,
+        to_type: AssetType,
+        value: BalanceOf<T>,
+    ) -> Result<(), AssetErr> {
         if value == Zero::zero() {
             // value is zero, do not read storage, no event
             return Ok(());
@@ -509,8 +1481,52 @@ impl<T: Config> Pallet<T> {
         from: &T::AccountId,
         to: &T::AccountId,
         value: BalanceOf<T>,
+        caller: MoveCaller,
     ) -> Result<(), AssetErr> {
-        Self::move_balance(id, from, AssetType::Usable, to, AssetType::Usable, value)
+        Self::move_balance(
+            id,
+            from,
+            AssetType::Usable,
+            to,
+            AssetType::Usable,
+            value,
+            caller,
+        )
+    }
+
+    /// Moves the usable balance like `move_usable_balance`, but refuses to drop the balance of
+    /// `from` below the minimum balance.
+    ///
+    /// For the native asset, the minimum balance is the existential deposit of the native
+    /// currency. For the other assets, it's the configured `AssetMinBalance`.
+    pub fn move_balance_keep_alive(
+        id: &AssetId,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        value: BalanceOf<T>,
+        caller: MoveCaller,
+    ) -> Result<(), AssetErr> {
+        if *id == T::NativeAssetId::get() {
+            Self::ensure_outflow_allowed(from, caller)?;
+            let remaining = T::Currency::free_balance(from)
+                .checked_sub(&value)
+                .ok_or(AssetErr::NotEnough)?;
+            ensure!(
+                remaining >= T::Currency::minimum_balance(),
+                AssetErr::WouldKill
+            );
+            return T::Currency::transfer(from, to, value, ExistenceRequirement::KeepAlive)
+                .map_err(|_| AssetErr::NotEnough);
+        }
+
+        let remaining = Self::usable_balance(from, id)
+            .checked_sub(&value)
+            .ok_or(AssetErr::NotEnough)?;
+        ensure!(
+            remaining >= Self::asset_min_balance(id),
+            AssetErr::WouldKill
+        );
+        Self::move_usable_balance(id, from, to, value, caller)
     }
 
     pub fn set_balance_impl(
@@ -519,6 +1535,10 @@ impl<T: Config> Pallet<T> {
         balances: BTreeMap<AssetType, BalanceOf<T>>,
     ) -> DispatchResult {
         Self::ensure_not_native_asset(id)?;
+        ensure!(
+            !Self::has_unknown_asset_types(who, id),
+            Error::<T>::UnknownAssetType
+        );
         for (type_, val) in balances.into_iter() {
             let old_val = Self::asset_typed_balance(who, id, type_);
             if old_val == val {
@@ -526,6 +1546,7 @@ impl<T: Config> Pallet<T> {
             }
 
             Self::make_type_balance_be(who, id, type_, val);
+            Self::note_total_issuance_changed(id);
 
             AssetChangedTrigger::<T>::on_set_balance(id, who, type_, val)?;
         }
@@ -534,10 +1555,7 @@ impl<T: Config> Pallet<T> {
 
     /// Returns the balance of `who` given `asset_id` and `ty`.
     fn asset_typed_balance(who: &T::AccountId, asset_id: &AssetId, ty: AssetType) -> BalanceOf<T> {
-        Self::asset_balance(who, asset_id)
-            .get(&ty)
-            .copied()
-            .unwrap_or_default()
+        Self::asset_balance(who, asset_id).get_or_zero(&ty)
     }
 
     fn new_account(who: &T::AccountId) {
@@ -589,9 +1607,13 @@ impl<T: Config> Pallet<T> {
                         }
                     }
                     Vacant(entry) => {
-                        entry.insert(new_balance);
+                        // never insert a zero balance
+                        if !new_balance.is_zero() {
+                            entry.insert(new_balance);
+                        }
                     }
                 };
+                debug_assert!(balances.len() <= MAX_ASSET_TYPES);
 
                 // if is_empty(), means not exists
                 exists = !balances.is_empty();
@@ -645,6 +1667,7 @@ impl<T: Config> Pallet<T> {
         AssetChangedTrigger::<T>::on_issue_pre(id, who);
 
         Self::make_type_balance_be(who, id, type_, new);
+        Self::note_total_issuance_changed(id);
 
         AssetChangedTrigger::<T>::on_issue_post(id, who, value, reward_pcx)?;
         Ok(())
@@ -671,8 +1694,66 @@ impl<T: Config> Pallet<T> {
         AssetChangedTrigger::<T>::on_destroy_pre(id, who);
 
         Self::make_type_balance_be(who, id, type_, new);
+        Self::note_total_issuance_changed(id);
 
         AssetChangedTrigger::<T>::on_destroy_post(id, who, value)?;
         Ok(())
     }
+
+    fn note_total_issuance_changed(id: &AssetId) {
+        TotalIssuanceChanged::<T>::mutate(|changed| {
+            changed.insert(*id);
+        });
+    }
+
+    /// Deposits the new total issuance of the assets changed in this block
+    /// into the header digest, which is used by the light clients.
+    fn deposit_asset_totals_digest() {
+        let changed = TotalIssuanceChanged::<T>::take();
+        if changed.is_empty() {
+            return;
+        }
+
+        let overflow = changed.len() > MAX_ASSET_TOTALS_DIGEST_ENTRIES;
+        let entries = changed
+            .into_iter()
+            .take(MAX_ASSET_TOTALS_DIGEST_ENTRIES)
+            .map(|id| (id, Self::total_issuance(&id)))
+            .collect();
+        let digest = AssetTotalsDigest::<BalanceOf<T>> { entries, overflow };
+        frame_system::Pallet::<T>::deposit_log(digest.to_digest_item());
+    }
+}
+
+impl<T: Config> DerivedAccountRegistry<T::AccountId> for Pallet<T> {
+    fn note_derived_account(who: &T::AccountId, kind: DerivedAccountKind<T::AccountId>) {
+        DerivedAccountOf::<T>::insert(who, kind);
+    }
+
+    fn ensure_can_move_out(who: &T::AccountId, caller: MoveCaller) -> DispatchResult {
+        Self::ensure_outflow_allowed(who, caller).map_err(|err| Error::<T>::from(err).into())
+    }
+}
+
+impl<T: Config> NativeHolds<T::AccountId, BalanceOf<T>> for Pallet<T> {
+    /// The holds plus the unvested balance of the vesting locks not allowed to be nominated.
+    fn native_held(who: &T::AccountId) -> BalanceOf<T> {
+        let native = T::NativeAssetId::get();
+        Self::held_balance(who, native).saturating_add(Self::unvested_balance(
+            who,
+            &native,
+            |lock| !lock.nominatable,
+        ))
+    }
+
+    /// The native holds of Staking are announced by its own events, no hold event is emitted.
+    fn hold_native(who: &T::AccountId, value: BalanceOf<T>) {
+        Holds::<T>::mutate(who, T::NativeAssetId::get(), |held| {
+            *held = held.saturating_add(value)
+        });
+    }
+
+    fn release_native(who: &T::AccountId, value: BalanceOf<T>) {
+        Self::take_hold(&T::NativeAssetId::get(), who, value);
+    }
 }
@@ -0,0 +1,64 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Storage migrations of the assets pallet, run by the runtime upgrade of the runtime.
+
+use frame_support::{
+    log::info,
+    traits::{Get, GetStorageVersion, StorageVersion},
+    weights::Weight,
+};
+
+use super::*;
+
+/// The storage version of the pallet, bumped by each migration below.
+pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+/// The derived accounts created by the other pallets before `DerivedAccountOf` existed.
+///
+/// The assets pallet knows nothing about the reward pots or the trustee multisig
+/// accounts, they are collected by the runtime.
+pub trait DerivedAccountSource<AccountId> {
+    /// Returns the existing derived accounts and their kinds.
+    fn derived_accounts() -> Vec<(AccountId, DerivedAccountKind<AccountId>)>;
+}
+
+/// Runs the migrations the on-chain storage version has not reached yet.
+pub fn migrate<T: Config, S: DerivedAccountSource<T::AccountId>>() -> Weight {
+    let on_chain = Pallet::<T>::on_chain_storage_version();
+    let mut weight = T::DbWeight::get().reads(1);
+    if on_chain >= STORAGE_VERSION {
+        return weight;
+    }
+
+    if on_chain < 1 {
+        weight = weight.saturating_add(backfill_derived_accounts::<T, S>());
+    }
+
+    STORAGE_VERSION.put::<Pallet<T>>();
+    weight.saturating_add(T::DbWeight::get().writes(1))
+}
+
+/// v1: records the existing derived accounts and the treasury account in `DerivedAccountOf`.
+///
+/// Only the accounts created since `DerivedAccountOf` are recorded by their pallets, the
+/// older ones would otherwise be left out of the `MoveGuard` checks.
+fn backfill_derived_accounts<T: Config, S: DerivedAccountSource<T::AccountId>>() -> Weight {
+    let mut count: Weight = 0;
+    for (who, kind) in S::derived_accounts() {
+        DerivedAccountOf::<T>::insert(who, kind);
+        count += 1;
+    }
+    if let Some(treasury) = T::TreasuryAccount::treasury_account() {
+        DerivedAccountOf::<T>::insert(treasury, DerivedAccountKind::Council);
+        count += 1;
+    }
+    info!(
+        target: "runtime::assets",
+        "[migrate] Recorded {} existing derived accounts",
+        count
+    );
+    // The treasury account is read besides the accounts of the source.
+    T::DbWeight::get()
+        .reads(1)
+        .saturating_add(T::DbWeight::get().reads_writes(count, count))
+}
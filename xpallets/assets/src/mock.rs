@@ -1,6 +1,6 @@
 // Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
 
-use std::collections::BTreeMap;
+use std::{cell::RefCell, collections::BTreeMap};
 
 use sp_core::H256;
 use sp_runtime::{
@@ -8,12 +8,20 @@ use sp_runtime::{
     traits::{BlakeTwo256, IdentityLookup},
 };
 
-use frame_support::{parameter_types, sp_io, traits::GenesisBuild};
+use frame_support::{
+    dispatch::{DispatchError, DispatchResult},
+    parameter_types, sp_io,
+    storage::unhashed,
+    traits::GenesisBuild,
+};
 
 use chainx_primitives::AssetId;
 pub use xp_protocol::X_BTC;
 
-use crate::{self as xpallet_assets, AssetInfo, AssetRestrictions, Chain, Config, Error};
+use crate::{
+    self as xpallet_assets, AssetInfo, AssetRestrictions, AssetType, Chain, Config,
+    DerivedAccountKind, Error, MoveCaller, MoveGuard, OnReceived,
+};
 
 /// The AccountId alias in this test module.
 pub(crate) type AccountId = u64;
@@ -100,9 +108,62 @@ impl Config for Test {
     type TreasuryAccount = ();
     type OnCreatedAccount = frame_system::Provider<Test>;
     type OnAssetChanged = ();
+    type OnReceived = MockReceiver;
+    type MoveGuard = MockMoveGuard;
+    type QuarantineOrigin = frame_system::EnsureRoot<AccountId>;
     type WeightInfo = ();
 }
 
+/// Only the staking modules are allowed to move the assets into or out of `Reserved`, and
+/// only the jackpot paths are allowed to move the balances out of the reward pots.
+pub struct MockMoveGuard;
+impl MoveGuard for MockMoveGuard {
+    fn can_move(
+        _id: &AssetId,
+        from_type: AssetType,
+        to_type: AssetType,
+        caller: MoveCaller,
+    ) -> bool {
+        caller == MoveCaller::Staking
+            || (from_type != AssetType::Reserved && to_type != AssetType::Reserved)
+    }
+
+    fn can_move_out<AccountId>(kind: &DerivedAccountKind<AccountId>, caller: MoveCaller) -> bool {
+        match kind {
+            DerivedAccountKind::TokenJackpot(_) | DerivedAccountKind::IntentionJackpot(_) => {
+                caller == MoveCaller::Jackpot
+            }
+            _ => true,
+        }
+    }
+}
+
+thread_local! {
+    pub static RECEIVED: RefCell<Vec<(AssetId, AccountId, AccountId, Balance)>> = RefCell::new(Vec::new());
+}
+
+/// The value rejected by `MockReceiver`.
+pub const REJECTED_VALUE: Balance = 13;
+/// The storage written by `MockReceiver` on each call.
+pub const LAST_RECEIVED_KEY: &[u8] = b":mock:last_received";
+
+pub struct MockReceiver;
+impl OnReceived<AccountId, Balance> for MockReceiver {
+    fn on_received(
+        id: &AssetId,
+        from: &AccountId,
+        to: &AccountId,
+        value: Balance,
+    ) -> DispatchResult {
+        RECEIVED.with(|received| received.borrow_mut().push((*id, *from, *to, value)));
+        unhashed::put(LAST_RECEIVED_KEY, &value);
+        if value == REJECTED_VALUE {
+            return Err(DispatchError::Other("rejected by the receiver"));
+        }
+        Ok(())
+    }
+}
+
 pub struct ExtBuilder;
 
 impl Default for ExtBuilder {
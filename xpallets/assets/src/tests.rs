@@ -2,14 +2,26 @@
 
 use std::collections::BTreeMap;
 
-use frame_support::{assert_noop, assert_ok};
+use codec::Encode;
+use frame_support::{
+    assert_noop, assert_ok,
+    storage::unhashed,
+    traits::{Currency, Get},
+};
+use sp_runtime::{traits::BlakeTwo256, DispatchError};
 use xp_protocol::X_BTC;
 
 pub use super::mock::{ExtBuilder, Test};
 use crate::{
-    mock::{Balance, Origin, XAssets, XAssetsErr},
-    AssetBalance, AssetErr, AssetInfo, AssetRestrictions, AssetType, Chain, TotalAssetBalance,
+    mock::{
+        AccountId, Balance, Balances, Event, Origin, System, XAssets, XAssetsErr, ALICE, BOB,
+        CHARLIE, DAVE, LAST_RECEIVED_KEY, RECEIVED, REJECTED_VALUE,
+    },
+    AccountStatus, AssetBalance, AssetErr, AssetInfo, AssetRestrictions, AssetType, Chain,
+    DerivedAccountKind, DerivedAccountRegistry, IssuanceCap, LockInfo, MoveCaller,
+    TotalAssetBalance, MAX_ASSET_TYPES, MAX_QUARANTINED_ISSUANCES,
 };
+use xpallet_support::traits::BalanceMap;
 
 #[test]
 fn test_genesis() {
@@ -91,7 +103,8 @@ fn test_normal_case() {
             AssetType::Usable,
             &999,
             AssetType::ReservedWithdrawal,
-            25
+            25,
+            MoveCaller::User
         ));
         assert_eq!(
             XAssets::total_asset_balance_of(&X_BTC, AssetType::Usable),
@@ -148,6 +161,7 @@ fn test_normal_issue_and_destroy() {
             &a,
             AssetType::ReservedWithdrawal,
             25,
+            MoveCaller::User,
         )
         .unwrap();
 
@@ -189,6 +203,7 @@ fn test_unlock_issue_and_destroy2() {
             &a,
             AssetType::ReservedWithdrawal,
             25,
+            MoveCaller::User,
         )
         .unwrap();
 
@@ -207,6 +222,7 @@ fn test_unlock_issue_and_destroy2() {
             &a,
             AssetType::Usable,
             10,
+            MoveCaller::User,
         )
         .unwrap();
 
@@ -242,7 +258,8 @@ fn test_error_issue_and_destroy1() {
                 AssetType::Usable,
                 &a,
                 AssetType::ReservedWithdrawal,
-                100
+                100,
+                MoveCaller::User
             ),
             AssetErr::NotEnough
         );
@@ -255,6 +272,7 @@ fn test_error_issue_and_destroy1() {
             &a,
             AssetType::ReservedWithdrawal,
             25,
+            MoveCaller::User,
         )
         .unwrap();
         // destroy
@@ -282,6 +300,7 @@ fn test_error_issue_and_destroy2() {
                 &a,
                 AssetType::ReservedWithdrawal,
                 i as Balance,
+                MoveCaller::User
             ),
             AssetErr::NotEnough
         );
@@ -311,7 +330,8 @@ fn test_error_issue_and_destroy3() {
                 AssetType::Usable,
                 &a,
                 AssetType::ReservedWithdrawal,
-                25
+                25,
+                MoveCaller::User
             ),
             AssetErr::NotEnough
         );
@@ -329,7 +349,8 @@ fn test_error_issue_and_destroy3() {
                 AssetType::Usable,
                 &a,
                 AssetType::ReservedWithdrawal,
-                25
+                25,
+                MoveCaller::User
             ),
             AssetErr::NotEnough
         );
@@ -343,6 +364,7 @@ fn test_error_issue_and_destroy3() {
             &a,
             AssetType::ReservedWithdrawal,
             25,
+            MoveCaller::User,
         )
         .unwrap();
 
@@ -366,6 +388,7 @@ fn test_balance_btree_map() {
             &a,
             AssetType::ReservedWithdrawal,
             30,
+            MoveCaller::User,
         );
         assert_eq!(AssetBalance::<Test>::get(&a, &btc_id).len(), 2);
         assert_eq!(TotalAssetBalance::<Test>::get(&btc_id).len(), 2);
@@ -377,6 +400,7 @@ fn test_balance_btree_map() {
             &a,
             AssetType::Usable,
             10,
+            MoveCaller::User,
         );
         let _ = XAssets::move_balance(
             &X_BTC,
@@ -385,6 +409,7 @@ fn test_balance_btree_map() {
             &b,
             AssetType::Usable,
             20,
+            MoveCaller::User,
         );
         assert_eq!(AssetBalance::<Test>::get(&a, &btc_id).len(), 1);
         assert_eq!(TotalAssetBalance::<Test>::get(&btc_id).len(), 1);
@@ -502,9 +527,9 @@ fn test_move() {
         let a: u64 = 1; // accountid
         let b: u64 = 2; // accountid
         let btc_id = X_BTC;
-        XAssets::move_usable_balance(&btc_id, &a, &b, 100).unwrap();
+        XAssets::move_usable_balance(&btc_id, &a, &b, 100, MoveCaller::User).unwrap();
         assert_noop!(
-            XAssets::move_usable_balance(&btc_id, &a, &b, 1000),
+            XAssets::move_usable_balance(&btc_id, &a, &b, 1000, MoveCaller::User),
             AssetErr::NotEnough
         );
         assert_eq!(XAssets::usable_balance(&a, &btc_id), 0);
@@ -512,14 +537,14 @@ fn test_move() {
 
         let token = X_BTC;
         assert_noop!(
-            XAssets::move_usable_balance(&token, &a, &b, 100),
+            XAssets::move_usable_balance(&token, &a, &b, 100, MoveCaller::User),
             AssetErr::NotEnough
         );
 
         XAssets::issue(&token, &a, 100, true).unwrap();
-        XAssets::move_usable_balance(&token, &a, &b, 100).unwrap();
+        XAssets::move_usable_balance(&token, &a, &b, 100, MoveCaller::User).unwrap();
         assert_noop!(
-            XAssets::move_usable_balance(&token, &a, &b, 1000),
+            XAssets::move_usable_balance(&token, &a, &b, 1000, MoveCaller::User),
             AssetErr::NotEnough
         );
 
@@ -527,3 +552,982 @@ fn test_move() {
         assert_eq!(XAssets::usable_balance(&b, &token), 200 + 100 + 100);
     })
 }
+
+#[test]
+fn test_asset_totals_digest() {
+    use chainx_primitives::digest::AssetTotalsDigest;
+    use frame_support::traits::Hooks;
+    use sp_runtime::traits::Header as _;
+
+    use crate::mock::{btc, System};
+
+    let abc_id = 100;
+    let abc_assets = (
+        abc_id,
+        AssetInfo::new::<Test>(
+            b"ABC".to_vec(),
+            b"ABC".to_vec(),
+            Chain::Bitcoin,
+            8,
+            b"abc".to_vec(),
+        )
+        .unwrap(),
+        AssetRestrictions::empty(),
+    );
+    let btc_assets = btc();
+
+    let mut endowed = BTreeMap::new();
+    endowed.insert(btc_assets.0, vec![(1, 100), (2, 200)]);
+    endowed.insert(abc_assets.0, vec![(1, 1000)]);
+
+    let assets = vec![
+        (btc_assets.0, btc_assets.1, btc_assets.2, true, true),
+        (abc_assets.0, abc_assets.1, abc_assets.2, true, true),
+    ];
+
+    ExtBuilder::default()
+        .build(assets, endowed)
+        .execute_with(|| {
+            // The totals endowed in genesis are committed in the first block.
+            System::initialize(&1, &Default::default(), &Default::default());
+            XAssets::on_finalize(1);
+            let header = System::finalize();
+            assert_eq!(
+                AssetTotalsDigest::<Balance>::from_digest(&header.digest),
+                Some(AssetTotalsDigest {
+                    entries: vec![(X_BTC, 300), (abc_id, 1000)],
+                    overflow: false,
+                })
+            );
+
+            // Nothing is committed if there is no total issuance change.
+            System::initialize(&2, &header.hash(), &Default::default());
+            assert_ok!(XAssets::transfer(Origin::signed(1), 2, X_BTC, 10));
+            XAssets::on_finalize(2);
+            let header = System::finalize();
+            assert_eq!(
+                AssetTotalsDigest::<Balance>::from_digest(&header.digest),
+                None
+            );
+
+            System::initialize(&3, &header.hash(), &Default::default());
+            assert_ok!(XAssets::issue(&X_BTC, &1, 50, true));
+            assert_ok!(XAssets::destroy_usable(&abc_id, &1, 10));
+            XAssets::on_finalize(3);
+            let header = System::finalize();
+            assert_eq!(
+                AssetTotalsDigest::<Balance>::from_digest(&header.digest),
+                Some(AssetTotalsDigest {
+                    entries: vec![(X_BTC, 350), (abc_id, 990)],
+                    overflow: false,
+                })
+            );
+        });
+}
+
+#[test]
+fn test_transfer_keep_alive() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XAssets::set_asset_min_balance(Origin::root(), X_BTC, 10));
+
+        // transfer exactly down to the minimum balance
+        assert_ok!(XAssets::transfer_v2(
+            Origin::signed(ALICE),
+            BOB,
+            X_BTC,
+            90,
+            true
+        ));
+        assert_eq!(XAssets::usable_balance(&ALICE, &X_BTC), 10);
+        assert_eq!(XAssets::usable_balance(&BOB, &X_BTC), 290);
+
+        // one unit more would drop below the minimum balance
+        assert_noop!(
+            XAssets::transfer_v2(Origin::signed(ALICE), BOB, X_BTC, 1, true),
+            XAssetsErr::WouldKill
+        );
+        assert_eq!(
+            XAssets::move_balance_keep_alive(&X_BTC, &ALICE, &BOB, 1, MoveCaller::User),
+            Err(AssetErr::WouldKill)
+        );
+
+        // the default path is unchanged
+        assert_ok!(XAssets::transfer_v2(
+            Origin::signed(ALICE),
+            BOB,
+            X_BTC,
+            10,
+            false
+        ));
+        assert_eq!(XAssets::usable_balance(&ALICE, &X_BTC), 0);
+        assert_eq!(XAssets::usable_balance(&BOB, &X_BTC), 300);
+    });
+}
+
+#[test]
+fn test_transfer_keep_alive_native_asset() {
+    ExtBuilder::default().build_and_execute(|| {
+        let pcx = <Test as xpallet_assets_registrar::Config>::NativeAssetId::get();
+        Balances::make_free_balance_be(&ALICE, 100);
+
+        // the existential deposit is 1
+        assert_ok!(XAssets::transfer_v2(
+            Origin::signed(ALICE),
+            BOB,
+            pcx,
+            99,
+            true
+        ));
+        assert_eq!(Balances::free_balance(&ALICE), 1);
+        assert_eq!(Balances::free_balance(&BOB), 99);
+
+        assert_noop!(
+            XAssets::transfer_v2(Origin::signed(ALICE), BOB, pcx, 1, true),
+            XAssetsErr::WouldKill
+        );
+
+        // the native asset can not be moved by the default path
+        assert_noop!(
+            XAssets::transfer_v2(Origin::signed(ALICE), BOB, pcx, 1, false),
+            XAssetsErr::InvalidAsset
+        );
+    });
+}
+
+fn assert_scheduled_totals(total_scheduled: Balance) {
+    assert_eq!(XAssets::total_issuance(&X_BTC), 1000);
+    assert_eq!(
+        XAssets::total_asset_balance_of(&X_BTC, AssetType::ReservedScheduled),
+        total_scheduled
+    );
+    let sum_of_accounts = [ALICE, BOB, CHARLIE, DAVE]
+        .iter()
+        .map(|who| XAssets::all_type_asset_balance(who, &X_BTC))
+        .sum::<Balance>();
+    assert_eq!(sum_of_accounts, 1000);
+}
+
+#[test]
+fn test_scheduled_transfer() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XAssets::schedule_transfer(
+            Origin::signed(ALICE),
+            BOB,
+            X_BTC,
+            40,
+            10,
+            b"vesting".to_vec().into()
+        ));
+        assert_eq!(XAssets::usable_balance(&ALICE, &X_BTC), 60);
+        assert_eq!(
+            XAssets::asset_balance_of(&ALICE, &X_BTC, AssetType::ReservedScheduled),
+            40
+        );
+        assert_eq!(XAssets::total_reserved_balance(&ALICE, &X_BTC), 40);
+        assert_eq!(XAssets::all_type_asset_balance(&ALICE, &X_BTC), 100);
+        assert_eq!(XAssets::next_scheduled_transfer_id(), 1);
+        assert_scheduled_totals(40);
+
+        System::set_block_number(9);
+        assert_noop!(
+            XAssets::execute_scheduled(Origin::signed(CHARLIE), 0),
+            XAssetsErr::ScheduledTransferLocked
+        );
+
+        // Anyone can execute the transfer once it's unlocked.
+        System::set_block_number(10);
+        assert_ok!(XAssets::execute_scheduled(Origin::signed(CHARLIE), 0));
+        assert_eq!(XAssets::all_type_asset_balance(&ALICE, &X_BTC), 60);
+        assert_eq!(XAssets::total_reserved_balance(&ALICE, &X_BTC), 0);
+        assert_eq!(XAssets::usable_balance(&BOB, &X_BTC), 240);
+        assert!(XAssets::scheduled_transfers(0).is_none());
+        assert_scheduled_totals(0);
+
+        assert_noop!(
+            XAssets::execute_scheduled(Origin::signed(CHARLIE), 0),
+            XAssetsErr::ScheduledTransferNotFound
+        );
+    })
+}
+
+#[test]
+fn test_cancel_scheduled_transfer() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_noop!(
+            XAssets::schedule_transfer(
+                Origin::signed(ALICE),
+                BOB,
+                X_BTC,
+                0,
+                10,
+                b"vesting".to_vec().into()
+            ),
+            XAssetsErr::ZeroBalance
+        );
+        assert_noop!(
+            XAssets::schedule_transfer(
+                Origin::signed(ALICE),
+                BOB,
+                X_BTC,
+                40,
+                1,
+                b"vesting".to_vec().into()
+            ),
+            XAssetsErr::InvalidUnlockBlock
+        );
+        assert_noop!(
+            XAssets::schedule_transfer(
+                Origin::signed(ALICE),
+                BOB,
+                X_BTC,
+                101,
+                10,
+                b"vesting".to_vec().into()
+            ),
+            XAssetsErr::InsufficientBalance
+        );
+
+        assert_ok!(XAssets::schedule_transfer(
+            Origin::signed(ALICE),
+            BOB,
+            X_BTC,
+            40,
+            10,
+            b"vesting".to_vec().into()
+        ));
+        assert_ok!(XAssets::schedule_transfer(
+            Origin::signed(ALICE),
+            BOB,
+            X_BTC,
+            30,
+            5,
+            b"vesting".to_vec().into()
+        ));
+        assert_eq!(XAssets::usable_balance(&ALICE, &X_BTC), 30);
+        assert_scheduled_totals(70);
+
+        assert_noop!(
+            XAssets::cancel_scheduled(Origin::signed(BOB), 0),
+            XAssetsErr::NotScheduledTransferOwner
+        );
+        assert_ok!(XAssets::cancel_scheduled(Origin::signed(ALICE), 0));
+        assert_eq!(XAssets::usable_balance(&ALICE, &X_BTC), 70);
+        assert!(XAssets::scheduled_transfers(0).is_none());
+        assert_scheduled_totals(30);
+
+        // Can not be cancelled since the unlock block.
+        System::set_block_number(5);
+        assert_noop!(
+            XAssets::cancel_scheduled(Origin::signed(ALICE), 1),
+            XAssetsErr::ScheduledTransferUnlocked
+        );
+        assert_ok!(XAssets::execute_scheduled(Origin::signed(BOB), 1));
+        assert_eq!(XAssets::all_type_asset_balance(&ALICE, &X_BTC), 70);
+        assert_eq!(XAssets::usable_balance(&BOB, &X_BTC), 230);
+        assert_scheduled_totals(0);
+    })
+}
+
+#[test]
+fn test_transfer_notify() {
+    ExtBuilder::default().build_and_execute(|| {
+        let received = || RECEIVED.with(|received| received.borrow().clone());
+
+        assert_noop!(
+            XAssets::set_transfer_notify(Origin::signed(BOB), BOB, true),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(XAssets::set_transfer_notify(Origin::root(), BOB, true));
+        assert!(XAssets::transfer_notify_of(&BOB));
+
+        // Not registered.
+        assert_ok!(XAssets::transfer(Origin::signed(ALICE), CHARLIE, X_BTC, 10));
+        // Moving the balance of a registered account between its own types.
+        assert_ok!(XAssets::move_balance(
+            &X_BTC,
+            &BOB,
+            AssetType::Usable,
+            &BOB,
+            AssetType::Locked,
+            10,
+            MoveCaller::User
+        ));
+        assert!(received().is_empty());
+
+        assert_ok!(XAssets::transfer(Origin::signed(ALICE), BOB, X_BTC, 10));
+        assert_eq!(received(), vec![(X_BTC, ALICE, BOB, 10)]);
+        assert_eq!(unhashed::get::<Balance>(LAST_RECEIVED_KEY), Some(10));
+
+        // The failed hook does not revert the transfer, only its own changes.
+        assert_ok!(XAssets::transfer(
+            Origin::signed(ALICE),
+            BOB,
+            X_BTC,
+            REJECTED_VALUE
+        ));
+        assert_eq!(received().len(), 2);
+        assert_eq!(unhashed::get::<Balance>(LAST_RECEIVED_KEY), Some(10));
+        assert_eq!(XAssets::usable_balance(&ALICE, &X_BTC), 67);
+        assert_eq!(XAssets::usable_balance(&BOB, &X_BTC), 213);
+        assert!(System::events().iter().any(|record| record.event
+            == crate::mock::Event::XAssets(crate::Event::ReceiveHookFailed(
+                X_BTC,
+                ALICE,
+                BOB,
+                REJECTED_VALUE
+            ))));
+
+        assert_ok!(XAssets::set_transfer_notify(Origin::root(), BOB, false));
+        assert!(!XAssets::transfer_notify_of(&BOB));
+        assert_ok!(XAssets::transfer(Origin::signed(ALICE), BOB, X_BTC, 10));
+        assert_eq!(received().len(), 2);
+    })
+}
+
+#[test]
+fn test_asset_balance_storage_proof() {
+    let mut ext = ExtBuilder::default().build_default();
+    ext.commit_all().unwrap();
+
+    let unknown = 999;
+    let (keys, balance) = ext.execute_with(|| {
+        (
+            vec![
+                XAssets::asset_balance_key(&ALICE, &X_BTC),
+                XAssets::asset_balance_key(&unknown, &X_BTC),
+            ],
+            XAssets::asset_balance(&ALICE, &X_BTC),
+        )
+    });
+    let root = *ext.backend.root();
+    let proof = sp_state_machine::prove_read(ext.backend.clone(), &keys).unwrap();
+
+    // the combined proof covers both the existing and the absent balance
+    let values = sp_state_machine::read_proof_check::<BlakeTwo256, _>(root, proof, &keys).unwrap();
+    assert_eq!(values[&keys[0]], Some(balance.encode()));
+    assert_eq!(values[&keys[1]], None);
+}
+
+#[test]
+fn test_balance_map_get_or_zero() {
+    let mut balances = BTreeMap::<AssetType, Balance>::new();
+    assert_eq!(balances.get_or_zero(&AssetType::Usable), 0);
+    balances.insert(AssetType::Usable, 10);
+    assert_eq!(balances.get_or_zero(&AssetType::Usable), 10);
+    assert_eq!(balances.get_or_zero(&AssetType::Locked), 0);
+    assert_eq!(balances, BTreeMap::from([(AssetType::Usable, 10)]));
+    assert_eq!(BTreeMap::<AssetType, Balance>::default(), BTreeMap::new());
+}
+
+#[test]
+fn test_asset_type_guard() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_eq!(AssetType::iter().count(), MAX_ASSET_TYPES);
+        assert!(AssetType::iter().all(|ty| AssetType::from_index(ty.index()) == Some(*ty)));
+        assert_eq!(AssetType::from_index(MAX_ASSET_TYPES as u8), None);
+
+        // Setting a zero balance never inserts a stray key.
+        assert_ok!(XAssets::set_balance(
+            Origin::root(),
+            BOB,
+            X_BTC,
+            BTreeMap::from([(AssetType::Locked, 0), (AssetType::Reserved, 5)])
+        ));
+        let balances = AssetBalance::<Test>::get(&BOB, &X_BTC);
+        assert!(!balances.contains_key(&AssetType::Locked));
+        assert_eq!(balances.get_or_zero(&AssetType::Reserved), 5);
+        assert!(balances.len() <= MAX_ASSET_TYPES);
+        assert!(!TotalAssetBalance::<Test>::get(&X_BTC).contains_key(&AssetType::Locked));
+    })
+}
+
+#[test]
+fn test_prune_unknown_asset_types() {
+    ExtBuilder::default().build_and_execute(|| {
+        let usable = XAssets::usable_balance(&BOB, &X_BTC);
+        let total_issuance = XAssets::total_issuance(&X_BTC);
+
+        // Stray keys written by an older runtime, the unknown asset type index 9 can not
+        // be decoded by this one.
+        let unknown = 9u8;
+        let account_key = AssetBalance::<Test>::hashed_key_for(&BOB, &X_BTC);
+        let raw = BTreeMap::from([(0u8, usable), (1u8, 0), (unknown, 7)]).encode();
+        unhashed::put_raw(&account_key, &raw);
+        let total_key = TotalAssetBalance::<Test>::hashed_key_for(&X_BTC);
+        let mut total = BTreeMap::<u8, Balance>::new();
+        for (ty, balance) in TotalAssetBalance::<Test>::get(&X_BTC) {
+            total.insert(ty.index(), balance);
+        }
+        total.insert(AssetType::Reserved.index(), 0);
+        total.insert(unknown, 7);
+        unhashed::put_raw(&total_key, &total.encode());
+        assert!(XAssets::has_unknown_asset_types(&BOB, &X_BTC));
+
+        // The guard rejects rewriting a map holding the unknown asset types.
+        assert_noop!(
+            XAssets::set_balance(
+                Origin::root(),
+                BOB,
+                X_BTC,
+                BTreeMap::from([(AssetType::Usable, usable)])
+            ),
+            XAssetsErr::UnknownAssetType
+        );
+
+        assert_noop!(
+            XAssets::prune_unknown_asset_types(Origin::signed(BOB), X_BTC, BOB),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(XAssets::prune_unknown_asset_types(
+            Origin::root(),
+            X_BTC,
+            BOB
+        ));
+        System::assert_last_event(crate::mock::Event::XAssets(crate::Event::AssetTypesPruned(
+            X_BTC,
+            BOB,
+            vec![(unknown, 7), (AssetType::Locked.index(), 0)],
+            vec![(unknown, 0), (AssetType::Reserved.index(), 0)],
+        )));
+        assert!(!XAssets::has_unknown_asset_types(&BOB, &X_BTC));
+        assert_eq!(
+            AssetBalance::<Test>::get(&BOB, &X_BTC),
+            BTreeMap::from([(AssetType::Usable, usable)])
+        );
+        assert!(!TotalAssetBalance::<Test>::get(&X_BTC).contains_key(&AssetType::Reserved));
+        assert_eq!(XAssets::total_issuance(&X_BTC), total_issuance);
+
+        // Nothing left to prune.
+        let events = System::events().len();
+        assert_ok!(XAssets::prune_unknown_asset_types(
+            Origin::root(),
+            X_BTC,
+            BOB
+        ));
+        assert_eq!(System::events().len(), events);
+
+        // A balance map with only the stray keys is removed along with its consumer reference.
+        let stray = 999;
+        frame_system::Pallet::<Test>::inc_providers(&stray);
+        assert_ok!(frame_system::Pallet::<Test>::inc_consumers(&stray));
+        AssetBalance::<Test>::insert(&stray, &X_BTC, BTreeMap::from([(AssetType::Usable, 0)]));
+        assert_ok!(XAssets::prune_unknown_asset_types(
+            Origin::root(),
+            X_BTC,
+            stray
+        ));
+        assert!(!AssetBalance::<Test>::contains_key(&stray, &X_BTC));
+        assert_eq!(System::consumers(&stray), 0);
+    })
+}
+
+#[test]
+fn test_move_guard() {
+    ExtBuilder::default().build_and_execute(|| {
+        let move_to_reserved = |caller| {
+            XAssets::move_balance(
+                &X_BTC,
+                &ALICE,
+                AssetType::Usable,
+                &ALICE,
+                AssetType::Reserved,
+                10,
+                caller,
+            )
+        };
+
+        assert_eq!(move_to_reserved(MoveCaller::User), Err(AssetErr::Forbidden));
+        System::assert_last_event(crate::mock::Event::XAssets(crate::Event::MoveForbidden(
+            X_BTC,
+            AssetType::Usable,
+            AssetType::Reserved,
+            MoveCaller::User,
+        )));
+        assert_eq!(XAssets::usable_balance(&ALICE, &X_BTC), 100);
+        assert_eq!(
+            XAssets::asset_balance_of(&ALICE, &X_BTC, AssetType::Reserved),
+            0
+        );
+
+        assert_ok!(move_to_reserved(MoveCaller::Staking));
+        assert_eq!(XAssets::usable_balance(&ALICE, &X_BTC), 90);
+        assert_eq!(
+            XAssets::asset_balance_of(&ALICE, &X_BTC, AssetType::Reserved),
+            10
+        );
+
+        // The transitions not touching `Reserved` are allowed for all the callers.
+        assert_ok!(XAssets::transfer(Origin::signed(ALICE), BOB, X_BTC, 10));
+        assert_eq!(XAssets::usable_balance(&ALICE, &X_BTC), 80);
+    })
+}
+
+#[test]
+fn migration_should_backfill_the_derived_accounts() {
+    use crate::migrations::{self, DerivedAccountSource};
+    use frame_support::traits::{GetStorageVersion, StorageVersion};
+
+    struct Source;
+    impl DerivedAccountSource<AccountId> for Source {
+        fn derived_accounts() -> Vec<(AccountId, DerivedAccountKind<AccountId>)> {
+            vec![
+                (ALICE, DerivedAccountKind::TokenJackpot(X_BTC)),
+                (BOB, DerivedAccountKind::TrusteePot(Chain::Bitcoin)),
+            ]
+        }
+    }
+
+    ExtBuilder::default().build_and_execute(|| {
+        // The chains upgraded from the storage version 0 are migrated.
+        assert_eq!(
+            XAssets::on_chain_storage_version(),
+            migrations::STORAGE_VERSION
+        );
+        StorageVersion::new(0).put::<XAssets>();
+
+        migrations::migrate::<Test, Source>();
+        assert_eq!(
+            XAssets::derived_account_of(ALICE),
+            Some(DerivedAccountKind::TokenJackpot(X_BTC))
+        );
+        assert_eq!(
+            XAssets::derived_account_of(BOB),
+            Some(DerivedAccountKind::TrusteePot(Chain::Bitcoin))
+        );
+        assert_eq!(
+            XAssets::on_chain_storage_version(),
+            migrations::STORAGE_VERSION
+        );
+
+        // Runs only once.
+        XAssets::note_derived_account(&ALICE, DerivedAccountKind::Council);
+        migrations::migrate::<Test, Source>();
+        assert_eq!(
+            XAssets::derived_account_of(ALICE),
+            Some(DerivedAccountKind::Council)
+        );
+    })
+}
+
+#[test]
+fn jackpot_outflow_should_be_guarded() {
+    ExtBuilder::default().build_and_execute(|| {
+        // ALICE plays the reward pot of X-BTC.
+        XAssets::note_derived_account(&ALICE, DerivedAccountKind::TokenJackpot(X_BTC));
+
+        assert_eq!(
+            XAssets::force_transfer(Origin::root(), ALICE, BOB, X_BTC, 10),
+            Err(XAssetsErr::MoveForbidden.into())
+        );
+        assert_eq!(
+            XAssets::set_balance(
+                Origin::root(),
+                ALICE,
+                X_BTC,
+                BTreeMap::from([(AssetType::Usable, 0)])
+            ),
+            Err(XAssetsErr::MoveForbidden.into())
+        );
+        assert_eq!(
+            XAssets::move_usable_balance(&X_BTC, &ALICE, &BOB, 10, MoveCaller::Staking),
+            Err(AssetErr::Forbidden)
+        );
+        System::assert_last_event(Event::XAssets(crate::Event::OutflowForbidden(
+            ALICE,
+            MoveCaller::Staking,
+        )));
+        assert_eq!(
+            XAssets::ensure_can_move_out(&ALICE, MoveCaller::User),
+            Err(XAssetsErr::MoveForbidden.into())
+        );
+        assert_eq!(XAssets::usable_balance(&ALICE, &X_BTC), 100);
+
+        // The jackpot paths still work, and the inflows are not restricted.
+        assert_ok!(XAssets::move_usable_balance(
+            &X_BTC,
+            &ALICE,
+            &BOB,
+            10,
+            MoveCaller::Jackpot
+        ));
+        assert_ok!(XAssets::transfer(Origin::signed(BOB), ALICE, X_BTC, 5));
+        assert_eq!(XAssets::usable_balance(&ALICE, &X_BTC), 95);
+        assert_ok!(XAssets::ensure_can_move_out(&BOB, MoveCaller::User));
+    })
+}
+
+#[test]
+fn issuance_beyond_cap_should_be_quarantined() {
+    ExtBuilder::default().build_no_endowed_and_execute(|| {
+        assert_noop!(
+            XAssets::set_max_issuance_per_window(
+                Origin::root(),
+                X_BTC,
+                Some(IssuanceCap {
+                    cap: 100,
+                    window_blocks: 0
+                })
+            ),
+            XAssetsErr::InvalidIssuanceCap
+        );
+        assert_ok!(XAssets::set_max_issuance_per_window(
+            Origin::root(),
+            X_BTC,
+            Some(IssuanceCap {
+                cap: 100,
+                window_blocks: 10
+            })
+        ));
+
+        // The issuance under the cap is credited as usual.
+        assert_ok!(XAssets::issue(&X_BTC, &ALICE, 60, true));
+        assert_eq!(XAssets::usable_balance(&ALICE, &X_BTC), 60);
+        assert!(XAssets::quarantined_issuances().is_empty());
+
+        // Only the part under the cap is credited, the rest is quarantined.
+        assert_ok!(XAssets::issue(&X_BTC, &BOB, 70, true));
+        assert_eq!(XAssets::usable_balance(&BOB, &X_BTC), 40);
+        System::assert_last_event(crate::mock::Event::XAssets(
+            crate::Event::IssuanceQuarantined(0, X_BTC, BOB, 30),
+        ));
+        assert_ok!(XAssets::issue(&X_BTC, &CHARLIE, 20, true));
+        assert_eq!(XAssets::usable_balance(&CHARLIE, &X_BTC), 0);
+        assert_eq!(XAssets::quarantined_issuances().len(), 2);
+        assert_eq!(XAssets::total_issuance(&X_BTC), 100);
+
+        // The quarantined issuances are credited once released by the council.
+        assert_noop!(
+            XAssets::release_quarantined(Origin::signed(ALICE), 1),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(XAssets::release_quarantined(Origin::root(), 1));
+        assert_eq!(XAssets::usable_balance(&BOB, &X_BTC), 70);
+        assert_ok!(XAssets::release_quarantined(Origin::root(), 10));
+        assert_eq!(XAssets::usable_balance(&CHARLIE, &X_BTC), 20);
+        assert!(XAssets::quarantined_issuances().is_empty());
+        assert_ok!(XAssets::release_quarantined(Origin::root(), 10));
+        assert_eq!(XAssets::usable_balance(&BOB, &X_BTC), 70);
+        assert_eq!(XAssets::usable_balance(&CHARLIE, &X_BTC), 20);
+        assert_eq!(XAssets::total_issuance(&X_BTC), 150);
+
+        // The rejected issuances are never credited.
+        assert_ok!(XAssets::issue(&X_BTC, &DAVE, 5, true));
+        assert_noop!(
+            XAssets::reject_quarantined(Origin::root(), vec![2, 0]),
+            XAssetsErr::QuarantinedIssuanceNotFound
+        );
+        assert_ok!(XAssets::reject_quarantined(Origin::root(), vec![2]));
+        assert!(XAssets::quarantined_issuances().is_empty());
+        assert_eq!(XAssets::usable_balance(&DAVE, &X_BTC), 0);
+
+        // The issuance fails once the quarantine is full.
+        for _ in 0..MAX_QUARANTINED_ISSUANCES {
+            assert_ok!(XAssets::issue(&X_BTC, &DAVE, 1, true));
+        }
+        let window = XAssets::issuance_window_of(X_BTC);
+        assert_noop!(
+            XAssets::issue(&X_BTC, &DAVE, 1, true),
+            XAssetsErr::TooManyQuarantinedIssuances
+        );
+        assert_eq!(XAssets::issuance_window_of(X_BTC), window);
+    })
+}
+
+#[test]
+fn issuance_cap_should_reset_on_new_window() {
+    ExtBuilder::default().build_no_endowed_and_execute(|| {
+        assert_ok!(XAssets::set_max_issuance_per_window(
+            Origin::root(),
+            X_BTC,
+            Some(IssuanceCap {
+                cap: 100,
+                window_blocks: 10
+            })
+        ));
+        assert_ok!(XAssets::issue(&X_BTC, &ALICE, 100, true));
+
+        System::set_block_number(10);
+        assert_ok!(XAssets::issue(&X_BTC, &ALICE, 10, true));
+        assert_eq!(XAssets::usable_balance(&ALICE, &X_BTC), 100);
+        assert_eq!(XAssets::quarantined_issuances().len(), 1);
+
+        System::set_block_number(11);
+        assert_ok!(XAssets::issue(&X_BTC, &ALICE, 100, true));
+        assert_eq!(XAssets::usable_balance(&ALICE, &X_BTC), 200);
+        assert_eq!(XAssets::issuance_window_of(X_BTC).started_at, 11);
+        assert_eq!(XAssets::quarantined_issuances().len(), 1);
+
+        // The native asset is never capped.
+        assert_noop!(
+            XAssets::set_max_issuance_per_window(
+                Origin::root(),
+                xp_protocol::PCX,
+                Some(IssuanceCap {
+                    cap: 100,
+                    window_blocks: 10
+                })
+            ),
+            XAssetsErr::DenyNativeAsset
+        );
+    })
+}
+
+#[test]
+fn account_status_should_show_foreign_asset_only_account() {
+    ExtBuilder::default().build_no_endowed_and_execute(|| {
+        assert_eq!(XAssets::account_status(&ALICE), AccountStatus::default());
+
+        assert_ok!(XAssets::issue(&X_BTC, &ALICE, 100, true));
+        assert_eq!(
+            XAssets::account_status(&ALICE),
+            AccountStatus {
+                exists: true,
+                pcx_free: 0,
+                pcx_locked: 0,
+                lock_count: 0,
+                has_foreign_assets: true,
+                asset_count: 1,
+                reapable: false,
+            }
+        );
+
+        // Draining the PCX received later does not reap the account.
+        let _ = Balances::deposit_creating(&ALICE, 50);
+        assert_ok!(Balances::transfer(Origin::signed(ALICE), BOB, 50));
+        assert!(XAssets::account_status(&ALICE).exists);
+        assert_eq!(XAssets::usable_balance(&ALICE, &X_BTC), 100);
+    })
+}
+
+#[test]
+fn account_holding_foreign_assets_should_not_be_reaped() {
+    ExtBuilder::default().build_no_endowed_and_execute(|| {
+        let _ = Balances::deposit_creating(&BOB, 100);
+        assert!(XAssets::account_status(&BOB).reapable);
+
+        assert_ok!(XAssets::issue(&X_BTC, &BOB, 10, true));
+        assert!(!XAssets::account_status(&BOB).reapable);
+        assert_noop!(
+            Balances::transfer(Origin::signed(BOB), CHARLIE, 100),
+            pallet_balances::Error::<Test>::KeepAlive
+        );
+        assert_eq!(XAssets::usable_balance(&BOB, &X_BTC), 10);
+
+        // The account is reaped as usual once the foreign assets are gone.
+        assert_ok!(XAssets::transfer(Origin::signed(BOB), DAVE, X_BTC, 10));
+        assert!(XAssets::account_status(&BOB).reapable);
+        assert_ok!(Balances::transfer(Origin::signed(BOB), CHARLIE, 100));
+        assert_eq!(XAssets::account_status(&BOB), AccountStatus::default());
+    })
+}
+
+#[test]
+fn holds_should_reduce_spendable_balance() {
+    ExtBuilder::default().build_no_endowed_and_execute(|| {
+        assert_ok!(XAssets::issue(&X_BTC, &ALICE, 100, true));
+        assert_eq!(XAssets::spendable_balance(&ALICE, &X_BTC), 100);
+
+        assert_ok!(XAssets::hold(&X_BTC, &ALICE, 60));
+        assert_eq!(XAssets::usable_balance(&ALICE, &X_BTC), 100);
+        assert_eq!(XAssets::spendable_balance(&ALICE, &X_BTC), 40);
+
+        // The held balance can not be held again by another module.
+        assert_noop!(
+            XAssets::hold(&X_BTC, &ALICE, 50),
+            XAssetsErr::InsufficientBalance
+        );
+
+        assert_eq!(XAssets::release_hold(&X_BTC, &ALICE, 80), 60);
+        assert_eq!(XAssets::held_balance(&ALICE, &X_BTC), 0);
+        assert_eq!(XAssets::spendable_balance(&ALICE, &X_BTC), 100);
+
+        // The holds apply to the native asset as well.
+        let pcx = <Test as xpallet_assets_registrar::Config>::NativeAssetId::get();
+        let _ = Balances::deposit_creating(&ALICE, 50);
+        assert_ok!(XAssets::hold(&pcx, &ALICE, 20));
+        assert_eq!(XAssets::spendable_balance(&ALICE, &pcx), 30);
+    })
+}
+
+fn t_lock(
+    asset_id: u32,
+    amount: Balance,
+    start: u64,
+    per_block: Balance,
+) -> LockInfo<Balance, u64> {
+    LockInfo {
+        asset_id,
+        amount,
+        unlock_start_block: start,
+        per_block_release: per_block,
+        nominatable: false,
+    }
+}
+
+#[test]
+fn vesting_lock_should_release_linearly() {
+    let lock = t_lock(X_BTC, 100, 10, 7);
+    assert_eq!(lock.locked_at(0), 100);
+    assert_eq!(lock.locked_at(10), 100);
+    assert_eq!(lock.locked_at(11), 93);
+    assert_eq!(lock.locked_at(20), 30);
+    assert_eq!(lock.locked_at(24), 2);
+    assert_eq!(lock.locked_at(25), 0);
+    assert_eq!(lock.locked_at(u64::MAX), 0);
+}
+
+#[test]
+fn vesting_lock_should_restrict_spending() {
+    ExtBuilder::default().build_no_endowed_and_execute(|| {
+        assert_ok!(XAssets::issue(&X_BTC, &ALICE, 100, true));
+        assert_ok!(XAssets::impose_lock(
+            Origin::root(),
+            ALICE,
+            t_lock(X_BTC, 60, 10, 10)
+        ));
+        assert_eq!(XAssets::spendable_balance(&ALICE, &X_BTC), 40);
+
+        assert_noop!(
+            XAssets::transfer(Origin::signed(ALICE), BOB, X_BTC, 41),
+            XAssetsErr::LiquidityRestrictions
+        );
+        assert_ok!(XAssets::transfer(Origin::signed(ALICE), BOB, X_BTC, 40));
+
+        // 30 is released by block 13.
+        System::set_block_number(13);
+        assert_eq!(XAssets::vesting_locked_balance(&ALICE, &X_BTC), 30);
+        assert_eq!(XAssets::spendable_balance(&ALICE, &X_BTC), 30);
+        assert_noop!(
+            XAssets::transfer(Origin::signed(ALICE), BOB, X_BTC, 31),
+            XAssetsErr::LiquidityRestrictions
+        );
+        assert_ok!(XAssets::transfer(Origin::signed(ALICE), BOB, X_BTC, 30));
+
+        System::set_block_number(16);
+        assert_ok!(XAssets::transfer(Origin::signed(ALICE), BOB, X_BTC, 30));
+        assert_eq!(XAssets::usable_balance(&ALICE, &X_BTC), 0);
+    })
+}
+
+#[test]
+fn vesting_locks_should_stack() {
+    ExtBuilder::default().build_no_endowed_and_execute(|| {
+        assert_ok!(XAssets::issue(&X_BTC, &ALICE, 100, true));
+        assert_ok!(XAssets::impose_lock(
+            Origin::root(),
+            ALICE,
+            t_lock(X_BTC, 50, 0, 10)
+        ));
+        assert_ok!(XAssets::impose_lock(
+            Origin::root(),
+            ALICE,
+            t_lock(X_BTC, 30, 5, 5)
+        ));
+        assert_noop!(
+            XAssets::impose_lock(Origin::root(), ALICE, t_lock(X_BTC, 0, 5, 5)),
+            XAssetsErr::InvalidLock
+        );
+
+        System::set_block_number(2);
+        assert_eq!(XAssets::vesting_locked_balance(&ALICE, &X_BTC), 30 + 30);
+        System::set_block_number(7);
+        assert_eq!(XAssets::vesting_locked_balance(&ALICE, &X_BTC), 20);
+        assert_eq!(XAssets::spendable_balance(&ALICE, &X_BTC), 80);
+
+        assert_noop!(
+            XAssets::remove_lock(Origin::root(), ALICE, 2),
+            XAssetsErr::LockNotFound
+        );
+        assert_ok!(XAssets::remove_lock(Origin::root(), ALICE, 1));
+        assert_eq!(XAssets::spendable_balance(&ALICE, &X_BTC), 100);
+        assert_eq!(XAssets::locks_of(&ALICE).len(), 1);
+
+        // The fully vested locks are dropped once unlocked.
+        assert_ok!(XAssets::unlock_vested(Origin::signed(ALICE)));
+        assert!(XAssets::locks_of(&ALICE).is_empty());
+    })
+}
+
+#[test]
+fn native_vesting_lock_should_restrict_transfer_and_nomination() {
+    use xpallet_support::traits::NativeHolds;
+
+    ExtBuilder::default().build_no_endowed_and_execute(|| {
+        let pcx = <Test as xpallet_assets_registrar::Config>::NativeAssetId::get();
+        let _ = Balances::deposit_creating(&ALICE, 100);
+        assert_ok!(XAssets::impose_lock(
+            Origin::root(),
+            ALICE,
+            t_lock(pcx, 60, 1, 10)
+        ));
+        assert_ok!(XAssets::impose_lock(
+            Origin::root(),
+            ALICE,
+            LockInfo {
+                nominatable: true,
+                ..t_lock(pcx, 20, 1, 10)
+            }
+        ));
+        assert_eq!(XAssets::spendable_balance(&ALICE, &pcx), 20);
+        // Only the nominatable lock can be bonded in Staking.
+        assert_eq!(XAssets::native_held(&ALICE), 60);
+        assert_eq!(XAssets::account_status(&ALICE).pcx_locked, 80);
+        assert_eq!(XAssets::account_status(&ALICE).lock_count, 2);
+
+        assert_noop!(
+            Balances::transfer(Origin::signed(ALICE), BOB, 21),
+            pallet_balances::Error::<Test>::LiquidityRestrictions
+        );
+        assert_ok!(Balances::transfer(Origin::signed(ALICE), BOB, 20));
+
+        // The vested PCX is released from the currency lock by `unlock_vested`.
+        System::set_block_number(4);
+        assert_eq!(XAssets::spendable_balance(&ALICE, &pcx), 50);
+        assert_noop!(
+            Balances::transfer(Origin::signed(ALICE), BOB, 30),
+            pallet_balances::Error::<Test>::LiquidityRestrictions
+        );
+        assert_ok!(XAssets::unlock_vested(Origin::signed(ALICE)));
+        assert_ok!(Balances::transfer(Origin::signed(ALICE), BOB, 30));
+        assert_eq!(XAssets::account_status(&ALICE).pcx_locked, 30);
+        assert_eq!(XAssets::account_status(&ALICE).lock_count, 1);
+    })
+}
+
+#[test]
+fn pallet_docs_should_cover_all_variants() {
+    use xpallet_support::docs::undocumented_variants;
+
+    assert_eq!(crate::PALLET_DOCS.name, "XAssets");
+    let (missing, unknown) = undocumented_variants::<crate::Event<Test>>(crate::PALLET_DOCS.events);
+    assert!(missing.is_empty(), "undocumented events: {:?}", missing);
+    assert!(unknown.is_empty(), "unknown events: {:?}", unknown);
+    let (missing, unknown) = undocumented_variants::<crate::Error<Test>>(crate::PALLET_DOCS.errors);
+    assert!(missing.is_empty(), "undocumented errors: {:?}", missing);
+    assert!(unknown.is_empty(), "unknown errors: {:?}", unknown);
+}
+
+#[test]
+fn asset_events_should_be_indexed_by_asset_topic() {
+    ExtBuilder::default().build_and_execute(|| {
+        let topic = chainx_primitives::asset_event_topic::<BlakeTwo256>(X_BTC);
+        assert_ok!(XAssets::issue(&X_BTC, &ALICE, 100, true));
+        assert_ok!(XAssets::transfer(Origin::signed(ALICE), BOB, X_BTC, 25));
+        assert_ok!(XAssets::destroy_reserved_withdrawal(&X_BTC, &BOB, 0));
+
+        let indexed = System::events()
+            .into_iter()
+            .filter(|record| {
+                matches!(
+                    record.event,
+                    Event::XAssets(crate::Event::Issued(..))
+                        | Event::XAssets(crate::Event::Moved(..))
+                        | Event::XAssets(crate::Event::Destroyed(..))
+                )
+            })
+            .map(|record| record.topics)
+            .collect::<Vec<_>>();
+        assert_eq!(indexed, vec![vec![topic]; 3]);
+        assert_eq!(System::event_topics(&topic).len(), 3);
+    })
+}
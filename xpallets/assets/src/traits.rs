@@ -3,9 +3,10 @@
 use frame_support::dispatch::{DispatchError, DispatchResult};
 
 use chainx_primitives::AssetId;
+use xp_gateway_common::ExternalAddress;
 use xpallet_assets_registrar::Chain;
 
-use crate::types::{AssetErr, AssetType, WithdrawalLimit};
+use crate::types::{AssetErr, AssetType, DerivedAccountKind, MoveCaller, WithdrawalLimit};
 
 pub trait ChainT<Balance: Default> {
     /// ASSET should be the native Asset for this chain.
@@ -18,11 +19,79 @@ pub trait ChainT<Balance: Default> {
     fn check_addr(_addr: &[u8], _ext: &[u8]) -> DispatchResult {
         Ok(())
     }
+    /// Validates the withdrawal address like `check_addr`, returning its typed value.
+    fn parse_addr(addr: &[u8], ext: &[u8]) -> Result<ExternalAddress, DispatchError> {
+        Self::check_addr(addr, ext)?;
+        Ok(ExternalAddress::Named(addr.to_vec()))
+    }
     fn withdrawal_limit(_asset_id: &AssetId) -> Result<WithdrawalLimit<Balance>, DispatchError> {
         Ok(WithdrawalLimit::default())
     }
 }
 
+/// Hook for the modules managing an account to react on the assets moved into it.
+///
+/// Only invoked for the destinations registered in `TransferNotifyOf`.
+pub trait OnReceived<AccountId, Balance> {
+    /// Triggered after `value` of asset `id` has been moved from `from` to `to`.
+    ///
+    /// An error does not revert the move, only the changes made by the hook itself.
+    fn on_received(
+        id: &AssetId,
+        from: &AccountId,
+        to: &AccountId,
+        value: Balance,
+    ) -> DispatchResult;
+}
+
+/// Guard of the asset type transitions performed by the internal move API, and of the
+/// outflows of the registered derived accounts.
+///
+/// The default implementation `()` allows all the transitions and outflows.
+pub trait MoveGuard {
+    /// Returns true if `caller` is allowed to move asset `id` from `from_type` to `to_type`.
+    fn can_move(id: &AssetId, from_type: AssetType, to_type: AssetType, caller: MoveCaller)
+        -> bool;
+
+    /// Returns true if `caller` is allowed to move the balances out of the derived account
+    /// of `kind`.
+    fn can_move_out<AccountId>(_kind: &DerivedAccountKind<AccountId>, _caller: MoveCaller) -> bool {
+        true
+    }
+}
+
+impl MoveGuard for () {
+    fn can_move(
+        _id: &AssetId,
+        _from_type: AssetType,
+        _to_type: AssetType,
+        _caller: MoveCaller,
+    ) -> bool {
+        true
+    }
+}
+
+/// Registry of the accounts derived by the modules, e.g., the reward pots.
+///
+/// The default implementation `()` records nothing.
+pub trait DerivedAccountRegistry<AccountId> {
+    /// Records `who` as the account derived for `kind`.
+    fn note_derived_account(who: &AccountId, kind: DerivedAccountKind<AccountId>);
+
+    /// Returns Ok if `caller` is allowed to move the balances out of `who`.
+    ///
+    /// Only the outflows of the registered derived accounts are restricted.
+    fn ensure_can_move_out(who: &AccountId, caller: MoveCaller) -> DispatchResult;
+}
+
+impl<AccountId> DerivedAccountRegistry<AccountId> for () {
+    fn note_derived_account(_who: &AccountId, _kind: DerivedAccountKind<AccountId>) {}
+
+    fn ensure_can_move_out(_who: &AccountId, _caller: MoveCaller) -> DispatchResult {
+        Ok(())
+    }
+}
+
 /// Hooks for doing stuff when the assets are minted/moved/destroyed.
 pub trait OnAssetChanged<AccountId, Balance> {
     /// Triggered before issuing the fresh assets.
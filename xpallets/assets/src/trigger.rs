@@ -4,11 +4,74 @@ use frame_support::dispatch::DispatchResult;
 
 use chainx_primitives::AssetId;
 
-use crate::traits::OnAssetChanged;
+use crate::traits::{OnAssetChanged, OnReceived};
 use crate::types::{AssetErr, AssetType};
 use crate::{BalanceOf, Config, Event, Pallet};
 
-impl<AccountId, Balance> OnAssetChanged<AccountId, Balance> for () {}
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl<AccountId, Balance: Copy> OnAssetChanged<AccountId, Balance> for Tuple {
+    fn on_issue_pre(id: &AssetId, who: &AccountId) {
+        for_tuples!( #( Tuple::on_issue_pre(id, who); )* );
+    }
+
+    fn on_issue_post(id: &AssetId, who: &AccountId, value: Balance) -> DispatchResult {
+        for_tuples!( #( Tuple::on_issue_post(id, who, value)?; )* );
+        Ok(())
+    }
+
+    fn on_move_pre(
+        id: &AssetId,
+        from: &AccountId,
+        from_type: AssetType,
+        to: &AccountId,
+        to_type: AssetType,
+        value: Balance,
+    ) {
+        for_tuples!( #( Tuple::on_move_pre(id, from, from_type, to, to_type, value); )* );
+    }
+
+    fn on_move_post(
+        id: &AssetId,
+        from: &AccountId,
+        from_type: AssetType,
+        to: &AccountId,
+        to_type: AssetType,
+        value: Balance,
+    ) -> Result<(), AssetErr> {
+        for_tuples!( #( Tuple::on_move_post(id, from, from_type, to, to_type, value)?; )* );
+        Ok(())
+    }
+
+    fn on_destroy_pre(id: &AssetId, who: &AccountId) {
+        for_tuples!( #( Tuple::on_destroy_pre(id, who); )* );
+    }
+
+    fn on_destroy_post(id: &AssetId, who: &AccountId, value: Balance) -> DispatchResult {
+        for_tuples!( #( Tuple::on_destroy_post(id, who, value)?; )* );
+        Ok(())
+    }
+
+    fn on_set_balance(
+        id: &AssetId,
+        who: &AccountId,
+        type_: AssetType,
+        value: Balance,
+    ) -> DispatchResult {
+        for_tuples!( #( Tuple::on_set_balance(id, who, type_, value)?; )* );
+        Ok(())
+    }
+}
+
+impl<AccountId, Balance> OnReceived<AccountId, Balance> for () {
+    fn on_received(
+        _id: &AssetId,
+        _from: &AccountId,
+        _to: &AccountId,
+        _value: Balance,
+    ) -> DispatchResult {
+        Ok(())
+    }
+}
 
 pub struct AssetChangedTrigger<T: Config>(sp_std::marker::PhantomData<T>);
 
@@ -32,15 +95,14 @@ impl<T: Config> AssetChangedTrigger<T> {
         to_type: AssetType,
         value: BalanceOf<T>,
     ) -> Result<(), AssetErr> {
-        Pallet::<T>::deposit_event(Event::<T>::Moved(
+        Pallet::<T>::deposit_asset_event(
             *id,
-            from.clone(),
-            from_type,
-            to.clone(),
-            to_type,
-            value,
-        ));
+            Event::<T>::Moved(*id, from.clone(), from_type, to.clone(), to_type, value),
+        );
         T::OnAssetChanged::on_move_post(id, from, from_type, to, to_type, value)?;
+        if from != to && Pallet::<T>::transfer_notify_of(to) {
+            Pallet::<T>::notify_received(id, from, to, value);
+        }
         Ok(())
     }
 
@@ -55,7 +117,7 @@ impl<T: Config> AssetChangedTrigger<T> {
         reward_pcx: bool,
     ) -> DispatchResult {
         if reward_pcx {
-            Pallet::<T>::deposit_event(Event::<T>::Issued(*id, who.clone(), value));
+            Pallet::<T>::deposit_asset_event(*id, Event::<T>::Issued(*id, who.clone(), value));
             T::OnAssetChanged::on_issue_post(id, who, value)?;
         }
 
@@ -71,7 +133,7 @@ impl<T: Config> AssetChangedTrigger<T> {
         who: &T::AccountId,
         value: BalanceOf<T>,
     ) -> DispatchResult {
-        Pallet::<T>::deposit_event(Event::<T>::Destroyed(*id, who.clone(), value));
+        Pallet::<T>::deposit_asset_event(*id, Event::<T>::Destroyed(*id, who.clone(), value));
         T::OnAssetChanged::on_destroy_post(id, who, value)?;
         Ok(())
     }
@@ -82,7 +144,10 @@ impl<T: Config> AssetChangedTrigger<T> {
         type_: AssetType,
         value: BalanceOf<T>,
     ) -> DispatchResult {
-        Pallet::<T>::deposit_event(Event::<T>::BalanceSet(*id, who.clone(), type_, value));
+        Pallet::<T>::deposit_asset_event(
+            *id,
+            Event::<T>::BalanceSet(*id, who.clone(), type_, value),
+        );
         T::OnAssetChanged::on_set_balance(id, who, type_, value)?;
         Ok(())
     }
@@ -7,23 +7,41 @@ use scale_info::TypeInfo;
 use serde::{Deserialize, Serialize};
 
 // Substrate
-use sp_runtime::RuntimeDebug;
+use sp_runtime::{
+    traits::{AtLeast32BitUnsigned, SaturatedConversion, Saturating},
+    RuntimeDebug,
+};
 use sp_std::{collections::btree_map::BTreeMap, prelude::*, slice::Iter};
 
 // ChainX
+use chainx_primitives::AssetId;
 pub use chainx_primitives::{Decimals, Desc, Token};
-use xpallet_assets_registrar::AssetInfo;
+use xp_runtime::Memo;
+use xpallet_assets_registrar::{AssetInfo, Chain};
 
 use frame_support::traits::LockIdentifier;
 
 use crate::{Config, Error};
 
-const ASSET_TYPES: [AssetType; 5] = [
+/// The number of the asset types, i.e., the most entries an asset balance map can have.
+pub const MAX_ASSET_TYPES: usize = ASSET_TYPES.len();
+
+/// The most vesting locks an account can have.
+pub const MAX_VESTING_LOCKS: usize = 16;
+
+/// The most quarantined issuances pending release or rejection.
+pub const MAX_QUARANTINED_ISSUANCES: usize = 1024;
+
+/// The identifier of the native currency lock enforcing the vesting locks of PCX.
+pub const VESTING_LOCK_ID: LockIdentifier = *b"xvesting";
+
+const ASSET_TYPES: [AssetType; 6] = [
     AssetType::Usable,
     AssetType::Locked,
     AssetType::Reserved,
     AssetType::ReservedWithdrawal,
     AssetType::ReservedDexSpot,
+    AssetType::ReservedScheduled,
 ];
 
 /// Concrete type of non-native asset balance.
@@ -41,12 +59,14 @@ pub enum AssetType {
     Locked,
     /// General reserved balance.
     ///
-    /// Unused for now.
+    /// Holds the deposits kept in custody by the gateway until they are released.
     Reserved,
     /// Reserved balance when an account redeems its bridged asset.
     ReservedWithdrawal,
     /// Reserved balance for creating order in DEX.
     ReservedDexSpot,
+    /// Reserved balance of the pending scheduled transfers.
+    ReservedScheduled,
 }
 
 impl AssetType {
@@ -54,6 +74,40 @@ impl AssetType {
     pub fn iter() -> Iter<'static, AssetType> {
         ASSET_TYPES.iter()
     }
+
+    /// Returns the SCALE index of the asset type.
+    pub fn index(&self) -> u8 {
+        *self as u8
+    }
+
+    /// Returns the asset type of the SCALE `index`, None if it is unknown to this runtime.
+    pub fn from_index(index: u8) -> Option<AssetType> {
+        ASSET_TYPES.get(index as usize).copied()
+    }
+}
+
+/// Decodes a raw asset balance map as stored, splitting the entries of the asset types
+/// unknown to this runtime (left by the older runtimes) from the known ones.
+///
+/// The unknown entries are returned with the raw asset type index.
+pub fn decode_raw_balance_map<Balance: Decode>(
+    raw: &[u8],
+) -> Option<(BTreeMap<AssetType, Balance>, Vec<(u8, Balance)>)> {
+    let input = &mut &raw[..];
+    let len = codec::Compact::<u32>::decode(input).ok()?.0;
+    let mut known = BTreeMap::new();
+    let mut unknown = Vec::new();
+    for _ in 0..len {
+        let index = u8::decode(input).ok()?;
+        let balance = Balance::decode(input).ok()?;
+        match AssetType::from_index(index) {
+            Some(ty) => {
+                known.insert(ty, balance);
+            }
+            None => unknown.push((index, balance)),
+        }
+    }
+    Some((known, unknown))
 }
 
 impl Default for AssetType {
@@ -90,6 +144,32 @@ pub struct TotalAssetInfo<Balance> {
     pub balance: BTreeMap<AssetType, Balance>,
     pub is_online: bool,
     pub restrictions: AssetRestrictions,
+    /// The source reference of a bridged asset, see `AssetSourceRefOf` of the registrar.
+    #[cfg_attr(feature = "std", serde(with = "xp_rpc::serde_text_opt"))]
+    pub source_ref: Option<Vec<u8>>,
+}
+
+/// The module on whose behalf the assets are moved by the internal API.
+///
+/// Passed to `MoveGuard`, which decides the asset type transitions each caller is allowed to
+/// perform.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum MoveCaller {
+    /// The transfers signed by the asset holder.
+    User,
+    /// The transfers forced by root.
+    Root,
+    /// The scheduled transfers of this module.
+    Assets,
+    /// The gateway modules, e.g., the withdrawal reservations and the trustee rewards.
+    Gateway,
+    /// The DEX spot module.
+    DexSpot,
+    /// The staking modules.
+    Staking,
+    /// The claim, reward and slash paths of the mining jackpots(reward pots).
+    Jackpot,
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo)]
@@ -101,6 +181,8 @@ pub enum AssetErr {
     TotalAssetOverFlow,
     InvalidAsset,
     NotAllow,
+    WouldKill,
+    Forbidden,
 }
 
 impl<T: Config> From<AssetErr> for Error<T> {
@@ -112,6 +194,8 @@ impl<T: Config> From<AssetErr> for Error<T> {
             AssetErr::TotalAssetOverFlow => Error::<T>::TotalAssetOverflow,
             AssetErr::InvalidAsset => Error::<T>::InvalidAsset,
             AssetErr::NotAllow => Error::<T>::ActionNotAllowed,
+            AssetErr::WouldKill => Error::<T>::WouldKill,
+            AssetErr::Forbidden => Error::<T>::MoveForbidden,
         }
     }
 }
@@ -135,3 +219,126 @@ pub struct WithdrawalLimit<Balance> {
     pub minimal_withdrawal: Balance,
     pub fee: Balance,
 }
+
+/// Id of the scheduled transfer.
+pub type ScheduledTransferId = u32;
+
+/// A transfer which can be executed by anyone once the unlock block is reached.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ScheduledTransfer<AccountId, Balance, BlockNumber> {
+    pub asset_id: AssetId,
+    pub from: AccountId,
+    pub dest: AccountId,
+    pub value: Balance,
+    pub unlock_block: BlockNumber,
+    pub memo: Memo,
+}
+
+/// A vesting lock on the balance of an asset, released linearly since `unlock_start_block`.
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct LockInfo<Balance, BlockNumber> {
+    pub asset_id: AssetId,
+    pub amount: Balance,
+    pub unlock_start_block: BlockNumber,
+    pub per_block_release: Balance,
+    /// Whether the unvested balance can still be nominated in Staking.
+    pub nominatable: bool,
+}
+
+impl<Balance, BlockNumber> LockInfo<Balance, BlockNumber>
+where
+    Balance: AtLeast32BitUnsigned + Copy,
+    BlockNumber: AtLeast32BitUnsigned + Copy,
+{
+    /// Returns the balance still locked at block `now`.
+    pub fn locked_at(&self, now: BlockNumber) -> Balance {
+        if now <= self.unlock_start_block {
+            return self.amount;
+        }
+        let elapsed = (now - self.unlock_start_block)
+            .saturated_into::<u128>()
+            .saturated_into::<Balance>();
+        self.amount
+            .saturating_sub(self.per_block_release.saturating_mul(elapsed))
+    }
+}
+
+/// Id of the quarantined issuance.
+pub type QuarantineId = u32;
+
+/// The most balance of an asset allowed to be issued within a window of blocks.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct IssuanceCap<Balance> {
+    pub cap: Balance,
+    pub window_blocks: u32,
+}
+
+/// The balance of an asset issued in the current window.
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct IssuanceWindow<Balance, BlockNumber> {
+    pub started_at: BlockNumber,
+    pub issued: Balance,
+}
+
+/// An issuance beyond the cap, which is credited only once released by the council.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct QuarantinedIssuance<AccountId, Balance, BlockNumber> {
+    pub id: QuarantineId,
+    pub asset_id: AssetId,
+    pub who: AccountId,
+    pub value: Balance,
+    pub reward_pcx: bool,
+    /// The block at which the issuance was quarantined.
+    pub block: BlockNumber,
+}
+
+/// The account existence of ChainX seen by the wallets, see [`crate::Pallet::account_status`].
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct AccountStatus<Balance> {
+    /// Whether the account exists, i.e., has at least one provider.
+    ///
+    /// The PCX transfer below the existential deposit to a non-existent account fails.
+    pub exists: bool,
+    pub pcx_free: Balance,
+    /// The PCX still locked by the vesting locks at the current block.
+    pub pcx_locked: Balance,
+    /// The number of the vesting locks of the account, on PCX or the other assets.
+    pub lock_count: u32,
+    pub has_foreign_assets: bool,
+    /// The number of the non-native assets held by the account.
+    pub asset_count: u32,
+    /// Whether the account would be reaped once its PCX is drained.
+    ///
+    /// Never true while the account holds any foreign asset, each held asset keeps a
+    /// consumer reference on the account.
+    pub reapable: bool,
+}
+
+/// The owner of an account derived by the modules, see [`crate::Pallet::derived_account_of`].
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum DerivedAccountKind<AccountId> {
+    /// The reward pot of a mining asset.
+    TokenJackpot(AssetId),
+    /// The reward pot of a validator.
+    IntentionJackpot(AccountId),
+    /// The multisig account of the trustees of a chain.
+    TrusteePot(Chain),
+    /// The treasury account spent by the council.
+    Council,
+    Team,
+    Burn,
+}
@@ -35,6 +35,17 @@ pub trait WeightInfo {
     fn force_transfer() -> Weight;
     fn set_balance(n: u32) -> Weight;
     fn set_asset_limit() -> Weight;
+    fn schedule_transfer() -> Weight;
+    fn execute_scheduled() -> Weight;
+    fn cancel_scheduled() -> Weight;
+    fn set_transfer_notify() -> Weight;
+    fn prune_unknown_asset_types() -> Weight;
+    fn set_max_issuance_per_window() -> Weight;
+    fn release_quarantined(n: u32) -> Weight;
+    fn reject_quarantined(n: u32) -> Weight;
+    fn impose_lock() -> Weight;
+    fn remove_lock() -> Weight;
+    fn unlock_vested() -> Weight;
 }
 
 /// Weights for xpallet_assets using the Substrate node and recommended hardware.
@@ -60,6 +71,81 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
             .saturating_add(T::DbWeight::get().reads(1 as Weight))
             .saturating_add(T::DbWeight::get().writes(1 as Weight))
     }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `schedule_transfer` benchmark.
+    fn schedule_transfer() -> Weight {
+        (98_317_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `execute_scheduled` benchmark.
+    fn execute_scheduled() -> Weight {
+        (112_604_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(7 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `cancel_scheduled` benchmark.
+    fn cancel_scheduled() -> Weight {
+        (87_261_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_transfer_notify` benchmark.
+    fn set_transfer_notify() -> Weight {
+        (9_864_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `prune_unknown_asset_types` benchmark.
+    fn prune_unknown_asset_types() -> Weight {
+        (38_216_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_max_issuance_per_window` benchmark.
+    fn set_max_issuance_per_window() -> Weight {
+        (12_108_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `release_quarantined` benchmark.
+    fn release_quarantined(n: u32) -> Weight {
+        (8_914_000 as Weight)
+            .saturating_add((71_402_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().reads((4 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(n as Weight)))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `reject_quarantined` benchmark.
+    fn reject_quarantined(n: u32) -> Weight {
+        (9_517_000 as Weight)
+            .saturating_add((1_236_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // The vesting lock weights are estimated from their storage accesses, they must be
+    // regenerated by the `impose_lock`, `remove_lock` and `unlock_vested` benchmarks.
+    fn impose_lock() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn remove_lock() -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn unlock_vested() -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
 }
 
 // For backwards compatibility and tests
@@ -84,4 +170,79 @@ impl WeightInfo for () {
             .saturating_add(RocksDbWeight::get().reads(1 as Weight))
             .saturating_add(RocksDbWeight::get().writes(1 as Weight))
     }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `schedule_transfer` benchmark.
+    fn schedule_transfer() -> Weight {
+        (98_317_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `execute_scheduled` benchmark.
+    fn execute_scheduled() -> Weight {
+        (112_604_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(7 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `cancel_scheduled` benchmark.
+    fn cancel_scheduled() -> Weight {
+        (87_261_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_transfer_notify` benchmark.
+    fn set_transfer_notify() -> Weight {
+        (9_864_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `prune_unknown_asset_types` benchmark.
+    fn prune_unknown_asset_types() -> Weight {
+        (38_216_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_max_issuance_per_window` benchmark.
+    fn set_max_issuance_per_window() -> Weight {
+        (12_108_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `release_quarantined` benchmark.
+    fn release_quarantined(n: u32) -> Weight {
+        (8_914_000 as Weight)
+            .saturating_add((71_402_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((4 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(n as Weight)))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `reject_quarantined` benchmark.
+    fn reject_quarantined(n: u32) -> Weight {
+        (9_517_000 as Weight)
+            .saturating_add((1_236_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // The vesting lock weights are estimated from their storage accesses, they must be
+    // regenerated by the `impose_lock`, `remove_lock` and `unlock_vested` benchmarks.
+    fn impose_lock() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn remove_lock() -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    fn unlock_vested() -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
 }
@@ -10,11 +10,20 @@ use sp_std::prelude::*;
 use codec::Codec;
 
 pub use xpallet_dex_spot::{
-    Depth, FullPairInfo, Handicap, OrderProperty, RpcOrder, TradingPairId, TradingPairInfo,
+    CompetitionId, Depth, FullPairInfo, FullPairInfoV2, Handicap, OrderBook, OrderProperty,
+    OrderStatus, RpcOrder, RpcOrderV5, SettlementCounters, TradingPairId, TradingPairInfo,
 };
 
 sp_api::decl_runtime_apis! {
     /// The API to query DEX Spot info.
+    ///
+    /// Version 2 adds `competition_leaderboard`.
+    /// Version 3 adds the `include_retired` flag to `trading_pairs`.
+    /// Version 4 adds `order_sequence`.
+    /// Version 5 adds `order_book`.
+    /// Version 6 adds the client order id to `RpcOrder`.
+    /// Version 7 adds `min_notional`.
+    #[api_version(7)]
     pub trait XSpotApi<AccountId, Balance, BlockNumber, Price>
     where
         AccountId: Codec,
@@ -22,13 +31,31 @@ sp_api::decl_runtime_apis! {
         BlockNumber: Codec,
         Price: Codec,
     {
-        /// Get the overall info of all trading pairs.
-        fn trading_pairs() -> Vec<FullPairInfo<Price, BlockNumber>>;
+        /// Get the overall info of all trading pairs, optionally including the retired ones.
+        fn trading_pairs(include_retired: bool) -> Vec<FullPairInfo<Price, Balance, BlockNumber>>;
+
+        #[changed_in(3)]
+        fn trading_pairs() -> Vec<FullPairInfoV2<Price, Balance, BlockNumber>>;
 
         /// Get the orders of an account.
         fn orders(who: AccountId, page_index: u32, page_size: u32) -> Vec<RpcOrder<TradingPairId, AccountId, Balance, Price, BlockNumber>>;
 
+        #[changed_in(6)]
+        fn orders(who: AccountId, page_index: u32, page_size: u32) -> Vec<RpcOrderV5<TradingPairId, AccountId, Balance, Price, BlockNumber>>;
+
         /// Get the depth of a trading pair.
         fn depth(pair_id: TradingPairId, depth_size: u32) -> Option<Depth<Price, Balance>>;
+
+        /// Get the order book of a trading pair aggregated per price level.
+        fn order_book(pair_id: TradingPairId, depth: u32) -> Option<OrderBook<Price, Balance, BlockNumber>>;
+
+        /// Get the minimum notional of a trading pair, zero means no minimum.
+        fn min_notional(pair_id: TradingPairId) -> Option<Balance>;
+
+        /// Get the accounts of a trading competition sorted by the volume.
+        fn competition_leaderboard(id: CompetitionId, page_index: u32, page_size: u32) -> Vec<(AccountId, Balance)>;
+
+        /// Get the sequence number of the order changes of an account.
+        fn order_sequence(who: AccountId) -> u64;
     }
 }
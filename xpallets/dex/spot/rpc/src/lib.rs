@@ -12,15 +12,19 @@ use codec::Codec;
 use jsonrpc_derive::rpc;
 use serde::{Deserialize, Serialize};
 
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_runtime::{generic::BlockId, traits::Block as BlockT};
 
-use xp_rpc::{runtime_error_into_rpc_err, Result, RpcBalance, RpcPrice};
+use xp_rpc::{
+    paginate, runtime_error_into_rpc_err, unsupported_api_rpc_err, Result, RpcBalance, RpcPrice,
+};
+
+pub use xp_rpc::Page;
 
 use xpallet_dex_spot_rpc_runtime_api::{
-    Depth, FullPairInfo, Handicap, OrderProperty, RpcOrder, TradingPairId, TradingPairInfo,
-    XSpotApi as XSpotRuntimeApi,
+    CompetitionId, Depth, FullPairInfo, Handicap, OrderBook, OrderProperty, OrderStatus, RpcOrder,
+    SettlementCounters, TradingPairId, TradingPairInfo, XSpotApi as XSpotRuntimeApi,
 };
 
 /// XSpot RPC methods.
@@ -31,13 +35,20 @@ where
     Price: Display + FromStr,
 {
     /// Get the overall info of all trading pairs.
+    ///
+    /// The retired trading pairs are excluded unless `include_retired` is true.
     #[rpc(name = "xspot_getTradingPairs")]
     fn trading_pairs(
         &self,
         at: Option<BlockHash>,
-    ) -> Result<Vec<FullPairInfo<RpcPrice<Price>, BlockNumber>>>;
+        include_retired: Option<bool>,
+    ) -> Result<Vec<FullPairInfo<RpcPrice<Price>, RpcBalance<Balance>, BlockNumber>>>;
 
     /// Get the orders of an account.
+    ///
+    /// The response carries the block hash it was served from, pass it back as `at`
+    /// when requesting the following pages so that the orders filled or placed in the
+    /// meantime don't shift the pages.
     #[rpc(name = "xspot_getOrdersByAccount")]
     fn orders(
         &self,
@@ -46,7 +57,8 @@ where
         page_size: u32,
         at: Option<BlockHash>,
     ) -> Result<
-        Page<
+        OrdersPage<
+            BlockHash,
             Vec<
                 RpcOrder<
                     TradingPairId,
@@ -67,6 +79,56 @@ where
         depth_size: u32,
         at: Option<BlockHash>,
     ) -> Result<Option<Depth<RpcPrice<Price>, RpcBalance<Balance>>>>;
+
+    /// Get the order book of a trading pair aggregated per price level,
+    /// the depth is capped at 200 on each side.
+    #[rpc(name = "xspot_getOrderBook")]
+    fn order_book(
+        &self,
+        pair_id: TradingPairId,
+        depth: u32,
+        at: Option<BlockHash>,
+    ) -> Result<Option<OrderBook<RpcPrice<Price>, RpcBalance<Balance>, BlockNumber>>>;
+
+    /// Get the minimum notional (price * amount, in the quote asset) of a trading pair.
+    ///
+    /// The orders below it are rejected with `NotionalTooSmall`, zero means no minimum.
+    #[rpc(name = "xspot_getMinNotional")]
+    fn min_notional(
+        &self,
+        pair_id: TradingPairId,
+        at: Option<BlockHash>,
+    ) -> Result<Option<RpcBalance<Balance>>>;
+
+    /// Get the top 100 accounts of a trading competition sorted by the volume,
+    /// the page size is capped at 100.
+    #[rpc(name = "xspot_getCompetitionLeaderboard")]
+    fn competition_leaderboard(
+        &self,
+        id: CompetitionId,
+        page_index: u32,
+        page_size: u32,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<(AccountId, RpcBalance<Balance>)>>;
+}
+
+/// A page of the orders of an account, pinned to the block it was served from.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrdersPage<BlockHash, T> {
+    #[serde(flatten)]
+    pub page: Page<T>,
+    /// The hash of the block the orders were read from.
+    pub at: BlockHash,
+    /// The number of the open orders.
+    pub total_open: u32,
+    /// The number of the closed orders included in the response.
+    pub total_closed_included: u32,
+    /// The sequence number of the order changes of the account, a different value
+    /// between two pages means the orders have moved in between.
+    ///
+    /// `None` for the blocks of the runtimes without the order sequence.
+    pub order_sequence: Option<u64>,
 }
 
 /// A struct that implements the [`XSpotApi`].
@@ -85,13 +147,61 @@ impl<C, B> XSpot<C, B> {
     }
 }
 
+impl<C, Block> XSpot<C, Block>
+where
+    Block: BlockT,
+    C: ProvideRuntimeApi<Block>,
+{
+    /// Returns true if the runtime at `at` provides an `XSpotApi` of `version` or newer.
+    fn has_api_version<AccountId, Balance, BlockNumber, Price>(
+        &self,
+        at: &BlockId<Block>,
+        version: u32,
+    ) -> Result<bool>
+    where
+        C::Api: XSpotRuntimeApi<Block, AccountId, Balance, BlockNumber, Price>,
+        AccountId: Codec,
+        Balance: Codec,
+        BlockNumber: Codec,
+        Price: Codec,
+    {
+        self.client
+            .runtime_api()
+            .has_api_with::<dyn XSpotRuntimeApi<Block, AccountId, Balance, BlockNumber, Price>, _>(
+                at,
+                |v| v >= version,
+            )
+            .map_err(runtime_error_into_rpc_err)
+    }
+
+    /// Returns an error if the runtime at `at` provides an `XSpotApi` older than `version`.
+    fn ensure_api_version<AccountId, Balance, BlockNumber, Price>(
+        &self,
+        at: &BlockId<Block>,
+        version: u32,
+    ) -> Result<()>
+    where
+        C::Api: XSpotRuntimeApi<Block, AccountId, Balance, BlockNumber, Price>,
+        AccountId: Codec,
+        Balance: Codec,
+        BlockNumber: Codec,
+        Price: Codec,
+    {
+        if self.has_api_version::<AccountId, Balance, BlockNumber, Price>(at, version)? {
+            Ok(())
+        } else {
+            Err(unsupported_api_rpc_err("XSpotApi", version))
+        }
+    }
+}
+
 impl<C, Block, AccountId, Balance, BlockNumber, Price>
     XSpotApi<<Block as BlockT>::Hash, AccountId, Balance, BlockNumber, Price> for XSpot<C, Block>
 where
     Block: BlockT,
     C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
     C::Api: XSpotRuntimeApi<Block, AccountId, Balance, BlockNumber, Price>,
-    AccountId: Codec,
+    AccountId: Codec + Clone,
     Balance: Codec + Display + FromStr,
     BlockNumber: Codec,
     Price: Codec + Display + FromStr,
@@ -99,28 +209,52 @@ where
     fn trading_pairs(
         &self,
         at: Option<<Block as BlockT>::Hash>,
-    ) -> Result<Vec<FullPairInfo<RpcPrice<Price>, BlockNumber>>> {
+        include_retired: Option<bool>,
+    ) -> Result<Vec<FullPairInfo<RpcPrice<Price>, RpcBalance<Balance>, BlockNumber>>> {
         let api = self.client.runtime_api();
         let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
-        api.trading_pairs(&at)
+        let trading_pairs =
+            if self.has_api_version::<AccountId, Balance, BlockNumber, Price>(&at, 3)? {
+                api.trading_pairs(&at, include_retired.unwrap_or(false))
+            } else {
+                // No trading pair could be retired before version 3.
+                #[allow(deprecated)]
+                let trading_pairs = api.trading_pairs_before_version_3(&at);
+                trading_pairs.map(|trading_pairs| {
+                    trading_pairs
+                        .into_iter()
+                        .map(Into::into)
+                        .collect::<Vec<_>>()
+                })
+            };
+        trading_pairs
             .map(|trading_pairs| {
                 trading_pairs
                     .into_iter()
-                    .map(
-                        |trading_pairs| FullPairInfo::<RpcPrice<Price>, BlockNumber> {
-                            profile: trading_pairs.profile,
-                            handicap: Handicap {
-                                highest_bid: trading_pairs.handicap.highest_bid.into(),
-                                lowest_ask: trading_pairs.handicap.lowest_ask.into(),
-                            },
-                            pair_info: TradingPairInfo {
-                                latest_price: trading_pairs.pair_info.latest_price.into(),
-                                last_updated: trading_pairs.pair_info.last_updated,
-                            },
-                            max_valid_bid: trading_pairs.max_valid_bid.into(),
-                            min_valid_ask: trading_pairs.min_valid_ask.into(),
+                    .map(|trading_pairs| FullPairInfo::<
+                        RpcPrice<Price>,
+                        RpcBalance<Balance>,
+                        BlockNumber,
+                    > {
+                        profile: trading_pairs.profile,
+                        handicap: Handicap {
+                            highest_bid: trading_pairs.handicap.highest_bid.into(),
+                            lowest_ask: trading_pairs.handicap.lowest_ask.into(),
+                        },
+                        pair_info: TradingPairInfo {
+                            latest_price: trading_pairs.pair_info.latest_price.into(),
+                            last_updated: trading_pairs.pair_info.last_updated,
+                        },
+                        max_valid_bid: trading_pairs.max_valid_bid.into(),
+                        min_valid_ask: trading_pairs.min_valid_ask.into(),
+                        settlement: SettlementCounters {
+                            base_debited: trading_pairs.settlement.base_debited.into(),
+                            base_credited: trading_pairs.settlement.base_credited.into(),
+                            quote_debited: trading_pairs.settlement.quote_debited.into(),
+                            quote_credited: trading_pairs.settlement.quote_credited.into(),
                         },
-                    )
+                        delist: trading_pairs.delist,
+                    })
                     .collect::<Vec<_>>()
             })
             .map_err(runtime_error_into_rpc_err)
@@ -133,7 +267,8 @@ where
         page_size: u32,
         at: Option<<Block as BlockT>::Hash>,
     ) -> Result<
-        Page<
+        OrdersPage<
+            <Block as BlockT>::Hash,
             Vec<
                 RpcOrder<
                     TradingPairId,
@@ -146,9 +281,27 @@ where
         >,
     > {
         let api = self.client.runtime_api();
-        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
-        let data = api
-            .orders(&at, who, page_index, page_size)
+        let hash = at.unwrap_or_else(|| self.client.info().best_hash);
+        let at = BlockId::hash(hash);
+        let order_sequence =
+            if self.has_api_version::<AccountId, Balance, BlockNumber, Price>(&at, 4)? {
+                let sequence = api
+                    .order_sequence(&at, who.clone())
+                    .map_err(runtime_error_into_rpc_err)?;
+                Some(sequence)
+            } else {
+                None
+            };
+        // Fetch all the orders sorted by the order index in descending order, and then
+        // paginate them here so that the page total can be reported.
+        let orders = if self.has_api_version::<AccountId, Balance, BlockNumber, Price>(&at, 6)? {
+            api.orders(&at, who, 0, u32::MAX)
+        } else {
+            #[allow(deprecated)]
+            let orders = api.orders_before_version_6(&at, who, 0, u32::MAX);
+            orders.map(|orders| orders.into_iter().map(Into::into).collect::<Vec<_>>())
+        };
+        let orders = orders
             .map(|orders| {
                 orders
                     .into_iter()
@@ -169,14 +322,29 @@ where
                         already_filled: order.already_filled.into(),
                         reserved_balance: order.reserved_balance.into(),
                         last_update_at: order.last_update_at,
+                        client_order_id: order.client_order_id,
                     })
                     .collect::<Vec<_>>()
             })
             .map_err(runtime_error_into_rpc_err)?;
-        Ok(Page {
-            page_index,
-            page_size,
-            data,
+        let total_closed_included = orders
+            .iter()
+            .filter(|order| {
+                matches!(
+                    order.status,
+                    OrderStatus::Filled
+                        | OrderStatus::Canceled
+                        | OrderStatus::PartialFillAndCanceled
+                )
+            })
+            .count() as u32;
+        let total_open = orders.len() as u32 - total_closed_included;
+        Ok(OrdersPage {
+            page: paginate(orders, page_index, page_size),
+            at: hash,
+            total_open,
+            total_closed_included,
+            order_sequence,
         })
     }
 
@@ -206,12 +374,66 @@ where
             Err(err) => Err(runtime_error_into_rpc_err(err)),
         }
     }
-}
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Page<T> {
-    pub page_index: u32,
-    pub page_size: u32,
-    pub data: T,
+    fn order_book(
+        &self,
+        pair_id: TradingPairId,
+        depth: u32,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<OrderBook<RpcPrice<Price>, RpcBalance<Balance>, BlockNumber>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        self.ensure_api_version::<AccountId, Balance, BlockNumber, Price>(&at, 5)?;
+        api.order_book(&at, pair_id, depth)
+            .map(|order_book| {
+                order_book.map(|order_book| {
+                    let convert = |levels: Vec<(Price, Balance, u32)>| {
+                        levels
+                            .into_iter()
+                            .map(|(price, quantity, count)| (price.into(), quantity.into(), count))
+                            .collect::<Vec<_>>()
+                    };
+                    OrderBook {
+                        asks: convert(order_book.asks),
+                        bids: convert(order_book.bids),
+                        last_price: order_book.last_price.into(),
+                        at_block: order_book.at_block,
+                    }
+                })
+            })
+            .map_err(runtime_error_into_rpc_err)
+    }
+
+    fn min_notional(
+        &self,
+        pair_id: TradingPairId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<RpcBalance<Balance>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        self.ensure_api_version::<AccountId, Balance, BlockNumber, Price>(&at, 7)?;
+        api.min_notional(&at, pair_id)
+            .map(|min_notional| min_notional.map(Into::into))
+            .map_err(runtime_error_into_rpc_err)
+    }
+
+    fn competition_leaderboard(
+        &self,
+        id: CompetitionId,
+        page_index: u32,
+        page_size: u32,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<(AccountId, RpcBalance<Balance>)>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        self.ensure_api_version::<AccountId, Balance, BlockNumber, Price>(&at, 2)?;
+        api.competition_leaderboard(&at, id, page_index, page_size)
+            .map(|leaderboard| {
+                leaderboard
+                    .into_iter()
+                    .map(|(who, volume)| (who, volume.into()))
+                    .collect()
+            })
+            .map_err(runtime_error_into_rpc_err)
+    }
 }
@@ -38,6 +38,10 @@ fn b_put_order<T: Config>(
         Side::Buy,
         pcx_value.into(),
         price.into(),
+        None,
+        None,
+        false,
+        false,
     )?;
     Ok(())
 }
@@ -49,7 +53,7 @@ benchmarks! {
 
         b_prepare_put_order::<T>(&user, 1000, 100)?;
 
-    }: put_order(RawOrigin::Signed(user.clone()), PAIR_ID, OrderType::Limit, Side::Buy, 1000u32.into(), 1_000_200u32.into())
+    }: put_order(RawOrigin::Signed(user.clone()), PAIR_ID, OrderType::Limit, Side::Buy, 1000u32.into(), 1_000_200u32.into(), None, Some(b"client-0".to_vec()), false, false)
     verify {
         assert!(OrderInfoOf::<T>::get(user, 0).is_some());
     }
@@ -59,11 +63,34 @@ benchmarks! {
 
         b_put_order::<T>(user.clone(), 1000, 100, 1_000_200)?;
 
-    }: _(RawOrigin::Signed(user.clone()), PAIR_ID, 0)
+    }: _(RawOrigin::Signed(user.clone()), PAIR_ID, 0, None)
     verify {
         assert!(OrderInfoOf::<T>::get(user, 0).is_none());
     }
 
+    cancel_by_client_id {
+        let user: T::AccountId = account("user", 0, SEED);
+
+        b_prepare_put_order::<T>(&user, 1000, 100)?;
+        Pallet::<T>::put_order(
+            RawOrigin::Signed(user.clone()).into(),
+            PAIR_ID,
+            OrderType::Limit,
+            Side::Buy,
+            1000u32.into(),
+            1_000_200u32.into(),
+            None,
+            Some(b"client-0".to_vec()),
+            false,
+            false,
+        )?;
+
+    }: _(RawOrigin::Signed(user.clone()), PAIR_ID, b"client-0".to_vec(), None)
+    verify {
+        assert!(OrderInfoOf::<T>::get(&user, 0).is_none());
+        assert!(OpenOrderOfClientId::<T>::get(user, b"client-0".to_vec()).is_none());
+    }
+
     force_cancel_order {
         let user: T::AccountId = account("user", 0, SEED);
 
@@ -76,6 +103,22 @@ benchmarks! {
         assert!(OrderInfoOf::<T>::get(user, 0).is_none());
     }
 
+    force_cancel_pair_orders {
+        let n in 1 .. 100;
+
+        for i in 0..n {
+            let user: T::AccountId = account("user", i, SEED);
+            b_put_order::<T>(user, 1000, 100, 1_000_200)?;
+        }
+
+    }: _(RawOrigin::Root, PAIR_ID, n)
+    verify {
+        for i in 0..n {
+            let user: T::AccountId = account("user", i, SEED);
+            assert!(OrderInfoOf::<T>::get(user, 0).is_none());
+        }
+    }
+
     set_handicap {
     }: _(RawOrigin::Root, PAIR_ID, Handicap::new(100u32.into(), 110u32.into()))
     verify {
@@ -90,7 +133,7 @@ benchmarks! {
 
     add_trading_pair {
         let pair = CurrencyPair::new(EOS, ETH);
-    }: _(RawOrigin::Root, pair.clone(), 2, 1, 100u32.into(), true)
+    }: _(RawOrigin::Root, pair.clone(), 2, 1, 100u32.into(), true, 10u32.into())
     verify {
         #[cfg(test)]
         assert_eq!(Pallet::<T>::trading_pair_count(), 3);
@@ -106,12 +149,82 @@ benchmarks! {
 
     update_trading_pair {
         let pair = CurrencyPair::new(EOS, ETH);
-        Pallet::<T>::add_trading_pair(RawOrigin::Root.into(), pair, 2, 1, 100u32.into(), true)?;
+        Pallet::<T>::add_trading_pair(RawOrigin::Root.into(), pair, 2, 1, 100u32.into(), true, Zero::zero())?;
     }: _(RawOrigin::Root, PAIR_ID, 888, false)
     verify {
         assert_eq!(Pallet::<T>::trading_pair_of(PAIR_ID).unwrap().tick_decimals, 888);
         assert!(!Pallet::<T>::trading_pair_of(PAIR_ID).unwrap().tradable);
     }
+
+    set_min_notional {
+    }: _(RawOrigin::Root, PAIR_ID, 1000u32.into())
+    verify {
+        assert_eq!(MinNotionalOf::<T>::get(PAIR_ID), 1000u32.into());
+    }
+
+    set_trading_operator {
+        let user: T::AccountId = account("user", 0, SEED);
+        let operator: T::AccountId = account("operator", 0, SEED);
+    }: _(RawOrigin::Signed(user.clone()), Some(operator.clone()))
+    verify {
+        assert_eq!(TradingOperatorOf::<T>::get(user), Some(operator));
+    }
+
+    set_circuit_breaker {
+        let breaker = CircuitBreaker {
+            max_move_per_block_percent: 10,
+            halt_duration_blocks: 10u32.into(),
+        };
+    }: _(RawOrigin::Root, PAIR_ID, Some(breaker))
+    verify {
+        assert_eq!(CircuitBreakerOf::<T>::get(PAIR_ID), Some(breaker));
+    }
+
+    reduce_order {
+        let user: T::AccountId = account("reducer", 0, SEED);
+
+        b_put_order::<T>(user.clone(), 2_000_000_000, 10_000, 1_000_200)?;
+
+    }: _(RawOrigin::Signed(user.clone()), PAIR_ID, 0, 1_000_000_000u32.into())
+    verify {
+        let order = OrderInfoOf::<T>::get(user, 0).unwrap();
+        assert_eq!(order.amount(), 1_000_000_000u32.into());
+    }
+
+    start_competition {
+    }: _(RawOrigin::Root, PAIR_ID, 0u32.into(), 100u32.into(), true)
+    verify {
+        assert!(Competitions::<T>::get(0).is_some());
+    }
+
+    prune_competition {
+        let n in 1 .. 1000;
+
+        Competitions::<T>::insert(0, Competition {
+            pair_id: PAIR_ID,
+            start_block: 0u32.into(),
+            end_block: 1u32.into(),
+            count_maker: true,
+            ended: true,
+        });
+        for i in 0..n {
+            let user: T::AccountId = account("trader", i, SEED);
+            CompetitionVolume::<T>::insert(0, user, BalanceOf::<T>::from(1u32));
+        }
+    }: _(RawOrigin::Root, 0, n)
+    verify {
+        assert!(Competitions::<T>::get(0).is_none());
+    }
+
+    schedule_pair_delist {
+        let effective_block: T::BlockNumber = 100u32.into();
+    }: _(RawOrigin::Root, PAIR_ID, effective_block)
+    verify {
+        assert_eq!(
+            DelistStatusOf::<T>::get(PAIR_ID),
+            Some(DelistStatus::Scheduled(effective_block))
+        );
+    }
 }
 
 #[cfg(test)]
@@ -138,11 +251,20 @@ mod tests {
 
             assert_ok!(Pallet::<Test>::test_benchmark_put_order());
             assert_ok!(Pallet::<Test>::test_benchmark_cancel_order());
+            assert_ok!(Pallet::<Test>::test_benchmark_cancel_by_client_id());
             assert_ok!(Pallet::<Test>::test_benchmark_force_cancel_order());
+            assert_ok!(Pallet::<Test>::test_benchmark_force_cancel_pair_orders());
             assert_ok!(Pallet::<Test>::test_benchmark_set_handicap());
             assert_ok!(Pallet::<Test>::test_benchmark_set_price_fluctuation());
             assert_ok!(Pallet::<Test>::test_benchmark_add_trading_pair());
             assert_ok!(Pallet::<Test>::test_benchmark_update_trading_pair());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_min_notional());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_trading_operator());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_circuit_breaker());
+            assert_ok!(Pallet::<Test>::test_benchmark_reduce_order());
+            assert_ok!(Pallet::<Test>::test_benchmark_start_competition());
+            assert_ok!(Pallet::<Test>::test_benchmark_prune_competition());
+            assert_ok!(Pallet::<Test>::test_benchmark_schedule_pair_delist());
         });
     }
 }
@@ -3,7 +3,11 @@
 //! This module handles all the asset related operations in Spot.
 
 use super::*;
-use xpallet_assets::AssetType::{self, ReservedDexSpot, Usable};
+use sp_runtime::traits::Saturating;
+use xpallet_assets::{
+    AssetType::{self, ReservedDexSpot, Usable},
+    MoveCaller,
+};
 
 impl<T: Config> Pallet<T> {
     /// Delivery the assets to maker and taker respectively when executing the order.
@@ -31,8 +35,8 @@ impl<T: Config> Pallet<T> {
                 let maker_turnover_amount = turnover;
                 let taker_turnover_amount = turnover_in_quote;
 
-                Self::apply_delivery(base, maker_turnover_amount, maker, taker)?;
-                Self::apply_delivery(quote, taker_turnover_amount, taker, maker)?;
+                Self::apply_delivery(pair, base, maker_turnover_amount, maker, taker)?;
+                Self::apply_delivery(pair, quote, taker_turnover_amount, taker, maker)?;
 
                 Ok((maker_turnover_amount, taker_turnover_amount))
             }
@@ -42,8 +46,8 @@ impl<T: Config> Pallet<T> {
                 let maker_turnover_amount = turnover_in_quote;
                 let taker_turnover_amount = turnover;
 
-                Self::apply_delivery(base, taker_turnover_amount, taker, maker)?;
-                Self::apply_delivery(quote, maker_turnover_amount, maker, taker)?;
+                Self::apply_delivery(pair, base, taker_turnover_amount, taker, maker)?;
+                Self::apply_delivery(pair, quote, maker_turnover_amount, maker, taker)?;
 
                 Ok((maker_turnover_amount, taker_turnover_amount))
             }
@@ -57,17 +61,50 @@ impl<T: Config> Pallet<T> {
     }
 
     /// Move the locked balance in Spot of account `from` to another account's Free.
-    #[inline]
+    ///
+    /// The actual balance changes of both sides are recorded in the settlement counters.
     fn apply_delivery(
+        pair: &TradingPairProfile,
         asset_id: AssetId,
         value: BalanceOf<T>,
         from: &T::AccountId,
         to: &T::AccountId,
     ) -> DispatchResult {
+        let (from_before, to_before) = Self::settlement_balances(asset_id, from, to);
+
         if Self::is_native_asset(asset_id) {
-            Self::transfer_native_asset(from, to, value)
+            Self::transfer_native_asset(from, to, value)?;
         } else {
-            Self::move_foreign_asset(asset_id, from, ReservedDexSpot, to, Usable, value)
+            Self::move_foreign_asset(asset_id, from, ReservedDexSpot, to, Usable, value)?;
+        }
+
+        let (from_after, to_after) = Self::settlement_balances(asset_id, from, to);
+        Self::note_settlement(
+            pair,
+            asset_id,
+            from_before.saturating_sub(from_after),
+            to_after.saturating_sub(to_before),
+        );
+
+        Ok(())
+    }
+
+    /// Returns the tuple of (reserved balance in Spot of `from`, free balance of `to`).
+    fn settlement_balances(
+        asset_id: AssetId,
+        from: &T::AccountId,
+        to: &T::AccountId,
+    ) -> (BalanceOf<T>, BalanceOf<T>) {
+        if Self::is_native_asset(asset_id) {
+            (
+                <T as xpallet_assets::Config>::Currency::reserved_balance(from),
+                <T as xpallet_assets::Config>::Currency::free_balance(to),
+            )
+        } else {
+            (
+                <xpallet_assets::Pallet<T>>::asset_balance_of(from, &asset_id, ReservedDexSpot),
+                <xpallet_assets::Pallet<T>>::usable_balance(to, &asset_id),
+            )
         }
     }
 
@@ -92,14 +129,14 @@ impl<T: Config> Pallet<T> {
         asset_id: AssetId,
         value: BalanceOf<T>,
     ) -> DispatchResult {
+        ensure!(
+            <xpallet_assets::Pallet<T>>::spendable_balance(who, &asset_id) >= value,
+            Error::<T>::InsufficientBalance
+        );
         if Self::is_native_asset(asset_id) {
             <T as xpallet_assets::Config>::Currency::reserve(who, value)?;
             NativeReserves::<T>::mutate(who, |reserved| *reserved += value);
         } else {
-            ensure!(
-                <xpallet_assets::Pallet<T>>::usable_balance(who, &asset_id) >= value,
-                Error::<T>::InsufficientBalance
-            );
             Self::move_foreign_asset(asset_id, who, Usable, who, ReservedDexSpot, value)?;
         }
         Ok(())
@@ -141,7 +178,8 @@ impl<T: Config> Pallet<T> {
         asset_id: AssetId,
         value: BalanceOf<T>,
     ) -> DispatchResult {
-        Self::move_foreign_asset(asset_id, who, ReservedDexSpot, who, Usable, value)
+        <xpallet_assets::Pallet<T>>::unreserve_dex_spot(&asset_id, who, value)
+            .map_err(|_| DispatchError::Other("Unexpected error from assets Pallet"))
     }
 
     /// Wrap the move_balance function in xassets module.
@@ -153,7 +191,15 @@ impl<T: Config> Pallet<T> {
         to_ty: AssetType,
         value: BalanceOf<T>,
     ) -> DispatchResult {
-        <xpallet_assets::Pallet<T>>::move_balance(&asset_id, from, from_ty, to, to_ty, value)
-            .map_err(|_| DispatchError::Other("Unexpected error from assets Pallet"))
+        <xpallet_assets::Pallet<T>>::move_balance(
+            &asset_id,
+            from,
+            from_ty,
+            to,
+            to_ty,
+            value,
+            MoveCaller::DexSpot,
+        )
+        .map_err(|_| DispatchError::Other("Unexpected error from assets Pallet"))
     }
 }
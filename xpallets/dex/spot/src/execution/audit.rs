@@ -0,0 +1,76 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! This module keeps track of the settlement counters of each trading pair,
+//! which are used to detect the imbalance bugs in the asset delivery.
+
+use super::*;
+use frame_support::log::error;
+use sp_runtime::traits::Saturating;
+
+impl<T: Config> Pallet<T> {
+    /// Records the actual debit and credit of `asset_id` on settlement.
+    pub(super) fn note_settlement(
+        pair: &TradingPairProfile,
+        asset_id: AssetId,
+        debited: BalanceOf<T>,
+        credited: BalanceOf<T>,
+    ) {
+        SettlementCountersOf::<T>::mutate(pair.id, |counters| {
+            if asset_id == pair.base() {
+                counters.base_debited = counters.base_debited.saturating_add(debited);
+                counters.base_credited = counters.base_credited.saturating_add(credited);
+            } else {
+                counters.quote_debited = counters.quote_debited.saturating_add(debited);
+                counters.quote_credited = counters.quote_credited.saturating_add(credited);
+            }
+        });
+    }
+
+    /// Checks whether the settlement counters of `pair_id` are still balanced.
+    ///
+    /// On imbalance, `SettlementImbalance` will be emitted and the trading pair
+    /// will be switched offline if `AutoPauseOnImbalance` is enabled.
+    #[cfg(any(feature = "settlement-audit", test))]
+    pub(crate) fn assert_settlement_balanced(pair_id: TradingPairId) {
+        let counters = Self::settlement_counters_of(pair_id);
+        if counters.is_balanced() {
+            return;
+        }
+
+        let base_delta = counters.base_delta();
+        let quote_delta = counters.quote_delta();
+        error!(
+            target: "runtime::dex::spot",
+            "[assert_settlement_balanced] Settlement imbalance, pair_id: {:?}, base_delta: {:?}, quote_delta: {:?}",
+            pair_id, base_delta, quote_delta
+        );
+        Self::deposit_event(Event::<T>::SettlementImbalance(
+            pair_id,
+            base_delta,
+            quote_delta,
+        ));
+
+        if T::AutoPauseOnImbalance::get() {
+            if let Some(mut pair) = Self::trading_pair_of(pair_id) {
+                if pair.tradable {
+                    pair.tradable = false;
+                    TradingPairOf::<T>::insert(pair_id, &pair);
+                    Self::deposit_event(Event::<T>::TradingPairUpdated(pair));
+                }
+            }
+        }
+    }
+
+    /// Test-only hook for injecting an artificial drift into the settlement counters.
+    #[cfg(test)]
+    pub(crate) fn inject_settlement_imbalance(
+        pair_id: TradingPairId,
+        base_drift: BalanceOf<T>,
+        quote_drift: BalanceOf<T>,
+    ) {
+        SettlementCountersOf::<T>::mutate(pair_id, |counters| {
+            counters.base_credited = counters.base_credited.saturating_add(base_drift);
+            counters.quote_credited = counters.quote_credited.saturating_add(quote_drift);
+        });
+    }
+}
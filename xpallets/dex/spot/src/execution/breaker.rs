@@ -0,0 +1,76 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! This module takes care of the circuit breaker of each trading pair, which halts
+//! the matching after an extreme price move within a single block.
+
+use super::*;
+use sp_runtime::traits::Saturating;
+
+impl<T: Config> Pallet<T> {
+    /// Returns true if the matching of `pair_id` is halted at the moment.
+    pub(crate) fn is_matching_halted(pair_id: TradingPairId) -> bool {
+        Self::halted_until_of(pair_id).map_or(false, |resume_block| {
+            <frame_system::Pallet<T>>::block_number() < resume_block
+        })
+    }
+
+    /// Ensures the new order does not cross the book while the matching is halted.
+    ///
+    /// The orders that would be matched can not rest on the book, the rest of them
+    /// are accepted as usual.
+    pub(crate) fn ensure_no_cross_while_halted(
+        pair_id: TradingPairId,
+        price: T::Price,
+        side: Side,
+    ) -> Result<(), Error<T>> {
+        if !Self::is_matching_halted(pair_id) {
+            return Ok(());
+        }
+
+        ensure!(
+            !Self::crosses_handicap(pair_id, price, side),
+            Error::<T>::TradingPairHalted
+        );
+
+        Ok(())
+    }
+
+    /// Returns false if a fill at `price` trips the circuit breaker of the trading pair.
+    ///
+    /// The first fill of each block sets the reference price, any subsequent fill in
+    /// the same block beyond the configured band halts the matching of the trading pair
+    /// until `now + halt_duration_blocks`.
+    pub(super) fn check_circuit_breaker(pair_id: TradingPairId, price: T::Price) -> bool {
+        let breaker = match Self::circuit_breaker_of(pair_id) {
+            Some(breaker) => breaker,
+            None => return true,
+        };
+
+        let now = <frame_system::Pallet<T>>::block_number();
+        match Self::block_opening_price_of(pair_id) {
+            Some((block, opening_price)) if block == now => {
+                if breaker.is_within_band(opening_price, price) {
+                    return true;
+                }
+
+                let resume_block = now.saturating_add(breaker.halt_duration_blocks);
+                debug!(
+                    target: "runtime::dex::spot",
+                    "[check_circuit_breaker] pair_id: {:?}, opening_price: {:?}, trigger_price: {:?}, resume_block: {:?}",
+                    pair_id, opening_price, price, resume_block
+                );
+                HaltedUntilOf::<T>::insert(pair_id, resume_block);
+                Self::deposit_event(Event::<T>::CircuitBreakerTripped(
+                    pair_id,
+                    price,
+                    resume_block,
+                ));
+                false
+            }
+            _ => {
+                BlockOpeningPriceOf::<T>::insert(pair_id, (now, price));
+                true
+            }
+        }
+    }
+}
@@ -0,0 +1,101 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! This module counts the trading volume of the accounts during the trading
+//! competitions, which are closed automatically at the end block.
+//!
+//! The top `MAX_LEADERBOARD_SIZE` accounts of each competition are maintained on
+//! every fill, so that the leaderboard is read without iterating all the counters.
+
+use super::*;
+use frame_support::weights::DispatchClass;
+use sp_runtime::traits::Saturating;
+
+impl<T: Config> Pallet<T> {
+    /// Adds the quote notional of a fill to the volume of the maker and taker in every
+    /// running competition of the trading pair.
+    ///
+    /// The volume of a self-trade is counted once. The storage accesses are registered
+    /// to the block weight since the number of fills of an order is not known in advance.
+    pub(super) fn note_competition_volume(
+        pair: &TradingPairProfile,
+        maker: &T::AccountId,
+        taker: &T::AccountId,
+        turnover: BalanceOf<T>,
+        price: T::Price,
+    ) {
+        let open_competitions = Self::open_competitions_of(pair.id);
+        if open_competitions.is_empty() {
+            return;
+        }
+
+        let now = <frame_system::Pallet<T>>::block_number();
+        let notional = Self::notional_of(turnover, price, pair);
+        if notional.is_zero() {
+            return;
+        }
+
+        let mut reads: Weight = 0;
+        let mut writes: Weight = 0;
+        for id in open_competitions {
+            reads += 1;
+            if let Some(competition) = Self::competitions(id) {
+                if competition.start_block <= now && now < competition.end_block {
+                    Self::add_competition_volume(id, taker, notional);
+                    reads += 2;
+                    writes += 2;
+                    if competition.count_maker && maker != taker {
+                        Self::add_competition_volume(id, maker, notional);
+                        reads += 2;
+                        writes += 2;
+                    }
+                }
+            }
+        }
+
+        <frame_system::Pallet<T>>::register_extra_weight_unchecked(
+            T::DbWeight::get().reads_writes(reads, writes),
+            DispatchClass::Normal,
+        );
+    }
+
+    pub(crate) fn add_competition_volume(
+        id: CompetitionId,
+        who: &T::AccountId,
+        notional: BalanceOf<T>,
+    ) {
+        let volume = CompetitionVolume::<T>::mutate(id, who, |volume| {
+            *volume = volume.saturating_add(notional);
+            *volume
+        });
+
+        CompetitionLeaderboard::<T>::mutate(id, |leaderboard| {
+            leaderboard.retain(|(account, _)| account != who);
+            let index = leaderboard
+                .iter()
+                .position(|(account, v)| *v < volume || (*v == volume && account > who))
+                .unwrap_or(leaderboard.len());
+            if index < MAX_LEADERBOARD_SIZE {
+                leaderboard.insert(index, (who.clone(), volume));
+                leaderboard.truncate(MAX_LEADERBOARD_SIZE);
+            }
+        });
+    }
+
+    /// Closes the competitions ending at block `now`.
+    pub(crate) fn close_ended_competitions(now: T::BlockNumber) -> Weight {
+        let ended = CompetitionEndings::<T>::take(now);
+        for id in &ended {
+            if let Some(mut competition) = Self::competitions(id) {
+                competition.ended = true;
+                Competitions::<T>::insert(id, competition);
+                OpenCompetitionsOf::<T>::mutate(competition.pair_id, |open| {
+                    open.retain(|open_id| open_id != id)
+                });
+                Self::deposit_event(Event::<T>::CompetitionEnded(*id));
+            }
+        }
+
+        let count = ended.len() as Weight;
+        T::DbWeight::get().reads_writes(1 + 2 * count, 1 + 2 * count)
+    }
+}
@@ -0,0 +1,156 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! This module delists the trading pairs in stages: the new orders are rejected
+//! once the delisting is scheduled, the matching stops at the effective block,
+//! then the open orders are canceled over the subsequent blocks and finally the
+//! trading pair is retired.
+
+use frame_support::{log::error, transactional};
+
+use super::*;
+
+impl<T: Config> Pallet<T> {
+    /// Schedules the delisting of the trading pair at `effective_block`, a scheduled
+    /// delisting is rescheduled.
+    pub(crate) fn apply_schedule_pair_delist(
+        pair_id: TradingPairId,
+        effective_block: T::BlockNumber,
+    ) -> DispatchResult {
+        Self::trading_pair(pair_id)?;
+        ensure!(
+            matches!(
+                Self::delist_status_of(pair_id),
+                None | Some(DelistStatus::Scheduled(_))
+            ),
+            Error::<T>::TradingPairDelisted
+        );
+        ensure!(
+            effective_block > <frame_system::Pallet<T>>::block_number(),
+            Error::<T>::InvalidDelistBlock
+        );
+
+        DelistStatusOf::<T>::insert(pair_id, DelistStatus::Scheduled(effective_block));
+        DelistSchedule::<T>::append(effective_block, pair_id);
+        Self::deposit_event(Event::<T>::TradingPairDelistScheduled(
+            pair_id,
+            effective_block,
+        ));
+        Ok(())
+    }
+
+    /// Rejects the new orders of a trading pair being delisted.
+    pub(super) fn ensure_not_delisting(pair_id: TradingPairId) -> Result<(), Error<T>> {
+        match Self::delist_status_of(pair_id) {
+            None => Ok(()),
+            Some(DelistStatus::Scheduled(effective_block)) => {
+                info!(
+                    target: "runtime::dex::spot",
+                    "[ensure_not_delisting] pair_id:{} is delisted at block {:?}",
+                    pair_id, effective_block
+                );
+                Err(Error::<T>::TradingPairDelisting)
+            }
+            Some(_) => Err(Error::<T>::TradingPairUntradable),
+        }
+    }
+
+    /// Returns true if the open orders of the trading pair are being canceled.
+    pub(crate) fn is_closing(pair_id: TradingPairId) -> bool {
+        Self::delist_status_of(pair_id) == Some(DelistStatus::Closing)
+    }
+
+    /// Cancels at most `limit` open orders of the trading pair, returns the number of
+    /// the canceled orders and whether the order book has been cleared.
+    ///
+    /// Nothing is canceled if any of the orders fails to be canceled.
+    #[transactional]
+    pub(crate) fn cancel_pair_orders(
+        pair_id: TradingPairId,
+        limit: u32,
+    ) -> Result<(u32, bool), DispatchError> {
+        // Fetch one more order to see whether the order book will be cleared.
+        let orders = QuotationsOf::<T>::iter_prefix_values(pair_id)
+            .flatten()
+            .take((limit as usize).saturating_add(1))
+            .collect::<Vec<_>>();
+        let book_cleared = orders.len() <= limit as usize;
+
+        let mut canceled = 0u32;
+        for (who, order_id) in orders.into_iter().take(limit as usize) {
+            Self::apply_cancel_order(&who, pair_id, order_id)?;
+            canceled += 1;
+        }
+
+        Self::deposit_event(Event::<T>::PairOrdersForceCanceled(
+            pair_id,
+            canceled,
+            book_cleared,
+        ));
+        Ok((canceled, book_cleared))
+    }
+
+    /// Stops the matching of the trading pairs delisted at block `now`, then cancels
+    /// at most `MAX_DELIST_CANCELS_PER_BLOCK` open orders of the closing trading pairs.
+    pub(crate) fn process_delisting(now: T::BlockNumber) -> Weight {
+        let mut weight = T::DbWeight::get().reads_writes(2, 1);
+
+        for pair_id in DelistSchedule::<T>::take(now) {
+            weight = weight.saturating_add(T::DbWeight::get().reads_writes(2, 3));
+            // Skip the outdated entries of the rescheduled delisting.
+            if Self::delist_status_of(pair_id) != Some(DelistStatus::Scheduled(now)) {
+                continue;
+            }
+            DelistStatusOf::<T>::insert(pair_id, DelistStatus::Closing);
+            TradingPairOf::<T>::mutate(pair_id, |pair| {
+                if let Some(pair) = pair {
+                    pair.tradable = false;
+                }
+            });
+            ClosingPairs::<T>::append(pair_id);
+            Self::deposit_event(Event::<T>::TradingPairDelistEffective(pair_id));
+        }
+
+        let closing = Self::closing_pairs();
+        if closing.is_empty() {
+            return weight;
+        }
+
+        let mut budget = MAX_DELIST_CANCELS_PER_BLOCK;
+        let mut still_closing = Vec::with_capacity(closing.len());
+        for pair_id in closing {
+            if budget == 0 {
+                still_closing.push(pair_id);
+                continue;
+            }
+            match Self::cancel_pair_orders(pair_id, budget) {
+                Ok((canceled, book_cleared)) => {
+                    budget -= canceled;
+                    weight = weight.saturating_add(
+                        <T as Config>::WeightInfo::force_cancel_pair_orders(canceled),
+                    );
+                    if book_cleared {
+                        DelistStatusOf::<T>::insert(pair_id, DelistStatus::Retired);
+                        Self::deposit_event(Event::<T>::TradingPairRetired(pair_id));
+                    } else {
+                        still_closing.push(pair_id);
+                    }
+                }
+                Err(err) => {
+                    // The cancellations are reverted, the trading pair is retried in the
+                    // next block, or cleared by `force_cancel_pair_orders` in the meantime.
+                    error!(
+                        target: "runtime::dex::spot",
+                        "[process_delisting] Failed to cancel the orders of pair_id:{}: {:?}",
+                        pair_id, err
+                    );
+                    weight = weight.saturating_add(
+                        <T as Config>::WeightInfo::force_cancel_pair_orders(budget),
+                    );
+                    still_closing.push(pair_id);
+                }
+            }
+        }
+        ClosingPairs::<T>::put(still_closing);
+        weight
+    }
+}
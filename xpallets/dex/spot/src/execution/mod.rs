@@ -1,7 +1,13 @@
 // Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
 
 mod asset;
+mod audit;
+mod breaker;
+mod competition;
+mod delisting;
 mod order;
+#[cfg(debug_assertions)]
+mod priority;
 mod state;
 
 use frame_support::log::debug;
@@ -77,6 +83,41 @@ impl<T: Config> Pallet<T> {
         }
     }
 
+    /// Returns true if an order at `price` and `side` would be matched against the opposite
+    /// side of the handicap.
+    pub(crate) fn crosses_handicap(pair_id: TradingPairId, price: T::Price, side: Side) -> bool {
+        let handicap = <HandicapOf<T>>::get(pair_id);
+        match side {
+            Side::Buy => !handicap.lowest_ask.is_zero() && price >= handicap.lowest_ask,
+            Side::Sell => !handicap.highest_bid.is_zero() && price <= handicap.highest_bid,
+        }
+    }
+
+    /// Returns the price at which a post-only order rests on the book.
+    ///
+    /// The order crossing the handicap is rejected, or re-priced one tick away from the
+    /// opposite side if `adjust` is true.
+    pub(crate) fn post_only_price(
+        pair: &TradingPairProfile,
+        price: T::Price,
+        side: Side,
+        adjust: bool,
+    ) -> Result<T::Price, Error<T>> {
+        if !Self::crosses_handicap(pair.id, price, side) {
+            return Ok(price);
+        }
+        ensure!(adjust, Error::<T>::PostOnlyWouldCross);
+
+        let handicap = <HandicapOf<T>>::get(pair.id);
+        let tick: T::Price = pair.tick().saturated_into();
+        let adjusted = match side {
+            Side::Buy => handicap.lowest_ask.saturating_sub(tick),
+            Side::Sell => handicap.highest_bid.saturating_add(tick),
+        };
+        ensure!(!adjusted.is_zero(), Error::<T>::PostOnlyWouldCross);
+        Ok(adjusted)
+    }
+
     /// Returns true if there are already too many orders at the `price` and `side` for a trading pair.
     pub(crate) fn has_too_many_backlog_orders(
         pair_id: TradingPairId,
@@ -96,7 +137,7 @@ impl<T: Config> Pallet<T> {
         Ok(())
     }
 
-    fn currency_decimals_of(asset_id: AssetId) -> Option<u8> {
+    pub(crate) fn currency_decimals_of(asset_id: AssetId) -> Option<u8> {
         <xpallet_assets_registrar::Pallet<T>>::asset_info_of(asset_id).map(|x| x.decimals())
     }
 
@@ -152,4 +193,50 @@ impl<T: Config> Pallet<T> {
             Err(Error::<T>::InvalidTradingPairAsset)
         }
     }
+
+    /// Returns the value of `amount` at `price` measured by the quote currency,
+    /// zero if it's too small to be converted.
+    fn notional_of(
+        amount: BalanceOf<T>,
+        price: T::Price,
+        pair: &TradingPairProfile,
+    ) -> BalanceOf<T> {
+        Self::convert_base_to_quote(amount, price, pair).unwrap_or_else(|_| Zero::zero())
+    }
+
+    /// Ensures the value of a new order is not below the minimum notional of the trading pair.
+    pub(crate) fn check_min_notional(
+        pair: &TradingPairProfile,
+        amount: BalanceOf<T>,
+        price: T::Price,
+    ) -> Result<(), Error<T>> {
+        let min_notional = Self::min_notional_of(pair.id);
+        if min_notional.is_zero() {
+            return Ok(());
+        }
+
+        let notional = Self::notional_of(amount, price, pair);
+        if notional < min_notional {
+            debug!(
+                target: "runtime::dex::spot",
+                "[check_min_notional] pair_id: {:?}, notional: {:?}, min_notional: {:?}",
+                pair.id, notional, min_notional
+            );
+            return Err(Error::<T>::NotionalTooSmall);
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if the unfilled part of a partially filled order should be
+    /// canceled instead of resting on the order book.
+    pub(crate) fn is_dust_remainder(pair: &TradingPairProfile, order: &OrderInfo<T>) -> bool {
+        if !T::AutoCancelDustOrders::get() || order.already_filled.is_zero() {
+            return false;
+        }
+
+        let min_notional = Self::min_notional_of(pair.id);
+        !min_notional.is_zero()
+            && Self::notional_of(order.remaining_in_base(), order.price(), pair) < min_notional
+    }
 }
@@ -3,6 +3,7 @@
 //! This module takes care of the order processing.
 
 use super::*;
+use frame_support::weights::DispatchClass;
 use sp_runtime::traits::CheckedAdd;
 use sp_std::cmp::Ordering;
 
@@ -50,6 +51,7 @@ impl<T: Config> Pallet<T> {
     }
 
     /// Insert a fresh order and return the inserted result.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn inject_order(
         who: T::AccountId,
         pair_id: TradingPairId,
@@ -58,6 +60,7 @@ impl<T: Config> Pallet<T> {
         side: Side,
         amount: BalanceOf<T>,
         remaining: BalanceOf<T>,
+        client_order_id: Option<ClientOrderId>,
     ) -> Order<TradingPairId, T::AccountId, BalanceOf<T>, T::Price, T::BlockNumber> {
         let order_id = Self::order_count_of(&who);
 
@@ -73,10 +76,35 @@ impl<T: Config> Pallet<T> {
         <OrderCountOf<T>>::insert(&who, order_id + 1);
 
         Self::deposit_event(Event::<T>::NewOrder(order.clone()));
+        if let Some(client_order_id) = client_order_id {
+            <ClientOrderIdOf<T>>::insert(&who, order_id, &client_order_id);
+            <OpenOrderOfClientId<T>>::insert(&who, &client_order_id, order_id);
+            Self::deposit_event(Event::<T>::ClientOrderIdAssigned(
+                who,
+                pair_id,
+                order_id,
+                client_order_id,
+            ));
+        }
+        Self::deposit_order_updated(&order);
 
         order
     }
 
+    /// Emits the snapshot of `order` after it was changed and bumps the order
+    /// sequence of its submitter.
+    ///
+    /// The orders are changed by the fills of other orders as well, so the access of
+    /// `OrderSequenceOf` is registered as extra weight.
+    pub(crate) fn deposit_order_updated(order: &OrderInfo<T>) {
+        OrderSequenceOf::<T>::mutate(order.submitter(), |seq| *seq = seq.saturating_add(1));
+        <frame_system::Pallet<T>>::register_extra_weight_unchecked(
+            T::DbWeight::get().reads_writes(1, 1),
+            DispatchClass::Normal,
+        );
+        Self::deposit_event(Event::<T>::OrderUpdated(order.into()));
+    }
+
     /// Create a brand new order with some defaults.
     #[allow(clippy::too_many_arguments)]
     fn new_fresh_order(
@@ -132,7 +160,14 @@ impl<T: Config> Pallet<T> {
         // should be updated.
         if order.is_fulfilled() {
             order.status = OrderStatus::Filled;
-            <OrderInfoOf<T>>::remove(order.submitter(), order.id());
+            Self::remove_order_info(&order.submitter(), order.id());
+        } else if Self::is_dust_remainder(pair, order) || Self::is_matching_halted(pair.id) {
+            // The remainder is too small to rest on the order book, or it would cross
+            // the book since the matching is halted by the circuit breaker.
+            let who = order.submitter();
+            let cancel_result = Self::update_order_and_unreserve_on_cancel(order, pair, &who);
+            assert!(cancel_result.is_ok(), "Cancel the dust order can not fail");
+            Self::remove_order_info(&who, order.id());
         } else {
             <QuotationsOf<T>>::mutate(order.pair_id(), order.price(), |quotations| {
                 quotations.push((order.submitter(), order.id()))
@@ -158,8 +193,9 @@ impl<T: Config> Pallet<T> {
     ) {
         let quotations = <QuotationsOf<T>>::get(pair.id, counterparty_price);
         let mut fulfilled_orders = Vec::new();
+        let mut dust_orders = Vec::new();
 
-        for (who, order_index) in quotations.iter() {
+        for (_queue_index, (who, order_index)) in quotations.iter().enumerate() {
             if taker_order.is_fulfilled() {
                 break;
             }
@@ -170,6 +206,22 @@ impl<T: Config> Pallet<T> {
                     "Opponent side should match the side of maker order."
                 );
 
+                // Stop matching once the circuit breaker is tripped.
+                if !Self::check_circuit_breaker(pair.id, counterparty_price) {
+                    break;
+                }
+
+                #[cfg(debug_assertions)]
+                Self::debug_assert_best_maker(
+                    pair.id,
+                    MatchingPriority {
+                        side: counterparty_side,
+                        price: counterparty_price,
+                        queue_index: _queue_index,
+                    },
+                    (who, *order_index),
+                );
+
                 let turnover = cmp::min(
                     taker_order.remaining_in_base(),
                     maker_order.remaining_in_base(),
@@ -190,6 +242,11 @@ impl<T: Config> Pallet<T> {
                 if maker_order.is_fulfilled() {
                     fulfilled_orders.push((maker_order.submitter(), maker_order.id()));
                     Self::update_handicap(pair, counterparty_price, maker_order.side());
+                } else if Self::is_dust_remainder(pair, &maker_order) {
+                    let cancel_result =
+                        Self::update_order_and_unreserve_on_cancel(&mut maker_order, pair, who);
+                    assert!(cancel_result.is_ok(), "Cancel the dust order can not fail");
+                    dust_orders.push((maker_order.submitter(), maker_order.id()));
                 }
 
                 Self::update_latest_price(pair.id, counterparty_price);
@@ -200,6 +257,12 @@ impl<T: Config> Pallet<T> {
         if !fulfilled_orders.is_empty() {
             Self::remove_orders_and_quotations(pair.id, counterparty_price, fulfilled_orders);
         }
+
+        // Remove the canceled dust orders and then check whether the handicap is still real.
+        if !dust_orders.is_empty() {
+            Self::remove_orders_and_quotations(pair.id, counterparty_price, dust_orders);
+            Self::update_handicap(pair, counterparty_price, counterparty_side);
+        }
     }
 
     fn match_taker_order_buy(
@@ -216,7 +279,7 @@ impl<T: Config> Pallet<T> {
         let mut counterparty_price = floor;
 
         while !counterparty_price.is_zero() && counterparty_price <= ceiling {
-            if taker_order.is_fulfilled() {
+            if taker_order.is_fulfilled() || Self::is_matching_halted(pair.id) {
                 return;
             }
             Self::apply_match_order_given_counterparty(
@@ -243,7 +306,7 @@ impl<T: Config> Pallet<T> {
         let mut counterparty_price = ceiling;
 
         while !counterparty_price.is_zero() && counterparty_price >= floor {
-            if taker_order.is_fulfilled() {
+            if taker_order.is_fulfilled() || Self::is_matching_halted(pair.id) {
                 return;
             }
             Self::apply_match_order_given_counterparty(
@@ -283,7 +346,7 @@ impl<T: Config> Pallet<T> {
         pair: TradingPairProfile,
         order_side: Side,
     ) {
-        <OrderInfoOf<T>>::remove(&who, order_index);
+        Self::remove_order_info(&who, order_index);
 
         let order_key = (who, order_index);
         Self::remove_quotation(pair_id, price, order_key);
@@ -291,6 +354,15 @@ impl<T: Config> Pallet<T> {
         Self::update_handicap(&pair, price, order_side);
     }
 
+    /// Removes the order info of a closed order along with its client order id, which
+    /// is free for reuse afterwards.
+    pub(crate) fn remove_order_info(who: &T::AccountId, order_id: OrderId) {
+        <OrderInfoOf<T>>::remove(who, order_id);
+        if let Some(client_order_id) = <ClientOrderIdOf<T>>::take(who, order_id) {
+            <OpenOrderOfClientId<T>>::remove(who, client_order_id);
+        }
+    }
+
     /// Update the status of order after the turnover is calculated.
     fn update_order_on_execute(
         order: &mut OrderInfo<T>,
@@ -367,6 +439,17 @@ impl<T: Config> Pallet<T> {
             taker_order,
         )?;
 
+        #[cfg(any(feature = "settlement-audit", test))]
+        Self::assert_settlement_balanced(pair_id);
+
+        Self::note_competition_volume(
+            &pair,
+            &maker_order.submitter(),
+            &taker_order.submitter(),
+            turnover,
+            price,
+        );
+
         maker_order.decrease_remaining_on_execute(maker_turnover_amount);
         taker_order.decrease_remaining_on_execute(taker_turnover_amount);
 
@@ -393,6 +476,8 @@ impl<T: Config> Pallet<T> {
             taker_order,
             <frame_system::Pallet<T>>::block_number(),
         )));
+        Self::deposit_order_updated(maker_order);
+        Self::deposit_order_updated(taker_order);
 
         Ok(())
     }
@@ -417,6 +502,7 @@ impl<T: Config> Pallet<T> {
         OrderInfoOf::<T>::insert(order.submitter(), order.id(), order.clone());
 
         Self::deposit_event(Event::<T>::CanceledOrderUpdated(order.clone()));
+        Self::deposit_order_updated(order);
 
         Ok(())
     }
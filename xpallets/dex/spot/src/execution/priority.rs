@@ -0,0 +1,63 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! This module checks the matching order against `MatchingPriority` in the debug builds.
+//!
+//! The matching walks the prices tick by tick from the handicap and each price level in
+//! the order of its quotations, so every step should pick the resting order of the
+//! highest priority on the whole order book.
+
+use super::*;
+
+impl<T: Config> Pallet<T> {
+    /// Returns the live resting order of `side` with the highest matching priority on the
+    /// order book of `pair_id`.
+    ///
+    /// The orders already filled or canceled in the ongoing matching are skipped, they are
+    /// removed from the quotations only after the price level has been processed.
+    fn best_resting_order(
+        pair_id: TradingPairId,
+        side: Side,
+    ) -> Option<(MatchingPriority<T::Price>, (T::AccountId, OrderId))> {
+        QuotationsOf::<T>::iter_prefix(pair_id)
+            .flat_map(|(price, quotations)| {
+                quotations
+                    .into_iter()
+                    .enumerate()
+                    .map(move |(queue_index, order_key)| {
+                        let priority = MatchingPriority {
+                            side,
+                            price,
+                            queue_index,
+                        };
+                        (priority, order_key)
+                    })
+            })
+            .filter(|(_, (who, order_id))| {
+                OrderInfoOf::<T>::get(who, order_id).map_or(false, |order| {
+                    order.side() == side && !order.is_fulfilled() && !order.is_canceled()
+                })
+            })
+            .min_by(|(x, _), (y, _)| x.cmp(y))
+    }
+
+    /// Panics if the maker `(who, order_id)` at `priority` is not the resting order of the
+    /// highest priority on the order book of `pair_id`.
+    pub(super) fn debug_assert_best_maker(
+        pair_id: TradingPairId,
+        priority: MatchingPriority<T::Price>,
+        (who, order_id): (&T::AccountId, OrderId),
+    ) {
+        let best = Self::best_resting_order(pair_id, priority.side);
+        debug_assert!(
+            best.as_ref()
+                .map_or(false, |(best_priority, (best_who, best_order_id))| {
+                    *best_priority == priority && best_who == who && *best_order_id == order_id
+                }),
+            "Matching order violated, pair_id: {:?}, maker: {:?}, priority: {:?}, best: {:?}",
+            pair_id,
+            (who, order_id),
+            priority,
+            best
+        );
+    }
+}
@@ -136,7 +136,8 @@ impl<T: Config> Pallet<T> {
 
     /// Removes the order as well as the quotations from the order list.
     ///
-    /// This happens when the maker orders have been completely filled.
+    /// This happens when the maker orders have been completely filled,
+    /// or their dust remainders have been canceled.
     pub(super) fn remove_orders_and_quotations(
         pair_id: TradingPairId,
         price: T::Price,
@@ -148,7 +149,7 @@ impl<T: Config> Pallet<T> {
             fulfilled_orders
         );
         for (who, order_idx) in fulfilled_orders.iter() {
-            <OrderInfoOf<T>>::remove(who, order_idx);
+            Self::remove_order_info(who, *order_idx);
         }
 
         <QuotationsOf<T>>::mutate(pair_id, price, |quotations| {
@@ -20,8 +20,8 @@ mod tests;
 use codec::Codec;
 
 use sp_runtime::traits::{
-    AtLeast32BitUnsigned, MaybeSerializeDeserialize, Member, SaturatedConversion, StaticLookup,
-    Zero,
+    AtLeast32BitUnsigned, MaybeSerializeDeserialize, Member, SaturatedConversion, Saturating,
+    StaticLookup, Zero,
 };
 use sp_std::prelude::*;
 use sp_std::{cmp, fmt::Debug};
@@ -31,6 +31,7 @@ use frame_support::{
     ensure,
     log::info,
     traits::{Currency, Get, ReservableCurrency},
+    weights::Weight,
     Parameter,
 };
 use frame_system::{ensure_root, ensure_signed};
@@ -45,6 +46,18 @@ pub use self::weights::WeightInfo;
 /// Maximum of backlog orders.
 const MAX_BACKLOG_ORDER: usize = 1000;
 
+/// Maximum length of the client order id.
+const MAX_CLIENT_ORDER_ID_LEN: usize = 32;
+
+/// Maximum of the open orders of the delisted trading pairs canceled per block.
+const MAX_DELIST_CANCELS_PER_BLOCK: u32 = 50;
+
+/// Maximum of the trading competitions of a trading pair open at the same time.
+const MAX_OPEN_COMPETITIONS_PER_PAIR: usize = 4;
+
+/// Maximum of the accounts kept in the leaderboard of a trading competition.
+const MAX_LEADERBOARD_SIZE: usize = 100;
+
 /// The maximum ticks that a price can deviated from the handicap.
 ///
 /// NOTE:
@@ -76,7 +89,7 @@ pub use pallet::*;
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
-    use frame_support::pallet_prelude::*;
+    use frame_support::{pallet_prelude::*, sp_io::KillStorageResult, transactional};
     use frame_system::pallet_prelude::*;
 
     #[pallet::config]
@@ -94,6 +107,20 @@ pub mod pallet {
             + MaybeSerializeDeserialize
             + Debug;
 
+        /// Whether to switch the trading pair offline automatically once
+        /// the settlement audit finds an imbalance.
+        #[pallet::constant]
+        type AutoPauseOnImbalance: Get<bool>;
+
+        /// Whether to cancel the resting remainder of a partially filled order
+        /// automatically once its notional falls below the minimum of the trading pair.
+        #[pallet::constant]
+        type AutoCancelDustOrders: Get<bool>;
+
+        /// The notice period of delisting the trading pairs of a deregistered asset.
+        #[pallet::constant]
+        type DelistNoticePeriod: Get<Self::BlockNumber>;
+
         type WeightInfo: WeightInfo;
     }
 
@@ -102,9 +129,30 @@ pub mod pallet {
     #[pallet::without_storage_info]
     pub struct Pallet<T>(PhantomData<T>);
 
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+            Self::close_ended_competitions(n).saturating_add(Self::process_delisting(n))
+        }
+    }
+
     #[pallet::call]
     impl<T: Config> Pallet<T> {
-        #[pallet::weight(<T as Config>::WeightInfo::put_order())]
+        /// Put an order.
+        ///
+        /// The order is put for `on_behalf_of` if specified, in which case the signer
+        /// must be the trading operator of `on_behalf_of`.
+        ///
+        /// `client_order_id` is echoed back in `ClientOrderIdAssigned`, it must be unique
+        /// among the open orders of the owner.
+        ///
+        /// A `post_only` order never takes liquidity. If it would be matched immediately,
+        /// it is rejected, or re-priced one tick away from the opposite side of the handicap
+        /// if `adjust` is true. `adjust` is ignored for the other orders.
+        #[pallet::weight(<T as Config>::WeightInfo::put_order()
+            .saturating_add(Pallet::<T>::operator_check_weight(on_behalf_of))
+            .saturating_add(Pallet::<T>::settlement_weight()))]
+        #[allow(clippy::too_many_arguments)]
         pub fn put_order(
             origin: OriginFor<T>,
             #[pallet::compact] pair_id: TradingPairId,
@@ -112,8 +160,16 @@ pub mod pallet {
             side: Side,
             #[pallet::compact] amount: BalanceOf<T>,
             #[pallet::compact] price: T::Price,
+            on_behalf_of: Option<T::AccountId>,
+            client_order_id: Option<ClientOrderId>,
+            post_only: bool,
+            adjust: bool,
         ) -> DispatchResult {
-            let who = ensure_signed(origin)?;
+            let signer = ensure_signed(origin)?;
+            let who = Self::ensure_trader(&signer, on_behalf_of)?;
+            if let Some(ref client_order_id) = client_order_id {
+                Self::ensure_valid_client_order_id(&who, client_order_id)?;
+            }
 
             ensure!(!price.is_zero(), Error::<T>::InvalidPrice);
             ensure!(!amount.is_zero(), Error::<T>::ZeroAmount);
@@ -122,10 +178,18 @@ pub mod pallet {
             let pair = Self::trading_pair(pair_id)?;
 
             ensure!(pair.tradable, Error::<T>::TradingPairUntradable);
+            Self::ensure_not_delisting(pair_id)?;
             ensure!(pair.is_valid_price(price), Error::<T>::InvalidPrice);
+            let price = if post_only {
+                Self::post_only_price(&pair, price, side, adjust)?
+            } else {
+                price
+            };
+            Self::check_min_notional(&pair, amount, price)?;
 
             Self::is_valid_quote(price, side, pair_id)?;
             Self::has_too_many_backlog_orders(pair_id, price, side)?;
+            Self::ensure_no_cross_while_halted(pair_id, price, side)?;
 
             // Reserve the token according to the order side.
             let (reserve_asset, reserve_amount) = match side {
@@ -136,26 +200,74 @@ pub mod pallet {
                 Side::Sell => (pair.base(), amount),
             };
             Self::put_order_reserve(&who, reserve_asset, reserve_amount)?;
+            let order_id = Self::order_count_of(&who);
             Self::apply_put_order(
-                who,
+                who.clone(),
                 pair_id,
                 order_type,
                 side,
                 amount,
                 price,
                 reserve_amount,
+                client_order_id,
             )?;
+            if who != signer {
+                Self::deposit_event(Event::<T>::OrderPutByOperator(
+                    who, signer, pair_id, order_id,
+                ));
+            }
             Ok(())
         }
 
-        #[pallet::weight(<T as Config>::WeightInfo::cancel_order())]
+        /// Cancel an order.
+        ///
+        /// The order of `on_behalf_of` is canceled if specified, in which case the signer
+        /// must be the trading operator of `on_behalf_of`.
+        #[pallet::weight(<T as Config>::WeightInfo::cancel_order()
+            .saturating_add(Pallet::<T>::operator_check_weight(on_behalf_of)))]
         pub fn cancel_order(
             origin: OriginFor<T>,
             #[pallet::compact] pair_id: TradingPairId,
             #[pallet::compact] order_id: OrderId,
+            on_behalf_of: Option<T::AccountId>,
         ) -> DispatchResult {
-            let who = ensure_signed(origin)?;
+            let signer = ensure_signed(origin)?;
+            let who = Self::ensure_trader(&signer, on_behalf_of)?;
+            Self::do_cancel_order(&who, pair_id, order_id)?;
+            if who != signer {
+                Self::deposit_event(Event::<T>::OrderCanceledByOperator(
+                    who, signer, pair_id, order_id,
+                ));
+            }
+            Ok(())
+        }
+
+        /// Cancel an open order given its client order id.
+        ///
+        /// The order of `on_behalf_of` is canceled if specified, in which case the signer
+        /// must be the trading operator of `on_behalf_of`.
+        #[pallet::weight(<T as Config>::WeightInfo::cancel_by_client_id()
+            .saturating_add(Pallet::<T>::operator_check_weight(on_behalf_of)))]
+        pub fn cancel_by_client_id(
+            origin: OriginFor<T>,
+            #[pallet::compact] pair_id: TradingPairId,
+            client_order_id: ClientOrderId,
+            on_behalf_of: Option<T::AccountId>,
+        ) -> DispatchResult {
+            let signer = ensure_signed(origin)?;
+            let who = Self::ensure_trader(&signer, on_behalf_of)?;
+            let order_id = Self::open_order_of_client_id(&who, &client_order_id)
+                .ok_or(Error::<T>::InvalidOrderId)?;
+            ensure!(
+                Self::get_order(&who, order_id)?.pair_id() == pair_id,
+                Error::<T>::InvalidOrderId
+            );
             Self::do_cancel_order(&who, pair_id, order_id)?;
+            if who != signer {
+                Self::deposit_event(Event::<T>::OrderCanceledByOperator(
+                    who, signer, pair_id, order_id,
+                ));
+            }
             Ok(())
         }
 
@@ -173,6 +285,24 @@ pub mod pallet {
             Ok(())
         }
 
+        /// Force cancel at most `limit` open orders of a trading pair regardless of the owner.
+        ///
+        /// The reserved balances are released to the order owners. This is used for
+        /// cleaning up the order book of a delisted trading pair, it can be called
+        /// repeatedly until the order book is empty.
+        #[pallet::weight(<T as Config>::WeightInfo::force_cancel_pair_orders(*limit))]
+        #[transactional]
+        pub fn force_cancel_pair_orders(
+            origin: OriginFor<T>,
+            #[pallet::compact] pair_id: TradingPairId,
+            #[pallet::compact] limit: u32,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            Self::trading_pair(pair_id)?;
+            Self::cancel_pair_orders(pair_id, limit)?;
+            Ok(())
+        }
+
         #[pallet::weight(<T as Config>::WeightInfo::set_handicap())]
         pub fn set_handicap(
             origin: OriginFor<T>,
@@ -198,6 +328,9 @@ pub mod pallet {
         }
 
         /// Add a new trading pair.
+        ///
+        /// `min_notional` is the minimum order value measured by the quote currency,
+        /// zero means no minimum.
         #[pallet::weight(<T as Config>::WeightInfo::add_trading_pair())]
         pub fn add_trading_pair(
             origin: OriginFor<T>,
@@ -206,6 +339,7 @@ pub mod pallet {
             #[pallet::compact] tick_decimals: u32,
             #[pallet::compact] latest_price: T::Price,
             tradable: bool,
+            #[pallet::compact] min_notional: BalanceOf<T>,
         ) -> DispatchResult {
             ensure_root(origin)?;
             ensure!(
@@ -218,6 +352,7 @@ pub mod pallet {
                 tick_decimals,
                 latest_price,
                 tradable,
+                min_notional,
             );
             Ok(())
         }
@@ -239,6 +374,166 @@ pub mod pallet {
             Self::apply_update_trading_pair(pair_id, tick_decimals, tradable);
             Ok(())
         }
+
+        /// Set the minimum order value of a trading pair measured by the quote currency.
+        ///
+        /// Zero means no minimum.
+        #[pallet::weight(<T as Config>::WeightInfo::set_min_notional())]
+        pub fn set_min_notional(
+            origin: OriginFor<T>,
+            #[pallet::compact] pair_id: TradingPairId,
+            #[pallet::compact] new: BalanceOf<T>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            Self::trading_pair(pair_id)?;
+            MinNotionalOf::<T>::insert(pair_id, new);
+            Self::deposit_event(Event::<T>::MinNotionalUpdated(pair_id, new));
+            Ok(())
+        }
+
+        /// Set or clear the trading operator of the sender.
+        ///
+        /// The operator is able to put and cancel orders on behalf of the sender,
+        /// but nothing else, e.g., transfer or withdraw the assets of the sender.
+        #[pallet::weight(<T as Config>::WeightInfo::set_trading_operator())]
+        pub fn set_trading_operator(
+            origin: OriginFor<T>,
+            operator: Option<T::AccountId>,
+        ) -> DispatchResult {
+            let owner = ensure_signed(origin)?;
+            match operator {
+                Some(ref operator) => TradingOperatorOf::<T>::insert(&owner, operator),
+                None => TradingOperatorOf::<T>::remove(&owner),
+            }
+            Self::deposit_event(Event::<T>::TradingOperatorUpdated(owner, operator));
+            Ok(())
+        }
+
+        /// Set or clear the circuit breaker of a trading pair.
+        #[pallet::weight(<T as Config>::WeightInfo::set_circuit_breaker())]
+        pub fn set_circuit_breaker(
+            origin: OriginFor<T>,
+            #[pallet::compact] pair_id: TradingPairId,
+            new: Option<CircuitBreaker<T::BlockNumber>>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            Self::trading_pair(pair_id)?;
+            match new {
+                Some(breaker) => {
+                    ensure!(
+                        breaker.max_move_per_block_percent > 0
+                            && !breaker.halt_duration_blocks.is_zero(),
+                        Error::<T>::InvalidCircuitBreaker
+                    );
+                    CircuitBreakerOf::<T>::insert(pair_id, breaker);
+                }
+                None => CircuitBreakerOf::<T>::remove(pair_id),
+            }
+            Self::deposit_event(Event::<T>::CircuitBreakerUpdated(pair_id, new));
+            Ok(())
+        }
+
+        /// Reduce the unfilled amount of an open order in place.
+        ///
+        /// The order keeps its position in the queue of its price and the reserved
+        /// balance of the reduced part is released immediately. `reduce_by` is measured
+        /// by the base currency and must be less than the unfilled amount, the order
+        /// should be canceled instead otherwise.
+        #[pallet::weight(<T as Config>::WeightInfo::reduce_order())]
+        pub fn reduce_order(
+            origin: OriginFor<T>,
+            #[pallet::compact] pair_id: TradingPairId,
+            #[pallet::compact] order_id: OrderId,
+            #[pallet::compact] reduce_by: BalanceOf<T>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::do_reduce_order(&who, pair_id, order_id, reduce_by)
+        }
+
+        /// Start a trading competition of a trading pair.
+        ///
+        /// The quote volume of the fills in `[start_block, end_block)` is counted for
+        /// the taker, and for the maker as well if `count_maker` is true.
+        #[pallet::weight(<T as Config>::WeightInfo::start_competition())]
+        pub fn start_competition(
+            origin: OriginFor<T>,
+            #[pallet::compact] pair_id: TradingPairId,
+            start_block: T::BlockNumber,
+            end_block: T::BlockNumber,
+            count_maker: bool,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            Self::trading_pair(pair_id)?;
+            let now = <frame_system::Pallet<T>>::block_number();
+            ensure!(
+                start_block < end_block && now < end_block,
+                Error::<T>::InvalidCompetitionWindow
+            );
+            ensure!(
+                Self::open_competitions_of(pair_id).len() < MAX_OPEN_COMPETITIONS_PER_PAIR,
+                Error::<T>::TooManyOpenCompetitions
+            );
+
+            let id = Self::competition_count();
+            CompetitionCount::<T>::put(id + 1);
+            Competitions::<T>::insert(
+                id,
+                Competition {
+                    pair_id,
+                    start_block,
+                    end_block,
+                    count_maker,
+                    ended: false,
+                },
+            );
+            OpenCompetitionsOf::<T>::append(pair_id, id);
+            CompetitionEndings::<T>::append(end_block, id);
+            Self::deposit_event(Event::<T>::CompetitionStarted(
+                id,
+                pair_id,
+                start_block,
+                end_block,
+            ));
+            Ok(())
+        }
+
+        /// Remove at most `limit` volume counters of an ended competition.
+        ///
+        /// The competition itself is removed once all its counters are removed.
+        #[pallet::weight(<T as Config>::WeightInfo::prune_competition(*limit))]
+        pub fn prune_competition(
+            origin: OriginFor<T>,
+            #[pallet::compact] id: CompetitionId,
+            limit: u32,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            let competition = Self::competitions(id).ok_or(Error::<T>::NonexistentCompetition)?;
+            ensure!(competition.ended, Error::<T>::CompetitionNotEnded);
+
+            if let KillStorageResult::AllRemoved(_) =
+                CompetitionVolume::<T>::remove_prefix(id, Some(limit))
+            {
+                Competitions::<T>::remove(id);
+                CompetitionLeaderboard::<T>::remove(id);
+                Self::deposit_event(Event::<T>::CompetitionPruned(id));
+            }
+            Ok(())
+        }
+
+        /// Schedule the delisting of a trading pair at `effective_block`.
+        ///
+        /// The new orders are rejected immediately while the matching continues until
+        /// `effective_block`, at which the open orders start to be canceled automatically.
+        /// The trading pair is retired once all of them are canceled.
+        #[pallet::weight(<T as Config>::WeightInfo::schedule_pair_delist())]
+        pub fn schedule_pair_delist(
+            origin: OriginFor<T>,
+            #[pallet::compact] pair_id: TradingPairId,
+            effective_block: T::BlockNumber,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            Self::apply_schedule_pair_delist(pair_id, effective_block)
+        }
     }
 
     #[pallet::event]
@@ -266,6 +561,43 @@ pub mod pallet {
         TradingPairUpdated(TradingPairProfile),
         /// Price fluctuation of trading pair has been updated. [pair_id, price_fluctuation]
         PriceFluctuationUpdated(TradingPairId, PriceFluctuation),
+        /// The settlement debits and credits of trading pair do not match. [pair_id, base_delta, quote_delta]
+        SettlementImbalance(TradingPairId, BalanceOf<T>, BalanceOf<T>),
+        /// Open orders of trading pair have been force canceled. [pair_id, canceled, book_cleared]
+        PairOrdersForceCanceled(TradingPairId, u32, bool),
+        /// Minimum order value of trading pair has been updated. [pair_id, min_notional]
+        MinNotionalUpdated(TradingPairId, BalanceOf<T>),
+        /// The trading operator of an account has been set or cleared. [owner, operator]
+        TradingOperatorUpdated(T::AccountId, Option<T::AccountId>),
+        /// An order was put by the trading operator on behalf of the owner. [owner, operator, pair_id, order_id]
+        OrderPutByOperator(T::AccountId, T::AccountId, TradingPairId, OrderId),
+        /// An order was canceled by the trading operator on behalf of the owner. [owner, operator, pair_id, order_id]
+        OrderCanceledByOperator(T::AccountId, T::AccountId, TradingPairId, OrderId),
+        /// The circuit breaker of trading pair has been set or cleared. [pair_id, circuit_breaker]
+        CircuitBreakerUpdated(TradingPairId, Option<CircuitBreaker<T::BlockNumber>>),
+        /// The matching of trading pair is halted due to an extreme price move. [pair_id, trigger_price, resume_block]
+        CircuitBreakerTripped(TradingPairId, T::Price, T::BlockNumber),
+        /// An order was created or updated, emitted after every change of the order. [order_snapshot]
+        OrderUpdated(
+            OrderSnapshot<TradingPairId, T::AccountId, BalanceOf<T>, T::Price, T::BlockNumber>,
+        ),
+        /// A new order was created with a client order id, emitted right after `NewOrder`.
+        /// [owner, pair_id, order_id, client_order_id]
+        ClientOrderIdAssigned(T::AccountId, TradingPairId, OrderId, ClientOrderId),
+        /// The unfilled amount of an order was reduced in place. [pair_id, order_id, new_remaining_in_base]
+        OrderReduced(TradingPairId, OrderId, BalanceOf<T>),
+        /// A trading competition was started. [competition_id, pair_id, start_block, end_block]
+        CompetitionStarted(CompetitionId, TradingPairId, T::BlockNumber, T::BlockNumber),
+        /// A trading competition ended, its volume is read-only since then. [competition_id]
+        CompetitionEnded(CompetitionId),
+        /// An ended trading competition was removed along with its volume. [competition_id]
+        CompetitionPruned(CompetitionId),
+        /// The delisting of trading pair was scheduled, no new orders are accepted since then. [pair_id, effective_block]
+        TradingPairDelistScheduled(TradingPairId, T::BlockNumber),
+        /// The matching of trading pair was stopped, its open orders are being canceled. [pair_id]
+        TradingPairDelistEffective(TradingPairId),
+        /// All the open orders of the delisted trading pair were canceled. [pair_id]
+        TradingPairRetired(TradingPairId),
     }
 
     /// Error for the spot module.
@@ -307,6 +639,40 @@ pub mod pallet {
         InvalidOrderId,
         /// Error from assets module.
         AssetError,
+        /// The order value (price * amount) is below the minimum notional of the trading pair,
+        /// the minimum is returned by the `xspot_getMinNotional` RPC.
+        NotionalTooSmall,
+        /// The pip and tick decimals are inconsistent with the decimals of the quote asset.
+        InconsistentPairDecimals,
+        /// The sender is not the trading operator of the order owner.
+        NotTradingOperator,
+        /// The price band and halt duration of circuit breaker can not be zero.
+        InvalidCircuitBreaker,
+        /// The matching of trading pair is halted, only the orders that do not cross the book can rest.
+        TradingPairHalted,
+        /// The client order id must be 1 to 32 ASCII letters, digits, `-` or `_`.
+        InvalidClientOrderId,
+        /// The client order id is in use by another open order of the account.
+        DuplicateClientOrderId,
+        /// The reduced amount must be less than the unfilled amount of the order.
+        InvalidReduceAmount,
+        /// The end block of the competition must be after its start block and the current block.
+        InvalidCompetitionWindow,
+        /// The trading competition does not exist.
+        NonexistentCompetition,
+        /// Only the ended trading competitions can be pruned.
+        CompetitionNotEnded,
+        /// The trading pair is being delisted and accepts no new orders, the effective
+        /// block can be found in the trading pair list.
+        TradingPairDelisting,
+        /// The matching of trading pair has already been stopped by the delisting.
+        TradingPairDelisted,
+        /// The effective block of the delisting must be after the current block.
+        InvalidDelistBlock,
+        /// The post-only order would be matched immediately.
+        PostOnlyWouldCross,
+        /// The trading pair has too many trading competitions open.
+        TooManyOpenCompetitions,
     }
 
     /// How many trading pairs so far.
@@ -363,12 +729,38 @@ pub mod pallet {
         ValueQuery,
     >;
 
+    /// The client order id of an open order given the account ID and order ID.
+    #[pallet::storage]
+    #[pallet::getter(fn client_order_id_of)]
+    pub(crate) type ClientOrderIdOf<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, T::AccountId, Twox64Concat, OrderId, ClientOrderId>;
+
+    /// The open order given the account ID and its client order id.
+    #[pallet::storage]
+    #[pallet::getter(fn open_order_of_client_id)]
+    pub(crate) type OpenOrderOfClientId<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, T::AccountId, Blake2_128Concat, ClientOrderId, OrderId>;
+
+    /// The trading operator which can put and cancel orders on behalf of the owner.
+    ///
+    /// owner => operator
+    #[pallet::storage]
+    #[pallet::getter(fn trading_operator_of)]
+    pub(crate) type TradingOperatorOf<T: Config> =
+        StorageMap<_, Twox64Concat, T::AccountId, T::AccountId>;
+
     /// TradingPairId => (highest_bid, lowest_ask)
     #[pallet::storage]
     #[pallet::getter(fn handicap_of)]
     pub(crate) type HandicapOf<T: Config> =
         StorageMap<_, Twox64Concat, TradingPairId, HandicapInfo<T>, ValueQuery>;
 
+    /// Cumulative settlement counters of each trading pair.
+    #[pallet::storage]
+    #[pallet::getter(fn settlement_counters_of)]
+    pub(crate) type SettlementCountersOf<T: Config> =
+        StorageMap<_, Twox64Concat, TradingPairId, SettlementCounters<BalanceOf<T>>, ValueQuery>;
+
     #[pallet::type_value]
     pub fn DefaultForPriceFluctuationOf() -> PriceFluctuation {
         DEFAULT_FLUCTUATION
@@ -386,6 +778,97 @@ pub mod pallet {
         DefaultForPriceFluctuationOf,
     >;
 
+    /// The minimum order value of each trading pair measured by the quote currency.
+    #[pallet::storage]
+    #[pallet::getter(fn min_notional_of)]
+    pub(crate) type MinNotionalOf<T: Config> =
+        StorageMap<_, Twox64Concat, TradingPairId, BalanceOf<T>, ValueQuery>;
+
+    /// The circuit breaker of each trading pair, none if disabled.
+    #[pallet::storage]
+    #[pallet::getter(fn circuit_breaker_of)]
+    pub(crate) type CircuitBreakerOf<T: Config> =
+        StorageMap<_, Twox64Concat, TradingPairId, CircuitBreaker<T::BlockNumber>>;
+
+    /// (block number, price) of the first trade of trading pair in that block.
+    #[pallet::storage]
+    #[pallet::getter(fn block_opening_price_of)]
+    pub(crate) type BlockOpeningPriceOf<T: Config> =
+        StorageMap<_, Twox64Concat, TradingPairId, (T::BlockNumber, T::Price)>;
+
+    /// The block at which the halted matching of trading pair resumes.
+    #[pallet::storage]
+    #[pallet::getter(fn halted_until_of)]
+    pub(crate) type HaltedUntilOf<T: Config> =
+        StorageMap<_, Twox64Concat, TradingPairId, T::BlockNumber>;
+
+    /// How many trading competitions so far.
+    #[pallet::storage]
+    #[pallet::getter(fn competition_count)]
+    pub(crate) type CompetitionCount<T: Config> = StorageValue<_, CompetitionId, ValueQuery>;
+
+    /// The trading competitions, removed once pruned.
+    #[pallet::storage]
+    #[pallet::getter(fn competitions)]
+    pub(crate) type Competitions<T: Config> =
+        StorageMap<_, Twox64Concat, CompetitionId, Competition<T::BlockNumber>>;
+
+    /// The competitions of each trading pair that have not ended yet.
+    #[pallet::storage]
+    #[pallet::getter(fn open_competitions_of)]
+    pub(crate) type OpenCompetitionsOf<T: Config> =
+        StorageMap<_, Twox64Concat, TradingPairId, Vec<CompetitionId>, ValueQuery>;
+
+    /// The competitions ending at each block.
+    #[pallet::storage]
+    pub(crate) type CompetitionEndings<T: Config> =
+        StorageMap<_, Twox64Concat, T::BlockNumber, Vec<CompetitionId>, ValueQuery>;
+
+    /// The quote volume of each account in each trading competition.
+    #[pallet::storage]
+    #[pallet::getter(fn competition_volume)]
+    pub(crate) type CompetitionVolume<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        CompetitionId,
+        Blake2_128Concat,
+        T::AccountId,
+        BalanceOf<T>,
+        ValueQuery,
+    >;
+
+    /// The top `MAX_LEADERBOARD_SIZE` accounts of each trading competition, sorted by
+    /// the volume in descending order.
+    #[pallet::storage]
+    #[pallet::getter(fn competition_leaderboard_of)]
+    pub(crate) type CompetitionLeaderboard<T: Config> =
+        StorageMap<_, Twox64Concat, CompetitionId, Vec<(T::AccountId, BalanceOf<T>)>, ValueQuery>;
+
+    /// The delisting stage of each trading pair, none if listed.
+    #[pallet::storage]
+    #[pallet::getter(fn delist_status_of)]
+    pub(crate) type DelistStatusOf<T: Config> =
+        StorageMap<_, Twox64Concat, TradingPairId, DelistStatus<T::BlockNumber>>;
+
+    /// The trading pairs whose delisting takes effect at each block.
+    #[pallet::storage]
+    pub(crate) type DelistSchedule<T: Config> =
+        StorageMap<_, Twox64Concat, T::BlockNumber, Vec<TradingPairId>, ValueQuery>;
+
+    /// The delisted trading pairs whose open orders are being canceled.
+    #[pallet::storage]
+    #[pallet::getter(fn closing_pairs)]
+    pub(crate) type ClosingPairs<T: Config> = StorageValue<_, Vec<TradingPairId>, ValueQuery>;
+
+    /// The sequence number of the order changes of each account.
+    ///
+    /// Bumped whenever an order of the account is created or changed, so that the
+    /// clients can tell whether the orders have moved between two queries.
+    #[pallet::storage]
+    #[pallet::getter(fn order_sequence_of)]
+    pub(crate) type OrderSequenceOf<T: Config> =
+        StorageMap<_, Twox64Concat, T::AccountId, u64, ValueQuery>;
+
     #[pallet::genesis_config]
     pub struct GenesisConfig<T: Config> {
         pub trading_pairs: Vec<(AssetId, AssetId, u32, u32, T::Price, bool)>,
@@ -413,6 +896,7 @@ pub mod pallet {
                         *tick_decimals,
                         *price,
                         *tradable,
+                        Zero::zero(),
                     );
                 }
             };
@@ -459,6 +943,7 @@ impl<T: Config> Pallet<T> {
         tick_decimals: u32,
         latest_price: T::Price,
         tradable: bool,
+        min_notional: BalanceOf<T>,
     ) {
         let pair_id = TradingPairCount::<T>::get();
 
@@ -481,6 +966,10 @@ impl<T: Config> Pallet<T> {
             },
         );
 
+        if !min_notional.is_zero() {
+            MinNotionalOf::<T>::insert(pair_id, min_notional);
+        }
+
         TradingPairCount::<T>::put(pair_id + 1);
 
         Self::deposit_event(Event::<T>::TradingPairAdded(pair));
@@ -501,6 +990,7 @@ impl<T: Config> Pallet<T> {
         });
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn apply_put_order(
         who: T::AccountId,
         pair_id: TradingPairId,
@@ -509,6 +999,7 @@ impl<T: Config> Pallet<T> {
         amount: BalanceOf<T>,
         price: T::Price,
         reserve_amount: BalanceOf<T>,
+        client_order_id: Option<ClientOrderId>,
     ) -> Result<(), Error<T>> {
         info!(
             target: "runtime::dex::spot",
@@ -526,6 +1017,7 @@ impl<T: Config> Pallet<T> {
             side,
             amount,
             reserve_amount,
+            client_order_id,
         );
 
         Self::try_match_order(&pair, &mut order, pair_id, side, price);
@@ -533,13 +1025,71 @@ impl<T: Config> Pallet<T> {
         Ok(())
     }
 
+    /// Ensures `client_order_id` is well-formed and not in use by the open orders of `who`.
+    fn ensure_valid_client_order_id(
+        who: &T::AccountId,
+        client_order_id: &[u8],
+    ) -> Result<(), Error<T>> {
+        ensure!(
+            !client_order_id.is_empty()
+                && client_order_id.len() <= MAX_CLIENT_ORDER_ID_LEN
+                && client_order_id
+                    .iter()
+                    .all(|&c| c.is_ascii_alphanumeric() || c == b'-' || c == b'_'),
+            Error::<T>::InvalidClientOrderId
+        );
+        ensure!(
+            !OpenOrderOfClientId::<T>::contains_key(who, client_order_id),
+            Error::<T>::DuplicateClientOrderId
+        );
+        Ok(())
+    }
+
+    /// Returns the account whose orders are operated by `signer`.
+    ///
+    /// The delegation is only honored by the order placement and cancellation.
+    fn ensure_trader(
+        signer: &T::AccountId,
+        on_behalf_of: Option<T::AccountId>,
+    ) -> Result<T::AccountId, Error<T>> {
+        match on_behalf_of {
+            Some(owner) => {
+                ensure!(
+                    Self::trading_operator_of(&owner).as_ref() == Some(signer),
+                    Error::<T>::NotTradingOperator
+                );
+                Ok(owner)
+            }
+            None => Ok(signer.clone()),
+        }
+    }
+
+    fn operator_check_weight(on_behalf_of: &Option<T::AccountId>) -> Weight {
+        if on_behalf_of.is_some() {
+            T::DbWeight::get().reads(1)
+        } else {
+            0
+        }
+    }
+
+    /// The settlement accounting of the two deliveries of a match, not covered by the
+    /// `put_order` benchmark: each delivery reads the balances of both sides before and
+    /// after the move, and updates the settlement counters of the pair.
+    fn settlement_weight() -> Weight {
+        T::DbWeight::get().reads_writes(2 * 4, 2)
+    }
+
     fn do_cancel_order(
         who: &T::AccountId,
         pair_id: TradingPairId,
         order_id: OrderId,
     ) -> DispatchResult {
         let pair = Self::trading_pair(pair_id)?;
-        ensure!(pair.tradable, Error::<T>::TradingPairUntradable);
+        // The open orders of a closing trading pair can still be canceled by the owners.
+        ensure!(
+            pair.tradable || Self::is_closing(pair_id),
+            Error::<T>::TradingPairUntradable
+        );
 
         let order = Self::get_order(who, order_id)?;
         ensure!(
@@ -579,17 +1129,103 @@ impl<T: Config> Pallet<T> {
 
         Ok(())
     }
+
+    fn do_reduce_order(
+        who: &T::AccountId,
+        pair_id: TradingPairId,
+        order_id: OrderId,
+        reduce_by: BalanceOf<T>,
+    ) -> DispatchResult {
+        let pair = Self::trading_pair(pair_id)?;
+        ensure!(pair.tradable, Error::<T>::TradingPairUntradable);
+        ensure!(!reduce_by.is_zero(), Error::<T>::ZeroAmount);
+
+        let mut order = Self::get_order(who, order_id)?;
+        ensure!(order.pair_id() == pair_id, Error::<T>::InvalidOrderId);
+        ensure!(
+            order.status == OrderStatus::Created || order.status == OrderStatus::PartialFill,
+            Error::<T>::CancelOrderNotAllowed
+        );
+        ensure!(
+            reduce_by < order.remaining_in_base(),
+            Error::<T>::InvalidReduceAmount
+        );
+
+        let new_remaining = order.remaining_in_base() - reduce_by;
+        Self::check_min_notional(&pair, new_remaining, order.price())?;
+
+        // Keep reserving what the rest of the order still needs.
+        let (release_asset, release_amount) = match order.side() {
+            Side::Sell => (pair.base(), reduce_by),
+            Side::Buy => {
+                let required = Self::convert_base_to_quote(new_remaining, order.price(), &pair)?;
+                (pair.quote(), order.remaining.saturating_sub(required))
+            }
+        };
+        Self::generic_unreserve(who, release_asset, release_amount)?;
+
+        order.decrease_amount_on_reduce(reduce_by, release_amount);
+        order.last_update_at = <frame_system::Pallet<T>>::block_number();
+        OrderInfoOf::<T>::insert(who, order_id, &order);
+
+        Self::deposit_event(Event::<T>::OrderReduced(pair_id, order_id, new_remaining));
+        Self::deposit_order_updated(&order);
+
+        Ok(())
+    }
 }
 
 impl<T: Config> xpallet_assets_registrar::RegistrarHandler for Pallet<T> {
+    fn on_register_trading_pair(
+        token: &AssetId,
+        pip_decimals: u32,
+        tick_decimals: u32,
+        initial_price: u128,
+    ) -> DispatchResult {
+        let native = <T as xpallet_assets_registrar::Config>::NativeAssetId::get();
+        let currency_pair = CurrencyPair::new(native, *token);
+        ensure!(
+            Self::get_trading_pair_by_currency_pair(&currency_pair).is_none(),
+            Error::<T>::TradingPairAlreadyExists
+        );
+
+        // The tick measured by the token must be representable in its decimals.
+        let decimals =
+            Self::currency_decimals_of(*token).ok_or(Error::<T>::InvalidTradingPairAsset)?;
+        ensure!(
+            tick_decimals <= pip_decimals && pip_decimals - tick_decimals <= u32::from(decimals),
+            Error::<T>::InconsistentPairDecimals
+        );
+        ensure!(
+            initial_price != 0
+                && 10_u128
+                    .checked_pow(tick_decimals)
+                    .map_or(false, |tick| initial_price % tick == 0),
+            Error::<T>::InvalidPrice
+        );
+
+        Self::apply_add_trading_pair(
+            currency_pair,
+            pip_decimals,
+            tick_decimals,
+            initial_price.saturated_into(),
+            true,
+            Zero::zero(),
+        );
+        Ok(())
+    }
+
+    /// Schedules the delisting of the trading pairs of `token` after `DelistNoticePeriod`.
     fn on_deregister(token: &AssetId) -> DispatchResult {
+        let effective_block =
+            <frame_system::Pallet<T>>::block_number().saturating_add(T::DelistNoticePeriod::get());
         let pair_len = TradingPairCount::<T>::get();
         for i in 0..pair_len {
-            if let Some(mut pair) = TradingPairOf::<T>::get(i) {
-                if pair.base().eq(token) || pair.quote().eq(token) {
-                    pair.tradable = false;
-                    TradingPairOf::<T>::insert(i, &pair);
-                    Self::deposit_event(Event::<T>::TradingPairUpdated(pair));
+            if let Some(pair) = TradingPairOf::<T>::get(i) {
+                if (pair.base().eq(token) || pair.quote().eq(token))
+                    && Self::delist_status_of(i).is_none()
+                {
+                    Self::apply_schedule_pair_delist(i, effective_block)?;
                 }
             }
         }
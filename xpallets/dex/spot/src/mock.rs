@@ -67,7 +67,7 @@ impl frame_system::Config for Test {
     type AccountId = AccountId;
     type Lookup = IdentityLookup<Self::AccountId>;
     type Header = Header;
-    type Event = ();
+    type Event = Event;
     type BlockHashCount = BlockHashCount;
     type DbWeight = ();
     type Version = ();
@@ -94,7 +94,7 @@ parameter_types! {
 impl pallet_balances::Config for Test {
     type MaxLocks = ();
     type Balance = Balance;
-    type Event = ();
+    type Event = Event;
     type DustRemoval = ();
     type ExistentialDeposit = ExistentialDeposit;
     type AccountStore = System;
@@ -103,9 +103,18 @@ impl pallet_balances::Config for Test {
     type MaxReserves = MaxReserves;
 }
 
+parameter_types! {
+    pub const AutoPauseOnImbalance: bool = true;
+    pub static AutoCancelDustOrders: bool = true;
+    pub const DelistNoticePeriod: u64 = 10;
+}
+
 impl Config for Test {
-    type Event = ();
+    type Event = Event;
     type Price = Price;
+    type AutoPauseOnImbalance = AutoPauseOnImbalance;
+    type AutoCancelDustOrders = AutoCancelDustOrders;
+    type DelistNoticePeriod = DelistNoticePeriod;
     type WeightInfo = ();
 }
 
@@ -114,18 +123,21 @@ parameter_types! {
 }
 
 impl xpallet_assets_registrar::Config for Test {
-    type Event = ();
+    type Event = Event;
     type NativeAssetId = ChainXAssetId;
     type RegistrarHandler = XSpot;
     type WeightInfo = ();
 }
 
 impl xpallet_assets::Config for Test {
-    type Event = ();
+    type Event = Event;
     type Currency = Balances;
     type TreasuryAccount = ();
     type OnCreatedAccount = frame_system::Provider<Test>;
     type OnAssetChanged = ();
+    type OnReceived = ();
+    type MoveGuard = ();
+    type QuarantineOrigin = frame_system::EnsureRoot<AccountId>;
     type WeightInfo = ();
 }
 
@@ -9,7 +9,7 @@ use sp_runtime::{traits::Saturating, RuntimeDebug};
 #[derive(PartialEq, Eq, Clone, Default, Encode, Decode)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
-pub struct FullPairInfo<Price, BlockNumber> {
+pub struct FullPairInfo<Price, Balance, BlockNumber> {
     #[cfg_attr(feature = "std", serde(flatten))]
     pub profile: TradingPairProfile,
     #[cfg_attr(feature = "std", serde(flatten))]
@@ -20,6 +20,38 @@ pub struct FullPairInfo<Price, BlockNumber> {
     pub max_valid_bid: Price,
     /// The minimum valid ask price.
     pub min_valid_ask: Price,
+    /// The cumulative settlement counters.
+    pub settlement: SettlementCounters<Balance>,
+    /// The delisting stage, none if listed.
+    pub delist: Option<DelistStatus<BlockNumber>>,
+}
+
+/// `FullPairInfo` without the delisting stage, returned by version 2 of `XSpotApi`.
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode)]
+pub struct FullPairInfoV2<Price, Balance, BlockNumber> {
+    pub profile: TradingPairProfile,
+    pub handicap: Handicap<Price>,
+    pub pair_info: TradingPairInfo<Price, BlockNumber>,
+    pub max_valid_bid: Price,
+    pub min_valid_ask: Price,
+    pub settlement: SettlementCounters<Balance>,
+}
+
+impl<Price, Balance, BlockNumber> From<FullPairInfoV2<Price, Balance, BlockNumber>>
+    for FullPairInfo<Price, Balance, BlockNumber>
+{
+    fn from(info: FullPairInfoV2<Price, Balance, BlockNumber>) -> Self {
+        Self {
+            profile: info.profile,
+            handicap: info.handicap,
+            pair_info: info.pair_info,
+            max_valid_bid: info.max_valid_bid,
+            min_valid_ask: info.min_valid_ask,
+            settlement: info.settlement,
+            // No trading pair could be delisted before.
+            delist: None,
+        }
+    }
 }
 
 /// Details of an order.
@@ -45,6 +77,39 @@ pub struct RpcOrder<PairId, AccountId, Balance, Price, BlockNumber> {
     pub reserved_balance: Balance,
     /// Block number at which the order details updated.
     pub last_update_at: BlockNumber,
+    /// The order id assigned by the client, if any.
+    pub client_order_id: Option<ClientOrderId>,
+}
+
+/// `RpcOrder` without the client order id, returned by version 5 of `XSpotApi`.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+pub struct RpcOrderV5<PairId, AccountId, Balance, Price, BlockNumber> {
+    pub props: OrderProperty<PairId, AccountId, Balance, Price, BlockNumber>,
+    pub status: OrderStatus,
+    pub remaining: Balance,
+    pub executed_indices: Vec<TradingHistoryIndex>,
+    pub already_filled: Balance,
+    pub reserved_balance: Balance,
+    pub last_update_at: BlockNumber,
+}
+
+impl<PairId, AccountId, Balance, Price, BlockNumber>
+    From<RpcOrderV5<PairId, AccountId, Balance, Price, BlockNumber>>
+    for RpcOrder<PairId, AccountId, Balance, Price, BlockNumber>
+{
+    fn from(order: RpcOrderV5<PairId, AccountId, Balance, Price, BlockNumber>) -> Self {
+        Self {
+            props: order.props,
+            status: order.status,
+            remaining: order.remaining,
+            executed_indices: order.executed_indices,
+            already_filled: order.already_filled,
+            reserved_balance: order.reserved_balance,
+            last_update_at: order.last_update_at,
+            // No order could carry a client order id before.
+            client_order_id: None,
+        }
+    }
 }
 
 #[derive(PartialEq, Eq, Clone, Default, Encode, Decode)]
@@ -57,6 +122,26 @@ pub struct Depth<Price, Balance> {
     pub bids: Vec<(Price, Balance)>,
 }
 
+/// The maximum number of price levels per side returned by the order book.
+pub const MAX_ORDER_BOOK_DEPTH: u32 = 200;
+
+/// The maximum number of accounts per page returned by the competition leaderboard.
+pub const MAX_LEADERBOARD_PAGE_SIZE: u32 = 100;
+
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize, Debug))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct OrderBook<Price, Balance, BlockNumber> {
+    /// List of asks in tuple of (price, unfilled quantity, order count), from the lowest price.
+    pub asks: Vec<(Price, Balance, u32)>,
+    /// List of bids in tuple of (price, unfilled quantity, order count), from the highest price.
+    pub bids: Vec<(Price, Balance, u32)>,
+    /// The latest executed price.
+    pub last_price: Price,
+    /// Block number at which the order book is taken.
+    pub at_block: BlockNumber,
+}
+
 impl<T: Config> Pallet<T> {
     /// Returns the range of a valid quotation for a trading pair.
     fn get_quotation_range(profile: &TradingPairProfile) -> (T::Price, T::Price) {
@@ -75,11 +160,18 @@ impl<T: Config> Pallet<T> {
         (min_valid_ask, max_valid_bid)
     }
 
-    /// Get the overall info of all trading pairs.
-    pub fn trading_pairs() -> Vec<FullPairInfo<T::Price, T::BlockNumber>> {
+    /// Get the overall info of all trading pairs, the retired ones are excluded
+    /// unless `include_retired` is true.
+    pub fn trading_pairs(
+        include_retired: bool,
+    ) -> Vec<FullPairInfo<T::Price, BalanceOf<T>, T::BlockNumber>> {
         let pair_count = Self::trading_pair_count();
         let mut pairs = Vec::with_capacity(pair_count as usize);
         for pair_id in 0..pair_count {
+            let delist = Self::delist_status_of(pair_id);
+            if !include_retired && delist == Some(DelistStatus::Retired) {
+                continue;
+            }
             if let Some(profile) = Self::trading_pair_of(pair_id) {
                 let (min_valid_ask, max_valid_bid) = Self::get_quotation_range(&profile);
                 let handicap = Self::handicap_of(pair_id);
@@ -91,13 +183,15 @@ impl<T: Config> Pallet<T> {
                     pair_info,
                     max_valid_bid,
                     min_valid_ask,
+                    settlement: Self::settlement_counters_of(pair_id),
+                    delist,
                 });
             }
         }
         pairs
     }
 
-    /// Get the orders of an account.
+    /// Get the orders of an account, sorted by the order index in descending order.
     ///
     /// The returned data will be empty if `page_index` is invalid.
     ///
@@ -107,7 +201,10 @@ impl<T: Config> Pallet<T> {
         page_index: u32,
         page_size: u32,
     ) -> Vec<RpcOrder<TradingPairId, T::AccountId, BalanceOf<T>, T::Price, T::BlockNumber>> {
-        OrderInfoOf::<T>::iter_prefix_values(who)
+        let mut orders = OrderInfoOf::<T>::iter_prefix_values(who).collect::<Vec<_>>();
+        orders.sort_unstable_by(|a, b| b.id().cmp(&a.id()));
+        orders
+            .into_iter()
             .flat_map(|order| {
                 Self::trading_pair(order.pair_id())
                     .ok()
@@ -121,6 +218,10 @@ impl<T: Config> Pallet<T> {
                         Side::Sell => Some(order.remaining),
                     })
                     .map(|reserved_balance| RpcOrder {
+                        client_order_id: Self::client_order_id_of(
+                            &order.props.submitter,
+                            order.props.id,
+                        ),
                         props: order.props,
                         status: order.status,
                         remaining: order.remaining,
@@ -130,23 +231,29 @@ impl<T: Config> Pallet<T> {
                         last_update_at: order.last_update_at,
                     })
             })
-            .skip((page_index * page_size) as usize)
+            .skip(page_index.saturating_mul(page_size) as usize)
             .take(page_size as usize)
             .collect()
     }
 
     /// Returns the sum of unfilled quantities at `price` of a trading pair `pair_id`.
     fn get_commulative_qty(pair_id: TradingPairId, price: T::Price) -> u128 {
+        Self::get_aggregated_quotation(pair_id, price).0
+    }
+
+    /// Returns the sum of unfilled quantities and the number of orders at `price`
+    /// of a trading pair `pair_id`.
+    fn get_aggregated_quotation(pair_id: TradingPairId, price: T::Price) -> (u128, u32) {
         QuotationsOf::<T>::get(pair_id, price)
             .iter()
             .filter_map(|(trader, order_id)| OrderInfoOf::<T>::get(trader, order_id))
-            .map(|order| {
-                order
+            .fold((0u128, 0u32), |(qty, count), order| {
+                let unfilled = order
                     .amount()
                     .saturating_sub(order.already_filled)
-                    .saturated_into::<u128>()
+                    .saturated_into::<u128>();
+                (qty.saturating_add(unfilled), count.saturating_add(1))
             })
-            .sum()
     }
 
     /// Get the depth of a trading pair around the handicap given the depth size.
@@ -188,13 +295,120 @@ impl<T: Config> Pallet<T> {
             Depth { asks, bids }
         })
     }
+
+    /// Returns the non-empty price levels of `prices` in tuple of (price, unfilled quantity, order count).
+    fn aggregate_quotations(
+        pair_id: TradingPairId,
+        prices: impl Iterator<Item = u128>,
+        depth: u32,
+    ) -> Vec<(T::Price, BalanceOf<T>, u32)> {
+        prices
+            .filter_map(|price| {
+                let price: T::Price = price.saturated_into();
+                let (qty, count) = Self::get_aggregated_quotation(pair_id, price);
+                if qty.is_zero() {
+                    None
+                } else {
+                    Some((price, qty.saturated_into(), count))
+                }
+            })
+            .take(depth as usize)
+            .collect()
+    }
+
+    /// Get the order book of a trading pair aggregated per price level.
+    ///
+    /// The depth is capped at `MAX_ORDER_BOOK_DEPTH`.
+    pub fn order_book(
+        pair_id: TradingPairId,
+        depth: u32,
+    ) -> Option<OrderBook<T::Price, BalanceOf<T>, T::BlockNumber>> {
+        Self::trading_pair_of(pair_id).map(|pair| {
+            let Handicap {
+                lowest_ask,
+                highest_bid,
+            } = Self::handicap_of(pair_id);
+
+            let (min_valid_ask, max_valid_bid) = Self::get_quotation_range(&pair);
+
+            let step = pair.tick().saturated_into::<u128>();
+            let depth = depth.min(MAX_ORDER_BOOK_DEPTH);
+
+            let (lowest_ask, max_valid_bid) = (
+                lowest_ask.saturated_into::<u128>(),
+                max_valid_bid.saturated_into::<u128>(),
+            );
+            let asks = Self::aggregate_quotations(
+                pair_id,
+                (0..)
+                    .map(|x| lowest_ask + step * x)
+                    .take_while(|&x| x <= max_valid_bid),
+                depth,
+            );
+
+            let (highest_bid, min_valid_ask) = (
+                highest_bid.saturated_into::<u128>(),
+                min_valid_ask.saturated_into::<u128>(),
+            );
+            let bids = Self::aggregate_quotations(
+                pair_id,
+                (0..)
+                    .map_while(|x| highest_bid.checked_sub(step * x))
+                    .take_while(|&x| x >= min_valid_ask),
+                depth,
+            );
+
+            OrderBook {
+                asks,
+                bids,
+                last_price: Self::trading_pair_info_of(pair_id)
+                    .map(|info| info.latest_price)
+                    .unwrap_or_default(),
+                at_block: frame_system::Pallet::<T>::block_number(),
+            }
+        })
+    }
+
+    /// Get the minimum notional of a trading pair, the orders below it are rejected
+    /// with `NotionalTooSmall`.
+    ///
+    /// Zero means no minimum, `None` if the trading pair does not exist.
+    pub fn min_notional(pair_id: TradingPairId) -> Option<BalanceOf<T>> {
+        Self::trading_pair_of(pair_id).map(|_| Self::min_notional_of(pair_id))
+    }
+
+    /// Get the top accounts of a trading competition sorted by the volume in descending order.
+    ///
+    /// Only the top `MAX_LEADERBOARD_SIZE` accounts are ranked, the page size is capped
+    /// at `MAX_LEADERBOARD_PAGE_SIZE`.
+    pub fn competition_leaderboard(
+        id: CompetitionId,
+        page_index: u32,
+        page_size: u32,
+    ) -> Vec<(T::AccountId, BalanceOf<T>)> {
+        let page_size = page_size.min(MAX_LEADERBOARD_PAGE_SIZE);
+        Self::competition_leaderboard_of(id)
+            .into_iter()
+            .skip(page_index.saturating_mul(page_size) as usize)
+            .take(page_size as usize)
+            .collect()
+    }
+
+    /// Get the total native coins reserved by the open orders of all the accounts.
+    ///
+    /// NOTE: all the accounts having reservations are iterated, which is only affordable off-chain.
+    pub fn total_native_reserves() -> BalanceOf<T> {
+        NativeReserves::<T>::iter_values().fold(Zero::zero(), |acc, x| acc + x)
+    }
 }
 
 #[cfg(test)]
 mod rpc_tests {
     use super::*;
     use crate::mock::*;
-    use crate::tests::{t_issue_pcx, t_put_order_sell, t_set_handicap};
+    use crate::tests::{
+        t_generic_issue, t_issue_pcx, t_put_order_buy, t_put_order_sell, t_set_handicap,
+    };
     use frame_support::assert_ok;
 
     #[test]
@@ -219,4 +433,139 @@ mod rpc_tests {
             });
         });
     }
+
+    #[test]
+    fn rpc_order_book_should_aggregate_unfilled_amounts() {
+        ExtBuilder::default().build_and_execute(|| {
+            let pair_id = 0;
+            let trading_pair = XSpot::trading_pair_of(pair_id).unwrap();
+
+            t_set_handicap(pair_id, 1_000_000, 1_100_000);
+
+            t_generic_issue(trading_pair.quote(), 1, 10);
+            t_issue_pcx(2, 2000);
+
+            assert_ok!(t_put_order_buy(1, pair_id, 1000, 1_000_000));
+            assert_ok!(t_put_order_buy(1, pair_id, 1000, 1_000_000));
+            assert_ok!(t_put_order_buy(1, pair_id, 1000, 999_900));
+            // Partially fill the first bid at 1_000_000.
+            assert_ok!(t_put_order_sell(2, pair_id, 500, 1_000_000));
+            assert_eq!(XSpot::order_info_of(1, 0).unwrap().already_filled, 500);
+
+            assert_ok!(t_put_order_sell(2, pair_id, 300, 1_100_000));
+            assert_ok!(t_put_order_sell(2, pair_id, 200, 1_100_000));
+            assert_ok!(t_put_order_sell(2, pair_id, 100, 1_100_100));
+
+            assert_eq!(
+                XSpot::order_book(pair_id, 100).unwrap(),
+                OrderBook {
+                    asks: vec![(1_100_000, 500, 2), (1_100_100, 100, 1)],
+                    bids: vec![(1_000_000, 1500, 2), (999_900, 1000, 1)],
+                    last_price: 1_000_000,
+                    at_block: 1,
+                }
+            );
+
+            let order_book = XSpot::order_book(pair_id, 1).unwrap();
+            assert_eq!(order_book.asks, vec![(1_100_000, 500, 2)]);
+            assert_eq!(order_book.bids, vec![(1_000_000, 1500, 2)]);
+
+            assert!(XSpot::order_book(100, 100).is_none());
+        });
+    }
+
+    #[test]
+    fn rpc_min_notional_should_work() {
+        ExtBuilder::default().build_and_execute(|| {
+            assert_eq!(XSpot::min_notional(0), Some(0));
+            assert_ok!(XSpot::set_min_notional(Origin::root(), 0, 10));
+            assert_eq!(XSpot::min_notional(0), Some(10));
+            assert_eq!(XSpot::min_notional(100), None);
+        });
+    }
+
+    #[test]
+    fn rpc_order_book_depth_should_be_capped() {
+        ExtBuilder::default().build_and_execute(|| {
+            let pair_id = 0;
+            let who = 1;
+
+            t_set_handicap(pair_id, 1_000_000, 1_100_000);
+            assert_ok!(XSpot::set_price_fluctuation(Origin::root(), pair_id, 300));
+
+            t_issue_pcx(who, 10_000);
+            for i in 0..MAX_ORDER_BOOK_DEPTH + 10 {
+                assert_ok!(t_put_order_sell(
+                    who,
+                    pair_id,
+                    10,
+                    1_100_000 + 100 * i as u128
+                ));
+            }
+
+            let asks = XSpot::order_book(pair_id, u32::MAX).unwrap().asks;
+            assert_eq!(asks.len(), MAX_ORDER_BOOK_DEPTH as usize);
+            assert_eq!(asks[0], (1_100_000, 10, 1));
+            assert_eq!(
+                asks[MAX_ORDER_BOOK_DEPTH as usize - 1],
+                (1_100_000 + 100 * (MAX_ORDER_BOOK_DEPTH as u128 - 1), 10, 1)
+            );
+        });
+    }
+
+    #[test]
+    fn rpc_competition_leaderboard_should_be_sorted_and_paged() {
+        ExtBuilder::default().build_and_execute(|| {
+            let id = 0;
+            for (who, volume) in [(3, 100), (2, 300), (1, 100), (4, 100)] {
+                XSpot::add_competition_volume(id, &who, volume);
+            }
+            XSpot::add_competition_volume(id, &4, 100);
+
+            assert_eq!(
+                XSpot::competition_leaderboard(id, 0, 10),
+                vec![(2, 300), (4, 200), (1, 100), (3, 100)]
+            );
+            assert_eq!(XSpot::competition_leaderboard(id, 1, 3), vec![(3, 100)]);
+            assert!(XSpot::competition_leaderboard(id, 2, 3).is_empty());
+
+            for who in 10..MAX_LEADERBOARD_PAGE_SIZE as u64 + 10 {
+                XSpot::add_competition_volume(id, &who, 1);
+            }
+            assert_eq!(
+                XSpot::competition_leaderboard(id, 0, u32::MAX).len(),
+                MAX_LEADERBOARD_PAGE_SIZE as usize
+            );
+
+            // Only the top accounts are kept, the others rank in once they catch up.
+            let leaderboard = XSpot::competition_leaderboard_of(id);
+            assert_eq!(leaderboard.len(), MAX_LEADERBOARD_SIZE);
+            let outsider = MAX_LEADERBOARD_PAGE_SIZE as u64 + 9;
+            assert!(leaderboard.iter().all(|(who, _)| *who != outsider));
+            XSpot::add_competition_volume(id, &outsider, 1000);
+            assert_eq!(
+                XSpot::competition_leaderboard(id, 0, 1),
+                vec![(outsider, 1001)]
+            );
+        });
+    }
+
+    #[test]
+    fn rpc_total_native_reserves_should_sum_all_accounts() {
+        ExtBuilder::default().build_and_execute(|| {
+            let pair_id = 0;
+            t_set_handicap(pair_id, 1_000_000, 1_100_000);
+            assert_eq!(XSpot::total_native_reserves(), 0);
+
+            t_issue_pcx(2, 1000);
+            t_issue_pcx(3, 1000);
+            assert_ok!(t_put_order_sell(2, pair_id, 300, 1_100_000));
+            assert_ok!(t_put_order_sell(3, pair_id, 200, 1_100_100));
+            assert_eq!(XSpot::total_native_reserves(), 500);
+            assert_eq!(
+                XSpot::total_native_reserves(),
+                Balances::reserved_balance(2) + Balances::reserved_balance(3)
+            );
+        });
+    }
 }
@@ -4,7 +4,7 @@
 
 use sp_std::collections::btree_map::BTreeMap;
 
-use frame_support::{assert_noop, assert_ok};
+use frame_support::{assert_noop, assert_ok, traits::Hooks};
 use xpallet_assets::AssetType;
 
 use super::mock::*;
@@ -37,7 +37,7 @@ fn t_trading_pair_of(idx: TradingPairId) -> TradingPairProfile {
     XSpot::trading_pair_of(idx).unwrap()
 }
 
-fn t_put_order_buy(
+pub(crate) fn t_put_order_buy(
     who: AccountId,
     pair_idx: TradingPairId,
     amount: Balance,
@@ -50,6 +50,10 @@ fn t_put_order_buy(
         Side::Buy,
         amount,
         price,
+        None,
+        None,
+        false,
+        false,
     )
 }
 
@@ -66,11 +70,15 @@ pub(crate) fn t_put_order_sell(
         Side::Sell,
         amount,
         price,
+        None,
+        None,
+        false,
+        false,
     )
 }
 
 fn t_cancel_order(who: AccountId, pair_id: TradingPairId, order_id: OrderId) -> DispatchResult {
-    XSpot::cancel_order(Origin::signed(who), pair_id, order_id)
+    XSpot::cancel_order(Origin::signed(who), pair_id, order_id, None)
 }
 
 pub(crate) fn t_set_handicap(pair_idx: TradingPairId, highest_bid: Price, lowest_ask: Price) {
@@ -95,6 +103,7 @@ pub(crate) fn t_add_trading_pair(
     tick_decimals: u32,
     latest_price: Price,
     tradable: bool,
+    min_notional: Balance,
 ) {
     assert_ok!(XSpot::add_trading_pair(
         Origin::root(),
@@ -102,7 +111,8 @@ pub(crate) fn t_add_trading_pair(
         pip_decimals,
         tick_decimals,
         latest_price,
-        tradable
+        tradable,
+        min_notional
     ));
 }
 
@@ -110,7 +120,7 @@ pub(crate) fn t_add_trading_pair(
 fn add_trading_pair_should_work() {
     ExtBuilder::default().build_and_execute(|| {
         let pair = CurrencyPair::new(EOS, ETH);
-        t_add_trading_pair(pair.clone(), 2, 1, 100, true);
+        t_add_trading_pair(pair.clone(), 2, 1, 100, true, 0);
         assert_eq!(XSpot::trading_pair_count(), 3);
         assert_eq!(
             XSpot::get_trading_pair_by_currency_pair(&pair)
@@ -125,7 +135,7 @@ fn add_trading_pair_should_work() {
 fn update_trading_pair_should_work() {
     ExtBuilder::default().build_and_execute(|| {
         let pair = CurrencyPair::new(EOS, ETH);
-        t_add_trading_pair(pair, 2, 1, 100, true);
+        t_add_trading_pair(pair, 2, 1, 100, true, 0);
         assert_eq!(t_trading_pair_of(2).tick_decimals, 1);
         assert!(t_trading_pair_of(2).tradable);
 
@@ -345,7 +355,7 @@ fn cancel_order_should_work() {
         assert_ok!(t_put_order_sell(2, 0, 500, 1_000_200));
 
         assert_eq!(XSpot::quotations_of(0, 1_000_100), vec![(1, 1)]);
-        assert_ok!(XSpot::cancel_order(Origin::signed(1), 0, 1));
+        assert_ok!(XSpot::cancel_order(Origin::signed(1), 0, 1, None));
 
         assert_eq!(XSpot::quotations_of(0, 1_200_000), vec![]);
         assert_eq!(XSpot::order_info_of(1, 1), None);
@@ -507,3 +517,1408 @@ fn quotations_order_should_be_preserved_when_removing_orders_and_quotations() {
         assert_eq!(XSpot::quotations_of(0, 2_000_000), [(2, 1), (5, 0), (6, 0)]);
     })
 }
+
+#[test]
+fn settlement_counters_should_be_balanced_after_fill() {
+    ExtBuilder::default().build_and_execute(|| {
+        let trading_pair = XSpot::trading_pair_of(0).unwrap();
+
+        t_set_handicap(0, 1_000_000, 1_100_000);
+
+        t_generic_issue(trading_pair.quote(), 1, 10);
+        t_issue_pcx(2, 2000);
+
+        assert_ok!(t_put_order_buy(1, 0, 1000, 1_000_100));
+        assert_ok!(t_put_order_sell(2, 0, 500, 1_000_100));
+
+        let counters = XSpot::settlement_counters_of(0);
+        assert_eq!(counters.base_debited, 500);
+        assert_eq!(counters.base_credited, 500);
+        assert_eq!(counters.quote_debited, counters.quote_credited);
+        assert!(counters.is_balanced());
+        assert!(t_trading_pair_of(0).tradable);
+    })
+}
+
+#[test]
+fn settlement_imbalance_should_pause_trading_pair() {
+    ExtBuilder::default().build_and_execute(|| {
+        System::set_block_number(1);
+        let trading_pair = XSpot::trading_pair_of(0).unwrap();
+
+        t_set_handicap(0, 1_000_000, 1_100_000);
+
+        t_generic_issue(trading_pair.quote(), 1, 10);
+        t_issue_pcx(2, 2000);
+
+        XSpot::inject_settlement_imbalance(0, 7, 0);
+
+        assert_ok!(t_put_order_buy(1, 0, 1000, 1_000_100));
+        assert_ok!(t_put_order_sell(2, 0, 500, 1_000_100));
+
+        let counters = XSpot::settlement_counters_of(0);
+        assert_eq!(counters.base_delta(), 7);
+        assert_eq!(counters.quote_delta(), 0);
+
+        let events = System::events()
+            .into_iter()
+            .map(|record| record.event)
+            .collect::<Vec<_>>();
+        let spot_event = |event: crate::Event<Test>| crate::mock::Event::XSpot(event);
+        assert!(events.contains(&spot_event(crate::Event::SettlementImbalance(0, 7, 0))));
+
+        let paused = t_trading_pair_of(0);
+        assert!(!paused.tradable);
+        assert!(events.contains(&spot_event(crate::Event::TradingPairUpdated(paused))));
+
+        // No more orders can be put on the paused trading pair.
+        assert_noop!(
+            t_put_order_sell(2, 0, 500, 1_000_100),
+            Error::<Test>::TradingPairUntradable
+        );
+    })
+}
+
+#[test]
+fn force_cancel_pair_orders_of_delisted_pair_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        System::set_block_number(1);
+        let trading_pair = XSpot::trading_pair_of(0).unwrap();
+        let quote = trading_pair.quote();
+
+        t_set_handicap(0, 1_000_000, 1_100_000);
+
+        t_generic_issue(quote, 1, 10);
+        t_generic_issue(quote, 2, 10);
+        t_issue_pcx(3, 2000);
+
+        assert_ok!(t_put_order_buy(1, 0, 1000, 1_000_000));
+        assert_ok!(t_put_order_buy(1, 0, 1000, 1_000_100));
+        assert_ok!(t_put_order_buy(2, 0, 1000, 1_000_000));
+        assert_ok!(t_put_order_sell(3, 0, 500, 1_000_200));
+
+        assert!(XAssets::asset_balance_of(&1, &quote, AssetType::ReservedDexSpot) > 0);
+        assert!(XAssets::asset_balance_of(&2, &quote, AssetType::ReservedDexSpot) > 0);
+        assert_eq!(XSpot::native_reserves(&3), 500);
+
+        // Delisting the quote asset schedules the delisting of the trading pair.
+        assert_ok!(XAssetsRegistrar::deregister(Origin::root(), quote));
+        assert_eq!(
+            XSpot::delist_status_of(0),
+            Some(DelistStatus::Scheduled(1 + DelistNoticePeriod::get()))
+        );
+        assert_noop!(
+            t_put_order_sell(3, 0, 500, 1_000_200),
+            Error::<Test>::TradingPairDelisting
+        );
+
+        assert_noop!(
+            XSpot::force_cancel_pair_orders(Origin::signed(1), 0, 2),
+            DispatchError::BadOrigin
+        );
+
+        let spot_event = |event: crate::Event<Test>| crate::mock::Event::XSpot(event);
+
+        assert_ok!(XSpot::force_cancel_pair_orders(Origin::root(), 0, 2));
+        System::assert_last_event(spot_event(crate::Event::PairOrdersForceCanceled(
+            0, 2, false,
+        )));
+
+        assert_ok!(XSpot::force_cancel_pair_orders(Origin::root(), 0, 2));
+        System::assert_last_event(spot_event(crate::Event::PairOrdersForceCanceled(
+            0, 2, true,
+        )));
+
+        // The order book is empty and all the reservations have been released.
+        assert_eq!(
+            QuotationsOf::<Test>::iter_prefix_values(0)
+                .flatten()
+                .count(),
+            0
+        );
+        for who in [1, 2, 3] {
+            assert_eq!(OrderInfoOf::<Test>::iter_prefix(who).count(), 0);
+        }
+        assert_eq!(
+            XAssets::asset_balance_of(&1, &quote, AssetType::ReservedDexSpot),
+            0
+        );
+        assert_eq!(
+            XAssets::asset_balance_of(&2, &quote, AssetType::ReservedDexSpot),
+            0
+        );
+        assert_eq!(t_generic_free_balance(1, quote), 10);
+        assert_eq!(t_generic_free_balance(2, quote), 10);
+        assert_eq!(XSpot::native_reserves(&3), 0);
+        assert_eq!(Balances::reserved_balance(3), 0);
+        assert_eq!(Balances::free_balance(3), 2000);
+    })
+}
+
+#[test]
+fn min_notional_should_reject_small_orders() {
+    ExtBuilder::default().build_and_execute(|| {
+        System::set_block_number(1);
+        let trading_pair = XSpot::trading_pair_of(0).unwrap();
+
+        t_generic_issue(trading_pair.quote(), 1, 100);
+        t_issue_pcx(2, 20_000);
+
+        assert_noop!(
+            XSpot::set_min_notional(Origin::signed(1), 0, 10),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            XSpot::set_min_notional(Origin::root(), 9, 10),
+            Error::<Test>::InvalidTradingPair
+        );
+        assert_ok!(XSpot::set_min_notional(Origin::root(), 0, 10));
+        assert_eq!(XSpot::min_notional_of(0), 10);
+        System::assert_last_event(crate::mock::Event::XSpot(crate::Event::MinNotionalUpdated(
+            0, 10,
+        )));
+
+        // 4_999 * 2_000_000 / 10^9 = 9 < 10
+        assert_eq!(t_convert_base_to_quote(4_999, 2_000_000, &trading_pair), 9);
+        assert_noop!(
+            t_put_order_buy(1, 0, 4_999, 2_000_000),
+            Error::<Test>::NotionalTooSmall
+        );
+        assert_noop!(
+            t_put_order_sell(2, 0, 4_999, 2_000_100),
+            Error::<Test>::NotionalTooSmall
+        );
+
+        // The orders exactly at the minimum are accepted.
+        assert_ok!(t_put_order_buy(1, 0, 5_000, 2_000_000));
+        assert_ok!(t_put_order_sell(2, 0, 5_000, 2_000_100));
+        assert!(XSpot::order_info_of(1, 0).is_some());
+        assert!(XSpot::order_info_of(2, 0).is_some());
+
+        // The minimum can be set on the creation of trading pair.
+        t_add_trading_pair(CurrencyPair::new(EOS, ETH), 2, 1, 100, true, 1_000);
+        assert_eq!(XSpot::min_notional_of(2), 1_000);
+    })
+}
+
+#[test]
+fn dust_remainder_should_be_canceled_after_partial_fill() {
+    ExtBuilder::default().build_and_execute(|| {
+        let trading_pair = XSpot::trading_pair_of(0).unwrap();
+        let quote = trading_pair.quote();
+
+        t_generic_issue(quote, 1, 100);
+        t_issue_pcx(2, 20_000);
+        t_issue_pcx(3, 20_000);
+
+        assert_ok!(XSpot::set_min_notional(Origin::root(), 0, 10));
+
+        // The remainder of maker order is worth 1_000 * 2_000_000 / 10^9 = 2.
+        assert_ok!(t_put_order_sell(2, 0, 6_000, 2_000_000));
+        assert_ok!(t_put_order_buy(1, 0, 5_000, 2_000_000));
+
+        assert_eq!(XSpot::order_info_of(2, 0), None);
+        assert!(XSpot::quotations_of(0, 2_000_000).is_empty());
+        assert_eq!(XSpot::native_reserves(&2), 0);
+        assert_eq!(Balances::reserved_balance(2), 0);
+        assert_eq!(Balances::free_balance(2), 15_000);
+        assert_eq!(t_generic_free_balance(2, quote), 10);
+
+        // The remainder of taker order is canceled as well.
+        assert_ok!(t_put_order_sell(3, 0, 5_000, 2_000_000));
+        assert_ok!(t_put_order_buy(1, 0, 6_000, 2_000_000));
+
+        assert_eq!(XSpot::order_info_of(1, 1), None);
+        assert!(XSpot::quotations_of(0, 2_000_000).is_empty());
+        assert_eq!(
+            XAssets::asset_balance_of(&1, &quote, AssetType::ReservedDexSpot),
+            0
+        );
+        assert_eq!(t_generic_free_balance(1, quote), 80);
+        assert_eq!(t_generic_free_balance(1, trading_pair.base()), 10_000);
+        assert!(XSpot::settlement_counters_of(0).is_balanced());
+    })
+}
+
+#[test]
+fn dust_remainder_should_rest_if_auto_cancel_disabled() {
+    ExtBuilder::default().build_and_execute(|| {
+        AutoCancelDustOrders::set(false);
+        let trading_pair = XSpot::trading_pair_of(0).unwrap();
+
+        t_generic_issue(trading_pair.quote(), 1, 100);
+        t_issue_pcx(2, 20_000);
+
+        assert_ok!(XSpot::set_min_notional(Origin::root(), 0, 10));
+
+        assert_ok!(t_put_order_sell(2, 0, 6_000, 2_000_000));
+        assert_ok!(t_put_order_buy(1, 0, 5_000, 2_000_000));
+
+        let maker_order = XSpot::order_info_of(2, 0).unwrap();
+        assert_eq!(maker_order.status, OrderStatus::PartialFill);
+        assert_eq!(maker_order.remaining_in_base(), 1_000);
+        assert_eq!(XSpot::quotations_of(0, 2_000_000), vec![(2, 0)]);
+        assert_eq!(XSpot::native_reserves(&2), 1_000);
+
+        AutoCancelDustOrders::set(true);
+    })
+}
+
+fn t_register_abc(auto_pair: Option<(u32, u32, u128)>) -> DispatchResult {
+    let abc = xpallet_assets_registrar::AssetInfo::new::<Test>(
+        b"ABC".to_vec(),
+        b"ABC".to_vec(),
+        xpallet_assets_registrar::Chain::Ethereum,
+        8,
+        b"abc".to_vec(),
+    )
+    .unwrap();
+    XAssetsRegistrar::register(Origin::root(), 100, abc, true, false, auto_pair, None)
+}
+
+#[test]
+fn register_with_auto_pair_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(t_register_abc(Some((9, 2, 100_000))));
+
+        let pair =
+            XSpot::get_trading_pair_by_currency_pair(&CurrencyPair::new(xp_protocol::PCX, 100))
+                .unwrap();
+        assert_eq!(pair.id, 2);
+        assert_eq!((pair.pip_decimals, pair.tick_decimals), (9, 2));
+        assert!(pair.tradable);
+        assert_eq!(
+            XSpot::trading_pair_info_of(2).unwrap().latest_price,
+            100_000
+        );
+
+        System::assert_has_event(crate::mock::Event::XAssetsRegistrar(
+            xpallet_assets_registrar::Event::Registered(100, false),
+        ));
+        System::assert_last_event(crate::mock::Event::XSpot(crate::Event::TradingPairAdded(
+            pair,
+        )));
+    })
+}
+
+#[test]
+fn register_with_inconsistent_auto_pair_should_not_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        // The tick 10^-9 of ABC is not representable with its 8 decimals.
+        assert_noop!(
+            t_register_abc(Some((9, 0, 100_000))),
+            Error::<Test>::InconsistentPairDecimals
+        );
+        assert_noop!(
+            t_register_abc(Some((2, 4, 100_000))),
+            Error::<Test>::InconsistentPairDecimals
+        );
+        assert_noop!(
+            t_register_abc(Some((9, 2, 100_001))),
+            Error::<Test>::InvalidPrice
+        );
+        assert!(xpallet_assets_registrar::Pallet::<Test>::asset_info_of(100).is_none());
+        assert_eq!(XSpot::trading_pair_count(), 2);
+    })
+}
+
+#[test]
+fn register_without_auto_pair_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(t_register_abc(None));
+        assert!(xpallet_assets_registrar::Pallet::<Test>::asset_info_of(100).is_some());
+        assert!(XSpot::get_trading_pair_by_currency_pair(&CurrencyPair::new(
+            xp_protocol::PCX,
+            100
+        ))
+        .is_none());
+        assert_eq!(XSpot::trading_pair_count(), 2);
+    })
+}
+
+#[test]
+fn trading_operator_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        let pair_id = 0;
+        let (owner, operator) = (1, 2);
+        t_set_handicap(pair_id, 1_000_000, 1_100_000);
+        t_issue_pcx(owner, 1000);
+
+        let put_order_for = |on_behalf_of| {
+            XSpot::put_order(
+                Origin::signed(operator),
+                pair_id,
+                OrderType::Limit,
+                Side::Sell,
+                100,
+                1_210_000,
+                on_behalf_of,
+                None,
+                false,
+                false,
+            )
+        };
+
+        // Not delegated yet.
+        assert_noop!(
+            put_order_for(Some(owner)),
+            Error::<Test>::NotTradingOperator
+        );
+
+        assert_ok!(XSpot::set_trading_operator(
+            Origin::signed(owner),
+            Some(operator)
+        ));
+        System::assert_last_event(crate::mock::Event::XSpot(
+            crate::Event::TradingOperatorUpdated(owner, Some(operator)),
+        ));
+
+        // The order is put and reserved by the owner.
+        assert_ok!(put_order_for(Some(owner)));
+        System::assert_last_event(crate::mock::Event::XSpot(crate::Event::OrderPutByOperator(
+            owner, operator, pair_id, 0,
+        )));
+        assert!(XSpot::order_info_of(owner, 0).is_some());
+        assert_eq!(XSpot::order_count_of(operator), 0);
+        assert_eq!(Balances::reserved_balance(owner), 100);
+        assert_eq!(Balances::free_balance(owner), 900);
+
+        // The operator can only operate the orders of the delegating owner.
+        assert_noop!(put_order_for(Some(3)), Error::<Test>::NotTradingOperator);
+
+        assert_ok!(XSpot::cancel_order(
+            Origin::signed(operator),
+            pair_id,
+            0,
+            Some(owner)
+        ));
+        System::assert_last_event(crate::mock::Event::XSpot(
+            crate::Event::OrderCanceledByOperator(owner, operator, pair_id, 0),
+        ));
+        assert!(XSpot::order_info_of(owner, 0).is_none());
+        assert_eq!(Balances::reserved_balance(owner), 0);
+        assert_eq!(Balances::free_balance(owner), 1000);
+    })
+}
+
+#[test]
+fn trading_operator_can_not_transfer_owner_funds() {
+    ExtBuilder::default().build_and_execute(|| {
+        let (owner, operator) = (1, 2);
+        t_issue_pcx(owner, 1000);
+        assert_ok!(XSpot::set_trading_operator(
+            Origin::signed(owner),
+            Some(operator)
+        ));
+
+        // The transfer signed by the operator only touches the funds of the operator.
+        assert!(Balances::transfer(Origin::signed(operator), 3, 100).is_err());
+        assert_eq!(Balances::free_balance(owner), 1000);
+        assert_eq!(Balances::free_balance(3), 0);
+    })
+}
+
+#[test]
+fn revoke_trading_operator_should_take_effect_immediately() {
+    ExtBuilder::default().build_and_execute(|| {
+        let pair_id = 0;
+        let (owner, operator) = (1, 2);
+        t_set_handicap(pair_id, 1_000_000, 1_100_000);
+        t_issue_pcx(owner, 1000);
+
+        assert_ok!(XSpot::set_trading_operator(
+            Origin::signed(owner),
+            Some(operator)
+        ));
+        assert_ok!(XSpot::put_order(
+            Origin::signed(operator),
+            pair_id,
+            OrderType::Limit,
+            Side::Sell,
+            100,
+            1_210_000,
+            Some(owner),
+            None,
+            false,
+            false
+        ));
+
+        assert_ok!(XSpot::set_trading_operator(Origin::signed(owner), None));
+        assert_eq!(XSpot::trading_operator_of(owner), None);
+
+        assert_noop!(
+            XSpot::cancel_order(Origin::signed(operator), pair_id, 0, Some(owner)),
+            Error::<Test>::NotTradingOperator
+        );
+        assert_noop!(
+            XSpot::put_order(
+                Origin::signed(operator),
+                pair_id,
+                OrderType::Limit,
+                Side::Sell,
+                100,
+                1_210_000,
+                Some(owner),
+                None,
+                false,
+                false
+            ),
+            Error::<Test>::NotTradingOperator
+        );
+
+        // The owner is still able to cancel the order.
+        assert_ok!(t_cancel_order(owner, pair_id, 0));
+        assert_eq!(Balances::reserved_balance(owner), 0);
+    })
+}
+
+#[test]
+fn orders_should_be_sorted_by_index_desc() {
+    ExtBuilder::default().build_and_execute(|| {
+        let (pair_id, who) = (0, 1);
+        t_set_handicap(pair_id, 1_000_000, 1_100_000);
+        t_issue_pcx(who, 1000);
+        for _ in 0..3 {
+            assert_ok!(t_put_order_sell(who, pair_id, 100, 1_210_000));
+        }
+
+        let ids = |page_index, page_size| {
+            XSpot::orders(who, page_index, page_size)
+                .into_iter()
+                .map(|order| order.props.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids(0, 2), vec![2, 1]);
+        assert_eq!(ids(1, 2), vec![0]);
+        assert!(ids(2, 2).is_empty());
+        assert!(ids(u32::MAX, u32::MAX).is_empty());
+    })
+}
+
+#[test]
+fn circuit_breaker_should_halt_matching_until_resume_block() {
+    ExtBuilder::default().build_and_execute(|| {
+        let trading_pair = XSpot::trading_pair_of(0).unwrap();
+        let (base, quote) = (trading_pair.base(), trading_pair.quote());
+
+        let breaker = CircuitBreaker {
+            max_move_per_block_percent: 10,
+            halt_duration_blocks: 5,
+        };
+        assert_noop!(
+            XSpot::set_circuit_breaker(Origin::signed(1), 0, Some(breaker)),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            XSpot::set_circuit_breaker(
+                Origin::root(),
+                0,
+                Some(CircuitBreaker {
+                    max_move_per_block_percent: 10,
+                    halt_duration_blocks: 0,
+                })
+            ),
+            Error::<Test>::InvalidCircuitBreaker
+        );
+        assert_ok!(XSpot::set_circuit_breaker(Origin::root(), 0, Some(breaker)));
+        t_set_price_fluctution(0, 10_000);
+
+        t_issue_pcx(2, 3_000_000);
+        t_generic_issue(quote, 1, 10_000);
+        t_generic_issue(quote, 3, 10_000);
+
+        assert_ok!(t_put_order_sell(2, 0, 1_000_000, 1_000_000));
+        assert_ok!(t_put_order_sell(2, 0, 1_000_000, 1_050_000));
+        assert_ok!(t_put_order_sell(2, 0, 1_000_000, 1_200_000));
+
+        // The first two fills are within 10% of the first trade price 1_000_000 in this block,
+        // the fill at 1_200_000 trips the breaker.
+        assert_ok!(t_put_order_buy(1, 0, 3_000_000, 1_200_000));
+        System::assert_has_event(crate::mock::Event::XSpot(
+            crate::Event::CircuitBreakerTripped(0, 1_200_000, 6),
+        ));
+        assert_eq!(XSpot::halted_until_of(0), Some(6));
+
+        // The taker order stopped after the partial execution and its remainder was canceled.
+        assert_eq!(XSpot::order_info_of(1, 0), None);
+        assert_eq!(t_generic_free_balance(1, base), 2_000_000);
+        assert_eq!(t_generic_free_balance(1, quote), 10_000 - 1_000 - 1_050);
+        assert_eq!(
+            XAssets::asset_balance_of(&1, &quote, AssetType::ReservedDexSpot),
+            0
+        );
+
+        // The resting order beyond the band is intact.
+        assert_eq!(XSpot::order_info_of(2, 0), None);
+        assert_eq!(XSpot::order_info_of(2, 1), None);
+        let resting = XSpot::order_info_of(2, 2).unwrap();
+        assert!(resting.already_filled.is_zero());
+        assert_eq!(XSpot::quotations_of(0, 1_200_000), vec![(2, 2)]);
+        assert!(XSpot::settlement_counters_of(0).is_balanced());
+
+        // During the halt, the orders crossing the book are rejected,
+        // the others rest and can be canceled.
+        System::set_block_number(2);
+        assert_noop!(
+            t_put_order_buy(3, 0, 1_000_000, 1_200_000),
+            Error::<Test>::TradingPairHalted
+        );
+        assert_ok!(t_put_order_buy(3, 0, 1_000_000, 900_000));
+        assert!(XSpot::order_info_of(3, 0).is_some());
+        assert_ok!(t_cancel_order(3, 0, 0));
+        assert_eq!(XSpot::order_info_of(3, 0), None);
+
+        // The matching resumes at the resume block.
+        System::set_block_number(6);
+        assert_ok!(t_put_order_buy(3, 0, 1_000_000, 1_200_000));
+        assert_eq!(XSpot::order_info_of(2, 2), None);
+        assert_eq!(XSpot::order_info_of(3, 1), None);
+        assert_eq!(t_generic_free_balance(3, base), 1_000_000);
+        assert_eq!(t_generic_free_balance(2, quote), 1_000 + 1_050 + 1_200);
+        assert!(XSpot::settlement_counters_of(0).is_balanced());
+    })
+}
+
+#[test]
+fn order_updated_snapshot_should_match_storage() {
+    ExtBuilder::default().build_and_execute(|| {
+        let trading_pair = XSpot::trading_pair_of(0).unwrap();
+
+        t_set_handicap(0, 1_000_000, 1_100_000);
+
+        t_generic_issue(trading_pair.quote(), 1, 10);
+        t_issue_pcx(2, 2000);
+
+        let last_snapshot_of = |who: AccountId, order_id: OrderId| {
+            System::events()
+                .into_iter()
+                .rev()
+                .find_map(|record| match record.event {
+                    crate::mock::Event::XSpot(crate::Event::OrderUpdated(snapshot))
+                        if snapshot.submitter == who && snapshot.id == order_id =>
+                    {
+                        Some(snapshot)
+                    }
+                    _ => None,
+                })
+                .unwrap()
+        };
+
+        // Place
+        assert_ok!(t_put_order_buy(1, 0, 1000, 1_000_100));
+        let order_1_0 = XSpot::order_info_of(1, 0).unwrap();
+        assert_eq!(last_snapshot_of(1, 0), OrderSnapshot::from(&order_1_0));
+        assert_eq!(last_snapshot_of(1, 0).status, OrderStatus::Created);
+
+        // Partial fill of the maker, the taker is filled and removed.
+        assert_ok!(t_put_order_sell(2, 0, 500, 1_000_100));
+        let order_1_0 = XSpot::order_info_of(1, 0).unwrap();
+        assert_eq!(last_snapshot_of(1, 0), OrderSnapshot::from(&order_1_0));
+        assert_eq!(last_snapshot_of(1, 0).status, OrderStatus::PartialFill);
+        assert_eq!(last_snapshot_of(1, 0).already_filled, 500);
+
+        assert_eq!(XSpot::order_info_of(2, 0), None);
+        let taker = last_snapshot_of(2, 0);
+        assert_eq!(taker.status, OrderStatus::Filled);
+        assert_eq!((taker.amount, taker.already_filled), (500, 500));
+        assert_eq!(taker.remaining, 0);
+
+        // Cancel
+        assert_ok!(t_cancel_order(1, 0, 0));
+        assert_eq!(XSpot::order_info_of(1, 0), None);
+        let canceled = last_snapshot_of(1, 0);
+        assert_eq!(canceled.status, OrderStatus::PartialFillAndCanceled);
+        assert_eq!(canceled.already_filled, 500);
+        assert_eq!(canceled.remaining, 0);
+        assert_eq!(canceled.updated_at, System::block_number());
+    })
+}
+
+/// A xorshift generator, good enough for generating the random order flows.
+struct XorShift(u64);
+
+impl XorShift {
+    fn new(seed: u64) -> Self {
+        Self(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    fn next(&mut self, bound: u64) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 % bound
+    }
+}
+
+/// Replays the random order flow generated from `seed` over a fresh storage,
+/// returning the fill events in the order they were emitted.
+fn t_replay_random_orders(seed: u64) -> Vec<crate::mock::Event> {
+    let mut fills = Vec::new();
+    ExtBuilder::default().build_and_execute(|| {
+        let trading_pair = XSpot::trading_pair_of(0).unwrap();
+        t_set_price_fluctution(0, 10_000);
+
+        let traders = 5;
+        for who in 1..=traders {
+            t_issue_pcx(who, 1_000_000_000_000);
+            t_generic_issue(trading_pair.quote(), who, 1_000_000_000);
+        }
+
+        let mut rng = XorShift::new(seed);
+        for _ in 0..200 {
+            let who = 1 + rng.next(traders);
+            if rng.next(10) == 0 {
+                // The order may have been filled or canceled already.
+                let order_count = XSpot::order_count_of(who);
+                if order_count > 0 {
+                    let _ = t_cancel_order(who, 0, rng.next(order_count));
+                }
+                continue;
+            }
+
+            // 11 ticks around 1_000_000.
+            let price = 999_500 + 100 * rng.next(11) as Price;
+            let amount = 1_000 + rng.next(100_000) as Balance;
+            let _ = if rng.next(2) == 0 {
+                t_put_order_buy(who, 0, amount, price)
+            } else {
+                t_put_order_sell(who, 0, amount, price)
+            };
+        }
+
+        fills = System::events()
+            .into_iter()
+            .map(|record| record.event)
+            .filter(|event| {
+                matches!(
+                    event,
+                    crate::mock::Event::XSpot(crate::Event::OrderExecuted(_))
+                )
+            })
+            .collect();
+    });
+    fills
+}
+
+#[test]
+fn matching_should_be_deterministic_on_random_orders() {
+    for seed in 0..20 {
+        let fills = t_replay_random_orders(seed);
+        assert!(!fills.is_empty(), "No fill for seed {}", seed);
+        assert_eq!(fills, t_replay_random_orders(seed), "Seed {}", seed);
+    }
+}
+
+#[test]
+fn matching_priority_should_be_price_then_queue_index() {
+    let ask = |price, queue_index| MatchingPriority {
+        side: Side::Sell,
+        price: price as Price,
+        queue_index,
+    };
+    let bid = |price, queue_index| MatchingPriority {
+        side: Side::Buy,
+        price: price as Price,
+        queue_index,
+    };
+
+    assert!(ask(100, 5) < ask(200, 0));
+    assert!(ask(100, 0) < ask(100, 1));
+    assert!(bid(200, 5) < bid(100, 0));
+    assert!(bid(200, 0) < bid(200, 1));
+}
+
+fn t_put_order_with_client_id(
+    who: AccountId,
+    pair_id: TradingPairId,
+    side: Side,
+    amount: Balance,
+    price: Price,
+    client_order_id: &[u8],
+) -> DispatchResult {
+    XSpot::put_order(
+        Origin::signed(who),
+        pair_id,
+        OrderType::Limit,
+        side,
+        amount,
+        price,
+        None,
+        Some(client_order_id.to_vec()),
+        false,
+        false,
+    )
+}
+
+fn t_put_post_only_order(
+    who: AccountId,
+    pair_id: TradingPairId,
+    side: Side,
+    amount: Balance,
+    price: Price,
+    adjust: bool,
+) -> DispatchResult {
+    XSpot::put_order(
+        Origin::signed(who),
+        pair_id,
+        OrderType::Limit,
+        side,
+        amount,
+        price,
+        None,
+        None,
+        true,
+        adjust,
+    )
+}
+
+#[test]
+fn crossing_post_only_order_should_be_rejected() {
+    ExtBuilder::default().build_and_execute(|| {
+        let (pair_id, maker, taker) = (0, 1, 2);
+        t_set_handicap(pair_id, 1_000_000, 1_200_000);
+        t_issue_pcx(maker, 1_000_000_000);
+        t_generic_issue(xp_protocol::X_BTC, taker, 10_000_000);
+        assert_ok!(t_put_order_sell(maker, pair_id, 1_000_000_000, 1_100_000));
+
+        assert_noop!(
+            t_put_post_only_order(taker, pair_id, Side::Buy, 1_000_000_000, 1_100_000, false),
+            Error::<Test>::PostOnlyWouldCross
+        );
+        assert_noop!(
+            t_put_post_only_order(taker, pair_id, Side::Buy, 1_000_000_000, 1_200_000, false),
+            Error::<Test>::PostOnlyWouldCross
+        );
+        assert_eq!(XSpot::order_info_of(maker, 0).unwrap().already_filled, 0);
+    });
+}
+
+#[test]
+fn adjusted_post_only_order_should_rest_one_tick_off() {
+    ExtBuilder::default().build_and_execute(|| {
+        let (pair_id, maker, buyer, seller) = (0, 1, 2, 3);
+        t_set_handicap(pair_id, 1_000_000, 1_100_000);
+        let pair = XSpot::trading_pair(pair_id).unwrap();
+        let tick = pair.tick() as Price;
+        t_generic_issue(xp_protocol::X_BTC, buyer, 10_000_000);
+        t_issue_pcx(seller, 1_000_000_000);
+
+        // The buy order is re-priced right below the lowest ask and reserves less.
+        assert_ok!(t_put_post_only_order(
+            buyer,
+            pair_id,
+            Side::Buy,
+            1_000_000_000,
+            1_200_000,
+            true
+        ));
+        let order = XSpot::order_info_of(buyer, 0).unwrap();
+        assert_eq!(order.price(), 1_100_000 - tick);
+        assert_eq!(order.already_filled, 0);
+        assert_eq!(
+            XSpot::quotations_of(pair_id, 1_100_000 - tick),
+            vec![(buyer, 0)]
+        );
+        let reserved = t_convert_base_to_quote(1_000_000_000, 1_100_000 - tick, &pair);
+        assert_eq!(
+            XAssets::asset_balance_of(&buyer, &xp_protocol::X_BTC, AssetType::ReservedDexSpot),
+            reserved
+        );
+        assert_eq!(
+            t_generic_free_balance(buyer, xp_protocol::X_BTC),
+            10_000_000 - reserved
+        );
+
+        // The sell order is re-priced right above the highest bid, which is the buy order.
+        assert_ok!(t_put_post_only_order(
+            seller,
+            pair_id,
+            Side::Sell,
+            1_000_000_000,
+            1_000_000,
+            true
+        ));
+        let order = XSpot::order_info_of(seller, 0).unwrap();
+        assert_eq!(order.price(), 1_100_000);
+        assert_eq!(order.already_filled, 0);
+        assert_eq!(XSpot::order_info_of(buyer, 0).unwrap().already_filled, 0);
+        assert_eq!(
+            XSpot::handicap_of(pair_id),
+            Handicap::new(1_100_000 - tick, 1_100_000)
+        );
+    });
+}
+
+#[test]
+fn non_crossing_post_only_order_should_work_as_limit_order() {
+    ExtBuilder::default().build_and_execute(|| {
+        let (pair_id, who) = (0, 1);
+        t_set_handicap(pair_id, 1_000_000, 1_100_000);
+        t_issue_pcx(who, 1000);
+
+        for adjust in [false, true] {
+            assert_ok!(t_put_post_only_order(
+                who,
+                pair_id,
+                Side::Sell,
+                100,
+                1_210_000,
+                adjust
+            ));
+        }
+        assert_ok!(t_put_order_sell(who, pair_id, 100, 1_210_000));
+
+        let orders = XSpot::orders(who, 0, 10);
+        assert_eq!(orders.len(), 3);
+        assert!(orders
+            .iter()
+            .all(|order| order.props.price == 1_210_000 && order.already_filled == 0));
+        assert_eq!(Balances::reserved_balance(who), 300);
+    });
+}
+
+#[test]
+fn client_order_id_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        let (pair_id, who) = (0, 1);
+        t_set_handicap(pair_id, 1_000_000, 1_100_000);
+        t_issue_pcx(who, 1000);
+
+        assert_ok!(t_put_order_with_client_id(
+            who,
+            pair_id,
+            Side::Sell,
+            100,
+            1_210_000,
+            b"order-1"
+        ));
+        assert!(System::events().iter().any(|record| record.event
+            == crate::mock::Event::XSpot(crate::Event::ClientOrderIdAssigned(
+                who,
+                pair_id,
+                0,
+                b"order-1".to_vec()
+            ))));
+        assert_eq!(
+            XSpot::open_order_of_client_id(who, b"order-1".to_vec()),
+            Some(0)
+        );
+
+        // The client order id is returned along with the order.
+        let orders = XSpot::orders(who, 0, 10);
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].client_order_id, Some(b"order-1".to_vec()));
+
+        assert_ok!(t_put_order_sell(who, pair_id, 100, 1_210_000));
+        assert_eq!(XSpot::orders(who, 0, 10)[0].client_order_id, None);
+
+        assert_noop!(
+            XSpot::cancel_by_client_id(Origin::signed(who), 1, b"order-1".to_vec(), None),
+            Error::<Test>::InvalidOrderId
+        );
+        assert_noop!(
+            XSpot::cancel_by_client_id(Origin::signed(who), pair_id, b"order-2".to_vec(), None),
+            Error::<Test>::InvalidOrderId
+        );
+        assert_ok!(XSpot::cancel_by_client_id(
+            Origin::signed(who),
+            pair_id,
+            b"order-1".to_vec(),
+            None
+        ));
+        assert!(XSpot::order_info_of(who, 0).is_none());
+        assert!(XSpot::order_info_of(who, 1).is_some());
+        assert_eq!(
+            XSpot::open_order_of_client_id(who, b"order-1".to_vec()),
+            None
+        );
+        assert_eq!(XSpot::client_order_id_of(who, 0), None);
+        assert_eq!(Balances::reserved_balance(who), 100);
+    })
+}
+
+#[test]
+fn client_order_id_should_be_unique_among_open_orders() {
+    ExtBuilder::default().build_and_execute(|| {
+        let pair_id = 0;
+        let (maker, taker) = (1, 2);
+        t_set_handicap(pair_id, 1_000_000, 1_100_000);
+        t_issue_pcx(maker, 3000);
+        t_issue_pcx(taker, 1000);
+
+        for invalid in [
+            &b""[..],
+            &[b'a'; 33][..],
+            &b"order 1"[..],
+            &b"\xe4\xb8\xad"[..],
+        ] {
+            assert_noop!(
+                t_put_order_with_client_id(maker, pair_id, Side::Sell, 100, 1_210_000, invalid),
+                Error::<Test>::InvalidClientOrderId
+            );
+        }
+
+        assert_ok!(t_put_order_with_client_id(
+            maker,
+            pair_id,
+            Side::Sell,
+            1000,
+            1_210_000,
+            b"order-1"
+        ));
+        assert_noop!(
+            t_put_order_with_client_id(maker, pair_id, Side::Sell, 100, 1_220_000, b"order-1"),
+            Error::<Test>::DuplicateClientOrderId
+        );
+        // The client order ids of different accounts are independent.
+        assert_ok!(t_put_order_with_client_id(
+            taker,
+            pair_id,
+            Side::Sell,
+            100,
+            1_220_000,
+            b"order-1"
+        ));
+
+        // The client order id can be reused once the order is filled.
+        let trading_pair = XSpot::trading_pair_of(pair_id).unwrap();
+        t_generic_issue(trading_pair.quote(), taker, 10);
+        assert_ok!(t_put_order_buy(taker, pair_id, 1000, 1_210_000));
+        assert!(XSpot::order_info_of(maker, 0).is_none());
+        assert_eq!(
+            XSpot::open_order_of_client_id(maker, b"order-1".to_vec()),
+            None
+        );
+        assert_ok!(t_put_order_with_client_id(
+            maker,
+            pair_id,
+            Side::Sell,
+            100,
+            1_230_000,
+            b"order-1"
+        ));
+        assert_eq!(
+            XSpot::open_order_of_client_id(maker, b"order-1".to_vec()),
+            Some(1)
+        );
+    })
+}
+
+#[test]
+fn reduce_order_should_release_reserved_balance() {
+    ExtBuilder::default().build_and_execute(|| {
+        System::set_block_number(1);
+        let trading_pair = XSpot::trading_pair_of(0).unwrap();
+        let quote = trading_pair.quote();
+
+        t_generic_issue(quote, 1, 100);
+        t_issue_pcx(2, 20_000);
+
+        // 10_000 * 2_000_000 / 10^9 = 20
+        assert_ok!(t_put_order_buy(1, 0, 10_000, 2_000_000));
+        assert_ok!(t_put_order_sell(2, 0, 10_000, 2_100_000));
+
+        assert_noop!(
+            XSpot::reduce_order(Origin::signed(1), 0, 0, 0),
+            Error::<Test>::ZeroAmount
+        );
+        assert_noop!(
+            XSpot::reduce_order(Origin::signed(1), 0, 0, 10_000),
+            Error::<Test>::InvalidReduceAmount
+        );
+        assert_noop!(
+            XSpot::reduce_order(Origin::signed(3), 0, 0, 1_000),
+            Error::<Test>::InvalidOrderId
+        );
+
+        // The buy order keeps reserving 6_000 * 2_000_000 / 10^9 = 12.
+        assert_ok!(XSpot::reduce_order(Origin::signed(1), 0, 0, 4_000));
+        System::assert_has_event(crate::mock::Event::XSpot(crate::Event::OrderReduced(
+            0, 0, 6_000,
+        )));
+        let order = XSpot::order_info_of(1, 0).unwrap();
+        assert_eq!(order.amount(), 6_000);
+        assert_eq!(order.remaining, 12);
+        assert_eq!(order.status, OrderStatus::Created);
+        assert_eq!(
+            XAssets::asset_balance_of(&1, &quote, AssetType::ReservedDexSpot),
+            12
+        );
+        assert_eq!(t_generic_free_balance(1, quote), 88);
+
+        // The sell order releases the reduced base currency.
+        assert_ok!(XSpot::reduce_order(Origin::signed(2), 0, 0, 3_000));
+        let order = XSpot::order_info_of(2, 0).unwrap();
+        assert_eq!(order.amount(), 7_000);
+        assert_eq!(order.remaining, 7_000);
+        assert_eq!(XSpot::native_reserves(&2), 7_000);
+        assert_eq!(Balances::reserved_balance(2), 7_000);
+        assert_eq!(Balances::free_balance(2), 13_000);
+
+        // The orders stay on the book at the same prices.
+        assert_eq!(XSpot::quotations_of(0, 2_000_000), vec![(1, 0)]);
+        assert_eq!(XSpot::quotations_of(0, 2_100_000), vec![(2, 0)]);
+    })
+}
+
+#[test]
+fn reduce_order_below_min_notional_should_not_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        let trading_pair = XSpot::trading_pair_of(0).unwrap();
+
+        t_generic_issue(trading_pair.quote(), 1, 100);
+
+        assert_ok!(XSpot::set_min_notional(Origin::root(), 0, 10));
+        assert_ok!(t_put_order_buy(1, 0, 10_000, 2_000_000));
+
+        // 4_999 * 2_000_000 / 10^9 = 9 < 10
+        assert_noop!(
+            XSpot::reduce_order(Origin::signed(1), 0, 0, 5_001),
+            Error::<Test>::NotionalTooSmall
+        );
+        assert_ok!(XSpot::reduce_order(Origin::signed(1), 0, 0, 5_000));
+        assert_eq!(
+            XSpot::order_info_of(1, 0).unwrap().remaining_in_base(),
+            5_000
+        );
+    })
+}
+
+#[test]
+fn reduce_order_should_preserve_time_priority() {
+    ExtBuilder::default().build_and_execute(|| {
+        let trading_pair = XSpot::trading_pair_of(0).unwrap();
+        let quote = trading_pair.quote();
+
+        t_generic_issue(quote, 1, 100);
+        t_generic_issue(quote, 3, 100);
+        t_issue_pcx(2, 20_000);
+
+        assert_ok!(t_put_order_buy(1, 0, 10_000, 2_000_000));
+        assert_ok!(t_put_order_buy(3, 0, 10_000, 2_000_000));
+
+        // Reduce a partially filled order.
+        assert_ok!(t_put_order_sell(2, 0, 2_000, 2_000_000));
+        assert_eq!(XSpot::order_info_of(1, 0).unwrap().already_filled, 2_000);
+        assert_ok!(XSpot::reduce_order(Origin::signed(1), 0, 0, 3_000));
+        assert_eq!(XSpot::quotations_of(0, 2_000_000), vec![(1, 0), (3, 0)]);
+
+        // The reduced order is still filled before the later one at the same price.
+        assert_ok!(t_put_order_sell(2, 0, 6_000, 2_000_000));
+        assert_eq!(XSpot::order_info_of(1, 0), None);
+        let later_order = XSpot::order_info_of(3, 0).unwrap();
+        assert_eq!(later_order.already_filled, 1_000);
+        assert_eq!(XSpot::quotations_of(0, 2_000_000), vec![(3, 0)]);
+
+        // 10_000 filled in total: 7_000 from the reduced order and 1_000 from the later one.
+        assert_eq!(t_generic_free_balance(1, trading_pair.base()), 7_000);
+        assert_eq!(
+            XAssets::asset_balance_of(&1, &quote, AssetType::ReservedDexSpot),
+            0
+        );
+        assert_eq!(t_generic_free_balance(1, quote), 86);
+        assert!(XSpot::settlement_counters_of(0).is_balanced());
+    })
+}
+
+#[test]
+fn competition_volume_should_only_count_fills_in_window() {
+    ExtBuilder::default().build_and_execute(|| {
+        System::set_block_number(1);
+        let trading_pair = XSpot::trading_pair_of(0).unwrap();
+
+        t_set_handicap(0, 1_000_000, 1_100_000);
+
+        t_generic_issue(trading_pair.quote(), 1, 10_000);
+        t_issue_pcx(2, 1_000_000);
+
+        assert_noop!(
+            XSpot::start_competition(Origin::root(), 0, 5, 5, false),
+            Error::<Test>::InvalidCompetitionWindow
+        );
+        // Only the taker volume is counted in competition 0.
+        assert_ok!(XSpot::start_competition(Origin::root(), 0, 5, 10, false));
+        assert_ok!(XSpot::start_competition(Origin::root(), 0, 1, 10, true));
+        assert_eq!(XSpot::open_competitions_of(0), vec![0, 1]);
+
+        let notional = t_convert_base_to_quote(100_000, 1_000_100, &trading_pair);
+        assert!(notional > 0);
+
+        assert_ok!(t_put_order_buy(1, 0, 1_000_000, 1_000_100));
+        assert_ok!(t_put_order_sell(2, 0, 100_000, 1_000_100));
+        assert_eq!(XSpot::competition_volume(0, 2), 0);
+        assert_eq!(XSpot::competition_volume(1, 1), notional);
+        assert_eq!(XSpot::competition_volume(1, 2), notional);
+
+        System::set_block_number(5);
+        assert_ok!(t_put_order_sell(2, 0, 100_000, 1_000_100));
+        assert_eq!(XSpot::competition_volume(0, 1), 0);
+        assert_eq!(XSpot::competition_volume(0, 2), notional);
+        assert_eq!(XSpot::competition_volume(1, 1), 2 * notional);
+        assert_eq!(XSpot::competition_volume(1, 2), 2 * notional);
+
+        System::set_block_number(10);
+        XSpot::on_initialize(10);
+        System::assert_has_event(crate::mock::Event::XSpot(crate::Event::CompetitionEnded(0)));
+        System::assert_has_event(crate::mock::Event::XSpot(crate::Event::CompetitionEnded(1)));
+        assert!(XSpot::competitions(0).unwrap().ended);
+        assert!(XSpot::open_competitions_of(0).is_empty());
+
+        assert_ok!(t_put_order_sell(2, 0, 100_000, 1_000_100));
+        assert_eq!(XSpot::competition_volume(0, 2), notional);
+        assert_eq!(XSpot::competition_volume(1, 2), 2 * notional);
+        assert_eq!(
+            XSpot::competition_leaderboard(1, 0, 10),
+            vec![(1, 2 * notional), (2, 2 * notional)]
+        );
+    })
+}
+
+#[test]
+fn competition_volume_should_count_self_trades_once() {
+    ExtBuilder::default().build_and_execute(|| {
+        System::set_block_number(1);
+        let trading_pair = XSpot::trading_pair_of(0).unwrap();
+
+        t_set_handicap(0, 1_000_000, 1_100_000);
+
+        t_generic_issue(trading_pair.quote(), 1, 10_000);
+        t_issue_pcx(1, 1_000_000);
+
+        for _ in 0..MAX_OPEN_COMPETITIONS_PER_PAIR {
+            assert_ok!(XSpot::start_competition(Origin::root(), 0, 1, 10, true));
+        }
+        assert_noop!(
+            XSpot::start_competition(Origin::root(), 0, 1, 10, true),
+            Error::<Test>::TooManyOpenCompetitions
+        );
+
+        let notional = t_convert_base_to_quote(100_000, 1_000_100, &trading_pair);
+        assert_ok!(t_put_order_buy(1, 0, 1_000_000, 1_000_100));
+        assert_ok!(t_put_order_sell(1, 0, 100_000, 1_000_100));
+        assert_eq!(XSpot::competition_volume(0, 1), notional);
+        assert_eq!(
+            XSpot::competition_leaderboard(0, 0, 10),
+            vec![(1, notional)]
+        );
+    })
+}
+
+#[test]
+fn prune_competition_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        System::set_block_number(1);
+        assert_ok!(XSpot::start_competition(Origin::root(), 0, 1, 3, true));
+        XSpot::add_competition_volume(0, &1, 100);
+        XSpot::add_competition_volume(0, &2, 200);
+
+        assert_noop!(
+            XSpot::prune_competition(Origin::root(), 1, 10),
+            Error::<Test>::NonexistentCompetition
+        );
+        assert_noop!(
+            XSpot::prune_competition(Origin::root(), 0, 10),
+            Error::<Test>::CompetitionNotEnded
+        );
+
+        System::set_block_number(3);
+        XSpot::on_initialize(3);
+
+        assert_ok!(XSpot::prune_competition(Origin::root(), 0, 1));
+        assert!(XSpot::competitions(0).is_some());
+        assert_eq!(CompetitionVolume::<Test>::iter_prefix(0).count(), 1);
+        assert_eq!(XSpot::competition_leaderboard(0, 0, 10).len(), 2);
+
+        assert_ok!(XSpot::prune_competition(Origin::root(), 0, 1));
+        assert!(XSpot::competitions(0).is_none());
+        assert!(XSpot::competition_leaderboard(0, 0, 10).is_empty());
+        System::assert_last_event(crate::mock::Event::XSpot(crate::Event::CompetitionPruned(
+            0,
+        )));
+    })
+}
+
+#[test]
+fn schedule_pair_delist_should_reject_new_orders() {
+    ExtBuilder::default().build_and_execute(|| {
+        System::set_block_number(1);
+        let trading_pair = XSpot::trading_pair_of(0).unwrap();
+
+        t_set_handicap(0, 1_000_000, 1_100_000);
+
+        t_generic_issue(trading_pair.quote(), 1, 10);
+        t_issue_pcx(2, 2000);
+
+        // The orders put before the delisting are matched as usual.
+        assert_ok!(t_put_order_buy(1, 0, 1000, 1_000_100));
+        assert_ok!(t_put_order_buy(1, 0, 1000, 1_000_000));
+        assert_ok!(t_put_order_sell(2, 0, 500, 1_000_100));
+        assert_eq!(XSpot::order_info_of(1, 0).unwrap().already_filled, 500);
+
+        assert_noop!(
+            XSpot::schedule_pair_delist(Origin::signed(1), 0, 5),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            XSpot::schedule_pair_delist(Origin::root(), 0, 1),
+            Error::<Test>::InvalidDelistBlock
+        );
+        assert_ok!(XSpot::schedule_pair_delist(Origin::root(), 0, 5));
+        System::assert_last_event(crate::mock::Event::XSpot(
+            crate::Event::TradingPairDelistScheduled(0, 5),
+        ));
+        assert_eq!(XSpot::delist_status_of(0), Some(DelistStatus::Scheduled(5)));
+
+        assert_noop!(
+            t_put_order_sell(2, 0, 500, 1_000_100),
+            Error::<Test>::TradingPairDelisting
+        );
+        assert_noop!(
+            t_put_order_buy(1, 0, 1000, 1_000_000),
+            Error::<Test>::TradingPairDelisting
+        );
+
+        // The trading pair stays tradable until the effective block.
+        System::set_block_number(4);
+        XSpot::on_initialize(4);
+        assert!(t_trading_pair_of(0).tradable);
+        assert_ok!(t_cancel_order(1, 0, 1));
+
+        // Rescheduling is allowed before the effective block.
+        assert_ok!(XSpot::schedule_pair_delist(Origin::root(), 0, 8));
+        System::set_block_number(5);
+        XSpot::on_initialize(5);
+        assert_eq!(XSpot::delist_status_of(0), Some(DelistStatus::Scheduled(8)));
+        assert!(t_trading_pair_of(0).tradable);
+    })
+}
+
+#[test]
+fn delisted_pair_should_be_retired_after_auto_cancel() {
+    ExtBuilder::default().build_and_execute(|| {
+        System::set_block_number(1);
+        let trading_pair = XSpot::trading_pair_of(0).unwrap();
+        let quote = trading_pair.quote();
+
+        t_set_handicap(0, 1_000_000, 1_100_000);
+
+        let orders = MAX_DELIST_CANCELS_PER_BLOCK + 10;
+        let issued = 10 * orders as Balance;
+        t_generic_issue(quote, 1, issued);
+        for _ in 0..orders {
+            assert_ok!(t_put_order_buy(1, 0, 1000, 1_000_000));
+        }
+
+        assert_ok!(XSpot::schedule_pair_delist(Origin::root(), 0, 3));
+
+        System::set_block_number(3);
+        XSpot::on_initialize(3);
+        let spot_event = |event: crate::Event<Test>| crate::mock::Event::XSpot(event);
+        System::assert_has_event(spot_event(crate::Event::TradingPairDelistEffective(0)));
+        assert!(!t_trading_pair_of(0).tradable);
+        assert_eq!(XSpot::delist_status_of(0), Some(DelistStatus::Closing));
+        assert_eq!(XSpot::closing_pairs(), vec![0]);
+        assert_eq!(
+            OrderInfoOf::<Test>::iter_prefix(1).count() as u32,
+            orders - MAX_DELIST_CANCELS_PER_BLOCK
+        );
+        assert_noop!(
+            t_put_order_buy(1, 0, 1000, 1_000_000),
+            Error::<Test>::TradingPairUntradable
+        );
+        assert_noop!(
+            XSpot::schedule_pair_delist(Origin::root(), 0, 10),
+            Error::<Test>::TradingPairDelisted
+        );
+
+        // The owners can still cancel the remaining orders themselves.
+        let order_id = OrderInfoOf::<Test>::iter_prefix_values(1)
+            .next()
+            .unwrap()
+            .id();
+        assert_ok!(t_cancel_order(1, 0, order_id));
+
+        System::set_block_number(4);
+        XSpot::on_initialize(4);
+        System::assert_has_event(spot_event(crate::Event::TradingPairRetired(0)));
+        assert_eq!(XSpot::delist_status_of(0), Some(DelistStatus::Retired));
+        assert!(XSpot::closing_pairs().is_empty());
+        assert_eq!(OrderInfoOf::<Test>::iter_prefix(1).count(), 0);
+        assert_eq!(
+            XAssets::asset_balance_of(&1, &quote, AssetType::ReservedDexSpot),
+            0
+        );
+        assert_eq!(t_generic_free_balance(1, quote), issued);
+    })
+}
+
+#[test]
+fn retired_pair_should_be_hidden_by_default() {
+    ExtBuilder::default().build_and_execute(|| {
+        System::set_block_number(1);
+        let pair_count = XSpot::trading_pair_count() as usize;
+        assert_eq!(XSpot::trading_pairs(false).len(), pair_count);
+
+        assert_ok!(XSpot::schedule_pair_delist(Origin::root(), 0, 2));
+        let pairs = XSpot::trading_pairs(false);
+        assert_eq!(pairs.len(), pair_count);
+        assert_eq!(pairs[0].delist, Some(DelistStatus::Scheduled(2)));
+
+        System::set_block_number(2);
+        XSpot::on_initialize(2);
+        assert_eq!(XSpot::delist_status_of(0), Some(DelistStatus::Retired));
+
+        let pairs = XSpot::trading_pairs(false);
+        assert_eq!(pairs.len(), pair_count - 1);
+        assert!(pairs.iter().all(|pair| pair.profile.id != 0));
+
+        let pairs = XSpot::trading_pairs(true);
+        assert_eq!(pairs.len(), pair_count);
+        assert_eq!(pairs[0].delist, Some(DelistStatus::Retired));
+    })
+}
+
+#[test]
+fn paging_orders_at_pinned_block_should_have_no_duplicates_or_gaps() {
+    let place_orders = || {
+        t_set_handicap(0, 1_000_000, 1_100_000);
+        t_issue_pcx(1, 3000);
+        for price in [1_000_300, 1_000_200, 1_000_100] {
+            assert_ok!(t_put_order_sell(1, 0, 1000, price));
+        }
+    };
+    let order_ids = |who: AccountId, page_index: u32| {
+        XSpot::orders(who, page_index, 2)
+            .into_iter()
+            .map(|order| order.props.id)
+            .collect::<Vec<_>>()
+    };
+
+    // The state of the pinned block.
+    let mut pinned = ExtBuilder::default().build();
+    pinned.execute_with(place_orders);
+    // The state of the best block, where a new fill has closed order 2 since.
+    let mut best = ExtBuilder::default().build();
+    best.execute_with(|| {
+        place_orders();
+        let quote = XSpot::trading_pair_of(0).unwrap().quote();
+        t_generic_issue(quote, 2, 10);
+        assert_ok!(t_put_order_buy(2, 0, 1000, 1_000_100));
+        assert_eq!(XSpot::order_info_of(1, 2), None);
+    });
+
+    let first_page = pinned.execute_with(|| order_ids(1, 0));
+    assert_eq!(first_page, vec![2, 1]);
+
+    // Reading the next page from the best block skips order 0 and the changed
+    // order sequence is what reveals it.
+    assert!(best.execute_with(|| order_ids(1, 1)).is_empty());
+    let pinned_sequence = pinned.execute_with(|| XSpot::order_sequence_of(1));
+    let best_sequence = best.execute_with(|| XSpot::order_sequence_of(1));
+    assert_eq!(pinned_sequence, 3);
+    assert_eq!(best_sequence, 4);
+
+    // Reading it from the pinned block covers every order exactly once.
+    let second_page = pinned.execute_with(|| order_ids(1, 1));
+    assert_eq!(second_page, vec![0]);
+}
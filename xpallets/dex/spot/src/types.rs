@@ -9,10 +9,14 @@ use scale_info::TypeInfo;
 use serde::{Deserialize, Serialize};
 use sp_arithmetic::traits::BaseArithmetic;
 use sp_runtime::RuntimeDebug;
+use sp_std::cmp::Ordering;
 
 /// Type for counting the number of user orders.
 pub type OrderId = u64;
 
+/// Type for the order id assigned by the client, unique among the open orders of an account.
+pub type ClientOrderId = Vec<u8>;
+
 /// Type for counting the number of trading pairs.
 pub type TradingPairId = u32;
 
@@ -55,6 +59,39 @@ impl Default for Side {
     }
 }
 
+/// Matching priority of a resting order on the order book.
+///
+/// The resting orders are matched in an explicit total order: the better price first,
+/// i.e., the lower ask or the higher bid, then the lower index in the quotations of
+/// that price, i.e., the earlier order first. Only the priorities of the same side
+/// are comparable.
+#[derive(PartialEq, Eq, Clone, Copy, RuntimeDebug)]
+pub struct MatchingPriority<Price> {
+    /// The side of the resting order.
+    pub side: Side,
+    /// The price of the resting order.
+    pub price: Price,
+    /// The index of the resting order in the quotations of `price`.
+    pub queue_index: usize,
+}
+
+impl<Price: Ord> Ord for MatchingPriority<Price> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        debug_assert!(self.side == other.side, "Priorities of different sides");
+        let price_priority = match self.side {
+            Side::Sell => self.price.cmp(&other.price),
+            Side::Buy => other.price.cmp(&self.price),
+        };
+        price_priority.then(self.queue_index.cmp(&other.queue_index))
+    }
+}
+
+impl<Price: Ord> PartialOrd for MatchingPriority<Price> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 /// Status of an order.
 #[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
@@ -218,7 +255,7 @@ impl TradingPairProfile {
     }
 }
 
-/// Immutable information of an order.
+/// Immutable information of an order, except that the `amount` can be reduced.
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
@@ -230,6 +267,8 @@ pub struct OrderProperty<PairId, AccountId, Amount, Price, BlockNumber> {
     /// The price of order.
     pub price: Price,
     /// The amount of order, measured in the base currency.
+    ///
+    /// It can be reduced by the owner of an open order, but never increased.
     pub amount: Amount,
     /// The trading pair identifier.
     pub pair_id: PairId,
@@ -368,6 +407,16 @@ where
         self._sub_remaining(refund)
     }
 
+    /// Minus the `amount` of the order by `reduce_by` and the `remaining` by the
+    /// released reserved balance.
+    pub fn decrease_amount_on_reduce(&mut self, reduce_by: Balance, release: Balance) {
+        self.props.amount = match self.amount().checked_sub(&reduce_by) {
+            Some(x) => x,
+            None => panic!("Fail to sub reduce_by when set amount"),
+        };
+        self._sub_remaining(release)
+    }
+
     /// Updates the status of an order when it's being canceled.
     ///
     /// If the `already_filled` is not zero, then the status of order become
@@ -381,6 +430,56 @@ where
     }
 }
 
+/// Snapshot of an order after it was created or updated.
+///
+/// It carries the whole current state of the order, so that the indexers can
+/// track the order from any single `OrderUpdated` event.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct OrderSnapshot<PairId, AccountId, Balance, Price, BlockNumber> {
+    pub pair_id: PairId,
+    pub id: OrderId,
+    pub submitter: AccountId,
+    pub side: Side,
+    pub price: Price,
+    /// The original amount, measured in the base currency.
+    pub amount: Balance,
+    /// The executed amount, measured in the base currency.
+    pub already_filled: Balance,
+    /// The amount still reserved, i.e., the quote currency of a bid or the base currency of an ask.
+    pub remaining: Balance,
+    pub status: OrderStatus,
+    /// Block number at which the order was updated.
+    pub updated_at: BlockNumber,
+}
+
+impl<PairId, AccountId, Balance, Price, BlockNumber>
+    From<&Order<PairId, AccountId, Balance, Price, BlockNumber>>
+    for OrderSnapshot<PairId, AccountId, Balance, Price, BlockNumber>
+where
+    PairId: Copy,
+    AccountId: Clone,
+    Balance: Copy,
+    Price: Copy,
+    BlockNumber: Copy,
+{
+    fn from(order: &Order<PairId, AccountId, Balance, Price, BlockNumber>) -> Self {
+        Self {
+            pair_id: order.props.pair_id,
+            id: order.props.id,
+            submitter: order.props.submitter.clone(),
+            side: order.props.side,
+            price: order.props.price,
+            amount: order.props.amount,
+            already_filled: order.already_filled,
+            remaining: order.remaining,
+            status: order.status,
+            updated_at: order.last_update_at,
+        }
+    }
+}
+
 /// Latest price of a trading pair.
 #[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
@@ -392,6 +491,78 @@ pub struct TradingPairInfo<Price, BlockNumber> {
     pub last_updated: BlockNumber,
 }
 
+/// Circuit breaker of a trading pair.
+///
+/// The matching of the trading pair is halted once a fill would execute beyond
+/// the band around the first trade price of the block.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct CircuitBreaker<BlockNumber> {
+    /// Maximum price move in percent relative to the first trade price of the block.
+    pub max_move_per_block_percent: u32,
+    /// How many blocks the matching stays halted once the breaker is tripped.
+    pub halt_duration_blocks: BlockNumber,
+}
+
+impl<BlockNumber> CircuitBreaker<BlockNumber> {
+    /// Returns true if `price` is within the band around `opening_price`.
+    pub fn is_within_band<Price: BaseArithmetic>(
+        &self,
+        opening_price: Price,
+        price: Price,
+    ) -> bool {
+        let opening_price = opening_price.saturated_into::<u128>();
+        let price = price.saturated_into::<u128>();
+        abs_diff(price, opening_price).saturating_mul(100)
+            <= opening_price.saturating_mul(u128::from(self.max_move_per_block_percent))
+    }
+}
+
+/// Cumulative asset movements of a trading pair on settlement.
+///
+/// Every settled amount is debited from the reserved balance of one party
+/// and credited to the free balance of the other, so the debits must always
+/// equal the credits for both the base and quote currency.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct SettlementCounters<Balance> {
+    /// Total base currency debited from the order submitters.
+    pub base_debited: Balance,
+    /// Total base currency credited to the order submitters.
+    pub base_credited: Balance,
+    /// Total quote currency debited from the order submitters.
+    pub quote_debited: Balance,
+    /// Total quote currency credited to the order submitters.
+    pub quote_credited: Balance,
+}
+
+impl<Balance: Copy + BaseArithmetic> SettlementCounters<Balance> {
+    /// Returns the absolute difference between the base debits and credits.
+    pub fn base_delta(&self) -> Balance {
+        abs_diff(self.base_debited, self.base_credited)
+    }
+
+    /// Returns the absolute difference between the quote debits and credits.
+    pub fn quote_delta(&self) -> Balance {
+        abs_diff(self.quote_debited, self.quote_credited)
+    }
+
+    /// Returns true if there is no drift for both the base and quote currency.
+    pub fn is_balanced(&self) -> bool {
+        self.base_delta().is_zero() && self.quote_delta().is_zero()
+    }
+}
+
+fn abs_diff<Balance: Copy + BaseArithmetic>(a: Balance, b: Balance) -> Balance {
+    if a >= b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
 /// Information about the executed orders.
 #[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub struct OrderExecutedInfo<AccountId, Balance, BlockNumber, Price> {
@@ -431,3 +602,35 @@ impl<AccountId: Clone, Balance: Copy + Ord + BaseArithmetic, BlockNumber: Copy,
         }
     }
 }
+
+/// Type for counting the number of trading competitions.
+pub type CompetitionId = u32;
+
+/// The stage of delisting a trading pair.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum DelistStatus<BlockNumber> {
+    /// The new orders are rejected, the matching continues until this block.
+    Scheduled(BlockNumber),
+    /// The matching is stopped, the open orders are being canceled.
+    Closing,
+    /// All the open orders have been canceled.
+    Retired,
+}
+
+/// A trading competition of a trading pair.
+///
+/// The quote volume of the fills in `[start_block, end_block)` is counted.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct Competition<BlockNumber> {
+    pub pair_id: TradingPairId,
+    pub start_block: BlockNumber,
+    pub end_block: BlockNumber,
+    /// Whether the volume of the maker is counted as well as the taker.
+    pub count_maker: bool,
+    /// The volume counters are read-only once the competition ended.
+    pub ended: bool,
+}
@@ -34,10 +34,19 @@ pub trait WeightInfo {
     fn put_order() -> Weight;
     fn cancel_order() -> Weight;
     fn force_cancel_order() -> Weight;
+    fn force_cancel_pair_orders(n: u32) -> Weight;
     fn set_handicap() -> Weight;
     fn set_price_fluctuation() -> Weight;
     fn add_trading_pair() -> Weight;
     fn update_trading_pair() -> Weight;
+    fn set_min_notional() -> Weight;
+    fn set_trading_operator() -> Weight;
+    fn set_circuit_breaker() -> Weight;
+    fn cancel_by_client_id() -> Weight;
+    fn reduce_order() -> Weight;
+    fn start_competition() -> Weight;
+    fn prune_competition(n: u32) -> Weight;
+    fn schedule_pair_delist() -> Weight;
 }
 
 /// Weights for xpallet_dex_spot using the Substrate node and recommended hardware.
@@ -45,8 +54,8 @@ pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
     fn put_order() -> Weight {
         (142_883_000 as Weight)
-            .saturating_add(T::DbWeight::get().reads(11 as Weight))
-            .saturating_add(T::DbWeight::get().writes(6 as Weight))
+            .saturating_add(T::DbWeight::get().reads(12 as Weight))
+            .saturating_add(T::DbWeight::get().writes(8 as Weight))
     }
     fn cancel_order() -> Weight {
         (133_946_000 as Weight)
@@ -58,6 +67,15 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
             .saturating_add(T::DbWeight::get().reads(8 as Weight))
             .saturating_add(T::DbWeight::get().writes(5 as Weight))
     }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `force_cancel_pair_orders` benchmark.
+    fn force_cancel_pair_orders(n: u32) -> Weight {
+        (14_215_000 as Weight)
+            .saturating_add((125_764_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().reads((7 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes((5 as Weight).saturating_mul(n as Weight)))
+    }
     fn set_handicap() -> Weight {
         (8_101_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
     }
@@ -65,23 +83,79 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
         (19_612_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
     }
     fn add_trading_pair() -> Weight {
-        (38_706_000 as Weight)
+        (40_112_000 as Weight)
             .saturating_add(T::DbWeight::get().reads(2 as Weight))
-            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
     }
     fn update_trading_pair() -> Weight {
         (32_363_000 as Weight)
             .saturating_add(T::DbWeight::get().reads(1 as Weight))
             .saturating_add(T::DbWeight::get().writes(1 as Weight))
     }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_min_notional` benchmark.
+    fn set_min_notional() -> Weight {
+        (23_845_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_trading_operator` benchmark.
+    fn set_trading_operator() -> Weight {
+        (18_372_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_circuit_breaker` benchmark.
+    fn set_circuit_breaker() -> Weight {
+        (24_107_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `cancel_by_client_id` benchmark.
+    fn cancel_by_client_id() -> Weight {
+        (136_512_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(9 as Weight))
+            .saturating_add(T::DbWeight::get().writes(7 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `reduce_order` benchmark.
+    fn reduce_order() -> Weight {
+        (71_254_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `start_competition` benchmark.
+    fn start_competition() -> Weight {
+        (27_318_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `prune_competition` benchmark.
+    fn prune_competition(n: u32) -> Weight {
+        (12_406_000 as Weight)
+            .saturating_add((2_873_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(n as Weight)))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `schedule_pair_delist` benchmark.
+    fn schedule_pair_delist() -> Weight {
+        (18_604_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
     fn put_order() -> Weight {
         (142_883_000 as Weight)
-            .saturating_add(RocksDbWeight::get().reads(11 as Weight))
-            .saturating_add(RocksDbWeight::get().writes(6 as Weight))
+            .saturating_add(RocksDbWeight::get().reads(12 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(8 as Weight))
     }
     fn cancel_order() -> Weight {
         (133_946_000 as Weight)
@@ -93,6 +167,15 @@ impl WeightInfo for () {
             .saturating_add(RocksDbWeight::get().reads(8 as Weight))
             .saturating_add(RocksDbWeight::get().writes(5 as Weight))
     }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `force_cancel_pair_orders` benchmark.
+    fn force_cancel_pair_orders(n: u32) -> Weight {
+        (14_215_000 as Weight)
+            .saturating_add((125_764_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((7 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes((5 as Weight).saturating_mul(n as Weight)))
+    }
     fn set_handicap() -> Weight {
         (8_101_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
     }
@@ -100,13 +183,69 @@ impl WeightInfo for () {
         (19_612_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
     }
     fn add_trading_pair() -> Weight {
-        (38_706_000 as Weight)
+        (40_112_000 as Weight)
             .saturating_add(RocksDbWeight::get().reads(2 as Weight))
-            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
     }
     fn update_trading_pair() -> Weight {
         (32_363_000 as Weight)
             .saturating_add(RocksDbWeight::get().reads(1 as Weight))
             .saturating_add(RocksDbWeight::get().writes(1 as Weight))
     }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_min_notional` benchmark.
+    fn set_min_notional() -> Weight {
+        (23_845_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_trading_operator` benchmark.
+    fn set_trading_operator() -> Weight {
+        (18_372_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_circuit_breaker` benchmark.
+    fn set_circuit_breaker() -> Weight {
+        (24_107_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `cancel_by_client_id` benchmark.
+    fn cancel_by_client_id() -> Weight {
+        (136_512_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(9 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(7 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `reduce_order` benchmark.
+    fn reduce_order() -> Weight {
+        (71_254_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `start_competition` benchmark.
+    fn start_competition() -> Weight {
+        (27_318_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `prune_competition` benchmark.
+    fn prune_competition(n: u32) -> Weight {
+        (12_406_000 as Weight)
+            .saturating_add((2_873_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(n as Weight)))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `schedule_pair_delist` benchmark.
+    fn schedule_pair_delist() -> Weight {
+        (18_604_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
 }
@@ -6,9 +6,20 @@
 
 use sp_runtime::DispatchError;
 use sp_std::vec::Vec;
-pub use xpallet_gateway_bitcoin::{types::BtcHeaderInfo, BtcHeader, BtcWithdrawalProposal, H256};
+pub use xpallet_gateway_bitcoin::{
+    types::{
+        BtcBridgeStatus, BtcHeaderInfo, BtcTxInfo, BtcWithdrawalNetworkFee, BtcWithdrawalOutput,
+        BtcWithdrawalTxInfo, VoteResult,
+    },
+    BtcHeader, BtcWithdrawalProposal, H256,
+};
 
 sp_api::decl_runtime_apis! {
+    /// Version 2 adds `archived_btc_headers`.
+    /// Version 3 adds `withdrawal_network_fee`.
+    /// Version 4 adds `get_withdrawal_tx_info`.
+    /// Version 5 adds `get_btc_tx_info`, `get_bridge_status` and `btc_relay_stats`.
+    #[api_version(5)]
     pub trait XGatewayBitcoinApi<AccountId>
         where AccountId: codec::Codec
     {
@@ -23,5 +34,21 @@ sp_api::decl_runtime_apis! {
         fn get_genesis_info() -> (BtcHeader, u32);
 
         fn get_btc_block_header(txid: H256) -> Option<BtcHeaderInfo>;
+
+        fn get_btc_tx_info(txid: H256) -> Option<BtcTxInfo>;
+
+        fn get_bridge_status() -> BtcBridgeStatus;
+
+        fn btc_relay_stats(last_n: u32) -> Vec<(u32, u32, u32, AccountId)>;
+
+        /// Returns the headers pruned from the storage in this block.
+        fn archived_btc_headers() -> Vec<(H256, BtcHeaderInfo)>;
+
+        /// Returns the estimated share of the bitcoin network fee of a withdrawal to `addr`.
+        fn withdrawal_network_fee(addr: Vec<u8>) -> Result<BtcWithdrawalNetworkFee, DispatchError>;
+
+        /// Returns the current withdrawal proposal with its tx outputs matched against the
+        /// withdrawal applications.
+        fn get_withdrawal_tx_info() -> Option<BtcWithdrawalTxInfo<AccountId>>;
     }
 }
@@ -1,21 +1,32 @@
 // Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
 
 //! RPC interface for the transaction verification.
-use codec::Codec;
+use codec::{Codec, Decode};
 use jsonrpc_derive::rpc;
 use std::sync::Arc;
 use std::vec::Vec;
 
-use sp_api::ProvideRuntimeApi;
+use sc_client_api::AuxStore;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_runtime::{generic::BlockId, traits::Block as BlockT};
 
-use xp_rpc::{runtime_error_into_rpc_err, Result};
+use xp_rpc::{
+    decode_error_into_rpc_err, runtime_error_into_rpc_err, unsupported_api_rpc_err, Result,
+};
 use xpallet_gateway_bitcoin_rpc_runtime_api::{
-    BtcHeader, BtcHeaderInfo, BtcWithdrawalProposal,
-    XGatewayBitcoinApi as XGatewayBitcoinRuntimeApi, H256,
+    BtcBridgeStatus, BtcHeader, BtcHeaderInfo, BtcTxInfo, BtcWithdrawalProposal,
+    BtcWithdrawalTxInfo, XGatewayBitcoinApi as XGatewayBitcoinRuntimeApi, H256,
 };
 
+/// Prefix of the archived bitcoin headers in the auxiliary storage of the archive nodes.
+const ARCHIVED_BTC_HEADER_PREFIX: &[u8] = b"chainx_archive_btc_header";
+
+/// Returns the auxiliary storage key of the archived bitcoin header `hash`.
+pub fn archived_btc_header_key(hash: &H256) -> Vec<u8> {
+    [ARCHIVED_BTC_HEADER_PREFIX, hash.as_bytes()].concat()
+}
+
 pub struct XGatewayBitcoin<C, B, AccountId> {
     client: Arc<C>,
     _marker: std::marker::PhantomData<(B, AccountId)>,
@@ -31,6 +42,31 @@ impl<C, B, AccountId> XGatewayBitcoin<C, B, AccountId> {
     }
 }
 
+impl<C, Block, AccountId> XGatewayBitcoin<C, Block, AccountId>
+where
+    Block: BlockT,
+    C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    C::Api: XGatewayBitcoinRuntimeApi<Block, AccountId>,
+    AccountId: Codec + Send + Sync + 'static,
+{
+    /// Returns an error if the runtime at `at` provides an `XGatewayBitcoinApi` older than
+    /// `version`.
+    fn ensure_api_version(&self, at: &BlockId<Block>, version: u32) -> Result<()> {
+        let supported = self
+            .client
+            .runtime_api()
+            .has_api_with::<dyn XGatewayBitcoinRuntimeApi<Block, AccountId>, _>(at, |v| {
+                v >= version
+            })
+            .map_err(runtime_error_into_rpc_err)?;
+        if supported {
+            Ok(())
+        } else {
+            Err(unsupported_api_rpc_err("XGatewayBitcoinApi", version))
+        }
+    }
+}
+
 #[rpc]
 pub trait XGatewayBitcoinApi<BlockHash, AccountId> {
     /// Verify transaction is valid
@@ -61,13 +97,41 @@ pub trait XGatewayBitcoinApi<BlockHash, AccountId> {
         txid: H256,
         at: Option<BlockHash>,
     ) -> Result<Option<BtcHeaderInfo>>;
+
+    /// Get the type, handling result and classification evidence of a relayed transaction
+    #[rpc(name = "chainx_getBitcoinTxInfo")]
+    fn get_btc_tx_info(&self, txid: H256, at: Option<BlockHash>) -> Result<Option<BtcTxInfo>>;
+
+    /// Get the status of the bridge, including the relayer whitelist mode and relayer count
+    #[rpc(name = "xgatewaybitcoin_getBridgeStatus")]
+    fn get_bridge_status(&self, at: Option<BlockHash>) -> Result<BtcBridgeStatus>;
+
+    /// Get the (btc_height, btc_time, accepted_at_chainx_block, relayer) of the last `last_n` headers
+    #[rpc(name = "xgatewaybitcoin_btcRelayStats")]
+    fn btc_relay_stats(
+        &self,
+        last_n: u32,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<(u32, u32, u32, AccountId)>>;
+
+    /// Get a block header pruned from the storage, only available on the archive nodes
+    #[rpc(name = "chainx_getArchivedBtcHeader")]
+    fn get_archived_btc_header(&self, hash: H256) -> Result<Option<BtcHeaderInfo>>;
+
+    /// Get the current withdrawal proposal decoded for the trustees to review, including
+    /// the destination and value of each output and the withdrawal it pays
+    #[rpc(name = "chainx_getWithdrawTx")]
+    fn get_withdrawal_tx_info(
+        &self,
+        at: Option<BlockHash>,
+    ) -> Result<Option<BtcWithdrawalTxInfo<AccountId>>>;
 }
 
 impl<C, Block, AccountId> XGatewayBitcoinApi<<Block as BlockT>::Hash, AccountId>
     for XGatewayBitcoin<C, Block, AccountId>
 where
     Block: BlockT,
-    C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore,
     C::Api: XGatewayBitcoinRuntimeApi<Block, AccountId>,
     AccountId: Codec + Send + Sync + 'static,
 {
@@ -121,4 +185,66 @@ where
             .map_err(runtime_error_into_rpc_err)?;
         Ok(reslut)
     }
+
+    fn get_btc_tx_info(
+        &self,
+        txid: H256,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<BtcTxInfo>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        self.ensure_api_version(&at, 5)?;
+        let result = api
+            .get_btc_tx_info(&at, txid)
+            .map_err(runtime_error_into_rpc_err)?;
+        Ok(result)
+    }
+
+    fn get_bridge_status(&self, at: Option<<Block as BlockT>::Hash>) -> Result<BtcBridgeStatus> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        self.ensure_api_version(&at, 5)?;
+        let result = api
+            .get_bridge_status(&at)
+            .map_err(runtime_error_into_rpc_err)?;
+        Ok(result)
+    }
+
+    fn btc_relay_stats(
+        &self,
+        last_n: u32,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<(u32, u32, u32, AccountId)>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        self.ensure_api_version(&at, 5)?;
+        let result = api
+            .btc_relay_stats(&at, last_n)
+            .map_err(runtime_error_into_rpc_err)?;
+        Ok(result)
+    }
+
+    fn get_archived_btc_header(&self, hash: H256) -> Result<Option<BtcHeaderInfo>> {
+        let encoded = self
+            .client
+            .get_aux(&archived_btc_header_key(&hash))
+            .map_err(runtime_error_into_rpc_err)?;
+        encoded
+            .map(|encoded| BtcHeaderInfo::decode(&mut encoded.as_slice()))
+            .transpose()
+            .map_err(decode_error_into_rpc_err)
+    }
+
+    fn get_withdrawal_tx_info(
+        &self,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<BtcWithdrawalTxInfo<AccountId>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        self.ensure_api_version(&at, 4)?;
+        let result = api
+            .get_withdrawal_tx_info(&at)
+            .map_err(runtime_error_into_rpc_err)?;
+        Ok(result)
+    }
 }
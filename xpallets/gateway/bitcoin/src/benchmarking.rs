@@ -9,6 +9,7 @@ use sp_std::{collections::btree_map::BTreeMap, prelude::*};
 use xp_gateway_bitcoin::{BtcTxType, OpReturnAccount};
 use xp_protocol::X_BTC;
 use xpallet_assets::BalanceOf;
+use xpallet_gateway_common::traits::TrusteeSession;
 use xpallet_gateway_records::{Pallet as XGatewayRecords, WithdrawalState};
 
 use light_bitcoin::{
@@ -18,7 +19,12 @@ use light_bitcoin::{
     serialization::{self, Reader, SERIALIZE_TRANSACTION_WITNESS},
 };
 
-use crate::{types::*, Call, Config, Pallet, PendingDeposits, TxState, WithdrawalProposal};
+use crate::{
+    types::*, AddressWithdrawCooldown, BlockHashFor, BridgeHalted, Call, ColdSpendAuthorization,
+    Config, ConfirmedIndex, FlaggedBtcAddresses, Headers, MaxQueueAgeBlocks, MaxQueueDepth, Pallet,
+    PendingDeposits, PrunedHeaderHeight, RawTxRegistrations, RelayerWhitelistEnabled, Relayers,
+    SuspectDeposits, TxState, WithdrawalProposal,
+};
 
 fn generate_blocks_63290_63310() -> BTreeMap<u32, BlockHeader> {
     let bytes = include_bytes!("./res/headers-63290-63310.raw");
@@ -43,7 +49,7 @@ fn alice<T: Config>() -> T::AccountId {
 //     account::<T>("8eaf04151687736326c9fea17e25fc5287613693c912909cb226aa4794f26a48")
 // }
 
-fn withdraw_tx() -> (Transaction, Vec<u8>, Transaction) {
+pub(crate) fn withdraw_tx() -> (Transaction, Vec<u8>, Transaction) {
     // block height: 63299
     // https://signet.bitcoinexplorer.org/tx/0f592933b493bedab209851cb2cf07871558ff57d86d645877b16651479b51a2
     const RAW_TX: &str = "020000000001015fea22ec1a3e3e7e1167fa220cc8376225f07bd20aa194e7f3c4ac68c7375d8e0000000000000000000250c3000000000000225120c9929543dfa1e0bb84891acd47bfa6546b05e26b7a04af8eb6765fcc969d565f409c0000000000002251209a9ea267884f5549c206b2aec2bd56d98730f90532ea7f7154d4d4f923b7e3bb03402639d4d9882f6e7e42db38dbd2845c87b131737bf557643ef575c49f8fc6928869d9edf5fd61606fb07cced365fdc2c7b637e6ecc85b29906c16d314e7543e94222086a60c7d5dd3f4931cc8ad77a614402bdb591c042347c89281c48c7e9439be9dac61c0e56a1792f348690cdeebe60e3db6c4e94d94e742c619f7278e52f6cbadf5efe96a528ba3f61a5b0d4fbceea425a9028381458b32492bccc3f1faa473a649e23605554f5ea4b4044229173719228a35635eeffbd8a8fe526270b737ad523b99f600000000";
@@ -243,6 +249,153 @@ benchmarks! {
     }: _(RawOrigin::Root,  Some(caller))
     verify {
     }
+
+    set_bridge_halted {
+    }: _(RawOrigin::Root, true)
+    verify {
+        assert!(BridgeHalted::<T>::get());
+    }
+
+    remove_suspect_deposit {
+        let tx_hash = H256::repeat_byte(1);
+        SuspectDeposits::<T>::insert(tx_hash, H256::repeat_byte(2));
+    }: _(RawOrigin::Root, tx_hash)
+    verify {
+        assert!(SuspectDeposits::<T>::get(tx_hash).is_none());
+    }
+
+    process_deferred_deposits {
+        let n in 1 .. 64;
+
+        let receiver: T::AccountId = whitelisted_caller();
+        for i in 0..n {
+            Pallet::<T>::push_deferred_deposit(BtcDeferredDeposit {
+                txid: H256::repeat_byte(i as u8),
+                input_addr: None,
+                balance: 100000,
+                account: OpReturnAccount::Wasm(receiver.clone()),
+            });
+        }
+    }: _(RawOrigin::Root, n)
+    verify {
+        assert!(Pallet::<T>::deferred_deposits().is_empty());
+    }
+
+    set_relayer_whitelist_enabled {
+    }: _(RawOrigin::Root, true)
+    verify {
+        assert!(RelayerWhitelistEnabled::<T>::get());
+    }
+
+    add_relayer {
+        let relayer: T::AccountId = whitelisted_caller();
+    }: _(RawOrigin::Root, relayer.clone())
+    verify {
+        assert!(Relayers::<T>::contains_key(&relayer));
+    }
+
+    remove_relayer {
+        let relayer: T::AccountId = whitelisted_caller();
+        Relayers::<T>::insert(&relayer, ());
+    }: _(RawOrigin::Root, relayer.clone())
+    verify {
+        assert!(!Relayers::<T>::contains_key(&relayer));
+    }
+
+    set_btc_address_flag {
+        let addr = b"1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2".to_vec();
+    }: _(RawOrigin::Root, addr.clone(), Some(FlagLevel::Block))
+    verify {
+        let address = Pallet::<T>::verify_btc_address(&addr).unwrap();
+        assert_eq!(
+            FlaggedBtcAddresses::<T>::get(Pallet::<T>::btc_address_hash(&address)),
+            Some(FlagLevel::Block)
+        );
+    }
+
+    set_address_withdraw_cooldown {
+        let cooldown: T::BlockNumber = 100u32.into();
+    }: _(RawOrigin::Root, Some(cooldown))
+    verify {
+        assert_eq!(AddressWithdrawCooldown::<T>::get(), Some(cooldown));
+    }
+
+    prune_headers {
+        let n in 1 .. 64;
+
+        let (_, genesis_height) = Pallet::<T>::genesis_info();
+        for i in 0..n {
+            let hash = H256::repeat_byte(i as u8 + 1);
+            let height = genesis_height + i;
+            Headers::<T>::insert(&hash, BtcHeaderInfo { header: Default::default(), height });
+            BlockHashFor::<T>::append(height, hash);
+        }
+        ConfirmedIndex::<T>::put(BtcHeaderIndex {
+            hash: H256::repeat_byte(0xff),
+            height: genesis_height + n + Pallet::<T>::params_info().retargeting_interval(),
+        });
+    }: _(RawOrigin::Root, genesis_height + n, n)
+    verify {
+        assert_eq!(PrunedHeaderHeight::<T>::get(), genesis_height + n);
+        assert!(Headers::<T>::get(&H256::repeat_byte(1)).is_none());
+    }
+
+    authorize_cold_spend {
+        let caller = T::TrusteeSessionProvider::trustee_multisig().unwrap();
+        let expiry = frame_system::Pallet::<T>::block_number() + 100u32.into();
+    }: _(RawOrigin::Signed(caller), 100_000_000, expiry)
+    verify {
+        assert_eq!(ColdSpendAuthorization::<T>::get().unwrap().max_value, 100_000_000);
+    }
+
+    register_raw_tx {
+        let caller: T::AccountId = alice::<T>();
+        let (tx, _, _) = withdraw_tx();
+        let tx_hash = tx.hash();
+        let tx_raw: Vec<u8> = serialization::serialize_with_flags(&tx, SERIALIZE_TRANSACTION_WITNESS).into();
+    }: _(RawOrigin::Signed(caller.clone()), tx_hash, 0, 1, tx_raw)
+    verify {
+        assert!(RawTxRegistrations::<T>::get(&caller, &tx_hash).unwrap().is_complete());
+    }
+
+    push_registered_transaction {
+        let caller: T::AccountId = alice::<T>();
+        prepare_headers::<T>(&caller);
+        let (tx, info, prev_tx) = withdraw_tx();
+        let tx_hash = tx.hash();
+        let tx_raw: Vec<u8> = serialization::serialize_with_flags(&tx, SERIALIZE_TRANSACTION_WITNESS).into();
+        let prev_tx_raw = serialization::serialize_with_flags(&prev_tx, SERIALIZE_TRANSACTION_WITNESS).into();
+        Pallet::<T>::register_raw_tx(RawOrigin::Signed(caller.clone()).into(), tx_hash, 0, 1, tx_raw)?;
+
+        let amount: BalanceOf<T> = 1_000_000_000u32.into();
+        let withdrawal = 550000u32.into();
+
+        XGatewayRecords::<T>::deposit(&caller, X_BTC, amount).unwrap();
+        XGatewayRecords::<T>::withdraw(&caller, X_BTC, withdrawal, b"tb1pexff2s7l58sthpyfrtx500ax234stcnt0gz2lr4kwe0ue95a2e0srxsc68".to_vec(), b"".to_vec().into()).unwrap();
+
+        XGatewayRecords::<T>::withdrawal_state_insert(0, WithdrawalState::Processing);
+
+        let proposal = BtcWithdrawalProposal::<T::AccountId> {
+            sig_state: VoteResult::Finish,
+            withdrawal_id_list: vec![0],
+            tx,
+            trustee_list: vec![],
+        };
+        WithdrawalProposal::<T>::put(proposal);
+
+    }: _(RawOrigin::Signed(caller.clone()), tx_hash, info, Some(prev_tx_raw))
+    verify {
+        assert!(WithdrawalProposal::<T>::get().is_none());
+        assert!(RawTxRegistrations::<T>::get(&caller, &tx_hash).is_none());
+    }
+
+    set_withdrawal_queue_thresholds {
+        let max_queue_age_blocks: T::BlockNumber = 100u32.into();
+    }: _(RawOrigin::Root, 10, max_queue_age_blocks)
+    verify {
+        assert_eq!(MaxQueueDepth::<T>::get(), 10);
+        assert_eq!(MaxQueueAgeBlocks::<T>::get(), max_queue_age_blocks);
+    }
 }
 
 #[cfg(test)]
@@ -263,6 +416,19 @@ mod tests {
             assert_ok!(Pallet::<Test>::test_benchmark_set_btc_withdrawal_fee());
             assert_ok!(Pallet::<Test>::test_benchmark_set_btc_deposit_limit());
             assert_ok!(Pallet::<Test>::test_benchmark_set_coming_bot());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_bridge_halted());
+            assert_ok!(Pallet::<Test>::test_benchmark_remove_suspect_deposit());
+            assert_ok!(Pallet::<Test>::test_benchmark_process_deferred_deposits());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_relayer_whitelist_enabled());
+            assert_ok!(Pallet::<Test>::test_benchmark_add_relayer());
+            assert_ok!(Pallet::<Test>::test_benchmark_remove_relayer());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_btc_address_flag());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_address_withdraw_cooldown());
+            assert_ok!(Pallet::<Test>::test_benchmark_prune_headers());
+            assert_ok!(Pallet::<Test>::test_benchmark_authorize_cold_spend());
+            assert_ok!(Pallet::<Test>::test_benchmark_register_raw_tx());
+            assert_ok!(Pallet::<Test>::test_benchmark_push_registered_transaction());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_withdrawal_queue_thresholds());
         });
     }
 }
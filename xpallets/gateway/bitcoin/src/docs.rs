@@ -0,0 +1,101 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Documentation of the events and errors, served by `chainx_getRuntimeDocs`.
+
+xpallet_support::pallet_docs! {
+    "XGatewayBitcoin",
+    events {
+        HeaderInserted => "A Bitcoin header was validated and inserted. [btc_header_hash]",
+        TxProcessed => "A Bitcoin transaction was processed. [tx_hash, block_hash, tx_state]",
+        Deposited => "An account deposited some token. [tx_hash, who, amount]",
+        Withdrawn => "A list of withdrawal applications were processed successfully. [tx_hash, withdrawal_ids, total_withdrawn]",
+        UnclaimedDeposit => "A new record of unclaimed deposit. [tx_hash, btc_address]",
+        PendingDepositRemoved => "A unclaimed deposit record was removed for wasm address. [depositor, deposit_amount, tx_hash, btc_address]",
+        WithdrawalProposalCreated => "A new withdrawal proposal was created. [proposer, withdrawal_ids]",
+        WithdrawalProposalVoted => "A trustee voted/vetoed a withdrawal proposal. [trustee, vote_status]",
+        WithdrawalFatalErr => "A fatal error happened during the withdrawal process. [tx_hash, proposal_hash]",
+        DepositedEvm => "An account deposited some token for evm address. [tx_hash, who, amount]",
+        PendingDepositEvmRemoved => "A unclaimed deposit record was removed for evm address. [depositor, deposit_amount, tx_hash, btc_address]",
+        DepositedAptos => "An account deposited some token for aptos address. [tx_hash, who, amount]",
+        PendingDepositAptosRemoved => "A unclaimed deposit record was removed for aptos address. [depositor, deposit_amount, tx_hash, btc_address]",
+        DepositedNamed => "An account deposited some token for named address. [tx_hash, prefix, who, amount]",
+        PendingDepositNamedRemoved => "A unclaimed deposit record was removed for named address. [prefix, depositor, deposit_amount, tx_hash, btc_address]",
+        ConfirmedBlockOrphaned => "A block previously confirmed is no longer on the main chain, the bridge is halted. [block_hash, height]",
+        BridgeHaltUpdated => "The bridge was halted or resumed. [halted]",
+        DepositDeferred => "A deposit was deferred since the deposits are disabled. [tx_hash, deposit_amount]",
+        DeferredDepositsProcessed => "Some deferred deposits were credited. [processed, remaining]",
+        RelayerWhitelistUpdated => "The relayer whitelist was enabled or disabled. [enabled]",
+        RelayerAdded => "An account was added to the relayer whitelist. [relayer]",
+        RelayerRemoved => "An account was removed from the relayer whitelist. [relayer]",
+        BtcAddressFlagUpdated => "The flag of a withdrawal address was updated. [btc_address, level]",
+        AddressWithdrawCooldownUpdated => "The cooldown of the withdrawal addresses was updated. [cooldown]",
+        FlaggedWithdrawal => "An account applied for a withdrawal to a flagged address. [who, btc_address]",
+        BtcHeaderArchived => "A header was removed from the storage, carrying its full content for the archive nodes. [block_hash, header_info]",
+        HeadersPruned => "The headers were pruned up to a height. [pruned_below_height]",
+        ColdSpendAuthorized => "The trustees authorized to spend the cold UTXOs. [max_value, expiry_block]",
+        ColdSpendUsed => "A withdrawal proposal spent some cold UTXOs. [spent_value, remaining_value]",
+        ColdSpendExpired => "The cold spend authorization expired. [used_value]",
+        WithdrawalAddressRejected => "A legacy withdrawal with an invalid address was canceled when creating the withdrawal proposal. [withdrawal_id]",
+        TxHandleSkipped => "A relayed tx was skipped without being handled, it could be relayed again with the right previous tx. [tx_hash, reason]",
+        RawTxRegistered => "All the chunks of a raw tx were registered. [relayer, tx_hash]",
+        RawTxRegistrationPruned => "A raw tx registration was pruned without being relayed. [relayer, tx_hash]",
+        ChannelOnlyDepositPending => "A deposit with a channel-only OP_RETURN went pending because the input address has no binding. [tx_hash, channel, btc_address]",
+        DepositHeldUntilMature => "A deposit spending a coinbase output was held until its block is deep enough. [tx_hash, mature_height]",
+        ImmatureDepositDropped => "A held deposit was dropped since its block is no longer in the main chain. [tx_hash]",
+        WithdrawalProposalRequired => "The applying withdrawals exceeded the queue thresholds while no proposal is in progress, the trustees should create one. [applying_count, total_value]",
+        DeferredDepositFailed => "A deferred deposit failed to be credited, it was kept in the queue. [tx_hash]",
+        ColdSpendRefunded => "A removed withdrawal proposal returned its cold UTXOs to the cold spend authorization. [refunded_value, remaining_value]",
+    }
+    errors {
+        InvalidBase58 => "parse base58 addr error",
+        InvalidAddr => "load addr from bytes error",
+        InvalidPoW => "Invalid proof-of-work (Block hash does not satisfy nBits)",
+        AncientFork => "Fork is too long to proceed",
+        InvalidPrevTx => "Previous tx id not equal input point hash",
+        HeaderFuturisticTimestamp => "Futuristic timestamp",
+        HeaderNBitsNotMatch => "nBits do not match difficulty rules",
+        ExistingHeader => "Header already exists",
+        PrevHeaderNotExisted => "Can't find previous header",
+        DeserializeErr => "Cannot deserialize the header or tx vec",
+        BadMerkleProof => "Invalid merkle proof",
+        UnconfirmedTx => "The tx is not yet confirmed, i.e, the block of which is not confirmed.",
+        ReplayedTx => "reject replay proccessed tx",
+        ProcessTxFailed => "process tx failed",
+        InvalidAddress => "invalid bitcoin address",
+        InvalidPublicKey => "invalid bitcoin public key",
+        NotTrustee => "not set trustee yet",
+        DuplicatedKeys => "duplicated pubkey for trustees",
+        GenerateMultisigFailed => "can't generate multisig address",
+        InvalidTrusteeCount => "invalid trustee count",
+        WrongWithdrawalCount => "unexpected withdraw records count",
+        NoProposal => "no proposal for current withdrawal",
+        TxOutputsNotMatch => "tx's outputs not match withdrawal id list",
+        NotFinishProposal => "last proposal not finished yet",
+        NoWithdrawalRecord => "no withdrawal record for this id",
+        DuplicateVote => "already vote for this withdrawal proposal",
+        TrusteeTransitionPeriod => "Trustee transition period",
+        TxOutputNotColdAddr => "The output address must be a cold address during the trust transition process",
+        TxNotFullAmount => "The total amount of the trust must be transferred out in full",
+        AddressNetworkMismatch => "the network of bitcoin address does not match the configured network",
+        WithdrawToTrusteeAddress => "withdraw to the trustee hot or cold address is not allowed",
+        BridgeHalted => "the bridge is halted",
+        NoSuspectDeposit => "no suspect deposit for this tx",
+        DepositsDisabled => "the deposits of bitcoin are disabled",
+        NotWhitelistedRelayer => "the signer is not a whitelisted relayer",
+        DuplicatedRelayer => "the relayer is already whitelisted",
+        NoRelayer => "the relayer is not whitelisted",
+        BlockedWithdrawalAddress => "the withdrawal address is blocked",
+        WithdrawalAddressInCooldown => "the withdrawal address is still in cooldown",
+        PruneHeightTooHigh => "the headers to prune are not old enough",
+        NotTrusteeMultisig => "the origin is not the trustee multisig",
+        InvalidColdSpendExpiry => "the expiry of the cold spend authorization is not in the future",
+        ColdSpendNotAuthorized => "the proposal spends the cold UTXOs without the authorization",
+        ColdSpendExceeded => "the proposal spends more cold UTXOs than authorized",
+        InvalidRawTxChunk => "the chunk of the raw tx is empty, out of order or beyond the declared chunks",
+        RawTxTooLarge => "the registered raw tx exceeds `MAX_RAW_TX_SIZE`",
+        RawTxHashMismatch => "the registered raw tx does not match the declared hash",
+        NoRegisteredRawTx => "no complete raw tx registered by the sender for this hash",
+        ProposalWithoutHotInput => "the proposal spends no hot UTXO to record the trustee signatures from",
+        TooManyRawTxRegistrations => "the relayer has `MAX_RAW_TX_REGISTRATIONS_PER_RELAYER` raw tx registrations",
+    }
+}
@@ -10,7 +10,8 @@ use light_bitcoin::primitives::{hash_rev, H256};
 
 use crate::{
     types::{BtcHeaderIndex, BtcHeaderInfo},
-    Config, ConfirmedIndex, Error, MainChain, Pallet,
+    BlockTxs, BridgeHalted, Config, ConfirmedIndex, Error, Event, MainChain, Pallet,
+    SuspectDeposits,
 };
 
 pub use self::header_proof::HeaderVerifier;
@@ -68,6 +69,7 @@ fn look_back_confirmed_header<T: Config>(
 
 pub fn update_confirmed_header<T: Config>(header_info: &BtcHeaderInfo) -> Option<BtcHeaderIndex> {
     let (confirmed, chain) = look_back_confirmed_header::<T>(header_info);
+    check_orphaned_confirmed_header::<T>(&chain);
     for index in chain {
         set_main_chain::<T>(index.height, index.hash);
     }
@@ -77,6 +79,51 @@ pub fn update_confirmed_header<T: Config>(header_info: &BtcHeaderInfo) -> Option
     })
 }
 
+/// Check whether the blocks previously confirmed are still on the look back path.
+///
+/// e.g:
+///                   current_confirmed
+///  b --------------- b(orphaned) ---- b  ------ b(best)
+///  | --------------- b ---- b ------- b  ------ b --- b(now)
+///  99              100     101      102      103   104
+///
+/// A confirmed block should never be orphaned, if it happens, the txs processed in the orphaned
+/// block are recorded as suspect deposits and the bridge is halted.
+fn check_orphaned_confirmed_header<T: Config>(chain: &[BtcHeaderIndex]) {
+    let current_confirmed = match ConfirmedIndex::<T>::get() {
+        Some(index) => index,
+        None => return,
+    };
+    for index in chain
+        .iter()
+        .filter(|index| index.height <= current_confirmed.height)
+    {
+        let orphaned = match main_chain_hash::<T>(index.height) {
+            Some(hash) if hash != index.hash => hash,
+            _ => continue,
+        };
+        error!(
+            target: "runtime::bitcoin",
+            "[check_orphaned_confirmed_header] Confirmed block is orphaned, height:{}, orphaned:{:?}, now:{:?}",
+            index.height,
+            hash_rev(orphaned),
+            hash_rev(index.hash)
+        );
+        for tx_hash in BlockTxs::<T>::take(&orphaned) {
+            SuspectDeposits::<T>::insert(tx_hash, orphaned);
+        }
+        BridgeHalted::<T>::put(true);
+        Pallet::<T>::deposit_event(Event::<T>::ConfirmedBlockOrphaned(orphaned, index.height));
+    }
+}
+
+/// Returns the hash of the main chain block at the height.
+fn main_chain_hash<T: Config>(height: u32) -> Option<H256> {
+    Pallet::<T>::block_hash_for(&height)
+        .into_iter()
+        .find(|hash| Pallet::<T>::main_chain(hash))
+}
+
 fn set_main_chain<T: Config>(height: u32, main_hash: H256) {
     let hashes = Pallet::<T>::block_hash_for(&height);
     if hashes.len() == 1 {
@@ -4,6 +4,7 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+mod docs;
 mod header;
 pub mod trustee;
 mod tx;
@@ -19,7 +20,7 @@ mod tests;
 
 use sp_core::sp_std::str::FromStr;
 use sp_runtime::SaturatedConversion;
-use sp_std::prelude::*;
+use sp_std::{collections::btree_map::BTreeMap, prelude::*};
 
 #[cfg(feature = "std")]
 pub use light_bitcoin::primitives::h256_rev;
@@ -30,33 +31,41 @@ pub use light_bitcoin::{
 };
 use light_bitcoin::{
     chain::Transaction,
-    keys::{Address, DisplayLayout},
-    serialization::{deserialize, Reader},
+    keys::{Address, AddressTypes, DisplayLayout},
+    serialization::{deserialize, serialize, Reader},
 };
 
-use chainx_primitives::{AssetId, ReferralId};
-use xp_gateway_common::AccountExtractor;
+use chainx_primitives::{asset_event_topic, AssetId, ReferralId};
+use xp_gateway_common::{AccountExtractor, ExternalAddress};
 use xpallet_assets::{BalanceOf, Chain, ChainT, WithdrawalLimit};
 use xpallet_gateway_common::{
     traits::{
-        AddressBinding, ProposalProvider, ReferralBinding, TotalSupply, TrusteeInfoUpdate,
-        TrusteeSession,
+        AddressBinding, OnWithdrawalApplied, ProposalProvider, ReferralBinding, TotalSupply,
+        TrusteeInfoUpdate, TrusteeSession,
     },
     trustees::bitcoin::BtcTrusteeAddrInfo,
 };
+use xpallet_gateway_records::{WithdrawalEstimate, WithdrawalState};
 use xpallet_support::try_addr;
 
 use self::{
     trustee::{get_current_trustee_address_pair, get_last_trustee_address_pair},
-    tx::remove_pending_deposit,
+    tx::{process_deferred_deposit, remove_pending_deposit},
     types::{
-        BtcDepositCache, BtcHeaderIndex, BtcHeaderInfo, BtcRelayedTx, BtcRelayedTxInfo,
-        BtcTxResult, BtcTxState,
+        BtcBridgeStatus, BtcColdSpendAuthorization, BtcDeferredDeposit, BtcDepositCache,
+        BtcHeaderIndex, BtcHeaderInfo, BtcImmatureDeposit, BtcInputScript, BtcRawTxRegistration,
+        BtcRelayInfo, BtcRelayedTx, BtcRelayedTxInfo, BtcTxInfo, BtcTxResult, BtcTxSkipReason,
+        BtcTxState, BtcWithdrawalNetworkFee, BtcWithdrawalTxInfo, BTC_BLOCK_SECONDS,
+        DEFAULT_COINBASE_DEPOSIT_CONFIRMATIONS, DEFAULT_NETWORK_FEE_RATE, MAX_RAW_TX_CHUNKS,
+        MAX_RAW_TX_REGISTRATIONS_PER_RELAYER, MAX_RAW_TX_SIZE, MAX_RELAY_STATS_HEADERS,
+        PROPOSAL_ALERT_BACKOFF, RAW_TX_REGISTRATION_LIFETIME, RELAY_LAG_WINDOW,
+        WITHDRAWAL_QUEUE_CHECK_INTERVAL,
     },
 };
 
 pub use self::{
-    types::{BtcAddress, BtcParams, BtcTxVerifier, BtcWithdrawalProposal},
+    docs::PALLET_DOCS,
+    types::{BtcAddress, BtcParams, BtcTxVerifier, BtcWithdrawalProposal, FlagLevel},
     weights::WeightInfo,
 };
 pub use pallet::*;
@@ -81,8 +90,8 @@ pub mod pallet {
     };
     use frame_system::pallet_prelude::*;
     use sp_core::H160;
-    use sp_runtime::traits::Saturating;
-    use xp_gateway_bitcoin::OpReturnAccount;
+    use sp_runtime::traits::{Saturating, Zero};
+    use xp_gateway_bitcoin::{BtcTxEvidence, OpReturnAccount};
 
     use super::*;
 
@@ -131,7 +140,21 @@ pub mod pallet {
     }
 
     #[pallet::hooks]
-    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+            let weight = match Self::cold_spend_authorization() {
+                Some(authorization) if n >= authorization.expiry => {
+                    ColdSpendAuthorization::<T>::kill();
+                    Self::deposit_event(Event::<T>::ColdSpendExpired(authorization.used_value));
+                    T::DbWeight::get().reads_writes(1, 1)
+                }
+                _ => T::DbWeight::get().reads(1),
+            };
+            weight
+                .saturating_add(Self::prune_raw_tx_registrations(n))
+                .saturating_add(Self::check_withdrawal_queue(n))
+        }
+    }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
@@ -139,11 +162,21 @@ pub mod pallet {
         #[pallet::weight(<T as Config>::WeightInfo::push_header())]
         pub fn push_header(origin: OriginFor<T>, header: Vec<u8>) -> DispatchResultWithPostInfo {
             let from = ensure_signed(origin)?;
+            Self::ensure_relayer(&from)?;
             let header: BtcHeader =
                 deserialize(header.as_slice()).map_err(|_| Error::<T>::DeserializeErr)?;
             log!(debug, "[push_header] from:{:?}, header:{:?}", from, header);
 
+            let hash = header.hash();
             Self::apply_push_header(header)?;
+            HeaderRelayInfo::<T>::insert(
+                &hash,
+                BtcRelayInfo {
+                    relayer: from,
+                    accepted_at: frame_system::Pallet::<T>::block_number(),
+                    accepted_time: T::UnixTime::now().as_secs(),
+                },
+            );
 
             // Relayer does not pay a fee.
             Ok(Pays::No.into())
@@ -157,31 +190,23 @@ pub mod pallet {
             relayed_info: Vec<u8>,
             prev_tx: Option<Vec<u8>>,
         ) -> DispatchResultWithPostInfo {
-            let _from = ensure_signed(origin)?;
-            let raw_tx = Self::deserialize_tx(raw_tx.as_slice())?;
-            let relayed_info: BtcRelayedTxInfo =
-                Decode::decode(&mut &relayed_info[..]).map_err(|_| Error::<T>::DeserializeErr)?;
-            let prev_tx = if let Some(prev_tx) = prev_tx {
-                Some(Self::deserialize_tx(prev_tx.as_slice())?)
-            } else {
-                None
-            };
-            let relay_tx = relayed_info.into_relayed_tx(raw_tx);
-            log!(
-                debug,
-                "[push_transaction] from:{:?}, relay_tx:{:?}, prev_tx:{:?}",
-                _from,
-                relay_tx,
-                prev_tx
-            );
-
-            Self::apply_push_transaction(relay_tx, prev_tx)?;
+            let from = ensure_signed(origin)?;
+            Self::ensure_relayer(&from)?;
+            Self::do_push_transaction(&from, &raw_tx, &relayed_info, prev_tx)?;
 
             Ok(Pays::No.into())
         }
 
         /// Trustee create a proposal for a withdrawal list. `tx` is the proposal withdrawal transaction.
-        #[pallet::weight(<T as Config>::WeightInfo::create_taproot_withdraw_tx())]
+        ///
+        /// The weight adds the pending check, the record and the state of each withdrawal.
+        #[pallet::weight(
+            <T as Config>::WeightInfo::create_taproot_withdraw_tx().saturating_add(
+                T::DbWeight::get()
+                    .reads_writes(3, 1)
+                    .saturating_mul(withdrawal_id_list.len() as Weight)
+            )
+        )]
         pub fn create_taproot_withdraw_tx(
             origin: OriginFor<T>,
             withdrawal_id_list: Vec<u32>,
@@ -189,6 +214,7 @@ pub mod pallet {
         ) -> DispatchResultWithPostInfo {
             let from = ensure_signed(origin)?;
 
+            ensure!(!Self::bridge_halted(), Error::<T>::BridgeHalted);
             ensure!(
                 !T::TrusteeSessionProvider::trustee_transition_state(),
                 Error::<T>::TrusteeTransitionPeriod
@@ -227,7 +253,9 @@ pub mod pallet {
                 remove_pending_deposit::<T>(&addr, &w);
             } else {
                 log!(info, "[remove_pending] Release pending deposit directly, not deposit to someone, addr:{:?}", try_addr(&addr));
-                PendingDeposits::<T>::remove(&addr);
+                for record in PendingDeposits::<T>::take(&addr) {
+                    ImmatureDepositOf::<T>::remove(&record.txid);
+                }
             }
             Ok(())
         }
@@ -307,6 +335,375 @@ pub mod pallet {
             }
             Ok(())
         }
+
+        /// Halt or resume the bridge, the relayed txs and withdrawal proposals are rejected
+        /// while the bridge is halted.
+        #[pallet::weight(<T as Config>::WeightInfo::set_bridge_halted())]
+        pub fn set_bridge_halted(origin: OriginFor<T>, halted: bool) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+            if halted {
+                BridgeHalted::<T>::put(true);
+            } else {
+                BridgeHalted::<T>::kill();
+            }
+            Self::deposit_event(Event::<T>::BridgeHaltUpdated(halted));
+            Ok(())
+        }
+
+        /// Remove the suspect deposit once it has been resolved manually.
+        #[pallet::weight(<T as Config>::WeightInfo::remove_suspect_deposit())]
+        pub fn remove_suspect_deposit(origin: OriginFor<T>, tx_hash: H256) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+            ensure!(
+                SuspectDeposits::<T>::contains_key(&tx_hash),
+                Error::<T>::NoSuspectDeposit
+            );
+            SuspectDeposits::<T>::remove(&tx_hash);
+            Ok(())
+        }
+
+        /// Credit at most `limit` deferred deposits in the order they were deferred.
+        ///
+        /// The deposits of bitcoin must have been re-enabled. Only the first `limit` deferred
+        /// deposits are visited, however long the queue is.
+        #[pallet::weight(<T as Config>::WeightInfo::process_deferred_deposits(*limit))]
+        pub fn process_deferred_deposits(origin: OriginFor<T>, limit: u32) -> DispatchResult {
+            ensure_root(origin)?;
+            ensure!(
+                xpallet_gateway_records::Pallet::<T>::deposits_enabled(Self::chain()),
+                Error::<T>::DepositsDisabled
+            );
+
+            let (first, next) = Self::deferred_deposit_range();
+            let count = next.wrapping_sub(first).min(limit);
+            let mut processed = 0u32;
+
+            for index in 0..count {
+                let record = match DeferredDeposits::<T>::take(first.wrapping_add(index)) {
+                    Some(record) => record,
+                    None => continue,
+                };
+                let txid = record.txid;
+                match process_deferred_deposit::<T>(record.clone()) {
+                    Ok(()) => processed += 1,
+                    Err(_) => {
+                        // Keep the failed deposit at the end of the queue for a retry,
+                        // so it doesn't block the following ones.
+                        Self::deposit_event(Event::<T>::DeferredDepositFailed(txid));
+                        Self::push_deferred_deposit(record);
+                    }
+                }
+            }
+            let (first, next) = DeferredDepositRange::<T>::mutate(|(first, next)| {
+                *first = first.wrapping_add(count);
+                (*first, *next)
+            });
+            Self::deposit_event(Event::<T>::DeferredDepositsProcessed(
+                processed,
+                next.wrapping_sub(first),
+            ));
+            Ok(())
+        }
+
+        /// Enable or disable the relayer whitelist, only the whitelisted relayers can push
+        /// the headers and transactions while it's enabled.
+        #[pallet::weight(<T as Config>::WeightInfo::set_relayer_whitelist_enabled())]
+        pub fn set_relayer_whitelist_enabled(
+            origin: OriginFor<T>,
+            enabled: bool,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            if enabled {
+                RelayerWhitelistEnabled::<T>::put(true);
+            } else {
+                RelayerWhitelistEnabled::<T>::kill();
+            }
+            Self::deposit_event(Event::<T>::RelayerWhitelistUpdated(enabled));
+            Ok(())
+        }
+
+        /// Add an account to the relayer whitelist.
+        #[pallet::weight(<T as Config>::WeightInfo::add_relayer())]
+        pub fn add_relayer(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+            ensure_root(origin)?;
+            ensure!(
+                !Relayers::<T>::contains_key(&who),
+                Error::<T>::DuplicatedRelayer
+            );
+            Relayers::<T>::insert(&who, ());
+            Self::deposit_event(Event::<T>::RelayerAdded(who));
+            Ok(())
+        }
+
+        /// Remove an account from the relayer whitelist.
+        #[pallet::weight(<T as Config>::WeightInfo::remove_relayer())]
+        pub fn remove_relayer(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+            ensure_root(origin)?;
+            ensure!(Relayers::<T>::contains_key(&who), Error::<T>::NoRelayer);
+            Relayers::<T>::remove(&who);
+            Self::deposit_event(Event::<T>::RelayerRemoved(who));
+            Ok(())
+        }
+
+        /// Flag a withdrawal address, or remove the flag if `level` is `None`.
+        ///
+        /// The withdrawals to a `Warn` address emit `FlaggedWithdrawal`, the withdrawals
+        /// to a `Block` address are rejected.
+        #[pallet::weight(<T as Config>::WeightInfo::set_btc_address_flag())]
+        pub fn set_btc_address_flag(
+            origin: OriginFor<T>,
+            addr: BtcAddress,
+            level: Option<FlagLevel>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            let address = Self::verify_btc_address(&addr)?;
+            let key = Self::btc_address_hash(&address);
+            match level {
+                Some(level) => FlaggedBtcAddresses::<T>::insert(key, level),
+                None => FlaggedBtcAddresses::<T>::remove(key),
+            }
+            Self::deposit_event(Event::<T>::BtcAddressFlagUpdated(addr, level));
+            Ok(())
+        }
+
+        /// Set the minimum blocks between two withdrawals to the same address,
+        /// disabled if `None`.
+        #[pallet::weight(<T as Config>::WeightInfo::set_address_withdraw_cooldown())]
+        pub fn set_address_withdraw_cooldown(
+            origin: OriginFor<T>,
+            cooldown: Option<T::BlockNumber>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            match cooldown {
+                Some(cooldown) => AddressWithdrawCooldown::<T>::put(cooldown),
+                None => AddressWithdrawCooldown::<T>::kill(),
+            }
+            Self::deposit_event(Event::<T>::AddressWithdrawCooldownUpdated(cooldown));
+            Ok(())
+        }
+
+        /// Prune the headers below `before_height`, at most `limit` heights at a time.
+        ///
+        /// The headers within a retargeting interval from the confirmed header are always
+        /// kept. Each pruned header is emitted in `BtcHeaderArchived` for the archive nodes.
+        #[pallet::weight(<T as Config>::WeightInfo::prune_headers(*limit))]
+        pub fn prune_headers(
+            origin: OriginFor<T>,
+            before_height: u32,
+            limit: u32,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            let confirmed = Self::confirmed_index().ok_or(Error::<T>::PruneHeightTooHigh)?;
+            ensure!(
+                before_height.saturating_add(Self::params_info().retargeting_interval())
+                    <= confirmed.height,
+                Error::<T>::PruneHeightTooHigh
+            );
+
+            let (_, genesis_height) = Self::genesis_info();
+            let mut height = Self::pruned_header_height().max(genesis_height);
+            let end = before_height.min(height.saturating_add(limit));
+            while height < end {
+                for hash in BlockHashFor::<T>::take(height) {
+                    MainChain::<T>::remove(&hash);
+                    HeaderRelayInfo::<T>::remove(&hash);
+                    BlockTxs::<T>::remove(&hash);
+                    if let Some(header_info) = Headers::<T>::take(&hash) {
+                        Self::deposit_event(Event::<T>::BtcHeaderArchived(hash, header_info));
+                    }
+                }
+                height += 1;
+            }
+            PrunedHeaderHeight::<T>::put(height);
+            Self::deposit_event(Event::<T>::HeadersPruned(height));
+            Ok(())
+        }
+
+        /// Allow the withdrawal proposals to spend the UTXOs of the cold address, up to
+        /// `max_value` in total until `expiry_block`.
+        ///
+        /// Only the trustee multisig is allowed to escalate, a new authorization replaces
+        /// the current one.
+        #[pallet::weight(<T as Config>::WeightInfo::authorize_cold_spend())]
+        pub fn authorize_cold_spend(
+            origin: OriginFor<T>,
+            #[pallet::compact] max_value: u64,
+            expiry_block: T::BlockNumber,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(
+                T::TrusteeSessionProvider::trustee_multisig().as_ref() == Some(&who),
+                Error::<T>::NotTrusteeMultisig
+            );
+            ensure!(
+                expiry_block > frame_system::Pallet::<T>::block_number(),
+                Error::<T>::InvalidColdSpendExpiry
+            );
+
+            ColdSpendAuthorization::<T>::put(BtcColdSpendAuthorization {
+                max_value,
+                used_value: 0,
+                expiry: expiry_block,
+            });
+            Self::deposit_event(Event::<T>::ColdSpendAuthorized(max_value, expiry_block));
+            Ok(())
+        }
+
+        /// Register the raw tx of `tx_hash` by chunks, for the txs too large to be relayed
+        /// inline by `push_transaction`.
+        ///
+        /// The chunks must be submitted in order, the chunk 0 restarts the registration.
+        /// The raw tx is verified against `tx_hash` once the last chunk arrives, then it
+        /// could be relayed by `push_registered_transaction`. The registrations are pruned
+        /// after `RAW_TX_REGISTRATION_LIFETIME` blocks.
+        ///
+        /// A relayer can have at most `MAX_RAW_TX_REGISTRATIONS_PER_RELAYER` registrations,
+        /// only the whitelisted relayers are free of the fee.
+        #[pallet::weight(<T as Config>::WeightInfo::register_raw_tx())]
+        pub fn register_raw_tx(
+            origin: OriginFor<T>,
+            tx_hash: H256,
+            #[pallet::compact] chunk_index: u32,
+            #[pallet::compact] total_chunks: u32,
+            chunk: Vec<u8>,
+        ) -> DispatchResultWithPostInfo {
+            let from = ensure_signed(origin)?;
+            Self::ensure_relayer(&from)?;
+            ensure!(
+                total_chunks > 0
+                    && total_chunks <= MAX_RAW_TX_CHUNKS
+                    && chunk_index < total_chunks
+                    && !chunk.is_empty(),
+                Error::<T>::InvalidRawTxChunk
+            );
+
+            let existing = Self::raw_tx_registration(&from, &tx_hash);
+            let mut registration = if chunk_index == 0 {
+                ensure!(
+                    existing.is_some()
+                        || Self::raw_tx_registration_count(&from)
+                            < MAX_RAW_TX_REGISTRATIONS_PER_RELAYER,
+                    Error::<T>::TooManyRawTxRegistrations
+                );
+                BtcRawTxRegistration {
+                    total_chunks,
+                    received_chunks: 0,
+                    raw_tx: Vec::new(),
+                    expires_at: frame_system::Pallet::<T>::block_number()
+                        .saturating_add(RAW_TX_REGISTRATION_LIFETIME.into()),
+                }
+            } else {
+                let registration = existing.clone().ok_or(Error::<T>::InvalidRawTxChunk)?;
+                ensure!(
+                    registration.total_chunks == total_chunks
+                        && registration.received_chunks == chunk_index,
+                    Error::<T>::InvalidRawTxChunk
+                );
+                registration
+            };
+            ensure!(
+                registration.raw_tx.len().saturating_add(chunk.len()) <= MAX_RAW_TX_SIZE,
+                Error::<T>::RawTxTooLarge
+            );
+            registration.raw_tx.extend_from_slice(&chunk);
+            registration.received_chunks += 1;
+
+            let complete = registration.is_complete();
+            if complete {
+                let tx = Self::deserialize_tx(&registration.raw_tx)?;
+                ensure!(tx.hash() == tx_hash, Error::<T>::RawTxHashMismatch);
+            }
+
+            if chunk_index == 0 {
+                RawTxRegistrationExpiries::<T>::append(
+                    registration.expires_at,
+                    (from.clone(), tx_hash),
+                );
+                if existing.is_none() {
+                    RawTxRegistrationCount::<T>::mutate(&from, |count| *count += 1);
+                }
+            }
+            RawTxRegistrations::<T>::insert(&from, &tx_hash, registration);
+            let pays = if Relayers::<T>::contains_key(&from) {
+                Pays::No
+            } else {
+                Pays::Yes
+            };
+            if complete {
+                Self::deposit_event(Event::<T>::RawTxRegistered(from, tx_hash));
+            }
+
+            // Only the whitelisted relayers do not pay a fee.
+            Ok(pays.into())
+        }
+
+        /// Same as `push_transaction`, but the raw tx is the one of `raw_tx_ref` registered
+        /// by the sender via `register_raw_tx`.
+        #[pallet::weight(<T as Config>::WeightInfo::push_registered_transaction())]
+        pub fn push_registered_transaction(
+            origin: OriginFor<T>,
+            raw_tx_ref: H256,
+            relayed_info: Vec<u8>,
+            prev_tx: Option<Vec<u8>>,
+        ) -> DispatchResultWithPostInfo {
+            let from = ensure_signed(origin)?;
+            Self::ensure_relayer(&from)?;
+            let registration = Self::raw_tx_registration(&from, &raw_tx_ref)
+                .filter(|registration| registration.is_complete())
+                .ok_or(Error::<T>::NoRegisteredRawTx)?;
+
+            Self::do_push_transaction(&from, &registration.raw_tx, &relayed_info, prev_tx)?;
+            Self::remove_raw_tx_registration(&from, &raw_tx_ref);
+
+            Ok(Pays::No.into())
+        }
+
+        /// Dangerous! Be careful to set CoinbaseDepositConfirmations
+        #[pallet::weight(0u64)]
+        pub fn set_coinbase_deposit_confirmations(
+            origin: OriginFor<T>,
+            number: u32,
+        ) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+            CoinbaseDepositConfirmations::<T>::put(number);
+            Ok(())
+        }
+
+        /// Set the bitcoin network fee rate (sat/vbyte) for estimating the withdrawal fee.
+        #[pallet::weight(0u64)]
+        pub fn set_network_fee_rate(origin: OriginFor<T>, rate: u64) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+            NetworkFeeRate::<T>::put(rate);
+            Ok(())
+        }
+
+        /// Set the thresholds of the withdrawal queue above which a withdrawal proposal is
+        /// required, zero for disabled.
+        ///
+        /// `max_queue_depth` is the number of the applying withdrawals and `max_queue_age_blocks`
+        /// is the age of the oldest one.
+        #[pallet::weight(<T as Config>::WeightInfo::set_withdrawal_queue_thresholds())]
+        pub fn set_withdrawal_queue_thresholds(
+            origin: OriginFor<T>,
+            max_queue_depth: u32,
+            max_queue_age_blocks: T::BlockNumber,
+        ) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+            MaxQueueDepth::<T>::put(max_queue_depth);
+            MaxQueueAgeBlocks::<T>::put(max_queue_age_blocks);
+            ProposalAlertBackoff::<T>::kill();
+            Ok(())
+        }
     }
 
     /// Error for the XBridge Bitcoin module
@@ -370,6 +767,48 @@ pub mod pallet {
         TxOutputNotColdAddr,
         /// The total amount of the trust must be transferred out in full
         TxNotFullAmount,
+        /// the network of bitcoin address does not match the configured network
+        AddressNetworkMismatch,
+        /// withdraw to the trustee hot or cold address is not allowed
+        WithdrawToTrusteeAddress,
+        /// the bridge is halted
+        BridgeHalted,
+        /// no suspect deposit for this tx
+        NoSuspectDeposit,
+        /// the deposits of bitcoin are disabled
+        DepositsDisabled,
+        /// the signer is not a whitelisted relayer
+        NotWhitelistedRelayer,
+        /// the relayer is already whitelisted
+        DuplicatedRelayer,
+        /// the relayer is not whitelisted
+        NoRelayer,
+        /// the withdrawal address is blocked
+        BlockedWithdrawalAddress,
+        /// the withdrawal address is still in cooldown
+        WithdrawalAddressInCooldown,
+        /// the headers to prune are not old enough
+        PruneHeightTooHigh,
+        /// the origin is not the trustee multisig
+        NotTrusteeMultisig,
+        /// the expiry of the cold spend authorization is not in the future
+        InvalidColdSpendExpiry,
+        /// the proposal spends the cold UTXOs without the authorization
+        ColdSpendNotAuthorized,
+        /// the proposal spends more cold UTXOs than authorized
+        ColdSpendExceeded,
+        /// the chunk of the raw tx is empty, out of order or beyond the declared chunks
+        InvalidRawTxChunk,
+        /// the registered raw tx exceeds `MAX_RAW_TX_SIZE`
+        RawTxTooLarge,
+        /// the registered raw tx does not match the declared hash
+        RawTxHashMismatch,
+        /// no complete raw tx registered by the sender for this hash
+        NoRegisteredRawTx,
+        /// the proposal spends no hot UTXO to record the trustee signatures from
+        ProposalWithoutHotInput,
+        /// the relayer has `MAX_RAW_TX_REGISTRATIONS_PER_RELAYER` raw tx registrations
+        TooManyRawTxRegistrations,
     }
 
     #[pallet::event]
@@ -405,6 +844,64 @@ pub mod pallet {
         DepositedNamed(H256, Vec<u8>, Vec<u8>, BalanceOf<T>),
         /// A unclaimed deposit record was removed for named address. [prefix, depositor, deposit_amount, tx_hash, btc_address]
         PendingDepositNamedRemoved(Vec<u8>, Vec<u8>, BalanceOf<T>, H256, BtcAddress),
+        /// A block previously confirmed is no longer on the main chain, the bridge is halted. [block_hash, height]
+        ConfirmedBlockOrphaned(H256, u32),
+        /// The bridge was halted or resumed. [halted]
+        BridgeHaltUpdated(bool),
+        /// A deposit was deferred since the deposits are disabled. [tx_hash, deposit_amount]
+        DepositDeferred(H256, BalanceOf<T>),
+        /// Some deferred deposits were credited. [processed, remaining]
+        DeferredDepositsProcessed(u32, u32),
+        /// The relayer whitelist was enabled or disabled. [enabled]
+        RelayerWhitelistUpdated(bool),
+        /// An account was added to the relayer whitelist. [relayer]
+        RelayerAdded(T::AccountId),
+        /// An account was removed from the relayer whitelist. [relayer]
+        RelayerRemoved(T::AccountId),
+        /// The flag of a withdrawal address was updated. [btc_address, level]
+        BtcAddressFlagUpdated(BtcAddress, Option<FlagLevel>),
+        /// The cooldown of the withdrawal addresses was updated. [cooldown]
+        AddressWithdrawCooldownUpdated(Option<T::BlockNumber>),
+        /// An account applied for a withdrawal to a flagged address. [who, btc_address]
+        FlaggedWithdrawal(T::AccountId, BtcAddress),
+        /// A header was removed from the storage, carrying its full content for the archive
+        /// nodes. [block_hash, header_info]
+        BtcHeaderArchived(H256, BtcHeaderInfo),
+        /// The headers were pruned up to a height. [pruned_below_height]
+        HeadersPruned(u32),
+        /// The trustees authorized to spend the cold UTXOs. [max_value, expiry_block]
+        ColdSpendAuthorized(u64, T::BlockNumber),
+        /// A withdrawal proposal spent some cold UTXOs. [spent_value, remaining_value]
+        ColdSpendUsed(u64, u64),
+        /// The cold spend authorization expired. [used_value]
+        ColdSpendExpired(u64),
+        /// A legacy withdrawal with an invalid address was canceled when creating
+        /// the withdrawal proposal. [withdrawal_id]
+        WithdrawalAddressRejected(u32),
+        /// A relayed tx was skipped without being handled, it could be relayed again with
+        /// the right previous tx. [tx_hash, reason]
+        TxHandleSkipped(H256, BtcTxSkipReason),
+        /// All the chunks of a raw tx were registered. [relayer, tx_hash]
+        RawTxRegistered(T::AccountId, H256),
+        /// A raw tx registration was pruned without being relayed. [relayer, tx_hash]
+        RawTxRegistrationPruned(T::AccountId, H256),
+        /// A deposit with a channel-only OP_RETURN went pending because the input address
+        /// has no binding. [tx_hash, channel, btc_address]
+        ChannelOnlyDepositPending(H256, ReferralId, BtcAddress),
+        /// A deposit spending a coinbase output was held until its block is deep enough.
+        /// [tx_hash, mature_height]
+        DepositHeldUntilMature(H256, u32),
+        /// A held deposit was dropped since its block is no longer in the main chain.
+        /// [tx_hash]
+        ImmatureDepositDropped(H256),
+        /// The applying withdrawals exceeded the queue thresholds while no proposal is in
+        /// progress, the trustees should create one. [applying_count, total_value]
+        WithdrawalProposalRequired(u32, u64),
+        /// A deferred deposit failed to be credited, it was kept in the queue. [tx_hash]
+        DeferredDepositFailed(H256),
+        /// A removed withdrawal proposal returned its cold UTXOs to the cold spend
+        /// authorization. [refunded_value, remaining_value]
+        ColdSpendRefunded(u64, u64),
     }
 
     /// best header info
@@ -438,6 +935,76 @@ pub mod pallet {
     #[pallet::getter(fn tx_state)]
     pub(crate) type TxState<T: Config> = StorageMap<_, Identity, H256, BtcTxState>;
 
+    /// the evidence collected when detecting the type of handled tx, for debugging purpose
+    #[pallet::storage]
+    #[pallet::getter(fn tx_evidence)]
+    pub(crate) type TxEvidence<T: Config> = StorageMap<_, Identity, H256, BtcTxEvidence>;
+
+    /// the txs processed successfully in the block
+    #[pallet::storage]
+    #[pallet::getter(fn block_txs)]
+    pub(crate) type BlockTxs<T: Config> = StorageMap<_, Identity, H256, Vec<H256>, ValueQuery>;
+
+    /// the txs processed in an orphaned confirmed block, tx_hash => block_hash,
+    /// which are waiting for the manual resolution of governance
+    #[pallet::storage]
+    #[pallet::getter(fn suspect_deposits)]
+    pub(crate) type SuspectDeposits<T: Config> = StorageMap<_, Identity, H256, H256>;
+
+    /// whether the bridge is halted
+    #[pallet::storage]
+    #[pallet::getter(fn bridge_halted)]
+    pub(crate) type BridgeHalted<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+    /// the deposits waiting to be credited once the deposits are re-enabled, index => deposit
+    #[pallet::storage]
+    pub(crate) type DeferredDeposits<T: Config> =
+        StorageMap<_, Twox64Concat, u32, BtcDeferredDeposit<T::AccountId>>;
+
+    /// the index of the first deferred deposit and the index of the next deferred one
+    #[pallet::storage]
+    #[pallet::getter(fn deferred_deposit_range)]
+    pub(crate) type DeferredDepositRange<T: Config> = StorageValue<_, (u32, u32), ValueQuery>;
+
+    /// whether only the whitelisted relayers can push the headers and transactions
+    #[pallet::storage]
+    #[pallet::getter(fn relayer_whitelist_enabled)]
+    pub(crate) type RelayerWhitelistEnabled<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+    /// the whitelisted relayers
+    #[pallet::storage]
+    #[pallet::getter(fn relayers)]
+    pub(crate) type Relayers<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, ()>;
+
+    /// the raw txs registered by chunks, relayer => tx_hash => registration
+    #[pallet::storage]
+    #[pallet::getter(fn raw_tx_registration)]
+    pub(crate) type RawTxRegistrations<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        T::AccountId,
+        Identity,
+        H256,
+        BtcRawTxRegistration<T::BlockNumber>,
+    >;
+
+    /// the number of the raw tx registrations of each relayer
+    #[pallet::storage]
+    #[pallet::getter(fn raw_tx_registration_count)]
+    pub(crate) type RawTxRegistrationCount<T: Config> =
+        StorageMap<_, Twox64Concat, T::AccountId, u32, ValueQuery>;
+
+    /// the raw tx registrations to be pruned at the block
+    #[pallet::storage]
+    pub(crate) type RawTxRegistrationExpiries<T: Config> =
+        StorageMap<_, Twox64Concat, T::BlockNumber, Vec<(T::AccountId, H256)>, ValueQuery>;
+
+    /// The relayer and acceptance time of the headers pushed by `push_header`.
+    #[pallet::storage]
+    #[pallet::getter(fn header_relay_info)]
+    pub(crate) type HeaderRelayInfo<T: Config> =
+        StorageMap<_, Identity, H256, BtcRelayInfo<T::AccountId, T::BlockNumber>>;
+
     /// unclaimed deposit info, addr => tx_hash, btc value,
     #[pallet::storage]
     #[pallet::getter(fn pending_deposits)]
@@ -500,6 +1067,102 @@ pub mod pallet {
     #[pallet::getter(fn coming_bot)]
     pub(crate) type ComingBot<T: Config> = StorageValue<_, T::AccountId, OptionQuery>;
 
+    /// the flagged withdrawal addresses, keyed by the decoded address hash
+    #[pallet::storage]
+    #[pallet::getter(fn flagged_btc_address)]
+    pub(crate) type FlaggedBtcAddresses<T: Config> =
+        StorageMap<_, Blake2_128Concat, Vec<u8>, FlagLevel>;
+
+    /// the minimum blocks between two withdrawals to the same address, disabled if none
+    #[pallet::storage]
+    #[pallet::getter(fn address_withdraw_cooldown)]
+    pub(crate) type AddressWithdrawCooldown<T: Config> = StorageValue<_, T::BlockNumber>;
+
+    /// the block of the latest withdrawal to each address, keyed by the decoded address hash
+    #[pallet::storage]
+    #[pallet::getter(fn last_withdrawal_to_address)]
+    pub(crate) type LastWithdrawalToAddress<T: Config> =
+        StorageMap<_, Blake2_128Concat, Vec<u8>, T::BlockNumber>;
+
+    /// the headers below this height have been pruned
+    #[pallet::storage]
+    #[pallet::getter(fn pruned_header_height)]
+    pub(crate) type PrunedHeaderHeight<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    /// the UTXOs of the cold address relayed so far, keyed by (txid, vout)
+    #[pallet::storage]
+    #[pallet::getter(fn cold_utxo)]
+    pub(crate) type ColdUtxos<T: Config> = StorageMap<_, Twox64Concat, (H256, u32), u64>;
+
+    /// the current authorization of spending the cold UTXOs in the withdrawal proposals
+    #[pallet::storage]
+    #[pallet::getter(fn cold_spend_authorization)]
+    pub(crate) type ColdSpendAuthorization<T: Config> =
+        StorageValue<_, BtcColdSpendAuthorization<T::BlockNumber>>;
+
+    /// the script which each input of the current withdrawal proposal is spent with
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_input_scripts)]
+    pub(crate) type ProposalInputScripts<T: Config> =
+        StorageValue<_, Vec<BtcInputScript>, ValueQuery>;
+
+    /// the (block number, unix time in seconds) of the recent withdrawal proposals, at most
+    /// `PROPOSAL_CADENCE_RING` entries in ascending order
+    #[pallet::storage]
+    #[pallet::getter(fn recent_proposals)]
+    pub(crate) type RecentProposals<T: Config> =
+        StorageValue<_, Vec<(T::BlockNumber, u64)>, ValueQuery>;
+
+    #[pallet::type_value]
+    pub fn DefaultForCoinbaseDepositConfirmations<T: Config>() -> u32 {
+        DEFAULT_COINBASE_DEPOSIT_CONFIRMATIONS
+    }
+
+    /// the confirmations required by the deposits spending a coinbase output
+    #[pallet::storage]
+    #[pallet::getter(fn coinbase_deposit_confirmations)]
+    pub(crate) type CoinbaseDepositConfirmations<T: Config> =
+        StorageValue<_, u32, ValueQuery, DefaultForCoinbaseDepositConfirmations<T>>;
+
+    /// the deposits spending a coinbase output, which are not deep enough to be credited,
+    /// mature height => deposits
+    #[pallet::storage]
+    #[pallet::getter(fn immature_deposits)]
+    pub(crate) type ImmatureDeposits<T: Config> =
+        StorageMap<_, Twox64Concat, u32, Vec<BtcImmatureDeposit<T::AccountId>>, ValueQuery>;
+
+    /// the coinbase-funded deposits not credited yet, either held in `ImmatureDeposits` or
+    /// pending for an unbound input address, tx_hash => (block_hash, mature height)
+    #[pallet::storage]
+    #[pallet::getter(fn immature_deposit_of)]
+    pub(crate) type ImmatureDepositOf<T: Config> = StorageMap<_, Identity, H256, (H256, u32)>;
+
+    #[pallet::type_value]
+    pub fn DefaultForNetworkFeeRate<T: Config>() -> u64 {
+        DEFAULT_NETWORK_FEE_RATE
+    }
+
+    /// the bitcoin network fee rate (sat/vbyte) for estimating the withdrawal fee
+    #[pallet::storage]
+    #[pallet::getter(fn network_fee_rate)]
+    pub(crate) type NetworkFeeRate<T: Config> =
+        StorageValue<_, u64, ValueQuery, DefaultForNetworkFeeRate<T>>;
+
+    /// the number of the applying withdrawals above which a proposal is required, zero for disabled
+    #[pallet::storage]
+    #[pallet::getter(fn max_queue_depth)]
+    pub(crate) type MaxQueueDepth<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    /// the age of the oldest applying withdrawal above which a proposal is required, zero for disabled
+    #[pallet::storage]
+    #[pallet::getter(fn max_queue_age_blocks)]
+    pub(crate) type MaxQueueAgeBlocks<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
+
+    /// the withdrawal queue is not checked before this block
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_alert_backoff)]
+    pub(crate) type ProposalAlertBackoff<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
+
     #[pallet::genesis_config]
     pub struct GenesisConfig<T: Config> {
         pub genesis_hash: H256,
@@ -508,6 +1171,7 @@ pub mod pallet {
         pub params_info: BtcParams,
         pub network_id: BtcNetwork,
         pub confirmation_number: u32,
+        pub coinbase_deposit_confirmations: u32,
         pub btc_withdrawal_fee: u64,
         pub max_withdrawal_count: u32,
         pub verifier: BtcTxVerifier,
@@ -523,6 +1187,7 @@ pub mod pallet {
                 params_info: Default::default(),
                 network_id: Default::default(),
                 confirmation_number: Default::default(),
+                coinbase_deposit_confirmations: DEFAULT_COINBASE_DEPOSIT_CONFIRMATIONS,
                 btc_withdrawal_fee: Default::default(),
                 max_withdrawal_count: Default::default(),
                 verifier: Default::default(),
@@ -553,6 +1218,7 @@ pub mod pallet {
             ParamsInfo::<T>::put(self.params_info);
             NetworkId::<T>::put(self.network_id);
             ConfirmationNumber::<T>::put(self.confirmation_number);
+            CoinbaseDepositConfirmations::<T>::put(self.coinbase_deposit_confirmations);
             BtcWithdrawalFee::<T>::put(self.btc_withdrawal_fee);
             MaxWithdrawalCount::<T>::put(self.max_withdrawal_count);
             Verifier::<T>::put(self.verifier);
@@ -574,7 +1240,11 @@ pub mod pallet {
             Chain::Bitcoin
         }
 
-        fn check_addr(addr: &[u8], _: &[u8]) -> DispatchResult {
+        fn check_addr(addr: &[u8], ext: &[u8]) -> DispatchResult {
+            Self::parse_addr(addr, ext).map(|_| ())
+        }
+
+        fn parse_addr(addr: &[u8], _: &[u8]) -> Result<ExternalAddress, DispatchError> {
             let address = Self::verify_btc_address(addr).map_err(|err| {
                 log!(
                     error,
@@ -585,19 +1255,38 @@ pub mod pallet {
                 err
             })?;
 
+            // the address must belong to the network configured for the bridge
+            if address.network != Self::network_id() {
+                log!(
+                    error,
+                    "[check_addr] Address network mismatch, expected:{:?}, got:{:?}",
+                    Self::network_id(),
+                    address.network
+                );
+                return Err(Error::<T>::AddressNetworkMismatch.into());
+            }
+
             match get_current_trustee_address_pair::<T>() {
                 Ok((hot_addr, cold_addr)) => {
-                    // do not allow withdraw from trustee address
-                    if address == hot_addr || address == cold_addr {
-                        return Err(Error::<T>::InvalidAddress.into());
+                    // do not allow withdraw to trustee address, whatever network it's encoded for
+                    if address.hash == hot_addr.hash || address.hash == cold_addr.hash {
+                        return Err(Error::<T>::WithdrawToTrusteeAddress.into());
                     }
                 }
                 Err(err) => {
                     log!(error, "[check_addr] Can not get trustee addr:{:?}", err);
                 }
             }
+            // the last trustee addresses are still in use during the trustee transition
+            if let Ok((hot_addr, cold_addr)) = get_last_trustee_address_pair::<T>() {
+                if address.hash == hot_addr.hash || address.hash == cold_addr.hash {
+                    return Err(Error::<T>::WithdrawToTrusteeAddress.into());
+                }
+            }
 
-            Ok(())
+            Self::check_withdrawal_address_policy(&address)?;
+
+            Ok(ExternalAddress::Btc(address))
         }
 
         fn withdrawal_limit(
@@ -632,6 +1321,28 @@ pub mod pallet {
         }
     }
 
+    impl<T: Config> OnWithdrawalApplied<T::AccountId> for Pallet<T> {
+        fn on_withdrawal_applied(who: &T::AccountId, addr: &[u8]) -> DispatchResult {
+            let address = Self::verify_btc_address(addr)?;
+            let key = Self::btc_address_hash(&address);
+
+            if Self::flagged_btc_address(&key) == Some(FlagLevel::Warn) {
+                log!(
+                    warn,
+                    "[on_withdrawal_applied] {:?} withdraws to flagged addr:{:?}",
+                    who,
+                    try_addr(addr)
+                );
+                Self::deposit_event(Event::<T>::FlaggedWithdrawal(who.clone(), addr.to_vec()));
+            }
+            if Self::address_withdraw_cooldown().is_some() {
+                let now = frame_system::Pallet::<T>::block_number();
+                LastWithdrawalToAddress::<T>::insert(key, now);
+            }
+            Ok(())
+        }
+    }
+
     impl<T: Config> ProposalProvider for Pallet<T> {
         type WithdrawalProposal = BtcWithdrawalProposal<T::AccountId>;
         fn get_withdrawal_proposal() -> Option<Self::WithdrawalProposal> {
@@ -640,12 +1351,193 @@ pub mod pallet {
     }
 
     impl<T: Config> Pallet<T> {
+        /// Appends `deposit` to the end of the deferred deposits.
+        pub(crate) fn push_deferred_deposit(deposit: BtcDeferredDeposit<T::AccountId>) {
+            DeferredDepositRange::<T>::mutate(|(_, next)| {
+                DeferredDeposits::<T>::insert(*next, deposit);
+                *next = next.wrapping_add(1);
+            });
+        }
+
+        /// Deposits the deposit or withdrawal event indexed by the topic of X-BTC,
+        /// see `asset_event_topic`.
+        ///
+        /// The write of the topic index is registered as extra weight.
+        pub(crate) fn deposit_token_event(event: Event<T>) {
+            let event = <T as Config>::Event::from(event);
+            frame_system::Pallet::<T>::deposit_event_indexed(
+                &[asset_event_topic::<T::Hashing>(Self::ASSET_ID)],
+                event.into(),
+            );
+            frame_system::Pallet::<T>::register_extra_weight_unchecked(
+                T::DbWeight::get().writes(1),
+                DispatchClass::Normal,
+            );
+        }
+
+        /// Ensures `who` can relay the headers and transactions.
+        pub(crate) fn ensure_relayer(who: &T::AccountId) -> DispatchResult {
+            if Self::relayer_whitelist_enabled() && !Relayers::<T>::contains_key(who) {
+                log!(
+                    warn,
+                    "[ensure_relayer] {:?} is not a whitelisted relayer",
+                    who
+                );
+                return Err(Error::<T>::NotWhitelistedRelayer.into());
+            }
+            Ok(())
+        }
+
+        /// Returns the decoded hash of the address, so that the different encodings of
+        /// the same address are treated as one.
+        pub(crate) fn btc_address_hash(address: &Address) -> Vec<u8> {
+            match address.hash {
+                AddressTypes::Legacy(h) => h.as_bytes().to_vec(),
+                AddressTypes::WitnessV0ScriptHash(h) => h.as_bytes().to_vec(),
+                AddressTypes::WitnessV0KeyHash(h) => h.as_bytes().to_vec(),
+                AddressTypes::WitnessV1Taproot(h) => h.as_bytes().to_vec(),
+            }
+        }
+
+        /// Ensures the address is neither blocked nor in cooldown.
+        fn check_withdrawal_address_policy(address: &Address) -> DispatchResult {
+            let key = Self::btc_address_hash(address);
+            if Self::flagged_btc_address(&key) == Some(FlagLevel::Block) {
+                log!(error, "[check_addr] Withdraw to blocked addr:{:?}", address);
+                return Err(Error::<T>::BlockedWithdrawalAddress.into());
+            }
+
+            if let (Some(cooldown), Some(last)) = (
+                Self::address_withdraw_cooldown(),
+                Self::last_withdrawal_to_address(&key),
+            ) {
+                let now = frame_system::Pallet::<T>::block_number();
+                ensure!(
+                    now >= last.saturating_add(cooldown),
+                    Error::<T>::WithdrawalAddressInCooldown
+                );
+            }
+            Ok(())
+        }
+
         /// Helper function for deserializing the slice of raw tx.
         #[inline]
         pub(crate) fn deserialize_tx(input: &[u8]) -> Result<Transaction, Error<T>> {
             deserialize(Reader::new(input)).map_err(|_| Error::<T>::DeserializeErr)
         }
 
+        fn do_push_transaction(
+            from: &T::AccountId,
+            raw_tx: &[u8],
+            relayed_info: &[u8],
+            prev_tx: Option<Vec<u8>>,
+        ) -> DispatchResult {
+            let raw_tx = Self::deserialize_tx(raw_tx)?;
+            let relayed_info: BtcRelayedTxInfo =
+                Decode::decode(&mut &relayed_info[..]).map_err(|_| Error::<T>::DeserializeErr)?;
+            let prev_tx = if let Some(prev_tx) = prev_tx {
+                Some(Self::deserialize_tx(prev_tx.as_slice())?)
+            } else {
+                None
+            };
+            let relay_tx = relayed_info.into_relayed_tx(raw_tx);
+            log!(
+                debug,
+                "[push_transaction] from:{:?}, relay_tx:{:?}, prev_tx:{:?}",
+                from,
+                relay_tx,
+                prev_tx
+            );
+
+            Self::apply_push_transaction(relay_tx, prev_tx)
+        }
+
+        /// Removes the raw tx registrations expired at block `n`.
+        fn prune_raw_tx_registrations(n: T::BlockNumber) -> Weight {
+            let expired = RawTxRegistrationExpiries::<T>::take(n);
+            let count = expired.len() as Weight;
+            for (who, tx_hash) in expired {
+                // The registration may have been relayed or restarted since then.
+                match Self::raw_tx_registration(&who, &tx_hash) {
+                    Some(registration) if registration.expires_at == n => {
+                        Self::remove_raw_tx_registration(&who, &tx_hash);
+                        Self::deposit_event(Event::<T>::RawTxRegistrationPruned(who, tx_hash));
+                    }
+                    _ => {}
+                }
+            }
+            T::DbWeight::get().reads_writes(
+                count.saturating_mul(2).saturating_add(1),
+                count.saturating_mul(2).saturating_add(1),
+            )
+        }
+
+        /// Removes a raw tx registration of `who` along with its count.
+        fn remove_raw_tx_registration(who: &T::AccountId, tx_hash: &H256) {
+            RawTxRegistrations::<T>::remove(who, tx_hash);
+            RawTxRegistrationCount::<T>::mutate_exists(who, |count| {
+                *count = count
+                    .map(|count| count.saturating_sub(1))
+                    .filter(|c| *c > 0);
+            });
+        }
+
+        /// Raises `WithdrawalProposalRequired` if the applying withdrawals exceed the queue
+        /// thresholds while no proposal is in progress, then backs off for
+        /// `PROPOSAL_ALERT_BACKOFF` blocks.
+        ///
+        /// The queue is only checked once every `WITHDRAWAL_QUEUE_CHECK_INTERVAL` blocks,
+        /// since it scans all the bitcoin withdrawals.
+        ///
+        /// NOTE: The proposal is not created automatically, as the runtime neither tracks the
+        /// UTXOs of the hot address nor selects the coins. Raising the event for the trustee
+        /// tooling instead is a descope of the original request and is pending agreement.
+        fn check_withdrawal_queue(n: T::BlockNumber) -> Weight {
+            if !(n % WITHDRAWAL_QUEUE_CHECK_INTERVAL.into()).is_zero() {
+                return 0;
+            }
+            let max_depth = Self::max_queue_depth();
+            let max_age = Self::max_queue_age_blocks();
+            if max_depth == 0 && max_age.is_zero() {
+                return T::DbWeight::get().reads(2);
+            }
+            if n < Self::proposal_alert_backoff() || Self::withdrawal_proposal().is_some() {
+                return T::DbWeight::get().reads(4);
+            }
+
+            let withdrawals =
+                xpallet_gateway_records::Pallet::<T>::withdrawals_list_by_chain(Chain::Bitcoin);
+            let reads = (withdrawals.len() as Weight).saturating_mul(2);
+            let applying = withdrawals
+                .into_values()
+                .filter(|withdrawal| withdrawal.state == WithdrawalState::Applying)
+                .collect::<Vec<_>>();
+            let count = applying.len() as u32;
+            let too_deep = max_depth != 0 && count > max_depth;
+            let too_old = !max_age.is_zero()
+                && applying
+                    .iter()
+                    .map(|withdrawal| withdrawal.height)
+                    .min()
+                    .map_or(false, |oldest| n.saturating_sub(oldest) > max_age);
+            if !too_deep && !too_old {
+                return T::DbWeight::get().reads(reads.saturating_add(4));
+            }
+
+            let total_value = applying.iter().fold(0u64, |total, withdrawal| {
+                total.saturating_add(withdrawal.balance.saturated_into())
+            });
+            log!(
+                warn,
+                "[check_withdrawal_queue] {} applying withdrawals of {} satoshis are waiting for a proposal",
+                count,
+                total_value
+            );
+            ProposalAlertBackoff::<T>::put(n.saturating_add(PROPOSAL_ALERT_BACKOFF.into()));
+            Self::deposit_event(Event::<T>::WithdrawalProposalRequired(count, total_value));
+            T::DbWeight::get().reads_writes(reads.saturating_add(4), 2)
+        }
+
         #[transactional]
         pub(crate) fn apply_push_header(header: BtcHeader) -> DispatchResult {
             // current should not exist
@@ -713,6 +1605,7 @@ pub mod pallet {
                     height: header_info.height,
                 };
                 BestIndex::<T>::put(new_best_index);
+                tx::credit_mature_deposits::<T>(header_info.height);
             } else {
                 // forked chain
                 log!(
@@ -731,6 +1624,7 @@ pub mod pallet {
             tx: BtcRelayedTx,
             prev_tx: Option<Transaction>,
         ) -> DispatchResult {
+            ensure!(!Self::bridge_halted(), Error::<T>::BridgeHalted);
             let tx_hash = tx.raw.hash();
             let block_hash = tx.block_hash;
             let header_info = Pallet::<T>::headers(&tx.block_hash).ok_or_else(|| {
@@ -774,31 +1668,54 @@ pub mod pallet {
             }
 
             let network = Pallet::<T>::network_id();
+            if let Some(reason) = tx::check_skip_reason(&tx.raw, prev_tx.as_ref(), network) {
+                log!(
+                    warn,
+                    "[apply_push_transaction] Skip tx (hash:{:?}), reason:{:?}",
+                    tx_hash,
+                    reason
+                );
+                Self::deposit_event(Event::<T>::TxHandleSkipped(tx_hash, reason));
+                return Ok(());
+            }
             let min_deposit = Pallet::<T>::btc_min_deposit();
             let current_trustee_pair = get_current_trustee_address_pair::<T>()?;
             let last_trustee_pair = get_last_trustee_address_pair::<T>().ok();
-            let state = tx::process_tx::<T>(
-                tx.raw,
+            let cold_addr = current_trustee_pair.1.clone();
+            let raw_tx = tx.raw;
+            let (state, evidence) = tx::process_tx::<T>(
+                raw_tx.clone(),
                 prev_tx,
+                BtcHeaderIndex {
+                    hash: block_hash,
+                    height,
+                },
                 network,
                 min_deposit,
                 current_trustee_pair,
                 last_trustee_pair,
             );
             TxState::<T>::insert(&tx_hash, state);
+            TxEvidence::<T>::insert(&tx_hash, evidence);
             Self::deposit_event(Event::<T>::TxProcessed(tx_hash, block_hash, state));
             match state.result {
-                BtcTxResult::Success => Ok(()),
+                BtcTxResult::Success => {
+                    tx::track_cold_utxos::<T>(&raw_tx, &cold_addr, network);
+                    BlockTxs::<T>::append(&block_hash, tx_hash);
+                    Ok(())
+                }
                 BtcTxResult::Failure => Err(Error::<T>::ProcessTxFailed.into()),
             }
         }
 
         pub(crate) fn apply_remove_proposal() -> DispatchResult {
+            ProposalInputScripts::<T>::kill();
             if let Some(proposal) = WithdrawalProposal::<T>::take() {
+                Self::refund_cold_spend(&proposal.tx);
                 for id in proposal.withdrawal_id_list.iter() {
                     xpallet_gateway_records::Pallet::<T>::set_withdrawal_state_by_root(
                         *id,
-                        xpallet_gateway_records::WithdrawalState::Applying,
+                        WithdrawalState::Applying,
                     )?;
                 }
             }
@@ -828,6 +1745,19 @@ pub mod pallet {
             Self::verify_bech32_address(data)
         }
 
+        /// Returns the bitcoin address of the pending withdrawal `id`.
+        ///
+        /// Only the legacy withdrawals applied with the raw address bytes are parsed here,
+        /// an invalid address fails the withdrawal alone.
+        pub fn withdrawal_btc_address(id: u32) -> Result<Address, DispatchError> {
+            match xpallet_gateway_records::Pallet::<T>::withdrawal_address(id)
+                .ok_or(Error::<T>::NoWithdrawalRecord)?
+            {
+                ExternalAddress::Btc(address) => Ok(address),
+                ExternalAddress::Named(addr) => Self::verify_btc_address(&addr),
+            }
+        }
+
         pub fn verify_tx_valid(
             raw_tx: Vec<u8>,
             withdrawal_id_list: Vec<u32>,
@@ -889,11 +1819,46 @@ pub mod pallet {
 
     /// Storage Query RPCs
     impl<T: Config> Pallet<T> {
+        /// Get the deferred deposits in the order they were deferred
+        pub fn deferred_deposits() -> Vec<BtcDeferredDeposit<T::AccountId>> {
+            let (first, next) = Self::deferred_deposit_range();
+            (0..next.wrapping_sub(first))
+                .filter_map(|index| DeferredDeposits::<T>::get(first.wrapping_add(index)))
+                .collect()
+        }
+
         /// Get withdrawal proposal
         pub fn get_withdrawal_proposal() -> Option<BtcWithdrawalProposal<T::AccountId>> {
             Self::withdrawal_proposal()
         }
 
+        /// Get the current withdrawal proposal with its tx outputs matched against the
+        /// withdrawal applications, for the trustees to review before signing
+        pub fn get_withdrawal_tx_info() -> Option<BtcWithdrawalTxInfo<T::AccountId>> {
+            let proposal = Self::withdrawal_proposal()?;
+            let outputs = trustee::decode_withdraw_tx_outputs::<T>(
+                &proposal.tx,
+                &proposal.withdrawal_id_list,
+            );
+            let redeem_script = trustee::proposal_trustee_session::<T>()
+                .map(|session_info| session_info.hot_address.redeem_script)
+                .unwrap_or_default();
+            let signed_trustees = proposal
+                .trustee_list
+                .into_iter()
+                .filter_map(|(trustee, signed)| if signed { Some(trustee) } else { None })
+                .collect::<Vec<_>>();
+            Some(BtcWithdrawalTxInfo {
+                sig_state: proposal.sig_state,
+                tx: serialize(&proposal.tx).into(),
+                withdrawal_id_list: proposal.withdrawal_id_list,
+                outputs,
+                redeem_script,
+                signed_count: signed_trustees.len() as u32,
+                signed_trustees,
+            })
+        }
+
         /// Get genesis info
         pub fn get_genesis_info() -> (BtcHeader, u32) {
             Self::genesis_info()
@@ -903,5 +1868,175 @@ pub mod pallet {
         pub fn get_btc_block_header(txid: H256) -> Option<BtcHeaderInfo> {
             Self::headers(txid)
         }
+
+        /// Get the handling state and classification evidence of the relayed tx
+        pub fn get_btc_tx_info(txid: H256) -> Option<BtcTxInfo> {
+            Self::tx_state(txid).map(|state| BtcTxInfo {
+                tx_type: state.tx_type,
+                result: state.result,
+                evidence: Self::tx_evidence(txid),
+                mature_at: Self::immature_deposit_of(txid).map(|(_, mature_at)| mature_at),
+            })
+        }
+
+        /// Get the status of the bridge
+        pub fn get_bridge_status() -> BtcBridgeStatus {
+            BtcBridgeStatus {
+                best_height: Self::best_index().height,
+                confirmed_height: Self::confirmed_index().map(|index| index.height),
+                halted: Self::bridge_halted(),
+                relayer_whitelist_enabled: Self::relayer_whitelist_enabled(),
+                relayer_count: Relayers::<T>::iter_keys().count() as u32,
+                average_relay_lag: Self::average_relay_lag(RELAY_LAG_WINDOW),
+            }
+        }
+
+        /// Get the (btc_height, btc_time, accepted_at_chainx_block, relayer) of the
+        /// last `last_n` headers in the main chain, in ascending order of the height.
+        ///
+        /// At most `MAX_RELAY_STATS_HEADERS` headers are returned.
+        pub fn btc_relay_stats(last_n: u32) -> Vec<(u32, u32, u32, T::AccountId)> {
+            Self::recent_relay_info(last_n)
+                .into_iter()
+                .map(|(header_info, relay_info)| {
+                    (
+                        header_info.height,
+                        header_info.header.time,
+                        relay_info.accepted_at.saturated_into(),
+                        relay_info.relayer,
+                    )
+                })
+                .collect()
+        }
+
+        /// The average seconds between the bitcoin timestamp and the acceptance
+        /// of the last `last_n` headers in the main chain.
+        pub fn average_relay_lag(last_n: u32) -> Option<u64> {
+            let lags = Self::recent_relay_info(last_n)
+                .into_iter()
+                .map(|(header_info, relay_info)| {
+                    relay_info
+                        .accepted_time
+                        .saturating_sub(u64::from(header_info.header.time))
+                })
+                .collect::<Vec<_>>();
+            if lags.is_empty() {
+                None
+            } else {
+                Some(lags.iter().sum::<u64>() / lags.len() as u64)
+            }
+        }
+
+        /// Returns the estimated completion time of the pending bitcoin withdrawals.
+        ///
+        /// The applications are assumed to be proposed in the order of their ids, at most
+        /// `MaxWithdrawalCount` per proposal, one proposal every average interval of the
+        /// recent proposals. Each withdrawal is then confirmed in `ConfirmationNumber`
+        /// bitcoin blocks of `BTC_BLOCK_SECONDS`, counted from the last proposal for the
+        /// withdrawals already proposed. Nothing is estimated before two proposals are
+        /// recorded.
+        pub fn withdrawal_estimates() -> BTreeMap<u32, WithdrawalEstimate> {
+            let (since_last_proposal, cadence, millis_per_block) = match Self::proposal_cadence() {
+                Some(cadence) => cadence,
+                None => return BTreeMap::new(),
+            };
+            let next_proposal_in = cadence.saturating_sub(since_last_proposal);
+            let blocks_to_seconds =
+                |blocks: u32| u64::from(blocks).saturating_mul(millis_per_block) / 1000;
+            let confirmation_seconds =
+                u64::from(Self::confirmation_number()).saturating_mul(BTC_BLOCK_SECONDS);
+            let confirmation_millis = confirmation_seconds.saturating_mul(1000);
+            let confirmation_blocks: u32 =
+                ((confirmation_millis + millis_per_block - 1) / millis_per_block).saturated_into();
+            let batch = Self::max_withdrawal_count().max(1);
+
+            let mut queue_position = 0u32;
+            xpallet_gateway_records::Pallet::<T>::withdrawals_list_by_chain(Chain::Bitcoin)
+                .into_iter()
+                .filter_map(|(id, withdrawal)| {
+                    let estimate = match withdrawal.state {
+                        WithdrawalState::Applying => {
+                            let round = queue_position / batch;
+                            queue_position += 1;
+                            let proposal_blocks =
+                                next_proposal_in.saturating_add(round.saturating_mul(cadence));
+                            WithdrawalEstimate {
+                                blocks_remaining: proposal_blocks
+                                    .saturating_add(confirmation_blocks),
+                                seconds: blocks_to_seconds(proposal_blocks)
+                                    .saturating_add(confirmation_seconds),
+                            }
+                        }
+                        // Proposed by the last proposal, the confirmations are under way.
+                        WithdrawalState::Processing => WithdrawalEstimate {
+                            blocks_remaining: confirmation_blocks
+                                .saturating_sub(since_last_proposal),
+                            seconds: confirmation_seconds
+                                .saturating_sub(blocks_to_seconds(since_last_proposal)),
+                        },
+                        _ => return None,
+                    };
+                    Some((id, estimate))
+                })
+                .collect()
+        }
+
+        /// Returns the estimated share of the bitcoin network fee of a withdrawal to `addr`,
+        /// under the current `NetworkFeeRate` and `MaxWithdrawalCount` withdrawals a proposal.
+        pub fn withdrawal_network_fee(
+            addr: &[u8],
+        ) -> Result<BtcWithdrawalNetworkFee, DispatchError> {
+            let address = match Self::parse_addr(addr, b"")? {
+                ExternalAddress::Btc(address) => address,
+                ExternalAddress::Named(_) => return Err(Error::<T>::InvalidAddr.into()),
+            };
+            Ok(BtcWithdrawalNetworkFee::new(
+                &address,
+                Self::network_fee_rate(),
+                Self::max_withdrawal_count(),
+            ))
+        }
+
+        /// Returns the blocks since the last proposal, the average blocks between the recent
+        /// proposals and the average milliseconds per block in between.
+        fn proposal_cadence() -> Option<(u32, u32, u64)> {
+            let recent = Self::recent_proposals();
+            let (first_block, first_time) = *recent.first()?;
+            let (last_block, last_time) = *recent.last()?;
+            let blocks: u32 = last_block.saturating_sub(first_block).saturated_into();
+            if blocks == 0 {
+                return None;
+            }
+            let millis_per_block =
+                last_time.saturating_sub(first_time).saturating_mul(1000) / u64::from(blocks);
+            if millis_per_block == 0 {
+                return None;
+            }
+            let cadence = (blocks / (recent.len() as u32 - 1)).max(1);
+            let since_last_proposal: u32 = frame_system::Pallet::<T>::block_number()
+                .saturating_sub(last_block)
+                .saturated_into();
+            Some((since_last_proposal, cadence, millis_per_block))
+        }
+
+        /// Walk back the main chain from the best header, stop at the first header
+        /// without relay info (e.g. the genesis header).
+        fn recent_relay_info(
+            last_n: u32,
+        ) -> Vec<(BtcHeaderInfo, BtcRelayInfo<T::AccountId, T::BlockNumber>)> {
+            let mut hash = Self::best_index().hash;
+            let mut result = Vec::new();
+            for _ in 0..last_n.min(MAX_RELAY_STATS_HEADERS) {
+                match (Self::headers(&hash), Self::header_relay_info(&hash)) {
+                    (Some(header_info), Some(relay_info)) => {
+                        hash = header_info.header.previous_header_hash;
+                        result.push((header_info, relay_info));
+                    }
+                    _ => break,
+                }
+            }
+            result.reverse();
+            result
+        }
     }
 }
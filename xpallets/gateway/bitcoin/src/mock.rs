@@ -37,7 +37,7 @@ use light_bitcoin::{
     serialization::{self, Reader},
 };
 use sp_runtime::traits::AccountIdConversion;
-use xpallet_support::traits::MultisigAddressFor;
+use xpallet_support::traits::{MultisigAddressFor, Validator};
 
 use crate::{
     self as xpallet_gateway_bitcoin,
@@ -94,7 +94,7 @@ impl frame_system::Config for Test {
     type AccountId = AccountId;
     type Lookup = IdentityLookup<Self::AccountId>;
     type Header = Header;
-    type Event = ();
+    type Event = Event;
     type BlockHashCount = BlockHashCount;
     type DbWeight = ();
     type Version = ();
@@ -116,7 +116,7 @@ impl pallet_balances::Config for Test {
     type MaxLocks = ();
     type Balance = Balance;
     type DustRemoval = ();
-    type Event = ();
+    type Event = Event;
     type ExistentialDeposit = ExistentialDeposit;
     type AccountStore = System;
     type WeightInfo = ();
@@ -140,7 +140,7 @@ frame_support::parameter_types! {
 }
 
 impl pallet_elections_phragmen::Config for Test {
-    type Event = ();
+    type Event = Event;
     type PalletId = ElectionsPhragmenPalletId;
     type Currency = Balances;
     type ChangeMembers = ();
@@ -163,7 +163,7 @@ parameter_types! {
 }
 
 impl xpallet_assets_registrar::Config for Test {
-    type Event = ();
+    type Event = Event;
     type NativeAssetId = ChainXAssetId;
     type RegistrarHandler = ();
     type WeightInfo = ();
@@ -181,11 +181,14 @@ impl xpallet_support::traits::TreasuryAccount<AccountId> for SimpleTreasuryAccou
 }
 
 impl xpallet_assets::Config for Test {
-    type Event = ();
+    type Event = Event;
     type Currency = Balances;
     type TreasuryAccount = SimpleTreasuryAccount;
     type OnCreatedAccount = frame_system::Provider<Test>;
     type OnAssetChanged = ();
+    type OnReceived = ();
+    type MoveGuard = ();
+    type QuarantineOrigin = frame_system::EnsureRoot<AccountId>;
     type WeightInfo = ();
 }
 
@@ -195,7 +198,7 @@ parameter_types! {
 }
 
 impl xpallet_gateway_records::Config for Test {
-    type Event = ();
+    type Event = Event;
     type WeightInfo = ();
 }
 
@@ -207,9 +210,33 @@ impl MultisigAddressFor<AccountId> for MultisigAddr {
     }
 }
 
+/// The referral id of charlie, bob's is `bobchannel`.
+pub const CHANNEL: &[u8] = b"polkaworld";
+
+pub struct MockValidator;
+impl Validator<AccountId> for MockValidator {
+    fn is_validator(who: &AccountId) -> bool {
+        *who == charlie() || *who == bob()
+    }
+
+    fn validator_for(name: &[u8]) -> Option<AccountId> {
+        if name == CHANNEL {
+            Some(charlie())
+        } else if name == b"bobchannel" {
+            Some(bob())
+        } else {
+            None
+        }
+    }
+
+    fn recent_authored_blocks(_: &AccountId, _: u32) -> u32 {
+        0
+    }
+}
+
 impl xpallet_gateway_common::Config for Test {
-    type Event = ();
-    type Validator = ();
+    type Event = Event;
+    type Validator = MockValidator;
     type DetermineMultisigAddress = MultisigAddr;
     type CouncilOrigin = EnsureSigned<AccountId>;
     type Bitcoin = XGatewayBitcoin;
@@ -217,6 +244,8 @@ impl xpallet_gateway_common::Config for Test {
     type BitcoinTrusteeSessionProvider = trustees::bitcoin::BtcTrusteeSessionManager<Test>;
     type BitcoinTotalSupply = XGatewayBitcoin;
     type BitcoinWithdrawalProposal = XGatewayBitcoin;
+    type BitcoinWithdrawalHook = XGatewayBitcoin;
+    type DerivedAccounts = XAssets;
     type WeightInfo = ();
 }
 
@@ -264,7 +293,7 @@ impl pallet_evm::Config for Test {
     type AddressMapping = pallet_evm::HashedAddressMapping<BlakeTwo256>;
     type Currency = Balances;
     type Runner = pallet_evm::runner::stack::Runner<Self>;
-    type Event = ();
+    type Event = Event;
     type PrecompilesType = ();
     type PrecompilesValue = ();
     type ChainId = ();
@@ -276,13 +305,13 @@ impl pallet_evm::Config for Test {
 }
 
 impl xpallet_assets_bridge::Config for Test {
-    type Event = ();
+    type Event = Event;
     type EvmCaller = EvmCaller;
     type ClaimBond = ClaimBond;
 }
 
 impl Config for Test {
-    type Event = ();
+    type Event = Event;
     type UnixTime = CustomTimestamp;
     type AccountExtractor = xp_gateway_bitcoin::OpReturnExtractor;
     type TrusteeSessionProvider =
@@ -368,6 +397,7 @@ impl ExtBuilder {
             ), // retargeting_factor
             verifier: BtcTxVerifier::Recover,
             confirmation_number: 4,
+            coinbase_deposit_confirmations: 8,
             btc_withdrawal_fee: 0,
             max_withdrawal_count: 100,
         }
@@ -451,6 +481,7 @@ impl ExtBuilder {
             ), // retargeting_factor
             verifier: BtcTxVerifier::Recover,
             confirmation_number: 4,
+            coinbase_deposit_confirmations: 8,
             btc_withdrawal_fee: 0,
             max_withdrawal_count: 100,
         }
@@ -1,5 +1,7 @@
 // Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
 
+use std::time::Duration;
+
 use frame_support::{assert_noop, assert_ok};
 
 use light_bitcoin::{
@@ -8,13 +10,14 @@ use light_bitcoin::{
     serialization,
 };
 
-use crate::mock::alice;
+use crate::mock::{alice, bob};
 use crate::{
     mock::{
-        generate_blocks_478557_478563, generate_blocks_63290_63310, ExtBuilder, XGatewayBitcoin,
-        XGatewayBitcoinErr,
+        generate_blocks_478557_478563, generate_blocks_63290_63310, ExtBuilder, Origin, System,
+        Test, XGatewayBitcoin, XGatewayBitcoinErr, NOW,
     },
-    types::BtcHeaderIndex,
+    types::{BtcHeaderIndex, BtcParams},
+    BlockTxs, ParamsInfo,
 };
 
 #[test]
@@ -192,6 +195,66 @@ fn test_insert_forked_headers() {
         });
 }
 
+#[test]
+fn test_orphaned_confirmed_header() {
+    // e.g.
+    //                 confirmed
+    // b0 --- b1 --- b2 --- b3 --- b4 --- b5
+    //        |----- f2 --- f3 --- f4 --- f5 --- f6
+    let (base_height, c1, forked) = generate_blocks_478557_478563();
+    ExtBuilder::default()
+        .build_mock((*c1.get(0).unwrap(), base_height), Network::Mainnet)
+        .execute_with(|| {
+            System::set_block_number(1);
+            for header in &c1[1..6] {
+                assert_ok!(XGatewayBitcoin::apply_push_header(*header));
+            }
+            let confirmed_index = XGatewayBitcoin::confirmed_index().unwrap();
+            assert_eq!(confirmed_index.hash, c1.get(2).unwrap().hash());
+            // the deposit relayed in the confirmed block
+            let tx_hash =
+                h256("0x0000000000000000000000000000000000000000000000000000000000000001");
+            BlockTxs::<Test>::insert(c1.get(2).unwrap().hash(), vec![tx_hash]);
+
+            // the forked headers do not reach the confirmed height
+            for header in &forked[2..5] {
+                assert_ok!(XGatewayBitcoin::apply_push_header(*header));
+            }
+            assert!(!XGatewayBitcoin::bridge_halted());
+
+            // the confirmation window is enlarged, the forked chain wins across the confirmed block
+            assert_ok!(XGatewayBitcoin::set_confirmed_number(Origin::root(), 6));
+            assert_ok!(XGatewayBitcoin::apply_push_header(*forked.get(5).unwrap()));
+            assert_ok!(XGatewayBitcoin::apply_push_header(*forked.get(6).unwrap()));
+            let best_index = XGatewayBitcoin::best_index();
+            assert_eq!(best_index.hash, forked.get(6).unwrap().hash());
+            should_in_mainchain(&c1[2..6], false);
+            should_in_mainchain(&forked[1..7], true);
+
+            System::assert_has_event(crate::mock::Event::XGatewayBitcoin(
+                crate::Event::ConfirmedBlockOrphaned(c1.get(2).unwrap().hash(), base_height + 2),
+            ));
+            assert!(XGatewayBitcoin::bridge_halted());
+            assert_eq!(
+                XGatewayBitcoin::suspect_deposits(tx_hash),
+                Some(c1.get(2).unwrap().hash())
+            );
+            assert!(XGatewayBitcoin::block_txs(c1.get(2).unwrap().hash()).is_empty());
+
+            // the bridge keeps halted until governance resumes it
+            assert_ok!(XGatewayBitcoin::remove_suspect_deposit(
+                Origin::root(),
+                tx_hash
+            ));
+            assert_noop!(
+                XGatewayBitcoin::remove_suspect_deposit(Origin::root(), tx_hash),
+                XGatewayBitcoinErr::NoSuspectDeposit
+            );
+            assert_ok!(XGatewayBitcoin::set_bridge_halted(Origin::root(), false));
+            assert!(!XGatewayBitcoin::bridge_halted());
+        });
+}
+
 #[test]
 fn test_change_difficulty() {
     ExtBuilder::default().build_and_execute(|| {
@@ -219,3 +282,204 @@ fn test_call() {
         assert_ok!(XGatewayBitcoin::push_header(origin, v));
     })
 }
+
+#[test]
+fn test_relayer_whitelist() {
+    ExtBuilder::default().build_and_execute(|| {
+        System::set_block_number(1);
+        let headers = generate_blocks_63290_63310();
+        let push_header = |who, height: u32| {
+            let header = serialization::serialize(&headers[&height]).take();
+            XGatewayBitcoin::push_header(frame_system::RawOrigin::Signed(who).into(), header)
+        };
+
+        assert_noop!(
+            XGatewayBitcoin::set_relayer_whitelist_enabled(
+                frame_system::RawOrigin::Signed(alice()).into(),
+                true
+            ),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(XGatewayBitcoin::set_relayer_whitelist_enabled(
+            Origin::root(),
+            true
+        ));
+        System::assert_last_event(crate::mock::Event::XGatewayBitcoin(
+            crate::Event::RelayerWhitelistUpdated(true),
+        ));
+        assert_ok!(XGatewayBitcoin::add_relayer(Origin::root(), bob()));
+        System::assert_last_event(crate::mock::Event::XGatewayBitcoin(
+            crate::Event::RelayerAdded(bob()),
+        ));
+        assert_noop!(
+            XGatewayBitcoin::add_relayer(Origin::root(), bob()),
+            XGatewayBitcoinErr::DuplicatedRelayer
+        );
+
+        // the stranger is rejected while the whitelisted relayer is accepted
+        assert_noop!(
+            push_header(alice(), 63291),
+            XGatewayBitcoinErr::NotWhitelistedRelayer
+        );
+        assert_noop!(
+            XGatewayBitcoin::push_transaction(
+                frame_system::RawOrigin::Signed(alice()).into(),
+                vec![],
+                vec![],
+                None
+            ),
+            XGatewayBitcoinErr::NotWhitelistedRelayer
+        );
+        assert_ok!(push_header(bob(), 63291));
+
+        let status = XGatewayBitcoin::get_bridge_status();
+        assert_eq!(status.best_height, 63291);
+        assert!(status.relayer_whitelist_enabled);
+        assert_eq!(status.relayer_count, 1);
+
+        // the removal takes effect immediately
+        assert_ok!(XGatewayBitcoin::remove_relayer(Origin::root(), bob()));
+        System::assert_last_event(crate::mock::Event::XGatewayBitcoin(
+            crate::Event::RelayerRemoved(bob()),
+        ));
+        assert_noop!(
+            push_header(bob(), 63292),
+            XGatewayBitcoinErr::NotWhitelistedRelayer
+        );
+        assert_noop!(
+            XGatewayBitcoin::remove_relayer(Origin::root(), bob()),
+            XGatewayBitcoinErr::NoRelayer
+        );
+        assert_eq!(XGatewayBitcoin::get_bridge_status().relayer_count, 0);
+
+        // anyone can relay once the whitelist is disabled
+        assert_ok!(XGatewayBitcoin::set_relayer_whitelist_enabled(
+            Origin::root(),
+            false
+        ));
+        assert_ok!(push_header(alice(), 63292));
+        assert!(!XGatewayBitcoin::get_bridge_status().relayer_whitelist_enabled);
+    })
+}
+
+#[test]
+fn test_relay_stats() {
+    ExtBuilder::default().build_and_execute(|| {
+        let headers = generate_blocks_63290_63310();
+        assert_eq!(XGatewayBitcoin::get_bridge_status().average_relay_lag, None);
+        assert!(XGatewayBitcoin::btc_relay_stats(10).is_empty());
+
+        // (btc height, chainx block number, relayer, lag in seconds)
+        let relays = [
+            (63291, 1, alice(), 600),
+            (63292, 3, bob(), 300),
+            (63293, 10, alice(), 1200),
+        ];
+        for (height, block_number, relayer, lag) in relays.iter().cloned() {
+            let header = headers[&height];
+            System::set_block_number(block_number);
+            NOW.with(|now| {
+                *now.borrow_mut() = Some(Duration::from_secs(u64::from(header.time) + lag))
+            });
+            assert_ok!(XGatewayBitcoin::push_header(
+                frame_system::RawOrigin::Signed(relayer).into(),
+                serialization::serialize(&header).take()
+            ));
+        }
+
+        let time_of = |height: u32| headers[&height].time;
+        assert_eq!(
+            XGatewayBitcoin::btc_relay_stats(10),
+            vec![
+                (63291, time_of(63291), 1, alice()),
+                (63292, time_of(63292), 3, bob()),
+                (63293, time_of(63293), 10, alice()),
+            ]
+        );
+        // the walk is clamped, so any count is served
+        assert_eq!(
+            XGatewayBitcoin::btc_relay_stats(u32::MAX),
+            XGatewayBitcoin::btc_relay_stats(10)
+        );
+        assert_eq!(
+            XGatewayBitcoin::btc_relay_stats(2),
+            vec![
+                (63292, time_of(63292), 3, bob()),
+                (63293, time_of(63293), 10, alice()),
+            ]
+        );
+
+        assert_eq!(XGatewayBitcoin::average_relay_lag(2), Some(750));
+        assert_eq!(
+            XGatewayBitcoin::get_bridge_status().average_relay_lag,
+            Some(700)
+        );
+    })
+}
+
+#[test]
+fn test_prune_headers() {
+    ExtBuilder::default().build_and_execute(|| {
+        let headers = generate_blocks_63290_63310();
+        for i in 63291..63310 {
+            assert_ok!(XGatewayBitcoin::apply_push_header(headers[&i]));
+        }
+        assert_eq!(XGatewayBitcoin::confirmed_index().unwrap().height, 63306);
+        // Shorten the retargeting interval to 10 blocks.
+        ParamsInfo::<Test>::put(BtcParams::new(545259519, 2 * 60 * 60, 6000, 10 * 60, 4));
+
+        assert_noop!(
+            XGatewayBitcoin::prune_headers(Origin::signed(alice()), 63296, 3),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            XGatewayBitcoin::prune_headers(Origin::root(), 63297, 3),
+            XGatewayBitcoinErr::PruneHeightTooHigh
+        );
+
+        let archived = || {
+            System::events()
+                .into_iter()
+                .filter_map(|record| match record.event {
+                    crate::mock::Event::XGatewayBitcoin(crate::Event::BtcHeaderArchived(
+                        hash,
+                        header_info,
+                    )) => Some((hash, header_info.height)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_ok!(XGatewayBitcoin::prune_headers(Origin::root(), 63296, 3));
+        assert_eq!(XGatewayBitcoin::pruned_header_height(), 63293);
+        assert_eq!(
+            archived(),
+            vec![
+                (headers[&63290].hash(), 63290),
+                (headers[&63291].hash(), 63291),
+                (headers[&63292].hash(), 63292),
+            ]
+        );
+        assert!(XGatewayBitcoin::headers(&headers[&63292].hash()).is_none());
+        assert!(XGatewayBitcoin::block_hash_for(&63292).is_empty());
+        assert!(!XGatewayBitcoin::main_chain(&headers[&63292].hash()));
+        assert!(XGatewayBitcoin::headers(&headers[&63293].hash()).is_some());
+
+        // Resume from the pruned height, stop at `before_height`.
+        System::reset_events();
+        assert_ok!(XGatewayBitcoin::prune_headers(Origin::root(), 63296, 100));
+        assert_eq!(XGatewayBitcoin::pruned_header_height(), 63296);
+        assert_eq!(archived().len(), 3);
+        assert!(XGatewayBitcoin::headers(&headers[&63296].hash()).is_some());
+
+        // The recent headers are still enough to extend the chain.
+        ParamsInfo::<Test>::put(BtcParams::new(
+            545259519,
+            2 * 60 * 60,
+            2 * 7 * 24 * 60 * 60,
+            10 * 60,
+            4,
+        ));
+        assert_ok!(XGatewayBitcoin::apply_push_header(headers[&63310]));
+    })
+}
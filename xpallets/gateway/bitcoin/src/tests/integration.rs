@@ -0,0 +1,891 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! End-to-end tests of the bridge, driven by a scripted bitcoin chain.
+//!
+//! The blocks are mined with the regtest difficulty on top of the genesis header of the mock,
+//! each of them carries at most one transaction, thus the merkle root of a block is the txid
+//! of its transaction.
+
+use codec::Encode;
+use frame_support::{
+    assert_noop, assert_ok,
+    dispatch::{DispatchResult, DispatchResultWithPostInfo},
+};
+use hex_literal::hex;
+use sp_core::crypto::{set_default_ss58_version, Ss58AddressFormatRegistry, Ss58Codec};
+
+use light_bitcoin::{
+    chain::{BlockHeader as BtcHeader, OutPoint, Transaction, TransactionInput, TransactionOutput},
+    keys::Network,
+    merkle::PartialMerkleTree,
+    primitives::{hash_rev, Bytes, Compact, H256, U256},
+    script::{Builder, Opcode},
+    serialization::{self, Reader, SERIALIZE_TRANSACTION_WITNESS},
+};
+
+use xp_assets_registrar::Chain;
+use xp_gateway_bitcoin::{extract_output_addr, BtcTxType};
+use xp_protocol::X_BTC;
+use xpallet_gateway_records::WithdrawalState;
+
+use crate::{
+    benchmarking::withdraw_tx,
+    mock::{
+        alice, bob, charlie, AccountId, ExtBuilder, Origin, System, XAssets, XGatewayBitcoin,
+        XGatewayBitcoinErr, XGatewayCommon, XGatewayRecords, CHANNEL,
+    },
+    types::{
+        BtcDepositCache, BtcRelayedTxInfo, BtcTxDisplay, BtcTxResult, BtcTxSkipReason, BtcTxState,
+    },
+    Event,
+};
+
+/// The lowest difficulty allowed by the bridge params of the mock.
+const REGTEST_BITS: u32 = 0x207fffff;
+const BLOCK_SPACING: u32 = 10 * 60;
+/// The output key of the taproot hot address of the mock trustees.
+const HOT_OUTPUT_KEY: [u8; 32] =
+    hex!("9a9ea267884f5549c206b2aec2bd56d98730f90532ea7f7154d4d4f923b7e3bb");
+/// The channel-only OP_RETURN naming the referral id of charlie.
+const CHANNEL_ONLY: &[u8] = b"@polkaworld";
+/// The withdrawal address paid by the signed `withdraw_tx`.
+const WITHDRAWAL_ADDR: &[u8] = b"tb1pexff2s7l58sthpyfrtx500ax234stcnt0gz2lr4kwe0ue95a2e0srxsc68";
+
+/// A block of the scripted bitcoin chain.
+#[derive(Clone)]
+struct BtcBlock {
+    header: BtcHeader,
+    tx: Option<Transaction>,
+}
+
+impl BtcBlock {
+    fn genesis() -> Self {
+        Self {
+            header: XGatewayBitcoin::genesis_info().0,
+            tx: None,
+        }
+    }
+
+    fn hash(&self) -> H256 {
+        self.header.hash()
+    }
+
+    /// Mines the next block carrying `tx`.
+    fn mine(&self, tx: Option<&Transaction>) -> Self {
+        self.mine_at(tx, self.header.time + BLOCK_SPACING)
+    }
+
+    /// Mines the next block carrying `tx` at `time`, so that the forks of the same
+    /// content can be told apart.
+    fn mine_at(&self, tx: Option<&Transaction>, time: u32) -> Self {
+        let mut header = BtcHeader {
+            version: 0x20000000,
+            previous_header_hash: self.hash(),
+            merkle_root_hash: tx.map(|tx| tx.hash()).unwrap_or_default(),
+            time,
+            bits: Compact::new(REGTEST_BITS),
+            nonce: 0,
+        };
+        let target = Compact::new(REGTEST_BITS).to_u256().unwrap();
+        while U256::from(hash_rev(header.hash()).as_bytes()) > target {
+            header.nonce += 1;
+        }
+        Self {
+            header,
+            tx: tx.cloned(),
+        }
+    }
+
+    /// Returns the proof of the only tx of the block.
+    fn merkle_proof(&self) -> PartialMerkleTree {
+        let tx = self.tx.as_ref().expect("the block carries a tx");
+        // tx count, the leaf hashes and the flag bits of a single tx tree
+        let mut raw = 1u32.to_le_bytes().to_vec();
+        raw.push(1);
+        raw.extend_from_slice(tx.hash().as_bytes());
+        raw.extend_from_slice(&[1, 1]);
+        serialization::deserialize(Reader::new(&raw)).unwrap()
+    }
+}
+
+fn relay_header(block: &BtcBlock) -> DispatchResultWithPostInfo {
+    XGatewayBitcoin::push_header(
+        Origin::signed(alice()),
+        serialization::serialize(&block.header).into(),
+    )
+}
+
+fn relay_tx(block: &BtcBlock, prev_tx: Option<&Transaction>) -> DispatchResultWithPostInfo {
+    let tx = block.tx.as_ref().expect("the block carries a tx");
+    let info = BtcRelayedTxInfo {
+        block_hash: block.hash(),
+        merkle_proof: block.merkle_proof(),
+    };
+    XGatewayBitcoin::push_transaction(
+        Origin::signed(alice()),
+        serialization::serialize_with_flags(tx, SERIALIZE_TRANSACTION_WITNESS).into(),
+        info.encode(),
+        prev_tx.map(|prev_tx| {
+            serialization::serialize_with_flags(prev_tx, SERIALIZE_TRANSACTION_WITNESS).into()
+        }),
+    )
+}
+
+/// Relays the tx of `block` without serializing it, for the txs which can't be deserialized.
+fn apply_relay_tx(block: &BtcBlock, prev_tx: Option<&Transaction>) -> DispatchResult {
+    let tx = block.tx.clone().expect("the block carries a tx");
+    let info = BtcRelayedTxInfo {
+        block_hash: block.hash(),
+        merkle_proof: block.merkle_proof(),
+    };
+    XGatewayBitcoin::apply_push_transaction(info.into_relayed_tx(tx), prev_tx.cloned())
+}
+
+/// Mines and relays `count` empty blocks on top of `parent`.
+fn relay_empty_blocks(parent: &BtcBlock, count: usize) -> Vec<BtcBlock> {
+    let mut blocks: Vec<BtcBlock> = Vec::with_capacity(count);
+    for _ in 0..count {
+        let block = blocks.last().unwrap_or(parent).mine(None);
+        assert_ok!(relay_header(&block));
+        blocks.push(block);
+    }
+    blocks
+}
+
+/// Mines and relays the block carrying `tx` along with the blocks confirming it,
+/// returns the block of `tx` and the new tip.
+fn relay_confirmed(parent: &BtcBlock, tx: &Transaction) -> (BtcBlock, BtcBlock) {
+    let block = parent.mine(Some(tx));
+    assert_ok!(relay_header(&block));
+    let confirmations = XGatewayBitcoin::confirmation_number() as usize;
+    let tip = relay_empty_blocks(&block, confirmations - 1).pop().unwrap();
+    assert_eq!(
+        XGatewayBitcoin::confirmed_index().unwrap().hash,
+        block.hash()
+    );
+    (block, tip)
+}
+
+fn tx(inputs: Vec<TransactionInput>, outputs: Vec<TransactionOutput>) -> Transaction {
+    Transaction {
+        version: 2,
+        inputs,
+        outputs,
+        lock_time: 0,
+    }
+}
+
+fn input(txid: H256, index: u32) -> TransactionInput {
+    TransactionInput {
+        previous_output: OutPoint { txid, index },
+        script_sig: Bytes::default(),
+        sequence: 0xffff_ffff,
+        script_witness: vec![],
+    }
+}
+
+fn output(value: u64, script_pubkey: Bytes) -> TransactionOutput {
+    TransactionOutput {
+        value,
+        script_pubkey,
+    }
+}
+
+fn hot_script() -> Bytes {
+    Builder::default()
+        .push_opcode(Opcode::OP_1)
+        .push_bytes(&HOT_OUTPUT_KEY)
+        .into_script()
+        .into()
+}
+
+fn p2pkh_script(pubkey_hash: [u8; 20]) -> Bytes {
+    Builder::default()
+        .push_opcode(Opcode::OP_DUP)
+        .push_opcode(Opcode::OP_HASH160)
+        .push_bytes(&pubkey_hash)
+        .push_opcode(Opcode::OP_EQUALVERIFY)
+        .push_opcode(Opcode::OP_CHECKSIG)
+        .into_script()
+        .into()
+}
+
+fn op_return_script(who: &AccountId) -> Bytes {
+    op_return_data_script(who.to_ss58check().as_bytes())
+}
+
+fn op_return_data_script(data: &[u8]) -> Bytes {
+    Builder::default()
+        .push_opcode(Opcode::OP_RETURN)
+        .push_bytes(data)
+        .into_script()
+        .into()
+}
+
+/// Returns the tx funding the depositor, so that the input address of its deposit
+/// can be resolved. `salt` makes the txid unique.
+fn funding_tx(depositor: Bytes, salt: u8) -> Transaction {
+    tx(
+        vec![input(H256::repeat_byte(salt), 0)],
+        vec![output(10_000_000, depositor)],
+    )
+}
+
+/// Returns the tx depositing `value` from the first output of `funding`.
+fn deposit_tx(funding: &Transaction, value: u64, who: Option<&AccountId>) -> Transaction {
+    let mut outputs = vec![output(value, hot_script())];
+    if let Some(who) = who {
+        outputs.push(output(0, op_return_script(who)));
+    }
+    tx(vec![input(funding.hash(), 0)], outputs)
+}
+
+/// Returns the tx depositing `value` from the first output of `funding` with the raw
+/// OP_RETURN `data`.
+fn raw_deposit_tx(funding: &Transaction, value: u64, data: &[u8]) -> Transaction {
+    let outputs = vec![
+        output(value, hot_script()),
+        output(0, op_return_data_script(data)),
+    ];
+    tx(vec![input(funding.hash(), 0)], outputs)
+}
+
+fn deposit_state() -> Option<BtcTxState> {
+    Some(BtcTxState {
+        tx_type: BtcTxType::Deposit,
+        result: BtcTxResult::Success,
+    })
+}
+
+fn execute(test: impl FnOnce(BtcBlock)) {
+    set_default_ss58_version(Ss58AddressFormatRegistry::ChainxAccount.into());
+    ExtBuilder::default().build_and_execute(|| test(BtcBlock::genesis()));
+}
+
+#[test]
+fn test_deposit_with_op_return() {
+    execute(|genesis| {
+        let funding = funding_tx(p2pkh_script([1u8; 20]), 1);
+        let deposit = deposit_tx(&funding, 500_000, Some(&alice()));
+
+        let block = genesis.mine(Some(&deposit));
+        assert_ok!(relay_header(&block));
+        assert_noop!(
+            relay_tx(&block, Some(&funding)),
+            XGatewayBitcoinErr::UnconfirmedTx
+        );
+
+        let confirmations = XGatewayBitcoin::confirmation_number() as usize;
+        relay_empty_blocks(&block, confirmations - 1);
+        assert_eq!(
+            XGatewayBitcoin::confirmed_index().unwrap().hash,
+            block.hash()
+        );
+        assert_ok!(relay_tx(&block, Some(&funding)));
+
+        assert_eq!(XGatewayBitcoin::tx_state(deposit.hash()), deposit_state());
+        assert_eq!(XAssets::usable_balance(&alice(), &X_BTC), 500_000);
+        System::assert_has_event(crate::mock::Event::XGatewayBitcoin(Event::Deposited(
+            deposit.hash(),
+            alice(),
+            500_000,
+        )));
+
+        // the processed tx can't be replayed
+        assert_noop!(
+            relay_tx(&block, Some(&funding)),
+            XGatewayBitcoinErr::ReplayedTx
+        );
+    });
+}
+
+#[test]
+fn test_deposit_without_op_return_claimed_later() {
+    execute(|genesis| {
+        let depositor = p2pkh_script([2u8; 20]);
+        let funding = funding_tx(depositor.clone(), 1);
+        let deposit = deposit_tx(&funding, 300_000, None);
+        let input_addr = extract_output_addr(&funding.outputs[0], Network::Testnet)
+            .unwrap()
+            .to_string()
+            .into_bytes();
+
+        // the deposit is kept pending under the input address
+        let (block, tip) = relay_confirmed(&genesis, &deposit);
+        assert_ok!(relay_tx(&block, Some(&funding)));
+        assert_eq!(XGatewayBitcoin::tx_state(deposit.hash()), deposit_state());
+        assert_eq!(
+            XGatewayBitcoin::pending_deposits(&input_addr),
+            vec![BtcDepositCache {
+                txid: deposit.hash(),
+                balance: 300_000,
+            }]
+        );
+        assert_eq!(XAssets::total_issuance(&X_BTC), 0);
+        System::assert_has_event(crate::mock::Event::XGatewayBitcoin(
+            Event::UnclaimedDeposit(deposit.hash(), input_addr.clone()),
+        ));
+
+        // the next deposit from the same address with OP_RETURN claims the pending one
+        let funding = funding_tx(depositor.clone(), 2);
+        let claim = deposit_tx(&funding, 200_000, Some(&bob()));
+        let (block, tip) = relay_confirmed(&tip, &claim);
+        assert_ok!(relay_tx(&block, Some(&funding)));
+        assert!(XGatewayBitcoin::pending_deposits(&input_addr).is_empty());
+        assert_eq!(XAssets::usable_balance(&bob(), &X_BTC), 500_000);
+        System::assert_has_event(crate::mock::Event::XGatewayBitcoin(
+            Event::PendingDepositRemoved(bob(), 300_000, deposit.hash(), input_addr),
+        ));
+
+        // the address is bound to the claimer from now on
+        let funding = funding_tx(depositor, 3);
+        let deposit = deposit_tx(&funding, 100_000, None);
+        let (block, _) = relay_confirmed(&tip, &deposit);
+        assert_ok!(relay_tx(&block, Some(&funding)));
+        assert_eq!(XAssets::usable_balance(&bob(), &X_BTC), 600_000);
+    });
+}
+
+#[test]
+fn test_withdrawal() {
+    execute(|genesis| {
+        let funding = funding_tx(p2pkh_script([3u8; 20]), 1);
+        let deposit = deposit_tx(&funding, 1_000_000, Some(&alice()));
+        let (block, tip) = relay_confirmed(&genesis, &deposit);
+        assert_ok!(relay_tx(&block, Some(&funding)));
+
+        assert_ok!(XGatewayCommon::withdraw(
+            Origin::signed(alice()),
+            X_BTC,
+            50_000,
+            WITHDRAWAL_ADDR.to_vec(),
+            Default::default()
+        ));
+        assert_eq!(
+            XGatewayRecords::state_of(0),
+            Some(WithdrawalState::Applying)
+        );
+
+        // the trustees propose the signed withdrawal tx
+        let (withdrawal, _, withdrawal_prev) = withdraw_tx();
+        assert_ok!(XGatewayBitcoin::create_taproot_withdraw_tx(
+            Origin::signed(alice()),
+            vec![0],
+            serialization::serialize_with_flags(&withdrawal, SERIALIZE_TRANSACTION_WITNESS).into()
+        ));
+        assert_eq!(
+            XGatewayRecords::state_of(0),
+            Some(WithdrawalState::Processing)
+        );
+
+        let (block, _) = relay_confirmed(&tip, &withdrawal);
+        assert_ok!(relay_tx(&block, Some(&withdrawal_prev)));
+        assert_eq!(
+            XGatewayBitcoin::tx_state(withdrawal.hash()),
+            Some(BtcTxState {
+                tx_type: BtcTxType::Withdrawal,
+                result: BtcTxResult::Success,
+            })
+        );
+        assert!(XGatewayBitcoin::withdrawal_proposal().is_none());
+        assert!(XGatewayRecords::pending_withdrawals(0).is_none());
+        assert_eq!(XAssets::usable_balance(&alice(), &X_BTC), 950_000);
+        assert_eq!(XAssets::total_issuance(&X_BTC), 950_000);
+        System::assert_has_event(crate::mock::Event::XGatewayBitcoin(Event::Withdrawn(
+            withdrawal.hash(),
+            vec![0],
+            50_000,
+        )));
+    });
+}
+
+#[test]
+fn test_withdrawal_shortfall_refunded() {
+    execute(|genesis| {
+        let funding = funding_tx(p2pkh_script([3u8; 20]), 1);
+        let deposit = deposit_tx(&funding, 1_000_000, Some(&alice()));
+        let (block, tip) = relay_confirmed(&genesis, &deposit);
+        assert_ok!(relay_tx(&block, Some(&funding)));
+
+        // The signed `withdraw_tx` pays 50_000, which matches 51_000 minus a fee of 1_000.
+        assert_ok!(XGatewayBitcoin::set_btc_withdrawal_fee(
+            Origin::root(),
+            1_000
+        ));
+        assert_ok!(XGatewayCommon::withdraw(
+            Origin::signed(alice()),
+            X_BTC,
+            51_000,
+            WITHDRAWAL_ADDR.to_vec(),
+            Default::default()
+        ));
+        let (withdrawal, _, withdrawal_prev) = withdraw_tx();
+        assert_ok!(XGatewayBitcoin::create_taproot_withdraw_tx(
+            Origin::signed(alice()),
+            vec![0],
+            serialization::serialize_with_flags(&withdrawal, SERIALIZE_TRANSACTION_WITNESS).into()
+        ));
+
+        // The fee is lowered before the confirmation, so the tx ate 1_000 more than expected.
+        assert_ok!(XGatewayBitcoin::set_btc_withdrawal_fee(Origin::root(), 0));
+        let (block, _) = relay_confirmed(&tip, &withdrawal);
+        assert_ok!(relay_tx(&block, Some(&withdrawal_prev)));
+
+        assert!(XGatewayRecords::pending_withdrawals(0).is_none());
+        System::assert_has_event(crate::mock::Event::XGatewayRecords(
+            xpallet_gateway_records::Event::WithdrawalShortfallRefunded(0, 1_000),
+        ));
+        assert_eq!(XAssets::usable_balance(&alice(), &X_BTC), 950_000);
+        assert_eq!(XAssets::total_issuance(&X_BTC), 950_000);
+        System::assert_has_event(crate::mock::Event::XGatewayBitcoin(Event::Withdrawn(
+            withdrawal.hash(),
+            vec![0],
+            50_000,
+        )));
+    });
+}
+
+#[test]
+fn test_reorg_across_confirmation() {
+    execute(|genesis| {
+        let main = relay_empty_blocks(&genesis, 2);
+        let (a1, a2) = (&main[0], &main[1]);
+
+        let funding = funding_tx(p2pkh_script([4u8; 20]), 1);
+        let deposit = deposit_tx(&funding, 400_000, Some(&alice()));
+        let a3 = a2.mine(Some(&deposit));
+        assert_ok!(relay_header(&a3));
+        relay_empty_blocks(&a3, 2);
+
+        // the deposit is one block short of the confirmation
+        assert_eq!(XGatewayBitcoin::confirmed_index().unwrap().hash, a2.hash());
+        assert_noop!(
+            relay_tx(&a3, Some(&funding)),
+            XGatewayBitcoinErr::UnconfirmedTx
+        );
+
+        // a longer fork packs the deposit into another block
+        let b3 = a2.mine_at(Some(&deposit), a3.header.time + 1);
+        assert_ok!(relay_header(&b3));
+        let fork = relay_empty_blocks(&b3, 3);
+        assert_eq!(XGatewayBitcoin::best_index().hash, fork[2].hash());
+        assert_eq!(XGatewayBitcoin::confirmed_index().unwrap().hash, b3.hash());
+        assert!(!XGatewayBitcoin::main_chain(&a3.hash()));
+        assert!(XGatewayBitcoin::main_chain(&b3.hash()));
+
+        // only the deposit of the main chain is accepted
+        assert_noop!(
+            relay_tx(&a3, Some(&funding)),
+            XGatewayBitcoinErr::UnconfirmedTx
+        );
+        assert_ok!(relay_tx(&b3, Some(&funding)));
+        assert_eq!(XAssets::usable_balance(&alice(), &X_BTC), 400_000);
+        assert!(!XGatewayBitcoin::bridge_halted());
+
+        // the confirmed blocks can't be reorganized
+        let c2 = a1.mine_at(None, a2.header.time + 1);
+        assert_ok!(relay_header(&c2));
+        let fork = relay_empty_blocks(&c2, 3);
+        let c6 = fork[2].mine(None);
+        assert_noop!(relay_header(&c6), XGatewayBitcoinErr::AncientFork);
+        assert_eq!(XGatewayBitcoin::confirmed_index().unwrap().hash, b3.hash());
+    });
+}
+
+fn assert_skipped(tx: &Transaction, reason: BtcTxSkipReason) {
+    assert_eq!(XGatewayBitcoin::tx_state(tx.hash()), None);
+    System::assert_has_event(crate::mock::Event::XGatewayBitcoin(Event::TxHandleSkipped(
+        tx.hash(),
+        reason,
+    )));
+}
+
+#[test]
+fn test_skip_tx_without_prev_output_address() {
+    execute(|genesis| {
+        // the funding output is spendable by anyone, no address could be extracted
+        let nonstandard = Builder::default()
+            .push_opcode(Opcode::OP_TRUE)
+            .into_script()
+            .into();
+        let funding = funding_tx(nonstandard, 1);
+        let deposit = deposit_tx(&funding, 500_000, Some(&alice()));
+        let (block, _) = relay_confirmed(&genesis, &deposit);
+
+        assert_ok!(relay_tx(&block, Some(&funding)));
+        assert_skipped(&deposit, BtcTxSkipReason::NoPrevOutputAddress);
+        assert_eq!(XAssets::total_issuance(&X_BTC), 0);
+
+        // the skipped deposit can be relayed again without the previous tx
+        assert_ok!(relay_tx(&block, None));
+        assert_eq!(XGatewayBitcoin::tx_state(deposit.hash()), deposit_state());
+        assert_eq!(XAssets::usable_balance(&alice(), &X_BTC), 500_000);
+    });
+}
+
+#[test]
+fn test_skip_tx_with_out_of_range_outpoint() {
+    execute(|genesis| {
+        let funding = funding_tx(p2pkh_script([5u8; 20]), 1);
+        let deposit = tx(
+            vec![input(funding.hash(), 1)],
+            vec![
+                output(500_000, hot_script()),
+                output(0, op_return_script(&alice())),
+            ],
+        );
+        let (block, _) = relay_confirmed(&genesis, &deposit);
+
+        assert_ok!(relay_tx(&block, Some(&funding)));
+        assert_skipped(&deposit, BtcTxSkipReason::PrevOutputOutOfRange);
+        assert_eq!(XAssets::total_issuance(&X_BTC), 0);
+    });
+}
+
+#[test]
+fn test_skip_tx_without_inputs() {
+    execute(|genesis| {
+        let funding = funding_tx(p2pkh_script([6u8; 20]), 1);
+        let deposit = tx(
+            vec![],
+            vec![
+                output(500_000, hot_script()),
+                output(0, op_return_script(&alice())),
+            ],
+        );
+        let (block, _) = relay_confirmed(&genesis, &deposit);
+
+        assert_ok!(apply_relay_tx(&block, Some(&funding)));
+        assert_skipped(&deposit, BtcTxSkipReason::NoInputs);
+        assert_ok!(apply_relay_tx(&block, None));
+        assert_eq!(XAssets::total_issuance(&X_BTC), 0);
+    });
+}
+
+fn register_chunk(
+    tx_hash: H256,
+    chunk_index: u32,
+    total_chunks: u32,
+    chunk: &[u8],
+) -> DispatchResultWithPostInfo {
+    XGatewayBitcoin::register_raw_tx(
+        Origin::signed(alice()),
+        tx_hash,
+        chunk_index,
+        total_chunks,
+        chunk.to_vec(),
+    )
+}
+
+fn raw_tx(tx: &Transaction) -> Vec<u8> {
+    serialization::serialize_with_flags(tx, SERIALIZE_TRANSACTION_WITNESS).into()
+}
+
+#[test]
+fn test_relay_registered_raw_tx() {
+    execute(|genesis| {
+        let funding = funding_tx(p2pkh_script([7u8; 20]), 1);
+        let deposit = deposit_tx(&funding, 500_000, Some(&alice()));
+        let (block, _) = relay_confirmed(&genesis, &deposit);
+        let info = BtcRelayedTxInfo {
+            block_hash: block.hash(),
+            merkle_proof: block.merkle_proof(),
+        };
+        let push_registered = || {
+            XGatewayBitcoin::push_registered_transaction(
+                Origin::signed(alice()),
+                deposit.hash(),
+                info.encode(),
+                Some(raw_tx(&funding)),
+            )
+        };
+
+        let raw_deposit = raw_tx(&deposit);
+        let chunks = raw_deposit
+            .chunks(raw_deposit.len() / 3 + 1)
+            .collect::<Vec<_>>();
+        assert_eq!(chunks.len(), 3);
+        assert_ok!(register_chunk(deposit.hash(), 0, 3, chunks[0]));
+        assert_ok!(register_chunk(deposit.hash(), 1, 3, chunks[1]));
+        // the relay is rejected until all the chunks are registered
+        assert_noop!(push_registered(), XGatewayBitcoinErr::NoRegisteredRawTx);
+        assert_ok!(register_chunk(deposit.hash(), 2, 3, chunks[2]));
+        System::assert_last_event(crate::mock::Event::XGatewayBitcoin(Event::RawTxRegistered(
+            alice(),
+            deposit.hash(),
+        )));
+
+        assert_ok!(push_registered());
+        assert_eq!(XGatewayBitcoin::tx_state(deposit.hash()), deposit_state());
+        assert_eq!(XAssets::usable_balance(&alice(), &X_BTC), 500_000);
+        assert_eq!(
+            XGatewayBitcoin::raw_tx_registration(alice(), deposit.hash()),
+            None
+        );
+    });
+}
+
+#[test]
+fn test_register_raw_tx_with_wrong_hash() {
+    execute(|_| {
+        let funding = funding_tx(p2pkh_script([8u8; 20]), 1);
+        let deposit = deposit_tx(&funding, 500_000, Some(&alice()));
+        let raw_deposit = raw_tx(&deposit);
+
+        assert_noop!(
+            register_chunk(funding.hash(), 0, 1, &raw_deposit),
+            XGatewayBitcoinErr::RawTxHashMismatch
+        );
+        // the chunks must be submitted in order
+        assert_noop!(
+            register_chunk(deposit.hash(), 1, 2, &raw_deposit),
+            XGatewayBitcoinErr::InvalidRawTxChunk
+        );
+        assert_ok!(register_chunk(deposit.hash(), 0, 1, &raw_deposit));
+    });
+}
+
+#[test]
+fn test_prune_stale_raw_tx_registration() {
+    use frame_support::traits::Hooks;
+
+    execute(|_| {
+        let funding = funding_tx(p2pkh_script([9u8; 20]), 1);
+        let deposit = deposit_tx(&funding, 500_000, Some(&alice()));
+        let raw_deposit = raw_tx(&deposit);
+
+        assert_ok!(register_chunk(deposit.hash(), 0, 3, &raw_deposit[..10]));
+        let expires_at = XGatewayBitcoin::raw_tx_registration(alice(), deposit.hash())
+            .unwrap()
+            .expires_at;
+        assert_eq!(expires_at, System::block_number() + 600);
+
+        XGatewayBitcoin::on_initialize(expires_at - 1);
+        assert!(XGatewayBitcoin::raw_tx_registration(alice(), deposit.hash()).is_some());
+
+        XGatewayBitcoin::on_initialize(expires_at);
+        assert!(XGatewayBitcoin::raw_tx_registration(alice(), deposit.hash()).is_none());
+        System::assert_last_event(crate::mock::Event::XGatewayBitcoin(
+            Event::RawTxRegistrationPruned(alice(), deposit.hash()),
+        ));
+    });
+}
+
+#[test]
+fn test_raw_tx_registrations_are_capped_and_charged() {
+    use frame_support::{traits::Hooks, weights::Pays};
+
+    execute(|_| {
+        let hash = |i: u8| H256::repeat_byte(i);
+        let pays_fee = |result: DispatchResultWithPostInfo| result.unwrap().pays_fee;
+
+        // the relayers out of the whitelist pay the fee
+        for i in 0..4 {
+            assert_eq!(pays_fee(register_chunk(hash(i), 0, 2, &[i])), Pays::Yes);
+        }
+        assert_eq!(XGatewayBitcoin::raw_tx_registration_count(alice()), 4);
+        assert_noop!(
+            register_chunk(hash(4), 0, 2, &[4]),
+            XGatewayBitcoinErr::TooManyRawTxRegistrations
+        );
+        // restarting a registration does not count
+        System::set_block_number(System::block_number() + 1);
+        assert_ok!(register_chunk(hash(0), 0, 2, &[0]));
+        assert_eq!(XGatewayBitcoin::raw_tx_registration_count(alice()), 4);
+
+        // the pruned registrations no longer count
+        let expires_at = XGatewayBitcoin::raw_tx_registration(alice(), hash(1))
+            .unwrap()
+            .expires_at;
+        XGatewayBitcoin::on_initialize(expires_at);
+        assert_eq!(XGatewayBitcoin::raw_tx_registration_count(alice()), 1);
+
+        // the whitelisted relayers are free
+        assert_ok!(XGatewayBitcoin::add_relayer(Origin::root(), alice()));
+        assert_eq!(pays_fee(register_chunk(hash(4), 0, 2, &[4])), Pays::No);
+        assert_eq!(XGatewayBitcoin::raw_tx_registration_count(alice()), 2);
+    });
+}
+
+#[test]
+fn test_channel_only_deposit_from_bound_address() {
+    execute(|genesis| {
+        let depositor = p2pkh_script([5u8; 20]);
+        let funding = funding_tx(depositor.clone(), 1);
+        let deposit = deposit_tx(&funding, 100_000, Some(&alice()));
+        let (block, tip) = relay_confirmed(&genesis, &deposit);
+        assert_ok!(relay_tx(&block, Some(&funding)));
+        assert_eq!(
+            XGatewayCommon::referral_binding_of(alice(), Chain::Bitcoin),
+            None
+        );
+
+        // the channel-only deposit credits the bound account and attributes the channel
+        let funding = funding_tx(depositor.clone(), 2);
+        let deposit = raw_deposit_tx(&funding, 200_000, CHANNEL_ONLY);
+        let (block, tip) = relay_confirmed(&tip, &deposit);
+        assert_ok!(relay_tx(&block, Some(&funding)));
+        assert_eq!(XGatewayBitcoin::tx_state(deposit.hash()), deposit_state());
+        assert_eq!(XAssets::usable_balance(&alice(), &X_BTC), 300_000);
+        assert_eq!(
+            XGatewayCommon::referral_binding_of(alice(), Chain::Bitcoin),
+            Some(charlie())
+        );
+
+        // the channel attribution is sticky
+        let funding = funding_tx(depositor, 3);
+        let deposit = raw_deposit_tx(&funding, 100_000, b"@bobchannel");
+        let (block, _) = relay_confirmed(&tip, &deposit);
+        assert_ok!(relay_tx(&block, Some(&funding)));
+        assert_eq!(XAssets::usable_balance(&alice(), &X_BTC), 400_000);
+        assert_eq!(
+            XGatewayCommon::referral_binding_of(alice(), Chain::Bitcoin),
+            Some(charlie())
+        );
+    });
+}
+
+#[test]
+fn test_channel_only_deposit_from_unbound_address_goes_pending() {
+    execute(|genesis| {
+        let funding = funding_tx(p2pkh_script([6u8; 20]), 1);
+        let deposit = raw_deposit_tx(&funding, 300_000, CHANNEL_ONLY);
+        let input_addr = extract_output_addr(&funding.outputs[0], Network::Testnet)
+            .unwrap()
+            .to_string()
+            .into_bytes();
+
+        let (block, _) = relay_confirmed(&genesis, &deposit);
+        assert_ok!(relay_tx(&block, Some(&funding)));
+        assert_eq!(XGatewayBitcoin::tx_state(deposit.hash()), deposit_state());
+        assert_eq!(
+            XGatewayBitcoin::pending_deposits(&input_addr),
+            vec![BtcDepositCache {
+                txid: deposit.hash(),
+                balance: 300_000,
+            }]
+        );
+        assert_eq!(XAssets::total_issuance(&X_BTC), 0);
+        System::assert_has_event(crate::mock::Event::XGatewayBitcoin(
+            Event::ChannelOnlyDepositPending(deposit.hash(), CHANNEL.to_vec(), input_addr.clone()),
+        ));
+        System::assert_has_event(crate::mock::Event::XGatewayBitcoin(
+            Event::UnclaimedDeposit(deposit.hash(), input_addr),
+        ));
+    });
+}
+
+#[test]
+fn test_malformed_channel_only_deposit_is_ignored() {
+    execute(|genesis| {
+        let depositor = p2pkh_script([7u8; 20]);
+        let funding = funding_tx(depositor.clone(), 1);
+        let deposit = deposit_tx(&funding, 100_000, Some(&alice()));
+        let (block, tip) = relay_confirmed(&genesis, &deposit);
+        assert_ok!(relay_tx(&block, Some(&funding)));
+
+        // the malformed opreturn is ignored as if there were no opreturn
+        let funding = funding_tx(depositor, 2);
+        let deposit = raw_deposit_tx(&funding, 200_000, b"@polka<world>");
+        let (block, tip) = relay_confirmed(&tip, &deposit);
+        assert_ok!(relay_tx(&block, Some(&funding)));
+        assert_eq!(XAssets::usable_balance(&alice(), &X_BTC), 300_000);
+        assert_eq!(
+            XGatewayCommon::referral_binding_of(alice(), Chain::Bitcoin),
+            None
+        );
+
+        // no channel is noted for the malformed opreturn from an unbound address either
+        let funding = funding_tx(p2pkh_script([8u8; 20]), 3);
+        let deposit = raw_deposit_tx(&funding, 200_000, b"@");
+        let (block, _) = relay_confirmed(&tip, &deposit);
+        assert_ok!(relay_tx(&block, Some(&funding)));
+        assert!(!System::events().iter().any(|record| matches!(
+            record.event,
+            crate::mock::Event::XGatewayBitcoin(Event::ChannelOnlyDepositPending(..))
+        )));
+    });
+}
+
+/// The upper bound of the encoded size of the bridge events in the deposit and withdrawal flows.
+const MAX_FLOW_EVENT_SIZE: usize = 128;
+
+#[test]
+fn test_flow_events_stay_compact() {
+    execute(|genesis| {
+        let funding = funding_tx(p2pkh_script([3u8; 20]), 1);
+        let deposit = deposit_tx(&funding, 1_000_000, Some(&alice()));
+        let (block, tip) = relay_confirmed(&genesis, &deposit);
+        assert_ok!(relay_tx(&block, Some(&funding)));
+
+        assert_ok!(XGatewayCommon::withdraw(
+            Origin::signed(alice()),
+            X_BTC,
+            50_000,
+            WITHDRAWAL_ADDR.to_vec(),
+            Default::default()
+        ));
+        let (withdrawal, _, withdrawal_prev) = withdraw_tx();
+        assert_ok!(XGatewayBitcoin::create_taproot_withdraw_tx(
+            Origin::signed(alice()),
+            vec![0],
+            serialization::serialize_with_flags(&withdrawal, SERIALIZE_TRANSACTION_WITNESS).into()
+        ));
+        let (block, _) = relay_confirmed(&tip, &withdrawal);
+        assert_ok!(relay_tx(&block, Some(&withdrawal_prev)));
+
+        let events = System::events()
+            .into_iter()
+            .filter_map(|record| match record.event {
+                crate::mock::Event::XGatewayBitcoin(event) => Some(event),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert!(!events.is_empty());
+        for event in &events {
+            assert!(
+                event.encoded_size() <= MAX_FLOW_EVENT_SIZE,
+                "{:?} is too large",
+                event
+            );
+        }
+
+        // The full hashes are kept, which the consumers look the txs up with.
+        let deposited = Event::Deposited(deposit.hash(), alice(), 1_000_000);
+        assert!(events.contains(&deposited));
+        // variant + tx_hash + who + amount
+        assert_eq!(deposited.encoded_size(), 1 + 32 + 32 + 16);
+        let withdrawn = Event::Withdrawn(withdrawal.hash(), vec![0], 50_000);
+        assert!(events.contains(&withdrawn));
+        // variant + tx_hash + withdrawal_ids + total_withdrawn
+        assert_eq!(withdrawn.encoded_size(), 1 + 32 + (1 + 4) + 16);
+    });
+}
+
+#[test]
+fn test_tx_display() {
+    let funding = funding_tx(p2pkh_script([3u8; 20]), 1);
+    let deposit = deposit_tx(&funding, 1_000_000, Some(&alice()));
+    let display = BtcTxDisplay::from(&deposit);
+    assert_eq!(display.txid, deposit.hash());
+    assert_eq!(display.inputs, 1);
+    assert_eq!(display.outputs, deposit.outputs().len() as u32);
+    assert_eq!(
+        display.value,
+        deposit
+            .outputs()
+            .iter()
+            .map(|output| output.value)
+            .sum::<u64>()
+    );
+    assert_eq!(
+        format!("{}", display),
+        format!(
+            "Tx({}, inputs:1, outputs:{}, value:{})",
+            xpallet_support::hash_strip(hash_rev(deposit.hash())),
+            display.outputs,
+            display.value
+        )
+    );
+    assert_eq!(format!("{:?}", display), format!("{}", display));
+}
@@ -1,26 +1,154 @@
 // Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
 
 mod header;
+mod integration;
 mod trustee;
 mod tx;
 
+use std::time::Duration;
+
+use frame_support::{assert_noop, assert_ok};
 use sp_core::crypto::{set_default_ss58_version, Ss58AddressFormatRegistry};
 
 use xp_gateway_common::AccountExtractor;
+use xpallet_assets::{Chain, ChainT};
 
-use light_bitcoin::script::Script;
+use light_bitcoin::{
+    keys::{Address, Network},
+    script::Script,
+};
 
 use crate::{
-    mock::{Test, XGatewayBitcoin},
-    Config,
+    mock::{
+        alice, ExtBuilder, Origin, System, Test, XAssets, XGatewayBitcoin, XGatewayBitcoinErr,
+        XGatewayCommon, XGatewayRecords, NOW, X_BTC,
+    },
+    trustee::get_current_trustee_address_pair,
+    types::{BtcWithdrawalNetworkFee, FlagLevel},
+    Config, Event, MaxWithdrawalCount,
 };
 
+const FLAGGED_ADDR: &[u8] = b"mqVznxoxdeSNYgDCg6ZVE5pc6476BY6zHK";
+
+fn withdraw_to(addr: &[u8]) -> frame_support::dispatch::DispatchResult {
+    XGatewayCommon::withdraw(
+        Origin::signed(alice()),
+        X_BTC,
+        100,
+        addr.to_vec(),
+        Default::default(),
+    )
+}
+
 #[test]
 pub fn test_verify_btc_address() {
     let address = b"mqVznxoxdeSNYgDCg6ZVE5pc6476BY6zHK".to_vec();
     assert!(XGatewayBitcoin::verify_btc_address(&address).is_ok());
 }
 
+#[test]
+fn test_check_withdrawal_address() {
+    ExtBuilder::default().build_and_execute(|| {
+        // not a bitcoin address
+        assert_noop!(
+            XGatewayBitcoin::check_addr(b"1NotABitcoinAddress", b""),
+            XGatewayBitcoinErr::InvalidAddr
+        );
+        // mainnet address while the network is testnet
+        assert_noop!(
+            XGatewayBitcoin::check_addr(b"1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2", b""),
+            XGatewayBitcoinErr::AddressNetworkMismatch
+        );
+        // trustee hot and cold address
+        let (hot_addr, cold_addr) = get_current_trustee_address_pair::<Test>().unwrap();
+        let hot_addr = Address {
+            network: Network::Testnet,
+            ..hot_addr
+        };
+        let cold_addr = Address {
+            network: Network::Testnet,
+            ..cold_addr
+        };
+        assert_noop!(
+            XGatewayBitcoin::check_addr(hot_addr.to_string().as_bytes(), b""),
+            XGatewayBitcoinErr::WithdrawToTrusteeAddress
+        );
+        assert_noop!(
+            XGatewayBitcoin::check_addr(cold_addr.to_string().as_bytes(), b""),
+            XGatewayBitcoinErr::WithdrawToTrusteeAddress
+        );
+        // valid testnet address
+        assert_ok!(XGatewayBitcoin::check_addr(
+            b"mqVznxoxdeSNYgDCg6ZVE5pc6476BY6zHK",
+            b""
+        ));
+    });
+}
+
+#[test]
+fn test_warn_flagged_withdrawal_address() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XAssets::issue(&X_BTC, &alice(), 1000, true));
+        assert_ok!(XGatewayBitcoin::set_btc_address_flag(
+            Origin::root(),
+            FLAGGED_ADDR.to_vec(),
+            Some(FlagLevel::Warn)
+        ));
+
+        assert_ok!(withdraw_to(FLAGGED_ADDR));
+        System::assert_has_event(crate::mock::Event::XGatewayBitcoin(
+            Event::FlaggedWithdrawal(alice(), FLAGGED_ADDR.to_vec()),
+        ));
+    });
+}
+
+#[test]
+fn test_block_flagged_withdrawal_address() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XAssets::issue(&X_BTC, &alice(), 1000, true));
+        assert_ok!(XGatewayBitcoin::set_btc_address_flag(
+            Origin::root(),
+            FLAGGED_ADDR.to_vec(),
+            Some(FlagLevel::Block)
+        ));
+        assert_noop!(
+            withdraw_to(FLAGGED_ADDR),
+            XGatewayBitcoinErr::BlockedWithdrawalAddress
+        );
+
+        assert_ok!(XGatewayBitcoin::set_btc_address_flag(
+            Origin::root(),
+            FLAGGED_ADDR.to_vec(),
+            None
+        ));
+        assert_ok!(withdraw_to(FLAGGED_ADDR));
+    });
+}
+
+#[test]
+fn test_withdrawal_address_cooldown() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XAssets::issue(&X_BTC, &alice(), 1000, true));
+
+        // the cooldown is disabled by default
+        assert_ok!(withdraw_to(FLAGGED_ADDR));
+        assert_ok!(withdraw_to(FLAGGED_ADDR));
+
+        assert_ok!(XGatewayBitcoin::set_address_withdraw_cooldown(
+            Origin::root(),
+            Some(10)
+        ));
+        assert_ok!(withdraw_to(FLAGGED_ADDR));
+        assert_noop!(
+            withdraw_to(FLAGGED_ADDR),
+            XGatewayBitcoinErr::WithdrawalAddressInCooldown
+        );
+
+        System::set_block_number(11);
+        assert_ok!(withdraw_to(FLAGGED_ADDR));
+    });
+}
+
 #[test]
 fn test_account_ss58_version() {
     set_default_ss58_version(Ss58AddressFormatRegistry::ChainxAccount.into());
@@ -32,3 +160,131 @@ fn test_account_ss58_version() {
     let data = script.to_bytes();
     assert!(<Test as Config>::AccountExtractor::extract_account(&data).is_some());
 }
+
+/// Records a proposal at `block`, assuming 6 seconds per block since the block 10.
+fn propose_at(block: u64) {
+    System::set_block_number(block);
+    NOW.with(|now| *now.borrow_mut() = Some(Duration::from_secs(1000 + 6 * (block - 10))));
+    XGatewayBitcoin::record_proposal();
+}
+
+fn estimate_of(id: u32) -> Option<(u32, u64)> {
+    XGatewayBitcoin::withdrawal_estimates()
+        .get(&id)
+        .map(|estimate| (estimate.blocks_remaining, estimate.seconds))
+}
+
+#[test]
+fn test_withdrawal_estimates() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XAssets::issue(&X_BTC, &alice(), 1000, true));
+        MaxWithdrawalCount::<Test>::put(2);
+        for _ in 0..5 {
+            assert_ok!(withdraw_to(FLAGGED_ADDR));
+        }
+        let ids = XGatewayRecords::withdrawals_list_by_chain(Chain::Bitcoin)
+            .into_keys()
+            .collect::<Vec<_>>();
+        assert_eq!(ids.len(), 5);
+
+        // nothing is estimated without the cadence
+        assert!(XGatewayBitcoin::withdrawal_estimates().is_empty());
+        propose_at(10);
+        assert!(XGatewayBitcoin::withdrawal_estimates().is_empty());
+
+        // a proposal every 10 blocks, 4 confirmations take 400 blocks of 6 seconds
+        propose_at(20);
+        propose_at(30);
+        System::set_block_number(35);
+        assert_eq!(estimate_of(ids[0]), Some((405, 2430)));
+        assert_eq!(estimate_of(ids[1]), Some((405, 2430)));
+        assert_eq!(estimate_of(ids[2]), Some((415, 2490)));
+        assert_eq!(estimate_of(ids[3]), Some((415, 2490)));
+        assert_eq!(estimate_of(ids[4]), Some((425, 2550)));
+
+        // the first batch is proposed, the rest move forward in the queue
+        assert_ok!(XGatewayRecords::process_withdrawals(
+            &ids[..2],
+            Chain::Bitcoin
+        ));
+        propose_at(40);
+        assert_eq!(estimate_of(ids[0]), Some((400, 2400)));
+        assert_eq!(estimate_of(ids[2]), Some((410, 2460)));
+        assert_eq!(estimate_of(ids[4]), Some((420, 2520)));
+
+        // the first batch is sent, then the second batch is proposed
+        assert_ok!(XGatewayRecords::finish_withdrawals(
+            &ids[..2],
+            Some(Chain::Bitcoin)
+        ));
+        assert_ok!(XGatewayRecords::process_withdrawals(
+            &ids[2..4],
+            Chain::Bitcoin
+        ));
+        propose_at(50);
+        assert_eq!(estimate_of(ids[0]), None);
+        assert_eq!(estimate_of(ids[2]), Some((400, 2400)));
+        assert_eq!(estimate_of(ids[4]), Some((410, 2460)));
+
+        // the estimates keep decreasing while waiting for the next proposal
+        System::set_block_number(55);
+        assert_eq!(estimate_of(ids[2]), Some((395, 2370)));
+        assert_eq!(estimate_of(ids[4]), Some((405, 2430)));
+        System::set_block_number(70);
+        assert_eq!(estimate_of(ids[2]), Some((380, 2280)));
+        assert_eq!(estimate_of(ids[4]), Some((400, 2400)));
+    });
+}
+
+#[test]
+fn test_withdrawal_network_fee() {
+    ExtBuilder::default().build_and_execute(|| {
+        // 100 withdrawals share the 154 vbytes of a proposal tx, 2 vbytes each
+        assert_eq!(
+            XGatewayBitcoin::withdrawal_network_fee(FLAGGED_ADDR),
+            Ok(BtcWithdrawalNetworkFee {
+                fee_rate: 10,
+                batch_size: 100,
+                vbytes: 34 + 2,
+                fee: 360,
+            })
+        );
+        // a P2WPKH output is smaller than a P2PKH one
+        let segwit_addr = b"tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx";
+        assert_eq!(
+            XGatewayBitcoin::withdrawal_network_fee(segwit_addr).map(|fee| fee.vbytes),
+            Ok(31 + 2)
+        );
+
+        // fewer withdrawals a proposal pay more for the shared vbytes
+        MaxWithdrawalCount::<Test>::put(2);
+        assert_ok!(XGatewayBitcoin::set_network_fee_rate(Origin::root(), 20));
+        assert_eq!(
+            XGatewayBitcoin::withdrawal_network_fee(FLAGGED_ADDR),
+            Ok(BtcWithdrawalNetworkFee {
+                fee_rate: 20,
+                batch_size: 2,
+                vbytes: 34 + 77,
+                fee: 2220,
+            })
+        );
+
+        assert_eq!(
+            XGatewayBitcoin::withdrawal_network_fee(b"invalid"),
+            Err(XGatewayBitcoinErr::InvalidAddr.into())
+        );
+    });
+}
+
+#[test]
+fn pallet_docs_should_cover_all_variants() {
+    use xpallet_support::docs::undocumented_variants;
+
+    assert_eq!(crate::PALLET_DOCS.name, "XGatewayBitcoin");
+    let (missing, unknown) = undocumented_variants::<crate::Event<Test>>(crate::PALLET_DOCS.events);
+    assert!(missing.is_empty(), "undocumented events: {:?}", missing);
+    assert!(unknown.is_empty(), "unknown events: {:?}", unknown);
+    let (missing, unknown) = undocumented_variants::<crate::Error<Test>>(crate::PALLET_DOCS.errors);
+    assert!(missing.is_empty(), "undocumented errors: {:?}", missing);
+    assert!(unknown.is_empty(), "unknown errors: {:?}", unknown);
+}
@@ -1,21 +1,34 @@
 // Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
 
-use frame_support::assert_noop;
+use frame_support::{assert_noop, assert_ok, traits::Hooks};
 use hex_literal::hex;
 use sp_std::convert::TryInto;
 
 use light_bitcoin::{
+    chain::Transaction,
     crypto::dhash160,
     keys::{Address, AddressTypes, Network, Public, Type},
     mast::Mast,
     script::{Builder, Opcode},
+    serialization::{self, SERIALIZE_TRANSACTION_WITNESS},
 };
 
+use xp_gateway_common::ExternalAddress;
+use xp_protocol::X_BTC;
+use xpallet_assets::Chain;
 use xpallet_gateway_common::traits::TrusteeForChain;
+use xpallet_gateway_records::WithdrawalState;
 
 use crate::{
-    mock::{ExtBuilder, Test, XGatewayBitcoin, XGatewayBitcoinErr},
-    trustee::create_multi_address,
+    benchmarking::withdraw_tx,
+    mock::{
+        alice, bob, ExtBuilder, Origin, System, Test, XAssets, XGatewayBitcoin, XGatewayBitcoinErr,
+        XGatewayCommon, XGatewayRecords,
+    },
+    trustee::{create_multi_address, current_trustee_session},
+    tx::track_cold_utxos,
+    types::{BtcInputScript, BtcWithdrawalProposal, VoteResult},
+    ColdUtxos, Event, WithdrawalProposal,
 };
 
 #[test]
@@ -156,3 +169,464 @@ fn test_create_taproot_address() {
         )
     })
 }
+
+/// Applies for the withdrawal paid by the proposal `withdraw_tx`, returns the raw tx.
+fn prepare_withdrawal() -> (Transaction, Vec<u8>) {
+    let (tx, _, _) = withdraw_tx();
+    let tx_raw = serialization::serialize_with_flags(&tx, SERIALIZE_TRANSACTION_WITNESS).into();
+
+    assert_ok!(XGatewayRecords::deposit(&alice(), X_BTC, 1_000_000_000));
+    assert_ok!(XGatewayRecords::withdraw(
+        &alice(),
+        X_BTC,
+        50000,
+        b"tb1pexff2s7l58sthpyfrtx500ax234stcnt0gz2lr4kwe0ue95a2e0srxsc68".to_vec(),
+        b"".to_vec().into()
+    ));
+    XGatewayRecords::withdrawal_state_insert(0, WithdrawalState::Applying);
+    (tx, tx_raw)
+}
+
+#[test]
+fn test_proposal_spends_hot_utxos_by_default() {
+    ExtBuilder::default().build_and_execute(|| {
+        let (_, tx_raw) = prepare_withdrawal();
+        assert_ok!(XGatewayBitcoin::create_taproot_withdraw_tx(
+            Origin::signed(alice()),
+            vec![0],
+            tx_raw
+        ));
+        assert_eq!(
+            XGatewayBitcoin::proposal_input_scripts(),
+            vec![BtcInputScript::Hot]
+        );
+        assert!(XGatewayBitcoin::cold_spend_authorization().is_none());
+    })
+}
+
+#[test]
+fn test_proposal_with_typed_withdrawal_address() {
+    ExtBuilder::default().build_and_execute(|| {
+        let (tx, _, _) = withdraw_tx();
+        let tx_raw = serialization::serialize_with_flags(&tx, SERIALIZE_TRANSACTION_WITNESS).into();
+        let addr = b"tb1pexff2s7l58sthpyfrtx500ax234stcnt0gz2lr4kwe0ue95a2e0srxsc68".to_vec();
+        let address = XGatewayBitcoin::verify_btc_address(&addr).unwrap();
+
+        assert_ok!(XGatewayRecords::deposit(&alice(), X_BTC, 1_000_000_000));
+        assert_ok!(XGatewayRecords::withdraw_to_address(
+            &alice(),
+            X_BTC,
+            50000,
+            addr,
+            ExternalAddress::Btc(address),
+            b"".to_vec().into()
+        ));
+        assert_eq!(
+            XGatewayRecords::withdrawal_address(0),
+            Some(ExternalAddress::Btc(address))
+        );
+        assert_eq!(XGatewayBitcoin::withdrawal_btc_address(0), Ok(address));
+
+        assert_ok!(XGatewayBitcoin::create_taproot_withdraw_tx(
+            Origin::signed(alice()),
+            vec![0],
+            tx_raw
+        ));
+        assert_eq!(
+            XGatewayRecords::state_of(0),
+            Some(WithdrawalState::Processing)
+        );
+    })
+}
+
+#[test]
+fn test_invalid_legacy_withdrawal_address_is_rejected_alone() {
+    ExtBuilder::default().build_and_execute(|| {
+        // The legacy withdrawal is read with its raw address bytes.
+        let (_, tx_raw) = prepare_withdrawal();
+        assert_eq!(
+            XGatewayRecords::withdrawal_address(0),
+            Some(ExternalAddress::Named(
+                b"tb1pexff2s7l58sthpyfrtx500ax234stcnt0gz2lr4kwe0ue95a2e0srxsc68".to_vec()
+            ))
+        );
+
+        assert_ok!(XGatewayRecords::withdraw(
+            &alice(),
+            X_BTC,
+            50000,
+            b"not a bitcoin address".to_vec(),
+            b"".to_vec().into()
+        ));
+        assert!(XGatewayBitcoin::withdrawal_btc_address(1).is_err());
+        let usable = XAssets::usable_balance(&alice(), &X_BTC);
+
+        // The invalid one is canceled, the rest of the proposal goes on.
+        assert_ok!(XGatewayBitcoin::create_taproot_withdraw_tx(
+            Origin::signed(alice()),
+            vec![0, 1],
+            tx_raw
+        ));
+        assert_eq!(
+            XGatewayRecords::state_of(0),
+            Some(WithdrawalState::Processing)
+        );
+        assert_eq!(
+            XGatewayBitcoin::withdrawal_proposal()
+                .unwrap()
+                .withdrawal_id_list,
+            vec![0]
+        );
+        assert!(XGatewayRecords::pending_withdrawals(1).is_none());
+        assert!(XGatewayRecords::withdrawal_address(1).is_none());
+        assert_eq!(XAssets::usable_balance(&alice(), &X_BTC), usable + 50000);
+        System::assert_has_event(crate::mock::Event::XGatewayBitcoin(
+            Event::WithdrawalAddressRejected(1),
+        ));
+    })
+}
+
+/// Appends an input spending a cold UTXO of 100_000 satoshis to the proposal `tx`.
+fn with_cold_input(tx: &Transaction) -> (Transaction, Vec<u8>) {
+    let mut tx = tx.clone();
+    let mut cold_input = tx.inputs[0].clone();
+    cold_input.previous_output.index += 1;
+    let outpoint = &cold_input.previous_output;
+    ColdUtxos::<Test>::insert((outpoint.txid, outpoint.index), 100_000);
+    tx.inputs.push(cold_input);
+    let tx_raw = serialization::serialize_with_flags(&tx, SERIALIZE_TRANSACTION_WITNESS).into();
+    (tx, tx_raw)
+}
+
+#[test]
+fn test_proposal_without_hot_input_should_fail() {
+    ExtBuilder::default().build_and_execute(|| {
+        let (tx, tx_raw) = prepare_withdrawal();
+        let outpoint = &tx.inputs()[0].previous_output;
+        ColdUtxos::<Test>::insert((outpoint.txid, outpoint.index), 100_000);
+
+        assert_noop!(
+            XGatewayBitcoin::create_taproot_withdraw_tx(Origin::signed(alice()), vec![0], tx_raw),
+            XGatewayBitcoinErr::ProposalWithoutHotInput
+        );
+    })
+}
+
+#[test]
+fn test_removed_proposal_should_refund_cold_spend() {
+    ExtBuilder::default().build_and_execute(|| {
+        let multisig = XGatewayCommon::trustee_multisig_addr(Chain::Bitcoin).unwrap();
+        let (tx, _) = prepare_withdrawal();
+        let (_, tx_raw) = with_cold_input(&tx);
+
+        assert_ok!(XGatewayBitcoin::authorize_cold_spend(
+            Origin::signed(multisig),
+            150_000,
+            10
+        ));
+        assert_ok!(XGatewayBitcoin::create_taproot_withdraw_tx(
+            Origin::signed(alice()),
+            vec![0],
+            tx_raw
+        ));
+        assert_eq!(
+            XGatewayBitcoin::cold_spend_authorization()
+                .unwrap()
+                .used_value,
+            100_000
+        );
+
+        assert_ok!(XGatewayBitcoin::remove_proposal(Origin::root()));
+        assert_eq!(
+            XGatewayBitcoin::cold_spend_authorization()
+                .unwrap()
+                .used_value,
+            0
+        );
+        System::assert_has_event(crate::mock::Event::XGatewayBitcoin(
+            Event::ColdSpendRefunded(100_000, 150_000),
+        ));
+    })
+}
+
+#[test]
+fn test_cold_spend_authorization() {
+    ExtBuilder::default().build_and_execute(|| {
+        let multisig = XGatewayCommon::trustee_multisig_addr(Chain::Bitcoin).unwrap();
+        let (tx, _) = prepare_withdrawal();
+        let (tx, tx_raw) = with_cold_input(&tx);
+        let outpoint = &tx.inputs()[1].previous_output;
+
+        // The cold UTXOs can't be spent without the authorization.
+        assert_noop!(
+            XGatewayBitcoin::create_taproot_withdraw_tx(
+                Origin::signed(alice()),
+                vec![0],
+                tx_raw.clone()
+            ),
+            XGatewayBitcoinErr::ColdSpendNotAuthorized
+        );
+
+        assert_noop!(
+            XGatewayBitcoin::authorize_cold_spend(Origin::signed(alice()), 100_000, 10),
+            XGatewayBitcoinErr::NotTrusteeMultisig
+        );
+        assert_noop!(
+            XGatewayBitcoin::authorize_cold_spend(Origin::signed(multisig.clone()), 100_000, 1),
+            XGatewayBitcoinErr::InvalidColdSpendExpiry
+        );
+
+        assert_ok!(XGatewayBitcoin::authorize_cold_spend(
+            Origin::signed(multisig.clone()),
+            99_999,
+            10
+        ));
+        assert_noop!(
+            XGatewayBitcoin::create_taproot_withdraw_tx(
+                Origin::signed(alice()),
+                vec![0],
+                tx_raw.clone()
+            ),
+            XGatewayBitcoinErr::ColdSpendExceeded
+        );
+
+        assert_ok!(XGatewayBitcoin::authorize_cold_spend(
+            Origin::signed(multisig),
+            150_000,
+            10
+        ));
+        System::assert_last_event(crate::mock::Event::XGatewayBitcoin(
+            Event::ColdSpendAuthorized(150_000, 10),
+        ));
+        assert_ok!(XGatewayBitcoin::create_taproot_withdraw_tx(
+            Origin::signed(alice()),
+            vec![0],
+            tx_raw
+        ));
+        System::assert_has_event(crate::mock::Event::XGatewayBitcoin(Event::ColdSpendUsed(
+            100_000, 50_000,
+        )));
+        assert_eq!(
+            XGatewayBitcoin::cold_spend_authorization()
+                .unwrap()
+                .used_value,
+            100_000
+        );
+
+        // The cold input is signed with the cold redeem script.
+        assert_eq!(
+            XGatewayBitcoin::proposal_input_scripts(),
+            vec![BtcInputScript::Hot, BtcInputScript::Cold]
+        );
+        let session_info = current_trustee_session::<Test>().unwrap();
+        assert_eq!(
+            XGatewayBitcoin::proposal_input_redeem_scripts().unwrap(),
+            vec![
+                session_info.hot_address.redeem_script,
+                session_info.cold_address.redeem_script
+            ]
+        );
+
+        // The spent cold UTXO is forgotten once the proposal tx is relayed.
+        let (_, cold_addr) = crate::trustee::get_current_trustee_address_pair::<Test>().unwrap();
+        track_cold_utxos::<Test>(&tx, &cold_addr, XGatewayBitcoin::network_id());
+        assert!(XGatewayBitcoin::cold_utxo((outpoint.txid, outpoint.index)).is_none());
+
+        XGatewayBitcoin::on_initialize(9);
+        assert!(XGatewayBitcoin::cold_spend_authorization().is_some());
+        XGatewayBitcoin::on_initialize(10);
+        assert!(XGatewayBitcoin::cold_spend_authorization().is_none());
+        System::assert_last_event(crate::mock::Event::XGatewayBitcoin(
+            Event::ColdSpendExpired(100_000),
+        ));
+    })
+}
+
+#[test]
+fn test_withdrawal_tx_info_should_match_outputs_to_applications() {
+    ExtBuilder::default().build_and_execute(|| {
+        let addr = b"tb1pexff2s7l58sthpyfrtx500ax234stcnt0gz2lr4kwe0ue95a2e0srxsc68".to_vec();
+        assert_ok!(XGatewayRecords::deposit(&alice(), X_BTC, 1_000_000_000));
+        assert_ok!(XGatewayRecords::withdraw(
+            &alice(),
+            X_BTC,
+            50000,
+            addr.clone(),
+            b"".to_vec().into()
+        ));
+        assert_ok!(XGatewayRecords::withdraw(
+            &alice(),
+            X_BTC,
+            30000,
+            addr.clone(),
+            b"".to_vec().into()
+        ));
+        assert!(XGatewayBitcoin::get_withdrawal_tx_info().is_none());
+
+        // The tx pays 50000 to the withdrawal address and 40000 back to the hot address,
+        // append an output paying the second application.
+        let (mut tx, _, _) = withdraw_tx();
+        let mut second = tx.outputs[0].clone();
+        second.value = 30000;
+        tx.outputs.push(second);
+        WithdrawalProposal::<Test>::put(BtcWithdrawalProposal::new(
+            VoteResult::Unfinish,
+            vec![1, 0],
+            tx.clone(),
+            vec![(alice(), true), (bob(), false)],
+        ));
+
+        let info = XGatewayBitcoin::get_withdrawal_tx_info().unwrap();
+        assert_eq!(info.sig_state, VoteResult::Unfinish);
+        assert_eq!(info.withdrawal_id_list, vec![1, 0]);
+        assert_eq!(info.tx, Vec::<u8>::from(serialization::serialize(&tx)));
+        assert_eq!(
+            info.redeem_script,
+            current_trustee_session::<Test>()
+                .unwrap()
+                .hot_address
+                .redeem_script
+        );
+        assert_eq!(info.signed_count, 1);
+        assert_eq!(info.signed_trustees, vec![alice()]);
+
+        let mapping = info
+            .outputs
+            .iter()
+            .map(|output| {
+                (
+                    output.output_index,
+                    output.value,
+                    output.withdrawal_id,
+                    output.is_change,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            mapping,
+            vec![
+                (0, 50000, Some(0), false),
+                (1, 40000, None, true),
+                (2, 30000, Some(1), false),
+            ]
+        );
+        assert_eq!(info.outputs[0].address, Some(addr.clone()));
+        assert_eq!(info.outputs[2].address, Some(addr));
+        assert_ne!(info.outputs[1].address, info.outputs[0].address);
+    })
+}
+
+/// Applies for a withdrawal of `value` at block `n`.
+fn apply_withdrawal_at(n: u64, value: u64) {
+    System::set_block_number(n);
+    assert_ok!(XGatewayRecords::withdraw(
+        &alice(),
+        X_BTC,
+        value,
+        b"tb1pexff2s7l58sthpyfrtx500ax234stcnt0gz2lr4kwe0ue95a2e0srxsc68".to_vec(),
+        b"".to_vec().into()
+    ));
+}
+
+fn proposal_required_events() -> Vec<(u32, u64)> {
+    System::events()
+        .into_iter()
+        .filter_map(|record| match record.event {
+            crate::mock::Event::XGatewayBitcoin(Event::WithdrawalProposalRequired(
+                count,
+                total_value,
+            )) => Some((count, total_value)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_withdrawal_queue_depth_should_require_proposal() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XGatewayRecords::deposit(&alice(), X_BTC, 1_000_000_000));
+        assert_noop!(
+            XGatewayBitcoin::set_withdrawal_queue_thresholds(Origin::signed(alice()), 2, 0),
+            sp_runtime::DispatchError::BadOrigin
+        );
+
+        // Disabled by default.
+        apply_withdrawal_at(1, 10000);
+        apply_withdrawal_at(1, 20000);
+        apply_withdrawal_at(1, 30000);
+        XGatewayBitcoin::on_initialize(10);
+        assert!(proposal_required_events().is_empty());
+
+        assert_ok!(XGatewayBitcoin::set_withdrawal_queue_thresholds(
+            Origin::root(),
+            3,
+            0
+        ));
+        XGatewayBitcoin::on_initialize(20);
+        assert!(proposal_required_events().is_empty());
+
+        // The queue is only checked once every `WITHDRAWAL_QUEUE_CHECK_INTERVAL` blocks.
+        apply_withdrawal_at(21, 40000);
+        XGatewayBitcoin::on_initialize(21);
+        assert!(proposal_required_events().is_empty());
+        XGatewayBitcoin::on_initialize(30);
+        assert_eq!(proposal_required_events(), vec![(4, 100000)]);
+    })
+}
+
+#[test]
+fn test_withdrawal_queue_age_should_require_proposal() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XGatewayRecords::deposit(&alice(), X_BTC, 1_000_000_000));
+        assert_ok!(XGatewayBitcoin::set_withdrawal_queue_thresholds(
+            Origin::root(),
+            0,
+            10
+        ));
+
+        apply_withdrawal_at(1, 50000);
+        XGatewayBitcoin::on_initialize(10);
+        assert!(proposal_required_events().is_empty());
+
+        XGatewayBitcoin::on_initialize(20);
+        assert_eq!(proposal_required_events(), vec![(1, 50000)]);
+    })
+}
+
+#[test]
+fn test_withdrawal_queue_alert_should_back_off() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XGatewayRecords::deposit(&alice(), X_BTC, 1_000_000_000));
+        assert_ok!(XGatewayBitcoin::set_withdrawal_queue_thresholds(
+            Origin::root(),
+            0,
+            10
+        ));
+        apply_withdrawal_at(1, 50000);
+
+        XGatewayBitcoin::on_initialize(20);
+        assert_eq!(proposal_required_events().len(), 1);
+        let backoff = 20 + u64::from(crate::types::PROPOSAL_ALERT_BACKOFF);
+        assert_eq!(XGatewayBitcoin::proposal_alert_backoff(), backoff);
+
+        // No alert is raised again during the backoff.
+        XGatewayBitcoin::on_initialize(30);
+        XGatewayBitcoin::on_initialize(backoff - 10);
+        assert_eq!(proposal_required_events().len(), 1);
+
+        // Nor while a proposal is in progress.
+        let (tx, _, _) = withdraw_tx();
+        WithdrawalProposal::<Test>::put(BtcWithdrawalProposal::new(
+            VoteResult::Finish,
+            vec![0],
+            tx,
+            vec![],
+        ));
+        XGatewayBitcoin::on_initialize(backoff);
+        assert_eq!(proposal_required_events().len(), 1);
+
+        WithdrawalProposal::<Test>::kill();
+        XGatewayBitcoin::on_initialize(backoff);
+        assert_eq!(proposal_required_events(), vec![(1, 50000), (1, 50000)]);
+    })
+}
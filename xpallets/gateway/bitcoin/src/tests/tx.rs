@@ -8,22 +8,27 @@ use sp_core::{
     crypto::{set_default_ss58_version, Ss58AddressFormatRegistry},
     H160,
 };
+use sp_runtime::traits::BlakeTwo256;
 
 use light_bitcoin::{
-    chain::Transaction,
+    chain::{OutPoint, Transaction},
     keys::{Address, Network},
     merkle::PartialMerkleTree,
     serialization::{self, Reader},
 };
 
-use xp_gateway_bitcoin::{AccountExtractor, BtcTxMetaType, BtcTxType, BtcTxTypeDetector};
+use xp_gateway_bitcoin::{
+    AccountExtractor, BtcTxEvidence, BtcTxMetaType, BtcTxType, BtcTxTypeDetector, OpReturnAccount,
+    OpReturnEvidence, TrusteeAddrKind,
+};
+use xpallet_assets::Chain;
 
 use crate::{
     mock::*,
     tx::process_tx,
     types::{
-        BtcDepositCache, BtcRelayedTxInfo, BtcTxResult, BtcTxState, BtcWithdrawalProposal,
-        VoteResult,
+        BtcDeferredDeposit, BtcDepositCache, BtcHeaderIndex, BtcImmatureDeposit, BtcRelayedTxInfo,
+        BtcTxInfo, BtcTxResult, BtcTxState, BtcWithdrawalProposal, VoteResult,
     },
     Config, WithdrawalProposal,
 };
@@ -89,6 +94,92 @@ fn mock_detect_transaction_type<T: Config>(
     )
 }
 
+fn mock_collect_evidence<T: Config>(
+    tx: &Transaction,
+    prev_tx: Option<&Transaction>,
+) -> BtcTxEvidence {
+    let btc_tx_detector = BtcTxTypeDetector::new(Network::Mainnet, 0);
+    let current_trustee_pair = (
+        DEPOSIT_HOT_ADDR.parse::<Address>().unwrap(),
+        DEPOSIT_COLD_ADDR.parse::<Address>().unwrap(),
+    );
+    btc_tx_detector
+        .detect_transaction_type_with_evidence::<T::AccountId, _>(
+            tx,
+            prev_tx,
+            |script| T::AccountExtractor::extract_account(script),
+            current_trustee_pair,
+            None,
+        )
+        .1
+}
+
+#[test]
+fn test_collect_tx_evidence() {
+    set_default_ss58_version(Ss58AddressFormatRegistry::ChainxAccount.into());
+
+    // deposit with op return, the change is sent back to the depositor
+    assert_eq!(
+        mock_collect_evidence::<Test>(&deposit_taproot2, None),
+        BtcTxEvidence {
+            input_addr_resolved: false,
+            input_trustee: None,
+            hot_outputs: 1,
+            cold_outputs: 0,
+            other_outputs: 1,
+            op_return: OpReturnEvidence::Valid,
+        }
+    );
+    // deposit without op return, but the input address is resolved
+    assert_eq!(
+        mock_collect_evidence::<Test>(&deposit_taproot1, Some(&deposit_taproot1_prev)),
+        BtcTxEvidence {
+            input_addr_resolved: true,
+            input_trustee: None,
+            hot_outputs: 1,
+            cold_outputs: 0,
+            other_outputs: 1,
+            op_return: OpReturnEvidence::Absent,
+        }
+    );
+    // withdrawal spends the hot trustee utxo
+    assert_eq!(
+        mock_collect_evidence::<Test>(&withdraw_taproot1, Some(&withdraw_taproot1_prev)),
+        BtcTxEvidence {
+            input_addr_resolved: true,
+            input_trustee: Some(TrusteeAddrKind::Hot),
+            hot_outputs: 1,
+            cold_outputs: 0,
+            other_outputs: 1,
+            op_return: OpReturnEvidence::Absent,
+        }
+    );
+    // hot to cold, all outputs are trustee addresses
+    assert_eq!(
+        mock_collect_evidence::<Test>(&hot_to_cold, Some(&hot_to_cold_prev)),
+        BtcTxEvidence {
+            input_addr_resolved: true,
+            input_trustee: Some(TrusteeAddrKind::Hot),
+            hot_outputs: 1,
+            cold_outputs: 1,
+            other_outputs: 0,
+            op_return: OpReturnEvidence::Absent,
+        }
+    );
+    // unrelated tx, nothing is sent to the trustee addresses
+    assert_eq!(
+        mock_collect_evidence::<Test>(&deposit_taproot1_prev, None),
+        BtcTxEvidence {
+            input_addr_resolved: false,
+            input_trustee: None,
+            hot_outputs: 0,
+            cold_outputs: 0,
+            other_outputs: 2,
+            op_return: OpReturnEvidence::Absent,
+        }
+    );
+}
+
 #[test]
 fn test_detect_tx_type() {
     set_default_ss58_version(Ss58AddressFormatRegistry::ChainxAccount.into());
@@ -191,6 +282,21 @@ fn test_detect_tx_type() {
 }
 
 fn mock_process_tx<T: Config>(tx: Transaction, prev_tx: Option<Transaction>) -> BtcTxState {
+    mock_process_tx_with_evidence::<T>(tx, prev_tx).0
+}
+
+fn mock_process_tx_with_evidence<T: Config>(
+    tx: Transaction,
+    prev_tx: Option<Transaction>,
+) -> (BtcTxState, BtcTxEvidence) {
+    mock_process_tx_in_block::<T>(tx, prev_tx, Default::default())
+}
+
+fn mock_process_tx_in_block<T: Config>(
+    tx: Transaction,
+    prev_tx: Option<Transaction>,
+    block: BtcHeaderIndex,
+) -> (BtcTxState, BtcTxEvidence) {
     let network = Network::Mainnet;
     let min_deposit = 0;
     let current_trustee_pair = (
@@ -201,6 +307,7 @@ fn mock_process_tx<T: Config>(tx: Transaction, prev_tx: Option<Transaction>) ->
     process_tx::<T>(
         tx,
         prev_tx,
+        block,
         network,
         min_deposit,
         current_trustee_pair,
@@ -306,35 +413,49 @@ fn test_process_tx() {
         // hot and cold
         let r = mock_process_tx::<Test>(hot_to_cold.clone(), None);
         assert_eq!(r.result, BtcTxResult::Failure);
-        let r = mock_process_tx::<Test>(hot_to_cold.clone(), Some(hot_to_cold_prev.clone()));
+        let (r, evidence) = mock_process_tx_with_evidence::<Test>(
+            hot_to_cold.clone(),
+            Some(hot_to_cold_prev.clone()),
+        );
         assert_eq!(r.tx_type, BtcTxType::HotAndCold);
         assert_eq!(r.result, BtcTxResult::Success);
+        assert_eq!(evidence.input_trustee, Some(TrusteeAddrKind::Hot));
     })
 }
 
-#[test]
-fn test_push_tx_call() {
-    set_default_ss58_version(Ss58AddressFormatRegistry::ChainxAccount.into());
+/// Returns the normal deposit tx in block 63299 and its relayed info.
+fn normal_deposit_with_info() -> (Transaction, BtcRelayedTxInfo) {
     // https://blockchain.info/rawtx/f1a9161a045a01db7ae02b8c0531e2fe2e9740efe30afe6d84a12e3cac251344?format=hex
     let normal_deposit: Transaction = "02000000000101aeee49e0bbf7a36f78ea4321b5c8bae0b8c72bdf2c024d2484b137fa7d0f8e1f01000000000000000003a0860100000000002251209a9ea267884f5549c206b2aec2bd56d98730f90532ea7f7154d4d4f923b7e3bb0000000000000000326a3035516a706f3772516e7751657479736167477a6334526a376f737758534c6d4d7141754332416255364c464646476a38801a060000000000225120c9929543dfa1e0bb84891acd47bfa6546b05e26b7a04af8eb6765fcc969d565f01409e325889515ed47099fdd7098e6fafdc880b21456d3f368457de923f4229286e34cef68816348a0581ae5885ede248a35ac4b09da61a7b9b90f34c200872d2e300000000".parse().unwrap();
-    let tx = serialization::serialize(&normal_deposit);
     let headers = generate_blocks_63290_63310();
     let block_hash = headers[&63299].hash();
 
     let raw_proof = hex::decode("0a000000050a59b195a68a29037580798ca0414941eb46eaf7607db2d0da1ff89e9570ce455fea22ec1a3e3e7e1167fa220cc8376225f07bd20aa194e7f3c4ac68c7375d8e0a35e47541de7d0aa7312dabcf3bc9f06603e832427b8e4fe9a97a309f8cd7141687d11a3fd8f21e2105a52a3c36a17ea870e326ecddb23221d4cc0398b6c44bdcce3f191919a31f4cfaca5a786cc8315db76683ad6b8008f2ed9b348df76a0d022f00").unwrap();
     let proof: PartialMerkleTree = serialization::deserialize(Reader::new(&raw_proof)).unwrap();
 
+    (
+        normal_deposit,
+        BtcRelayedTxInfo {
+            block_hash,
+            merkle_proof: proof,
+        },
+    )
+}
+
+#[test]
+fn test_push_tx_call() {
+    set_default_ss58_version(Ss58AddressFormatRegistry::ChainxAccount.into());
+    let (normal_deposit, info) = normal_deposit_with_info();
+    let tx = serialization::serialize(&normal_deposit);
+    let info = info.encode();
+    let headers = generate_blocks_63290_63310();
+
     ExtBuilder::default().build_and_execute(|| {
         let confirmed = XGatewayBitcoin::confirmation_number();
         // insert headers
         for i in 63291..=63299 + confirmed {
             assert_ok!(XGatewayBitcoin::apply_push_header(headers[&i]));
         }
-        let info = BtcRelayedTxInfo {
-            block_hash,
-            merkle_proof: proof,
-        }
-        .encode();
 
         assert_ok!(XGatewayBitcoin::push_transaction(
             frame_system::RawOrigin::Signed(alice()).into(),
@@ -342,6 +463,22 @@ fn test_push_tx_call() {
             info.clone(),
             None,
         ));
+        assert_eq!(
+            XGatewayBitcoin::get_btc_tx_info(normal_deposit.hash()),
+            Some(BtcTxInfo {
+                tx_type: BtcTxType::Deposit,
+                result: BtcTxResult::Success,
+                evidence: Some(BtcTxEvidence {
+                    input_addr_resolved: false,
+                    input_trustee: None,
+                    hot_outputs: 1,
+                    cold_outputs: 0,
+                    other_outputs: 1,
+                    op_return: OpReturnEvidence::Valid,
+                }),
+                mature_at: None,
+            })
+        );
 
         // reject replay
         assert_noop!(
@@ -355,3 +492,316 @@ fn test_push_tx_call() {
         );
     });
 }
+
+#[test]
+fn test_deferred_deposit() {
+    set_default_ss58_version(Ss58AddressFormatRegistry::ChainxAccount.into());
+    let (normal_deposit, info) = normal_deposit_with_info();
+    let tx = serialization::serialize(&normal_deposit);
+    let info = info.encode();
+    let headers = generate_blocks_63290_63310();
+
+    ExtBuilder::default().build_and_execute(|| {
+        let confirmed = XGatewayBitcoin::confirmation_number();
+        for i in 63291..=63299 + confirmed {
+            assert_ok!(XGatewayBitcoin::apply_push_header(headers[&i]));
+        }
+
+        assert_ok!(XGatewayRecords::set_deposits_enabled(
+            frame_system::RawOrigin::Root.into(),
+            Chain::Bitcoin,
+            false
+        ));
+
+        let balance = XAssets::usable_balance(&op_account, &X_BTC);
+        assert_ok!(XGatewayBitcoin::push_transaction(
+            frame_system::RawOrigin::Signed(alice()).into(),
+            tx.clone().into(),
+            info.clone(),
+            None,
+        ));
+        // the deposit is not credited, but deferred
+        assert_eq!(
+            XGatewayBitcoin::tx_state(normal_deposit.hash()).map(|state| state.result),
+            Some(BtcTxResult::Success)
+        );
+        assert_eq!(XAssets::usable_balance(&op_account, &X_BTC), balance);
+        assert_eq!(
+            XGatewayBitcoin::deferred_deposits(),
+            vec![BtcDeferredDeposit {
+                txid: normal_deposit.hash(),
+                input_addr: None,
+                balance: 100000,
+                account: OpReturnAccount::Wasm(op_account.clone()),
+            }]
+        );
+
+        assert_noop!(
+            XGatewayBitcoin::process_deferred_deposits(frame_system::RawOrigin::Root.into(), 10),
+            XGatewayBitcoinErr::DepositsDisabled,
+        );
+
+        assert_ok!(XGatewayRecords::set_deposits_enabled(
+            frame_system::RawOrigin::Root.into(),
+            Chain::Bitcoin,
+            true
+        ));
+        assert_ok!(XGatewayBitcoin::process_deferred_deposits(
+            frame_system::RawOrigin::Root.into(),
+            10
+        ));
+        assert_eq!(
+            XAssets::usable_balance(&op_account, &X_BTC),
+            balance + 100000
+        );
+        assert!(XGatewayBitcoin::deferred_deposits().is_empty());
+
+        // credited exactly once
+        assert_ok!(XGatewayBitcoin::process_deferred_deposits(
+            frame_system::RawOrigin::Root.into(),
+            10
+        ));
+        assert_noop!(
+            XGatewayBitcoin::push_transaction(
+                frame_system::RawOrigin::Signed(alice()).into(),
+                tx.into(),
+                info,
+                None,
+            ),
+            XGatewayBitcoinErr::ReplayedTx,
+        );
+        assert_eq!(
+            XAssets::usable_balance(&op_account, &X_BTC),
+            balance + 100000
+        );
+    });
+}
+
+#[test]
+fn test_binding_while_deposits_disabled() {
+    set_default_ss58_version(Ss58AddressFormatRegistry::ChainxAccount.into());
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XGatewayRecords::set_deposits_enabled(
+            frame_system::RawOrigin::Root.into(),
+            Chain::Bitcoin,
+            false
+        ));
+
+        // the binding from the op return is still processed
+        let balance = XAssets::usable_balance(&op_account, &X_BTC);
+        let r = mock_process_tx::<Test>(
+            deposit_taproot2.clone(),
+            Some(deposit_taproot2_prev.clone()),
+        );
+        assert_eq!(r.result, BtcTxResult::Success);
+        assert!(!XGatewayCommon::bound_addrs(&op_account).is_empty());
+        assert_eq!(XAssets::usable_balance(&op_account, &X_BTC), balance);
+
+        let deferred = XGatewayBitcoin::deferred_deposits();
+        assert_eq!(deferred.len(), 1);
+        assert!(deferred[0].input_addr.is_some());
+
+        // drain the deferred deposits in batches
+        let r = mock_process_tx::<Test>(deposit_taproot2.clone(), None);
+        assert_eq!(r.result, BtcTxResult::Success);
+        assert_eq!(XGatewayBitcoin::deferred_deposits().len(), 2);
+
+        assert_ok!(XGatewayRecords::set_deposits_enabled(
+            frame_system::RawOrigin::Root.into(),
+            Chain::Bitcoin,
+            true
+        ));
+        assert_ok!(XGatewayBitcoin::process_deferred_deposits(
+            frame_system::RawOrigin::Root.into(),
+            1
+        ));
+        assert_eq!(XGatewayBitcoin::deferred_deposits().len(), 1);
+        assert_ok!(XGatewayBitcoin::process_deferred_deposits(
+            frame_system::RawOrigin::Root.into(),
+            1
+        ));
+        assert!(XGatewayBitcoin::deferred_deposits().is_empty());
+
+        let credited = deferred[0].balance + 100000;
+        assert_eq!(
+            XAssets::usable_balance(&op_account, &X_BTC),
+            balance + credited as u128
+        );
+    });
+}
+
+#[test]
+fn test_coinbase_deposit_held_until_mature() {
+    set_default_ss58_version(Ss58AddressFormatRegistry::ChainxAccount.into());
+    let headers = generate_blocks_63290_63310();
+    // the previous tx spends nothing, i.e., a coinbase tx
+    let mut coinbase_prev = deposit_taproot2_prev.clone();
+    coinbase_prev.inputs[0].previous_output = OutPoint::null();
+    assert!(coinbase_prev.is_coinbase());
+
+    ExtBuilder::default().build_and_execute(|| {
+        let confirmed = XGatewayBitcoin::confirmation_number();
+        for i in 63291..=63299 + confirmed {
+            assert_ok!(XGatewayBitcoin::apply_push_header(headers[&i]));
+        }
+        let block = BtcHeaderIndex {
+            hash: headers[&63299].hash(),
+            height: 63299,
+        };
+
+        let balance = XAssets::usable_balance(&op_account, &X_BTC);
+        let (r, _) =
+            mock_process_tx_in_block::<Test>(deposit_taproot2.clone(), Some(coinbase_prev), block);
+        assert_eq!(r.result, BtcTxResult::Success);
+
+        // the deposit is held until the block is 8 blocks deep
+        let mature_at = 63299 + XGatewayBitcoin::coinbase_deposit_confirmations() - 1;
+        assert_eq!(mature_at, 63306);
+        assert_eq!(XAssets::usable_balance(&op_account, &X_BTC), balance);
+        assert_eq!(
+            XGatewayBitcoin::immature_deposits(mature_at),
+            vec![BtcImmatureDeposit {
+                deposit: BtcDeferredDeposit {
+                    txid: deposit_taproot2.hash(),
+                    input_addr: Some(deposit_taproot1_input_account.to_vec()),
+                    balance: 100000,
+                    account: OpReturnAccount::Wasm(op_account.clone()),
+                },
+                block_hash: block.hash,
+                mature_at,
+            }]
+        );
+        System::assert_has_event(Event::XGatewayBitcoin(
+            crate::Event::DepositHeldUntilMature(deposit_taproot2.hash(), mature_at),
+        ));
+
+        for i in 63300 + confirmed..mature_at {
+            assert_ok!(XGatewayBitcoin::apply_push_header(headers[&i]));
+        }
+        assert_eq!(XAssets::usable_balance(&op_account, &X_BTC), balance);
+        assert_eq!(XGatewayBitcoin::immature_deposits(mature_at).len(), 1);
+        assert_eq!(
+            XGatewayBitcoin::immature_deposit_of(deposit_taproot2.hash()),
+            Some((block.hash, mature_at))
+        );
+
+        assert_ok!(XGatewayBitcoin::apply_push_header(headers[&mature_at]));
+        assert_eq!(
+            XAssets::usable_balance(&op_account, &X_BTC),
+            balance + 100000
+        );
+        assert!(XGatewayBitcoin::immature_deposits(mature_at).is_empty());
+        assert!(XGatewayBitcoin::immature_deposit_of(deposit_taproot2.hash()).is_none());
+    });
+}
+
+#[test]
+fn test_pending_coinbase_deposit_held_until_mature() {
+    set_default_ss58_version(Ss58AddressFormatRegistry::ChainxAccount.into());
+    let headers = generate_blocks_63290_63310();
+    let mut coinbase_prev = deposit_taproot1_prev.clone();
+    coinbase_prev.inputs[0].previous_output = OutPoint::null();
+    assert!(coinbase_prev.is_coinbase());
+
+    ExtBuilder::default().build_and_execute(|| {
+        let confirmed = XGatewayBitcoin::confirmation_number();
+        for i in 63291..=63299 + confirmed {
+            assert_ok!(XGatewayBitcoin::apply_push_header(headers[&i]));
+        }
+        let block = BtcHeaderIndex {
+            hash: headers[&63299].hash(),
+            height: 63299,
+        };
+        let mature_at = 63299 + XGatewayBitcoin::coinbase_deposit_confirmations() - 1;
+
+        // no opreturn and the input address is unbound, the deposit goes pending
+        let (r, _) =
+            mock_process_tx_in_block::<Test>(deposit_taproot1.clone(), Some(coinbase_prev), block);
+        assert_eq!(r.result, BtcTxResult::Success);
+        assert_eq!(
+            XGatewayBitcoin::immature_deposit_of(deposit_taproot1.hash()),
+            Some((block.hash, mature_at))
+        );
+
+        // binding the input address holds the pending deposit instead of crediting it
+        let balance = XAssets::usable_balance(&op_account, &X_BTC);
+        let (r, _) = mock_process_tx_in_block::<Test>(
+            deposit_taproot2.clone(),
+            Some(deposit_taproot2_prev.clone()),
+            block,
+        );
+        assert_eq!(r.result, BtcTxResult::Success);
+        assert_eq!(
+            XAssets::usable_balance(&op_account, &X_BTC),
+            balance + 100000
+        );
+        assert!(
+            XGatewayBitcoin::pending_deposits(&deposit_taproot1_input_account.to_vec()).is_empty()
+        );
+        assert_eq!(
+            XGatewayBitcoin::immature_deposits(mature_at),
+            vec![BtcImmatureDeposit {
+                deposit: BtcDeferredDeposit {
+                    txid: deposit_taproot1.hash(),
+                    input_addr: Some(deposit_taproot1_input_account.to_vec()),
+                    balance: 100000,
+                    account: OpReturnAccount::Wasm(op_account.clone()),
+                },
+                block_hash: block.hash,
+                mature_at,
+            }]
+        );
+
+        for i in 63300 + confirmed..=mature_at {
+            assert_ok!(XGatewayBitcoin::apply_push_header(headers[&i]));
+        }
+        assert_eq!(
+            XAssets::usable_balance(&op_account, &X_BTC),
+            balance + 200000
+        );
+        assert!(XGatewayBitcoin::immature_deposit_of(deposit_taproot1.hash()).is_none());
+    });
+}
+
+#[test]
+fn test_normal_deposit_not_held() {
+    set_default_ss58_version(Ss58AddressFormatRegistry::ChainxAccount.into());
+    let headers = generate_blocks_63290_63310();
+
+    ExtBuilder::default().build_and_execute(|| {
+        let confirmed = XGatewayBitcoin::confirmation_number();
+        for i in 63291..=63299 + confirmed {
+            assert_ok!(XGatewayBitcoin::apply_push_header(headers[&i]));
+        }
+        let block = BtcHeaderIndex {
+            hash: headers[&63299].hash(),
+            height: 63299,
+        };
+
+        let balance = XAssets::usable_balance(&op_account, &X_BTC);
+        let (r, _) = mock_process_tx_in_block::<Test>(
+            deposit_taproot2.clone(),
+            Some(deposit_taproot2_prev.clone()),
+            block,
+        );
+        assert_eq!(r.result, BtcTxResult::Success);
+        assert_eq!(
+            XAssets::usable_balance(&op_account, &X_BTC),
+            balance + 100000
+        );
+        assert!(XGatewayBitcoin::immature_deposit_of(deposit_taproot2.hash()).is_none());
+
+        // the deposit event is indexed by the topic of X-BTC
+        let topic = chainx_primitives::asset_event_topic::<BlakeTwo256>(X_BTC);
+        let deposited = System::events()
+            .into_iter()
+            .find(|record| {
+                matches!(
+                    record.event,
+                    Event::XGatewayBitcoin(crate::Event::Deposited(..))
+                )
+            })
+            .unwrap();
+        assert_eq!(deposited.topics, vec![topic]);
+    });
+}
@@ -2,7 +2,11 @@
 extern crate alloc;
 
 use alloc::string::ToString;
-use frame_support::dispatch::{DispatchError, DispatchResult};
+use frame_support::{
+    dispatch::{DispatchError, DispatchResult},
+    ensure,
+    traits::UnixTime,
+};
 use sp_runtime::SaturatedConversion;
 use sp_std::{
     cmp::max,
@@ -27,11 +31,16 @@ use xpallet_gateway_common::{
     types::{ScriptInfo, TrusteeInfoConfig, TrusteeIntentionProps, TrusteeSessionInfo},
     utils::two_thirds_unsafe,
 };
+use xpallet_gateway_records::WithdrawalState;
 
 use crate::{
     log,
-    types::{BtcWithdrawalProposal, VoteResult},
-    Config, Error, Event, Pallet, WithdrawalProposal,
+    types::{
+        BtcColdSpendAuthorization, BtcInputScript, BtcWithdrawalOutput, BtcWithdrawalProposal,
+        VoteResult, PROPOSAL_CADENCE_RING,
+    },
+    ColdSpendAuthorization, ColdUtxos, Config, Error, Event, Pallet, ProposalInputScripts,
+    RecentProposals, WithdrawalProposal,
 };
 
 pub fn current_trustee_session<T: Config>(
@@ -79,6 +88,19 @@ pub fn get_last_trustee_address_pair<T: Config>() -> Result<(Address, Address),
     })
 }
 
+/// Returns the trustee session whose hot address the withdrawal proposal spends.
+///
+/// During a trustee transition the proposal moves the funds out of the last hot
+/// address, otherwise it spends the current one.
+pub fn proposal_trustee_session<T: Config>(
+) -> Result<TrusteeSessionInfo<T::AccountId, T::BlockNumber, BtcTrusteeAddrInfo>, DispatchError> {
+    if T::TrusteeSessionProvider::trustee_transition_state() {
+        T::TrusteeSessionProvider::last_trustee_session()
+    } else {
+        T::TrusteeSessionProvider::current_trustee_session()
+    }
+}
+
 pub fn check_keys<T: Config>(keys: &[Public]) -> DispatchResult {
     let has_duplicate = (1..keys.len()).any(|i| keys[i..].contains(&keys[i - 1]));
     if has_duplicate {
@@ -340,7 +362,22 @@ impl<T: Config> Pallet<T> {
         withdrawal_id_list.sort_unstable();
         withdrawal_id_list.dedup();
 
+        // reject the legacy withdrawals with an invalid address one by one instead of
+        // failing the whole proposal, their outputs can not be in the tx anyway.
+        let (withdrawal_id_list, rejected_id_list): (Vec<u32>, Vec<u32>) =
+            withdrawal_id_list.into_iter().partition(|id| {
+                xpallet_gateway_records::Pallet::<T>::pending_withdrawals(id).is_none()
+                    || Self::withdrawal_btc_address(*id).is_ok()
+            });
+
         check_withdraw_tx::<T>(&tx, &withdrawal_id_list)?;
+        let (input_scripts, cold_value) = Self::classify_proposal_inputs(&tx);
+        // The trustee signatures are recorded from a hot input once the proposal is relayed.
+        ensure!(
+            input_scripts.contains(&BtcInputScript::Hot),
+            Error::<T>::ProposalWithoutHotInput
+        );
+        let cold_spend_authorization = Self::check_cold_spend(cold_value)?;
         log!(
             info,
             "[apply_create_withdraw] Create new withdraw, id_list:{:?}",
@@ -351,6 +388,9 @@ impl<T: Config> Pallet<T> {
             &withdrawal_id_list,
             Chain::Bitcoin,
         )?;
+        for id in rejected_id_list {
+            Self::reject_withdrawal_address(id);
+        }
 
         let proposal = BtcWithdrawalProposal::new(
             VoteResult::Finish,
@@ -370,9 +410,124 @@ impl<T: Config> Pallet<T> {
         ));
 
         WithdrawalProposal::<T>::put(proposal);
+        ProposalInputScripts::<T>::put(input_scripts);
+        Self::record_proposal();
+        if let Some(authorization) = cold_spend_authorization {
+            Self::deposit_event(Event::<T>::ColdSpendUsed(
+                cold_value,
+                authorization.max_value - authorization.used_value,
+            ));
+            ColdSpendAuthorization::<T>::put(authorization);
+        }
 
         Ok(())
     }
+
+    /// Records the current block in the ring of the recent proposals.
+    pub(crate) fn record_proposal() {
+        RecentProposals::<T>::mutate(|recent| {
+            recent.push((
+                frame_system::Pallet::<T>::block_number(),
+                T::UnixTime::now().as_secs(),
+            ));
+            if recent.len() > PROPOSAL_CADENCE_RING {
+                recent.remove(0);
+            }
+        });
+    }
+
+    /// Cancels the withdrawal `id` whose address can not be parsed, unlocking its balance.
+    fn reject_withdrawal_address(id: u32) {
+        match xpallet_gateway_records::Pallet::<T>::set_withdrawal_state_by_root(
+            id,
+            WithdrawalState::RootCancel,
+        ) {
+            Ok(()) => Self::deposit_event(Event::<T>::WithdrawalAddressRejected(id)),
+            Err(err) => log!(
+                error,
+                "[reject_withdrawal_address] Failed to cancel withdrawal {}: {:?}",
+                id,
+                err
+            ),
+        }
+    }
+
+    /// Returns the script each input of the proposal `tx` is spent with, along with
+    /// the total value of the cold UTXOs it spends.
+    fn classify_proposal_inputs(tx: &Transaction) -> (Vec<BtcInputScript>, u64) {
+        let mut cold_value = 0u64;
+        let input_scripts = tx
+            .inputs()
+            .iter()
+            .map(|input| {
+                let outpoint = (input.previous_output.txid, input.previous_output.index);
+                match ColdUtxos::<T>::get(outpoint) {
+                    Some(value) => {
+                        cold_value = cold_value.saturating_add(value);
+                        BtcInputScript::Cold
+                    }
+                    None => BtcInputScript::Hot,
+                }
+            })
+            .collect::<Vec<_>>();
+        (input_scripts, cold_value)
+    }
+
+    /// Ensures spending `cold_value` of the cold UTXOs is within the authorization of the
+    /// trustees, returns the authorization with the value consumed.
+    fn check_cold_spend(
+        cold_value: u64,
+    ) -> Result<Option<BtcColdSpendAuthorization<T::BlockNumber>>, DispatchError> {
+        if cold_value == 0 {
+            return Ok(None);
+        }
+
+        let authorization = Self::cold_spend_authorization()
+            .filter(|authorization| {
+                frame_system::Pallet::<T>::block_number() < authorization.expiry
+            })
+            .ok_or(Error::<T>::ColdSpendNotAuthorized)?;
+        let used_value = authorization.used_value.saturating_add(cold_value);
+        ensure!(
+            used_value <= authorization.max_value,
+            Error::<T>::ColdSpendExceeded
+        );
+        Ok(Some(BtcColdSpendAuthorization {
+            used_value,
+            ..authorization
+        }))
+    }
+
+    /// Gives the value of the cold UTXOs spent by the removed proposal `tx` back to the
+    /// cold spend authorization, if it is still in effect.
+    pub(crate) fn refund_cold_spend(tx: &Transaction) {
+        let (_, cold_value) = Self::classify_proposal_inputs(tx);
+        if cold_value == 0 {
+            return;
+        }
+        ColdSpendAuthorization::<T>::mutate(|authorization| {
+            if let Some(authorization) = authorization {
+                authorization.used_value = authorization.used_value.saturating_sub(cold_value);
+                Self::deposit_event(Event::<T>::ColdSpendRefunded(
+                    cold_value,
+                    authorization.max_value - authorization.used_value,
+                ));
+            }
+        });
+    }
+
+    /// Returns the redeem script of each input of the current withdrawal proposal,
+    /// which the trustees are expected to sign with.
+    pub fn proposal_input_redeem_scripts() -> Result<Vec<Vec<u8>>, DispatchError> {
+        let session_info = current_trustee_session::<T>()?;
+        Ok(Self::proposal_input_scripts()
+            .into_iter()
+            .map(|script| match script {
+                BtcInputScript::Hot => session_info.hot_address.redeem_script.clone(),
+                BtcInputScript::Cold => session_info.cold_address.redeem_script.clone(),
+            })
+            .collect())
+    }
 }
 
 /// Get the required number of signatures
@@ -458,9 +613,7 @@ fn check_withdraw_tx_impl<T: Config>(
     for withdraw_index in withdrawal_id_list.iter() {
         let record = xpallet_gateway_records::Pallet::<T>::pending_withdrawals(withdraw_index)
             .ok_or(Error::<T>::NoWithdrawalRecord)?;
-        // record.addr() is base58
-        // verify btc address would conveRelayedTx a base58 addr to Address
-        let addr: Address = Pallet::<T>::verify_btc_address(record.addr())?;
+        let addr: Address = Pallet::<T>::withdrawal_btc_address(*withdraw_index)?;
 
         appl_withdrawal_list.push((addr, record.balance().saturated_into::<u64>()));
     }
@@ -523,3 +676,51 @@ fn check_withdraw_tx_impl<T: Config>(
 
     Ok(())
 }
+
+/// Decodes the outputs of the withdrawal tx, matching them against the applications
+/// of `withdrawal_id_list` in the same way as `check_withdraw_tx`.
+pub fn decode_withdraw_tx_outputs<T: Config>(
+    tx: &Transaction,
+    withdrawal_id_list: &[u32],
+) -> Vec<BtcWithdrawalOutput> {
+    let mut appl_withdrawal_list = withdrawal_id_list
+        .iter()
+        .filter_map(|id| {
+            let record = xpallet_gateway_records::Pallet::<T>::pending_withdrawals(id)?;
+            let addr = Pallet::<T>::withdrawal_btc_address(*id).ok()?;
+            Some((*id, addr, record.balance().saturated_into::<u64>()))
+        })
+        .collect::<Vec<_>>();
+    let hot_trustee_address = get_hot_trustee_address::<T>().ok();
+    let btc_withdrawal_fee = Pallet::<T>::btc_withdrawal_fee();
+    let btc_network = Pallet::<T>::network_id();
+
+    tx.outputs
+        .iter()
+        .enumerate()
+        .map(|(index, output)| {
+            let addr = extract_output_addr(output, btc_network);
+            let is_change = match (&addr, &hot_trustee_address) {
+                (Some(addr), Some(hot)) => addr.hash == hot.hash,
+                _ => false,
+            };
+            let withdrawal_id = match addr {
+                Some(ref addr) if !is_change => appl_withdrawal_list
+                    .iter()
+                    .position(|(_, appl_addr, balance)| {
+                        appl_addr.hash == addr.hash
+                            && *balance == output.value.saturating_add(btc_withdrawal_fee)
+                    })
+                    .map(|pos| appl_withdrawal_list.remove(pos).0),
+                _ => None,
+            };
+            BtcWithdrawalOutput {
+                output_index: index as u32,
+                address: addr.map(|addr| addr.to_string().into_bytes()),
+                value: output.value,
+                withdrawal_id,
+                is_change,
+            }
+        })
+        .collect()
+}
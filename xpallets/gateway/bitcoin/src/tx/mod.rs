@@ -8,8 +8,12 @@ pub mod validator;
 use frame_support::{
     dispatch::DispatchResult,
     log::{self, debug, error, info, warn},
+    weights::DispatchClass,
+};
+use sp_runtime::{
+    traits::{Saturating, Zero},
+    SaturatedConversion,
 };
-use sp_runtime::{traits::Zero, SaturatedConversion};
 use sp_std::prelude::*;
 
 use light_bitcoin::{
@@ -20,7 +24,10 @@ use light_bitcoin::{
 use sp_core::H160;
 
 use chainx_primitives::AssetId;
-use xp_gateway_bitcoin::{BtcDepositInfo, BtcTxMetaType, BtcTxTypeDetector, OpReturnAccount};
+use xp_gateway_bitcoin::{
+    extract_addr_from_transaction, extract_output_addr, BtcDepositInfo, BtcTxEvidence,
+    BtcTxMetaType, BtcTxTypeDetector, OpReturnAccount,
+};
 use xp_gateway_common::{AccountExtractor, DstChain};
 use xpallet_assets::ChainT;
 use xpallet_gateway_common::traits::{AddressBinding, ReferralBinding, TrusteeInfoUpdate};
@@ -28,30 +35,69 @@ use xpallet_support::try_str;
 
 pub use self::validator::validate_transaction;
 use crate::{
-    types::{AccountInfo, BtcAddress, BtcDepositCache, BtcTxResult, BtcTxState},
-    BalanceOf, Config, Event, Pallet, PendingDeposits, WithdrawalProposal,
+    types::{
+        AccountInfo, BtcAddress, BtcDeferredDeposit, BtcDepositCache, BtcHeaderIndex,
+        BtcImmatureDeposit, BtcInputScript, BtcTxDisplay, BtcTxResult, BtcTxSkipReason, BtcTxState,
+    },
+    weights::WeightInfo,
+    BalanceOf, ColdUtxos, Config, Event, ImmatureDepositOf, ImmatureDeposits, Pallet,
+    PendingDeposits, ProposalInputScripts, TxEvidence, TxState, WithdrawalProposal,
 };
 
+/// Returns the reason to skip the relayed tx if its inputs can't be resolved with `prev_tx`.
+///
+/// The skipped tx is not recorded, so that it could be relayed again.
+pub fn check_skip_reason(
+    tx: &Transaction,
+    prev_tx: Option<&Transaction>,
+    network: Network,
+) -> Option<BtcTxSkipReason> {
+    let input = match tx.inputs.first() {
+        Some(input) => input,
+        None => return Some(BtcTxSkipReason::NoInputs),
+    };
+    let prev_tx = prev_tx?;
+    let index = input.previous_output.index as usize;
+    if index >= prev_tx.outputs.len() {
+        return Some(BtcTxSkipReason::PrevOutputOutOfRange);
+    }
+    if extract_addr_from_transaction(prev_tx, index, network).is_none() {
+        return Some(BtcTxSkipReason::NoPrevOutputAddress);
+    }
+    None
+}
+
 pub fn process_tx<T: Config>(
     tx: Transaction,
     prev_tx: Option<Transaction>,
+    block: BtcHeaderIndex,
     network: Network,
     min_deposit: u64,
     current_trustee_pair: (Address, Address),
     last_trustee_pair: Option<(Address, Address)>,
-) -> BtcTxState {
+) -> (BtcTxState, BtcTxEvidence) {
     let btc_tx_detector = BtcTxTypeDetector::new(network, min_deposit);
-    let meta_type = btc_tx_detector.detect_transaction_type::<T::AccountId, _>(
-        &tx,
-        prev_tx.as_ref(),
-        T::AccountExtractor::extract_account,
-        current_trustee_pair,
-        last_trustee_pair,
+    let (meta_type, evidence) = btc_tx_detector
+        .detect_transaction_type_with_evidence::<T::AccountId, _>(
+            &tx,
+            prev_tx.as_ref(),
+            T::AccountExtractor::extract_account,
+            current_trustee_pair,
+            last_trustee_pair,
+        );
+    debug!(
+        target: "runtime::bitcoin",
+        "[process_tx] {} evidence:{:?}",
+        BtcTxDisplay::from(&tx),
+        evidence
     );
 
     let tx_type = meta_type.ref_into();
     let result = match meta_type {
-        BtcTxMetaType::<_>::Deposit(deposit_info) => deposit::<T>(tx.hash(), deposit_info),
+        BtcTxMetaType::<_>::Deposit(deposit_info) => {
+            let mature_at = coinbase_mature_height::<T>(prev_tx.as_ref(), block.height);
+            deposit::<T>(tx.hash(), block, deposit_info, mature_at)
+        }
         BtcTxMetaType::<_>::Withdrawal => withdraw::<T>(tx),
         BtcTxMetaType::TrusteeTransition => trustee_transition::<T>(tx),
         BtcTxMetaType::HotAndCold => BtcTxResult::Success,
@@ -59,7 +105,24 @@ pub fn process_tx<T: Config>(
         BtcTxMetaType::<_>::Irrelevance => BtcTxResult::Failure,
     };
 
-    BtcTxState { tx_type, result }
+    (BtcTxState { tx_type, result }, evidence)
+}
+
+/// Tracks the UTXOs of the cold address created by `tx` and forgets the ones it spends.
+pub fn track_cold_utxos<T: Config>(tx: &Transaction, cold_addr: &Address, network: Network) {
+    for input in tx.inputs() {
+        ColdUtxos::<T>::remove((input.previous_output.txid, input.previous_output.index));
+    }
+
+    let txid = tx.hash();
+    for (index, output) in tx.outputs().iter().enumerate() {
+        match extract_output_addr(output, network) {
+            Some(addr) if addr.hash == cold_addr.hash => {
+                ColdUtxos::<T>::insert((txid, index as u32), output.value);
+            }
+            _ => {}
+        }
+    }
 }
 
 fn trustee_transition<T: Config>(tx: Transaction) -> BtcTxResult {
@@ -70,9 +133,36 @@ fn trustee_transition<T: Config>(tx: Transaction) -> BtcTxResult {
     BtcTxResult::Success
 }
 
-fn deposit<T: Config>(txid: H256, deposit_info: BtcDepositInfo<T::AccountId>) -> BtcTxResult {
+/// Returns the height at which a deposit spending the outputs of `prev_tx` could be credited,
+/// if `prev_tx` is a coinbase tx and the deposit in block `height` is not deep enough yet.
+fn coinbase_mature_height<T: Config>(prev_tx: Option<&Transaction>, height: u32) -> Option<u32> {
+    if !prev_tx
+        .map(|prev_tx| prev_tx.is_coinbase())
+        .unwrap_or(false)
+    {
+        return None;
+    }
+    let confirmations = Pallet::<T>::coinbase_deposit_confirmations();
+    let mature_at = height.saturating_add(confirmations.saturating_sub(1));
+    if mature_at > Pallet::<T>::best_index().height {
+        Some(mature_at)
+    } else {
+        None
+    }
+}
+
+fn deposit<T: Config>(
+    txid: H256,
+    block: BtcHeaderIndex,
+    deposit_info: BtcDepositInfo<T::AccountId>,
+    mature_at: Option<u32>,
+) -> BtcTxResult {
     // check address in op_return whether allow binding
     let deposit_info = T::AddressBinding::check_allowed_binding(deposit_info);
+    let input_addr_bytes = deposit_info
+        .input_addr
+        .as_ref()
+        .map(|addr| addr.to_string().into_bytes());
     let account_info = match (deposit_info.op_return, deposit_info.input_addr) {
         (Some((account, referral)), Some(input_addr)) => {
             let input_addr = input_addr.to_string().into_bytes();
@@ -95,9 +185,17 @@ fn deposit<T: Config>(txid: H256, deposit_info: BtcDepositInfo<T::AccountId>) ->
         (None, Some(input_addr)) => {
             // no opreturn but have input addr, use input addr to get accountid
             let addr_bytes = input_addr.to_string().into_bytes();
-            match T::AddressBinding::address(Pallet::<T>::chain(), addr_bytes) {
-                Some(account) => AccountInfo::Account((account, None)),
-                None => AccountInfo::Address(input_addr),
+            match T::AddressBinding::address(Pallet::<T>::chain(), addr_bytes.clone()) {
+                // the channel of a channel-only opreturn is attributed to the bound account
+                Some(account) => AccountInfo::Account((account, deposit_info.channel)),
+                None => {
+                    if let Some(channel) = deposit_info.channel {
+                        Pallet::<T>::deposit_event(Event::<T>::ChannelOnlyDepositPending(
+                            txid, channel, addr_bytes,
+                        ));
+                    }
+                    AccountInfo::Address(input_addr)
+                }
             }
         }
         (None, None) => {
@@ -120,6 +218,25 @@ fn deposit<T: Config>(txid: H256, deposit_info: BtcDepositInfo<T::AccountId>) ->
                 );
             }
 
+            if let Some(mature_at) = mature_at {
+                hold_immature_deposit::<T>(
+                    BtcDeferredDeposit {
+                        txid,
+                        input_addr: input_addr_bytes,
+                        balance: deposit_info.deposit_value,
+                        account,
+                    },
+                    block.hash,
+                    mature_at,
+                );
+                return BtcTxResult::Success;
+            }
+
+            if !deposits_enabled::<T>() {
+                defer_deposit::<T>(txid, input_addr_bytes, account, deposit_info.deposit_value);
+                return BtcTxResult::Success;
+            }
+
             match deposit_token::<T>(txid, &account, deposit_info.deposit_value) {
                 Ok(_) => {
                     info!(
@@ -136,6 +253,10 @@ fn deposit<T: Config>(txid: H256, deposit_info: BtcDepositInfo<T::AccountId>) ->
         }
         AccountInfo::<_>::Address(input_addr) => {
             insert_pending_deposit::<T>(&input_addr, txid, deposit_info.deposit_value);
+            // the maturity is checked again once the input address is bound
+            if let Some(mature_at) = mature_at {
+                ImmatureDepositOf::<T>::insert(txid, (block.hash, mature_at));
+            }
             info!(
                 target: "runtime::bitcoin",
                 "[deposit] Deposit tx ({:?}) into pending, addr:{:?}, balance:{}",
@@ -148,6 +269,127 @@ fn deposit<T: Config>(txid: H256, deposit_info: BtcDepositInfo<T::AccountId>) ->
     }
 }
 
+fn deposits_enabled<T: Config>() -> bool {
+    xpallet_gateway_records::Pallet::<T>::deposits_enabled(Pallet::<T>::chain())
+}
+
+fn defer_deposit<T: Config>(
+    txid: H256,
+    input_addr: Option<BtcAddress>,
+    account: OpReturnAccount<T::AccountId>,
+    balance: u64,
+) {
+    info!(
+        target: "runtime::bitcoin",
+        "[defer_deposit] Deposits are disabled, defer the deposit tx ({:?}), who:{:?}, balance:{}",
+        hash_rev(txid),
+        account,
+        balance
+    );
+    Pallet::<T>::push_deferred_deposit(BtcDeferredDeposit {
+        txid,
+        input_addr,
+        balance,
+        account,
+    });
+    Pallet::<T>::deposit_event(Event::<T>::DepositDeferred(txid, balance.saturated_into()));
+}
+
+/// Holds the coinbase-funded deposit in block `block_hash` until the best height reaches
+/// `mature_at`, or the next best height if `mature_at` has been passed.
+fn hold_immature_deposit<T: Config>(
+    deposit: BtcDeferredDeposit<T::AccountId>,
+    block_hash: H256,
+    mature_at: u32,
+) {
+    let mature_at = mature_at.max(Pallet::<T>::best_index().height.saturating_add(1));
+    info!(
+        target: "runtime::bitcoin",
+        "[hold_immature_deposit] Deposit tx ({:?}) spends a coinbase output, hold it until height:{}, who:{:?}, balance:{}",
+        hash_rev(deposit.txid),
+        mature_at,
+        deposit.account,
+        deposit.balance
+    );
+    let txid = deposit.txid;
+    ImmatureDepositOf::<T>::insert(txid, (block_hash, mature_at));
+    ImmatureDeposits::<T>::append(
+        mature_at,
+        BtcImmatureDeposit {
+            deposit,
+            block_hash,
+            mature_at,
+        },
+    );
+    Pallet::<T>::deposit_event(Event::<T>::DepositHeldUntilMature(txid, mature_at));
+}
+
+/// Credits the held coinbase-funded deposits which mature at `best_height`, the best height
+/// grows one by one so that no height is skipped.
+///
+/// The deposit whose block is no longer in the main chain is dropped along with its tx
+/// state, so that it could be relayed again in the new main chain.
+pub fn credit_mature_deposits<T: Config>(best_height: u32) {
+    let mature = ImmatureDeposits::<T>::take(best_height);
+    if mature.is_empty() {
+        return;
+    }
+    <frame_system::Pallet<T>>::register_extra_weight_unchecked(
+        <T as Config>::WeightInfo::process_deferred_deposits(mature.len() as u32),
+        DispatchClass::Normal,
+    );
+
+    for record in mature {
+        let txid = record.deposit.txid;
+        ImmatureDepositOf::<T>::remove(&txid);
+        if !Pallet::<T>::main_chain(&record.block_hash) {
+            warn!(
+                target: "runtime::bitcoin",
+                "[credit_mature_deposits] Block ({:?}) of deposit tx ({:?}) is not in the main chain, drop it",
+                hash_rev(record.block_hash),
+                hash_rev(txid)
+            );
+            TxState::<T>::remove(&txid);
+            TxEvidence::<T>::remove(&txid);
+            Pallet::<T>::deposit_event(Event::<T>::ImmatureDepositDropped(txid));
+            continue;
+        }
+        if deposits_enabled::<T>() {
+            let _ = process_deferred_deposit::<T>(record.deposit);
+        } else {
+            let deposit = record.deposit;
+            defer_deposit::<T>(txid, deposit.input_addr, deposit.account, deposit.balance);
+        }
+    }
+}
+
+/// Credits the deferred deposit to its account.
+pub fn process_deferred_deposit<T: Config>(
+    record: BtcDeferredDeposit<T::AccountId>,
+) -> DispatchResult {
+    match deposit_token::<T>(record.txid, &record.account, record.balance) {
+        Ok(()) => {
+            info!(
+                target: "runtime::bitcoin",
+                "[process_deferred_deposit] Deferred deposit tx ({:?}) success, who:{:?}, balance:{}",
+                hash_rev(record.txid),
+                record.account,
+                record.balance
+            );
+            Ok(())
+        }
+        Err(err) => {
+            error!(
+                target: "runtime::bitcoin",
+                "[process_deferred_deposit] Deferred deposit tx ({:?}) error:{:?}, must use root to fix it",
+                hash_rev(record.txid),
+                err
+            );
+            Err(err)
+        }
+    }
+}
+
 fn deposit_token<T: Config>(
     txid: H256,
     who: &OpReturnAccount<T::AccountId>,
@@ -167,7 +409,7 @@ fn deposit_wasm<T: Config>(txid: H256, who: &T::AccountId, balance: u64) -> Disp
     let value: BalanceOf<T> = balance.saturated_into();
     match <xpallet_gateway_records::Pallet<T>>::deposit(who, id, value) {
         Ok(()) => {
-            Pallet::<T>::deposit_event(Event::<T>::Deposited(txid, who.clone(), value));
+            Pallet::<T>::deposit_token_event(Event::<T>::Deposited(txid, who.clone(), value));
             Ok(())
         }
         Err(err) => {
@@ -186,7 +428,7 @@ fn deposit_evm<T: Config>(txid: H256, who: &H160, balance: u64) -> DispatchResul
 
     match xpallet_assets_bridge::Pallet::<T>::apply_direct_deposit(*who, id, balance as u128) {
         Ok(_) => {
-            Pallet::<T>::deposit_event(Event::<T>::DepositedEvm(
+            Pallet::<T>::deposit_token_event(Event::<T>::DepositedEvm(
                 txid,
                 *who,
                 balance.saturated_into(),
@@ -211,7 +453,7 @@ fn deposit_aptos<T: Config>(txid: H256, who: &H256, balance: u64) -> DispatchRes
     if let Some(proxy_address) = T::AddressBinding::dst_chain_proxy_address(DstChain::Aptos) {
         match <xpallet_gateway_records::Pallet<T>>::deposit(&proxy_address, id, value) {
             Ok(()) => {
-                Pallet::<T>::deposit_event(Event::<T>::DepositedAptos(txid, *who, value));
+                Pallet::<T>::deposit_token_event(Event::<T>::DepositedAptos(txid, *who, value));
             }
             Err(err) => {
                 error!(
@@ -240,7 +482,9 @@ fn deposit_named<T: Config>(
     {
         match <xpallet_gateway_records::Pallet<T>>::deposit(&proxy_address, id, value) {
             Ok(()) => {
-                Pallet::<T>::deposit_event(Event::<T>::DepositedNamed(txid, prefix, who, value));
+                Pallet::<T>::deposit_token_event(Event::<T>::DepositedNamed(
+                    txid, prefix, who, value,
+                ));
             }
             Err(err) => {
                 error!(
@@ -262,6 +506,28 @@ pub fn remove_pending_deposit<T: Config>(
     // notice this would delete this cache
     let records = PendingDeposits::<T>::take(input_address);
     for record in records {
+        if let Some((block_hash, mature_at)) = ImmatureDepositOf::<T>::take(&record.txid) {
+            hold_immature_deposit::<T>(
+                BtcDeferredDeposit {
+                    txid: record.txid,
+                    input_addr: Some(input_address.clone()),
+                    balance: record.balance,
+                    account: who.clone(),
+                },
+                block_hash,
+                mature_at,
+            );
+            continue;
+        }
+        if !deposits_enabled::<T>() {
+            defer_deposit::<T>(
+                record.txid,
+                Some(input_address.clone()),
+                who.clone(),
+                record.balance,
+            );
+            continue;
+        }
         // ignore error
         let _ = deposit_token::<T>(record.txid, who, record.balance);
         info!(
@@ -329,40 +595,131 @@ fn insert_pending_deposit<T: Config>(input_addr: &Address, txid: H256, balance:
     });
 }
 
+/// Returns how much the output paid to the applicant of withdrawal `id` falls short of
+/// the application `balance` minus the withdrawal `fee`, e.g., the fee was changed
+/// between the creation of the proposal and its confirmation.
+///
+/// The outputs are matched by the withdrawal address, each of them at most once, and
+/// the closest one not above the expected value is preferred. No shortfall is reported
+/// if there is no matching output.
+fn withdrawal_shortfall<T: Config>(
+    tx: &Transaction,
+    id: u32,
+    balance: u64,
+    fee: u64,
+    matched_outputs: &mut Vec<usize>,
+) -> u64 {
+    let addr = match Pallet::<T>::withdrawal_btc_address(id) {
+        Ok(addr) => addr,
+        Err(_) => return 0,
+    };
+    let network = Pallet::<T>::network_id();
+    let expected = balance.saturating_sub(fee);
+
+    let candidates = tx
+        .outputs
+        .iter()
+        .enumerate()
+        .filter(|(index, output)| {
+            !matched_outputs.contains(index)
+                && extract_output_addr(output, network)
+                    .map_or(false, |output_addr| output_addr.hash == addr.hash)
+        })
+        .collect::<Vec<_>>();
+    let matched = candidates
+        .iter()
+        .filter(|(_, output)| output.value <= expected)
+        .max_by_key(|(_, output)| output.value)
+        .or_else(|| candidates.first());
+
+    match matched {
+        Some((index, output)) => {
+            matched_outputs.push(*index);
+            let shortfall = expected.saturating_sub(output.value);
+            if shortfall > 0 {
+                warn!(
+                    target: "runtime::bitcoin",
+                    "[withdraw] Withdrawal ({}) was paid {} instead of {}",
+                    id, output.value, expected
+                );
+            }
+            shortfall
+        }
+        None => {
+            warn!(
+                target: "runtime::bitcoin",
+                "[withdraw] No output of the withdrawal tx pays the withdrawal ({})", id
+            );
+            0
+        }
+    }
+}
+
 fn withdraw<T: Config>(tx: Transaction) -> BtcTxResult {
     if let Some(proposal) = WithdrawalProposal::<T>::take() {
         log::debug!(
             target: "runtime::bitcoin",
-            "[withdraw] Withdraw tx {:?}, proposal:{:?}",
-            proposal,
-            tx
+            "[withdraw] Withdraw {}, proposal:{} with withdrawals {:?}",
+            BtcTxDisplay::from(&tx),
+            BtcTxDisplay::from(&proposal.tx),
+            proposal.withdrawal_id_list
         );
         let proposal_hash = proposal.tx.hash();
         let tx_hash = tx.hash();
 
         if proposal_hash == tx_hash {
+            // The trustee signatures are recorded from the first input spent with the hot
+            // address script, the cold address is not bound to the aggregated pubkeys.
+            let sig_input = match ProposalInputScripts::<T>::take()
+                .iter()
+                .position(|script| *script == BtcInputScript::Hot)
+            {
+                Some(index) => index,
+                None => {
+                    error!(
+                        target: "runtime::bitcoin",
+                        "[withdraw] Withdraw {} spends no hot input to record the signatures from, proposal:{:?}",
+                        BtcTxDisplay::from(&tx),
+                        proposal.withdrawal_id_list
+                    );
+                    return BtcTxResult::Failure;
+                }
+            };
             // Check if the transaction is normal witness
-            let input = &tx.inputs()[0];
-            if input.script_witness.len() != 3 {
-                error!(
-                    target: "runtime::bitcoin",
-                    "[withdraw] Withdraw tx {:?} is not normal witness, proposal:{:?}",
-                    tx,
-                    proposal
-                );
-                return BtcTxResult::Failure;
-            }
+            let input = match tx.inputs().get(sig_input) {
+                Some(input) if input.script_witness.len() == 3 => input,
+                _ => {
+                    error!(
+                        target: "runtime::bitcoin",
+                        "[withdraw] Withdraw {} is not normal witness, proposal:{:?}",
+                        BtcTxDisplay::from(&tx),
+                        proposal.withdrawal_id_list
+                    );
+                    return BtcTxResult::Failure;
+                }
+            };
 
+            let btc_withdrawal_fee = Pallet::<T>::btc_withdrawal_fee();
+            let mut matched_outputs = Vec::new();
             let mut total = BalanceOf::<T>::zero();
             for number in proposal.withdrawal_id_list.iter() {
-                // just for event record
                 let withdraw_balance =
                     xpallet_gateway_records::Pallet::<T>::pending_withdrawals(number)
                         .map(|record| record.balance())
                         .unwrap_or_else(BalanceOf::<T>::zero);
-                total += withdraw_balance;
+                let shortfall = withdrawal_shortfall::<T>(
+                    &tx,
+                    *number,
+                    withdraw_balance.saturated_into(),
+                    btc_withdrawal_fee,
+                    &mut matched_outputs,
+                );
+                let sent = withdraw_balance.saturating_sub(shortfall.saturated_into());
+                total += sent;
 
-                match xpallet_gateway_records::Pallet::<T>::finish_withdrawal(*number, None) {
+                match xpallet_gateway_records::Pallet::<T>::finish_withdrawal_with_sent(
+                    *number, sent,
+                ) {
                     Ok(_) => {
                         info!(target: "runtime::bitcoin", "[withdraw] Withdrawal ({}) completion", *number);
                     }
@@ -376,7 +733,6 @@ fn withdraw<T: Config>(tx: Transaction) -> BtcTxResult {
                 }
             }
 
-            let btc_withdrawal_fee = Pallet::<T>::btc_withdrawal_fee();
             // real withdraw value would reduce withdraw_fee
             total -=
                 (proposal.withdrawal_id_list.len() as u64 * btc_withdrawal_fee).saturated_into();
@@ -388,7 +744,7 @@ fn withdraw<T: Config>(tx: Transaction) -> BtcTxResult {
                 total.saturated_into(),
             );
 
-            Pallet::<T>::deposit_event(Event::<T>::Withdrawn(
+            Pallet::<T>::deposit_token_event(Event::<T>::Withdrawn(
                 tx_hash,
                 proposal.withdrawal_id_list,
                 total,
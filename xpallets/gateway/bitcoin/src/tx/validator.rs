@@ -8,7 +8,10 @@ use sp_std::prelude::Vec;
 
 use light_bitcoin::{chain::Transaction, primitives::H256};
 
-use crate::{types::BtcRelayedTx, Config, Error};
+use crate::{
+    types::{BtcRelayedTx, BtcTxDisplay},
+    Config, Error,
+};
 
 pub fn validate_transaction<T: Config>(
     tx: &BtcRelayedTx,
@@ -18,8 +21,8 @@ pub fn validate_transaction<T: Config>(
     let tx_hash = tx.raw.hash();
     debug!(
         target: "runtime::bitcoin",
-        "[validate_transaction] tx_hash:{:?}, relay tx:{:?}",
-        tx_hash, tx
+        "[validate_transaction] Relay {}",
+        BtcTxDisplay::from(&tx.raw)
     );
 
     // verify merkle proof
@@ -45,11 +48,12 @@ pub fn validate_transaction<T: Config>(
         return Err(Error::<T>::BadMerkleProof.into());
     }
 
-    if let Some(prev) = prev_tx {
+    // the tx without any input is skipped later on
+    if let (Some(prev), Some(input)) = (prev_tx, tx.raw.inputs.first()) {
         // verify prev tx for input
         // only check the first(0) input in transaction
         let previous_txid = prev.hash();
-        let expected_id = tx.raw.inputs[0].previous_output.txid;
+        let expected_id = input.previous_output.txid;
         if previous_txid != expected_id {
             error!(
                 target: "runtime::bitcoin",
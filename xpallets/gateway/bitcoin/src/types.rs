@@ -10,13 +10,13 @@ use sp_std::prelude::*;
 
 use light_bitcoin::{
     chain::{BlockHeader as BtcHeader, Transaction as BtcTransaction},
-    keys::Address,
+    keys::{Address, AddressTypes, Type},
     merkle::PartialMerkleTree,
-    primitives::{Compact, H256},
+    primitives::{hash_rev, Compact, H256},
 };
 
 use chainx_primitives::ReferralId;
-use xp_gateway_bitcoin::{BtcTxType, OpReturnAccount};
+use xp_gateway_bitcoin::{BtcTxEvidence, BtcTxType, OpReturnAccount};
 
 /// BtcAddress is an bitcoin address encoded in base58
 /// like: "1Nekoo5VTe7yQQ8WFqrva2UbdyRMVYCP1t" or "3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy"
@@ -67,11 +67,153 @@ pub struct BtcTxState {
 }
 
 #[derive(PartialEq, Clone, Copy, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub enum BtcTxResult {
     Success,
     Failure,
 }
 
+/// The reason why a relayed tx was skipped without being handled.
+#[derive(PartialEq, Clone, Copy, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum BtcTxSkipReason {
+    /// The tx spends no input.
+    NoInputs,
+    /// The first input spends an output the relayed previous tx does not have.
+    PrevOutputOutOfRange,
+    /// No address can be extracted from the output spent by the first input.
+    NoPrevOutputAddress,
+}
+
+/// The compact summary of a bitcoin tx for the diagnostics, instead of the whole tx.
+#[derive(PartialEq, Clone, Copy, Eq, Encode, Decode, TypeInfo)]
+pub struct BtcTxDisplay {
+    pub txid: H256,
+    pub inputs: u32,
+    pub outputs: u32,
+    /// The total value of the outputs in satoshi.
+    pub value: u64,
+}
+
+impl From<&BtcTransaction> for BtcTxDisplay {
+    fn from(tx: &BtcTransaction) -> Self {
+        Self {
+            txid: tx.hash(),
+            inputs: tx.inputs().len() as u32,
+            outputs: tx.outputs().len() as u32,
+            value: tx.outputs().iter().map(|output| output.value).sum(),
+        }
+    }
+}
+
+impl sp_std::fmt::Display for BtcTxDisplay {
+    fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+        write!(
+            f,
+            "Tx({}, inputs:{}, outputs:{}, value:{})",
+            xpallet_support::hash_strip(hash_rev(self.txid)),
+            self.inputs,
+            self.outputs,
+            self.value
+        )
+    }
+}
+
+impl sp_std::fmt::Debug for BtcTxDisplay {
+    fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+        sp_std::fmt::Display::fmt(self, f)
+    }
+}
+
+/// The handling state of relayed tx with the evidence of its classification.
+#[derive(PartialEq, Clone, Copy, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct BtcTxInfo {
+    pub tx_type: BtcTxType,
+    pub result: BtcTxResult,
+    /// `None` if the tx was handled before the evidence is recorded.
+    pub evidence: Option<BtcTxEvidence>,
+    /// The bitcoin height from which the deposit can be credited, `None` unless the deposit
+    /// spends a coinbase output and is held, or pending, until it is deep enough.
+    pub mature_at: Option<u32>,
+}
+
+/// The status of the bridge.
+#[derive(PartialEq, Clone, Copy, Eq, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct BtcBridgeStatus {
+    pub best_height: u32,
+    /// `None` if no header has been confirmed yet.
+    pub confirmed_height: Option<u32>,
+    pub halted: bool,
+    pub relayer_whitelist_enabled: bool,
+    pub relayer_count: u32,
+    /// The average seconds between the bitcoin timestamp and the acceptance of the
+    /// last `RELAY_LAG_WINDOW` headers, `None` if no relay info is recorded.
+    pub average_relay_lag: Option<u64>,
+}
+
+/// The count of the recent headers used for calculating the average relay lag.
+pub const RELAY_LAG_WINDOW: u32 = 144;
+
+/// The maximum count of the recent headers walked for the relay stats, about a week.
+pub const MAX_RELAY_STATS_HEADERS: u32 = 1008;
+
+/// The maximum number of the recent withdrawal proposals kept for estimating the cadence.
+pub const PROPOSAL_CADENCE_RING: usize = 10;
+
+/// The expected seconds between two bitcoin blocks.
+pub const BTC_BLOCK_SECONDS: u64 = 600;
+
+/// The relay information of an accepted bitcoin header.
+#[derive(PartialEq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct BtcRelayInfo<AccountId, BlockNumber> {
+    pub relayer: AccountId,
+    /// The ChainX block number when the header was accepted.
+    pub accepted_at: BlockNumber,
+    /// The unix time (in seconds) when the header was accepted.
+    pub accepted_time: u64,
+}
+
+/// The maximum size of a raw tx registered by chunks.
+pub const MAX_RAW_TX_SIZE: usize = 1024 * 1024;
+
+/// The maximum number of the chunks of a registered raw tx.
+pub const MAX_RAW_TX_CHUNKS: u32 = 64;
+
+/// The number of blocks a raw tx registration is kept before being pruned.
+pub const RAW_TX_REGISTRATION_LIFETIME: u32 = 600;
+
+/// The maximum number of the raw tx registrations a relayer can have at the same time.
+pub const MAX_RAW_TX_REGISTRATIONS_PER_RELAYER: u32 = 4;
+
+/// The number of blocks before the withdrawal queue is checked again after raising
+/// `WithdrawalProposalRequired`.
+pub const PROPOSAL_ALERT_BACKOFF: u32 = 100;
+
+/// The withdrawal queue is checked against its thresholds once every this number of blocks.
+pub const WITHDRAWAL_QUEUE_CHECK_INTERVAL: u32 = 10;
+
+/// A raw tx registered by chunks, waiting to be relayed by its hash.
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct BtcRawTxRegistration<BlockNumber> {
+    pub total_chunks: u32,
+    pub received_chunks: u32,
+    /// The concatenation of the received chunks.
+    pub raw_tx: Vec<u8>,
+    /// The registration is pruned at this block.
+    pub expires_at: BlockNumber,
+}
+
+impl<BlockNumber> BtcRawTxRegistration<BlockNumber> {
+    /// Returns true if all the chunks have been received.
+    pub fn is_complete(&self) -> bool {
+        self.received_chunks == self.total_chunks
+    }
+}
+
 pub enum AccountInfo<AccountId> {
     /// A value of type `L`.
     Account((OpReturnAccount<AccountId>, Option<ReferralId>)),
@@ -85,6 +227,81 @@ pub struct BtcDepositCache {
     pub balance: u64,
 }
 
+/// A confirmed deposit which was not credited because the deposits were disabled.
+#[derive(PartialEq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct BtcDeferredDeposit<AccountId> {
+    pub txid: H256,
+    pub input_addr: Option<BtcAddress>,
+    pub balance: u64,
+    pub account: OpReturnAccount<AccountId>,
+}
+
+/// The default confirmations required by the deposits spending a coinbase output,
+/// in line with the coinbase maturity of bitcoin.
+pub const DEFAULT_COINBASE_DEPOSIT_CONFIRMATIONS: u32 = 100;
+
+/// A confirmed deposit spending a coinbase output, which is held until the block of
+/// the deposit is `CoinbaseDepositConfirmations` deep.
+#[derive(PartialEq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct BtcImmatureDeposit<AccountId> {
+    pub deposit: BtcDeferredDeposit<AccountId>,
+    /// The block of the deposit, the deposit is dropped if it's no longer in the main chain.
+    pub block_hash: H256,
+    /// The bitcoin height at which the deposit is credited.
+    pub mature_at: u32,
+}
+
+/// The default bitcoin network fee rate (sat/vbyte) for estimating the withdrawal fee.
+pub const DEFAULT_NETWORK_FEE_RATE: u64 = 10;
+
+/// The estimated vbytes of a withdrawal proposal tx shared by all the withdrawals in it,
+/// i.e., the tx overhead (11), a trustee taproot input spent by the script path (100)
+/// and the change output to the hot address (43).
+pub const WITHDRAWAL_TX_SHARED_VBYTES: u64 = 11 + 100 + 43;
+
+/// Returns the vbytes of the withdrawal output paying to `address`.
+pub fn withdrawal_output_vbytes(address: &Address) -> u64 {
+    match address.hash {
+        AddressTypes::Legacy(_) if address.kind == Type::P2SH => 32,
+        AddressTypes::Legacy(_) => 34,
+        AddressTypes::WitnessV0KeyHash(_) => 31,
+        AddressTypes::WitnessV0ScriptHash(_) | AddressTypes::WitnessV1Taproot(_) => 43,
+    }
+}
+
+/// The estimated share of the bitcoin network fee of a withdrawal.
+///
+/// A withdrawal pays for its own output and an equal share of the vbytes common to the
+/// proposal tx, assuming the proposal is full of `batch_size` withdrawals.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct BtcWithdrawalNetworkFee {
+    /// The fee rate in sat/vbyte.
+    pub fee_rate: u64,
+    /// The number of the withdrawals sharing a proposal tx.
+    pub batch_size: u32,
+    /// The vbytes paid by the withdrawal.
+    pub vbytes: u64,
+    /// The fee in satoshis, i.e., `fee_rate * vbytes`.
+    pub fee: u64,
+}
+
+impl BtcWithdrawalNetworkFee {
+    pub fn new(address: &Address, fee_rate: u64, batch_size: u32) -> Self {
+        let batch_size = batch_size.max(1);
+        let batch = u64::from(batch_size);
+        let shared = (WITHDRAWAL_TX_SHARED_VBYTES + batch - 1) / batch;
+        let vbytes = withdrawal_output_vbytes(address) + shared;
+        Self {
+            fee_rate,
+            batch_size,
+            vbytes,
+            fee: fee_rate.saturating_mul(vbytes),
+        }
+    }
+}
+
 #[derive(PartialEq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct BtcWithdrawalProposal<AccountId> {
@@ -110,6 +327,77 @@ impl<AccountId> BtcWithdrawalProposal<AccountId> {
     }
 }
 
+/// An output of the withdrawal proposal tx, matched against the withdrawal applications.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct BtcWithdrawalOutput {
+    pub output_index: u32,
+    /// The destination address, `None` if no address can be extracted from the script.
+    #[cfg_attr(feature = "std", serde(with = "xp_rpc::serde_text_opt"))]
+    pub address: Option<BtcAddress>,
+    /// The value in satoshis.
+    pub value: u64,
+    /// The withdrawal application paid by the output, `None` for the change output.
+    pub withdrawal_id: Option<u32>,
+    /// Whether the output is the change back to the hot trustee address.
+    pub is_change: bool,
+}
+
+/// The decoded withdrawal proposal for the trustees to review before signing.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct BtcWithdrawalTxInfo<AccountId> {
+    pub sig_state: VoteResult,
+    pub withdrawal_id_list: Vec<u32>,
+    /// The raw tx of the proposal without the witnesses.
+    #[cfg_attr(feature = "std", serde(with = "xp_rpc::serde_hex"))]
+    pub tx: Vec<u8>,
+    pub outputs: Vec<BtcWithdrawalOutput>,
+    /// The redeem script of the hot trustee address spent by the proposal.
+    #[cfg_attr(feature = "std", serde(with = "xp_rpc::serde_hex"))]
+    pub redeem_script: Vec<u8>,
+    /// The number of the trustees who have signed the proposal.
+    pub signed_count: u32,
+    /// The trustees who have signed the proposal.
+    pub signed_trustees: Vec<AccountId>,
+}
+
+/// The flag of a withdrawal address.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum FlagLevel {
+    /// The withdrawals are allowed, but `FlaggedWithdrawal` is emitted.
+    Warn,
+    /// The withdrawals are rejected.
+    Block,
+}
+
+/// The authorization of the trustees to spend the UTXOs of the cold address in the
+/// withdrawal proposals.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct BtcColdSpendAuthorization<BlockNumber> {
+    /// The max total value of the cold UTXOs allowed to be spent.
+    pub max_value: u64,
+    /// The total value of the cold UTXOs spent so far.
+    pub used_value: u64,
+    /// The authorization is revoked since this block.
+    pub expiry: BlockNumber,
+}
+
+/// The script which an input of the withdrawal proposal is spent with.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum BtcInputScript {
+    /// Spends an UTXO of the hot address.
+    Hot,
+    /// Spends an UTXO of the cold address.
+    Cold,
+}
+
 #[derive(PartialEq, Clone, Copy, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub enum VoteResult {
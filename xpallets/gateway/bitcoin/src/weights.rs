@@ -41,25 +41,38 @@ pub trait WeightInfo {
     fn set_btc_withdrawal_fee() -> Weight;
     fn set_btc_deposit_limit() -> Weight;
     fn set_coming_bot() -> Weight;
+    fn set_bridge_halted() -> Weight;
+    fn remove_suspect_deposit() -> Weight;
+    fn process_deferred_deposits(n: u32) -> Weight;
+    fn set_relayer_whitelist_enabled() -> Weight;
+    fn add_relayer() -> Weight;
+    fn remove_relayer() -> Weight;
+    fn set_btc_address_flag() -> Weight;
+    fn set_address_withdraw_cooldown() -> Weight;
+    fn prune_headers(n: u32) -> Weight;
+    fn authorize_cold_spend() -> Weight;
+    fn register_raw_tx() -> Weight;
+    fn push_registered_transaction() -> Weight;
+    fn set_withdrawal_queue_thresholds() -> Weight;
 }
 
 /// Weights for xpallet_gateway_bitcoin using the Substrate node and recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
     fn push_header() -> Weight {
-        (116_466_000 as Weight)
-            .saturating_add(T::DbWeight::get().reads(10 as Weight))
-            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+        (118_302_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(12 as Weight))
+            .saturating_add(T::DbWeight::get().writes(7 as Weight))
     }
     fn push_transaction() -> Weight {
-        (313_612_000 as Weight)
-            .saturating_add(T::DbWeight::get().reads(23 as Weight))
+        (315_448_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(24 as Weight))
             .saturating_add(T::DbWeight::get().writes(10 as Weight))
     }
     fn create_taproot_withdraw_tx() -> Weight {
         (147_105_000 as Weight)
-            .saturating_add(T::DbWeight::get().reads(14 as Weight))
-            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+            .saturating_add(T::DbWeight::get().reads(15 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
     }
     fn set_best_index() -> Weight {
         (3_180_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
@@ -86,24 +99,111 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
     fn set_coming_bot() -> Weight {
         (2_887_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
     }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_bridge_halted` benchmark.
+    fn set_bridge_halted() -> Weight {
+        (2_901_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `remove_suspect_deposit` benchmark.
+    fn remove_suspect_deposit() -> Weight {
+        (4_215_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `process_deferred_deposits` benchmark.
+    fn process_deferred_deposits(n: u32) -> Weight {
+        (10_000_000 as Weight)
+            .saturating_add((100_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().reads((6 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes((5 as Weight).saturating_mul(n as Weight)))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_relayer_whitelist_enabled` benchmark.
+    fn set_relayer_whitelist_enabled() -> Weight {
+        (2_874_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `add_relayer` benchmark.
+    fn add_relayer() -> Weight {
+        (4_536_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `remove_relayer` benchmark.
+    fn remove_relayer() -> Weight {
+        (4_402_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_btc_address_flag` benchmark.
+    fn set_btc_address_flag() -> Weight {
+        (6_817_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_address_withdraw_cooldown` benchmark.
+    fn set_address_withdraw_cooldown() -> Weight {
+        (2_376_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `prune_headers` benchmark.
+    fn prune_headers(n: u32) -> Weight {
+        (10_214_000 as Weight)
+            .saturating_add((24_806_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes((5 as Weight).saturating_mul(n as Weight)))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `authorize_cold_spend` benchmark.
+    fn authorize_cold_spend() -> Weight {
+        (21_372_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `register_raw_tx` benchmark.
+    fn register_raw_tx() -> Weight {
+        (48_926_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `push_registered_transaction` benchmark.
+    fn push_registered_transaction() -> Weight {
+        (318_207_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(26 as Weight))
+            .saturating_add(T::DbWeight::get().writes(12 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_withdrawal_queue_thresholds` benchmark.
+    fn set_withdrawal_queue_thresholds() -> Weight {
+        (5_000_000 as Weight).saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
     fn push_header() -> Weight {
-        (116_466_000 as Weight)
-            .saturating_add(RocksDbWeight::get().reads(10 as Weight))
-            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+        (118_302_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(12 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(7 as Weight))
     }
     fn push_transaction() -> Weight {
-        (313_612_000 as Weight)
-            .saturating_add(RocksDbWeight::get().reads(23 as Weight))
+        (315_448_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(24 as Weight))
             .saturating_add(RocksDbWeight::get().writes(10 as Weight))
     }
     fn create_taproot_withdraw_tx() -> Weight {
         (147_105_000 as Weight)
-            .saturating_add(RocksDbWeight::get().reads(14 as Weight))
-            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+            .saturating_add(RocksDbWeight::get().reads(15 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
     }
     fn set_best_index() -> Weight {
         (3_180_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
@@ -130,4 +230,91 @@ impl WeightInfo for () {
     fn set_coming_bot() -> Weight {
         (2_887_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
     }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_bridge_halted` benchmark.
+    fn set_bridge_halted() -> Weight {
+        (2_901_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `remove_suspect_deposit` benchmark.
+    fn remove_suspect_deposit() -> Weight {
+        (4_215_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `process_deferred_deposits` benchmark.
+    fn process_deferred_deposits(n: u32) -> Weight {
+        (10_000_000 as Weight)
+            .saturating_add((100_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((6 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((5 as Weight).saturating_mul(n as Weight)))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_relayer_whitelist_enabled` benchmark.
+    fn set_relayer_whitelist_enabled() -> Weight {
+        (2_874_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `add_relayer` benchmark.
+    fn add_relayer() -> Weight {
+        (4_536_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `remove_relayer` benchmark.
+    fn remove_relayer() -> Weight {
+        (4_402_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_btc_address_flag` benchmark.
+    fn set_btc_address_flag() -> Weight {
+        (6_817_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_address_withdraw_cooldown` benchmark.
+    fn set_address_withdraw_cooldown() -> Weight {
+        (2_376_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `prune_headers` benchmark.
+    fn prune_headers(n: u32) -> Weight {
+        (10_214_000 as Weight)
+            .saturating_add((24_806_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes((5 as Weight).saturating_mul(n as Weight)))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `authorize_cold_spend` benchmark.
+    fn authorize_cold_spend() -> Weight {
+        (21_372_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `register_raw_tx` benchmark.
+    fn register_raw_tx() -> Weight {
+        (48_926_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `push_registered_transaction` benchmark.
+    fn push_registered_transaction() -> Weight {
+        (318_207_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(26 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(12 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_withdrawal_queue_thresholds` benchmark.
+    fn set_withdrawal_queue_thresholds() -> Weight {
+        (5_000_000 as Weight).saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
 }
@@ -18,9 +18,15 @@ pub use xpallet_gateway_common::{
     trustees,
     types::{GenericTrusteeIntentionProps, GenericTrusteeSessionInfo, ScriptInfo},
 };
-pub use xpallet_gateway_records::{Withdrawal, WithdrawalRecordId, WithdrawalState};
+pub use xpallet_gateway_records::{
+    Withdrawal, WithdrawalAllowance, WithdrawalCost, WithdrawalRecordId, WithdrawalState,
+};
 sp_api::decl_runtime_apis! {
     /// The API to query account nonce (aka transaction index).
+    ///
+    /// Version 2 adds `estimate_withdrawal_cost`.
+    /// Version 3 adds `withdrawal_limit_of`.
+    #[api_version(3)]
     pub trait XGatewayCommonApi<AccountId, Balance, BlockNumber>
     where
         AccountId: codec::Codec,
@@ -53,5 +59,11 @@ sp_api::decl_runtime_apis! {
         fn trustee_session_info(chain: Chain, session_number: i32) -> Option<GenericTrusteeSessionInfo<AccountId, BlockNumber>>;
 
         fn generate_trustee_session_info(chain: Chain, Vec<AccountId>) -> Result<(GenericTrusteeSessionInfo<AccountId, BlockNumber>, ScriptInfo<AccountId>), DispatchError>;
+
+        /// Get the withdrawal limit with the remaining withdrawal allowance of `who`.
+        fn withdrawal_limit_of(asset_id: AssetId, who: AccountId) -> Result<(WithdrawalLimit<Balance>, Option<WithdrawalAllowance<Balance>>), DispatchError>;
+
+        /// Estimate the cost of a withdrawal, the withdrawal is verified like `verify_withdrawal`.
+        fn estimate_withdrawal_cost(asset_id: AssetId, value: Balance, addr: AddrStr, memo: Memo) -> Result<WithdrawalCost<Balance>, DispatchError>;
     }
 }
@@ -12,13 +12,13 @@ use codec::Codec;
 use jsonrpc_derive::rpc;
 use serde::{Deserialize, Serialize};
 
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_runtime::{generic::BlockId, traits::Block as BlockT};
 
 use xp_rpc::{
     hex_decode_error_into_rpc_err, runtime_error_into_rpc_err, trustee_decode_error_into_rpc_err,
-    trustee_inexistent_rpc_err, Result, RpcBalance,
+    trustee_inexistent_rpc_err, unsupported_api_rpc_err, Result, RpcBalance,
 };
 
 use xpallet_gateway_common_rpc_runtime_api::trustees::bitcoin::{
@@ -26,8 +26,8 @@ use xpallet_gateway_common_rpc_runtime_api::trustees::bitcoin::{
 };
 use xpallet_gateway_common_rpc_runtime_api::{
     AssetId, Chain, GenericTrusteeIntentionProps, GenericTrusteeSessionInfo, ScriptInfo,
-    Withdrawal, WithdrawalLimit, WithdrawalRecordId, WithdrawalState,
-    XGatewayCommonApi as XGatewayCommonRuntimeApi,
+    Withdrawal, WithdrawalAllowance, WithdrawalCost, WithdrawalLimit, WithdrawalRecordId,
+    WithdrawalState, XGatewayCommonApi as XGatewayCommonRuntimeApi,
 };
 
 /// XGatewayCommon RPC methods.
@@ -91,11 +91,14 @@ where
         at: Option<BlockHash>,
     ) -> Result<BtcTrusteeIntentionProps<AccountId>>;
 
-    /// Return bitcoin trustee for current session(e.g. trustee hot/cold address and else)
+    /// Return bitcoin trustee of a session(e.g. trustee hot/cold address and else)
+    ///
+    /// `session_number` is either a session number, `-1` for the current session or `-2`
+    /// for the previous one, the current session if not given.
     #[rpc(name = "xgatewaycommon_bitcoinTrusteeSessionInfo")]
     fn btc_trustee_session_info(
         &self,
-        session_number: i32,
+        session_number: Option<i32>,
         at: Option<BlockHash>,
     ) -> Result<BtcTrusteeSessionInfo<AccountId, BlockNumber>>;
 
@@ -106,6 +109,30 @@ where
         candidates: Vec<AccountId>,
         at: Option<BlockHash>,
     ) -> Result<BtcTrusteeSessionInfo<AccountId, BlockNumber>>;
+
+    /// Get withdrawal limit of an AssetId with the remaining withdrawal allowance of an account,
+    /// the allowance is null if the asset has no withdrawal rate limit.
+    #[rpc(name = "xgatewaycommon_withdrawalLimitOf")]
+    fn withdrawal_limit_of(
+        &self,
+        asset_id: AssetId,
+        who: AccountId,
+        at: Option<BlockHash>,
+    ) -> Result<(
+        WithdrawalLimit<RpcBalance<Balance>>,
+        Option<WithdrawalAllowance<RpcBalance<Balance>>>,
+    )>;
+
+    /// Estimate the total cost of a withdrawal before applying it, including the ChainX
+    /// transaction fee, the withdrawal fee and the share of the network fee on the issuing chain.
+    #[rpc(name = "chainx_estimateWithdrawalCost")]
+    fn estimate_withdrawal_cost(
+        &self,
+        asset_id: AssetId,
+        value: u64,
+        addr: String,
+        at: Option<BlockHash>,
+    ) -> Result<WithdrawalCost<RpcBalance<Balance>>>;
 }
 
 /// A struct that implements the [`XStakingApi`].
@@ -134,6 +161,24 @@ where
     Balance: Codec + Send + Sync + 'static,
     BlockNumber: Codec + Send + Sync + 'static,
 {
+    /// Returns an error if the runtime at `at` provides an `XGatewayCommonApi` older than
+    /// `version`.
+    fn ensure_api_version(&self, at: &BlockId<Block>, version: u32) -> Result<()> {
+        let supported = self
+            .client
+            .runtime_api()
+            .has_api_with::<dyn XGatewayCommonRuntimeApi<Block, AccountId, Balance, BlockNumber>, _>(
+                at,
+                |v| v >= version,
+            )
+            .map_err(runtime_error_into_rpc_err)?;
+        if supported {
+            Ok(())
+        } else {
+            Err(unsupported_api_rpc_err("XGatewayCommonApi", version))
+        }
+    }
+
     fn generic_trustee_properties(
         &self,
         chain: Chain,
@@ -344,9 +389,10 @@ where
 
     fn btc_trustee_session_info(
         &self,
-        session_number: i32,
+        session_number: Option<i32>,
         at: Option<<Block as BlockT>::Hash>,
     ) -> Result<BtcTrusteeSessionInfo<AccountId, BlockNumber>> {
+        let session_number = session_number.unwrap_or(-1);
         let info = self.generic_trustee_session_info(Chain::Bitcoin, session_number, at)?;
         BtcTrusteeSessionInfo::<_, _>::try_from(info).map_err(trustee_decode_error_into_rpc_err)
     }
@@ -359,6 +405,74 @@ where
         let info = self.generate_generic_trustee_session_info(Chain::Bitcoin, candidates, at)?;
         BtcTrusteeSessionInfo::<_, _>::try_from(info.0).map_err(trustee_decode_error_into_rpc_err)
     }
+
+    fn withdrawal_limit_of(
+        &self,
+        asset_id: AssetId,
+        who: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<(
+        WithdrawalLimit<RpcBalance<Balance>>,
+        Option<WithdrawalAllowance<RpcBalance<Balance>>>,
+    )> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        self.ensure_api_version(&at, 3)?;
+
+        let result = api
+            .withdrawal_limit_of(&at, asset_id, who)
+            .map_err(runtime_error_into_rpc_err)?
+            .map(|(limit, allowance)| {
+                (
+                    WithdrawalLimit {
+                        minimal_withdrawal: limit.minimal_withdrawal.into(),
+                        fee: limit.fee.into(),
+                    },
+                    allowance.map(|allowance| WithdrawalAllowance {
+                        remaining_count: allowance.remaining_count,
+                        remaining_value: allowance.remaining_value.into(),
+                    }),
+                )
+            })
+            .map_err(runtime_error_into_rpc_err)?;
+        Ok(result)
+    }
+
+    fn estimate_withdrawal_cost(
+        &self,
+        asset_id: AssetId,
+        value: u64,
+        addr: String,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<WithdrawalCost<RpcBalance<Balance>>> {
+        let value: Balance = Balance::from(value);
+        let addr = if let Some(stirp_addr) = addr.strip_prefix("0x") {
+            hex::decode(&stirp_addr).map_err(hex_decode_error_into_rpc_err)?
+        } else {
+            hex::decode(&addr).unwrap_or_else(|_| addr.into_bytes())
+        };
+
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        self.ensure_api_version(&at, 2)?;
+
+        let result = api
+            .estimate_withdrawal_cost(&at, asset_id, value, addr, Vec::<u8>::new().into())
+            .map_err(runtime_error_into_rpc_err)?
+            .map(into_rpc_withdrawal_cost)
+            .map_err(runtime_error_into_rpc_err)?;
+        Ok(result)
+    }
+}
+
+fn into_rpc_withdrawal_cost<Balance: Display + FromStr>(
+    cost: WithdrawalCost<Balance>,
+) -> WithdrawalCost<RpcBalance<Balance>> {
+    WithdrawalCost {
+        chainx_fee: cost.chainx_fee.into(),
+        withdrawal_fee: cost.withdrawal_fee.into(),
+        network_fee: cost.network_fee.into(),
+    }
 }
 
 #[derive(PartialEq, Eq, Clone, Serialize, Deserialize)]
@@ -390,3 +504,21 @@ impl<AccountId, Balance: Display + FromStr, BlockNumber>
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn withdrawal_cost_should_be_serialized_as_strings() {
+        let cost = into_rpc_withdrawal_cost(WithdrawalCost::<u128> {
+            chainx_fee: 1_000_000,
+            withdrawal_fee: 50_000,
+            network_fee: 360,
+        });
+        assert_eq!(
+            serde_json::to_string(&cost).unwrap(),
+            r#"{"chainxFee":"1000000","withdrawalFee":"50000","networkFee":"360"}"#
+        );
+    }
+}
@@ -7,7 +7,7 @@ use frame_system::RawOrigin;
 use sp_core::crypto::AccountId32;
 #[cfg(feature = "runtime-benchmarks")]
 use sp_runtime::traits::CheckedDiv;
-use sp_runtime::traits::StaticLookup;
+use sp_runtime::{traits::StaticLookup, Percent};
 use sp_std::prelude::*;
 
 use xp_assets_registrar::Chain;
@@ -270,6 +270,31 @@ benchmarks! {
     verify {
         assert_eq!(Pallet::<T>::referral_binding_of(&who, Chain::Bitcoin), Some(who));
     }
+
+    set_trustee_uptime_requirement {
+        let requirement = UptimeRequirement {
+            min_authored_blocks: 10,
+            sessions: 12,
+        };
+    }: _(RawOrigin::Root, Some(requirement))
+    verify {
+        assert_eq!(Pallet::<T>::trustee_uptime_requirement(), Some(requirement));
+    }
+
+    force_trustee_candidate {
+        let who: T::AccountId = alice::<T>();
+        let who_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(who.clone());
+    }: _(RawOrigin::Root, who_lookup)
+    verify {
+        assert_eq!(Pallet::<T>::forced_trustee_candidates(), vec![who]);
+    }
+
+    set_min_trustee_participation {
+        let min_participation = Percent::from_percent(10);
+    }: _(RawOrigin::Root, Chain::Bitcoin, min_participation)
+    verify {
+        assert_eq!(Pallet::<T>::min_trustee_participation(Chain::Bitcoin), min_participation);
+    }
 }
 
 #[cfg(test)]
@@ -292,6 +317,9 @@ mod tests {
             assert_ok!(Pallet::<Test>::test_benchmark_force_trustee_election());
             assert_ok!(Pallet::<Test>::test_benchmark_force_update_trustee());
             assert_ok!(Pallet::<Test>::test_benchmark_force_set_referral_binding());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_trustee_uptime_requirement());
+            assert_ok!(Pallet::<Test>::test_benchmark_force_trustee_candidate());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_min_trustee_participation());
         });
     }
 }
@@ -30,32 +30,40 @@ use frame_support::{
     ensure,
     log::{error, info},
     traits::{ChangeMembers, Currency, ExistenceRequirement, Get},
+    weights::Weight,
 };
 use frame_system::{ensure_root, ensure_signed, pallet_prelude::OriginFor};
 
 use sp_runtime::{
     traits::{CheckedDiv, Saturating, StaticLookup, UniqueSaturatedInto, Zero},
-    SaturatedConversion,
+    Percent, SaturatedConversion,
 };
 use sp_std::{collections::btree_map::BTreeMap, convert::TryFrom, prelude::*};
 
 /// ChainX primitives
 use chainx_primitives::{AddrStr, AssetId, ChainAddress, Text};
-use xp_gateway_common::DstChain;
-use xp_protocol::X_BTC;
+use xp_gateway_common::{DstChain, ExternalAddress};
+use xp_protocol::{PCX, X_BTC};
 use xp_runtime::Memo;
 
 /// ChainX pallets
-use xpallet_assets::{AssetRestrictions, BalanceOf, Chain, ChainT, WithdrawalLimit};
-use xpallet_gateway_records::{Withdrawal, WithdrawalRecordId};
+use xpallet_assets::{
+    AssetRestrictions, BalanceOf, Chain, ChainT, DerivedAccountKind, DerivedAccountRegistry,
+    MoveCaller, WithdrawalLimit,
+};
+use xpallet_gateway_records::{Withdrawal, WithdrawalAllowance, WithdrawalRecordId};
 use xpallet_support::traits::{MultisigAddressFor, Validator};
 
 use self::{
-    traits::{ProposalProvider, TotalSupply, TrusteeForChain, TrusteeInfoUpdate, TrusteeSession},
+    traits::{
+        OnWithdrawalApplied, ProposalProvider, TotalSupply, TrusteeForChain, TrusteeInfoUpdate,
+        TrusteeSession,
+    },
     trustees::bitcoin::BtcTrusteeAddrInfo,
     types::{
         GenericTrusteeIntentionProps, GenericTrusteeSessionInfo, RewardInfo, ScriptInfo,
-        TrusteeInfoConfig, TrusteeIntentionProps, TrusteeSessionInfo,
+        TrusteeInfoConfig, TrusteeIntentionProps, TrusteeSessionInfo, TrusteeSkipReason,
+        UptimeRequirement,
     },
 };
 
@@ -109,6 +117,12 @@ pub mod pallet {
         /// Get btc withdrawal proposal.
         type BitcoinWithdrawalProposal: ProposalProvider;
 
+        /// Handle the applied withdrawals to the btc addresses.
+        type BitcoinWithdrawalHook: OnWithdrawalApplied<Self::AccountId>;
+
+        /// The registry of the trustee multisig accounts.
+        type DerivedAccounts: DerivedAccountRegistry<Self::AccountId>;
+
         /// Weight information for extrinsics in this pallet.
         type WeightInfo: WeightInfo;
     }
@@ -141,9 +155,12 @@ pub mod pallet {
                 xpallet_assets::Pallet::<T>::can_do(&asset_id, AssetRestrictions::WITHDRAW),
                 xpallet_assets::Error::<T>::ActionNotAllowed,
             );
-            Self::verify_withdrawal(asset_id, value, &addr, &ext)?;
+            let address = Self::verify_withdrawal_address(asset_id, value, &addr, &ext)?;
+            Self::on_withdrawal_applied(&who, asset_id, &addr)?;
 
-            xpallet_gateway_records::Pallet::<T>::withdraw(&who, asset_id, value, addr, ext)?;
+            xpallet_gateway_records::Pallet::<T>::withdraw_to_address(
+                &who, asset_id, value, addr, address, ext,
+            )?;
             Ok(())
         }
 
@@ -207,7 +224,7 @@ pub mod pallet {
         }
 
         /// Manual execution of the election by admin.
-        #[pallet::weight(0u64)]
+        #[pallet::weight(Pallet::<T>::trustee_election_weight())]
         pub fn excute_trustee_election(origin: OriginFor<T>, chain: Chain) -> DispatchResult {
             T::CouncilOrigin::try_origin(origin)
                 .map(|_| ())
@@ -221,7 +238,10 @@ pub mod pallet {
         /// Force cancel trustee transition
         ///
         /// This is called by the root.
-        #[pallet::weight(0u64)]
+        /// # <weight>
+        /// Bounded by the election, which generates the session info the same way.
+        /// # </weight>
+        #[pallet::weight(Pallet::<T>::trustee_election_weight())]
         pub fn cancel_trustee_election(origin: OriginFor<T>, chain: Chain) -> DispatchResult {
             T::CouncilOrigin::try_origin(origin)
                 .map(|_| ())
@@ -239,9 +259,15 @@ pub mod pallet {
         ///
         /// This is called by the trustee admin and root.
         /// # <weight>
-        /// Since this is a root call and will go into trustee election, we assume full block for now.
+        /// The election plus the black room and the signature record of each trustee.
         /// # </weight>
-        #[pallet::weight(0u64)]
+        #[pallet::weight(
+            Pallet::<T>::trustee_election_weight().saturating_add(
+                T::DbWeight::get()
+                    .reads_writes(2, 1)
+                    .saturating_mul(trustees.as_ref().map_or(0, |t| t.len()) as Weight)
+            )
+        )]
         #[transactional]
         pub fn move_trust_into_black_room(
             origin: OriginFor<T>,
@@ -283,9 +309,13 @@ pub mod pallet {
         ///
         /// This is called by the trustee admin and root.
         /// # <weight>
-        /// Since this is a root call and will go into trustee election, we assume full block for now.
+        /// The black room is read for each member and written for each one in it.
         /// # </weight>
-        #[pallet::weight(0u64)]
+        #[pallet::weight(
+            10_000_000 + T::DbWeight::get()
+                .reads_writes(1, 1)
+                .saturating_mul(members.len() as Weight)
+        )]
         pub fn move_trust_out_black_room(
             origin: OriginFor<T>,
             chain: Chain,
@@ -340,7 +370,8 @@ pub mod pallet {
                 session_num as u32
             };
 
-            if session_num == Self::trustee_session_info_len(chain) {
+            let is_current_session = session_num == Self::trustee_session_info_len(chain);
+            if is_current_session {
                 T::CouncilOrigin::ensure_origin(origin)?;
                 // update trustee sig record info (update reward weight)
                 TrusteeSessionInfoOf::<T>::mutate(chain, session_num, |info| {
@@ -359,7 +390,12 @@ pub mod pallet {
                 );
             }
 
-            Self::apply_claim_trustee_reward(session_num)
+            Self::apply_claim_trustee_reward(session_num)?;
+            if is_current_session {
+                // the signatures so far are rewarded, start a new reward period
+                TrusteeSigRecord::<T>::remove_prefix(chain, None);
+            }
+            Ok(())
         }
 
         /// Force trustee election
@@ -537,6 +573,80 @@ pub mod pallet {
             });
             Ok(())
         }
+
+        /// Set the minimum uptime of the trustee candidates, `None` to disable the check.
+        ///
+        /// The members of the trustee pool that authored fewer blocks than required
+        /// are skipped in the trustee election.
+        ///
+        /// This is called by the root.
+        #[pallet::weight(< T as Config >::WeightInfo::set_trustee_uptime_requirement())]
+        pub fn set_trustee_uptime_requirement(
+            origin: OriginFor<T>,
+            requirement: Option<UptimeRequirement>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            match requirement {
+                Some(requirement) => {
+                    ensure!(
+                        requirement.sessions > 0,
+                        Error::<T>::InvalidUptimeRequirement
+                    );
+                    TrusteeUptimeRequirement::<T>::put(requirement);
+                }
+                None => TrusteeUptimeRequirement::<T>::kill(),
+            }
+            Self::deposit_event(Event::<T>::TrusteeUptimeRequirementUpdated(requirement));
+            Ok(())
+        }
+
+        /// Let `who` skip the uptime check in the next trustee election.
+        ///
+        /// This is for bootstrapping the trustees, `who` still has to be in the trustee pool.
+        ///
+        /// This is called by the root.
+        #[pallet::weight(< T as Config >::WeightInfo::force_trustee_candidate())]
+        pub fn force_trustee_candidate(
+            origin: OriginFor<T>,
+            who: <T::Lookup as StaticLookup>::Source,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            let who = T::Lookup::lookup(who)?;
+            ForcedTrusteeCandidates::<T>::mutate(|candidates| {
+                if !candidates.contains(&who) {
+                    candidates.push(who.clone());
+                }
+            });
+            Self::deposit_event(Event::<T>::TrusteeCandidateForced(who));
+            Ok(())
+        }
+
+        /// Set the minimum share of the signatures of a trustee to be rewarded.
+        ///
+        /// The trustees whose reward weight is below the share of the total weight
+        /// of the session get nothing when the trustee reward is assigned.
+        ///
+        /// The share is snapshotted when a trustee session starts, so the new share only
+        /// applies to the sessions after the next trustee transition.
+        ///
+        /// This is called by the council or root.
+        #[pallet::weight(< T as Config >::WeightInfo::set_min_trustee_participation())]
+        pub fn set_min_trustee_participation(
+            origin: OriginFor<T>,
+            chain: Chain,
+            min_participation: Percent,
+        ) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+
+            MinTrusteeParticipation::<T>::insert(chain, min_participation);
+            Self::deposit_event(Event::<T>::MinTrusteeParticipationUpdated(
+                chain,
+                min_participation,
+            ));
+            Ok(())
+        }
     }
 
     #[pallet::event]
@@ -567,6 +677,16 @@ pub mod pallet {
         AllocNativeReward(T::AccountId, u32, BalanceOf<T>),
         /// The not native asset of trustee multi_account is assigned. [multi_account, session_number, asset_id, total_reward]
         AllocNotNativeReward(T::AccountId, u32, AssetId, BalanceOf<T>),
+        /// The minimum uptime of the trustee candidates was updated. [requirement]
+        TrusteeUptimeRequirementUpdated(Option<UptimeRequirement>),
+        /// An account skips the uptime check in the next trustee election. [who]
+        TrusteeCandidateForced(T::AccountId),
+        /// A member of the trustee pool was skipped in the trustee election. [who, reason]
+        TrusteeCandidateSkipped(T::AccountId, TrusteeSkipReason),
+        /// The minimum share of the signatures to be rewarded was updated. [chain, min_participation]
+        MinTrusteeParticipationUpdated(Chain, Percent),
+        /// A trustee received the reward of a session. [trustee, session_number, asset_id, amount]
+        TrusteeRewardPaid(T::AccountId, u32, AssetId, BalanceOf<T>),
     }
 
     #[pallet::error]
@@ -605,11 +725,17 @@ pub mod pallet {
         TrusteeMembersNotEnough,
         /// exist in current trustee
         ExistCurrentTrustee,
+        /// the withdrawal address is empty
+        EmptyWithdrawalAddress,
+        /// the assets of ChainX can not be withdrawn
+        ChainXAssetNotWithdrawable,
+        /// the uptime must be counted in at least one session
+        InvalidUptimeRequirement,
     }
 
     #[pallet::storage]
     #[pallet::getter(fn trustee_multisig_addr)]
-    pub(crate) type TrusteeMultiSigAddr<T: Config> =
+    pub type TrusteeMultiSigAddr<T: Config> =
         StorageMap<_, Twox64Concat, Chain, T::AccountId, OptionQuery>;
 
     /// Trustee info config of the corresponding chain.
@@ -767,6 +893,19 @@ pub mod pallet {
     pub(crate) type TrusteeSigRecord<T: Config> =
         StorageDoubleMap<_, Twox64Concat, Chain, Twox64Concat, T::AccountId, u64, ValueQuery>;
 
+    /// The minimum share of the total reward weight of a session for a trustee to be rewarded.
+    #[pallet::storage]
+    #[pallet::getter(fn min_trustee_participation)]
+    pub(crate) type MinTrusteeParticipation<T: Config> =
+        StorageMap<_, Twox64Concat, Chain, Percent, ValueQuery>;
+
+    /// The `MinTrusteeParticipation` snapshotted when the trustee session of the
+    /// corresponding chain and number started.
+    #[pallet::storage]
+    #[pallet::getter(fn session_min_trustee_participation)]
+    pub(crate) type SessionMinTrusteeParticipation<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, Chain, Twox64Concat, u32, Percent, ValueQuery>;
+
     /// The status of the of the trustee transition
     #[pallet::storage]
     #[pallet::getter(fn trustee_transition_status)]
@@ -788,6 +927,17 @@ pub mod pallet {
     pub(crate) type PreTotalSupply<T: Config> =
         StorageDoubleMap<_, Twox64Concat, Chain, Twox64Concat, u32, BalanceOf<T>, ValueQuery>;
 
+    /// The minimum uptime of the trustee candidates, unchecked if absent.
+    #[pallet::storage]
+    #[pallet::getter(fn trustee_uptime_requirement)]
+    pub(crate) type TrusteeUptimeRequirement<T: Config> = StorageValue<_, UptimeRequirement>;
+
+    /// The accounts skipping the uptime check in the next trustee election.
+    #[pallet::storage]
+    #[pallet::getter(fn forced_trustee_candidates)]
+    pub(crate) type ForcedTrusteeCandidates<T: Config> =
+        StorageValue<_, Vec<T::AccountId>, ValueQuery>;
+
     #[pallet::genesis_config]
     pub struct GenesisConfig<T: Config> {
         pub trustees: Vec<(
@@ -840,14 +990,28 @@ impl<T: Config> Pallet<T> {
         addr: &[u8],
         ext: &Memo,
     ) -> DispatchResult {
+        Self::verify_withdrawal_address(asset_id, value, addr, ext).map(|_| ())
+    }
+
+    /// Verifies the withdrawal like `verify_withdrawal`, returning the typed address
+    /// parsed by the target chain.
+    pub fn verify_withdrawal_address(
+        asset_id: AssetId,
+        value: BalanceOf<T>,
+        addr: &[u8],
+        ext: &Memo,
+    ) -> Result<ExternalAddress, DispatchError> {
         ext.check_validity()?;
+        ensure!(!addr.is_empty(), Error::<T>::EmptyWithdrawalAddress);
 
         let chain = xpallet_assets_registrar::Pallet::<T>::chain_of(&asset_id)?;
-        match chain {
+        let address = match chain {
             Chain::Bitcoin => {
                 // bitcoin do not need memo
-                T::Bitcoin::check_addr(addr, b"")?;
+                T::Bitcoin::parse_addr(addr, b"")?
             }
+            // the assets of ChainX never leave the chain
+            Chain::ChainX => return Err(Error::<T>::ChainXAssetNotWithdrawable.into()),
             _ => return Err(Error::<T>::NotSupportedChain.into()),
         };
         // we could only split withdrawal limit due to a runtime-api would call `withdrawal_limit`
@@ -858,7 +1022,21 @@ impl<T: Config> Pallet<T> {
             value >= limit.minimal_withdrawal,
             Error::<T>::InvalidWithdrawal
         );
-        Ok(())
+        Ok(address)
+    }
+
+    /// Notifies the target chain of `asset_id` that `who` has applied for a withdrawal
+    /// to `addr`.
+    pub fn on_withdrawal_applied(
+        who: &T::AccountId,
+        asset_id: AssetId,
+        addr: &[u8],
+    ) -> DispatchResult {
+        let chain = xpallet_assets_registrar::Pallet::<T>::chain_of(&asset_id)?;
+        match chain {
+            Chain::Bitcoin => T::BitcoinWithdrawalHook::on_withdrawal_applied(who, addr),
+            _ => Ok(()),
+        }
     }
 }
 
@@ -960,6 +1138,25 @@ impl<T: Config> Pallet<T> {
 
 /// Trustee transition
 impl<T: Config> Pallet<T> {
+    /// Returns the weight of `do_trustee_election`, which visits every member and runner-up
+    /// of the elections and generates the session info of the new trustees.
+    ///
+    /// The new trustee multisig account is also recorded in `DerivedAccounts`, the same
+    /// as cancelling the transition.
+    ///
+    /// NOTE: Estimated from the storage accesses, it must be regenerated once the election
+    /// is benchmarked.
+    pub fn trustee_election_weight() -> Weight {
+        let pool = <T as pallet_elections_phragmen::Config>::DesiredMembers::get()
+            .saturating_add(<T as pallet_elections_phragmen::Config>::DesiredRunnersUp::get())
+            as Weight;
+        (100_000_000 as Weight)
+            .saturating_add((20_000_000 as Weight).saturating_mul(pool))
+            .saturating_add(T::DbWeight::get().reads_writes(10, 8))
+            .saturating_add(T::DbWeight::get().reads_writes(2, 1).saturating_mul(pool))
+            .saturating_add(T::DbWeight::get().writes(1))
+    }
+
     // Make sure the hot and cold pubkey are set and do not check the validity of the address
     pub fn ensure_set_address(who: &T::AccountId, chain: Chain) -> bool {
         Self::trustee_intention_props_of(who, chain).is_some()
@@ -994,13 +1191,29 @@ impl<T: Config> Pallet<T> {
 
         let all_trustee_pool = Self::generate_trustee_pool();
 
+        let uptime_requirement = Self::trustee_uptime_requirement();
+        let forced_candidates = Self::forced_trustee_candidates();
+        let mut skipped = Vec::new();
+
         let new_trustee_pool: Vec<T::AccountId> = all_trustee_pool
             .iter()
             .filter_map(|who| {
-                match filter_members.contains(who) || !Self::ensure_set_address(who, chain) {
-                    true => None,
-                    false => Some(who.clone()),
+                if filter_members.contains(who) || !Self::ensure_set_address(who, chain) {
+                    return None;
+                }
+                if let Some(requirement) = uptime_requirement {
+                    let authored = T::Validator::recent_authored_blocks(who, requirement.sessions);
+                    if authored < requirement.min_authored_blocks
+                        && !forced_candidates.contains(who)
+                    {
+                        skipped.push((
+                            who.clone(),
+                            TrusteeSkipReason::InsufficientAuthoredBlocks(authored),
+                        ));
+                        return None;
+                    }
                 }
+                Some(who.clone())
             })
             .collect::<Vec<T::AccountId>>();
 
@@ -1039,6 +1252,10 @@ impl<T: Config> Pallet<T> {
 
         Self::transition_trustee_session_impl(chain, new_trustee_candidate)?;
         LittleBlackHouse::<T>::insert(chain, remain_filter_members);
+        ForcedTrusteeCandidates::<T>::kill();
+        for (who, reason) in skipped {
+            Self::deposit_event(Event::<T>::TrusteeCandidateSkipped(who, reason));
+        }
         if Self::trustee_session_info_len(chain) != 1 {
             TrusteeTransitionStatus::<T>::insert(chain, true);
             let total_supply = T::BitcoinTotalSupply::total_supply();
@@ -1118,6 +1335,10 @@ impl<T: Config> Pallet<T> {
 
         TrusteeSessionInfoLen::<T>::insert(chain, session_number);
         TrusteeSessionInfoOf::<T>::insert(chain, session_number, session_info.0.clone());
+        T::DerivedAccounts::note_derived_account(
+            &multi_addr,
+            DerivedAccountKind::TrusteePot(chain),
+        );
         TrusteeMultiSigAddr::<T>::insert(chain, multi_addr);
         // Remove the information of the previous aggregate public key，Withdrawal is prohibited at this time.
         AggPubkeyInfo::<T>::remove_all(None);
@@ -1147,7 +1368,13 @@ impl<T: Config> Pallet<T> {
             .checked_add(1)
             .unwrap_or(0u32);
         let mut session_info = Self::try_generate_session_info(chain, new_trustees)?;
-        Self::alter_trustee_session(chain, session_number, &mut session_info)
+        Self::alter_trustee_session(chain, session_number, &mut session_info)?;
+        SessionMinTrusteeParticipation::<T>::insert(
+            chain,
+            session_number,
+            Self::min_trustee_participation(chain),
+        );
+        Ok(())
     }
 
     fn cancel_trustee_transition_impl(chain: Chain) -> DispatchResult {
@@ -1210,52 +1437,86 @@ impl<T: Config> Pallet<T> {
 
 /// Trustee rewards
 impl<T: Config> Pallet<T> {
+    /// Splits `reward` among the trustees by their reward weights, i.e., the signed
+    /// withdrawals of the session.
+    ///
+    /// The trustees below `min_participation` of the total weight get nothing, and the
+    /// reward is split equally if no trustee signed at all.
     fn compute_reward<Balance>(
         reward: Balance,
         trustee_info: &TrusteeSessionInfo<T::AccountId, T::BlockNumber, BtcTrusteeAddrInfo>,
+        min_participation: Percent,
     ) -> Result<RewardInfo<T::AccountId, Balance>, DispatchError>
     where
         Balance: Saturating + CheckedDiv + Zero + Copy,
         u64: UniqueSaturatedInto<Balance>,
     {
-        let sum_weight = trustee_info
+        let total_weight = trustee_info
             .trustee_list
             .iter()
             .map(|n| n.1)
-            .sum::<u64>()
-            .saturated_into::<Balance>();
+            .fold(0u64, |acc, weight| acc.saturating_add(weight));
+
+        let weights = if total_weight == 0 {
+            trustee_info
+                .trustee_list
+                .iter()
+                .map(|n| (n.0.clone(), 1u64))
+                .collect::<Vec<_>>()
+        } else {
+            let min_weight = min_participation.mul_ceil(total_weight);
+            trustee_info
+                .trustee_list
+                .iter()
+                .map(|(trustee, weight)| {
+                    let weight = if *weight < min_weight { 0 } else { *weight };
+                    (trustee.clone(), weight)
+                })
+                .collect::<Vec<_>>()
+        };
 
-        let trustee_len = trustee_info.trustee_list.len();
         let mut reward_info = RewardInfo { rewards: vec![] };
+        // the last rewarded trustee takes the rest of the reward
+        let last = match weights.iter().rposition(|n| n.1 > 0) {
+            Some(last) => last,
+            None => return Ok(reward_info),
+        };
+        let sum_weight = weights
+            .iter()
+            .map(|n| n.1)
+            .fold(0u64, |acc, weight| acc.saturating_add(weight))
+            .saturated_into::<Balance>();
+
         let mut acc_balance = Balance::zero();
-        for i in 0..trustee_len - 1 {
-            let trustee_weight = trustee_info.trustee_list[i].1.saturated_into::<Balance>();
+        for (trustee, weight) in weights[..last].iter() {
             let amount = reward
-                .saturating_mul(trustee_weight)
+                .saturating_mul(weight.saturated_into::<Balance>())
                 .checked_div(&sum_weight)
                 .ok_or(Error::<T>::InvalidTrusteeWeight)?;
-            reward_info
-                .rewards
-                .push((trustee_info.trustee_list[i].0.clone(), amount));
+            reward_info.rewards.push((trustee.clone(), amount));
             acc_balance = acc_balance.saturating_add(amount);
         }
         let amount = reward.saturating_sub(acc_balance);
-        reward_info
-            .rewards
-            .push((trustee_info.trustee_list[trustee_len - 1].0.clone(), amount));
+        reward_info.rewards.push((weights[last].0.clone(), amount));
         Ok(reward_info)
     }
 
     fn alloc_native_reward(
         from: &T::AccountId,
+        session_num: u32,
         trustee_info: &TrusteeSessionInfo<T::AccountId, T::BlockNumber, BtcTrusteeAddrInfo>,
+        min_participation: Percent,
     ) -> Result<BalanceOf<T>, DispatchError> {
         let total_reward = <T as xpallet_assets::Config>::Currency::free_balance(from);
         if total_reward.is_zero() {
             return Ok(BalanceOf::<T>::zero());
         }
-        let reward_info = Self::compute_reward(total_reward, trustee_info)?;
+        let reward_info = Self::compute_reward(total_reward, trustee_info, min_participation)?;
+        let mut allocated = BalanceOf::<T>::zero();
         for (acc, amount) in reward_info.rewards.iter() {
+            if amount.is_zero() {
+                continue;
+            }
             <T as xpallet_assets::Config>::Currency::transfer(
                 from,
                 acc,
@@ -1270,24 +1531,41 @@ impl<T: Config> Pallet<T> {
                 );
                 e
             })?;
+            allocated = allocated.saturating_add(*amount);
+            Self::deposit_event(Event::<T>::TrusteeRewardPaid(
+                acc.clone(),
+                session_num,
+                PCX,
+                *amount,
+            ));
         }
-        Ok(total_reward)
+        Ok(allocated)
     }
 
     fn alloc_not_native_reward(
         from: &T::AccountId,
+        session_num: u32,
         asset_id: AssetId,
         trustee_info: &TrusteeSessionInfo<T::AccountId, T::BlockNumber, BtcTrusteeAddrInfo>,
+        min_participation: Percent,
     ) -> Result<BalanceOf<T>, DispatchError> {
         xpallet_assets::Pallet::<T>::ensure_not_native_asset(&asset_id)?;
         let total_reward = xpallet_assets::Pallet::<T>::usable_balance(from, &asset_id);
         if total_reward.is_zero() {
             return Ok(BalanceOf::<T>::zero());
         }
-        let reward_info = Self::compute_reward(total_reward, trustee_info)?;
+        let reward_info = Self::compute_reward(total_reward, trustee_info, min_participation)?;
+        let mut allocated = BalanceOf::<T>::zero();
         for (acc, amount) in reward_info.rewards.iter() {
+            if amount.is_zero() {
+                continue;
+            }
             xpallet_assets::Pallet::<T>::move_usable_balance(
-                &asset_id, from, acc, *amount,
+                &asset_id,
+                from,
+                acc,
+                *amount,
+                MoveCaller::Gateway,
             )
             .map_err(|e| {
                 error!(
@@ -1297,8 +1575,15 @@ impl<T: Config> Pallet<T> {
                 );
                 xpallet_assets::Error::<T>::InsufficientBalance
             })?;
+            allocated = allocated.saturating_add(*amount);
+            Self::deposit_event(Event::<T>::TrusteeRewardPaid(
+                acc.clone(),
+                session_num,
+                asset_id,
+                *amount,
+            ));
         }
-        Ok(total_reward)
+        Ok(allocated)
     }
 
     pub fn apply_claim_trustee_reward(session_num: u32) -> DispatchResult {
@@ -1307,8 +1592,15 @@ impl<T: Config> Pallet<T> {
             None => return Err(Error::<T>::InvalidMultiAccount.into()),
             Some(n) => n,
         };
+        let min_participation =
+            Self::session_min_trustee_participation(Chain::Bitcoin, session_num);
         // alloc native reward
-        match Self::alloc_native_reward(&multi_account, &session_info) {
+        match Self::alloc_native_reward(
+            &multi_account,
+            session_num,
+            &session_info,
+            min_participation,
+        ) {
             Ok(total_native_reward) => {
                 if !total_native_reward.is_zero() {
                     Self::deposit_event(Event::<T>::AllocNativeReward(
@@ -1321,7 +1613,13 @@ impl<T: Config> Pallet<T> {
             Err(e) => return Err(e),
         }
         // alloc btc reward
-        match Self::alloc_not_native_reward(&multi_account, X_BTC, &session_info) {
+        match Self::alloc_not_native_reward(
+            &multi_account,
+            session_num,
+            X_BTC,
+            &session_info,
+            min_participation,
+        ) {
             Ok(total_btc_reward) => {
                 if !total_btc_reward.is_zero() {
                     Self::deposit_event(Event::<T>::AllocNotNativeReward(
@@ -1366,6 +1664,33 @@ impl<T: Config> Pallet<T> {
         }
     }
 
+    /// Returns the trustee session info of `session`, the current session if `None`.
+    ///
+    /// The historical sessions are kept in the storage, so any past session can be queried.
+    pub fn trustee_session(
+        chain: Chain,
+        session: Option<u32>,
+    ) -> Option<GenericTrusteeSessionInfo<T::AccountId, T::BlockNumber>> {
+        let session = session.unwrap_or_else(|| Self::trustee_session_info_len(chain));
+        Self::trustee_session_info_of(chain, session)
+    }
+
+    /// Returns the withdrawal limit of `asset_id` with the remaining allowance of `who`.
+    pub fn withdrawal_limit_of(
+        asset_id: &AssetId,
+        who: &T::AccountId,
+    ) -> Result<
+        (
+            WithdrawalLimit<BalanceOf<T>>,
+            Option<WithdrawalAllowance<BalanceOf<T>>>,
+        ),
+        DispatchError,
+    > {
+        let limit = Self::withdrawal_limit(asset_id)?;
+        let allowance = xpallet_gateway_records::Pallet::<T>::withdrawal_allowance(who, *asset_id);
+        Ok((limit, allowance))
+    }
+
     pub fn withdrawal_list_with_fee_info(
         asset_id: &AssetId,
     ) -> Result<
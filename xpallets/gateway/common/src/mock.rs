@@ -2,7 +2,7 @@
 
 use std::cmp::max;
 use std::convert::TryInto;
-use std::{cell::RefCell, convert::TryFrom, time::Duration};
+use std::{cell::RefCell, collections::BTreeMap, convert::TryFrom, time::Duration};
 
 use codec::{Decode, Encode};
 use frame_support::{
@@ -85,7 +85,7 @@ impl frame_system::Config for Test {
     type AccountId = AccountId;
     type Lookup = IdentityLookup<Self::AccountId>;
     type Header = Header;
-    type Event = ();
+    type Event = Event;
     type BlockHashCount = BlockHashCount;
     type DbWeight = ();
     type Version = ();
@@ -107,7 +107,7 @@ impl pallet_balances::Config for Test {
     type MaxLocks = ();
     type Balance = Balance;
     type DustRemoval = ();
-    type Event = ();
+    type Event = Event;
     type ExistentialDeposit = ExistentialDeposit;
     type AccountStore = System;
     type WeightInfo = ();
@@ -179,7 +179,7 @@ impl ChangeMembers<u64> for TestChangeMembers {
 
 impl pallet_elections_phragmen::Config for Test {
     type PalletId = ElectionsPhragmenPalletId;
-    type Event = ();
+    type Event = Event;
     type Currency = Balances;
     type CurrencyToVote = frame_support::traits::SaturatingCurrencyToVote;
     type ChangeMembers = ();
@@ -208,7 +208,7 @@ parameter_types! {
 }
 
 impl xpallet_assets_registrar::Config for Test {
-    type Event = ();
+    type Event = Event;
     type NativeAssetId = ChainXAssetId;
     type RegistrarHandler = ();
     type WeightInfo = ();
@@ -250,7 +250,7 @@ impl pallet_evm::Config for Test {
     type AddressMapping = pallet_evm::HashedAddressMapping<BlakeTwo256>;
     type Currency = Balances;
     type Runner = pallet_evm::runner::stack::Runner<Self>;
-    type Event = ();
+    type Event = Event;
     type PrecompilesType = ();
     type PrecompilesValue = ();
     type ChainId = ();
@@ -262,16 +262,19 @@ impl pallet_evm::Config for Test {
 }
 
 impl xpallet_assets::Config for Test {
-    type Event = ();
+    type Event = Event;
     type Currency = Balances;
     type TreasuryAccount = SimpleTreasuryAccount;
     type OnCreatedAccount = frame_system::Provider<Test>;
     type OnAssetChanged = ();
+    type OnReceived = ();
+    type MoveGuard = ();
+    type QuarantineOrigin = frame_system::EnsureRoot<AccountId>;
     type WeightInfo = ();
 }
 
 impl xpallet_assets_bridge::Config for Test {
-    type Event = ();
+    type Event = Event;
     type EvmCaller = EvmCaller;
     type ClaimBond = ClaimBond;
 }
@@ -282,7 +285,7 @@ parameter_types! {
 }
 
 impl xpallet_gateway_records::Config for Test {
-    type Event = ();
+    type Event = Event;
     type WeightInfo = ();
 }
 
@@ -305,7 +308,7 @@ impl UnixTime for CustomTimestamp {
 }
 
 impl xpallet_gateway_bitcoin::Config for Test {
-    type Event = ();
+    type Event = Event;
     type UnixTime = CustomTimestamp;
     type AccountExtractor = xp_gateway_bitcoin::OpReturnExtractor;
     type CouncilOrigin = EnsureSigned<AccountId>;
@@ -323,6 +326,12 @@ impl MultisigAddressFor<AccountId> for MultisigAddr {
         AccountId::decode(&mut &entropy[..]).unwrap()
     }
 }
+thread_local! {
+    pub static AUTHORED_BLOCKS: RefCell<BTreeMap<AccountId, u32>> = RefCell::new(BTreeMap::new());
+}
+pub fn set_authored_blocks(who: AccountId, blocks: u32) {
+    AUTHORED_BLOCKS.with(|authored| authored.borrow_mut().insert(who, blocks));
+}
 pub struct AlwaysValidator;
 impl Validator<AccountId> for AlwaysValidator {
     fn is_validator(_who: &AccountId) -> bool {
@@ -332,6 +341,10 @@ impl Validator<AccountId> for AlwaysValidator {
     fn validator_for(_: &[u8]) -> Option<AccountId> {
         None
     }
+
+    fn recent_authored_blocks(who: &AccountId, _sessions: u32) -> u32 {
+        AUTHORED_BLOCKS.with(|authored| authored.borrow().get(who).copied().unwrap_or_default())
+    }
 }
 pub struct MockBitcoin<T: xpallet_gateway_bitcoin::Config>(sp_std::marker::PhantomData<T>);
 impl<T: xpallet_gateway_bitcoin::Config> ChainT<BalanceOf<T>> for MockBitcoin<T> {
@@ -518,7 +531,7 @@ impl<T: xpallet_gateway_bitcoin::Config>
 }
 
 impl crate::Config for Test {
-    type Event = ();
+    type Event = Event;
     type Validator = AlwaysValidator;
     type DetermineMultisigAddress = MultisigAddr;
     type CouncilOrigin = EnsureSigned<AccountId>;
@@ -527,6 +540,8 @@ impl crate::Config for Test {
     type BitcoinTrusteeSessionProvider = trustees::bitcoin::BtcTrusteeSessionManager<Test>;
     type BitcoinTotalSupply = MockBitcoin<Test>;
     type BitcoinWithdrawalProposal = ();
+    type BitcoinWithdrawalHook = ();
+    type DerivedAccounts = XAssets;
     type WeightInfo = ();
 }
 
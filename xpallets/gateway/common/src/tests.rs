@@ -1,12 +1,36 @@
 use frame_system::RawOrigin;
 
 use crate::{
-    mock::{bob, charlie, dave, ExtBuilder, Test, XAssets, XGatewayCommon, XGatewayRecords},
-    Pallet, TrusteeSessionInfoLen, TrusteeSessionInfoOf, TrusteeSigRecord,
+    mock::{
+        alice, bob, charlie, dave, set_authored_blocks, AccountId, Event, ExtBuilder, Origin,
+        System, Test, XAssets, XGatewayCommon, XGatewayRecords,
+    },
+    types::{TrusteeSkipReason, UptimeRequirement},
+    Error, Pallet, TrusteeSessionInfoLen, TrusteeSessionInfoOf, TrusteeSigRecord,
 };
-use frame_support::assert_ok;
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::Percent;
 use xp_assets_registrar::Chain;
 use xp_protocol::X_BTC;
+use xp_runtime::Memo;
+use xpallet_assets::AssetInfo;
+
+fn skipped_event(who: AccountId, authored: u32) -> Event {
+    Event::XGatewayCommon(crate::Event::TrusteeCandidateSkipped(
+        who,
+        TrusteeSkipReason::InsufficientAuthoredBlocks(authored),
+    ))
+}
+
+fn t_skipped_candidates() -> Vec<AccountId> {
+    System::events()
+        .into_iter()
+        .filter_map(|record| match record.event {
+            Event::XGatewayCommon(crate::Event::TrusteeCandidateSkipped(who, _)) => Some(who),
+            _ => None,
+        })
+        .collect()
+}
 
 #[test]
 fn test_do_trustee_election() {
@@ -83,3 +107,267 @@ fn test_claim_not_native_asset_reward() {
         assert_eq!(XAssets::usable_balance(&charlie(), &X_BTC), 1);
     });
 }
+
+fn claim_current_btc_reward_with_sig_records(records: &[u64], reward: u128) {
+    assert_eq!(XGatewayCommon::do_trustee_election(Chain::Bitcoin), Ok(()));
+
+    for (trustee, record) in [bob(), charlie(), dave()].iter().zip(records) {
+        TrusteeSigRecord::<Test>::insert(Chain::Bitcoin, trustee, record);
+    }
+
+    let multi_address = XGatewayCommon::trustee_multisig_addr(Chain::Bitcoin).unwrap();
+    assert_ok!(XGatewayRecords::deposit(&multi_address, X_BTC, reward));
+
+    assert_ok!(XGatewayCommon::claim_trustee_reward(
+        Origin::signed(alice()),
+        Chain::Bitcoin,
+        -1
+    ));
+}
+
+#[test]
+fn test_claim_reward_split_by_signing_participation() {
+    ExtBuilder::default().build().execute_with(|| {
+        claim_current_btc_reward_with_sig_records(&[5, 3, 0], 80);
+
+        assert_eq!(XAssets::usable_balance(&bob(), &X_BTC), 50);
+        assert_eq!(XAssets::usable_balance(&charlie(), &X_BTC), 30);
+        assert_eq!(XAssets::usable_balance(&dave(), &X_BTC), 0);
+
+        // the signatures are counted from zero in the next reward period
+        for trustee in [bob(), charlie(), dave()] {
+            assert_eq!(
+                XGatewayCommon::trustee_sig_record(Chain::Bitcoin, trustee),
+                0
+            );
+        }
+    });
+}
+
+#[test]
+fn test_claim_reward_below_min_participation() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_ok!(XGatewayCommon::set_min_trustee_participation(
+            RawOrigin::Root.into(),
+            Chain::Bitcoin,
+            Percent::from_percent(40),
+        ));
+
+        claim_current_btc_reward_with_sig_records(&[5, 3, 0], 80);
+
+        assert_eq!(XAssets::usable_balance(&bob(), &X_BTC), 80);
+        assert_eq!(XAssets::usable_balance(&charlie(), &X_BTC), 0);
+        assert_eq!(XAssets::usable_balance(&dave(), &X_BTC), 0);
+    });
+}
+
+#[test]
+fn test_claim_reward_uses_session_min_participation() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_eq!(XGatewayCommon::do_trustee_election(Chain::Bitcoin), Ok(()));
+
+        // The share set during the session only applies to the following sessions.
+        assert_ok!(XGatewayCommon::set_min_trustee_participation(
+            RawOrigin::Root.into(),
+            Chain::Bitcoin,
+            Percent::from_percent(40),
+        ));
+        assert_eq!(
+            XGatewayCommon::session_min_trustee_participation(Chain::Bitcoin, 1),
+            Percent::from_percent(0)
+        );
+
+        for (trustee, record) in [bob(), charlie(), dave()].iter().zip([5, 3, 0]) {
+            TrusteeSigRecord::<Test>::insert(Chain::Bitcoin, trustee, record);
+        }
+        let multi_address = XGatewayCommon::trustee_multisig_addr(Chain::Bitcoin).unwrap();
+        assert_ok!(XGatewayRecords::deposit(&multi_address, X_BTC, 80));
+        assert_ok!(XGatewayCommon::claim_trustee_reward(
+            Origin::signed(alice()),
+            Chain::Bitcoin,
+            -1
+        ));
+
+        assert_eq!(XAssets::usable_balance(&bob(), &X_BTC), 50);
+        assert_eq!(XAssets::usable_balance(&charlie(), &X_BTC), 30);
+    });
+}
+
+#[test]
+fn test_claim_reward_split_equally_without_signatures() {
+    ExtBuilder::default().build().execute_with(|| {
+        claim_current_btc_reward_with_sig_records(&[0, 0, 0], 9);
+
+        assert_eq!(XAssets::usable_balance(&bob(), &X_BTC), 3);
+        assert_eq!(XAssets::usable_balance(&charlie(), &X_BTC), 3);
+        assert_eq!(XAssets::usable_balance(&dave(), &X_BTC), 3);
+    });
+}
+
+#[test]
+fn test_verify_withdrawal_rejects_empty_address() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_noop!(
+            XGatewayCommon::verify_withdrawal(X_BTC, 10, b"", &Memo::from(vec![])),
+            Error::<Test>::EmptyWithdrawalAddress
+        );
+    });
+}
+
+#[test]
+fn test_verify_withdrawal_rejects_chainx_asset() {
+    ExtBuilder::default().build().execute_with(|| {
+        let asset_id = 100;
+        let info = AssetInfo::new::<Test>(
+            b"C-PCX".to_vec(),
+            b"C-PCX".to_vec(),
+            Chain::ChainX,
+            8,
+            b"ChainX's native token wrapper".to_vec(),
+        )
+        .unwrap();
+        assert_ok!(xpallet_assets_registrar::Pallet::<Test>::register(
+            RawOrigin::Root.into(),
+            asset_id,
+            info,
+            true,
+            false,
+            None,
+            None
+        ));
+
+        assert_noop!(
+            XGatewayCommon::verify_withdrawal(
+                asset_id,
+                10,
+                b"5Uj3ehamDZWPfgA8iAZenhcAmPDakjf4aMbkBB4dXVvjoW6x",
+                &Memo::from(vec![])
+            ),
+            Error::<Test>::ChainXAssetNotWithdrawable
+        );
+    });
+}
+
+#[test]
+fn test_trustee_session_lookup() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_eq!(XGatewayCommon::do_trustee_election(Chain::Bitcoin), Ok(()));
+        let first = XGatewayCommon::trustee_session(Chain::Bitcoin, None).unwrap();
+        assert_eq!(
+            XGatewayCommon::trustee_session(Chain::Bitcoin, Some(1)),
+            Some(first.clone())
+        );
+
+        let mut second = first.clone();
+        second.0.threshold += 1;
+        second.0.start_height = Some(100);
+        TrusteeSessionInfoOf::<Test>::insert(Chain::Bitcoin, 2, second.clone());
+        TrusteeSessionInfoLen::<Test>::insert(Chain::Bitcoin, 2);
+
+        // The current session is the default, the historical sessions are still available.
+        assert_eq!(
+            XGatewayCommon::trustee_session(Chain::Bitcoin, None),
+            Some(second.clone())
+        );
+        assert_eq!(
+            XGatewayCommon::trustee_session(Chain::Bitcoin, Some(2)),
+            Some(second)
+        );
+        assert_eq!(
+            XGatewayCommon::trustee_session(Chain::Bitcoin, Some(1)),
+            Some(first)
+        );
+        assert_eq!(
+            XGatewayCommon::trustee_session(Chain::Bitcoin, Some(3)),
+            None
+        );
+    });
+}
+
+fn t_current_trustees() -> Vec<sp_core::crypto::AccountId32> {
+    let mut trustees = XGatewayCommon::trustee_session(Chain::Bitcoin, None)
+        .unwrap()
+        .0
+        .trustee_list
+        .into_iter()
+        .map(|(who, _)| who)
+        .collect::<Vec<_>>();
+    trustees.sort_unstable();
+    trustees
+}
+
+fn t_set_uptime_requirement() {
+    assert_ok!(XGatewayCommon::set_trustee_uptime_requirement(
+        RawOrigin::Root.into(),
+        Some(UptimeRequirement {
+            min_authored_blocks: 5,
+            sessions: 12,
+        })
+    ));
+}
+
+#[test]
+fn test_trustee_election_skips_offline_candidates() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        assert_noop!(
+            XGatewayCommon::set_trustee_uptime_requirement(
+                RawOrigin::Root.into(),
+                Some(UptimeRequirement {
+                    min_authored_blocks: 5,
+                    sessions: 0,
+                })
+            ),
+            Error::<Test>::InvalidUptimeRequirement
+        );
+        t_set_uptime_requirement();
+
+        // Alice authored nothing while the others meet the requirement.
+        set_authored_blocks(bob(), 5);
+        set_authored_blocks(charlie(), 8);
+        set_authored_blocks(dave(), 10);
+
+        assert_ok!(XGatewayCommon::do_trustee_election(Chain::Bitcoin));
+        let mut expected = vec![bob(), charlie(), dave()];
+        expected.sort_unstable();
+        assert_eq!(t_current_trustees(), expected);
+        System::assert_has_event(skipped_event(alice(), 0));
+        assert_eq!(t_skipped_candidates(), vec![alice()]);
+    });
+}
+
+#[test]
+fn test_trustee_election_with_forced_candidate() {
+    ExtBuilder::default().build().execute_with(|| {
+        System::set_block_number(1);
+        t_set_uptime_requirement();
+        set_authored_blocks(bob(), 5);
+        set_authored_blocks(charlie(), 8);
+
+        // Only 2 members meet the requirement out of the 3 trustees required.
+        assert_noop!(
+            XGatewayCommon::do_trustee_election(Chain::Bitcoin),
+            Error::<Test>::TrusteeMembersNotEnough
+        );
+
+        assert_noop!(
+            XGatewayCommon::force_trustee_candidate(Origin::signed(alice()), alice()),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(XGatewayCommon::force_trustee_candidate(
+            RawOrigin::Root.into(),
+            alice()
+        ));
+        assert_eq!(XGatewayCommon::forced_trustee_candidates(), vec![alice()]);
+
+        System::reset_events();
+        assert_ok!(XGatewayCommon::do_trustee_election(Chain::Bitcoin));
+        // The forced candidate is not skipped, only dave is.
+        assert_eq!(t_skipped_candidates(), vec![dave()]);
+        let mut expected = vec![alice(), bob(), charlie()];
+        expected.sort_unstable();
+        assert_eq!(t_current_trustees(), expected);
+        // The override only applies to a single election.
+        assert!(XGatewayCommon::forced_trustee_candidates().is_empty());
+    });
+}
@@ -1,6 +1,6 @@
 // Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
 
-use frame_support::dispatch::DispatchError;
+use frame_support::dispatch::{DispatchError, DispatchResult};
 use sp_std::{convert::TryFrom, prelude::Vec};
 
 use chainx_primitives::{AssetId, ReferralId};
@@ -71,6 +71,9 @@ pub trait TrusteeSession<AccountId, BlockNumber, TrusteeAddress: BytesLike> {
 
     fn trustee_transition_state() -> bool;
 
+    /// Returns the multisig account of the current trustees.
+    fn trustee_multisig() -> Option<AccountId>;
+
     #[cfg(feature = "std")]
     fn genesis_trustee(chain: Chain, init: &[AccountId]);
 }
@@ -102,6 +105,10 @@ impl<AccountId, BlockNumber, TrusteeAddress: BytesLike>
         false
     }
 
+    fn trustee_multisig() -> Option<AccountId> {
+        None
+    }
+
     #[cfg(feature = "std")]
     fn genesis_trustee(_: Chain, _: &[AccountId]) {}
 }
@@ -119,6 +126,16 @@ impl TrusteeInfoUpdate for () {
     fn update_trustee_sig_record(_: Chain, _: &[u8], _: u64) {}
 }
 
+pub trait OnWithdrawalApplied<AccountId> {
+    fn on_withdrawal_applied(who: &AccountId, addr: &[u8]) -> DispatchResult;
+}
+
+impl<AccountId> OnWithdrawalApplied<AccountId> for () {
+    fn on_withdrawal_applied(_: &AccountId, _: &[u8]) -> DispatchResult {
+        Ok(())
+    }
+}
+
 pub trait ReferralBinding<AccountId> {
     fn update_binding(asset_id: &AssetId, who: &AccountId, referral_name: Option<ReferralId>);
     fn referral(asset_id: &AssetId, who: &AccountId) -> Option<AccountId>;
@@ -119,3 +119,29 @@ fn test_serde_btc_trustee_type() {
     let de = serde_json::from_str::<BtcTrusteeType>(&ser).unwrap();
     assert_eq!(de, pubkey);
 }
+
+#[test]
+fn test_serde_btc_trustee_session_info() {
+    let info = BtcTrusteeSessionInfo::<u64, u32> {
+        trustee_list: vec![(1, 2)],
+        threshold: 1,
+        hot_address: BtcTrusteeAddrInfo {
+            addr: b"hot".to_vec(),
+            redeem_script: vec![0x51],
+        },
+        cold_address: BtcTrusteeAddrInfo {
+            addr: b"cold".to_vec(),
+            redeem_script: vec![0x52],
+        },
+        multi_account: None,
+        start_height: Some(10),
+        end_height: None,
+    };
+    let ser = serde_json::to_string(&info).unwrap();
+    assert_eq!(
+        ser,
+        r#"{"trusteeList":[[1,2]],"threshold":1,"hotAddress":{"addr":"hot","redeemScript":"0x51"},"coldAddress":{"addr":"cold","redeemScript":"0x52"},"multiAccount":null,"startHeight":10,"endHeight":null}"#
+    );
+    let de = serde_json::from_str::<BtcTrusteeSessionInfo<u64, u32>>(&ser).unwrap();
+    assert_eq!(de, info);
+}
@@ -90,6 +90,10 @@ impl<T: Config, TrusteeAddress: BytesLike + ChainProvider>
         Pallet::<T>::trustee_transition_status(TrusteeAddress::chain())
     }
 
+    fn trustee_multisig() -> Option<T::AccountId> {
+        Pallet::<T>::trustee_multisig_addr(TrusteeAddress::chain())
+    }
+
     #[cfg(feature = "std")]
     fn genesis_trustee(chain: Chain, trustees: &[T::AccountId]) {
         Pallet::<T>::transition_trustee_session_impl(chain, trustees.to_vec())
@@ -21,6 +21,25 @@ pub struct TrusteeInfoConfig {
     pub max_trustee_count: u32,
 }
 
+/// The minimum uptime of the trustee candidates.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, Default, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct UptimeRequirement {
+    /// The minimum number of blocks the candidate must have authored.
+    pub min_authored_blocks: u32,
+    /// The number of the latest ended sessions the authored blocks are counted in.
+    pub sessions: u32,
+}
+
+/// The reason why a member of the trustee pool is not elected as trustee.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum TrusteeSkipReason {
+    /// The candidate authored fewer blocks than required. [authored_blocks]
+    InsufficientAuthoredBlocks(u32),
+}
+
 /// The trustee session info.
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
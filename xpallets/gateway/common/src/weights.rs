@@ -42,6 +42,9 @@ pub trait WeightInfo {
     fn force_trustee_election() -> Weight;
     fn force_update_trustee() -> Weight;
     fn force_set_referral_binding() -> Weight;
+    fn set_trustee_uptime_requirement() -> Weight;
+    fn force_trustee_candidate() -> Weight;
+    fn set_min_trustee_participation() -> Weight;
 }
 
 /// Weights for xpallet_gateway_common using the Substrate node and recommended hardware.
@@ -94,6 +97,23 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
     fn force_set_referral_binding() -> Weight {
         (19_517_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
     }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_trustee_uptime_requirement` benchmark.
+    fn set_trustee_uptime_requirement() -> Weight {
+        (3_612_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `force_trustee_candidate` benchmark.
+    fn force_trustee_candidate() -> Weight {
+        (6_185_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_min_trustee_participation` benchmark.
+    fn set_min_trustee_participation() -> Weight {
+        (5_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
 }
 
 // For backwards compatibility and tests
@@ -145,4 +165,21 @@ impl WeightInfo for () {
     fn force_set_referral_binding() -> Weight {
         (19_517_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
     }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_trustee_uptime_requirement` benchmark.
+    fn set_trustee_uptime_requirement() -> Weight {
+        (3_612_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `force_trustee_candidate` benchmark.
+    fn force_trustee_candidate() -> Weight {
+        (6_185_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_min_trustee_participation` benchmark.
+    fn set_min_trustee_participation() -> Weight {
+        (5_000_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
 }
@@ -3,15 +3,20 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::too_many_arguments, clippy::unnecessary_mut_passed)]
 
-use sp_std::collections::btree_map::BTreeMap;
+use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
 
 use codec::Codec;
 
 pub use chainx_primitives::{AssetId, Decimals};
 pub use xpallet_assets::Chain;
-pub use xpallet_gateway_records::{Withdrawal, WithdrawalRecordId, WithdrawalState};
+pub use xpallet_gateway_records::{
+    Withdrawal, WithdrawalEstimate, WithdrawalRecordId, WithdrawalState,
+};
 
 sp_api::decl_runtime_apis! {
+    /// Version 2 adds `archived_withdrawals`.
+    /// Version 3 adds `withdrawal_estimates`.
+    #[api_version(3)]
     pub trait XGatewayRecordsApi<AccountId, Balance, BlockNumber>
     where
         AccountId: Codec,
@@ -21,5 +26,12 @@ sp_api::decl_runtime_apis! {
         fn withdrawal_list() -> BTreeMap<WithdrawalRecordId, Withdrawal<AccountId, Balance, BlockNumber>>;
 
         fn withdrawal_list_by_chain(chain: Chain) -> BTreeMap<WithdrawalRecordId, Withdrawal<AccountId, Balance, BlockNumber>>;
+
+        /// Returns the withdrawal records removed from the storage in this block.
+        fn archived_withdrawals() -> Vec<(WithdrawalRecordId, Withdrawal<AccountId, Balance, BlockNumber>)>;
+
+        /// Returns the estimated completion time of the pending withdrawals, the records
+        /// without an estimate are omitted.
+        fn withdrawal_estimates() -> BTreeMap<WithdrawalRecordId, WithdrawalEstimate>;
     }
 }
@@ -5,21 +5,35 @@ use std::fmt::Display;
 use std::str::FromStr;
 use std::sync::Arc;
 
-use codec::Codec;
+use codec::{Codec, Decode};
 use jsonrpc_derive::rpc;
 use serde::{Deserialize, Serialize};
 
-use sp_api::ProvideRuntimeApi;
+use sc_client_api::AuxStore;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_runtime::{generic::BlockId, traits::Block as BlockT};
 
-use xp_rpc::{runtime_error_into_rpc_err, Result};
+use xp_rpc::{decode_error_into_rpc_err, display_amount, runtime_error_into_rpc_err, Result};
+
+use xpallet_assets_rpc_runtime_api::XAssetsApi as XAssetsRuntimeApi;
 
 use xpallet_gateway_records_rpc_runtime_api::{
     AssetId, Chain, Withdrawal, WithdrawalRecordId, WithdrawalState,
     XGatewayRecordsApi as GatewayRecordsRuntimeApi,
 };
 
+/// The version of `XGatewayRecordsApi` adding `withdrawal_estimates`.
+const ESTIMATES_API_VERSION: u32 = 3;
+
+/// Prefix of the archived withdrawal records in the auxiliary storage of the archive nodes.
+const ARCHIVED_WITHDRAWAL_PREFIX: &[u8] = b"chainx_archive_withdrawal";
+
+/// Returns the auxiliary storage key of the archived withdrawal record `id`.
+pub fn archived_withdrawal_key(id: WithdrawalRecordId) -> Vec<u8> {
+    [ARCHIVED_WITHDRAWAL_PREFIX, &id.to_le_bytes()[..]].concat()
+}
+
 pub struct XGatewayRecords<C, B> {
     client: Arc<C>,
     _marker: std::marker::PhantomData<B>,
@@ -62,14 +76,22 @@ where
         chain: Chain,
         at: Option<BlockHash>,
     ) -> Result<BTreeMap<WithdrawalRecordId, RpcWithdrawalRecord<AccountId, Balance, BlockNumber>>>;
+
+    /// Return a withdrawal record removed from the storage, only available on the archive nodes
+    #[rpc(name = "chainx_getArchivedWithdrawal")]
+    fn get_archived_withdrawal(
+        &self,
+        id: WithdrawalRecordId,
+    ) -> Result<Option<RpcWithdrawalRecord<AccountId, Balance, BlockNumber>>>;
 }
 
 impl<C, Block, AccountId, Balance, BlockNumber>
     XGatewayRecordsApi<<Block as BlockT>::Hash, AccountId, Balance, BlockNumber>
     for XGatewayRecords<C, Block>
 where
-    C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
-    C::Api: GatewayRecordsRuntimeApi<Block, AccountId, Balance, BlockNumber>,
+    C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block> + AuxStore,
+    C::Api: GatewayRecordsRuntimeApi<Block, AccountId, Balance, BlockNumber>
+        + XAssetsRuntimeApi<Block, AccountId, Balance>,
     Block: BlockT,
     AccountId: Clone + Display + FromStr + Codec,
     Balance: Clone + Display + FromStr + Codec,
@@ -81,13 +103,16 @@ where
     ) -> Result<BTreeMap<u32, RpcWithdrawalRecord<AccountId, Balance, BlockNumber>>> {
         let api = self.client.runtime_api();
         let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
-        api.withdrawal_list(&at)
+        let records = api
+            .withdrawal_list(&at)
             .map(|map| {
                 map.into_iter()
                     .map(|(id, withdrawal)| (id, withdrawal.into()))
                     .collect()
             })
-            .map_err(runtime_error_into_rpc_err)
+            .map_err(runtime_error_into_rpc_err)?;
+        let records = self.with_display(&at, records)?;
+        self.with_estimates(&at, records)
     }
 
     fn withdrawal_list_by_chain(
@@ -97,13 +122,16 @@ where
     ) -> Result<BTreeMap<u32, RpcWithdrawalRecord<AccountId, Balance, BlockNumber>>> {
         let api = self.client.runtime_api();
         let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
-        api.withdrawal_list_by_chain(&at, chain)
+        let records = api
+            .withdrawal_list_by_chain(&at, chain)
             .map(|map| {
                 map.into_iter()
                     .map(|(id, withdrawal)| (id, withdrawal.into()))
                     .collect()
             })
-            .map_err(runtime_error_into_rpc_err)
+            .map_err(runtime_error_into_rpc_err)?;
+        let records = self.with_display(&at, records)?;
+        self.with_estimates(&at, records)
     }
 
     fn pending_withdrawal_list_by_chain(
@@ -113,7 +141,8 @@ where
     ) -> Result<BTreeMap<u32, RpcWithdrawalRecord<AccountId, Balance, BlockNumber>>> {
         let api = self.client.runtime_api();
         let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
-        api.withdrawal_list_by_chain(&at, chain)
+        let records = api
+            .withdrawal_list_by_chain(&at, chain)
             .map(|map| {
                 map.into_iter()
                     .filter_map(|(id, withdrawal)| {
@@ -125,7 +154,103 @@ where
                     })
                     .collect()
             })
-            .map_err(runtime_error_into_rpc_err)
+            .map_err(runtime_error_into_rpc_err)?;
+        let records = self.with_display(&at, records)?;
+        self.with_estimates(&at, records)
+    }
+
+    fn get_archived_withdrawal(
+        &self,
+        id: WithdrawalRecordId,
+    ) -> Result<Option<RpcWithdrawalRecord<AccountId, Balance, BlockNumber>>> {
+        let encoded = self
+            .client
+            .get_aux(&archived_withdrawal_key(id))
+            .map_err(runtime_error_into_rpc_err)?;
+        let withdrawal = match encoded {
+            Some(encoded) => {
+                Withdrawal::<AccountId, Balance, BlockNumber>::decode(&mut encoded.as_slice())
+                    .map_err(decode_error_into_rpc_err)?
+            }
+            None => return Ok(None),
+        };
+        let at = BlockId::hash(self.client.info().best_hash);
+        let mut records = BTreeMap::new();
+        records.insert(id, withdrawal.into());
+        Ok(self.with_display(&at, records)?.remove(&id))
+    }
+}
+
+impl<C, Block> XGatewayRecords<C, Block>
+where
+    C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    Block: BlockT,
+{
+    /// Renders the withdrawal balances by the asset precision, which is fetched
+    /// once for all the records.
+    fn with_display<AccountId, Balance, BlockNumber>(
+        &self,
+        at: &BlockId<Block>,
+        mut records: BTreeMap<u32, RpcWithdrawalRecord<AccountId, Balance, BlockNumber>>,
+    ) -> Result<BTreeMap<u32, RpcWithdrawalRecord<AccountId, Balance, BlockNumber>>>
+    where
+        C::Api: XAssetsRuntimeApi<Block, AccountId, Balance>,
+        AccountId: Codec,
+        Balance: Display + FromStr + Codec,
+    {
+        if records.is_empty() {
+            return Ok(records);
+        }
+        let assets = self
+            .client
+            .runtime_api()
+            .assets(at)
+            .map_err(runtime_error_into_rpc_err)?;
+        for record in records.values_mut() {
+            record.balance_display = assets
+                .get(&record.asset_id)
+                .map(|info| display_amount(&record.balance, info.info.decimals()));
+        }
+        Ok(records)
+    }
+
+    /// Fills in the estimated completion time of the pending withdrawal records.
+    ///
+    /// The estimates are left empty at the blocks before `withdrawal_estimates` is available.
+    fn with_estimates<AccountId, Balance, BlockNumber>(
+        &self,
+        at: &BlockId<Block>,
+        mut records: BTreeMap<u32, RpcWithdrawalRecord<AccountId, Balance, BlockNumber>>,
+    ) -> Result<BTreeMap<u32, RpcWithdrawalRecord<AccountId, Balance, BlockNumber>>>
+    where
+        C::Api: GatewayRecordsRuntimeApi<Block, AccountId, Balance, BlockNumber>,
+        AccountId: Codec,
+        Balance: Display + FromStr + Codec,
+        BlockNumber: Codec,
+    {
+        if records.is_empty() {
+            return Ok(records);
+        }
+        let api = self.client.runtime_api();
+        let has_estimates = api
+            .has_api_with::<dyn GatewayRecordsRuntimeApi<Block, AccountId, Balance, BlockNumber>, _>(
+                at,
+                |version| version >= ESTIMATES_API_VERSION,
+            )
+            .map_err(runtime_error_into_rpc_err)?;
+        if !has_estimates {
+            return Ok(records);
+        }
+        let estimates = api
+            .withdrawal_estimates(at)
+            .map_err(runtime_error_into_rpc_err)?;
+        for (id, record) in records.iter_mut() {
+            if let Some(estimate) = estimates.get(id) {
+                record.estimated_blocks_remaining = Some(estimate.blocks_remaining);
+                record.estimated_seconds = Some(estimate.seconds);
+            }
+        }
+        Ok(records)
     }
 }
 
@@ -136,10 +261,16 @@ pub struct RpcWithdrawalRecord<AccountId, Balance: Display + FromStr, BlockNumbe
     pub applicant: AccountId,
     #[serde(with = "xp_rpc::serde_num_str")]
     pub balance: Balance,
+    /// The balance rendered by the asset precision, `None` if the asset is unknown.
+    pub balance_display: Option<String>,
     pub addr: String,
     pub ext: String,
     pub height: BlockNumber,
     pub state: WithdrawalState,
+    /// The estimated ChainX blocks until the withdrawal is confirmed, `None` if unknown.
+    pub estimated_blocks_remaining: Option<u32>,
+    /// The estimated seconds until the withdrawal is confirmed, `None` if unknown.
+    pub estimated_seconds: Option<u64>,
 }
 
 impl<AccountId, Balance: Display + FromStr, BlockNumber>
@@ -151,10 +282,13 @@ impl<AccountId, Balance: Display + FromStr, BlockNumber>
             asset_id: record.asset_id,
             applicant: record.applicant,
             balance: record.balance,
+            balance_display: None,
             addr: String::from_utf8_lossy(record.addr.as_ref()).into_owned(),
             ext: String::from_utf8_lossy(record.ext.as_ref()).into_owned(),
             height: record.height,
             state: record.state,
+            estimated_blocks_remaining: None,
+            estimated_seconds: None,
         }
     }
 }
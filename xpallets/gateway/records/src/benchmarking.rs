@@ -87,6 +87,52 @@ benchmarks! {
     verify {
         assert_eq!(XGatewayRecords::<T>::state_of(0), None);
     }
+
+    set_deposits_enabled {
+    }: _(RawOrigin::Root, Chain::Bitcoin, false)
+    verify {
+        assert!(!XGatewayRecords::<T>::deposits_enabled(Chain::Bitcoin));
+    }
+
+    set_withdrawal_rate_limit {
+        let limit = WithdrawalRateLimit {
+            max_count: MAXIMUM_WITHDRAWAL_RATE_COUNT,
+            max_value: 100_000_000u32.into(),
+            window: 100u32.into(),
+        };
+    }: _(RawOrigin::Root, ASSET_ID, Some(limit.clone()))
+    verify {
+        assert_eq!(XGatewayRecords::<T>::withdrawal_rate_limit_of(ASSET_ID), Some(limit));
+    }
+
+    set_deposit_policy {
+        let who: T::AccountId = whitelisted_caller();
+        let who_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(who.clone());
+        let destination = DepositDestination::Reserved(AssetType::Reserved);
+    }: _(RawOrigin::Root, who_lookup, ASSET_ID, Some(destination))
+    verify {
+        assert_eq!(XGatewayRecords::<T>::deposit_policy_of(who, ASSET_ID), Some(destination));
+    }
+
+    release_custody {
+        let who: T::AccountId = whitelisted_caller();
+        let who_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(who.clone());
+        XGatewayRecords::<T>::set_deposit_policy(
+            RawOrigin::Root.into(),
+            who_lookup.clone(),
+            ASSET_ID,
+            Some(DepositDestination::Reserved(AssetType::Reserved)),
+        )?;
+        let amount: BalanceOf<T> = 1_000u32.into();
+        deposit::<T>(who.clone(), amount);
+    }: _(RawOrigin::Root, who_lookup, ASSET_ID, amount)
+    verify {
+        assert_eq!(
+            xpallet_assets::Pallet::<T>::asset_balance_of(&who, &ASSET_ID, AssetType::Reserved),
+            Zero::zero()
+        );
+        assert_eq!(xpallet_assets::Pallet::<T>::usable_balance(&who, &ASSET_ID), amount);
+    }
 }
 
 #[cfg(test)]
@@ -101,6 +147,10 @@ mod tests {
             assert_ok!(Pallet::<Test>::test_benchmark_root_deposit());
             assert_ok!(Pallet::<Test>::test_benchmark_root_withdraw());
             assert_ok!(Pallet::<Test>::test_benchmark_set_withdrawal_state());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_deposits_enabled());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_withdrawal_rate_limit());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_deposit_policy());
+            assert_ok!(Pallet::<Test>::test_benchmark_release_custody());
         });
     }
 }
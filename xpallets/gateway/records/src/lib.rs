@@ -20,15 +20,20 @@ use frame_support::{
     log::{error, info},
     transactional,
 };
-use frame_system::ensure_root;
-use sp_runtime::traits::StaticLookup;
+use frame_system::{ensure_root, ensure_signed};
+use sp_runtime::traits::{Saturating, StaticLookup, Zero};
 
 use chainx_primitives::{AddrStr, AssetId};
+use xp_gateway_common::ExternalAddress;
 use xp_runtime::Memo;
-use xpallet_assets::{AssetType, BalanceOf, Chain};
+use xpallet_assets::{AssetType, BalanceOf, Chain, MoveCaller};
 use xpallet_support::try_addr;
 
-pub use self::types::{Withdrawal, WithdrawalRecord, WithdrawalRecordId, WithdrawalState};
+pub use self::types::{
+    DepositDestination, Withdrawal, WithdrawalAllowance, WithdrawalCost, WithdrawalEstimate,
+    WithdrawalRateLimit, WithdrawalRecord, WithdrawalRecordId, WithdrawalState,
+    MAXIMUM_WITHDRAWAL_RATE_COUNT,
+};
 pub use self::weights::WeightInfo;
 
 pub type WithdrawalRecordOf<T> = WithdrawalRecord<
@@ -37,6 +42,9 @@ pub type WithdrawalRecordOf<T> = WithdrawalRecord<
     <T as frame_system::Config>::BlockNumber,
 >;
 
+pub type WithdrawalRateLimitOf<T> =
+    WithdrawalRateLimit<BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
+
 pub use pallet::*;
 
 #[frame_support::pallet]
@@ -122,13 +130,117 @@ pub mod pallet {
             }
             Ok(())
         }
+
+        /// Enable or disable the deposits of the given chain.
+        ///
+        /// The withdrawals and transfers of the assets are not affected.
+        ///
+        /// This is a root-only operation.
+        #[pallet::weight(<T as Config>::WeightInfo::set_deposits_enabled())]
+        pub fn set_deposits_enabled(
+            origin: OriginFor<T>,
+            chain: Chain,
+            enabled: bool,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            DepositsEnabled::<T>::insert(chain, enabled);
+            Self::deposit_event(Event::<T>::DepositsEnabledUpdated(chain, enabled));
+            Ok(())
+        }
+
+        /// Set the rate limit of the withdrawal applications of each account for the asset.
+        ///
+        /// `None` removes the rate limit, which is the default.
+        ///
+        /// This is a root-only operation.
+        #[pallet::weight(<T as Config>::WeightInfo::set_withdrawal_rate_limit())]
+        pub fn set_withdrawal_rate_limit(
+            origin: OriginFor<T>,
+            #[pallet::compact] asset_id: AssetId,
+            limit: Option<WithdrawalRateLimitOf<T>>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            match limit {
+                Some(ref limit) => {
+                    ensure!(
+                        limit.max_count > 0
+                            && limit.max_count <= MAXIMUM_WITHDRAWAL_RATE_COUNT
+                            && !limit.window.is_zero(),
+                        Error::<T>::InvalidWithdrawalRateLimit
+                    );
+                    WithdrawalRateLimits::<T>::insert(asset_id, limit);
+                }
+                None => WithdrawalRateLimits::<T>::remove(asset_id),
+            }
+            Self::deposit_event(Event::<T>::WithdrawalRateLimitUpdated(asset_id, limit));
+            Ok(())
+        }
+
+        /// Set where the deposits of `asset_id` to `who` are credited, e.g. into a reserved
+        /// balance kept in custody until it's released by `release_custody`.
+        ///
+        /// `None` clears the policy, the deposits are credited to the free balance then.
+        ///
+        /// This can only be called by `who` itself or root.
+        #[pallet::weight(<T as Config>::WeightInfo::set_deposit_policy())]
+        pub fn set_deposit_policy(
+            origin: OriginFor<T>,
+            who: <T::Lookup as StaticLookup>::Source,
+            #[pallet::compact] asset_id: AssetId,
+            destination: Option<DepositDestination>,
+        ) -> DispatchResult {
+            let who = T::Lookup::lookup(who)?;
+            Self::ensure_account_or_root(origin, &who)?;
+            match destination {
+                None | Some(DepositDestination::Free) => DepositPolicy::<T>::remove(&who, asset_id),
+                Some(DepositDestination::Reserved(asset_type)) => {
+                    ensure!(
+                        asset_type == AssetType::Reserved,
+                        Error::<T>::InvalidDepositDestination
+                    );
+                    DepositPolicy::<T>::insert(
+                        &who,
+                        asset_id,
+                        DepositDestination::Reserved(asset_type),
+                    );
+                }
+            }
+            Self::deposit_event(Event::<T>::DepositPolicyUpdated(who, asset_id, destination));
+            Ok(())
+        }
+
+        /// Move the deposits of `asset_id` kept in custody for `who` to its free balance.
+        ///
+        /// This can only be called by `who` itself or root.
+        #[pallet::weight(<T as Config>::WeightInfo::release_custody())]
+        pub fn release_custody(
+            origin: OriginFor<T>,
+            who: <T::Lookup as StaticLookup>::Source,
+            #[pallet::compact] asset_id: AssetId,
+            #[pallet::compact] value: BalanceOf<T>,
+        ) -> DispatchResult {
+            let who = T::Lookup::lookup(who)?;
+            Self::ensure_account_or_root(origin, &who)?;
+            xpallet_assets::Pallet::<T>::move_balance(
+                &asset_id,
+                &who,
+                AssetType::Reserved,
+                &who,
+                AssetType::Usable,
+                value,
+                MoveCaller::Gateway,
+            )
+            .map_err::<xpallet_assets::Error<T>, _>(Into::into)?;
+            Self::deposit_event(Event::<T>::CustodyReleased(who, asset_id, value));
+            Ok(())
+        }
     }
 
     #[pallet::event]
     #[pallet::generate_deposit(pub(crate) fn deposit_event)]
     pub enum Event<T: Config> {
-        /// An account deposited some asset. [who, asset_id, amount]
-        Deposited(T::AccountId, AssetId, BalanceOf<T>),
+        /// An account deposited some asset. [who, asset_id, amount, asset_type]
+        Deposited(T::AccountId, AssetId, BalanceOf<T>, AssetType),
         /// A withdrawal application was created. [withdrawal_id, record_info]
         WithdrawalCreated(WithdrawalRecordId, WithdrawalRecordOf<T>),
         /// A withdrawal proposal was processed. [withdrawal_id]
@@ -139,6 +251,20 @@ pub mod pallet {
         WithdrawalCanceled(WithdrawalRecordId, WithdrawalState),
         /// A withdrawal proposal was finished successfully. [withdrawal_id, withdrawal_state]
         WithdrawalFinished(WithdrawalRecordId, WithdrawalState),
+        /// The deposits of a chain were enabled or disabled. [chain, enabled]
+        DepositsEnabledUpdated(Chain, bool),
+        /// The withdrawal rate limit of an asset was updated. [asset_id, limit]
+        WithdrawalRateLimitUpdated(AssetId, Option<WithdrawalRateLimitOf<T>>),
+        /// A withdrawal record was removed from the storage, carrying its full content for
+        /// the archive nodes. [withdrawal_id, record_info, withdrawal_state]
+        WithdrawalArchived(WithdrawalRecordId, WithdrawalRecordOf<T>, WithdrawalState),
+        /// The deposit policy of an account was updated. [who, asset_id, destination]
+        DepositPolicyUpdated(T::AccountId, AssetId, Option<DepositDestination>),
+        /// Some deposits kept in custody were released to the free balance. [who, asset_id, amount]
+        CustodyReleased(T::AccountId, AssetId, BalanceOf<T>),
+        /// The part of a finished withdrawal that was not actually sent out was refunded
+        /// to the applicant. [withdrawal_id, amount]
+        WithdrawalShortfallRefunded(WithdrawalRecordId, BalanceOf<T>),
     }
 
     #[pallet::error]
@@ -155,6 +281,14 @@ pub mod pallet {
         InvalidState,
         /// Meet unexpected chain
         UnexpectedChain,
+        /// Too many withdrawal applications within the rate limit window
+        TooManyWithdrawals,
+        /// The total withdrawal value within the rate limit window is too large
+        WithdrawalValueExceeded,
+        /// The rate limit must allow 1 to `MAXIMUM_WITHDRAWAL_RATE_COUNT` applications in a non-empty window
+        InvalidWithdrawalRateLimit,
+        /// The deposits can only be kept in custody in the `Reserved` asset type
+        InvalidDepositDestination,
     }
 
     #[pallet::type_value]
@@ -179,9 +313,62 @@ pub mod pallet {
     #[pallet::getter(fn state_of)]
     pub(crate) type WithdrawalStateOf<T: Config> =
         StorageMap<_, Twox64Concat, WithdrawalRecordId, WithdrawalState>;
+
+    /// The typed address of a pending withdrawal, validated when it was applied.
+    ///
+    /// Absent for the withdrawals applied with the raw address bytes only.
+    #[pallet::storage]
+    pub(crate) type WithdrawalAddressOf<T: Config> =
+        StorageMap<_, Twox64Concat, WithdrawalRecordId, ExternalAddress>;
+
+    #[pallet::type_value]
+    pub fn DefaultForDepositsEnabled() -> bool {
+        true
+    }
+
+    /// Whether the deposits of the chain are accepted.
+    #[pallet::storage]
+    #[pallet::getter(fn deposits_enabled)]
+    pub(crate) type DepositsEnabled<T: Config> =
+        StorageMap<_, Twox64Concat, Chain, bool, ValueQuery, DefaultForDepositsEnabled>;
+
+    /// The withdrawal rate limit of an asset, no limit if absent.
+    #[pallet::storage]
+    #[pallet::getter(fn withdrawal_rate_limit_of)]
+    pub(crate) type WithdrawalRateLimits<T: Config> =
+        StorageMap<_, Twox64Concat, AssetId, WithdrawalRateLimitOf<T>>;
+
+    /// The recent withdrawal applications `(height, value)` of an account for a rate limited asset.
+    ///
+    /// The entries out of the rate limit window are pruned on the next application.
+    #[pallet::storage]
+    #[pallet::getter(fn recent_withdrawals)]
+    pub(crate) type RecentWithdrawals<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        T::AccountId,
+        Twox64Concat,
+        AssetId,
+        Vec<(T::BlockNumber, BalanceOf<T>)>,
+        ValueQuery,
+    >;
+
+    /// Where the deposits of an account are credited, the free balance if absent.
+    #[pallet::storage]
+    #[pallet::getter(fn deposit_policy_of)]
+    pub(crate) type DepositPolicy<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, T::AccountId, Twox64Concat, AssetId, DepositDestination>;
 }
 
 impl<T: Config> Pallet<T> {
+    fn ensure_account_or_root(origin: T::Origin, who: &T::AccountId) -> DispatchResult {
+        if ensure_root(origin.clone()).is_err() {
+            let sender = ensure_signed(origin)?;
+            ensure!(sender == *who, DispatchError::BadOrigin);
+        }
+        Ok(())
+    }
+
     fn ensure_asset_belongs_to_chain(asset_id: AssetId, expected_chain: Chain) -> DispatchResult {
         let asset_chain = xpallet_assets_registrar::Pallet::<T>::chain_of(&asset_id)?;
         ensure!(asset_chain == expected_chain, Error::<T>::UnexpectedChain);
@@ -193,7 +380,7 @@ impl<T: Config> Pallet<T> {
         asset_id: AssetId,
         value: BalanceOf<T>,
     ) -> DispatchResult {
-        let available = xpallet_assets::Pallet::<T>::usable_balance(who, &asset_id);
+        let available = xpallet_assets::Pallet::<T>::spendable_balance(who, &asset_id);
         ensure!(
             available >= value,
             xpallet_assets::Error::<T>::InsufficientBalance
@@ -201,6 +388,47 @@ impl<T: Config> Pallet<T> {
         Ok(())
     }
 
+    /// Returns the recent withdrawals of `who` within the rate limit window of `asset_id`.
+    fn recent_withdrawals_in_window(
+        who: &T::AccountId,
+        asset_id: AssetId,
+        limit: &WithdrawalRateLimitOf<T>,
+        now: T::BlockNumber,
+    ) -> Vec<(T::BlockNumber, BalanceOf<T>)> {
+        let mut recent = Self::recent_withdrawals(who, asset_id);
+        recent.retain(|(height, _)| now < height.saturating_add(limit.window));
+        recent
+    }
+
+    /// Ensures the new withdrawal application is allowed by the rate limit of `asset_id`.
+    ///
+    /// Returns the recent withdrawals including the new one to record, `None` if not rate limited.
+    fn ensure_withdrawal_rate(
+        who: &T::AccountId,
+        asset_id: AssetId,
+        value: BalanceOf<T>,
+        now: T::BlockNumber,
+    ) -> Result<Option<Vec<(T::BlockNumber, BalanceOf<T>)>>, DispatchError> {
+        let limit = match Self::withdrawal_rate_limit_of(asset_id) {
+            Some(limit) => limit,
+            None => return Ok(None),
+        };
+        let mut recent = Self::recent_withdrawals_in_window(who, asset_id, &limit, now);
+        ensure!(
+            (recent.len() as u32) < limit.max_count,
+            Error::<T>::TooManyWithdrawals
+        );
+        let total = recent
+            .iter()
+            .fold(BalanceOf::<T>::zero(), |acc, (_, v)| acc.saturating_add(*v));
+        ensure!(
+            total.saturating_add(value) <= limit.max_value,
+            Error::<T>::WithdrawalValueExceeded
+        );
+        recent.push((now, value));
+        Ok(Some(recent))
+    }
+
     fn ensure_withdrawal_records_exists(
         id: WithdrawalRecordId,
     ) -> Result<(WithdrawalRecordOf<T>, WithdrawalState), DispatchError> {
@@ -224,7 +452,26 @@ impl<T: Config> Pallet<T> {
         );
 
         xpallet_assets::Pallet::<T>::issue(&asset_id, who, balance, true)?;
-        Self::deposit_event(Event::<T>::Deposited(who.clone(), asset_id, balance));
+        let destination = Self::deposit_policy_of(who, asset_id).unwrap_or_default();
+        let asset_type = destination.asset_type();
+        if asset_type != AssetType::Usable {
+            xpallet_assets::Pallet::<T>::move_balance(
+                &asset_id,
+                who,
+                AssetType::Usable,
+                who,
+                asset_type,
+                balance,
+                MoveCaller::Gateway,
+            )
+            .map_err::<xpallet_assets::Error<T>, _>(Into::into)?;
+        }
+        Self::deposit_event(Event::<T>::Deposited(
+            who.clone(),
+            asset_id,
+            balance,
+            asset_type,
+        ));
         Ok(())
     }
 
@@ -239,9 +486,37 @@ impl<T: Config> Pallet<T> {
         balance: BalanceOf<T>,
         addr: AddrStr,
         ext: Memo,
+    ) -> DispatchResult {
+        Self::apply_withdrawal(who, asset_id, balance, addr, None, ext)
+    }
+
+    /// Withdrawal asset to the `address` validated by the target chain, which is
+    /// `addr` in its typed form.
+    ///
+    /// WithdrawalRecord State: `Applying`
+    pub fn withdraw_to_address(
+        who: &T::AccountId,
+        asset_id: AssetId,
+        balance: BalanceOf<T>,
+        addr: AddrStr,
+        address: ExternalAddress,
+        ext: Memo,
+    ) -> DispatchResult {
+        Self::apply_withdrawal(who, asset_id, balance, addr, Some(address), ext)
+    }
+
+    fn apply_withdrawal(
+        who: &T::AccountId,
+        asset_id: AssetId,
+        balance: BalanceOf<T>,
+        addr: AddrStr,
+        address: Option<ExternalAddress>,
+        ext: Memo,
     ) -> DispatchResult {
         xpallet_assets::Pallet::<T>::ensure_not_native_asset(&asset_id)?;
         Self::ensure_withdrawal_available_balance(who, asset_id, balance)?;
+        let height = frame_system::Pallet::<T>::block_number();
+        let recent_withdrawals = Self::ensure_withdrawal_rate(who, asset_id, balance, height)?;
 
         let id = Self::id();
         info!(
@@ -254,7 +529,6 @@ impl<T: Config> Pallet<T> {
             try_addr(&addr),
             ext
         );
-        let height = frame_system::Pallet::<T>::block_number();
         let record =
             WithdrawalRecordOf::<T>::new(who.clone(), asset_id, balance, addr, ext, height);
 
@@ -264,8 +538,14 @@ impl<T: Config> Pallet<T> {
         // Set storages
         PendingWithdrawals::<T>::insert(id, record.clone());
         WithdrawalStateOf::<T>::insert(id, WithdrawalState::Applying);
+        if let Some(address) = address {
+            WithdrawalAddressOf::<T>::insert(id, address);
+        }
         let next_id = id.checked_add(1_u32).unwrap_or(0);
         NextWithdrawalRecordId::<T>::put(next_id);
+        if let Some(recent) = recent_withdrawals {
+            RecentWithdrawals::<T>::insert(who, asset_id, recent);
+        }
 
         Self::deposit_event(Event::<T>::WithdrawalCreated(id, record));
         Ok(())
@@ -372,7 +652,9 @@ impl<T: Config> Pallet<T> {
         // Remove storage
         PendingWithdrawals::<T>::remove(id);
         WithdrawalStateOf::<T>::remove(id);
+        WithdrawalAddressOf::<T>::remove(id);
 
+        Self::deposit_event(Event::<T>::WithdrawalArchived(id, record, new_state));
         Self::deposit_event(Event::<T>::WithdrawalCanceled(id, new_state));
         Ok(())
     }
@@ -398,7 +680,23 @@ impl<T: Config> Pallet<T> {
         if let Some(chain) = expected_chain {
             Self::ensure_asset_belongs_to_chain(record.asset_id(), chain)?;
         }
-        Self::finish_withdrawal_impl(id, record, curr_state, WithdrawalState::NormalFinish)
+        let sent = record.balance();
+        Self::finish_withdrawal_impl(id, record, curr_state, WithdrawalState::NormalFinish, sent)
+    }
+
+    /// Finish withdrawal given the value actually sent out on the target chain, measured
+    /// like the record balance, i.e., with the withdrawal fee included.
+    ///
+    /// Only the `sent` part of the reserved withdrawal asset is destroyed, the shortfall
+    /// is refunded to the usable balance of the applicant.
+    ///
+    /// WithdrawalRecord State: `Processing` ==> `NormalFinish`
+    pub fn finish_withdrawal_with_sent(
+        id: WithdrawalRecordId,
+        sent: BalanceOf<T>,
+    ) -> DispatchResult {
+        let (record, curr_state) = Self::ensure_withdrawal_records_exists(id)?;
+        Self::finish_withdrawal_impl(id, record, curr_state, WithdrawalState::NormalFinish, sent)
     }
 
     fn finish_withdrawal_impl(
@@ -406,6 +704,7 @@ impl<T: Config> Pallet<T> {
         record: WithdrawalRecordOf<T>,
         curr_state: WithdrawalState,
         new_state: WithdrawalState,
+        sent: BalanceOf<T>,
     ) -> DispatchResult {
         if curr_state != WithdrawalState::Processing {
             error!(
@@ -416,13 +715,21 @@ impl<T: Config> Pallet<T> {
             return Err(Error::<T>::NotProcessingState.into());
         }
 
-        // Destroy locked asset
-        Self::destroy(record.applicant(), record.asset_id(), record.balance())?;
+        // Destroy locked asset, refund what was not sent out.
+        let sent = sent.min(record.balance());
+        let shortfall = record.balance() - sent;
+        Self::destroy(record.applicant(), record.asset_id(), sent)?;
+        if !shortfall.is_zero() {
+            Self::unlock(record.applicant(), record.asset_id(), shortfall)?;
+            Self::deposit_event(Event::<T>::WithdrawalShortfallRefunded(id, shortfall));
+        }
 
         // Remove storage
         PendingWithdrawals::<T>::remove(id);
         WithdrawalStateOf::<T>::remove(id);
+        WithdrawalAddressOf::<T>::remove(id);
 
+        Self::deposit_event(Event::<T>::WithdrawalArchived(id, record, new_state));
         Self::deposit_event(Event::<T>::WithdrawalFinished(id, new_state));
         Ok(())
     }
@@ -464,12 +771,14 @@ impl<T: Config> Pallet<T> {
                 // State: `Applying` ==> `Processing` ==> `NormalFinish`|`RootFinish`
                 Self::process_withdrawal_impl(id, curr_state)?;
                 let curr_state = Self::state_of(id).ok_or(Error::<T>::NotExisted)?;
-                Self::finish_withdrawal_impl(id, record, curr_state, new_state)
+                let sent = record.balance();
+                Self::finish_withdrawal_impl(id, record, curr_state, new_state, sent)
             }
             (WithdrawalState::Processing, WithdrawalState::NormalFinish)
             | (WithdrawalState::Processing, WithdrawalState::RootFinish) => {
                 // State: `Processing` ==> `NormalFinish`|`RootFinish`
-                Self::finish_withdrawal_impl(id, record, curr_state, new_state)
+                let sent = record.balance();
+                Self::finish_withdrawal_impl(id, record, curr_state, new_state, sent)
             }
             _ => {
                 error!(
@@ -521,6 +830,7 @@ impl<T: Config> Pallet<T> {
             who,
             AssetType::ReservedWithdrawal,
             value,
+            MoveCaller::Gateway,
         )
         .map_err::<xpallet_assets::Error<T>, _>(Into::into)?;
         Ok(())
@@ -534,6 +844,7 @@ impl<T: Config> Pallet<T> {
             who,
             AssetType::Usable,
             value,
+            MoveCaller::Gateway,
         )
         .map_err::<xpallet_assets::Error<T>, _>(Into::into)?;
         Ok(())
@@ -544,6 +855,17 @@ impl<T: Config> Pallet<T> {
         Ok(())
     }
 
+    /// Returns the address of the pending withdrawal `id`.
+    ///
+    /// The withdrawals applied before the addresses were typed are read as
+    /// `ExternalAddress::Named` with their raw address bytes.
+    pub fn withdrawal_address(id: WithdrawalRecordId) -> Option<ExternalAddress> {
+        WithdrawalAddressOf::<T>::get(id).or_else(|| {
+            Self::pending_withdrawals(id)
+                .map(|record| ExternalAddress::Named(record.addr().clone()))
+        })
+    }
+
     #[inline]
     pub fn pending_withdrawal_set(
     ) -> impl Iterator<Item = (WithdrawalRecordId, WithdrawalRecordOf<T>)> {
@@ -578,4 +900,21 @@ impl<T: Config> Pallet<T> {
     pub fn withdrawal_state_insert(id: WithdrawalRecordId, state: WithdrawalState) {
         WithdrawalStateOf::<T>::insert(id, state)
     }
+
+    /// Returns the remaining withdrawal allowance of `who` for `asset_id`, `None` if not rate limited.
+    pub fn withdrawal_allowance(
+        who: &T::AccountId,
+        asset_id: AssetId,
+    ) -> Option<WithdrawalAllowance<BalanceOf<T>>> {
+        let limit = Self::withdrawal_rate_limit_of(asset_id)?;
+        let now = frame_system::Pallet::<T>::block_number();
+        let recent = Self::recent_withdrawals_in_window(who, asset_id, &limit, now);
+        let total = recent
+            .iter()
+            .fold(BalanceOf::<T>::zero(), |acc, (_, v)| acc.saturating_add(*v));
+        Some(WithdrawalAllowance {
+            remaining_count: limit.max_count.saturating_sub(recent.len() as u32),
+            remaining_value: limit.max_value.saturating_sub(total),
+        })
+    }
 }
@@ -55,7 +55,7 @@ impl frame_system::Config for Test {
     type AccountId = u64;
     type Lookup = IdentityLookup<Self::AccountId>;
     type Header = Header;
-    type Event = ();
+    type Event = Event;
     type BlockHashCount = BlockHashCount;
     type DbWeight = ();
     type Version = ();
@@ -77,7 +77,7 @@ impl pallet_balances::Config for Test {
     type MaxLocks = ();
     type Balance = Balance;
     type DustRemoval = ();
-    type Event = ();
+    type Event = Event;
     type ExistentialDeposit = ExistentialDeposit;
     type AccountStore = System;
     type WeightInfo = ();
@@ -91,23 +91,26 @@ parameter_types! {
 }
 
 impl xpallet_assets_registrar::Config for Test {
-    type Event = ();
+    type Event = Event;
     type NativeAssetId = ChainXAssetId;
     type RegistrarHandler = ();
     type WeightInfo = ();
 }
 
 impl xpallet_assets::Config for Test {
-    type Event = ();
+    type Event = Event;
     type Currency = Balances;
     type TreasuryAccount = ();
     type OnCreatedAccount = frame_system::Provider<Test>;
     type OnAssetChanged = ();
+    type OnReceived = ();
+    type MoveGuard = ();
+    type QuarantineOrigin = frame_system::EnsureRoot<AccountId>;
     type WeightInfo = ();
 }
 
 impl Config for Test {
-    type Event = ();
+    type Event = Event;
     type WeightInfo = ();
 }
 
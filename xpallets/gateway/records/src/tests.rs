@@ -135,6 +135,26 @@ fn test_withdrawal_more_then_usable() {
     })
 }
 
+#[test]
+fn test_withdrawal_excludes_held_balance() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XGatewayRecords::deposit(&ALICE, X_BTC, 100));
+
+        // Another module holds 60 of the usable balance in the same block, the withdrawal
+        // used to be checked against the unchanged usable balance and pass as well.
+        assert_ok!(XAssets::hold(&X_BTC, &ALICE, 60));
+        assert_eq!(XAssets::usable_balance(&ALICE, &X_BTC), 100);
+        assert_noop!(
+            t_withdraw(ALICE, 50),
+            xpallet_assets::Error::<Test>::InsufficientBalance
+        );
+        assert_ok!(t_withdraw(ALICE, 40));
+
+        XAssets::release_hold(&X_BTC, &ALICE, 60);
+        assert_ok!(t_withdraw(ALICE, 60));
+    })
+}
+
 #[test]
 fn test_withdrawal_force_set_state() {
     ExtBuilder::default().build_and_execute(|| {
@@ -206,3 +226,251 @@ fn test_withdrawal_chainx() {
         );
     })
 }
+
+#[test]
+fn test_set_deposits_enabled() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert!(XGatewayRecords::deposits_enabled(Chain::Bitcoin));
+
+        assert_noop!(
+            XGatewayRecords::set_deposits_enabled(Origin::signed(ALICE), Chain::Bitcoin, false),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(XGatewayRecords::set_deposits_enabled(
+            RawOrigin::Root.into(),
+            Chain::Bitcoin,
+            false
+        ));
+        assert!(!XGatewayRecords::deposits_enabled(Chain::Bitcoin));
+        assert!(XGatewayRecords::deposits_enabled(Chain::Ethereum));
+
+        assert_ok!(XGatewayRecords::set_deposits_enabled(
+            RawOrigin::Root.into(),
+            Chain::Bitcoin,
+            true
+        ));
+        assert!(XGatewayRecords::deposits_enabled(Chain::Bitcoin));
+    })
+}
+
+fn t_withdraw(who: AccountId, value: Balance) -> DispatchResult {
+    XGatewayRecords::withdraw(&who, X_BTC, value, b"addr".to_vec(), b"ext".to_vec().into())
+}
+
+#[test]
+fn test_withdrawal_rate_limit() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XGatewayRecords::deposit(&ALICE, X_BTC, 100));
+        assert_eq!(XGatewayRecords::withdrawal_allowance(&ALICE, X_BTC), None);
+
+        let limit = WithdrawalRateLimit {
+            max_count: 2,
+            max_value: 50,
+            window: 10,
+        };
+        assert_noop!(
+            XGatewayRecords::set_withdrawal_rate_limit(
+                RawOrigin::Root.into(),
+                X_BTC,
+                Some(WithdrawalRateLimit {
+                    max_count: MAXIMUM_WITHDRAWAL_RATE_COUNT + 1,
+                    ..limit.clone()
+                })
+            ),
+            Error::<Test>::InvalidWithdrawalRateLimit
+        );
+        assert_ok!(XGatewayRecords::set_withdrawal_rate_limit(
+            RawOrigin::Root.into(),
+            X_BTC,
+            Some(limit)
+        ));
+
+        // Exceed the value limit.
+        assert_ok!(t_withdraw(ALICE, 30));
+        assert_noop!(
+            t_withdraw(ALICE, 21),
+            Error::<Test>::WithdrawalValueExceeded
+        );
+        assert_eq!(
+            XGatewayRecords::withdrawal_allowance(&ALICE, X_BTC),
+            Some(WithdrawalAllowance {
+                remaining_count: 1,
+                remaining_value: 20,
+            })
+        );
+
+        // Exceed the count limit.
+        System::set_block_number(5);
+        assert_ok!(t_withdraw(ALICE, 10));
+        assert_noop!(t_withdraw(ALICE, 1), Error::<Test>::TooManyWithdrawals);
+        // The other accounts and assets are limited separately.
+        assert_ok!(t_withdraw(BOB, 50));
+        assert_ok!(XGatewayRecords::withdraw(
+            &ALICE,
+            X_ETH,
+            100,
+            b"addr".to_vec(),
+            b"ext".to_vec().into()
+        ));
+
+        // The application at block 1 leaves the window at block 11.
+        System::set_block_number(11);
+        assert_eq!(
+            XGatewayRecords::withdrawal_allowance(&ALICE, X_BTC),
+            Some(WithdrawalAllowance {
+                remaining_count: 1,
+                remaining_value: 40,
+            })
+        );
+        assert_noop!(
+            t_withdraw(ALICE, 41),
+            Error::<Test>::WithdrawalValueExceeded
+        );
+        assert_ok!(t_withdraw(ALICE, 40));
+        assert_eq!(
+            XGatewayRecords::recent_withdrawals(&ALICE, X_BTC),
+            vec![(5, 10), (11, 40)]
+        );
+
+        // Removing the limit restores the unlimited withdrawals.
+        assert_ok!(XGatewayRecords::set_withdrawal_rate_limit(
+            RawOrigin::Root.into(),
+            X_BTC,
+            None
+        ));
+        assert_eq!(XGatewayRecords::withdrawal_allowance(&ALICE, X_BTC), None);
+        assert_ok!(t_withdraw(ALICE, 20));
+    })
+}
+
+#[test]
+fn test_withdrawal_archived_on_removal() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(t_withdraw(ALICE, 30));
+        assert_ok!(t_withdraw(ALICE, 20));
+        let record = XGatewayRecords::pending_withdrawals(0).unwrap();
+
+        assert_ok!(XGatewayRecords::process_withdrawals(&[0], Chain::Bitcoin));
+        assert_ok!(XGatewayRecords::finish_withdrawal(0, None));
+        assert_ok!(XGatewayRecords::cancel_withdrawal(1, &ALICE));
+        assert!(XGatewayRecords::pending_withdrawals(0).is_none());
+        assert!(XGatewayRecords::pending_withdrawals(1).is_none());
+
+        let archived = System::events()
+            .into_iter()
+            .filter_map(|record| match record.event {
+                crate::mock::Event::XGatewayRecords(crate::Event::WithdrawalArchived(
+                    id,
+                    record,
+                    state,
+                )) => Some((id, record, state)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(archived.len(), 2);
+        assert_eq!(archived[0], (0, record, WithdrawalState::NormalFinish));
+        assert_eq!(archived[1].0, 1);
+        assert_eq!(archived[1].1.balance(), 20);
+        assert_eq!(archived[1].2, WithdrawalState::NormalCancel);
+    })
+}
+
+#[test]
+fn test_deposit_policy() {
+    ExtBuilder::default().build_and_execute(|| {
+        let custody = DepositDestination::Reserved(AssetType::Reserved);
+
+        // Credited to the free balance by default.
+        assert_ok!(XGatewayRecords::deposit(&ALICE, X_BTC, 100));
+        assert_eq!(XAssets::usable_balance(&ALICE, &X_BTC), 100 + 100);
+
+        assert_noop!(
+            XGatewayRecords::set_deposit_policy(Origin::signed(BOB), ALICE, X_BTC, Some(custody)),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            XGatewayRecords::set_deposit_policy(
+                Origin::signed(ALICE),
+                ALICE,
+                X_BTC,
+                Some(DepositDestination::Reserved(AssetType::ReservedWithdrawal))
+            ),
+            Error::<Test>::InvalidDepositDestination
+        );
+        assert_ok!(XGatewayRecords::set_deposit_policy(
+            Origin::signed(ALICE),
+            ALICE,
+            X_BTC,
+            Some(custody)
+        ));
+        assert_eq!(
+            XGatewayRecords::deposit_policy_of(ALICE, X_BTC),
+            Some(custody)
+        );
+
+        // Credited to the reserved balance with the policy set.
+        assert_ok!(XGatewayRecords::deposit(&ALICE, X_BTC, 50));
+        assert_eq!(XAssets::usable_balance(&ALICE, &X_BTC), 100 + 100);
+        assert_eq!(
+            XAssets::asset_balance_of(&ALICE, &X_BTC, AssetType::Reserved),
+            50
+        );
+
+        // The custody is released by the account itself or root.
+        assert_noop!(
+            XGatewayRecords::release_custody(Origin::signed(BOB), ALICE, X_BTC, 20),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(XGatewayRecords::release_custody(
+            Origin::signed(ALICE),
+            ALICE,
+            X_BTC,
+            20
+        ));
+        assert_ok!(XGatewayRecords::release_custody(
+            RawOrigin::Root.into(),
+            ALICE,
+            X_BTC,
+            30
+        ));
+        assert_eq!(XAssets::usable_balance(&ALICE, &X_BTC), 100 + 100 + 50);
+        assert_eq!(
+            XAssets::asset_balance_of(&ALICE, &X_BTC, AssetType::Reserved),
+            0
+        );
+        assert!(XGatewayRecords::release_custody(Origin::signed(ALICE), ALICE, X_BTC, 1).is_err());
+
+        // Credited to the free balance again once the policy is cleared.
+        assert_ok!(XGatewayRecords::set_deposit_policy(
+            RawOrigin::Root.into(),
+            ALICE,
+            X_BTC,
+            None
+        ));
+        assert_eq!(XGatewayRecords::deposit_policy_of(ALICE, X_BTC), None);
+        assert_ok!(XGatewayRecords::deposit(&ALICE, X_BTC, 10));
+        assert_eq!(XAssets::usable_balance(&ALICE, &X_BTC), 100 + 100 + 50 + 10);
+    })
+}
+
+#[test]
+fn test_finish_withdrawal_with_sent() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(t_withdraw(ALICE, 30));
+        assert_ok!(XGatewayRecords::process_withdrawals(&[0], Chain::Bitcoin));
+        let issuance = XAssets::total_issuance(&X_BTC);
+
+        // Only 25 of 30 was sent out, the shortfall goes back to the usable balance.
+        assert_ok!(XGatewayRecords::finish_withdrawal_with_sent(0, 25));
+        assert!(XGatewayRecords::pending_withdrawals(0).is_none());
+        assert_eq!(XAssets::usable_balance(&ALICE, &X_BTC), 100 - 30 + 5);
+        assert_eq!(
+            XAssets::asset_balance_of(&ALICE, &X_BTC, AssetType::ReservedWithdrawal),
+            0
+        );
+        assert_eq!(XAssets::total_issuance(&X_BTC), issuance - 25);
+        System::assert_has_event(crate::mock::Event::XGatewayRecords(
+            crate::Event::WithdrawalShortfallRefunded(0, 5),
+        ));
+    })
+}
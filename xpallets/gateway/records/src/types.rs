@@ -9,10 +9,14 @@ use sp_runtime::RuntimeDebug;
 
 use chainx_primitives::{AddrStr, AssetId};
 use xp_runtime::Memo;
+use xpallet_assets::AssetType;
 
 /// The id of withdrawal record (u32 is enough).
 pub type WithdrawalRecordId = u32;
 
+/// Maximum number of withdrawal applications allowed by a rate limit.
+pub const MAXIMUM_WITHDRAWAL_RATE_COUNT: u32 = 32;
+
 /// The state machine of WithdrawState:
 ///
 /// Applying (lock token) <---> Processing (can't cancel, but can be recovered to `Applying`)
@@ -121,3 +125,78 @@ impl<AccountId, Balance, BlockNumber> Withdrawal<AccountId, Balance, BlockNumber
         }
     }
 }
+
+/// Rate limit of the withdrawal applications of each account for a token.
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct WithdrawalRateLimit<Balance, BlockNumber> {
+    /// Maximum number of applications within the window.
+    pub max_count: u32,
+    /// Maximum total value of applications within the window.
+    pub max_value: Balance,
+    /// Length of the rolling window in blocks.
+    pub window: BlockNumber,
+}
+
+/// Remaining withdrawal allowance of an account for a token in the current window.
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct WithdrawalAllowance<Balance> {
+    pub remaining_count: u32,
+    pub remaining_value: Balance,
+}
+
+/// The estimated time until a pending withdrawal is sent and confirmed on the issuing chain.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct WithdrawalEstimate {
+    /// The estimated number of ChainX blocks remaining.
+    pub blocks_remaining: u32,
+    /// The estimated seconds remaining.
+    pub seconds: u64,
+}
+
+/// The estimated cost of applying a withdrawal.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct WithdrawalCost<Balance> {
+    /// The ChainX transaction fee of the withdrawal call, in PCX.
+    pub chainx_fee: Balance,
+    /// The withdrawal fee charged by the bridge, in the withdrawn asset.
+    pub withdrawal_fee: Balance,
+    /// The estimated share of the network fee on the issuing chain, in the withdrawn asset,
+    /// which is paid by the trustees out of the withdrawal fees.
+    pub network_fee: Balance,
+}
+
+/// Where the deposits of an account are credited.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum DepositDestination {
+    /// The free balance, which is the default.
+    Free,
+    /// A reserved balance, which is released to the free balance by `release_custody`.
+    ///
+    /// Only `AssetType::Reserved` is accepted for now.
+    Reserved(AssetType),
+}
+
+impl Default for DepositDestination {
+    fn default() -> Self {
+        DepositDestination::Free
+    }
+}
+
+impl DepositDestination {
+    /// Returns the asset type the deposits are credited to.
+    pub fn asset_type(&self) -> AssetType {
+        match self {
+            DepositDestination::Free => AssetType::Usable,
+            DepositDestination::Reserved(asset_type) => *asset_type,
+        }
+    }
+}
@@ -35,6 +35,10 @@ pub trait WeightInfo {
     fn root_withdraw() -> Weight;
     fn set_withdrawal_state() -> Weight;
     fn set_withdrawal_state_list(u: u32) -> Weight;
+    fn set_deposits_enabled() -> Weight;
+    fn set_withdrawal_rate_limit() -> Weight;
+    fn set_deposit_policy() -> Weight;
+    fn release_custody() -> Weight;
 }
 
 /// Weights for xpallet_gateway_records using the Substrate node and recommended hardware.
@@ -46,9 +50,9 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
             .saturating_add(T::DbWeight::get().writes(5 as Weight))
     }
     fn root_withdraw() -> Weight {
-        (109_687_000 as Weight)
-            .saturating_add(T::DbWeight::get().reads(5 as Weight))
-            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+        (112_389_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(7 as Weight))
+            .saturating_add(T::DbWeight::get().writes(6 as Weight))
     }
     fn set_withdrawal_state() -> Weight {
         (121_624_000 as Weight)
@@ -60,6 +64,28 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
             .saturating_add(T::DbWeight::get().reads(8 as Weight))
             .saturating_add(T::DbWeight::get().writes(6 as Weight))
     }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_deposits_enabled` benchmark.
+    fn set_deposits_enabled() -> Weight {
+        (3_021_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_withdrawal_rate_limit` benchmark.
+    fn set_withdrawal_rate_limit() -> Weight {
+        (3_254_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_deposit_policy` benchmark.
+    fn set_deposit_policy() -> Weight {
+        (3_512_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `release_custody` benchmark.
+    fn release_custody() -> Weight {
+        (58_364_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
 }
 
 // For backwards compatibility and tests
@@ -70,9 +96,9 @@ impl WeightInfo for () {
             .saturating_add(RocksDbWeight::get().writes(5 as Weight))
     }
     fn root_withdraw() -> Weight {
-        (109_687_000 as Weight)
-            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
-            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+        (112_389_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(7 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(6 as Weight))
     }
     fn set_withdrawal_state() -> Weight {
         (121_624_000 as Weight)
@@ -84,4 +110,26 @@ impl WeightInfo for () {
             .saturating_add(RocksDbWeight::get().reads(8 as Weight))
             .saturating_add(RocksDbWeight::get().writes(6 as Weight))
     }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_deposits_enabled` benchmark.
+    fn set_deposits_enabled() -> Weight {
+        (3_021_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_withdrawal_rate_limit` benchmark.
+    fn set_withdrawal_rate_limit() -> Weight {
+        (3_254_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_deposit_policy` benchmark.
+    fn set_deposit_policy() -> Weight {
+        (3_512_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `release_custody` benchmark.
+    fn release_custody() -> Weight {
+        (58_364_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
 }
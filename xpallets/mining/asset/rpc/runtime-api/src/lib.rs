@@ -11,11 +11,17 @@ use codec::Codec;
 
 pub use chainx_primitives::AssetId;
 pub use xpallet_mining_asset::{
-    AssetLedger, MinerLedger, MiningAssetInfo, MiningDividendInfo, MiningWeight,
+    AssetLedger, InvariantCursor, InvariantReport, InvariantViolation, MinerLedger,
+    MiningAssetInfo, MiningAssetInfoV3, MiningDividendInfo, MiningWeight, PseduClaimable,
 };
 
 sp_api::decl_runtime_apis! {
     /// The API to query mining asset info.
+    ///
+    /// Version 2 adds `check_invariants`.
+    /// Version 3 adds `psedu_claimable`.
+    /// Version 4 adds the claim switches to `MiningAssetInfo`.
+    #[api_version(4)]
     pub trait XMiningAssetApi<AccountId, Balance, MiningWeight, BlockNumber>
     where
         AccountId: Codec,
@@ -26,10 +32,24 @@ sp_api::decl_runtime_apis! {
         /// Get overall information about all mining assets.
         fn mining_assets() -> Vec<MiningAssetInfo<AccountId, Balance, MiningWeight, BlockNumber>>;
 
+        #[changed_in(4)]
+        fn mining_assets() -> Vec<MiningAssetInfoV3<AccountId, Balance, MiningWeight, BlockNumber>>;
+
         /// Get the asset mining dividends info given the asset miner AccountId.
         fn mining_dividend(who: AccountId) -> BTreeMap<AssetId, MiningDividendInfo<Balance>>;
 
         /// Get the mining ledger details given the asset miner AccountId.
         fn miner_ledger(who: AccountId) -> BTreeMap<AssetId, MinerLedger<MiningWeight, BlockNumber>>;
+
+        /// Check the asset balances and mining weights of `asset_id`, walking at most
+        /// `max_accounts` storage entries, resumed from `cursor` if any.
+        fn check_invariants(
+            asset_id: AssetId,
+            max_accounts: u32,
+            cursor: Option<InvariantCursor<Balance, BlockNumber>>,
+        ) -> InvariantReport<Balance, BlockNumber>;
+
+        /// Estimate the asset mining dividends of `who` as if claimed in the next block.
+        fn psedu_claimable(who: AccountId) -> Vec<PseduClaimable<Balance, MiningWeight>>;
     }
 }
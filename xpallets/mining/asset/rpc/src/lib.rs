@@ -10,14 +10,16 @@ use std::sync::Arc;
 use codec::Codec;
 use jsonrpc_derive::rpc;
 
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_runtime::{generic::BlockId, traits::Block as BlockT};
 
-use xp_rpc::{runtime_error_into_rpc_err, Result, RpcBalance, RpcMiningWeight};
+use xp_rpc::{
+    runtime_error_into_rpc_err, unsupported_api_rpc_err, Result, RpcBalance, RpcMiningWeight,
+};
 
 use xpallet_mining_asset_rpc_runtime_api::{
-    AssetId, AssetLedger, MinerLedger, MiningAssetInfo, MiningDividendInfo,
+    AssetId, AssetLedger, MinerLedger, MiningAssetInfo, MiningDividendInfo, PseduClaimable,
     XMiningAssetApi as XMiningAssetRuntimeApi,
 };
 
@@ -59,6 +61,14 @@ where
         who: AccountId,
         at: Option<BlockHash>,
     ) -> Result<BTreeMap<AssetId, MinerLedger<RpcMiningWeight<MiningWeight>, BlockNumber>>>;
+
+    /// Get the asset mining dividends of the miner estimated for a claim in the next block.
+    #[rpc(name = "xminingasset_getPseduClaimable")]
+    fn psedu_claimable(
+        &self,
+        who: AccountId,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<PseduClaimable<RpcBalance<Balance>, RpcMiningWeight<MiningWeight>>>>;
 }
 
 /// A struct that implements the [`XMiningAssetApi`].
@@ -77,6 +87,34 @@ impl<C, B> XMiningAsset<C, B> {
     }
 }
 
+impl<C, Block> XMiningAsset<C, Block>
+where
+    Block: BlockT,
+    C: ProvideRuntimeApi<Block>,
+{
+    /// Returns true if the runtime at `at` provides an `XMiningAssetApi` of `version` or newer.
+    fn has_api_version<AccountId, Balance, MiningWeight, BlockNumber>(
+        &self,
+        at: &BlockId<Block>,
+        version: u32,
+    ) -> Result<bool>
+    where
+        C::Api: XMiningAssetRuntimeApi<Block, AccountId, Balance, MiningWeight, BlockNumber>,
+        AccountId: Codec,
+        Balance: Codec,
+        MiningWeight: Codec,
+        BlockNumber: Codec,
+    {
+        self.client
+            .runtime_api()
+            .has_api_with::<dyn XMiningAssetRuntimeApi<Block, AccountId, Balance, MiningWeight, BlockNumber>, _>(
+                at,
+                |v| v >= version,
+            )
+            .map_err(runtime_error_into_rpc_err)
+    }
+}
+
 impl<C, Block, AccountId, Balance, MiningWeight, BlockNumber>
     XMiningAssetApi<<Block as BlockT>::Hash, AccountId, Balance, MiningWeight, BlockNumber>
     for XMiningAsset<C, Block>
@@ -104,7 +142,20 @@ where
     > {
         let api = self.client.runtime_api();
         let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
-        api.mining_assets(&at)
+        let mining_assets =
+            if self.has_api_version::<AccountId, Balance, MiningWeight, BlockNumber>(&at, 4)? {
+                api.mining_assets(&at)
+            } else {
+                #[allow(deprecated)]
+                let mining_assets = api.mining_assets_before_version_4(&at);
+                mining_assets.map(|mining_assets| {
+                    mining_assets
+                        .into_iter()
+                        .map(Into::into)
+                        .collect::<Vec<_>>()
+                })
+            };
+        mining_assets
             .map(|mining_assets| {
                 mining_assets
                     .into_iter()
@@ -122,6 +173,8 @@ where
                                 .ledger_info
                                 .last_total_mining_weight_update,
                         },
+                        claim_paused: mining_asset.claim_paused,
+                        claim_enabled: mining_asset.claim_enabled,
                     })
                     .collect::<Vec<_>>()
             })
@@ -179,4 +232,34 @@ where
             })
             .map_err(runtime_error_into_rpc_err)
     }
+
+    fn psedu_claimable(
+        &self,
+        who: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<PseduClaimable<RpcBalance<Balance>, RpcMiningWeight<MiningWeight>>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        if !self.has_api_version::<AccountId, Balance, MiningWeight, BlockNumber>(&at, 3)? {
+            return Err(unsupported_api_rpc_err("XMiningAssetApi", 3));
+        }
+        api.psedu_claimable(&at, who)
+            .map(|claimables| {
+                claimables
+                    .into_iter()
+                    .map(|claimable| PseduClaimable {
+                        asset_id: claimable.asset_id,
+                        precision: claimable.precision,
+                        balance: claimable.balance.into(),
+                        mining_weight: claimable.mining_weight.into(),
+                        total_mining_weight: claimable.total_mining_weight.into(),
+                        reward_pot_balance: claimable.reward_pot_balance.into(),
+                        dividend: claimable.dividend.into(),
+                        insufficient_stake: claimable.insufficient_stake.into(),
+                        claim_paused: claimable.claim_paused,
+                    })
+                    .collect()
+            })
+            .map_err(runtime_error_into_rpc_err)
+    }
 }
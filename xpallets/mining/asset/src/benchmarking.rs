@@ -2,7 +2,7 @@
 
 pub use frame_benchmarking::{account, benchmarks};
 use frame_system::RawOrigin;
-use xp_protocol::{X_BTC, X_DOT};
+use xp_protocol::{X_BTC, X_DOT, X_ETH};
 
 use super::*;
 
@@ -22,6 +22,8 @@ benchmarks! {
             ).unwrap(),
             true,
             true,
+            None,
+            None,
         ).unwrap();
 
         FixedAssetPowerOf::<T>::insert(X_DOT, 100);
@@ -64,6 +66,56 @@ benchmarks! {
     verify {
         assert_eq!(FixedAssetPowerOf::<T>::get(X_BTC), c);
     }
+
+    set_claim_paused {
+    }: _(RawOrigin::Root, X_BTC, true)
+    verify {
+        assert!(ClaimPaused::<T>::get(X_BTC));
+    }
+
+    set_channel_first_deposit_bonus {
+        let bonus: BalanceOf<T> = 1_000u32.into();
+    }: _(RawOrigin::Root, X_BTC, Some(bonus))
+    verify {
+        assert_eq!(ChannelFirstDepositBonus::<T>::get(X_BTC), Some(bonus));
+    }
+
+    claim_to {
+        xpallet_assets_registrar::Pallet::<T>::register(
+            frame_system::RawOrigin::Root.into(),
+            X_ETH,
+            xpallet_assets_registrar::AssetInfo::new::<T>(
+                b"X-ETH".to_vec(),
+                b"Ethereum".to_vec(),
+                xpallet_assets_registrar::Chain::Ethereum,
+                18,
+                b"Ethereum".to_vec(),
+            ).unwrap(),
+            true,
+            true,
+            None,
+            None,
+        ).unwrap();
+
+        FixedAssetPowerOf::<T>::insert(X_ETH, 100);
+
+        let miner = account("miner", 0, SEED);
+        let dest: T::AccountId = account("dest", 0, SEED);
+        xpallet_assets::Pallet::<T>::issue(&X_ETH, &miner, 1000u32.into(), true)?;
+
+        let reward_pot = T::DetermineRewardPotAccount::reward_pot_account_for(&X_ETH);
+        <T as xpallet_assets::Config>::Currency::make_free_balance_be(&reward_pot, 100u32.into());
+        <T as xpallet_assets::Config>::Currency::issue(100u32.into());
+
+        Pallet::<T>::set_claim_staking_requirement(RawOrigin::Root.into(), X_ETH, 0)?;
+
+        let block_number: T::BlockNumber = frame_system::Pallet::<T>::block_number();
+        frame_system::Pallet::<T>::set_block_number(block_number + 100u32.into());
+
+    }: _(RawOrigin::Signed(miner), X_ETH, T::Lookup::unlookup(dest.clone()))
+    verify {
+        assert!(Pallet::<T>::free_balance(&dest) == 90u32.into());
+    }
 }
 
 #[cfg(test)]
@@ -80,6 +132,9 @@ mod tests {
             assert_ok!(Pallet::<Test>::test_benchmark_set_claim_staking_requirement());
             assert_ok!(Pallet::<Test>::test_benchmark_set_claim_frequency_limit());
             assert_ok!(Pallet::<Test>::test_benchmark_set_asset_power());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_claim_paused());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_channel_first_deposit_bonus());
+            assert_ok!(Pallet::<Test>::test_benchmark_claim_to());
         });
     }
 }
@@ -0,0 +1,28 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Documentation of the events and errors, served by `chainx_getRuntimeDocs`.
+
+xpallet_support::pallet_docs! {
+    "XMiningAsset",
+    events {
+        Claimed => "An asset miner claimed the mining reward. [claimer, asset_id, amount]",
+        Minted => "Issue new balance to the reward pot. [reward_pot_account, amount]",
+        ClaimPausedUpdated => "The mining claims of an asset have been paused or resumed. [asset_id, paused]",
+        InvariantAsserted => "The balances and mining weights of an asset are consistent. [asset_id]",
+        ChannelFirstDepositBonusUpdated => "The first deposit bonus of the channels was updated. [asset_id, bonus]",
+        ChannelBonusPaid => "A channel was paid for the first deposit of a depositor it referred. [channel, asset_id, depositor, amount]",
+        ClaimedTo => "An asset miner claimed the mining reward to another account. [claimer, dest, asset_id, amount]",
+    }
+    errors {
+        NotPrevilegedAsset => "The asset does not have the mining rights.",
+        InsufficientStaking => "Claimer does not have enough Staking locked balance.",
+        UnexpiredFrequencyLimit => "Claimer just did a claim recently, the next frequency limit is not expired.",
+        ZeroMiningWeight => "Zero mining weight.",
+        DispatchError => "Balances error.",
+        ClaimPaused => "Claims paused for this token.",
+        ClaimsDisabled => "The asset mining claims are temporarily disabled.",
+        InvariantAssertionDisabled => "The invariant assertion is not enabled in this runtime.",
+        InvariantViolated => "The balances or mining weights of the asset are inconsistent.",
+        SystemAccountDestination => "The mining reward can not be claimed to the treasury or a reward pot.",
+    }
+}
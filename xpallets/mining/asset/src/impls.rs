@@ -102,9 +102,10 @@ impl<T: Config> xpallet_assets::OnAssetChanged<T::AccountId, BalanceOf<T>> for P
     fn on_issue_post(
         target: &AssetId,
         source: &T::AccountId,
-        _value: BalanceOf<T>,
+        value: BalanceOf<T>,
     ) -> DispatchResult {
-        Self::issue_deposit_reward(source, target)
+        Self::issue_deposit_reward(source, target)?;
+        Self::issue_channel_bonus(source, target, value)
     }
 
     fn on_move_pre(
@@ -139,8 +140,8 @@ impl<T: Config> Pallet<T> {
         claimee: &AssetId,
         block_number: T::BlockNumber,
     ) -> Result<(BalanceOf<T>, WeightType, WeightType, T::AccountId), Error<T>> {
-        let reward_pot = T::DetermineRewardPotAccount::reward_pot_account_for(claimee);
-        let reward_pot_balance = Self::free_balance(&reward_pot);
+        let (reward_pot, reward_pot_balance) =
+            <Self as VoteWeightSettlement<T::AccountId, T::BlockNumber>>::jackpot_of(claimee);
 
         let (dividend, source_weight, target_weight) =
             <Self as ComputeMiningWeight<T::AccountId, T::BlockNumber>>::compute_dividend(
@@ -171,11 +172,12 @@ impl<T: Config> Pallet<T> {
     ///
     /// total_asset_miner_dividend
     ///   ├──> referral(treasury) 10%
-    ///   └──> claimer            90%
+    ///   └──> dest(claimer)      90%
     fn allocate_dividend(
         claimee_reward_pot: &T::AccountId,
         claimer: &T::AccountId,
         claimee: &AssetId,
+        dest: &T::AccountId,
         dividend: BalanceOf<T>,
     ) -> Result<(), Error<T>> {
         let to_referral_or_treasury = dividend / 10u32.saturated_into();
@@ -184,24 +186,34 @@ impl<T: Config> Pallet<T> {
                 <T as Config>::TreasuryAccount::treasury_account()
                     .expect("TreasuryAccount is some; qed")
             });
-        Self::transfer(
+        Self::move_from_jackpot(
             claimee_reward_pot,
             &reward_splitter,
             to_referral_or_treasury,
         )?;
 
         let to_claimer = dividend - to_referral_or_treasury;
-        Self::transfer(claimee_reward_pot, claimer, to_claimer)?;
+        Self::move_from_jackpot(claimee_reward_pot, dest, to_claimer)?;
 
         Ok(())
     }
-}
 
-impl<T: Config> Claim<T::AccountId> for Pallet<T> {
-    type Claimee = AssetId;
-    type Error = Error<T>;
+    /// Returns true if `who` is the treasury or the reward pot of any mining asset.
+    pub(crate) fn is_system_account(who: &T::AccountId) -> bool {
+        <T as Config>::TreasuryAccount::treasury_account().as_ref() == Some(who)
+            || Self::mining_previleged_assets().iter().any(|asset_id| {
+                &T::DetermineRewardPotAccount::reward_pot_account_for(asset_id) == who
+            })
+    }
 
-    fn claim(claimer: &T::AccountId, claimee: &Self::Claimee) -> Result<(), Error<T>> {
+    /// Settles the claim of `claimer` to `claimee`, the dividend is paid to `dest`.
+    ///
+    /// Returns the total dividend taken from the reward pot.
+    pub(crate) fn settle_claim(
+        claimer: &T::AccountId,
+        claimee: &AssetId,
+        dest: &T::AccountId,
+    ) -> Result<BalanceOf<T>, Error<T>> {
         let current_block = <frame_system::Pallet<T>>::block_number();
 
         let ClaimRestriction {
@@ -211,24 +223,62 @@ impl<T: Config> Claim<T::AccountId> for Pallet<T> {
 
         Self::passed_enough_interval(claimer, claimee, frequency_limit, current_block)?;
 
-        let (dividend, source_weight, target_weight, claimee_reward_pot) =
-            Self::calculate_dividend_on_claim(claimer, claimee, current_block)?;
-
-        Self::has_enough_staking(claimer, dividend, staking_requirement)?;
-
-        Self::allocate_dividend(&claimee_reward_pot, claimer, claimee, dividend)?;
-
-        Self::apply_update_miner_mining_weight(claimer, claimee, 0, current_block);
-        Self::apply_update_asset_mining_weight(
-            claimee,
-            target_weight - source_weight,
-            current_block,
-        );
+        let dividend =
+            <Self as VoteWeightSettlement<T::AccountId, T::BlockNumber>>::claim_against_jackpot(
+                claimer,
+                claimee,
+                current_block,
+                |claimee_reward_pot, dividend| {
+                    Self::has_enough_staking(claimer, dividend, staking_requirement)?;
+                    Self::allocate_dividend(claimee_reward_pot, claimer, claimee, dest, dividend)
+                },
+            )?;
 
         MinerLedgers::<T>::mutate(claimer, claimee, |miner_ledger| {
             miner_ledger.last_claim = Some(current_block);
         });
 
+        Ok(dividend)
+    }
+}
+
+impl<T: Config> VoteWeightSettlement<T::AccountId, T::BlockNumber> for Pallet<T> {
+    type Balance = BalanceOf<T>;
+
+    fn jackpot_of(asset_id: &AssetId) -> (T::AccountId, BalanceOf<T>) {
+        let reward_pot = T::DetermineRewardPotAccount::reward_pot_account_for(asset_id);
+        let reward_pot_balance = Self::free_balance(&reward_pot);
+        (reward_pot, reward_pot_balance)
+    }
+
+    // The amounts of asset miners are managed by the assets module, `delta` is ignored.
+    fn set_claimer_weight(
+        miner: &T::AccountId,
+        asset_id: &AssetId,
+        new_weight: WeightType,
+        current_block: T::BlockNumber,
+        _delta: Delta<BalanceOf<T>>,
+    ) {
+        Self::apply_update_miner_mining_weight(miner, asset_id, new_weight, current_block);
+    }
+
+    fn set_claimee_weight(
+        asset_id: &AssetId,
+        new_weight: WeightType,
+        current_block: T::BlockNumber,
+        _delta: Delta<BalanceOf<T>>,
+    ) {
+        Self::apply_update_asset_mining_weight(asset_id, new_weight, current_block);
+    }
+}
+
+impl<T: Config> Claim<T::AccountId> for Pallet<T> {
+    type Claimee = AssetId;
+    type Error = Error<T>;
+
+    fn claim(claimer: &T::AccountId, claimee: &Self::Claimee) -> Result<(), Error<T>> {
+        let dividend = Self::settle_claim(claimer, claimee, claimer)?;
+
         Self::deposit_event(Event::<T>::Claimed(claimer.clone(), *claimee, dividend));
 
         Ok(())
@@ -241,6 +291,10 @@ impl<T: Config> xpallet_assets_registrar::RegistrarHandler for Pallet<T> {
             return Ok(());
         }
         MiningPrevilegedAssets::<T>::mutate(|i| i.push(*asset_id));
+        T::DerivedAccounts::note_derived_account(
+            &Self::reward_pot_for(asset_id),
+            DerivedAccountKind::TokenJackpot(*asset_id),
+        );
         AssetLedgers::<T>::insert(
             asset_id,
             AssetLedger {
@@ -289,18 +343,31 @@ where
 impl<T: Config> xp_mining_staking::AssetMining<BalanceOf<T>> for Pallet<T> {
     /// Collects the mining power of all mining assets.
     fn asset_mining_power() -> Vec<(AssetId, MiningPower)> {
-        // Currently only X-BTC asset.
-        FixedAssetPowerOf::<T>::iter()
-            .map(|(asset_id, fixed_power)| {
-                let total_issuance = <xpallet_assets::Pallet<T>>::total_issuance(&asset_id);
-                (
-                    asset_id,
-                    total_issuance
-                        .saturating_mul(fixed_power.saturated_into())
-                        .saturated_into::<MiningPower>(),
-                )
-            })
-            .collect()
+        let mining_power_of = |asset_id: AssetId, price: u128| {
+            let total_issuance = <xpallet_assets::Pallet<T>>::total_issuance(&asset_id);
+            (
+                asset_id,
+                total_issuance
+                    .saturating_mul(price.saturated_into())
+                    .saturated_into::<MiningPower>(),
+            )
+        };
+
+        let mut mining_power = FixedAssetPowerOf::<T>::iter()
+            .map(|(asset_id, fixed_power)| mining_power_of(asset_id, fixed_power.into()))
+            .collect::<Vec<_>>();
+
+        // The privileged assets without a fixed power fall back to the oracle price.
+        for asset_id in Self::mining_previleged_assets() {
+            if FixedAssetPowerOf::<T>::contains_key(asset_id) {
+                continue;
+            }
+            if let Some(price) = T::PriceProvider::price_of(&asset_id) {
+                mining_power.push(mining_power_of(asset_id, price));
+            }
+        }
+
+        mining_power
     }
 
     /// Issues reward to the reward pot of an Asset.
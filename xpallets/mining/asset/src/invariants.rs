@@ -0,0 +1,135 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Consistency check of the asset balances and the mining weights.
+//!
+//! The balances of all the holders of an asset should add up to its
+//! `TotalAssetBalance` per asset type, and the settled mining weights of all
+//! its miners should add up to the settled mining weight of the asset. The
+//! check walks `AssetBalance` and then `MinerLedgers` at most `max_accounts`
+//! entries per call, the returned cursor resumes the walk.
+
+use sp_runtime::traits::Saturating;
+
+use super::*;
+
+impl<T: Config> Pallet<T> {
+    /// Checks the invariants of `asset_id`, walking at most `max_accounts` entries.
+    ///
+    /// All the calls of a check are expected to be made against the same block,
+    /// otherwise the sums of the walked entries would be out of date.
+    pub fn check_invariants(
+        asset_id: AssetId,
+        max_accounts: u32,
+        cursor: Option<InvariantCursor<BalanceOf<T>, T::BlockNumber>>,
+    ) -> InvariantReport<BalanceOf<T>, T::BlockNumber> {
+        let mut cursor = cursor.unwrap_or_else(|| InvariantCursor {
+            phase: InvariantPhase::AssetBalances,
+            last_key: None,
+            at: <frame_system::Pallet<T>>::block_number(),
+            balances: BTreeMap::new(),
+            mining_weight: Zero::zero(),
+        });
+        let mut scanned = 0u32;
+
+        if cursor.phase == InvariantPhase::AssetBalances {
+            let mut iter = match cursor.last_key.take() {
+                Some(last_key) => xpallet_assets::AssetBalance::<T>::iter_from(last_key),
+                None => xpallet_assets::AssetBalance::<T>::iter(),
+            };
+            loop {
+                if scanned >= max_accounts {
+                    return InvariantReport {
+                        asset_id,
+                        scanned,
+                        next_cursor: Some(cursor),
+                        violations: Vec::new(),
+                    };
+                }
+                let (who, id, balances) = match iter.next() {
+                    Some(entry) => entry,
+                    None => break,
+                };
+                scanned += 1;
+                cursor.last_key =
+                    Some(xpallet_assets::AssetBalance::<T>::hashed_key_for(&who, &id));
+                if id == asset_id {
+                    for (asset_type, balance) in balances {
+                        let sum = cursor.balances.entry(asset_type).or_default();
+                        *sum = sum.saturating_add(balance);
+                    }
+                }
+            }
+            cursor.phase = InvariantPhase::MiningWeights;
+            cursor.last_key = None;
+        }
+
+        let mut iter = match cursor.last_key.take() {
+            Some(last_key) => MinerLedgers::<T>::iter_from(last_key),
+            None => MinerLedgers::<T>::iter(),
+        };
+        loop {
+            if scanned >= max_accounts {
+                return InvariantReport {
+                    asset_id,
+                    scanned,
+                    next_cursor: Some(cursor),
+                    violations: Vec::new(),
+                };
+            }
+            let (who, id, _) = match iter.next() {
+                Some(entry) => entry,
+                None => break,
+            };
+            scanned += 1;
+            cursor.last_key = Some(MinerLedgers::<T>::hashed_key_for(&who, &id));
+            if id == asset_id {
+                let weight = Self::settle_claimer_weight(&who, &id, cursor.at);
+                cursor.mining_weight = cursor.mining_weight.saturating_add(weight);
+            }
+        }
+
+        InvariantReport {
+            asset_id,
+            scanned,
+            next_cursor: None,
+            violations: Self::collect_violations(&asset_id, cursor),
+        }
+    }
+
+    fn collect_violations(
+        asset_id: &AssetId,
+        cursor: InvariantCursor<BalanceOf<T>, T::BlockNumber>,
+    ) -> Vec<InvariantViolation<BalanceOf<T>>> {
+        let mut violations = Vec::new();
+
+        let totals = xpallet_assets::Pallet::<T>::total_asset_balance(asset_id);
+        let asset_types = totals
+            .keys()
+            .chain(cursor.balances.keys())
+            .copied()
+            .collect::<sp_std::collections::btree_set::BTreeSet<_>>();
+        for asset_type in asset_types {
+            let sum = cursor
+                .balances
+                .get(&asset_type)
+                .copied()
+                .unwrap_or_default();
+            let total = totals.get(&asset_type).copied().unwrap_or_default();
+            if sum != total {
+                violations.push(InvariantViolation::TotalAssetBalance(
+                    asset_type, sum, total,
+                ));
+            }
+        }
+
+        let total_weight = Self::settle_claimee_weight(asset_id, cursor.at);
+        if cursor.mining_weight != total_weight {
+            violations.push(InvariantViolation::TotalMiningWeight(
+                cursor.mining_weight,
+                total_weight,
+            ));
+        }
+
+        violations
+    }
+}
@@ -5,7 +5,10 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::type_complexity)]
 
+mod docs;
 mod impls;
+mod invariants;
+pub mod migrations;
 mod rpc;
 mod types;
 pub mod weights;
@@ -17,25 +20,28 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
-use sp_std::prelude::*;
+use sp_std::{collections::btree_map::BTreeMap, prelude::*};
 
 use frame_support::{
     dispatch::{DispatchError, DispatchResult},
     ensure,
-    log::warn,
+    log::{error, warn},
     traits::{Currency, ExistenceRequirement},
 };
 use frame_system::{ensure_root, ensure_signed};
-use sp_runtime::traits::{SaturatedConversion, Zero};
+use sp_runtime::traits::{SaturatedConversion, StaticLookup, Zero};
 
 use chainx_primitives::AssetId;
 use xp_mining_common::{
-    Claim, ComputeMiningWeight, MiningWeight as _, RewardPotAccountFor, WeightType,
-    ZeroMiningWeightError,
+    Claim, Delta, MiningWeight as _, RewardPotAccountFor, WeightType, ZeroMiningWeightError,
 };
-use xpallet_assets::{AssetType, BalanceOf};
+use xpallet_assets::{
+    AssetType, BalanceOf, DerivedAccountKind, DerivedAccountRegistry, MoveCaller,
+};
+use xpallet_mining_staking::{ClaimTypeKind, VoteWeightSettlement};
 use xpallet_support::traits::TreasuryAccount;
 
+pub use self::docs::PALLET_DOCS;
 pub use self::impls::SimpleAssetRewardPotAccountDeterminer;
 pub use self::rpc::*;
 pub use self::types::*;
@@ -60,20 +66,40 @@ pub mod pallet {
         /// Get the possible referral of asset miner.
         type GatewayInterface: GatewayInterface<Self::AccountId>;
 
+        /// Get the price of the mining assets without a fixed mining power.
+        type PriceProvider: AssetPriceProvider;
+
         /// Get the treasury account.
         type TreasuryAccount: TreasuryAccount<Self::AccountId>;
 
         /// Generate the reward pot account for mining asset.
         type DetermineRewardPotAccount: RewardPotAccountFor<Self::AccountId, AssetId>;
 
+        /// The registry of the reward pots of the mining assets.
+        type DerivedAccounts: DerivedAccountRegistry<Self::AccountId>;
+
+        /// Whether `assert_token_invariant` can be dispatched.
+        ///
+        /// The assertion walks all the asset holders within a single block, which
+        /// should never be enabled on the mainnet.
+        type InvariantAssertionEnabled: Get<bool>;
+
         type WeightInfo: WeightInfo;
     }
 
     #[pallet::pallet]
     #[pallet::generate_store(pub(super) trait Store)]
+    #[pallet::storage_version(migrations::STORAGE_VERSION)]
     #[pallet::without_storage_info]
     pub struct Pallet<T>(PhantomData<T>);
 
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_runtime_upgrade() -> Weight {
+            migrations::migrate::<T>()
+        }
+    }
+
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         /// Claims the staking reward given the `target` validator.
@@ -85,6 +111,11 @@ pub mod pallet {
                 Self::mining_previleged_assets().contains(&target),
                 Error::<T>::NotPrevilegedAsset
             );
+            ensure!(!Self::claim_paused(target), Error::<T>::ClaimPaused);
+            ensure!(
+                T::StakingInterface::claim_enabled(ClaimTypeKind::PseduIntention),
+                Error::<T>::ClaimsDisabled
+            );
 
             <Self as Claim<T::AccountId>>::claim(&sender, &target)?;
 
@@ -127,6 +158,107 @@ pub mod pallet {
             FixedAssetPowerOf::<T>::insert(asset_id, new);
             Ok(())
         }
+
+        /// Pauses or resumes the mining claims of `asset_id`.
+        ///
+        /// The deposit reward of `asset_id` is not issued either while paused, but the
+        /// mining weights still accrue as usual.
+        #[pallet::weight(<T as Config>::WeightInfo::set_claim_paused())]
+        pub fn set_claim_paused(
+            origin: OriginFor<T>,
+            #[pallet::compact] asset_id: AssetId,
+            paused: bool,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            if paused {
+                ClaimPaused::<T>::insert(asset_id, true);
+            } else {
+                ClaimPaused::<T>::remove(asset_id);
+            }
+            Self::deposit_event(Event::<T>::ClaimPausedUpdated(asset_id, paused));
+            Ok(())
+        }
+
+        /// Checks the invariants of `asset_id` in one go, fails if any of them is violated.
+        ///
+        /// Only available if `InvariantAssertionEnabled` is set.
+        #[pallet::weight(<T as frame_system::Config>::BlockWeights::get().max_block)]
+        pub fn assert_token_invariant(
+            origin: OriginFor<T>,
+            #[pallet::compact] asset_id: AssetId,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            ensure!(
+                T::InvariantAssertionEnabled::get(),
+                Error::<T>::InvariantAssertionDisabled
+            );
+
+            let report = Self::check_invariants(asset_id, u32::MAX, None);
+            if !report.is_consistent() {
+                error!(
+                    target: "runtime::mining::asset",
+                    "[assert_token_invariant] asset_id: {}, violations: {:?}",
+                    asset_id,
+                    report.violations
+                );
+                return Err(Error::<T>::InvariantViolated.into());
+            }
+
+            Self::deposit_event(Event::<T>::InvariantAsserted(asset_id));
+            Ok(())
+        }
+
+        /// Sets the one-time bonus paid to the referral of a depositor on its first
+        /// deposit of `asset_id`, `None` to disable it.
+        ///
+        /// The bonus is paid from the reward pot of `asset_id`.
+        #[pallet::weight(<T as Config>::WeightInfo::set_channel_first_deposit_bonus())]
+        pub fn set_channel_first_deposit_bonus(
+            origin: OriginFor<T>,
+            #[pallet::compact] asset_id: AssetId,
+            bonus: Option<BalanceOf<T>>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            match bonus {
+                Some(bonus) => ChannelFirstDepositBonus::<T>::insert(asset_id, bonus),
+                None => ChannelFirstDepositBonus::<T>::remove(asset_id),
+            }
+            Self::deposit_event(Event::<T>::ChannelFirstDepositBonusUpdated(asset_id, bonus));
+            Ok(())
+        }
+
+        /// Claims the mining reward of `target` to the `dest` account.
+        ///
+        /// The claim is settled against the mining weight of the sender as `claim` does,
+        /// only the dividend of the sender is paid to `dest` instead.
+        #[pallet::weight(<T as Config>::WeightInfo::claim_to())]
+        pub fn claim_to(
+            origin: OriginFor<T>,
+            #[pallet::compact] target: AssetId,
+            dest: <T::Lookup as StaticLookup>::Source,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            let dest = T::Lookup::lookup(dest)?;
+
+            ensure!(
+                Self::mining_previleged_assets().contains(&target),
+                Error::<T>::NotPrevilegedAsset
+            );
+            ensure!(!Self::claim_paused(target), Error::<T>::ClaimPaused);
+            ensure!(
+                T::StakingInterface::claim_enabled(ClaimTypeKind::PseduIntention),
+                Error::<T>::ClaimsDisabled
+            );
+            ensure!(
+                !Self::is_system_account(&dest),
+                Error::<T>::SystemAccountDestination
+            );
+
+            let dividend = Self::settle_claim(&sender, &target, &dest)?;
+            Self::deposit_event(Event::<T>::ClaimedTo(sender, dest, target, dividend));
+
+            Ok(())
+        }
     }
 
     #[pallet::event]
@@ -136,6 +268,16 @@ pub mod pallet {
         Claimed(T::AccountId, AssetId, BalanceOf<T>),
         /// Issue new balance to the reward pot. [reward_pot_account, amount]
         Minted(T::AccountId, BalanceOf<T>),
+        /// The mining claims of an asset have been paused or resumed. [asset_id, paused]
+        ClaimPausedUpdated(AssetId, bool),
+        /// The balances and mining weights of an asset are consistent. [asset_id]
+        InvariantAsserted(AssetId),
+        /// The first deposit bonus of the channels was updated. [asset_id, bonus]
+        ChannelFirstDepositBonusUpdated(AssetId, Option<BalanceOf<T>>),
+        /// A channel was paid for the first deposit of a depositor it referred. [channel, asset_id, depositor, amount]
+        ChannelBonusPaid(T::AccountId, AssetId, T::AccountId, BalanceOf<T>),
+        /// An asset miner claimed the mining reward to another account. [claimer, dest, asset_id, amount]
+        ClaimedTo(T::AccountId, T::AccountId, AssetId, BalanceOf<T>),
     }
 
     /// Old name generated by `decl_event`.
@@ -155,6 +297,16 @@ pub mod pallet {
         ZeroMiningWeight,
         /// Balances error.
         DispatchError,
+        /// Claims paused for this token.
+        ClaimPaused,
+        /// The asset mining claims are temporarily disabled.
+        ClaimsDisabled,
+        /// The invariant assertion is not enabled in this runtime.
+        InvariantAssertionDisabled,
+        /// The balances or mining weights of the asset are inconsistent.
+        InvariantViolated,
+        /// The mining reward can not be claimed to the treasury or a reward pot.
+        SystemAccountDestination,
     }
 
     #[pallet::type_value]
@@ -174,6 +326,11 @@ pub mod pallet {
     pub type ClaimRestrictionOf<T: Config> =
         StorageMap<_, Twox64Concat, AssetId, ClaimRestriction<T::BlockNumber>, ValueQuery>;
 
+    /// Assets of which the mining claims and deposit rewards are paused.
+    #[pallet::storage]
+    #[pallet::getter(fn claim_paused)]
+    pub type ClaimPaused<T: Config> = StorageMap<_, Twox64Concat, AssetId, bool, ValueQuery>;
+
     /// External Assets that have the mining rights.
     #[pallet::storage]
     #[pallet::getter(fn mining_previleged_assets)]
@@ -204,6 +361,17 @@ pub mod pallet {
     pub type FixedAssetPowerOf<T: Config> =
         StorageMap<_, Twox64Concat, AssetId, FixedAssetPower, ValueQuery>;
 
+    /// The bonus paid to the referral of a depositor on its first deposit of an asset.
+    #[pallet::storage]
+    #[pallet::getter(fn channel_first_deposit_bonus)]
+    pub type ChannelFirstDepositBonus<T: Config> =
+        StorageMap<_, Twox64Concat, AssetId, BalanceOf<T>>;
+
+    /// The accounts that have deposited an asset no less than its minimal deposit.
+    #[pallet::storage]
+    pub type HasDeposited<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, T::AccountId, Twox64Concat, AssetId, ()>;
+
     #[pallet::genesis_config]
     pub struct GenesisConfig<T: Config> {
         pub claim_restrictions: Vec<(AssetId, (StakingRequirement, T::BlockNumber))>,
@@ -245,12 +413,19 @@ pub mod pallet {
     pub trait StakingInterface<AccountId, Balance> {
         /// Returns the amount of `who`s locked balances in Staking.
         fn staked_of(who: &AccountId) -> Balance;
+
+        /// Returns true if the claims of the given kind are enabled.
+        fn claim_enabled(kind: ClaimTypeKind) -> bool;
     }
 
     impl<AccountId, Balance: Default> StakingInterface<AccountId, Balance> for () {
         fn staked_of(_: &AccountId) -> Balance {
             Default::default()
         }
+
+        fn claim_enabled(_: ClaimTypeKind) -> bool {
+            true
+        }
     }
 
     impl<T: Config> StakingInterface<<T as frame_system::Config>::AccountId, u128> for T
@@ -260,17 +435,40 @@ pub mod pallet {
         fn staked_of(who: &<T as frame_system::Config>::AccountId) -> u128 {
             xpallet_mining_staking::Pallet::<T>::staked_of(who).saturated_into()
         }
+
+        fn claim_enabled(kind: ClaimTypeKind) -> bool {
+            xpallet_mining_staking::Pallet::<T>::claim_switch(kind)
+        }
     }
 
     pub trait GatewayInterface<AccountId> {
         /// Returns the potential referral of `who` for `asset_id`.
         fn referral_of(who: &AccountId, asset_id: AssetId) -> Option<AccountId>;
+
+        /// Returns the minimal deposit of `asset_id` accepted by its bridge.
+        fn minimal_deposit_of(asset_id: AssetId) -> u128;
     }
 
     impl<AccountId> GatewayInterface<AccountId> for () {
         fn referral_of(_: &AccountId, _: AssetId) -> Option<AccountId> {
             None
         }
+
+        fn minimal_deposit_of(_: AssetId) -> u128 {
+            0
+        }
+    }
+
+    pub trait AssetPriceProvider {
+        /// Returns the price of `asset_id` measured in PCX, in the same scale as
+        /// `FixedAssetPowerOf`.
+        fn price_of(asset_id: &AssetId) -> Option<u128>;
+    }
+
+    impl AssetPriceProvider for () {
+        fn price_of(_: &AssetId) -> Option<u128> {
+            None
+        }
     }
 
     impl<T: Config> From<ZeroMiningWeightError> for Error<T> {
@@ -297,11 +495,17 @@ impl<T: Config> Pallet<T> {
         <T as xpallet_assets::Config>::Currency::free_balance(who)
     }
 
-    #[inline]
-    fn transfer(from: &T::AccountId, to: &T::AccountId, value: BalanceOf<T>) -> DispatchResult {
+    /// Moves `value` out of the reward pot `jackpot` to `dest` on the claim/reward paths,
+    /// which is checked by the `MoveGuard` of XAssets like any outflow of the reward pots.
+    fn move_from_jackpot(
+        jackpot: &T::AccountId,
+        dest: &T::AccountId,
+        value: BalanceOf<T>,
+    ) -> DispatchResult {
+        T::DerivedAccounts::ensure_can_move_out(jackpot, MoveCaller::Jackpot)?;
         <T as xpallet_assets::Config>::Currency::transfer(
-            from,
-            to,
+            jackpot,
+            dest,
             value,
             ExistenceRequirement::KeepAlive,
         )
@@ -397,13 +601,11 @@ impl<T: Config> Pallet<T> {
         target: &AssetId,
         current_block: T::BlockNumber,
     ) {
-        let new_weight =
-            <Self as ComputeMiningWeight<T::AccountId, T::BlockNumber>>::settle_claimer_weight(
-                from,
-                target,
-                current_block,
-            );
-        Self::apply_update_miner_mining_weight(from, target, new_weight, current_block);
+        <Self as VoteWeightSettlement<T::AccountId, T::BlockNumber>>::settle_claimer(
+            from,
+            target,
+            current_block,
+        );
     }
 
     #[cfg(feature = "std")]
@@ -437,15 +639,6 @@ impl<T: Config> Pallet<T> {
         MinerLedgers::<T>::insert(from, target, inner);
     }
 
-    fn update_asset_mining_weight(target: &AssetId, current_block: T::BlockNumber) {
-        let new_weight =
-            <Self as ComputeMiningWeight<T::AccountId, T::BlockNumber>>::settle_claimee_weight(
-                target,
-                current_block,
-            );
-        Self::apply_update_asset_mining_weight(target, new_weight, current_block);
-    }
-
     fn apply_update_asset_mining_weight(
         target: &AssetId,
         new_weight: WeightType,
@@ -462,18 +655,31 @@ impl<T: Config> Pallet<T> {
         target: &AssetId,
         current_block: T::BlockNumber,
     ) {
-        Self::update_miner_mining_weight(source, target, current_block);
-        Self::update_asset_mining_weight(target, current_block);
+        <Self as VoteWeightSettlement<T::AccountId, T::BlockNumber>>::settle(
+            source,
+            target,
+            current_block,
+            Delta::Zero,
+        );
     }
 
     /// Gives a tiny reward to the depositor in case of it
     /// does not have enough balances to claim the mining reward.
     fn issue_deposit_reward(depositor: &T::AccountId, target: &AssetId) -> DispatchResult {
+        if Self::claim_paused(target) {
+            warn!(
+                target: "runtime::mining::asset",
+                "asset {}'s claim is paused, skipped issuing deposit reward for depositor {:?}",
+                target,
+                depositor
+            );
+            return Ok(());
+        }
         let deposit_reward = Self::deposit_reward();
         let reward_pot = T::DetermineRewardPotAccount::reward_pot_account_for(target);
         let reward_pot_balance = Self::free_balance(&reward_pot);
         if reward_pot_balance >= deposit_reward && Self::free_balance(depositor) <= deposit_reward {
-            Self::transfer(&reward_pot, depositor, deposit_reward)?;
+            Self::move_from_jackpot(&reward_pot, depositor, deposit_reward)?;
         } else {
             warn!(
                 target: "runtime::mining::asset",
@@ -485,4 +691,59 @@ impl<T: Config> Pallet<T> {
         }
         Ok(())
     }
+
+    /// Pays the first deposit bonus of `target` to the referral of the depositor.
+    ///
+    /// Only the first deposit no less than the minimal deposit counts, the bonus
+    /// is skipped if the reward pot can not afford it.
+    fn issue_channel_bonus(
+        depositor: &T::AccountId,
+        target: &AssetId,
+        value: BalanceOf<T>,
+    ) -> DispatchResult {
+        if HasDeposited::<T>::contains_key(depositor, target)
+            || value.saturated_into::<u128>() < T::GatewayInterface::minimal_deposit_of(*target)
+        {
+            return Ok(());
+        }
+        HasDeposited::<T>::insert(depositor, target, ());
+
+        let (bonus, channel) = match (
+            Self::channel_first_deposit_bonus(target),
+            T::GatewayInterface::referral_of(depositor, *target),
+        ) {
+            (Some(bonus), Some(channel)) => (bonus, channel),
+            _ => return Ok(()),
+        };
+
+        let reward_pot = T::DetermineRewardPotAccount::reward_pot_account_for(target);
+        if Self::claim_paused(target) || Self::free_balance(&reward_pot) < bonus {
+            warn!(
+                target: "runtime::mining::asset",
+                "asset {}'s reward pot can not pay the channel bonus {:?}, skipped for depositor {:?}",
+                target,
+                bonus,
+                depositor
+            );
+            return Ok(());
+        }
+        if let Err(err) = Self::move_from_jackpot(&reward_pot, &channel, bonus) {
+            warn!(
+                target: "runtime::mining::asset",
+                "failed to pay the channel bonus of asset {} for depositor {:?}: {:?}",
+                target,
+                depositor,
+                err
+            );
+            return Ok(());
+        }
+
+        Self::deposit_event(Event::<T>::ChannelBonusPaid(
+            channel,
+            *target,
+            depositor.clone(),
+            bonus,
+        ));
+        Ok(())
+    }
 }
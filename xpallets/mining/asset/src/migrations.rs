@@ -0,0 +1,49 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Storage migrations of the asset mining pallet, run by `on_runtime_upgrade`.
+
+use frame_support::{
+    log::info,
+    traits::{Get, GetStorageVersion, StorageVersion},
+    weights::Weight,
+};
+
+use super::*;
+
+/// The storage version of the pallet, bumped by each migration below.
+pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+/// Runs the migrations the on-chain storage version has not reached yet.
+pub fn migrate<T: Config>() -> Weight {
+    let on_chain = Pallet::<T>::on_chain_storage_version();
+    let mut weight = T::DbWeight::get().reads(1);
+    if on_chain >= STORAGE_VERSION {
+        return weight;
+    }
+
+    if on_chain < 1 {
+        weight = weight.saturating_add(backfill_has_deposited::<T>());
+    }
+
+    STORAGE_VERSION.put::<Pallet<T>>();
+    weight.saturating_add(T::DbWeight::get().writes(1))
+}
+
+/// v1: marks the existing miners of each asset in `HasDeposited`.
+///
+/// The deposits before the first deposit bonus are not tracked, so every account
+/// having a ledger of the asset, which is created by its first balance change, is
+/// taken as a depositor and never pays the bonus for the old users.
+fn backfill_has_deposited<T: Config>() -> Weight {
+    let mut count: Weight = 0;
+    for (who, asset_id) in MinerLedgers::<T>::iter_keys() {
+        HasDeposited::<T>::insert(who, asset_id, ());
+        count += 1;
+    }
+    info!(
+        target: "runtime::mining::asset",
+        "[migrate] Marked {} existing miners as depositors",
+        count
+    );
+    T::DbWeight::get().reads_writes(count, count)
+}
@@ -132,9 +132,34 @@ impl xpallet_assets::Config for Test {
     type TreasuryAccount = ();
     type OnCreatedAccount = frame_system::Provider<Test>;
     type OnAssetChanged = XMiningAsset;
+    type OnReceived = ();
+    type MoveGuard = JackpotGuard;
+    type QuarantineOrigin = frame_system::EnsureRoot<AccountId>;
     type WeightInfo = ();
 }
 
+/// Only the jackpot paths are allowed to move the balances out of the reward pots.
+pub struct JackpotGuard;
+impl xpallet_assets::MoveGuard for JackpotGuard {
+    fn can_move(
+        _id: &AssetId,
+        _from_type: AssetType,
+        _to_type: AssetType,
+        _caller: MoveCaller,
+    ) -> bool {
+        true
+    }
+
+    fn can_move_out<AccountId>(kind: &DerivedAccountKind<AccountId>, caller: MoveCaller) -> bool {
+        match kind {
+            DerivedAccountKind::TokenJackpot(_) | DerivedAccountKind::IntentionJackpot(_) => {
+                caller == MoveCaller::Jackpot
+            }
+            _ => true,
+        }
+    }
+}
+
 /// Another session handler struct to test on_disabled.
 pub struct OtherSessionHandler;
 impl frame_support::traits::OneSessionHandler<AccountId> for OtherSessionHandler {
@@ -251,11 +276,14 @@ impl xpallet_mining_staking::Config for Test {
     type Currency = Balances;
     type Event = Event;
     type AssetMining = XMiningAsset;
+    type NativeHolds = XAssets;
+    type DerivedAccounts = XAssets;
     type SessionDuration = SessionDuration;
     type MinimumReferralId = MinimumReferralId;
     type MaximumReferralId = MaximumReferralId;
     type SessionInterface = Self;
     type TreasuryAccount = DummyTreasuryAccount;
+    type CouncilOrigin = frame_system::EnsureRoot<AccountId>;
     type DetermineRewardPotAccount = DummyStakingRewardPotAccountDeterminer;
     type ValidatorRegistration = Registration;
     type WeightInfo = ();
@@ -271,20 +299,39 @@ impl xp_mining_common::RewardPotAccountFor<AccountId, AssetId>
     }
 }
 
+pub struct DummyPriceProvider;
+
+impl AssetPriceProvider for DummyPriceProvider {
+    fn price_of(_: &AssetId) -> Option<u128> {
+        ORACLE_PRICE.with(|v| *v.borrow())
+    }
+}
+
 pub struct DummyGatewayReferralGetter;
 
 impl GatewayInterface<AccountId> for DummyGatewayReferralGetter {
     fn referral_of(who: &AccountId, _: AssetId) -> Option<AccountId> {
-        Some(10_000_000_000 + *who)
+        if UNREFERRED.with(|unreferred| unreferred.borrow().contains(who)) {
+            None
+        } else {
+            Some(10_000_000_000 + *who)
+        }
+    }
+
+    fn minimal_deposit_of(_: AssetId) -> u128 {
+        MINIMAL_DEPOSIT.with(|v| *v.borrow())
     }
 }
 
 impl Config for Test {
     type StakingInterface = Self;
     type GatewayInterface = DummyGatewayReferralGetter;
+    type PriceProvider = DummyPriceProvider;
     type Event = Event;
     type TreasuryAccount = ();
     type DetermineRewardPotAccount = DummyAssetRewardPotAccountDeterminer;
+    type DerivedAccounts = XAssets;
+    type InvariantAssertionEnabled = frame_support::traits::ConstBool<true>;
     type WeightInfo = ();
 }
 
@@ -294,6 +341,9 @@ thread_local! {
     static EXISTENTIAL_DEPOSIT: RefCell<Balance> = RefCell::new(0);
     static ELECTION_LOOKAHEAD: RefCell<BlockNumber> = RefCell::new(0);
     static PERIOD: RefCell<BlockNumber> = RefCell::new(1);
+    pub static UNREFERRED: RefCell<HashSet<AccountId>> = RefCell::new(Default::default());
+    pub static MINIMAL_DEPOSIT: RefCell<Balance> = RefCell::new(0);
+    pub static ORACLE_PRICE: RefCell<Option<u128>> = RefCell::new(None);
     static MAX_ITERATIONS: RefCell<u32> = RefCell::new(0);
 }
 
@@ -409,11 +459,6 @@ impl ExtBuilder {
 }
 
 pub fn t_register(who: AccountId, initial_bond: Balance) -> DispatchResult {
-    let mut referral_id = who.to_string().as_bytes().to_vec();
-
-    if referral_id.len() < 2 {
-        referral_id.extend_from_slice(&[1, 1, 1, who as u8]);
-    }
-
+    let referral_id = format!("{:02}", who).into_bytes();
     XStaking::register(Origin::signed(who), referral_id, initial_bond)
 }
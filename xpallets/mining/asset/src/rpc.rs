@@ -6,14 +6,18 @@ use codec::{Decode, Encode};
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 
-use sp_runtime::{RuntimeDebug, SaturatedConversion};
+use sp_runtime::{
+    traits::{One, Saturating, Zero},
+    RuntimeDebug, SaturatedConversion,
+};
 
-use chainx_primitives::AssetId;
-use xp_mining_common::RewardPotAccountFor;
+use chainx_primitives::{AssetId, Decimals};
+use xp_mining_common::{compute_dividend, ComputeMiningWeight, RewardPotAccountFor};
+use xpallet_mining_staking::ClaimTypeKind;
 
 use crate::{
-    types::*, AssetLedgers, BalanceOf, ClaimRestrictionOf, Config, FixedAssetPowerOf, MinerLedgers,
-    MiningPrevilegedAssets, Pallet,
+    types::*, AssetLedgers, BalanceOf, ClaimPaused, ClaimRestrictionOf, Config, FixedAssetPowerOf,
+    MinerLedgers, MiningPrevilegedAssets, Pallet, StakingInterface,
 };
 
 /// Mining asset info.
@@ -27,6 +31,38 @@ pub struct MiningAssetInfo<AccountId, Balance, MiningWeight, BlockNumber> {
     pub reward_pot_balance: Balance,
     #[cfg_attr(feature = "std", serde(flatten))]
     pub ledger_info: AssetLedger<MiningWeight, BlockNumber>,
+    /// Whether the mining claims of this asset are paused.
+    pub claim_paused: bool,
+    /// Whether the asset mining claims are enabled globally.
+    pub claim_enabled: bool,
+}
+
+/// `MiningAssetInfo` without the claim switches, returned by version 3 of `XMiningAssetApi`.
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug)]
+pub struct MiningAssetInfoV3<AccountId, Balance, MiningWeight, BlockNumber> {
+    pub asset_id: AssetId,
+    pub mining_power: FixedAssetPower,
+    pub reward_pot: AccountId,
+    pub reward_pot_balance: Balance,
+    pub ledger_info: AssetLedger<MiningWeight, BlockNumber>,
+}
+
+impl<AccountId, Balance, MiningWeight, BlockNumber>
+    From<MiningAssetInfoV3<AccountId, Balance, MiningWeight, BlockNumber>>
+    for MiningAssetInfo<AccountId, Balance, MiningWeight, BlockNumber>
+{
+    fn from(info: MiningAssetInfoV3<AccountId, Balance, MiningWeight, BlockNumber>) -> Self {
+        Self {
+            asset_id: info.asset_id,
+            mining_power: info.mining_power,
+            reward_pot: info.reward_pot,
+            reward_pot_balance: info.reward_pot_balance,
+            ledger_info: info.ledger_info,
+            // The mining claims could be neither paused nor disabled before.
+            claim_paused: false,
+            claim_enabled: true,
+        }
+    }
 }
 
 /// Detailed dividend info of asset miner.
@@ -42,6 +78,30 @@ pub struct MiningDividendInfo<Balance> {
     pub insufficient_stake: Balance,
 }
 
+/// The asset mining dividend of a miner estimated for a claim in the next block.
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct PseduClaimable<Balance, MiningWeight> {
+    pub asset_id: AssetId,
+    /// Decimals of the asset.
+    pub precision: Decimals,
+    /// Balance of the miner, all the asset types summed up.
+    pub balance: Balance,
+    /// Mining weight of the miner accrued up to the next block.
+    pub mining_weight: MiningWeight,
+    /// Total mining weight of the asset accrued up to the next block.
+    pub total_mining_weight: MiningWeight,
+    /// Balance of the reward pot of the asset.
+    pub reward_pot_balance: Balance,
+    /// Estimated dividend, including the 10% cut for the referral of the miner or treasury.
+    pub dividend: Balance,
+    /// Required more stake to claim the dividend.
+    pub insufficient_stake: Balance,
+    /// Whether the claim is paused for this asset or the asset mining claims are disabled.
+    pub claim_paused: bool,
+}
+
 impl<T: Config> Pallet<T> {
     /// Get overall information about all mining assets.
     pub fn mining_assets(
@@ -60,6 +120,10 @@ impl<T: Config> Pallet<T> {
                     reward_pot,
                     reward_pot_balance,
                     ledger_info,
+                    claim_paused: ClaimPaused::<T>::get(asset_id),
+                    claim_enabled: T::StakingInterface::claim_enabled(
+                        ClaimTypeKind::PseduIntention,
+                    ),
                 }
             })
             .collect()
@@ -104,4 +168,60 @@ impl<T: Config> Pallet<T> {
     ) -> BTreeMap<AssetId, MinerLedger<MiningWeight, T::BlockNumber>> {
         MinerLedgers::<T>::iter_prefix(&who).collect()
     }
+
+    /// Estimates the dividends of all the mining assets of `who` as if claimed in the next block.
+    ///
+    /// The estimate is zero if there is no mining weight yet.
+    pub fn psedu_claimable(who: T::AccountId) -> Vec<PseduClaimable<BalanceOf<T>, MiningWeight>> {
+        let next_block = <frame_system::Pallet<T>>::block_number().saturating_add(One::one());
+        let claim_enabled = T::StakingInterface::claim_enabled(ClaimTypeKind::PseduIntention);
+        MinerLedgers::<T>::iter_prefix(&who)
+            .map(|(asset_id, _)| {
+                let precision = xpallet_assets_registrar::Pallet::<T>::asset_info_of(asset_id)
+                    .map(|info| info.decimals())
+                    .unwrap_or_default();
+                let reward_pot = T::DetermineRewardPotAccount::reward_pot_account_for(&asset_id);
+                let reward_pot_balance: BalanceOf<T> = Self::free_balance(&reward_pot);
+
+                let mining_weight = Self::settle_claimer_weight(&who, &asset_id, next_block);
+                let total_mining_weight = Self::settle_claimee_weight(&asset_id, next_block);
+                let dividend = if mining_weight.is_zero() || total_mining_weight.is_zero() {
+                    Zero::zero()
+                } else {
+                    compute_dividend::<T::AccountId, _>(
+                        mining_weight,
+                        total_mining_weight,
+                        reward_pot_balance,
+                    )
+                };
+
+                let ClaimRestriction {
+                    staking_requirement,
+                    ..
+                } = ClaimRestrictionOf::<T>::get(&asset_id);
+                let insufficient_stake =
+                    Self::need_more_stake(&who, dividend, staking_requirement).unwrap_or_default();
+
+                PseduClaimable {
+                    asset_id,
+                    precision,
+                    balance: xpallet_assets::Pallet::<T>::all_type_asset_balance(&who, &asset_id),
+                    mining_weight,
+                    total_mining_weight,
+                    reward_pot_balance,
+                    dividend,
+                    insufficient_stake,
+                    claim_paused: ClaimPaused::<T>::get(asset_id) || !claim_enabled,
+                }
+            })
+            .collect()
+    }
+
+    /// Get the total balance of the reward pots of all the mining assets.
+    pub fn total_reward_pot_balance() -> BalanceOf<T> {
+        MiningPrevilegedAssets::<T>::get()
+            .iter()
+            .map(|asset_id| Self::free_balance(&Self::reward_pot_for(asset_id)))
+            .fold(Zero::zero(), |acc: BalanceOf<T>, x| acc.saturating_add(x))
+    }
 }
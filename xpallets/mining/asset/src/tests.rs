@@ -1,13 +1,15 @@
 // Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
 
 use frame_support::{
-    assert_err, assert_ok,
-    traits::{Get, OnInitialize},
+    assert_err, assert_noop, assert_ok,
+    traits::{Get, GetStorageVersion, OnInitialize, StorageVersion},
 };
 use frame_system::RawOrigin;
 
+use xp_mining_common::ComputeMiningWeight;
 use xp_mining_staking::SessionIndex;
 use xp_protocol::X_BTC;
+use xpallet_assets::MoveCaller;
 
 use super::*;
 use crate::mock::*;
@@ -30,7 +32,15 @@ fn t_issue_xbtc(to: AccountId, value: Balance) -> DispatchResult {
 
 pub(crate) fn t_register_xbtc() -> DispatchResult {
     let btc_asset = crate::mock::btc();
-    XAssetsRegistrar::register(RawOrigin::Root.into(), btc_asset.0, btc_asset.1, true, true)?;
+    XAssetsRegistrar::register(
+        RawOrigin::Root.into(),
+        btc_asset.0,
+        btc_asset.1,
+        true,
+        true,
+        None,
+        None,
+    )?;
     XAssets::set_asset_limit(RawOrigin::Root.into(), btc_asset.0, btc_asset.2)
 }
 
@@ -52,7 +62,8 @@ fn t_xbtc_move(from: AccountId, to: AccountId, value: Balance) {
         AssetType::Usable,
         &to,
         AssetType::Usable,
-        value
+        value,
+        MoveCaller::User
     ));
 }
 
@@ -456,3 +467,492 @@ fn asset_mining_reward_should_work() {
         assert_eq!(Balances::free_balance(&TREASURY_ACCOUNT), treasury_balance);
     });
 }
+
+#[test]
+fn claim_paused_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(t_register_xbtc());
+        t_xbtc_set_claim_frequency_limit(0);
+        t_xbtc_set_claim_staking_requirement(0);
+
+        // Block 1
+        t_start_session(1);
+        let reward_pot = XMiningAsset::reward_pot_for(&X_BTC);
+        let reward_pot_balance = Balances::free_balance(&reward_pot);
+        assert!(reward_pot_balance > XMiningAsset::deposit_reward());
+
+        let mining_asset_event =
+            |event: crate::Event<Test>| crate::mock::Event::XMiningAsset(event);
+
+        assert_ok!(XMiningAsset::set_claim_paused(Origin::root(), X_BTC, true));
+        System::assert_last_event(mining_asset_event(crate::Event::ClaimPausedUpdated(
+            X_BTC, true,
+        )));
+        assert!(XMiningAsset::mining_assets()[0].claim_paused);
+
+        // Deposits still credit the asset, but no deposit reward is issued.
+        let t_1 = 777;
+        assert_ok!(t_issue_xbtc(t_1, 100));
+        assert_eq!(XAssets::usable_balance(&t_1, &X_BTC), 100);
+        assert_eq!(Balances::free_balance(&t_1), 0);
+        assert_eq!(Balances::free_balance(&reward_pot), reward_pot_balance);
+
+        // Block 2
+        t_start_session(2);
+        assert_err!(
+            XMiningAsset::claim(Origin::signed(t_1), X_BTC),
+            Error::<Test>::ClaimPaused
+        );
+
+        // The mining weight keeps accruing while paused.
+        assert!(t_xbtc_latest_weight_of(t_1) > 0);
+
+        assert_ok!(XMiningAsset::set_claim_paused(Origin::root(), X_BTC, false));
+        assert!(!XMiningAsset::mining_assets()[0].claim_paused);
+
+        let dividend =
+            XMiningAsset::compute_dividend_at(&t_1, &X_BTC, System::block_number()).unwrap();
+        assert!(dividend > 0);
+        assert_ok!(XMiningAsset::claim(Origin::signed(t_1), X_BTC));
+        System::assert_last_event(mining_asset_event(crate::Event::Claimed(
+            t_1, X_BTC, dividend,
+        )));
+    });
+}
+
+#[test]
+fn psedu_claimable_should_match_next_block_claim() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(t_register_xbtc());
+        t_xbtc_set_claim_frequency_limit(0);
+        t_xbtc_set_claim_staking_requirement(0);
+
+        // Block 1
+        t_start_session(1);
+        let t_1 = 777;
+        assert!(XMiningAsset::psedu_claimable(t_1).is_empty());
+
+        assert_ok!(t_issue_xbtc(t_1, 100));
+        let claimables = XMiningAsset::psedu_claimable(t_1);
+        assert_eq!(claimables.len(), 1);
+        assert_eq!(claimables[0].asset_id, X_BTC);
+        assert_eq!(claimables[0].precision, btc().1.decimals());
+        assert_eq!(claimables[0].balance, 100);
+        assert!(!claimables[0].claim_paused);
+
+        // Block 2
+        t_start_session(2);
+        let claimable = XMiningAsset::psedu_claimable(t_1).remove(0);
+        assert!(claimable.mining_weight > 0);
+        assert!(claimable.total_mining_weight >= claimable.mining_weight);
+        assert!(claimable.dividend > 0);
+        assert_eq!(claimable.insufficient_stake, 0);
+
+        // The estimate is made for the claim executed in the next block.
+        System::set_block_number(System::block_number() + 1);
+        assert_ok!(XMiningAsset::claim(Origin::signed(t_1), X_BTC));
+        System::assert_last_event(crate::mock::Event::XMiningAsset(crate::Event::Claimed(
+            t_1,
+            X_BTC,
+            claimable.dividend,
+        )));
+
+        assert_ok!(XMiningAsset::set_claim_paused(Origin::root(), X_BTC, true));
+        assert!(XMiningAsset::psedu_claimable(t_1)[0].claim_paused);
+    });
+}
+
+#[test]
+fn claim_switch_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(t_register_xbtc());
+        t_xbtc_set_claim_frequency_limit(0);
+        t_xbtc_set_claim_staking_requirement(0);
+
+        let t_1 = 777;
+        t_issue_pcx(t_1, 100);
+        assert_ok!(t_bond(t_1, 1, 10));
+        assert_ok!(t_issue_xbtc(t_1, 100));
+
+        assert_ok!(XStaking::set_claim_switch(
+            Origin::root(),
+            xpallet_mining_staking::ClaimTypeKind::Intention,
+            false
+        ));
+
+        // Block 1
+        t_start_session(1);
+        assert_err!(
+            XStaking::claim(Origin::signed(t_1), 1),
+            xpallet_mining_staking::Error::<Test>::ClaimsDisabled
+        );
+
+        // The asset mining claims still work.
+        assert!(XMiningAsset::mining_assets()[0].claim_enabled);
+        let dividend =
+            XMiningAsset::compute_dividend_at(&t_1, &X_BTC, System::block_number()).unwrap();
+        assert!(dividend > 0);
+        assert_ok!(XMiningAsset::claim(Origin::signed(t_1), X_BTC));
+        System::assert_last_event(crate::mock::Event::XMiningAsset(crate::Event::Claimed(
+            t_1, X_BTC, dividend,
+        )));
+
+        assert_ok!(XStaking::set_claim_switch(
+            Origin::root(),
+            xpallet_mining_staking::ClaimTypeKind::PseduIntention,
+            false
+        ));
+        assert!(!XMiningAsset::mining_assets()[0].claim_enabled);
+
+        // Block 2
+        t_start_session(2);
+        assert_err!(
+            XMiningAsset::claim(Origin::signed(t_1), X_BTC),
+            Error::<Test>::ClaimsDisabled
+        );
+    });
+}
+
+fn t_check_xbtc_invariants(max_accounts: u32) -> InvariantReport<Balance, BlockNumber> {
+    let mut report = XMiningAsset::check_invariants(X_BTC, max_accounts, None);
+    while let Some(cursor) = report.next_cursor.take() {
+        assert!(report.violations.is_empty());
+        report = XMiningAsset::check_invariants(X_BTC, max_accounts, Some(cursor));
+    }
+    report
+}
+
+#[test]
+fn invariant_check_should_flag_corrupted_totals() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(t_register_xbtc());
+
+        let t_1 = 777;
+        let t_2 = 888;
+        t_system_block_number_inc(1);
+        assert_ok!(t_issue_xbtc(t_1, 100));
+        t_system_block_number_inc(1);
+        assert_ok!(t_issue_xbtc(t_2, 200));
+        t_system_block_number_inc(1);
+        t_xbtc_move(t_1, t_2, 50);
+        t_system_block_number_inc(1);
+
+        // The result does not depend on how the walk is split.
+        assert!(t_check_xbtc_invariants(1).is_consistent());
+        assert!(t_check_xbtc_invariants(u32::MAX).is_consistent());
+        assert_ok!(XMiningAsset::assert_token_invariant(Origin::root(), X_BTC));
+
+        xpallet_assets::TotalAssetBalance::<Test>::mutate(X_BTC, |totals| {
+            *totals.entry(AssetType::Usable).or_default() += 1;
+        });
+
+        let report = t_check_xbtc_invariants(1);
+        assert_eq!(
+            report.violations,
+            vec![InvariantViolation::TotalAssetBalance(
+                AssetType::Usable,
+                300,
+                301
+            )]
+        );
+        assert_err!(
+            XMiningAsset::assert_token_invariant(Origin::root(), X_BTC),
+            Error::<Test>::InvariantViolated
+        );
+
+        xpallet_assets::TotalAssetBalance::<Test>::mutate(X_BTC, |totals| {
+            *totals.entry(AssetType::Usable).or_default() -= 1;
+        });
+        let now = System::block_number();
+        let total_weight = t_xbtc_latest_total_weights();
+        XMiningAsset::force_set_asset_mining_weight(&X_BTC, total_weight + 1, now);
+
+        let report = t_check_xbtc_invariants(1);
+        assert_eq!(
+            report.violations,
+            vec![InvariantViolation::TotalMiningWeight(
+                total_weight,
+                total_weight + 1
+            )]
+        );
+    });
+}
+
+#[test]
+fn channel_first_deposit_bonus_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(t_register_xbtc());
+        MINIMAL_DEPOSIT.with(|v| *v.borrow_mut() = 10);
+
+        let bonus = 1_000;
+        assert_ok!(XMiningAsset::set_channel_first_deposit_bonus(
+            Origin::root(),
+            X_BTC,
+            Some(bonus)
+        ));
+        assert_eq!(
+            XMiningAsset::channel_first_deposit_bonus(X_BTC),
+            Some(bonus)
+        );
+
+        t_start_session(1);
+        let reward_pot = XMiningAsset::reward_pot_for(&X_BTC);
+
+        let depositor = 777;
+        let channel = 10_000_000_000 + depositor;
+        let bonus_event = crate::mock::Event::XMiningAsset(crate::Event::ChannelBonusPaid(
+            channel, X_BTC, depositor, bonus,
+        ));
+
+        // The dust deposit is not the first deposit.
+        assert_ok!(t_issue_xbtc(depositor, 9));
+        assert_eq!(Balances::free_balance(&channel), 0);
+        assert!(!HasDeposited::<Test>::contains_key(depositor, X_BTC));
+
+        // The first deposit pays once.
+        assert_ok!(t_issue_xbtc(depositor, 100));
+        assert_eq!(Balances::free_balance(&channel), bonus);
+        System::assert_last_event(bonus_event);
+        assert!(HasDeposited::<Test>::contains_key(depositor, X_BTC));
+
+        // The second deposit pays nothing.
+        let reward_pot_balance = Balances::free_balance(&reward_pot);
+        assert_ok!(t_issue_xbtc(depositor, 100));
+        assert_eq!(Balances::free_balance(&channel), bonus);
+        assert_eq!(Balances::free_balance(&reward_pot), reward_pot_balance);
+
+        // No channel, no payment.
+        let unreferred = 778;
+        UNREFERRED.with(|unreferred_set| unreferred_set.borrow_mut().insert(unreferred));
+        // Not eligible for the deposit reward either.
+        t_issue_pcx(unreferred, XMiningAsset::deposit_reward() + 1);
+        assert_ok!(t_issue_xbtc(unreferred, 100));
+        assert!(HasDeposited::<Test>::contains_key(unreferred, X_BTC));
+        assert_eq!(Balances::free_balance(&reward_pot), reward_pot_balance);
+    });
+}
+
+#[test]
+fn channel_bonus_should_be_skipped_if_reward_pot_is_empty() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(t_register_xbtc());
+        // The bonus is skipped when the reward pot can't afford it.
+        assert_ok!(XMiningAsset::set_channel_first_deposit_bonus(
+            Origin::root(),
+            X_BTC,
+            Some(1_000)
+        ));
+        let reward_pot = XMiningAsset::reward_pot_for(&X_BTC);
+        assert_eq!(Balances::free_balance(&reward_pot), 0);
+
+        let depositor = 777;
+        let channel = 10_000_000_000 + depositor;
+        assert_ok!(t_issue_xbtc(depositor, 100));
+        assert_eq!(XAssets::usable_balance(&depositor, &X_BTC), 100);
+        assert_eq!(Balances::free_balance(&channel), 0);
+        assert!(HasDeposited::<Test>::contains_key(depositor, X_BTC));
+
+        // Clearing the bonus disables it.
+        assert_ok!(XMiningAsset::set_channel_first_deposit_bonus(
+            Origin::root(),
+            X_BTC,
+            None
+        ));
+        assert_eq!(XMiningAsset::channel_first_deposit_bonus(X_BTC), None);
+    });
+}
+
+#[test]
+fn has_deposited_should_be_backfilled() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(t_register_xbtc());
+
+        // Deposited before the first deposit bonus.
+        let depositor = 777;
+        assert_ok!(t_issue_xbtc(depositor, 100));
+        let _ = HasDeposited::<Test>::remove_all(None);
+        StorageVersion::new(0).put::<XMiningAsset>();
+
+        crate::migrations::migrate::<Test>();
+        assert!(HasDeposited::<Test>::contains_key(depositor, X_BTC));
+        assert_eq!(
+            XMiningAsset::on_chain_storage_version(),
+            crate::migrations::STORAGE_VERSION
+        );
+    });
+}
+
+#[test]
+fn asset_mining_power_should_fall_back_to_oracle_price() {
+    use xp_mining_staking::AssetMining;
+
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(t_register_xbtc());
+        assert_ok!(t_issue_xbtc(777, 100));
+
+        let asset_mining_power = <XMiningAsset as AssetMining<Balance>>::asset_mining_power;
+        assert_eq!(asset_mining_power(), vec![(X_BTC, 100 * 400)]);
+
+        // The fixed power takes precedence over the oracle price.
+        ORACLE_PRICE.with(|v| *v.borrow_mut() = Some(300));
+        assert_eq!(asset_mining_power(), vec![(X_BTC, 100 * 400)]);
+
+        FixedAssetPowerOf::<Test>::remove(X_BTC);
+        assert_eq!(asset_mining_power(), vec![(X_BTC, 100 * 300)]);
+
+        ORACLE_PRICE.with(|v| *v.borrow_mut() = None);
+        assert_eq!(asset_mining_power(), vec![]);
+    });
+}
+
+#[test]
+fn claim_to_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(t_register_xbtc());
+        t_xbtc_set_claim_frequency_limit(0);
+        t_xbtc_set_claim_staking_requirement(0);
+
+        let t_1 = 777;
+        let dest = 888;
+        assert_ok!(t_issue_xbtc(t_1, 100));
+
+        // Block 1
+        t_start_session(1);
+        let reward_pot = XMiningAsset::reward_pot_for(&X_BTC);
+        assert_err!(
+            XMiningAsset::claim_to(Origin::signed(t_1), X_BTC, reward_pot),
+            Error::<Test>::SystemAccountDestination
+        );
+        assert_err!(
+            XMiningAsset::claim_to(Origin::signed(t_1), X_BTC, TREASURY_ACCOUNT),
+            Error::<Test>::SystemAccountDestination
+        );
+
+        // Block 2
+        t_start_session(2);
+        assert!(t_xbtc_latest_weight_of(t_1) > 0);
+        let claimer_balance = Balances::free_balance(&t_1);
+        let dividend =
+            XMiningAsset::compute_dividend_at(&t_1, &X_BTC, System::block_number()).unwrap();
+        assert!(dividend > 0);
+
+        assert_ok!(XMiningAsset::claim_to(Origin::signed(t_1), X_BTC, dest));
+        System::assert_last_event(crate::mock::Event::XMiningAsset(crate::Event::ClaimedTo(
+            t_1, dest, X_BTC, dividend,
+        )));
+
+        // The dividend is paid to dest, the mining weight of the claimer is settled.
+        assert_eq!(Balances::free_balance(&dest), dividend - dividend / 10);
+        assert_eq!(Balances::free_balance(&t_1), claimer_balance);
+        assert_eq!(t_xbtc_latest_weight_of(t_1), 0);
+        assert_eq!(
+            XMiningAsset::miner_ledgers(t_1, X_BTC).last_claim,
+            Some(System::block_number())
+        );
+        assert_eq!(XMiningAsset::miner_ledgers(dest, X_BTC), Default::default());
+    });
+}
+
+#[test]
+fn derived_accounts_should_be_identified() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(t_register_xbtc());
+        t_issue_pcx(5, 100);
+        assert_ok!(t_register(5, 10));
+
+        let identify = |who: AccountId| XAssets::derived_account_of(who);
+        assert_eq!(
+            identify(XMiningAsset::reward_pot_for(&X_BTC)),
+            Some(DerivedAccountKind::TokenJackpot(X_BTC))
+        );
+        // The genesis validator and the newly registered one.
+        assert_eq!(
+            identify(XStaking::reward_pot_for(&1)),
+            Some(DerivedAccountKind::IntentionJackpot(1))
+        );
+        assert_eq!(
+            identify(XStaking::reward_pot_for(&5)),
+            Some(DerivedAccountKind::IntentionJackpot(5))
+        );
+        assert_eq!(
+            identify(TREASURY_ACCOUNT),
+            Some(DerivedAccountKind::Council)
+        );
+        assert_eq!(identify(5), None);
+    });
+}
+
+#[test]
+fn jackpot_outflow_should_be_guarded() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(t_register_xbtc());
+        t_start_session(1);
+
+        let t_1 = 666_666;
+        let xbtc_pot = XMiningAsset::reward_pot_for(&X_BTC);
+        let pot_balance = Balances::free_balance(&xbtc_pot);
+        assert!(pot_balance > 0);
+
+        // The moves out of the reward pots not made by the jackpot paths are rejected.
+        assert_eq!(
+            XAssets::move_balance_keep_alive(&0, &xbtc_pot, &t_1, 1, MoveCaller::User),
+            Err(xpallet_assets::AssetErr::Forbidden)
+        );
+        assert_err!(
+            XStaking::move_from_jackpot(
+                &XStaking::reward_pot_for(&1),
+                &t_1,
+                1,
+                MoveCaller::Staking
+            ),
+            xpallet_mining_staking::Error::<Test>::JackpotOutflowForbidden
+        );
+        assert_eq!(Balances::free_balance(&xbtc_pot), pot_balance);
+
+        // The claim/reward paths still work.
+        assert_ok!(XMiningAsset::move_from_jackpot(&xbtc_pot, &t_1, 1));
+        assert_eq!(Balances::free_balance(&t_1), 1);
+    });
+}
+
+#[test]
+fn pallet_docs_should_cover_all_variants() {
+    use xpallet_support::docs::undocumented_variants;
+
+    assert_eq!(crate::PALLET_DOCS.name, "XMiningAsset");
+    let (missing, unknown) = undocumented_variants::<crate::Event<Test>>(crate::PALLET_DOCS.events);
+    assert!(missing.is_empty(), "undocumented events: {:?}", missing);
+    assert!(unknown.is_empty(), "unknown events: {:?}", unknown);
+    let (missing, unknown) = undocumented_variants::<crate::Error<Test>>(crate::PALLET_DOCS.errors);
+    assert!(missing.is_empty(), "undocumented errors: {:?}", missing);
+    assert!(unknown.is_empty(), "unknown errors: {:?}", unknown);
+}
+
+#[test]
+fn bonded_balance_should_not_be_committed_twice() {
+    ExtBuilder::default().build_and_execute(|| {
+        let pcx = <Test as xpallet_assets_registrar::Config>::NativeAssetId::get();
+        let t_1 = 777;
+        t_issue_pcx(t_1, 100);
+        assert_ok!(t_bond(t_1, 1, 60));
+
+        // The bonded balance is still in the free balance the modules used to check against,
+        // so another module could commit it again in the same block.
+        assert_eq!(Balances::free_balance(&t_1), 100);
+        assert_eq!(XAssets::held_balance(&t_1, pcx), 60);
+        assert_eq!(XAssets::spendable_balance(&t_1, &pcx), 40);
+        assert_noop!(
+            XAssets::hold(&pcx, &t_1, 50),
+            xpallet_assets::Error::<Test>::InsufficientBalance
+        );
+
+        // Nor can the balance held by another module be bonded.
+        assert_ok!(XAssets::hold(&pcx, &t_1, 30));
+        assert_noop!(
+            t_bond(t_1, 1, 20),
+            xpallet_mining_staking::Error::<Test>::InsufficientBalance
+        );
+        assert_ok!(t_bond(t_1, 1, 10));
+        assert_eq!(XAssets::spendable_balance(&t_1, &pcx), 0);
+    });
+}
@@ -1,5 +1,7 @@
 // Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
 
+use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
+
 use codec::{Decode, Encode};
 use scale_info::TypeInfo;
 #[cfg(feature = "std")]
@@ -8,6 +10,7 @@ use sp_runtime::RuntimeDebug;
 
 use chainx_primitives::AssetId;
 use xp_mining_common::WeightType;
+use xpallet_assets::AssetType;
 
 use crate::Config;
 
@@ -86,3 +89,66 @@ pub struct ClaimRestriction<BlockNumber> {
     /// Claimer can only claim once per `frequency_limit`.
     pub frequency_limit: BlockNumber,
 }
+
+/// The storage walked by the invariant check at the moment.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum InvariantPhase {
+    /// Summing up the balances of the asset holders.
+    AssetBalances,
+    /// Summing up the mining weights of the asset miners.
+    MiningWeights,
+}
+
+/// The resumable position of an invariant check.
+///
+/// The accumulated sums are carried along, so the check can be split
+/// across several calls against the same block.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct InvariantCursor<Balance, BlockNumber> {
+    /// The storage being walked.
+    pub phase: InvariantPhase,
+    /// Raw storage key of the last walked entry in current phase.
+    pub last_key: Option<Vec<u8>>,
+    /// Block number at which the mining weights are settled.
+    pub at: BlockNumber,
+    /// Sum of the walked asset balances per asset type.
+    pub balances: BTreeMap<AssetType, Balance>,
+    /// Sum of the walked miner mining weights.
+    pub mining_weight: MiningWeight,
+}
+
+/// A discrepancy found by the invariant check.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum InvariantViolation<Balance> {
+    /// The holder balances do not add up to the total. [asset_type, sum, total]
+    TotalAssetBalance(AssetType, Balance, Balance),
+    /// The miner weights do not add up to the asset weight. [sum, total]
+    TotalMiningWeight(MiningWeight, MiningWeight),
+}
+
+/// Result of a single invariant check call.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct InvariantReport<Balance, BlockNumber> {
+    pub asset_id: AssetId,
+    /// Number of storage entries walked in this call.
+    pub scanned: u32,
+    /// Pass it to the next call to continue the check, `None` once the check is done.
+    pub next_cursor: Option<InvariantCursor<Balance, BlockNumber>>,
+    /// Always empty until the check is done.
+    pub violations: Vec<InvariantViolation<Balance>>,
+}
+
+impl<Balance, BlockNumber> InvariantReport<Balance, BlockNumber> {
+    /// Returns true if the check is done and nothing is off.
+    pub fn is_consistent(&self) -> bool {
+        self.next_cursor.is_none() && self.violations.is_empty()
+    }
+}
@@ -35,6 +35,9 @@ pub trait WeightInfo {
     fn set_claim_staking_requirement() -> Weight;
     fn set_claim_frequency_limit() -> Weight;
     fn set_asset_power() -> Weight;
+    fn set_claim_paused() -> Weight;
+    fn set_channel_first_deposit_bonus() -> Weight;
+    fn claim_to() -> Weight;
 }
 
 /// Weights for xpallet_mining_asset using the Substrate node and recommended hardware.
@@ -58,6 +61,23 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
     fn set_asset_power() -> Weight {
         (3_043_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
     }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_claim_paused` benchmark.
+    fn set_claim_paused() -> Weight {
+        (3_286_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_channel_first_deposit_bonus` benchmark.
+    fn set_channel_first_deposit_bonus() -> Weight {
+        (3_318_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `claim_to` benchmark.
+    fn claim_to() -> Weight {
+        (189_327_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(13 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
 }
 
 // For backwards compatibility and tests
@@ -80,4 +100,21 @@ impl WeightInfo for () {
     fn set_asset_power() -> Weight {
         (3_043_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
     }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_claim_paused` benchmark.
+    fn set_claim_paused() -> Weight {
+        (3_286_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_channel_first_deposit_bonus` benchmark.
+    fn set_channel_first_deposit_bonus() -> Weight {
+        (3_318_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `claim_to` benchmark.
+    fn claim_to() -> Weight {
+        (189_327_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(13 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
 }
@@ -8,13 +8,23 @@
 use sp_std::{collections::btree_map::BTreeMap, prelude::*};
 
 use codec::Codec;
+use sp_runtime::traits::Block as BlockT;
 
 pub use xpallet_mining_staking::{
-    NominatorInfo, NominatorLedger, Unbonded, ValidatorInfo, ValidatorLedger, VoteWeight,
+    EraIndex, EraProgress, JackpotAccounting, NominationProof, NominatorInfo, NominatorLedger,
+    TreasuryAccounting, TreasurySpend, Unbonded, UnbondedInfo, ValidatorInfo, ValidatorInfoV4,
+    ValidatorLedger, VoteWeight,
 };
 
 sp_api::decl_runtime_apis! {
     /// The API to query Staking info.
+    ///
+    /// Version 2 adds `nomination_proof`.
+    /// Version 3 adds `nomination_records_detailed`.
+    /// Version 4 adds `jackpot_accounting`.
+    /// Version 5 adds `era_progress`, `treasury_accounting` and `unbonded_chunks_of`, and
+    /// the metadata, the claim switch, the performance and the reputation to `ValidatorInfo`.
+    #[api_version(5)]
     pub trait XStakingApi<AccountId, Balance, VoteWeight, BlockNumber>
     where
         AccountId: Codec + Ord,
@@ -25,9 +35,15 @@ sp_api::decl_runtime_apis! {
         /// Get overall information about all potential validators.
         fn validators() -> Vec<ValidatorInfo<AccountId, Balance, VoteWeight, BlockNumber>>;
 
+        #[changed_in(5)]
+        fn validators() -> Vec<ValidatorInfoV4<AccountId, Balance, VoteWeight, BlockNumber>>;
+
         /// Get overall information given the validator AccountId.
         fn validator_info_of(who: AccountId) -> ValidatorInfo<AccountId, Balance, VoteWeight, BlockNumber>;
 
+        #[changed_in(5)]
+        fn validator_info_of(who: AccountId) -> ValidatorInfoV4<AccountId, Balance, VoteWeight, BlockNumber>;
+
         /// Get the staking dividends info given the staker AccountId.
         fn staking_dividend_of(who: AccountId) -> BTreeMap<AccountId, Balance>;
 
@@ -36,5 +52,25 @@ sp_api::decl_runtime_apis! {
 
         /// Get individual nominator information given the nominator AccountId.
         fn nominator_info_of(who: AccountId) -> NominatorInfo<BlockNumber>;
+
+        /// Get the progress of current session and era.
+        fn era_progress() -> EraProgress<BlockNumber>;
+
+        /// Get the accounting of the treasury account.
+        fn treasury_accounting() -> TreasuryAccounting<AccountId, Balance, BlockNumber>;
+
+        /// Get the unbonded chunks given the staker AccountId, with their bonding durations.
+        fn unbonded_chunks_of(who: AccountId) -> BTreeMap<AccountId, Vec<UnbondedInfo<Balance, BlockNumber>>>;
+
+        /// Get the merkle proof of the nomination in the snapshot of the given era and validator.
+        fn nomination_proof(era: EraIndex, validator: AccountId, nominator: AccountId) -> Option<NominationProof<VoteWeight, <Block as BlockT>::Hash>>;
+
+        /// Get the nominations of an account along with the vote weights of the nominator and
+        /// the validator accrued up to the current block.
+        fn nomination_records_detailed(who: AccountId) -> Vec<(AccountId, NominatorLedger<Balance, VoteWeight, BlockNumber>, VoteWeight, VoteWeight)>;
+
+        /// Get the accounting of the reward pots, with their total balance checked against
+        /// the cumulative inflow minus outflow.
+        fn jackpot_accounting() -> JackpotAccounting<Balance>;
     }
 }
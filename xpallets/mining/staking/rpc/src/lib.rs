@@ -9,15 +9,21 @@ use std::sync::Arc;
 
 use codec::Codec;
 use jsonrpc_derive::rpc;
+use serde::{Deserialize, Serialize};
 
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_runtime::{generic::BlockId, traits::Block as BlockT};
 
-use xp_rpc::{runtime_error_into_rpc_err, Result, RpcBalance, RpcVoteWeight};
+use xp_protocol::PCX_DECIMALS;
+use xp_rpc::{
+    display_amount, runtime_error_into_rpc_err, unsupported_api_rpc_err, Result, RpcBalance,
+    RpcVoteWeight,
+};
 
 use xpallet_mining_staking_rpc_runtime_api::{
-    NominatorInfo, NominatorLedger, Unbonded, ValidatorInfo, ValidatorLedger,
+    EraProgress, JackpotAccounting, NominatorInfo, NominatorLedger, TreasuryAccounting,
+    TreasurySpend, Unbonded, UnbondedInfo, ValidatorInfo, ValidatorLedger,
     XStakingApi as XStakingRuntimeApi,
 };
 
@@ -60,12 +66,7 @@ where
         &self,
         who: AccountId,
         at: Option<BlockHash>,
-    ) -> Result<
-        BTreeMap<
-            AccountId,
-            NominatorLedger<RpcBalance<Balance>, RpcVoteWeight<VoteWeight>, BlockNumber>,
-        >,
-    >;
+    ) -> Result<BTreeMap<AccountId, RpcNominatorLedger<Balance, VoteWeight, BlockNumber>>>;
 
     /// Get individual nominator information given the nominator AccountId.
     #[rpc(name = "xstaking_getNominatorByAccount")]
@@ -74,6 +75,35 @@ where
         who: AccountId,
         at: Option<BlockHash>,
     ) -> Result<NominatorInfo<BlockNumber>>;
+
+    /// Get the progress of current session and era, including the countdown to the next era.
+    #[rpc(name = "chainx_getSessionEraProgress")]
+    fn era_progress(&self, at: Option<BlockHash>) -> Result<EraProgress<BlockNumber>>;
+
+    /// Get the rewards received and the spends recorded of the treasury account,
+    /// including its current balance.
+    #[rpc(name = "chainx_getGovernanceAccounts")]
+    fn treasury_accounting(
+        &self,
+        at: Option<BlockHash>,
+    ) -> Result<TreasuryAccounting<AccountId, RpcBalance<Balance>, BlockNumber>>;
+
+    /// Get the unbonded chunks given the staker AccountId, along with the bonding
+    /// durations they were unbonded under.
+    #[rpc(name = "xstaking_getUnbondedChunksByAccount")]
+    fn unbonded_chunks_of(
+        &self,
+        who: AccountId,
+        at: Option<BlockHash>,
+    ) -> Result<BTreeMap<AccountId, Vec<UnbondedInfo<RpcBalance<Balance>, BlockNumber>>>>;
+
+    /// Get the total balance of the reward pots along with their cumulative inflow and
+    /// outflow, which are expected to be balanced.
+    #[rpc(name = "xstaking_getJackpotAccounting")]
+    fn jackpot_accounting(
+        &self,
+        at: Option<BlockHash>,
+    ) -> Result<JackpotAccounting<RpcBalance<Balance>>>;
 }
 
 /// A struct that implements the [`XStakingApi`].
@@ -92,6 +122,34 @@ impl<C, B> XStaking<C, B> {
     }
 }
 
+impl<C, Block> XStaking<C, Block>
+where
+    Block: BlockT,
+    C: ProvideRuntimeApi<Block>,
+{
+    /// Returns true if the runtime at `at` provides an `XStakingApi` of `version` or newer.
+    fn has_api_version<AccountId, Balance, VoteWeight, BlockNumber>(
+        &self,
+        at: &BlockId<Block>,
+        version: u32,
+    ) -> Result<bool>
+    where
+        C::Api: XStakingRuntimeApi<Block, AccountId, Balance, VoteWeight, BlockNumber>,
+        AccountId: Codec + Ord,
+        Balance: Codec,
+        VoteWeight: Codec,
+        BlockNumber: Codec,
+    {
+        self.client
+            .runtime_api()
+            .has_api_with::<dyn XStakingRuntimeApi<Block, AccountId, Balance, VoteWeight, BlockNumber>, _>(
+                at,
+                |v| v >= version,
+            )
+            .map_err(runtime_error_into_rpc_err)
+    }
+}
+
 impl<C, Block, AccountId, Balance, VoteWeight, BlockNumber>
     XStakingApi<<Block as BlockT>::Hash, AccountId, Balance, VoteWeight, BlockNumber>
     for XStaking<C, Block>
@@ -112,7 +170,16 @@ where
     > {
         let api = self.client.runtime_api();
         let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
-        api.validators(&at)
+        let validators = if self
+            .has_api_version::<AccountId, Balance, VoteWeight, BlockNumber>(&at, 5)?
+        {
+            api.validators(&at)
+        } else {
+            #[allow(deprecated)]
+            let validators = api.validators_before_version_5(&at);
+            validators.map(|validators| validators.into_iter().map(Into::into).collect::<Vec<_>>())
+        };
+        validators
             .map(|validators| {
                 validators
                     .into_iter()
@@ -130,6 +197,10 @@ where
                         self_bonded: validator.self_bonded.into(),
                         reward_pot_account: validator.reward_pot_account,
                         reward_pot_balance: validator.reward_pot_balance.into(),
+                        metadata: validator.metadata,
+                        claim_enabled: validator.claim_enabled,
+                        performance: validator.performance,
+                        reputation: validator.reputation,
                     })
                     .collect::<Vec<_>>()
             })
@@ -144,7 +215,15 @@ where
     {
         let api = self.client.runtime_api();
         let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
-        api.validator_info_of(&at, who)
+        let validator =
+            if self.has_api_version::<AccountId, Balance, VoteWeight, BlockNumber>(&at, 5)? {
+                api.validator_info_of(&at, who)
+            } else {
+                #[allow(deprecated)]
+                let validator = api.validator_info_of_before_version_5(&at, who);
+                validator.map(Into::into)
+            };
+        validator
             .map(|validator| ValidatorInfo {
                 account: validator.account,
                 profile: validator.profile,
@@ -157,6 +236,10 @@ where
                 self_bonded: validator.self_bonded.into(),
                 reward_pot_account: validator.reward_pot_account,
                 reward_pot_balance: validator.reward_pot_balance.into(),
+                metadata: validator.metadata,
+                claim_enabled: validator.claim_enabled,
+                performance: validator.performance,
+                reputation: validator.reputation,
             })
             .map_err(runtime_error_into_rpc_err)
     }
@@ -182,33 +265,55 @@ where
         &self,
         who: AccountId,
         at: Option<<Block as BlockT>::Hash>,
-    ) -> Result<
-        BTreeMap<
-            AccountId,
-            NominatorLedger<RpcBalance<Balance>, RpcVoteWeight<VoteWeight>, BlockNumber>,
-        >,
-    > {
+    ) -> Result<BTreeMap<AccountId, RpcNominatorLedger<Balance, VoteWeight, BlockNumber>>> {
         let api = self.client.runtime_api();
         let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
-        api.nomination_details_of(&at, who)
-            .map(|nomination_details| {
-                nomination_details
+        let nomination_records =
+            if self.has_api_version::<AccountId, Balance, VoteWeight, BlockNumber>(&at, 3)? {
+                api.nomination_records_detailed(&at, who).map(|records| {
+                    records
+                        .into_iter()
+                        .map(|(account, ledger, weight, total_weight)| {
+                            (account, ledger, Some(weight), Some(total_weight))
+                        })
+                        .collect::<Vec<_>>()
+                })
+            } else {
+                // The accrued vote weights are unavailable before version 3.
+                api.nomination_details_of(&at, who).map(|records| {
+                    records
+                        .into_iter()
+                        .map(|(account, ledger)| (account, ledger, None, None))
+                        .collect::<Vec<_>>()
+                })
+            };
+        nomination_records
+            .map(|nomination_records| {
+                nomination_records
                     .into_iter()
-                    .map(|(account, nominator_ledger)| {
+                    .map(|(account, nominator_ledger, weight, total_weight)| {
+                        let nomination_display =
+                            display_amount(&nominator_ledger.nomination, PCX_DECIMALS);
+                        let ledger = NominatorLedger {
+                            nomination: nominator_ledger.nomination.into(),
+                            last_vote_weight: nominator_ledger.last_vote_weight.into(),
+                            last_vote_weight_update: nominator_ledger.last_vote_weight_update,
+                            unbonded_chunks: nominator_ledger
+                                .unbonded_chunks
+                                .into_iter()
+                                .map(|unbonded| Unbonded {
+                                    value: unbonded.value.into(),
+                                    locked_until: unbonded.locked_until,
+                                })
+                                .collect(),
+                        };
                         (
                             account,
-                            NominatorLedger {
-                                nomination: nominator_ledger.nomination.into(),
-                                last_vote_weight: nominator_ledger.last_vote_weight.into(),
-                                last_vote_weight_update: nominator_ledger.last_vote_weight_update,
-                                unbonded_chunks: nominator_ledger
-                                    .unbonded_chunks
-                                    .into_iter()
-                                    .map(|unbonded| Unbonded {
-                                        value: unbonded.value.into(),
-                                        locked_until: unbonded.locked_until,
-                                    })
-                                    .collect(),
+                            RpcNominatorLedger {
+                                ledger,
+                                nomination_display,
+                                current_vote_weight: weight.map(Into::into),
+                                validator_current_vote_weight: total_weight.map(Into::into),
                             },
                         )
                     })
@@ -227,4 +332,115 @@ where
         api.nominator_info_of(&at, who)
             .map_err(runtime_error_into_rpc_err)
     }
+
+    fn era_progress(
+        &self,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<EraProgress<BlockNumber>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        if !self.has_api_version::<AccountId, Balance, VoteWeight, BlockNumber>(&at, 5)? {
+            return Err(unsupported_api_rpc_err("XStakingApi", 5));
+        }
+        api.era_progress(&at).map_err(runtime_error_into_rpc_err)
+    }
+
+    fn treasury_accounting(
+        &self,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<TreasuryAccounting<AccountId, RpcBalance<Balance>, BlockNumber>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        if !self.has_api_version::<AccountId, Balance, VoteWeight, BlockNumber>(&at, 5)? {
+            return Err(unsupported_api_rpc_err("XStakingApi", 5));
+        }
+        api.treasury_accounting(&at)
+            .map(|accounting| TreasuryAccounting {
+                account: accounting.account,
+                balance: accounting.balance.into(),
+                cumulative_rewards: accounting.cumulative_rewards.into(),
+                cumulative_spends: accounting.cumulative_spends.into(),
+                spends: accounting
+                    .spends
+                    .into_iter()
+                    .map(|spend| TreasurySpend {
+                        amount: spend.amount.into(),
+                        purpose: spend.purpose,
+                        recorded_at: spend.recorded_at,
+                    })
+                    .collect(),
+            })
+            .map_err(runtime_error_into_rpc_err)
+    }
+
+    fn unbonded_chunks_of(
+        &self,
+        who: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<BTreeMap<AccountId, Vec<UnbondedInfo<RpcBalance<Balance>, BlockNumber>>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        if !self.has_api_version::<AccountId, Balance, VoteWeight, BlockNumber>(&at, 5)? {
+            return Err(unsupported_api_rpc_err("XStakingApi", 5));
+        }
+        api.unbonded_chunks_of(&at, who)
+            .map(|unbonded_chunks| {
+                unbonded_chunks
+                    .into_iter()
+                    .map(|(validator, chunks)| {
+                        let chunks = chunks
+                            .into_iter()
+                            .map(|chunk| UnbondedInfo {
+                                unbonded: Unbonded {
+                                    value: chunk.unbonded.value.into(),
+                                    locked_until: chunk.unbonded.locked_until,
+                                },
+                                bonding_duration: chunk.bonding_duration,
+                            })
+                            .collect();
+                        (validator, chunks)
+                    })
+                    .collect()
+            })
+            .map_err(runtime_error_into_rpc_err)
+    }
+
+    fn jackpot_accounting(
+        &self,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<JackpotAccounting<RpcBalance<Balance>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        if !self.has_api_version::<AccountId, Balance, VoteWeight, BlockNumber>(&at, 4)? {
+            return Err(unsupported_api_rpc_err("XStakingApi", 4));
+        }
+        api.jackpot_accounting(&at)
+            .map(|accounting| JackpotAccounting {
+                total_balance: accounting.total_balance.into(),
+                cumulative_inflow: accounting.cumulative_inflow.into(),
+                cumulative_outflow: accounting.cumulative_outflow.into(),
+                unsettled: accounting.unsettled.into(),
+                balanced: accounting.balanced,
+            })
+            .map_err(runtime_error_into_rpc_err)
+    }
+}
+
+/// `NominatorLedger` with the nomination rendered by the PCX precision.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcNominatorLedger<Balance, VoteWeight, BlockNumber>
+where
+    Balance: Display + FromStr,
+    VoteWeight: Display + FromStr,
+{
+    #[serde(flatten)]
+    pub ledger: NominatorLedger<RpcBalance<Balance>, RpcVoteWeight<VoteWeight>, BlockNumber>,
+    pub nomination_display: String,
+    /// The vote weight of the nominator accrued up to the queried block, `None` at the
+    /// blocks of the runtimes before `nomination_records_detailed`.
+    pub current_vote_weight: Option<RpcVoteWeight<VoteWeight>>,
+    /// The total vote weight of the validator accrued up to the queried block, the
+    /// share of the nominator is `current_vote_weight / validator_current_vote_weight`.
+    pub validator_current_vote_weight: Option<RpcVoteWeight<VoteWeight>>,
 }
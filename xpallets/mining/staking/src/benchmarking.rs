@@ -4,6 +4,7 @@ use super::*;
 
 pub use frame_benchmarking::{account, benchmarks};
 use frame_system::RawOrigin;
+use sp_std::vec;
 
 const SEED: u32 = 0;
 
@@ -17,6 +18,21 @@ pub fn create_funded_user<T: Config>(string: &'static str, n: u32, value: u32) -
     user
 }
 
+/// Sets `n` guardians for the `validator` with the threshold of `n`, returns the guardians.
+fn set_guardians_of<T: Config>(validator: &T::AccountId, n: u32) -> Vec<T::AccountId> {
+    let guardians = (0..n)
+        .map(|i| account::<T::AccountId>("guardian", i, SEED))
+        .collect::<Vec<_>>();
+    assert!(crate::Pallet::<T>::set_guardians(
+        RawOrigin::Signed(validator.clone()).into(),
+        guardians.clone(),
+        n,
+        Zero::zero(),
+    )
+    .is_ok());
+    guardians
+}
+
 fn b_bond<T: Config>(nominator: T::AccountId, validator: T::AccountId, value: u32) {
     let validator_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(validator);
     assert!(crate::Pallet::<T>::bond(
@@ -27,11 +43,19 @@ fn b_bond<T: Config>(nominator: T::AccountId, validator: T::AccountId, value: u3
     .is_ok());
 }
 
+/// Returns a referral identity within the valid charset for the `n`th validator.
+fn referral_id_of(n: u32) -> ReferralId {
+    n.to_be_bytes()
+        .iter()
+        .flat_map(|byte| [b'a' + (byte >> 4), b'a' + (byte & 0x0f)])
+        .collect()
+}
+
 pub fn create_validator<T: Config>(string: &'static str, n: u32, value: u32) -> T::AccountId {
     let validator = create_funded_user::<T>(string, n, value);
     assert!(crate::Pallet::<T>::register(
         RawOrigin::Signed(validator.clone()).into(),
-        n.to_be_bytes().to_vec(),
+        referral_id_of(n),
         value.into()
     )
     .is_ok());
@@ -41,8 +65,8 @@ pub fn create_validator<T: Config>(string: &'static str, n: u32, value: u32) ->
 benchmarks! {
     register {
         let validator = create_funded_user::<T>("validator", 0, 100);
-        let referral_id = 0_u32.to_be_bytes();
-    }: _(RawOrigin::Signed(validator.clone()), referral_id.to_vec(), 10u32.into())
+        let referral_id = referral_id_of(0);
+    }: _(RawOrigin::Signed(validator.clone()), referral_id, 10u32.into())
     verify {
         assert!(Validators::<T>::contains_key(validator));
     }
@@ -181,6 +205,234 @@ benchmarks! {
     verify {
         assert_eq!(SessionsPerEra::<T>::get(), c);
     }
+
+    set_metadata {
+        let validator: T::AccountId = create_validator::<T>("validator", 2, 1000);
+        let entries = vec![
+            (MetaKey::Website, b"https://chainx.org".to_vec()),
+            (MetaKey::Email, b"validator@chainx.org".to_vec()),
+            (MetaKey::Riot, b"@validator:matrix.org".to_vec()),
+            (MetaKey::Twitter, b"@chainx_org".to_vec()),
+            (MetaKey::Telemetry, b"validator".to_vec()),
+        ];
+    }: _(RawOrigin::Signed(validator.clone()), entries)
+    verify {
+        assert_eq!(ValidatorMetadataOf::<T>::get(validator).len(), 5);
+    }
+
+    record_treasury_spend {
+        let history = (0..MAXIMUM_TREASURY_SPEND_HISTORY)
+            .map(|_| TreasurySpend {
+                amount: 1u32.into(),
+                purpose: b"benchmark".to_vec().into(),
+                recorded_at: Zero::zero(),
+            })
+            .collect::<Vec<_>>();
+        TreasurySpendHistory::<T>::put(history);
+        let amount: BalanceOf<T> = 1000u32.into();
+    }: _(RawOrigin::Root, amount, b"treasury spend".to_vec().into())
+    verify {
+        assert_eq!(CumulativeTreasurySpends::<T>::get(), amount);
+        assert_eq!(TreasurySpendHistory::<T>::get().len(), MAXIMUM_TREASURY_SPEND_HISTORY);
+    }
+
+    set_claim_switch {
+    }: _(RawOrigin::Root, ClaimTypeKind::Intention, false)
+    verify {
+        assert!(!ClaimSwitch::<T>::get(ClaimTypeKind::Intention));
+    }
+
+    set_performance_leniency {
+    }: _(RawOrigin::Root, Some(150))
+    verify {
+        assert_eq!(PerformanceLeniency::<T>::get(), Some(150));
+    }
+
+    set_reserved_name_prefixes {
+        let prefixes = vec![b"chainx".to_vec(); MAXIMUM_RESERVED_NAME_PREFIXES];
+    }: _(RawOrigin::Root, prefixes)
+    verify {
+        assert_eq!(ReservedNamePrefixes::<T>::get().len(), MAXIMUM_RESERVED_NAME_PREFIXES);
+    }
+
+    audit_existing_names {
+        let n in 1 .. 100;
+        for i in 0..n {
+            create_validator::<T>("audited", i, 100);
+        }
+        NameAuditCursor::<T>::kill();
+    }: _(RawOrigin::Root, n)
+    verify {
+        assert!(NameAuditCursor::<T>::get().is_some());
+    }
+
+    settle_jackpot {
+        let validator: T::AccountId = create_validator::<T>("validator", 2, 1000);
+        let validator_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(validator.clone());
+
+        let reward = UnsettledReward {
+            validator: 10u32.into(),
+            reward_pot: 40u32.into(),
+            last_session: 1,
+        };
+        UnsettledRewards::<T>::insert(&validator, reward);
+        UnsettledRewardTotal::<T>::put::<BalanceOf<T>>(50u32.into());
+
+        let caller: T::AccountId = create_funded_user::<T>("caller", 1, 100);
+    }: _(RawOrigin::Signed(caller), validator_lookup)
+    verify {
+        assert!(UnsettledRewards::<T>::get(&validator).is_none());
+        assert!(UnsettledRewardTotal::<T>::get().is_zero());
+    }
+
+    set_reputation_params {
+        let params = ReputationParams {
+            slash_step_bps: 2_000,
+            recovery_bps: 500,
+            min_bps: 5_000,
+        };
+    }: _(RawOrigin::Root, Some(params))
+    verify {
+        assert_eq!(ReputationConfig::<T>::get(), Some(params));
+    }
+
+    unlock_unbonded_withdrawal_for {
+        let caller = create_funded_user::<T>("caller", 0, 100);
+        let validator: T::AccountId = create_validator::<T>("validator", 2, 100);
+        let validator_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(validator.clone());
+
+        Pallet::<T>::set_validator_bonding_duration(RawOrigin::Root.into(), 0u32.into())?;
+        Pallet::<T>::set_unlock_for_params(
+            RawOrigin::Root.into(),
+            Some(UnlockForParams {
+                grace_period: 0u32.into(),
+                incentive_bps: MAX_UNLOCK_INCENTIVE_BPS,
+            }),
+        )?;
+
+        Pallet::<T>::unbond(
+            RawOrigin::Signed(validator.clone()).into(),
+            validator_lookup.clone(),
+            20u32.into(),
+        )?;
+
+        let block_number: T::BlockNumber = frame_system::Pallet::<T>::block_number();
+        frame_system::Pallet::<T>::set_block_number(block_number + 1u32.into());
+
+    }: _(RawOrigin::Signed(caller), validator_lookup.clone(), validator_lookup, 0)
+    verify {
+        assert!(Pallet::<T>::unbonded_chunks_of(&validator, &validator).is_empty());
+    }
+
+    set_unlock_for_params {
+        let params = UnlockForParams {
+            grace_period: 100u32.into(),
+            incentive_bps: 10,
+        };
+    }: _(RawOrigin::Root, Some(params))
+    verify {
+        assert_eq!(UnlockForConfig::<T>::get(), Some(params));
+    }
+
+    claim_to {
+        let validator: T::AccountId = create_validator::<T>("validator", 2, 1000);
+        let validator_lookup: <T::Lookup as StaticLookup>::Source = T::Lookup::unlookup(validator.clone());
+        let dest: T::AccountId = account("dest", 0, SEED);
+
+        let validator_pot = T::DetermineRewardPotAccount::reward_pot_account_for(&validator);
+
+        let pot_balance = 50u32;
+        T::Currency::make_free_balance_be(&validator_pot, pot_balance.into());
+        T::Currency::issue(pot_balance.into());
+
+        let block_number: T::BlockNumber = frame_system::Pallet::<T>::block_number();
+        frame_system::Pallet::<T>::set_block_number(block_number + 1u32.into());
+    }: _(RawOrigin::Signed(validator), validator_lookup, T::Lookup::unlookup(dest.clone()))
+    verify {
+        assert!(T::Currency::free_balance(&dest) == pot_balance.into());
+    }
+
+    set_guardians {
+        let n in 1 .. MAXIMUM_GUARDIANS;
+        let validator: T::AccountId = create_validator::<T>("validator", 2, 1000);
+        let guardians = (0..n)
+            .map(|i| account::<T::AccountId>("guardian", i, SEED))
+            .collect::<Vec<_>>();
+    }: _(RawOrigin::Signed(validator.clone()), guardians, n, 10u32.into())
+    verify {
+        assert_eq!(GuardiansOf::<T>::get(validator).map(|config| config.threshold), Some(n));
+    }
+
+    initiate_recovery {
+        let validator: T::AccountId = create_validator::<T>("validator", 2, 1000);
+        let guardians = set_guardians_of::<T>(&validator, MAXIMUM_GUARDIANS);
+        let new_account: T::AccountId = account("new_account", 0, SEED);
+    }: _(
+        RawOrigin::Signed(guardians[0].clone()),
+        T::Lookup::unlookup(validator.clone()),
+        T::Lookup::unlookup(new_account)
+    )
+    verify {
+        assert!(ActiveRecoveries::<T>::contains_key(&validator));
+    }
+
+    approve_recovery {
+        let validator: T::AccountId = create_validator::<T>("validator", 2, 1000);
+        let guardians = set_guardians_of::<T>(&validator, MAXIMUM_GUARDIANS);
+        let new_account: T::AccountId = account("new_account", 0, SEED);
+        Pallet::<T>::initiate_recovery(
+            RawOrigin::Signed(guardians[0].clone()).into(),
+            T::Lookup::unlookup(validator.clone()),
+            T::Lookup::unlookup(new_account),
+        )?;
+    }: _(RawOrigin::Signed(guardians[1].clone()), T::Lookup::unlookup(validator.clone()))
+    verify {
+        assert_eq!(ActiveRecoveries::<T>::get(&validator).unwrap().approvals.len(), 2);
+    }
+
+    cancel_recovery {
+        let validator: T::AccountId = create_validator::<T>("validator", 2, 1000);
+        let guardians = set_guardians_of::<T>(&validator, MAXIMUM_GUARDIANS);
+        let new_account: T::AccountId = account("new_account", 0, SEED);
+        Pallet::<T>::initiate_recovery(
+            RawOrigin::Signed(guardians[0].clone()).into(),
+            T::Lookup::unlookup(validator.clone()),
+            T::Lookup::unlookup(new_account),
+        )?;
+    }: _(RawOrigin::Signed(validator.clone()))
+    verify {
+        assert!(!ActiveRecoveries::<T>::contains_key(&validator));
+    }
+
+    finalize_recovery {
+        let validator: T::AccountId = create_validator::<T>("validator", 2, 1000);
+        let guardians = set_guardians_of::<T>(&validator, MAXIMUM_GUARDIANS);
+        let new_account: T::AccountId = account("new_account", 0, SEED);
+        Pallet::<T>::initiate_recovery(
+            RawOrigin::Signed(guardians[0].clone()).into(),
+            T::Lookup::unlookup(validator.clone()),
+            T::Lookup::unlookup(new_account.clone()),
+        )?;
+        for guardian in guardians.iter().skip(1) {
+            Pallet::<T>::approve_recovery(
+                RawOrigin::Signed(guardian.clone()).into(),
+                T::Lookup::unlookup(validator.clone()),
+            )?;
+        }
+    }: _(RawOrigin::Signed(new_account.clone()), T::Lookup::unlookup(validator.clone()))
+    verify {
+        assert_eq!(OperatedValidatorOf::<T>::get(&new_account), Some(validator));
+    }
+
+    emergency_set_validators {
+        let n in 1 .. MAXIMUM_EMERGENCY_VALIDATORS;
+        let validators = (0..n)
+            .map(|i| (create_validator::<T>("validator", i, 1000), u64::from(i)))
+            .collect::<Vec<_>>();
+    }: _(RawOrigin::Root, validators)
+    verify {
+        assert!(EmergencyGovernance::<T>::get().is_some());
+    }
 }
 
 #[cfg(test)]
@@ -204,6 +456,23 @@ mod tests {
             assert_ok!(Pallet::<Test>::test_benchmark_set_minimum_validator_count());
             assert_ok!(Pallet::<Test>::test_benchmark_set_bonding_duration());
             assert_ok!(Pallet::<Test>::test_benchmark_set_validator_bonding_duration());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_metadata());
+            assert_ok!(Pallet::<Test>::test_benchmark_record_treasury_spend());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_claim_switch());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_performance_leniency());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_reserved_name_prefixes());
+            assert_ok!(Pallet::<Test>::test_benchmark_audit_existing_names());
+            assert_ok!(Pallet::<Test>::test_benchmark_settle_jackpot());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_reputation_params());
+            assert_ok!(Pallet::<Test>::test_benchmark_unlock_unbonded_withdrawal_for());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_unlock_for_params());
+            assert_ok!(Pallet::<Test>::test_benchmark_claim_to());
+            assert_ok!(Pallet::<Test>::test_benchmark_set_guardians());
+            assert_ok!(Pallet::<Test>::test_benchmark_initiate_recovery());
+            assert_ok!(Pallet::<Test>::test_benchmark_approve_recovery());
+            assert_ok!(Pallet::<Test>::test_benchmark_cancel_recovery());
+            assert_ok!(Pallet::<Test>::test_benchmark_finalize_recovery());
+            assert_ok!(Pallet::<Test>::test_benchmark_emergency_set_validators());
         });
     }
 }
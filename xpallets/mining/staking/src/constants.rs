@@ -18,6 +18,9 @@ pub const FIXED_TOTAL: u64 = 2_100_000_000_000_000;
 /// so we don't want the candidate list too huge.
 pub const DEFAULT_MAXIMUM_VALIDATOR_COUNT: u32 = 1000;
 
+/// The maximum byte length of each validator metadata value.
+pub const MAXIMUM_METADATA_VALUE_LENGTH: usize = 128;
+
 /// The maximum number of ongoing unbonded operations in parallel.
 pub const DEFAULT_MAXIMUM_UNBONDED_CHUNK_SIZE: u32 = 10;
 
@@ -36,3 +39,33 @@ pub const DEFAULT_BONDING_DURATION: u64 = DEFAULT_BLOCKS_PER_SESSION * 12 * 24 *
 
 /// The default bonding duration for validator is 3 * 10 days.
 pub const DEFAULT_VALIDATOR_BONDING_DURATION: u64 = DEFAULT_BONDING_DURATION * 10;
+
+/// The maximum number of the latest treasury spends kept on chain.
+pub const MAXIMUM_TREASURY_SPEND_HISTORY: usize = 100;
+
+/// The maximum number of the latest bonding duration changes kept on chain.
+pub const MAXIMUM_BONDING_DURATION_HISTORY: usize = 16;
+
+/// The full reputation of a validator in basis points, i.e., 100%.
+pub const MAX_REPUTATION_BPS: u32 = 10_000;
+
+/// The maximum incentive of unlocking the unbonded balances of others, in basis points.
+pub const MAX_UNLOCK_INCENTIVE_BPS: u32 = 100;
+
+/// The maximum number of the recovery guardians of a validator.
+pub const MAXIMUM_GUARDIANS: u32 = 16;
+
+/// The number of the latest ended sessions whose authored blocks are kept on chain.
+pub const AUTHORED_BLOCKS_HISTORY_DEPTH: u32 = 48;
+
+/// The maximum number of the reserved prefixes of referral identities.
+pub const MAXIMUM_RESERVED_NAME_PREFIXES: usize = 32;
+
+/// The number of the latest eras whose nomination snapshots are kept on chain.
+pub const ERA_SNAPSHOT_HISTORY_DEPTH: u32 = 28;
+
+/// The maximum number of the nominations checked per block while taking the era snapshot.
+pub const MAX_SNAPSHOT_NOMINATIONS_PER_BLOCK: u32 = 256;
+
+/// The maximum number of the validators in an emergency validator set.
+pub const MAXIMUM_EMERGENCY_VALIDATORS: u32 = 100;
@@ -0,0 +1,89 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Documentation of the events and errors, served by `chainx_getRuntimeDocs`.
+
+xpallet_support::pallet_docs! {
+    "XStaking",
+    events {
+        Minted => "Issue new balance to this account. [account, reward_amount]",
+        MintedForValidator => "Issue new balance to validator and pot. [validator, reward_amount, validator_pot, reward_amount]",
+        Slashed => "A validator (and its reward pot) was slashed. [validator, slashed_amount]",
+        Bonded => "A nominator bonded to the validator this amount. [nominator, validator, amount, memo]",
+        Rebonded => "A nominator switched the vote from one validator to another. [nominator, from, to, amount]",
+        Unbonded => "A nominator unbonded this amount. [nominator, validator, amount, memo]",
+        Claimed => "A nominator claimed the staking dividend. [nominator, validator, dividend, memo]",
+        Withdrawn => "The nominator withdrew the locked balance from the unlocking queue. [nominator, amount]",
+        ForceChilled => "Offenders were forcibly to be chilled due to insufficient reward pot balance. [session_index, chilled_validators]",
+        ForceAllWithdrawn => "Unlock the unbonded withdrawal by force. [account]",
+        MetadataUpdated => "The validator has updated its metadata. [validator]",
+        TreasurySpendRecorded => "A spend of the treasury account was recorded. [amount, purpose]",
+        ClaimSwitchUpdated => "The claims of the given kind were enabled or disabled. [kind, enabled]",
+        PerformanceLeniencyUpdated => "The leniency of the performance weighting was updated. [leniency]",
+        EraPerformanceRecorded => "The block production of a validator in the ended era. [era, validator, authored_blocks, expected_blocks, missed_sessions]",
+        ReservedNamePrefixesUpdated => "The reserved prefixes of referral identities were updated. [prefixes]",
+        ReferralIdentityViolated => "An existing referral identity violates the naming rules. [validator, referral_id, violation]",
+        NameAuditCompleted => "All the existing referral identities have been audited.",
+        NonElectedValidatorBonded => "A nominator bonded to an active validator outside the current validator set, which earns nothing until it is elected. [nominator, validator]",
+        BondingDurationChanged => "The bonding duration was changed by root. [kind, old, new, at_block]",
+        ReputationParamsUpdated => "The reputation params were updated. [params]",
+        ReputationUpdated => "The reputation of a validator changed. [validator, old_bps, new_bps]",
+        UnbondedWithdrawalUnlockedFor => "The unbonded balance of the owner was unlocked by someone else. [caller, owner, amount, incentive]",
+        UnlockForParamsUpdated => "The params of unlocking the unbonded balances of others were updated. [params]",
+        ClaimedTo => "A nominator claimed the staking dividend to another account. [nominator, dest, validator, dividend]",
+        GuardiansSet => "The recovery guardians of a validator were set. [validator, guardians, threshold, delay_blocks]",
+        RecoveryInitiated => "A guardian initiated the recovery of a validator account. [lost, new_account, guardian]",
+        RecoveryApproved => "A guardian approved the recovery of a validator account. [lost, guardian, approvals]",
+        RecoveryCancelled => "The recovery of a validator account was cancelled. [lost, new_account]",
+        RecoveryFinalized => "The new account took over the operation of the validator. [lost, new_account]",
+        OperatedSessionKeysSet => "The operator set the session keys of the recovered validator. [validator, operator]",
+        EmergencyValidatorsSet => "The emergency validator set was installed, the chain is in the emergency governance state. [validators, active_since_session]",
+        EmergencyGovernanceCleared => "The emergency governance state was cleared by the start of a normal era. [era]",
+        JackpotOutflowRejected => "An outflow from the reward pot not made by the claim/reward/slash paths was rejected. [reward_pot, dest, value, caller]",
+    }
+    errors {
+        ZeroBalance => "The operation of zero balance in Staking makes no sense.",
+        ZeroVoteWeight => "No rewards when the vote weight is zero.",
+        NotValidator => "Invalid validator target.",
+        AlreadyValidator => "The account is already registered as a validator.",
+        TooManyValidators => "The validators count already reaches `MaximumValidatorCount`.",
+        NoMoreAcceptableVotes => "The validator can accept no more votes from other voters.",
+        TooFewActiveValidators => "The validator can not (forcedly) be chilled due to the limit of minimal validators count.",
+        InsufficientBalance => "Free balance can not cover this bond operation.",
+        NoMoreRebond => "Can not rebond due to the restriction of rebond frequency limit.",
+        InvalidRebondBalance => "An account can only rebond the balance that is no more than what it has bonded to the validator.",
+        RebondSelfBondedNotAllowed => "Can not rebond the validator self-bonded votes as it has a much longer bonding duration.",
+        InvalidUnbondBalance => "An account can only unbond the balance that is no more than what it has bonded to the validator.",
+        NoMoreUnbondChunks => "An account can have only `MaximumUnbondedChunkSize` unbonded entries in parallel.",
+        EmptyUnbondedChunks => "The account has no unbonded entries.",
+        InvalidUnbondedIndex => "Can not find the unbonded entry given the index.",
+        UnbondedWithdrawalNotYetDue => "The unbonded balances are still in the locked state.",
+        InvalidReferralIdentityLength => "The length of referral identity is either too long or too short.",
+        OccupiedReferralIdentity => "The referral identity has been claimed by someone else.",
+        XssCheckFailed => "Failed to pass the xss check.",
+        AllocateDividendFailed => "Failed to allocate the dividend.",
+        InvalidReferralIdentityCharset => "The referral identity contains the characters other than ASCII alphanumerics and `-_.`.",
+        ReservedReferralIdentity => "The referral identity starts with a reserved prefix.",
+        DuplicateMetadataKey => "The metadata key is specified more than once.",
+        InvalidMetadataValue => "The metadata value is either too long, empty or malformed.",
+        ClaimsDisabled => "The claims are temporarily disabled.",
+        InvalidPerformanceLeniency => "The performance leniency can not be less than 100 percent.",
+        InvalidReservedNamePrefixes => "Too many reserved prefixes, or a prefix is either empty or too long.",
+        InactiveValidator => "The validator is chilled, use `bond_inactive` to nominate it anyway.",
+        InvalidReputationParams => "The reputation params can not exceed 100 percent.",
+        UnlockForDisabled => "Only the owner can unlock its unbonded balances.",
+        InvalidUnlockForParams => "The incentive of unlocking the unbonded balances of others exceeds `MAX_UNLOCK_INCENTIVE_BPS`.",
+        SystemAccountDestination => "The dividend can not be claimed to the treasury or a reward pot.",
+        InvalidGuardians => "The guardians are duplicated, include the validator itself, exceed `MAXIMUM_GUARDIANS`, or the threshold is out of range.",
+        NotGuardian => "The origin is not a guardian of the validator.",
+        RecoveryInProgress => "A recovery of the validator account is already in progress.",
+        NoActiveRecovery => "No recovery of the validator account is in progress.",
+        AlreadyApprovedRecovery => "The guardian has already approved the recovery.",
+        InvalidRecoveryAccount => "The new account is a validator, an operator or the lost account itself.",
+        InsufficientRecoveryApprovals => "The recovery has not been approved by enough guardians.",
+        RecoveryDelayNotElapsed => "The recovery can not be finalized before the delay elapses.",
+        InvalidEmergencyValidators => "The emergency validators are empty, duplicated or exceed `MAXIMUM_EMERGENCY_VALIDATORS`.",
+        NoSessionKeys => "The validator has not set the session keys.",
+        InvalidSessionKeys => "The session keys can not be decoded.",
+        JackpotOutflowForbidden => "The balance of the reward pot can only be moved out by the claim/reward/slash paths.",
+    }
+}
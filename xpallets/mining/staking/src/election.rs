@@ -1,12 +1,17 @@
 // Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
 
 use super::*;
-use frame_support::log;
+use frame_support::{log, weights::Weight};
+use sp_runtime::traits::One;
 use sp_std::vec::Vec;
 
 impl<T: Config> Pallet<T> {
-    /// Returns a new validator set for the new era.
-    pub(crate) fn new_era(start_session_index: SessionIndex) -> Option<Vec<T::AccountId>> {
+    /// Returns the new validator set for the new era, which is `maybe_new_validators` elected
+    /// in advance.
+    pub(crate) fn new_era(
+        start_session_index: SessionIndex,
+        maybe_new_validators: Option<Vec<T::AccountId>>,
+    ) -> Option<Vec<T::AccountId>> {
         // Increment or set current era.
         let current_era = CurrentEra::<T>::mutate(|s| {
             *s = Some(s.map(|s| s + 1).unwrap_or(0));
@@ -15,7 +20,10 @@ impl<T: Config> Pallet<T> {
         ErasStartSessionIndex::<T>::insert(&current_era, &start_session_index);
 
         // Set staking information for new era.
-        let maybe_new_validators = Self::select_and_update_validators(current_era);
+        match maybe_new_validators {
+            Some(ref validators) => Self::note_era_snapshot(current_era, validators),
+            None => Self::note_era_snapshot(current_era, &T::SessionInterface::validators()),
+        }
         debug!(
             target: "runtime::mining::staking",
             "[new_era] era_index:{}, start_session_index:{}, maybe_new_validators:{:?}",
@@ -25,6 +33,113 @@ impl<T: Config> Pallet<T> {
         maybe_new_validators
     }
 
+    /// Returns true if the validator election is deferred to a block without the session
+    /// rotation.
+    ///
+    /// When every block rotates the session, there is no such block and the election is
+    /// performed in `new_session` right away.
+    pub(crate) fn defers_election() -> bool {
+        T::SessionInterface::average_session_length() > One::one()
+    }
+
+    /// Returns true if the validators of the new era starting at `session_index` have been
+    /// or will be elected in advance.
+    pub(crate) fn is_election_planned(session_index: SessionIndex) -> bool {
+        Self::pending_election() == Some(session_index)
+            || Self::planned_validators().map(|(planned, _)| planned) == Some(session_index)
+    }
+
+    /// Returns the validators elected in advance for the new era starting at `session_index`,
+    /// the outdated election result is dropped.
+    pub(crate) fn take_planned_validators(
+        session_index: SessionIndex,
+    ) -> Option<Option<Vec<T::AccountId>>> {
+        PlannedValidators::<T>::take()
+            .filter(|(planned, _)| *planned == session_index)
+            .map(|(_, validators)| validators)
+    }
+
+    /// Returns the validators elected in advance for `next_era`, or re-elects them if the set
+    /// has gone stale.
+    ///
+    /// The set is stale once any validator of it, or of the current set if it's kept, has been
+    /// chilled since the election, e.g., an offender force chilled in the final session of the
+    /// era, which forces the new era to get rid of it.
+    pub(crate) fn refresh_planned_validators(
+        next_era: EraIndex,
+        planned: Option<Vec<T::AccountId>>,
+    ) -> Option<Vec<T::AccountId>> {
+        let stale = match planned {
+            Some(ref validators) => validators.iter().any(Self::is_chilled),
+            None => T::SessionInterface::validators()
+                .iter()
+                .any(Self::is_chilled),
+        };
+        if stale {
+            debug!(
+                target: "runtime::mining::staking",
+                "[refresh_planned_validators] re-elect the stale planned validators:{:?}", planned
+            );
+            Self::select_and_update_validators(next_era)
+        } else {
+            planned
+        }
+    }
+
+    /// Schedules the validator election if `new_session(next_session_index)` will plan a new era.
+    pub(crate) fn schedule_election(next_session_index: SessionIndex) {
+        if !Self::defers_election() || Self::is_election_planned(next_session_index) {
+            return;
+        }
+        let era_due = match ForceEra::<T>::get() {
+            Forcing::ForceNew | Forcing::ForceAlways => true,
+            Forcing::ForceNone => false,
+            Forcing::NotForcing => {
+                let era_start_session_index = Self::current_era()
+                    .and_then(Self::eras_start_session_index)
+                    .unwrap_or_default();
+                next_session_index.saturating_sub(era_start_session_index)
+                    >= Self::sessions_per_era()
+            }
+        };
+        if era_due {
+            PendingElection::<T>::put(next_session_index);
+        }
+    }
+
+    /// Performs the pending validator election, unless the session was rotated in block `now`.
+    ///
+    /// The new era forced or overdue since the last rotation is scheduled here as well, so that
+    /// a forced era is elected in the block after it's forced.
+    pub(crate) fn elect_pending(now: T::BlockNumber) -> Weight {
+        let mut weight = T::DbWeight::get().reads(1);
+        if !Self::defers_election() || Self::last_rotation_block() == Some(now) {
+            return weight;
+        }
+
+        // `new_session(current_index + 1)` has already been called when the current session
+        // started, the next era can be planned by `new_session(current_index + 2)`.
+        Self::schedule_election(T::SessionInterface::current_index() + 2);
+        weight = weight.saturating_add(T::DbWeight::get().reads(7));
+
+        if let Some(session_index) = PendingElection::<T>::take() {
+            let validators = Self::validator_set().count() as Weight;
+            let next_era = Self::current_era().map(|era| era + 1).unwrap_or_default();
+            let maybe_new_validators = Self::select_and_update_validators(next_era);
+            debug!(
+                target: "runtime::mining::staking",
+                "[elect_pending] session_index:{}, maybe_new_validators:{:?}",
+                session_index, maybe_new_validators
+            );
+            PlannedValidators::<T>::put((session_index, maybe_new_validators));
+            // Each candidate is checked against its profile, ledger, self bond and session keys.
+            weight = weight.saturating_add(
+                T::DbWeight::get().reads_writes(validators.saturating_mul(4).saturating_add(2), 2),
+            );
+        }
+        weight
+    }
+
     /// Returns true if the (potential) validator is able to join in the election.
     ///
     /// Three requirements:
@@ -71,7 +186,9 @@ impl<T: Config> Pallet<T> {
     /// choose the top-most ValidatorCount::get() of them.
     ///
     /// This should only be called at the end of an era.
-    fn select_and_update_validators(_current_era: EraIndex) -> Option<Vec<T::AccountId>> {
+    pub(crate) fn select_and_update_validators(
+        _current_era: EraIndex,
+    ) -> Option<Vec<T::AccountId>> {
         // TODO: might move to offchain worker solution in the future.
         // Currently there is no performance issue practically.
         let candidates = Self::filter_out_candidates();
@@ -0,0 +1,64 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Emergency override of the validator set.
+//!
+//! When too many validators go offline at once, the chain may stall before the
+//! next election. The council can then install a set of registered validators by
+//! `emergency_set_validators`, and puts the chain into the emergency governance
+//! state. No session is forced, the set is planned at the next natural session
+//! rotation and, as any planned set, becomes active one session later, i.e. at
+//! `current_index + 2`. The normal era rotation keeps going and the state is
+//! cleared once the next era starts after the emergency set takes over.
+
+use super::*;
+
+impl<T: Config> Pallet<T> {
+    /// Returns the accounts of `validators` ordered by the given priority, if they are
+    /// all registered validators with the session keys set.
+    pub(crate) fn check_emergency_validators(
+        mut validators: Vec<(T::AccountId, u64)>,
+    ) -> Result<Vec<T::AccountId>, Error<T>> {
+        let count = validators.len();
+        ensure!(
+            count > 0 && count <= MAXIMUM_EMERGENCY_VALIDATORS as usize,
+            Error::<T>::InvalidEmergencyValidators
+        );
+        validators.sort_by(|(a, _), (b, _)| a.cmp(b));
+        validators.dedup_by(|(a, _), (b, _)| a == b);
+        ensure!(
+            validators.len() == count,
+            Error::<T>::InvalidEmergencyValidators
+        );
+        for (validator, _) in validators.iter() {
+            ensure!(Self::is_validator(validator), Error::<T>::NotValidator);
+            ensure!(
+                T::ValidatorRegistration::is_registered(validator),
+                Error::<T>::NoSessionKeys
+            );
+        }
+        validators.sort_by(|(_, p1), (_, p2)| p2.cmp(p1));
+        Ok(validators.into_iter().map(|(v, _)| v).collect())
+    }
+
+    /// Queues `validators` to be planned by `new_session` at the next session rotation.
+    ///
+    /// The rotation after the current session plans the validators of the session after
+    /// it, so the emergency set is active since `current_index + 2`, which is returned.
+    pub(crate) fn apply_emergency_validators(validators: Vec<T::AccountId>) -> SessionIndex {
+        EmergencyValidators::<T>::put(validators);
+        let active_since = T::SessionInterface::current_index() + 2;
+        EmergencyGovernance::<T>::put(active_since);
+        active_since
+    }
+
+    /// Clears the emergency governance state if the era starting at `start_session`
+    /// is the first one after the emergency set took over.
+    pub(crate) fn try_clear_emergency_governance(era: EraIndex, start_session: SessionIndex) {
+        if let Some(active_since) = Self::emergency_governance() {
+            if start_session > active_since {
+                EmergencyGovernance::<T>::kill();
+                Self::deposit_event(Event::<T>::EmergencyGovernanceCleared(era));
+            }
+        }
+    }
+}
@@ -14,6 +14,7 @@ use xp_mining_common::{
     generic_weight_factors, BaseMiningWeight, Claim, ComputeMiningWeight, WeightFactors, WeightType,
 };
 use xp_mining_staking::SessionIndex;
+use xp_runtime::Memo;
 
 use crate::*;
 
@@ -115,8 +116,8 @@ impl<T: Config> Pallet<T> {
         validator: &T::AccountId,
         block_number: T::BlockNumber,
     ) -> Result<DividendParams<T>, Error<T>> {
-        let validator_pot = T::DetermineRewardPotAccount::reward_pot_account_for(validator);
-        let reward_pot_balance = Self::free_balance(&validator_pot);
+        let (validator_pot, reward_pot_balance) =
+            <Self as VoteWeightSettlement<T::AccountId, T::BlockNumber>>::jackpot_of(validator);
 
         let (dividend, source_weight, target_weight) =
             <Self as ComputeMiningWeight<T::AccountId, T::BlockNumber>>::compute_dividend(
@@ -140,11 +141,11 @@ impl<T: Config> Pallet<T> {
     }
 
     fn allocate_dividend(
-        claimer: &T::AccountId,
+        dest: &T::AccountId,
         pot_account: &T::AccountId,
         dividend: BalanceOf<T>,
     ) -> Result<(), Error<T>> {
-        Self::transfer(pot_account, claimer, dividend)
+        Self::move_from_jackpot(pot_account, dest, dividend, MoveCaller::Jackpot)
             .map_err(|_| Error::<T>::AllocateDividendFailed)
     }
 
@@ -156,6 +157,7 @@ impl<T: Config> Pallet<T> {
         current_block: T::BlockNumber,
         delta: Delta<BalanceOf<T>>,
     ) {
+        Self::note_snapshot_before_update(nominator, validator);
         Nominations::<T>::mutate(nominator, validator, |claimer| {
             claimer.nomination = delta.calculate(claimer.nomination);
             claimer.last_vote_weight = new_weight;
@@ -176,21 +178,35 @@ impl<T: Config> Pallet<T> {
             validator.last_total_vote_weight_update = current_block;
         });
     }
+}
 
-    fn update_claimer_vote_weight_on_claim(
-        claimer: &T::AccountId,
-        target: &T::AccountId,
+impl<T: Config> VoteWeightSettlement<T::AccountId, T::BlockNumber> for Pallet<T> {
+    type Balance = BalanceOf<T>;
+
+    fn jackpot_of(validator: &T::AccountId) -> (T::AccountId, BalanceOf<T>) {
+        (
+            T::DetermineRewardPotAccount::reward_pot_account_for(validator),
+            Self::reward_pot_balance_of(validator),
+        )
+    }
+
+    fn set_claimer_weight(
+        nominator: &T::AccountId,
+        validator: &T::AccountId,
+        new_weight: WeightType,
         current_block: T::BlockNumber,
+        delta: Delta<BalanceOf<T>>,
     ) {
-        Self::set_nominator_vote_weight(claimer, target, 0, current_block, Delta::Zero);
+        Self::set_nominator_vote_weight(nominator, validator, new_weight, current_block, delta);
     }
 
-    fn update_claimee_vote_weight_on_claim(
-        claimee: &T::AccountId,
-        new_vote_weight: WeightType,
+    fn set_claimee_weight(
+        validator: &T::AccountId,
+        new_weight: WeightType,
         current_block: T::BlockNumber,
+        delta: Delta<BalanceOf<T>>,
     ) {
-        Self::set_validator_vote_weight(claimee, new_vote_weight, current_block, Delta::Zero);
+        Self::set_validator_vote_weight(validator, new_weight, current_block, delta);
     }
 }
 
@@ -199,25 +215,46 @@ impl<T: Config> Claim<T::AccountId> for Pallet<T> {
     type Error = Error<T>;
 
     fn claim(claimer: &T::AccountId, claimee: &Self::Claimee) -> Result<(), Self::Error> {
-        let current_block = <frame_system::Pallet<T>>::block_number();
+        Self::apply_claim(claimer, claimee, Memo::default())
+    }
+}
 
-        let (dividend, source_weight, target_weight, claimee_pot) =
-            Self::calculate_dividend_on_claim(claimer, claimee, current_block)?;
+impl<T: Config> Pallet<T> {
+    /// Claims the staking dividend of `claimer` from `claimee`, `memo` is attached to the
+    /// `Claimed` event.
+    pub(crate) fn apply_claim(
+        claimer: &T::AccountId,
+        claimee: &T::AccountId,
+        memo: Memo,
+    ) -> Result<(), Error<T>> {
+        let dividend = Self::settle_claim(claimer, claimee, claimer)?;
 
-        Self::allocate_dividend(claimer, &claimee_pot, dividend)?;
+        Self::deposit_validator_event(
+            claimee,
+            Event::<T>::Claimed(claimer.clone(), claimee.clone(), dividend, memo),
+        );
 
-        Self::deposit_event(Event::<T>::Claimed(
-            claimer.clone(),
-            claimee.clone(),
-            dividend,
-        ));
+        Ok(())
+    }
 
-        let new_target_weight = target_weight - source_weight;
+    /// Settles the vote weights of `claimer` to `claimee` and pays the dividend to `dest`.
+    ///
+    /// Returns the dividend paid.
+    pub(crate) fn settle_claim(
+        claimer: &T::AccountId,
+        claimee: &T::AccountId,
+        dest: &T::AccountId,
+    ) -> Result<BalanceOf<T>, Error<T>> {
+        let current_block = <frame_system::Pallet<T>>::block_number();
 
-        Self::update_claimer_vote_weight_on_claim(claimer, claimee, current_block);
-        Self::update_claimee_vote_weight_on_claim(claimee, new_target_weight, current_block);
+        Self::settle_reward_of(claimee);
 
-        Ok(())
+        <Self as VoteWeightSettlement<T::AccountId, T::BlockNumber>>::claim_against_jackpot(
+            claimer,
+            claimee,
+            current_block,
+            |claimee_pot, dividend| Self::allocate_dividend(dest, claimee_pot, dividend),
+        )
     }
 }
 
@@ -225,7 +262,8 @@ impl<T: Config> Pallet<T> {
     /// Issue new session reward and try slashing the offenders at the same time.
     fn mint_and_slash(session_index: SessionIndex) {
         // Only the active validators can be rewarded.
-        let validator_rewards = Self::distribute_session_reward();
+        let validator_rewards = Self::distribute_session_reward(session_index);
+        Self::note_session_performance(session_index.saturating_sub(1));
 
         // Reset the session offenders.
         if let Some(offenders) = SessionOffenders::<T>::take() {
@@ -249,6 +287,18 @@ impl<T: Config> Pallet<T> {
             Self::current_era(),
         );
 
+        // The emergency set takes over this session only, the era is planned as usual
+        // by the following sessions.
+        let maybe_new_validators = match EmergencyValidators::<T>::take() {
+            Some(validators) => Some(validators),
+            None => Self::try_new_era(session_index),
+        };
+        Self::schedule_election(session_index + 1);
+        maybe_new_validators
+    }
+
+    /// Plans a new era starting at `session_index` if it's due.
+    fn try_new_era(session_index: SessionIndex) -> Option<Vec<T::AccountId>> {
         if let Some(current_era) = Self::current_era() {
             // Initial era has been set.
 
@@ -263,8 +313,7 @@ impl<T: Config> Pallet<T> {
             let ideal_era_length = Self::sessions_per_era().saturated_into::<SessionIndex>();
 
             match ForceEra::<T>::get() {
-                Forcing::ForceNew => ForceEra::<T>::kill(),
-                Forcing::ForceAlways => (),
+                Forcing::ForceNew | Forcing::ForceAlways => (),
                 Forcing::NotForcing if era_length >= ideal_era_length => (),
                 _ => {
                     // Either `ForceNone`, or `NotForcing && era_length < T::SessionsPerEra::get()`.
@@ -281,15 +330,34 @@ impl<T: Config> Pallet<T> {
             }
 
             // new era.
-            Self::new_era(session_index)
+            let maybe_new_validators = if Self::defers_election() {
+                match Self::take_planned_validators(session_index) {
+                    Some(maybe_new_validators) => {
+                        Self::refresh_planned_validators(current_era + 1, maybe_new_validators)
+                    }
+                    None => {
+                        // Not elected in advance, e.g., the era was forced in this block,
+                        // the new era is planned by the next rotation.
+                        return None;
+                    }
+                }
+            } else {
+                Self::select_and_update_validators(current_era + 1)
+            };
+            if ForceEra::<T>::get() == Forcing::ForceNew {
+                ForceEra::<T>::kill();
+            }
+            Self::new_era(session_index, maybe_new_validators)
         } else {
-            // Set initial era
-            Self::new_era(session_index)
+            // Set initial era, there is no reward to distribute yet.
+            Self::new_era(session_index, Self::select_and_update_validators(0))
         }
     }
 
     /// Start a session potentially starting an era.
     fn start_session(start_session: SessionIndex) {
+        LastRotationBlock::<T>::put(<frame_system::Pallet<T>>::block_number());
+
         // Skip the reward minting for the genesis initialization.
         // Actually start from session index 1.
         if start_session > 0 {
@@ -334,8 +402,8 @@ impl<T: Config> Pallet<T> {
     /// * Increment `active_era.index`,
     /// * reset `active_era.start`,
     /// * update `BondedEras` and apply slashes.
-    fn start_era(_start_session: SessionIndex) {
-        let _active_era = ActiveEra::<T>::mutate(|active_era| {
+    fn start_era(start_session: SessionIndex) {
+        let active_era = ActiveEra::<T>::mutate(|active_era| {
             let new_index = active_era.as_ref().map(|info| info.index + 1).unwrap_or(0);
             *active_era = Some(ActiveEraInfo {
                 index: new_index,
@@ -344,6 +412,14 @@ impl<T: Config> Pallet<T> {
             });
             new_index
         });
+
+        // The last session of an era is rewarded right before the next era starts,
+        // so the performance of the ended era is only complete now.
+        if active_era > 0 {
+            Self::record_era_performance(active_era - 1);
+            Self::recover_reputation();
+        }
+        Self::try_clear_emergency_governance(active_era, start_session);
     }
 
     /// Compute payout for era.
@@ -0,0 +1,49 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Guard of the outflows of the reward pots(jackpots).
+//!
+//! The reward pots are only funded by the session rewards and are supposed to be
+//! drained by the dividend claims and the slashes only. Every outflow goes through
+//! [`Pallet::move_from_jackpot`] tagged with the caller, which is checked by
+//! `T::DerivedAccounts` against the reward pots registered in XAssets. The runtime
+//! `MoveGuard` only allows `MoveCaller::Jackpot`, used by the claim/reward/slash
+//! paths, to move the balance out, any other attempt is rejected and logged by
+//! `JackpotOutflowRejected`.
+//!
+//! The root calls of Balances, e.g., `set_balance` and `force_transfer`, never reach
+//! this guard, such changes show up as an unbalanced jackpot accounting instead.
+//!
+//! The inflows and outflows are accumulated so that the total balance of the reward
+//! pots can be checked against them, see [`Pallet::jackpot_accounting`].
+
+use super::*;
+
+impl<T: Config> Pallet<T> {
+    /// Moves `value` out of the reward pot `jackpot` to `dest` on behalf of `caller`.
+    pub fn move_from_jackpot(
+        jackpot: &T::AccountId,
+        dest: &T::AccountId,
+        value: BalanceOf<T>,
+        caller: MoveCaller,
+    ) -> DispatchResult {
+        if let Err(err) = T::DerivedAccounts::ensure_can_move_out(jackpot, caller) {
+            log::warn!(
+                target: "runtime::mining::staking",
+                "[move_from_jackpot] Rejected the outflow of {:?} from {:?} to {:?} by {:?}: {:?}",
+                value, jackpot, dest, caller, err
+            );
+            Self::deposit_event(Event::<T>::JackpotOutflowRejected(
+                jackpot.clone(),
+                dest.clone(),
+                value,
+                caller,
+            ));
+            return Err(Error::<T>::JackpotOutflowForbidden.into());
+        }
+
+        Self::transfer(jackpot, dest, value)?;
+        CumulativeJackpotOutflow::<T>::mutate(|outflow| *outflow = outflow.saturating_add(value));
+
+        Ok(())
+    }
+}
@@ -14,12 +14,22 @@
 #![allow(clippy::result_unit_err)]
 
 mod constants;
+mod docs;
 mod election;
+mod emergency;
 mod impls;
+mod jackpot;
+pub mod migrations;
+mod naming;
+mod performance;
+mod recovery;
+mod reputation;
 mod reward;
 mod rpc;
 mod slashing;
+mod snapshot;
 mod types;
+pub mod vote_weight;
 pub mod weights;
 
 #[cfg(any(feature = "runtime-benchmarks", test))]
@@ -31,13 +41,16 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+use codec::Decode;
 use frame_support::{
     ensure,
     log::debug,
     traits::{
-        Currency, ExistenceRequirement, Get, LockableCurrency, ValidatorRegistration,
-        WithdrawReasons,
+        Currency, EnsureOrigin, EstimateNextSessionRotation, ExistenceRequirement, Get,
+        LockableCurrency, ValidatorRegistration, WithdrawReasons,
     },
+    transactional,
+    weights::{DispatchClass, Weight},
 };
 use frame_system::{ensure_root, ensure_signed};
 use sp_runtime::{
@@ -46,16 +59,21 @@ use sp_runtime::{
 };
 use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
 
-use chainx_primitives::ReferralId;
+use chainx_primitives::{account_event_topic, ReferralId};
 use xp_mining_common::{Claim, ComputeMiningWeight, Delta, ZeroMiningWeightError};
 use xp_mining_staking::{AssetMining, SessionIndex, UnbondedIndex};
-use xpallet_support::traits::TreasuryAccount;
+use xp_runtime::Memo;
+use xpallet_assets::{DerivedAccountKind, DerivedAccountRegistry, MoveCaller};
+use xpallet_support::traits::{NativeHolds, TreasuryAccount};
 
 use crate::constants::*;
 
+pub use self::docs::PALLET_DOCS;
 pub use self::impls::{IdentificationTuple, SimpleValidatorRewardPotAccountDeterminer};
 pub use self::rpc::*;
+pub use self::snapshot::verify_nomination_proof;
 pub use self::types::*;
+pub use self::vote_weight::VoteWeightSettlement;
 pub use self::weights::WeightInfo;
 pub use xp_mining_common::RewardPotAccountFor;
 
@@ -84,9 +102,21 @@ pub mod pallet {
         /// Get the treasury account.
         type TreasuryAccount: TreasuryAccount<Self::AccountId>;
 
+        /// The origin which can record the spends of the treasury account.
+        type CouncilOrigin: EnsureOrigin<Self::Origin>;
+
         /// Asset mining integration.
         type AssetMining: AssetMining<BalanceOf<Self>>;
 
+        /// The native balances held by the modules, which can not be bonded.
+        ///
+        /// The bonded balances are held here as well, so that the other modules see them
+        /// as not spendable.
+        type NativeHolds: NativeHolds<Self::AccountId, BalanceOf<Self>>;
+
+        /// The registry of the reward pots and the treasury account.
+        type DerivedAccounts: DerivedAccountRegistry<Self::AccountId>;
+
         /// Generate the reward pot account for a validator.
         type DetermineRewardPotAccount: RewardPotAccountFor<Self::AccountId, Self::AccountId>;
 
@@ -117,12 +147,28 @@ pub mod pallet {
 
     #[pallet::pallet]
     #[pallet::generate_store(pub(super) trait Store)]
+    #[pallet::storage_version(migrations::STORAGE_VERSION)]
     #[pallet::without_storage_info]
     pub struct Pallet<T>(_);
 
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(n: BlockNumberFor<T>) -> Weight {
+            Self::elect_pending(n).saturating_add(Self::progress_era_snapshot(
+                MAX_SNAPSHOT_NOMINATIONS_PER_BLOCK,
+            ))
+        }
+
+        fn on_runtime_upgrade() -> Weight {
+            migrations::migrate::<T>()
+        }
+    }
+
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         /// Nominate the `target` with `value` of the origin account's balance locked.
+        ///
+        /// The `target` must be active, see `bond_inactive` for nominating a chilled validator.
         #[pallet::weight(T::WeightInfo::bond())]
         pub fn bond(
             origin: OriginFor<T>,
@@ -132,18 +178,7 @@ pub mod pallet {
             let sender = ensure_signed(origin)?;
             let target = T::Lookup::lookup(target)?;
 
-            ensure!(!value.is_zero(), Error::<T>::ZeroBalance);
-            ensure!(Self::is_validator(&target), Error::<T>::NotValidator);
-            ensure!(
-                value + Self::total_locked_of(&sender) <= Self::free_balance(&sender),
-                Error::<T>::InsufficientBalance
-            );
-            if !Self::is_validator_bonding_itself(&sender, &target) {
-                Self::check_validator_acceptable_votes_limit(&target, value)?;
-            }
-
-            Self::apply_bond(&sender, &target, value)?;
-            Ok(())
+            Self::do_bond(&sender, &target, value, Memo::default(), false)
         }
 
         /// Move the `value` of current nomination from one validator to another.
@@ -163,6 +198,11 @@ pub mod pallet {
                 Self::is_validator(&from) && Self::is_validator(&to),
                 Error::<T>::NotValidator
             );
+            // the same as `bond`, a chilled validator only accepts its own bond
+            ensure!(
+                sender == to || Self::is_active(&to),
+                Error::<T>::InactiveValidator
+            );
             ensure!(sender != from, Error::<T>::RebondSelfBondedNotAllowed);
             ensure!(
                 value <= Self::bonded_to(&sender, &from),
@@ -196,7 +236,7 @@ pub mod pallet {
             let target = T::Lookup::lookup(target)?;
 
             Self::can_unbond(&sender, &target, value)?;
-            Self::apply_unbond(&sender, &target, value)?;
+            Self::apply_unbond(&sender, &target, value, Memo::default())?;
             Ok(())
         }
 
@@ -210,30 +250,12 @@ pub mod pallet {
             let sender = ensure_signed(origin)?;
             let target = T::Lookup::lookup(target)?;
 
-            // TODO: use try_mutate
-            let mut unbonded_chunks = Self::unbonded_chunks_of(&sender, &target);
-            ensure!(!unbonded_chunks.is_empty(), Error::<T>::EmptyUnbondedChunks);
-            ensure!(
-                unbonded_index < unbonded_chunks.len() as u32,
-                Error::<T>::InvalidUnbondedIndex
-            );
-
-            let Unbonded {
-                value,
-                locked_until,
-            } = unbonded_chunks[unbonded_index as usize];
-            let current_block = <frame_system::Pallet<T>>::block_number();
-            ensure!(
-                current_block > locked_until,
-                Error::<T>::UnbondedWithdrawalNotYetDue
-            );
-
-            Self::apply_unlock_unbonded_withdrawal(&sender, value);
-
-            unbonded_chunks.swap_remove(unbonded_index as usize);
-            Nominations::<T>::mutate(&sender, &target, |nominator| {
-                nominator.unbonded_chunks = unbonded_chunks;
-            });
+            let value = Self::do_unlock_unbonded_withdrawal(
+                &sender,
+                &target,
+                unbonded_index,
+                Zero::zero(),
+            )?;
 
             Self::deposit_event(Event::<T>::Withdrawn(sender, value));
             Ok(())
@@ -248,6 +270,10 @@ pub mod pallet {
             let sender = ensure_signed(origin)?;
             let target = T::Lookup::lookup(target)?;
 
+            ensure!(
+                Self::claim_switch(ClaimTypeKind::Intention),
+                Error::<T>::ClaimsDisabled
+            );
             ensure!(Self::is_validator(&target), Error::<T>::NotValidator);
 
             <Self as Claim<T::AccountId>>::claim(&sender, &target)?;
@@ -257,7 +283,7 @@ pub mod pallet {
         /// Declare the desire to validate for the origin account.
         #[pallet::weight(T::WeightInfo::validate())]
         pub fn validate(origin: OriginFor<T>) -> DispatchResult {
-            let sender = ensure_signed(origin)?;
+            let sender = Self::operated_validator(ensure_signed(origin)?);
             ensure!(Self::is_validator(&sender), Error::<T>::NotValidator);
             Validators::<T>::mutate(sender, |validator| {
                 validator.is_chilled = false;
@@ -268,7 +294,7 @@ pub mod pallet {
         /// Declare no desire to validate for the origin account.
         #[pallet::weight(T::WeightInfo::chill())]
         pub fn chill(origin: OriginFor<T>) -> DispatchResult {
-            let sender = ensure_signed(origin)?;
+            let sender = Self::operated_validator(ensure_signed(origin)?);
             ensure!(Self::is_validator(&sender), Error::<T>::NotValidator);
             if Self::is_active(&sender) {
                 ensure!(
@@ -291,27 +317,51 @@ pub mod pallet {
         /// can help reduce some misunderstanding for these unfamiliar with
         /// the referral mechanism in Asset Mining. In the context of codebase, we
         /// always use the concept of referral id.
-        #[pallet::weight(T::WeightInfo::register())]
+        ///
+        /// The write of recording the reward pot in `DerivedAccounts` is not covered
+        /// by the benchmark and added on top of it.
+        #[pallet::weight(T::WeightInfo::register().saturating_add(T::DbWeight::get().writes(1)))]
         pub fn register(
             origin: OriginFor<T>,
             validator_nickname: ReferralId,
             #[pallet::compact] initial_bond: BalanceOf<T>,
         ) -> DispatchResult {
             let sender = ensure_signed(origin)?;
-            Self::check_referral_id(&validator_nickname)?;
+            Self::check_new_referral_id(&validator_nickname)?;
             ensure!(!Self::is_validator(&sender), Error::<T>::AlreadyValidator);
             ensure!(
                 (Self::validator_set().count() as u32) < MaximumValidatorCount::<T>::get(),
                 Error::<T>::TooManyValidators
             );
             ensure!(
-                initial_bond <= Self::free_balance(&sender),
+                initial_bond <= Self::spendable_balance(&sender),
                 Error::<T>::InsufficientBalance
             );
             Self::apply_register(&sender, validator_nickname);
             if !initial_bond.is_zero() {
-                Self::apply_bond(&sender, &sender, initial_bond)?;
+                Self::apply_bond(&sender, &sender, initial_bond, Memo::default())?;
+            }
+            Ok(())
+        }
+
+        /// Set the metadata of the validator for the origin account.
+        ///
+        /// The whole set of `entries` overwrites the previous one, an empty set
+        /// means clearing the metadata.
+        #[pallet::weight(T::WeightInfo::set_metadata())]
+        pub fn set_metadata(
+            origin: OriginFor<T>,
+            entries: Vec<(MetaKey, Vec<u8>)>,
+        ) -> DispatchResult {
+            let sender = Self::operated_validator(ensure_signed(origin)?);
+            ensure!(Self::is_validator(&sender), Error::<T>::NotValidator);
+            Self::check_metadata(&entries)?;
+            if entries.is_empty() {
+                ValidatorMetadataOf::<T>::remove(&sender);
+            } else {
+                ValidatorMetadataOf::<T>::insert(&sender, entries);
             }
+            Self::deposit_event(Event::<T>::MetadataUpdated(sender));
             Ok(())
         }
 
@@ -335,23 +385,29 @@ pub mod pallet {
             Ok(())
         }
 
+        /// Set the bonding duration of the nominators.
+        ///
+        /// The in-flight unbondings keep their original unlock block.
         #[pallet::weight(T::WeightInfo::set_bonding_duration())]
         pub fn set_bonding_duration(
             origin: OriginFor<T>,
             #[pallet::compact] new: T::BlockNumber,
         ) -> DispatchResult {
             ensure_root(origin)?;
-            BondingDuration::<T>::put(new);
+            Self::apply_set_bonding_duration(BondingDurationKind::Nominator, new);
             Ok(())
         }
 
+        /// Set the bonding duration of the self-bonded balances of validators.
+        ///
+        /// The in-flight unbondings keep their original unlock block.
         #[pallet::weight(T::WeightInfo::set_validator_bonding_duration())]
         pub fn set_validator_bonding_duration(
             origin: OriginFor<T>,
             #[pallet::compact] new: T::BlockNumber,
         ) -> DispatchResult {
             ensure_root(origin)?;
-            ValidatorBondingDuration::<T>::put(new);
+            Self::apply_set_bonding_duration(BondingDurationKind::Validator, new);
             Ok(())
         }
 
@@ -375,7 +431,7 @@ pub mod pallet {
             Ok(())
         }
 
-        #[pallet::weight(10_000_000)]
+        #[pallet::weight(10_000_000 + T::DbWeight::get().reads(new.len() as Weight))]
         pub fn set_immortals(origin: OriginFor<T>, new: Vec<T::AccountId>) -> DispatchResult {
             ensure_root(origin)?;
             ensure!(
@@ -397,7 +453,7 @@ pub mod pallet {
             who: T::AccountId,
         ) -> DispatchResult {
             ensure_root(origin)?;
-            Locks::<T>::mutate(&who, |locks| {
+            Self::mutate_locks(&who, |locks| {
                 locks.remove(&LockedType::BondedWithdrawal);
             });
             Self::purge_unlockings(&who);
@@ -405,14 +461,20 @@ pub mod pallet {
             Ok(())
         }
 
-        #[pallet::weight(10_000_000)]
+        /// The weight covers the locks of each account and one nomination per account, the
+        /// accounts nominating many validators should be reset in smaller batches.
+        #[pallet::weight(
+            10_000_000 + T::DbWeight::get()
+                .reads_writes(4, 4)
+                .saturating_mul(accounts.len() as Weight)
+        )]
         pub fn force_reset_staking_lock(
             origin: OriginFor<T>,
             accounts: Vec<T::AccountId>,
         ) -> DispatchResult {
             ensure_root(origin)?;
             for who in accounts.iter() {
-                Locks::<T>::mutate(who, |locks| {
+                Self::mutate_locks(who, |locks| {
                     locks.remove(&LockedType::BondedWithdrawal);
                     Self::purge_unlockings(who);
                     Self::set_lock(who, *locks.entry(LockedType::Bonded).or_default());
@@ -421,7 +483,13 @@ pub mod pallet {
             Ok(())
         }
 
-        #[pallet::weight(10_000_000)]
+        /// The weight covers the `Locks` and the account data `set_lock` reads and writes for
+        /// each account.
+        #[pallet::weight(
+            10_000_000 + T::DbWeight::get()
+                .reads_writes(2, 2)
+                .saturating_mul(new_locks.len() as Weight)
+        )]
         pub fn force_set_lock(
             origin: OriginFor<T>,
             new_locks: Vec<(T::AccountId, BalanceOf<T>)>,
@@ -432,6 +500,504 @@ pub mod pallet {
             }
             Ok(())
         }
+
+        /// Same as `bond`, but with a `memo` attached to the `Bonded` event.
+        #[pallet::weight(T::WeightInfo::bond())]
+        pub fn bond_with_memo(
+            origin: OriginFor<T>,
+            target: <T::Lookup as StaticLookup>::Source,
+            #[pallet::compact] value: BalanceOf<T>,
+            memo: Memo,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            let target = T::Lookup::lookup(target)?;
+
+            memo.check_validity()?;
+
+            Self::do_bond(&sender, &target, value, memo, false)
+        }
+
+        /// Same as `unbond`, but with a `memo` attached to the `Unbonded` event.
+        #[pallet::weight(T::WeightInfo::unbond())]
+        pub fn unbond_with_memo(
+            origin: OriginFor<T>,
+            target: <T::Lookup as StaticLookup>::Source,
+            #[pallet::compact] value: BalanceOf<T>,
+            memo: Memo,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            let target = T::Lookup::lookup(target)?;
+
+            memo.check_validity()?;
+
+            Self::can_unbond(&sender, &target, value)?;
+            Self::apply_unbond(&sender, &target, value, memo)?;
+            Ok(())
+        }
+
+        /// Same as `claim`, but with a `memo` attached to the `Claimed` event.
+        #[pallet::weight(T::WeightInfo::claim())]
+        pub fn claim_with_memo(
+            origin: OriginFor<T>,
+            target: <T::Lookup as StaticLookup>::Source,
+            memo: Memo,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            let target = T::Lookup::lookup(target)?;
+
+            memo.check_validity()?;
+            ensure!(
+                Self::claim_switch(ClaimTypeKind::Intention),
+                Error::<T>::ClaimsDisabled
+            );
+            ensure!(Self::is_validator(&target), Error::<T>::NotValidator);
+
+            Self::apply_claim(&sender, &target, memo)?;
+            Ok(())
+        }
+
+        /// Record an outgoing spend of the treasury account with its purpose.
+        ///
+        /// Only the latest `MAXIMUM_TREASURY_SPEND_HISTORY` spends are kept.
+        #[pallet::weight(T::WeightInfo::record_treasury_spend())]
+        pub fn record_treasury_spend(
+            origin: OriginFor<T>,
+            #[pallet::compact] amount: BalanceOf<T>,
+            purpose: Memo,
+        ) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+            ensure!(!amount.is_zero(), Error::<T>::ZeroBalance);
+            purpose.check_validity()?;
+
+            CumulativeTreasurySpends::<T>::mutate(|spends| *spends = spends.saturating_add(amount));
+            TreasurySpendHistory::<T>::mutate(|history| {
+                if history.len() >= MAXIMUM_TREASURY_SPEND_HISTORY {
+                    history.remove(0);
+                }
+                history.push(TreasurySpend {
+                    amount,
+                    purpose: purpose.clone(),
+                    recorded_at: <frame_system::Pallet<T>>::block_number(),
+                });
+            });
+            Self::deposit_event(Event::<T>::TreasurySpendRecorded(amount, purpose));
+            Ok(())
+        }
+
+        /// Enable or disable all the claims of the given kind.
+        ///
+        /// The vote weight keeps accruing while the claims are disabled.
+        #[pallet::weight(T::WeightInfo::set_claim_switch())]
+        pub fn set_claim_switch(
+            origin: OriginFor<T>,
+            kind: ClaimTypeKind,
+            enabled: bool,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            ClaimSwitch::<T>::insert(kind, enabled);
+            Self::deposit_event(Event::<T>::ClaimSwitchUpdated(kind, enabled));
+            Ok(())
+        }
+
+        /// Set the leniency (in percent) of the performance weighting of validator rewards.
+        ///
+        /// The staking reward of a validator is scaled by
+        /// `min(1, authored_blocks * leniency / 100 / expected_blocks)` and the withheld
+        /// part goes to the treasury. `None` disables the weighting.
+        #[pallet::weight(T::WeightInfo::set_performance_leniency())]
+        pub fn set_performance_leniency(
+            origin: OriginFor<T>,
+            leniency: Option<u32>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            if let Some(leniency) = leniency {
+                ensure!(leniency >= 100, Error::<T>::InvalidPerformanceLeniency);
+            }
+            PerformanceLeniency::<T>::set(leniency);
+            Self::deposit_event(Event::<T>::PerformanceLeniencyUpdated(leniency));
+            Ok(())
+        }
+
+        /// Set the prefixes the referral identities of new validators can not start with.
+        ///
+        /// The prefixes are matched case-insensitively.
+        #[pallet::weight(T::WeightInfo::set_reserved_name_prefixes())]
+        pub fn set_reserved_name_prefixes(
+            origin: OriginFor<T>,
+            prefixes: Vec<ReferralId>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            ensure!(
+                prefixes.len() <= MAXIMUM_RESERVED_NAME_PREFIXES
+                    && prefixes.iter().all(|prefix| !prefix.is_empty()
+                        && prefix.len() <= T::MaximumReferralId::get() as usize),
+                Error::<T>::InvalidReservedNamePrefixes
+            );
+            let prefixes = prefixes
+                .iter()
+                .map(|prefix| prefix.to_ascii_lowercase())
+                .collect::<Vec<_>>();
+            ReservedNamePrefixes::<T>::put(&prefixes);
+            Self::deposit_event(Event::<T>::ReservedNamePrefixesUpdated(prefixes));
+            Ok(())
+        }
+
+        /// Audit at most `limit` referral identities of the existing validators against
+        /// the naming rules, continuing from where the last audit stopped.
+        ///
+        /// The offending validators are only reported via events, nobody is renamed.
+        #[pallet::weight(T::WeightInfo::audit_existing_names(*limit))]
+        pub fn audit_existing_names(origin: OriginFor<T>, limit: u32) -> DispatchResult {
+            ensure_root(origin)?;
+            Self::audit_referral_ids(limit);
+            Ok(())
+        }
+
+        /// Mint the session rewards accrued to the `target` validator and its reward pot.
+        ///
+        /// The rewards are otherwise minted once the reward pot is claimed or slashed.
+        #[pallet::weight(T::WeightInfo::settle_jackpot())]
+        pub fn settle_jackpot(
+            origin: OriginFor<T>,
+            target: <T::Lookup as StaticLookup>::Source,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            let target = T::Lookup::lookup(target)?;
+            ensure!(Self::is_validator(&target), Error::<T>::NotValidator);
+            Self::settle_reward_of(&target);
+            Ok(())
+        }
+
+        /// Same as `bond`, but the `target` is allowed to be chilled.
+        ///
+        /// The chilled validators earn nothing until they are active and elected again,
+        /// this is for the nominators that intend to bond before the validator activates.
+        #[pallet::weight(T::WeightInfo::bond())]
+        pub fn bond_inactive(
+            origin: OriginFor<T>,
+            target: <T::Lookup as StaticLookup>::Source,
+            #[pallet::compact] value: BalanceOf<T>,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            let target = T::Lookup::lookup(target)?;
+
+            Self::do_bond(&sender, &target, value, Memo::default(), true)
+        }
+
+        /// Set the decay and recovery of the validator reputations.
+        ///
+        /// The staking reward of a validator is scaled by its reputation, which is cut
+        /// by `slash_step_bps` on every slash down to `min_bps` and recovered by
+        /// `recovery_bps` after each era without any slash. `None` disables the reputation.
+        #[pallet::weight(T::WeightInfo::set_reputation_params())]
+        pub fn set_reputation_params(
+            origin: OriginFor<T>,
+            params: Option<ReputationParams>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            if let Some(params) = params {
+                ensure!(
+                    params.min_bps <= MAX_REPUTATION_BPS
+                        && params.slash_step_bps <= MAX_REPUTATION_BPS
+                        && params.recovery_bps <= MAX_REPUTATION_BPS,
+                    Error::<T>::InvalidReputationParams
+                );
+            }
+            ReputationConfig::<T>::set(params);
+            Self::deposit_event(Event::<T>::ReputationParamsUpdated(params));
+            Ok(())
+        }
+
+        /// Unlock the frozen unbonded balances of `owner` that are due.
+        ///
+        /// The owner can always unlock its own unbonded balances once they are due, anyone
+        /// else has to wait for an extra grace period. The unlocked balances always stay
+        /// in the owner's free balance, except the incentive paid to the caller.
+        #[pallet::weight(T::WeightInfo::unlock_unbonded_withdrawal_for())]
+        #[transactional]
+        pub fn unlock_unbonded_withdrawal_for(
+            origin: OriginFor<T>,
+            owner: <T::Lookup as StaticLookup>::Source,
+            target: <T::Lookup as StaticLookup>::Source,
+            #[pallet::compact] unbonded_index: UnbondedIndex,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            let owner = T::Lookup::lookup(owner)?;
+            let target = T::Lookup::lookup(target)?;
+
+            if sender == owner {
+                let value = Self::do_unlock_unbonded_withdrawal(
+                    &owner,
+                    &target,
+                    unbonded_index,
+                    Zero::zero(),
+                )?;
+                Self::deposit_event(Event::<T>::Withdrawn(owner, value));
+                return Ok(());
+            }
+
+            let params = Self::unlock_for_params().ok_or(Error::<T>::UnlockForDisabled)?;
+            let value = Self::do_unlock_unbonded_withdrawal(
+                &owner,
+                &target,
+                unbonded_index,
+                params.grace_period,
+            )?;
+
+            let incentive = Perbill::from_rational(params.incentive_bps, 10_000) * value;
+            if !incentive.is_zero() {
+                T::Currency::transfer(
+                    &owner,
+                    &sender,
+                    incentive,
+                    ExistenceRequirement::AllowDeath,
+                )?;
+            }
+
+            Self::deposit_event(Event::<T>::UnbondedWithdrawalUnlockedFor(
+                sender, owner, value, incentive,
+            ));
+            Ok(())
+        }
+
+        /// Set the grace period and the incentive of unlocking the unbonded balances of
+        /// others, see `unlock_unbonded_withdrawal_for`.
+        ///
+        /// `None` only allows the owners to unlock their unbonded balances.
+        #[pallet::weight(T::WeightInfo::set_unlock_for_params())]
+        pub fn set_unlock_for_params(
+            origin: OriginFor<T>,
+            params: Option<UnlockForParams<T::BlockNumber>>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            if let Some(params) = params {
+                ensure!(
+                    params.incentive_bps <= MAX_UNLOCK_INCENTIVE_BPS,
+                    Error::<T>::InvalidUnlockForParams
+                );
+            }
+            UnlockForConfig::<T>::set(params);
+            Self::deposit_event(Event::<T>::UnlockForParamsUpdated(params));
+            Ok(())
+        }
+
+        /// Claim the staking reward given the `target` validator to the `dest` account.
+        ///
+        /// The vote weights are settled the same as `claim`, only the dividend is paid
+        /// to `dest` instead of the sender.
+        #[pallet::weight(T::WeightInfo::claim_to())]
+        pub fn claim_to(
+            origin: OriginFor<T>,
+            target: <T::Lookup as StaticLookup>::Source,
+            dest: <T::Lookup as StaticLookup>::Source,
+        ) -> DispatchResult {
+            let sender = ensure_signed(origin)?;
+            let target = T::Lookup::lookup(target)?;
+            let dest = T::Lookup::lookup(dest)?;
+
+            ensure!(
+                Self::claim_switch(ClaimTypeKind::Intention),
+                Error::<T>::ClaimsDisabled
+            );
+            ensure!(Self::is_validator(&target), Error::<T>::NotValidator);
+            ensure!(
+                !Self::is_system_account(&dest),
+                Error::<T>::SystemAccountDestination
+            );
+
+            let dividend = Self::settle_claim(&sender, &target, &dest)?;
+            Self::deposit_validator_event(
+                &target,
+                Event::<T>::ClaimedTo(sender, dest, target.clone(), dividend),
+            );
+            Ok(())
+        }
+
+        /// Set the guardians who can recover the validator account of the origin.
+        ///
+        /// `threshold` of the `guardians` have to approve a recovery, which can only be
+        /// finalized `delay_blocks` after the initiation. An empty set of guardians
+        /// disables the recovery.
+        #[pallet::weight(T::WeightInfo::set_guardians(guardians.len() as u32))]
+        pub fn set_guardians(
+            origin: OriginFor<T>,
+            guardians: Vec<T::AccountId>,
+            threshold: u32,
+            delay_blocks: T::BlockNumber,
+        ) -> DispatchResult {
+            let validator = Self::operated_validator(ensure_signed(origin)?);
+            ensure!(Self::is_validator(&validator), Error::<T>::NotValidator);
+            ensure!(
+                !ActiveRecoveries::<T>::contains_key(&validator),
+                Error::<T>::RecoveryInProgress
+            );
+            let guardians = Self::check_guardians(&validator, guardians, threshold)?;
+            if guardians.is_empty() {
+                GuardiansOf::<T>::remove(&validator);
+            } else {
+                GuardiansOf::<T>::insert(
+                    &validator,
+                    GuardianConfig {
+                        guardians: guardians.clone(),
+                        threshold,
+                        delay: delay_blocks,
+                    },
+                );
+            }
+            Self::deposit_event(Event::<T>::GuardiansSet(
+                validator,
+                guardians,
+                threshold,
+                delay_blocks,
+            ));
+            Ok(())
+        }
+
+        /// Initiate the recovery of the `lost` validator account to `new_account`.
+        ///
+        /// Only a guardian of `lost` can initiate the recovery, which counts as its approval.
+        #[pallet::weight(T::WeightInfo::initiate_recovery())]
+        pub fn initiate_recovery(
+            origin: OriginFor<T>,
+            lost: <T::Lookup as StaticLookup>::Source,
+            new_account: <T::Lookup as StaticLookup>::Source,
+        ) -> DispatchResult {
+            let guardian = ensure_signed(origin)?;
+            let lost = T::Lookup::lookup(lost)?;
+            let new_account = T::Lookup::lookup(new_account)?;
+
+            Self::ensure_guardian(&lost, &guardian)?;
+            ensure!(
+                !ActiveRecoveries::<T>::contains_key(&lost),
+                Error::<T>::RecoveryInProgress
+            );
+            Self::check_recovery_account(&lost, &new_account)?;
+
+            ActiveRecoveries::<T>::insert(
+                &lost,
+                RecoveryProcess {
+                    new_account: new_account.clone(),
+                    initiated_at: <frame_system::Pallet<T>>::block_number(),
+                    approvals: sp_std::vec![guardian.clone()],
+                },
+            );
+            Self::deposit_event(Event::<T>::RecoveryInitiated(lost, new_account, guardian));
+            Ok(())
+        }
+
+        /// Approve the ongoing recovery of the `lost` validator account as a guardian.
+        #[pallet::weight(T::WeightInfo::approve_recovery())]
+        pub fn approve_recovery(
+            origin: OriginFor<T>,
+            lost: <T::Lookup as StaticLookup>::Source,
+        ) -> DispatchResult {
+            let guardian = ensure_signed(origin)?;
+            let lost = T::Lookup::lookup(lost)?;
+
+            Self::ensure_guardian(&lost, &guardian)?;
+            let approvals = ActiveRecoveries::<T>::try_mutate(&lost, |maybe_process| {
+                let process = maybe_process.as_mut().ok_or(Error::<T>::NoActiveRecovery)?;
+                ensure!(
+                    !process.approvals.contains(&guardian),
+                    Error::<T>::AlreadyApprovedRecovery
+                );
+                process.approvals.push(guardian.clone());
+                Ok::<_, Error<T>>(process.approvals.len() as u32)
+            })?;
+            Self::deposit_event(Event::<T>::RecoveryApproved(lost, guardian, approvals));
+            Ok(())
+        }
+
+        /// Cancel the ongoing recovery of the validator account of the origin.
+        #[pallet::weight(T::WeightInfo::cancel_recovery())]
+        pub fn cancel_recovery(origin: OriginFor<T>) -> DispatchResult {
+            let validator = Self::operated_validator(ensure_signed(origin)?);
+            let process =
+                ActiveRecoveries::<T>::take(&validator).ok_or(Error::<T>::NoActiveRecovery)?;
+            Self::deposit_event(Event::<T>::RecoveryCancelled(
+                validator,
+                process.new_account,
+            ));
+            Ok(())
+        }
+
+        /// Finalize the recovery of the `lost` validator account once it has been
+        /// approved by enough guardians and the delay has elapsed.
+        ///
+        /// The new account becomes the operator of the validator, the balances and
+        /// the nominations stay with the `lost` account.
+        #[pallet::weight(T::WeightInfo::finalize_recovery())]
+        pub fn finalize_recovery(
+            origin: OriginFor<T>,
+            lost: <T::Lookup as StaticLookup>::Source,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            let lost = T::Lookup::lookup(lost)?;
+
+            let process = Self::active_recoveries(&lost).ok_or(Error::<T>::NoActiveRecovery)?;
+            let config = Self::guardians_of(&lost).ok_or(Error::<T>::NoActiveRecovery)?;
+            ensure!(
+                process.approvals.len() as u32 >= config.threshold,
+                Error::<T>::InsufficientRecoveryApprovals
+            );
+            ensure!(
+                <frame_system::Pallet<T>>::block_number()
+                    >= process.initiated_at.saturating_add(config.delay),
+                Error::<T>::RecoveryDelayNotElapsed
+            );
+            Self::check_recovery_account(&lost, &process.new_account)?;
+
+            ActiveRecoveries::<T>::remove(&lost);
+            Self::apply_recovery(&lost, &process.new_account);
+            Self::deposit_event(Event::<T>::RecoveryFinalized(lost, process.new_account));
+            Ok(())
+        }
+
+        /// Set the session keys of the validator operated by the origin.
+        ///
+        /// The session keys are kept under the validator account, which can not sign for
+        /// them any more once it's recovered, the operator re-points them by this call.
+        /// `keys` and `proof` are the same as those of `Session::set_keys`.
+        #[pallet::weight(
+            T::SessionInterface::set_keys_weight().saturating_add(T::DbWeight::get().reads(2))
+        )]
+        pub fn set_operated_session_keys(
+            origin: OriginFor<T>,
+            keys: Vec<u8>,
+            proof: Vec<u8>,
+        ) -> DispatchResult {
+            let operator = ensure_signed(origin)?;
+            let validator =
+                Self::operated_validator_of(&operator).ok_or(Error::<T>::NotValidator)?;
+            ensure!(Self::is_validator(&validator), Error::<T>::NotValidator);
+            T::SessionInterface::set_keys(&validator, keys, proof)?;
+            Self::deposit_event(Event::<T>::OperatedSessionKeysSet(validator, operator));
+            Ok(())
+        }
+
+        /// Install `validators` as the session validators bypassing the election, as the
+        /// last resort to recover from a mass validator failure.
+        ///
+        /// Each validator is given with its priority in the set, all of them must be
+        /// registered validators with the session keys set, otherwise the whole call is
+        /// rejected. No session is forced, the set is planned at the next session rotation
+        /// and becomes active from the session after it, i.e. `current_index + 2`. The
+        /// chain stays in the emergency governance state until the next normal era starts.
+        #[pallet::weight(T::WeightInfo::emergency_set_validators(validators.len() as u32))]
+        pub fn emergency_set_validators(
+            origin: OriginFor<T>,
+            validators: Vec<(T::AccountId, u64)>,
+        ) -> DispatchResult {
+            T::CouncilOrigin::try_origin(origin)
+                .map(|_| ())
+                .or_else(ensure_root)?;
+            let validators = Self::check_emergency_validators(validators)?;
+            let active_since = Self::apply_emergency_validators(validators.clone());
+            Self::deposit_event(Event::<T>::EmergencyValidatorsSet(validators, active_since));
+            Ok(())
+        }
     }
 
     #[pallet::event]
@@ -443,20 +1009,76 @@ pub mod pallet {
         MintedForValidator(T::AccountId, BalanceOf<T>, T::AccountId, BalanceOf<T>),
         /// A validator (and its reward pot) was slashed. [validator, slashed_amount]
         Slashed(T::AccountId, BalanceOf<T>),
-        /// A nominator bonded to the validator this amount. [nominator, validator, amount]
-        Bonded(T::AccountId, T::AccountId, BalanceOf<T>),
+        /// A nominator bonded to the validator this amount. [nominator, validator, amount, memo]
+        Bonded(T::AccountId, T::AccountId, BalanceOf<T>, Memo),
         /// A nominator switched the vote from one validator to another. [nominator, from, to, amount]
         Rebonded(T::AccountId, T::AccountId, T::AccountId, BalanceOf<T>),
-        /// A nominator unbonded this amount. [nominator, validator, amount]
-        Unbonded(T::AccountId, T::AccountId, BalanceOf<T>),
-        /// A nominator claimed the staking dividend. [nominator, validator, dividend]
-        Claimed(T::AccountId, T::AccountId, BalanceOf<T>),
+        /// A nominator unbonded this amount. [nominator, validator, amount, memo]
+        Unbonded(T::AccountId, T::AccountId, BalanceOf<T>, Memo),
+        /// A nominator claimed the staking dividend. [nominator, validator, dividend, memo]
+        Claimed(T::AccountId, T::AccountId, BalanceOf<T>, Memo),
         /// The nominator withdrew the locked balance from the unlocking queue. [nominator, amount]
         Withdrawn(T::AccountId, BalanceOf<T>),
         /// Offenders were forcibly to be chilled due to insufficient reward pot balance. [session_index, chilled_validators]
         ForceChilled(SessionIndex, Vec<T::AccountId>),
         /// Unlock the unbonded withdrawal by force. [account]
         ForceAllWithdrawn(T::AccountId),
+        /// The validator has updated its metadata. [validator]
+        MetadataUpdated(T::AccountId),
+        /// A spend of the treasury account was recorded. [amount, purpose]
+        TreasurySpendRecorded(BalanceOf<T>, Memo),
+        /// The claims of the given kind were enabled or disabled. [kind, enabled]
+        ClaimSwitchUpdated(ClaimTypeKind, bool),
+        /// The leniency of the performance weighting was updated. [leniency]
+        PerformanceLeniencyUpdated(Option<u32>),
+        /// The block production of a validator in the ended era. [era, validator, authored_blocks, expected_blocks, missed_sessions]
+        EraPerformanceRecorded(EraIndex, T::AccountId, u32, u32, u32),
+        /// The reserved prefixes of referral identities were updated. [prefixes]
+        ReservedNamePrefixesUpdated(Vec<ReferralId>),
+        /// An existing referral identity violates the naming rules. [validator, referral_id, violation]
+        ReferralIdentityViolated(T::AccountId, ReferralId, NameViolation),
+        /// All the existing referral identities have been audited.
+        NameAuditCompleted,
+        /// A nominator bonded to an active validator outside the current validator set,
+        /// which earns nothing until it is elected. [nominator, validator]
+        NonElectedValidatorBonded(T::AccountId, T::AccountId),
+        /// The bonding duration was changed by root. [kind, old, new, at_block]
+        BondingDurationChanged(
+            BondingDurationKind,
+            T::BlockNumber,
+            T::BlockNumber,
+            T::BlockNumber,
+        ),
+        /// The reputation params were updated. [params]
+        ReputationParamsUpdated(Option<ReputationParams>),
+        /// The reputation of a validator changed. [validator, old_bps, new_bps]
+        ReputationUpdated(T::AccountId, u32, u32),
+        /// The unbonded balance of the owner was unlocked by someone else. [caller, owner, amount, incentive]
+        UnbondedWithdrawalUnlockedFor(T::AccountId, T::AccountId, BalanceOf<T>, BalanceOf<T>),
+        /// The params of unlocking the unbonded balances of others were updated. [params]
+        UnlockForParamsUpdated(Option<UnlockForParams<T::BlockNumber>>),
+        /// A nominator claimed the staking dividend to another account. [nominator, dest, validator, dividend]
+        ClaimedTo(T::AccountId, T::AccountId, T::AccountId, BalanceOf<T>),
+        /// The recovery guardians of a validator were set. [validator, guardians, threshold, delay_blocks]
+        GuardiansSet(T::AccountId, Vec<T::AccountId>, u32, T::BlockNumber),
+        /// A guardian initiated the recovery of a validator account. [lost, new_account, guardian]
+        RecoveryInitiated(T::AccountId, T::AccountId, T::AccountId),
+        /// A guardian approved the recovery of a validator account. [lost, guardian, approvals]
+        RecoveryApproved(T::AccountId, T::AccountId, u32),
+        /// The recovery of a validator account was cancelled. [lost, new_account]
+        RecoveryCancelled(T::AccountId, T::AccountId),
+        /// The new account took over the operation of the validator. [lost, new_account]
+        RecoveryFinalized(T::AccountId, T::AccountId),
+        /// The operator set the session keys of the recovered validator. [validator, operator]
+        OperatedSessionKeysSet(T::AccountId, T::AccountId),
+        /// The emergency validator set was installed, the chain is in the emergency
+        /// governance state. [validators, active_since_session]
+        EmergencyValidatorsSet(Vec<T::AccountId>, SessionIndex),
+        /// The emergency governance state was cleared by the start of a normal era. [era]
+        EmergencyGovernanceCleared(EraIndex),
+        /// An outflow from the reward pot not made by the claim/reward/slash paths was
+        /// rejected. [reward_pot, dest, value, caller]
+        JackpotOutflowRejected(T::AccountId, T::AccountId, BalanceOf<T>, MoveCaller),
     }
 
     /// Old name generated by `decl_event`.
@@ -506,6 +1128,55 @@ pub mod pallet {
         XssCheckFailed,
         /// Failed to allocate the dividend.
         AllocateDividendFailed,
+        /// The referral identity contains the characters other than ASCII alphanumerics and `-_.`.
+        InvalidReferralIdentityCharset,
+        /// The referral identity starts with a reserved prefix.
+        ReservedReferralIdentity,
+        /// The metadata key is specified more than once.
+        DuplicateMetadataKey,
+        /// The metadata value is either too long, empty or malformed.
+        InvalidMetadataValue,
+        /// The claims are temporarily disabled.
+        ClaimsDisabled,
+        /// The performance leniency can not be less than 100 percent.
+        InvalidPerformanceLeniency,
+        /// Too many reserved prefixes, or a prefix is either empty or too long.
+        InvalidReservedNamePrefixes,
+        /// The validator is chilled, use `bond_inactive` to nominate it anyway.
+        InactiveValidator,
+        /// The reputation params can not exceed 100 percent.
+        InvalidReputationParams,
+        /// Only the owner can unlock its unbonded balances.
+        UnlockForDisabled,
+        /// The incentive of unlocking the unbonded balances of others exceeds `MAX_UNLOCK_INCENTIVE_BPS`.
+        InvalidUnlockForParams,
+        /// The dividend can not be claimed to the treasury or a reward pot.
+        SystemAccountDestination,
+        /// The guardians are duplicated, include the validator itself, exceed
+        /// `MAXIMUM_GUARDIANS`, or the threshold is out of range.
+        InvalidGuardians,
+        /// The origin is not a guardian of the validator.
+        NotGuardian,
+        /// A recovery of the validator account is already in progress.
+        RecoveryInProgress,
+        /// No recovery of the validator account is in progress.
+        NoActiveRecovery,
+        /// The guardian has already approved the recovery.
+        AlreadyApprovedRecovery,
+        /// The new account is a validator, an operator or the lost account itself.
+        InvalidRecoveryAccount,
+        /// The recovery has not been approved by enough guardians.
+        InsufficientRecoveryApprovals,
+        /// The recovery can not be finalized before the delay elapses.
+        RecoveryDelayNotElapsed,
+        /// The emergency validators are empty, duplicated or exceed `MAXIMUM_EMERGENCY_VALIDATORS`.
+        InvalidEmergencyValidators,
+        /// The validator has not set the session keys.
+        NoSessionKeys,
+        /// The session keys can not be decoded.
+        InvalidSessionKeys,
+        /// The balance of the reward pot can only be moved out by the claim/reward/slash paths.
+        JackpotOutflowForbidden,
     }
 
     /// The ideal number of staking participants.
@@ -568,6 +1239,12 @@ pub mod pallet {
     pub type ValidatorBondingDuration<T: Config> =
         StorageValue<_, T::BlockNumber, ValueQuery, DefaultForValidatorBondingDuration<T>>;
 
+    /// The latest changes of the bonding durations, from the oldest one.
+    #[pallet::storage]
+    #[pallet::getter(fn bonding_duration_history)]
+    pub type BondingDurationHistory<T: Config> =
+        StorageValue<_, Vec<BondingDurationChange<T::BlockNumber>>, ValueQuery>;
+
     #[pallet::type_value]
     pub fn DefaultForMaximumUnbondedChunkSize() -> u32 {
         DEFAULT_MAXIMUM_UNBONDED_CHUNK_SIZE
@@ -610,6 +1287,12 @@ pub mod pallet {
     pub type Validators<T: Config> =
         StorageMap<_, Twox64Concat, T::AccountId, ValidatorProfile<T::BlockNumber>, ValueQuery>;
 
+    /// The map from validator key to the metadata of that validator.
+    #[pallet::storage]
+    #[pallet::getter(fn validator_metadata_of)]
+    pub type ValidatorMetadataOf<T: Config> =
+        StorageMap<_, Twox64Concat, T::AccountId, Vec<(MetaKey, Vec<u8>)>, ValueQuery>;
+
     /// The map from validator key to the vote weight ledger of that validator.
     #[pallet::storage]
     #[pallet::getter(fn validator_ledgers)]
@@ -688,6 +1371,26 @@ pub mod pallet {
     pub type IsCurrentSessionFinal<T: Config> =
         StorageValue<_, bool, ValueQuery, DefaultForIsCurrentSessionFinal>;
 
+    /// The session index of the new era whose validator election is pending.
+    ///
+    /// The election is performed in the next block without the session rotation, so that it
+    /// never coincides with the reward distribution.
+    #[pallet::storage]
+    #[pallet::getter(fn pending_election)]
+    pub type PendingElection<T: Config> = StorageValue<_, SessionIndex>;
+
+    /// The validators elected in advance for the new era starting at the given session index,
+    /// `None` if the current validator set is kept.
+    #[pallet::storage]
+    #[pallet::getter(fn planned_validators)]
+    pub type PlannedValidators<T: Config> =
+        StorageValue<_, (SessionIndex, Option<Vec<T::AccountId>>)>;
+
+    /// The block in which the session was rotated last time.
+    #[pallet::storage]
+    #[pallet::getter(fn last_rotation_block)]
+    pub type LastRotationBlock<T: Config> = StorageValue<_, T::BlockNumber>;
+
     /// Offenders reported in last session.
     #[pallet::storage]
     #[pallet::getter(fn session_offenders)]
@@ -705,6 +1408,201 @@ pub mod pallet {
     #[pallet::getter(fn immortals)]
     pub(super) type Immortals<T: Config> = StorageValue<_, Vec<T::AccountId>>;
 
+    /// The cumulative session rewards minted to the treasury account.
+    #[pallet::storage]
+    #[pallet::getter(fn cumulative_treasury_rewards)]
+    pub type CumulativeTreasuryRewards<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+    /// The cumulative spends of the treasury account recorded by the council.
+    #[pallet::storage]
+    #[pallet::getter(fn cumulative_treasury_spends)]
+    pub type CumulativeTreasurySpends<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+    /// The latest spends of the treasury account, from the oldest one.
+    #[pallet::storage]
+    #[pallet::getter(fn treasury_spend_history)]
+    pub type TreasurySpendHistory<T: Config> =
+        StorageValue<_, Vec<TreasurySpend<BalanceOf<T>, T::BlockNumber>>, ValueQuery>;
+
+    #[pallet::type_value]
+    pub fn DefaultForClaimSwitch() -> bool {
+        true
+    }
+
+    /// Whether the claims of the given kind are enabled.
+    #[pallet::storage]
+    #[pallet::getter(fn claim_switch)]
+    pub type ClaimSwitch<T: Config> =
+        StorageMap<_, Twox64Concat, ClaimTypeKind, bool, ValueQuery, DefaultForClaimSwitch>;
+
+    /// The leniency (in percent) of the performance weighting of validator rewards.
+    ///
+    /// The rewards are split by the vote weight only if it's `None`.
+    #[pallet::storage]
+    #[pallet::getter(fn performance_leniency)]
+    pub type PerformanceLeniency<T: Config> = StorageValue<_, u32>;
+
+    /// The number of blocks authored in the current session.
+    #[pallet::storage]
+    #[pallet::getter(fn session_blocks)]
+    pub type SessionBlocks<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    /// The number of blocks authored by each validator in the current session.
+    #[pallet::storage]
+    #[pallet::getter(fn session_authored_blocks)]
+    pub type SessionAuthoredBlocks<T: Config> =
+        StorageMap<_, Twox64Concat, T::AccountId, u32, ValueQuery>;
+
+    /// The block production of each validator in the active era.
+    ///
+    /// The current session is not included until it ends.
+    #[pallet::storage]
+    #[pallet::getter(fn era_performance_of)]
+    pub type EraPerformance<T: Config> =
+        StorageMap<_, Twox64Concat, T::AccountId, ValidatorPerformance, ValueQuery>;
+
+    /// The lowercase prefixes the referral identities of new validators can not start with.
+    #[pallet::storage]
+    #[pallet::getter(fn reserved_name_prefixes)]
+    pub type ReservedNamePrefixes<T: Config> = StorageValue<_, Vec<ReferralId>, ValueQuery>;
+
+    /// The validators holding each referral identity, keyed by its lowercase form.
+    ///
+    /// More than one validator only for the identities registered before the naming rules.
+    #[pallet::storage]
+    pub type ReferralIdHolders<T: Config> =
+        StorageMap<_, Twox64Concat, ReferralId, Vec<T::AccountId>, ValueQuery>;
+
+    /// The raw key of `ValidatorFor` the last audit of the referral identities stopped at.
+    #[pallet::storage]
+    pub type NameAuditCursor<T: Config> = StorageValue<_, Vec<u8>>;
+
+    /// The session rewards of each validator accrued but not yet minted.
+    #[pallet::storage]
+    #[pallet::getter(fn unsettled_reward_of)]
+    pub type UnsettledRewards<T: Config> =
+        StorageMap<_, Twox64Concat, T::AccountId, UnsettledReward<BalanceOf<T>>>;
+
+    /// The sum of all the `UnsettledRewards`, i.e., the issuance not yet minted.
+    #[pallet::storage]
+    #[pallet::getter(fn unsettled_reward_total)]
+    pub type UnsettledRewardTotal<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+    /// The decay and recovery of the validator reputations, disabled if none.
+    #[pallet::storage]
+    #[pallet::getter(fn reputation_params)]
+    pub type ReputationConfig<T: Config> = StorageValue<_, ReputationParams>;
+
+    /// The grace period and the incentive of unlocking the unbonded balances of others,
+    /// only the owners can unlock if none.
+    #[pallet::storage]
+    #[pallet::getter(fn unlock_for_params)]
+    pub type UnlockForConfig<T: Config> = StorageValue<_, UnlockForParams<T::BlockNumber>>;
+
+    #[pallet::type_value]
+    pub fn DefaultForReputation() -> u32 {
+        MAX_REPUTATION_BPS
+    }
+
+    /// The reputation of each validator in basis points.
+    ///
+    /// Only the reputations below 100% are stored.
+    #[pallet::storage]
+    #[pallet::getter(fn reputation_of)]
+    pub type ReputationOf<T: Config> =
+        StorageMap<_, Twox64Concat, T::AccountId, u32, ValueQuery, DefaultForReputation>;
+
+    /// The validators slashed in the active era, whose reputations won't recover
+    /// once the era ends.
+    #[pallet::storage]
+    pub type SlashedInActiveEra<T: Config> = StorageMap<_, Twox64Concat, T::AccountId, ()>;
+
+    /// The number of blocks authored by each validator in the latest
+    /// `AUTHORED_BLOCKS_HISTORY_DEPTH` ended sessions.
+    #[pallet::storage]
+    #[pallet::getter(fn authored_blocks_history)]
+    pub type AuthoredBlocksHistory<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        SessionIndex,
+        Twox64Concat,
+        T::AccountId,
+        u32,
+        ValueQuery,
+    >;
+
+    /// The total vote weight and the merkle root of the nominations of each elected
+    /// validator, taken at the rotation to the era.
+    ///
+    /// Only the latest `ERA_SNAPSHOT_HISTORY_DEPTH` eras are kept.
+    #[pallet::storage]
+    #[pallet::getter(fn era_snapshot_root)]
+    pub type EraSnapshotRoot<T: Config> =
+        StorageMap<_, Twox64Concat, EraIndex, Vec<(T::AccountId, VoteWeight, T::Hash)>, ValueQuery>;
+
+    /// The nominations committed by `EraSnapshotRoot`, ordered by the nominator.
+    #[pallet::storage]
+    #[pallet::getter(fn era_snapshot_leaves)]
+    pub type EraSnapshotLeaves<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        EraIndex,
+        Twox64Concat,
+        T::AccountId,
+        Vec<(T::AccountId, VoteWeight)>,
+        ValueQuery,
+    >;
+
+    /// The era snapshot being taken, see `snapshot` for the details.
+    #[pallet::storage]
+    #[pallet::getter(fn pending_era_snapshot)]
+    pub type PendingEraSnapshot<T: Config> =
+        StorageValue<_, EraSnapshotProgress<T::AccountId, T::BlockNumber>>;
+
+    /// The recovery guardians of a validator.
+    #[pallet::storage]
+    #[pallet::getter(fn guardians_of)]
+    pub type GuardiansOf<T: Config> =
+        StorageMap<_, Twox64Concat, T::AccountId, GuardianConfig<T::AccountId, T::BlockNumber>>;
+
+    /// The ongoing recoveries, keyed by the lost validator account.
+    #[pallet::storage]
+    #[pallet::getter(fn active_recoveries)]
+    pub type ActiveRecoveries<T: Config> =
+        StorageMap<_, Twox64Concat, T::AccountId, RecoveryProcess<T::AccountId, T::BlockNumber>>;
+
+    /// The account operating a recovered validator, keyed by the validator.
+    #[pallet::storage]
+    #[pallet::getter(fn validator_operator_of)]
+    pub type ValidatorOperatorOf<T: Config> =
+        StorageMap<_, Twox64Concat, T::AccountId, T::AccountId>;
+
+    /// The recovered validator operated by an account, keyed by the operator.
+    #[pallet::storage]
+    #[pallet::getter(fn operated_validator_of)]
+    pub type OperatedValidatorOf<T: Config> =
+        StorageMap<_, Twox64Concat, T::AccountId, T::AccountId>;
+
+    /// The emergency validator set to be planned by the next `new_session`.
+    #[pallet::storage]
+    pub type EmergencyValidators<T: Config> = StorageValue<_, Vec<T::AccountId>>;
+
+    /// The session index from which the emergency validator set is active, set while
+    /// the chain is in the emergency governance state.
+    #[pallet::storage]
+    #[pallet::getter(fn emergency_governance)]
+    pub type EmergencyGovernance<T: Config> = StorageValue<_, SessionIndex>;
+
+    /// The cumulative session rewards minted to the reward pots.
+    #[pallet::storage]
+    #[pallet::getter(fn cumulative_jackpot_inflow)]
+    pub type CumulativeJackpotInflow<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
+    /// The cumulative balances moved out of the reward pots by the claims and slashes.
+    #[pallet::storage]
+    #[pallet::getter(fn cumulative_jackpot_outflow)]
+    pub type CumulativeJackpotOutflow<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
+
     #[pallet::genesis_config]
     pub struct GenesisConfig<T: Config> {
         pub validator_count: u32,
@@ -776,6 +1674,12 @@ pub mod pallet {
                     self_bonded: config.candidate_requirement.0,
                     total: config.candidate_requirement.1,
                 });
+                if let Some(treasury) = T::TreasuryAccount::treasury_account() {
+                    T::DerivedAccounts::note_derived_account(
+                        &treasury,
+                        DerivedAccountKind::Council,
+                    );
+                }
                 Immortals::<T>::put(
                     config
                         .validators
@@ -791,7 +1695,7 @@ pub mod pallet {
                     Pallet::<T>::check_referral_id(referral_id)
                         .expect("Validator referral id must be valid; qed");
                     Pallet::<T>::apply_register(validator, referral_id.to_vec());
-                    Pallet::<T>::apply_bond(validator, validator, *balance)
+                    Pallet::<T>::apply_bond(validator, validator, *balance, Memo::default())
                         .expect("Bonding to validator itself can not fail; qed");
                 }
             };
@@ -819,6 +1723,21 @@ pub trait SessionInterface<AccountId>: frame_system::Config {
 
     /// Get the validators from session.
     fn validators() -> Vec<AccountId>;
+
+    /// Get the index of current session.
+    fn current_index() -> SessionIndex;
+
+    /// Get the average length of a session in blocks.
+    fn average_session_length() -> Self::BlockNumber;
+
+    /// Get the estimated block number at which the next session rotation happens.
+    fn estimate_next_session_rotation(now: Self::BlockNumber) -> Option<Self::BlockNumber>;
+
+    /// Set the encoded session `keys` of `validator`, as if `validator` called `set_keys`.
+    fn set_keys(validator: &AccountId, keys: Vec<u8>, proof: Vec<u8>) -> DispatchResult;
+
+    /// Get the weight of `set_keys`.
+    fn set_keys_weight() -> Weight;
 }
 
 impl<T: Config> SessionInterface<<T as frame_system::Config>::AccountId> for T
@@ -838,6 +1757,36 @@ where
     fn validators() -> Vec<<T as frame_system::Config>::AccountId> {
         <pallet_session::Pallet<T>>::validators()
     }
+
+    fn current_index() -> SessionIndex {
+        <pallet_session::Pallet<T>>::current_index()
+    }
+
+    fn average_session_length() -> T::BlockNumber {
+        T::NextSessionRotation::average_session_length()
+    }
+
+    fn estimate_next_session_rotation(now: T::BlockNumber) -> Option<T::BlockNumber> {
+        T::NextSessionRotation::estimate_next_session_rotation(now).0
+    }
+
+    fn set_keys(
+        validator: &<T as frame_system::Config>::AccountId,
+        keys: Vec<u8>,
+        proof: Vec<u8>,
+    ) -> DispatchResult {
+        let keys = <T as pallet_session::Config>::Keys::decode(&mut keys.as_slice())
+            .map_err(|_| Error::<T>::InvalidSessionKeys)?;
+        <pallet_session::Pallet<T>>::set_keys(
+            frame_system::RawOrigin::Signed(validator.clone()).into(),
+            keys,
+            proof,
+        )
+    }
+
+    fn set_keys_weight() -> Weight {
+        <<T as pallet_session::Config>::WeightInfo as pallet_session::WeightInfo>::set_keys()
+    }
 }
 
 impl<T: Config> xpallet_support::traits::Validator<T::AccountId> for Pallet<T> {
@@ -848,9 +1797,29 @@ impl<T: Config> xpallet_support::traits::Validator<T::AccountId> for Pallet<T> {
     fn validator_for(name: &[u8]) -> Option<T::AccountId> {
         Self::validator_for(name)
     }
+
+    fn recent_authored_blocks(who: &T::AccountId, sessions: u32) -> u32 {
+        Self::recent_authored_blocks(who, sessions)
+    }
 }
 
 impl<T: Config> Pallet<T> {
+    /// Deposits the nomination event indexed by the topic of `validator`,
+    /// see `account_event_topic`.
+    ///
+    /// The write of the topic index is registered as extra weight.
+    pub(crate) fn deposit_validator_event(validator: &T::AccountId, event: Event<T>) {
+        let event = <T as Config>::Event::from(event);
+        frame_system::Pallet::<T>::deposit_event_indexed(
+            &[account_event_topic::<T::Hashing, _>(validator)],
+            event.into(),
+        );
+        frame_system::Pallet::<T>::register_extra_weight_unchecked(
+            T::DbWeight::get().writes(1),
+            DispatchClass::Normal,
+        );
+    }
+
     /// Returns true if the account `who` is a validator.
     #[inline]
     pub fn is_validator(who: &T::AccountId) -> bool {
@@ -863,6 +1832,14 @@ impl<T: Config> Pallet<T> {
         ValidatorFor::<T>::get(referral_id)
     }
 
+    /// Returns the website of validator `who`, which is the `Website` entry of its metadata.
+    pub fn website_of(who: &T::AccountId) -> Option<Vec<u8>> {
+        Self::validator_metadata_of(who)
+            .into_iter()
+            .find(|(key, _)| *key == MetaKey::Website)
+            .map(|(_, value)| value)
+    }
+
     /// Return true if the validator `who` is chilled.
     #[inline]
     pub fn is_chilled(who: &T::AccountId) -> bool {
@@ -919,6 +1896,12 @@ impl<T: Config> Pallet<T> {
         T::DetermineRewardPotAccount::reward_pot_account_for(validator)
     }
 
+    /// Returns true if `who` is the treasury or the reward pot of any validator.
+    fn is_system_account(who: &T::AccountId) -> bool {
+        T::TreasuryAccount::treasury_account().as_ref() == Some(who)
+            || Self::validator_set().any(|validator| &Self::reward_pot_for(&validator) == who)
+    }
+
     #[inline]
     fn unbonded_chunks_of(
         nominator: &T::AccountId,
@@ -932,6 +1915,13 @@ impl<T: Config> Pallet<T> {
         T::Currency::free_balance(who)
     }
 
+    /// Returns the free balance of `who` minus the holds, including the bonded balance
+    /// held by Staking itself.
+    #[inline]
+    fn spendable_balance(who: &T::AccountId) -> BalanceOf<T> {
+        Self::free_balance(who).saturating_sub(T::NativeHolds::native_held(who))
+    }
+
     /// Returns the total votes of a validator.
     #[inline]
     fn total_votes_of(validator: &T::AccountId) -> BalanceOf<T> {
@@ -1009,6 +1999,25 @@ impl<T: Config> Pallet<T> {
         Ok(())
     }
 
+    /// Returns Ok if all the metadata entries are well-formed and the keys are unique.
+    fn check_metadata(entries: &[(MetaKey, Vec<u8>)]) -> Result<(), Error<T>> {
+        let mut keys = entries.iter().map(|(key, _)| *key).collect::<Vec<_>>();
+        keys.sort_unstable();
+        keys.dedup();
+        ensure!(
+            keys.len() == entries.len(),
+            Error::<T>::DuplicateMetadataKey
+        );
+        for (key, value) in entries {
+            ensure!(key.is_valid_value(value), Error::<T>::InvalidMetadataValue);
+            ensure!(
+                xp_runtime::xss_check(value).is_ok(),
+                Error::<T>::XssCheckFailed
+            );
+        }
+        Ok(())
+    }
+
     /// Returns Ok if the validator can still accept the `value` of new votes.
     fn check_validator_acceptable_votes_limit(
         validator: &T::AccountId,
@@ -1071,9 +2080,32 @@ impl<T: Config> Pallet<T> {
         });
     }
 
+    /// Mutates the Staking locks of `who`, the native hold follows their total.
+    fn mutate_locks<R>(
+        who: &T::AccountId,
+        f: impl FnOnce(&mut BTreeMap<LockedType, BalanceOf<T>>) -> R,
+    ) -> R {
+        let total_of = |locks: &BTreeMap<LockedType, BalanceOf<T>>| {
+            locks
+                .values()
+                .fold(Zero::zero(), |acc: BalanceOf<T>, x| acc + *x)
+        };
+        Locks::<T>::mutate(who, |locks| {
+            let old_total = total_of(locks);
+            let result = f(locks);
+            let new_total = total_of(locks);
+            if new_total > old_total {
+                T::NativeHolds::hold_native(who, new_total - old_total);
+            } else if new_total < old_total {
+                T::NativeHolds::release_native(who, old_total - new_total);
+            }
+            result
+        })
+    }
+
     /// Set a lock on `value` of free balance of an account.
     pub(crate) fn bond_reserve(who: &T::AccountId, value: BalanceOf<T>) {
-        Locks::<T>::mutate(who, |locks| {
+        Self::mutate_locks(who, |locks| {
             *locks.entry(LockedType::Bonded).or_default() += value;
 
             let staking_locked = locks
@@ -1104,7 +2136,7 @@ impl<T: Config> Pallet<T> {
 
     /// `unbond` only triggers the internal change of Staking locked type.
     fn unbond_reserve(who: &T::AccountId, value: BalanceOf<T>) -> Result<(), Error<T>> {
-        Locks::<T>::mutate(who, |locks| {
+        Self::mutate_locks(who, |locks| {
             *locks.entry(LockedType::Bonded).or_default() -= value;
             *locks.entry(LockedType::BondedWithdrawal).or_default() += value;
         });
@@ -1126,27 +2158,22 @@ impl<T: Config> Pallet<T> {
         delta: Delta<BalanceOf<T>>,
     ) {
         let current_block = <frame_system::Pallet<T>>::block_number();
-
-        let source_weight =
-            <Self as ComputeMiningWeight<T::AccountId, T::BlockNumber>>::settle_claimer_weight(
-                source,
-                target,
-                current_block,
-            );
-
-        let target_weight =
-            <Self as ComputeMiningWeight<T::AccountId, T::BlockNumber>>::settle_claimee_weight(
-                target,
-                current_block,
-            );
-
-        Self::set_nominator_vote_weight(source, target, source_weight, current_block, delta);
-        Self::set_validator_vote_weight(target, target_weight, current_block, delta);
+        <Self as VoteWeightSettlement<T::AccountId, T::BlockNumber>>::settle(
+            source,
+            target,
+            current_block,
+            delta,
+        );
     }
 
     fn apply_register(who: &T::AccountId, referral_id: ReferralId) {
         let current_block = <frame_system::Pallet<T>>::block_number();
+        T::DerivedAccounts::note_derived_account(
+            &Self::reward_pot_for(who),
+            DerivedAccountKind::IntentionJackpot(who.clone()),
+        );
         ValidatorFor::<T>::insert(&referral_id, who.clone());
+        Self::note_referral_id_holder(&referral_id, who.clone());
         Validators::<T>::insert(
             who,
             ValidatorProfile {
@@ -1157,18 +2184,51 @@ impl<T: Config> Pallet<T> {
         );
     }
 
+    fn do_bond(
+        nominator: &T::AccountId,
+        nominee: &T::AccountId,
+        value: BalanceOf<T>,
+        memo: Memo,
+        allow_inactive: bool,
+    ) -> DispatchResult {
+        ensure!(!value.is_zero(), Error::<T>::ZeroBalance);
+        ensure!(Self::is_validator(nominee), Error::<T>::NotValidator);
+        // the validators can always bond to themselves before they activate
+        ensure!(
+            allow_inactive || nominator == nominee || Self::is_active(nominee),
+            Error::<T>::InactiveValidator
+        );
+        ensure!(
+            value <= Self::spendable_balance(nominator),
+            Error::<T>::InsufficientBalance
+        );
+        if !Self::is_validator_bonding_itself(nominator, nominee) {
+            Self::check_validator_acceptable_votes_limit(nominee, value)?;
+        }
+
+        Self::apply_bond(nominator, nominee, value, memo)?;
+
+        if Self::is_active(nominee) && !T::SessionInterface::validators().contains(nominee) {
+            Self::deposit_event(Event::<T>::NonElectedValidatorBonded(
+                nominator.clone(),
+                nominee.clone(),
+            ));
+        }
+        Ok(())
+    }
+
     fn apply_bond(
         nominator: &T::AccountId,
         nominee: &T::AccountId,
         value: BalanceOf<T>,
+        memo: Memo,
     ) -> DispatchResult {
         Self::bond_reserve(nominator, value);
         Self::update_vote_weight(nominator, nominee, Delta::Add(value));
-        Self::deposit_event(Event::<T>::Bonded(
-            nominator.clone(),
-            nominee.clone(),
-            value,
-        ));
+        Self::deposit_validator_event(
+            nominee,
+            Event::<T>::Bonded(nominator.clone(), nominee.clone(), value, memo),
+        );
         Ok(())
     }
 
@@ -1214,18 +2274,57 @@ impl<T: Config> Pallet<T> {
         });
     }
 
-    fn bonding_duration_for(who: &T::AccountId, target: &T::AccountId) -> T::BlockNumber {
+    fn bonding_duration_kind_for(who: &T::AccountId, target: &T::AccountId) -> BondingDurationKind {
         if Self::is_validator(who) && *who == *target {
-            Self::validator_bonding_duration()
+            BondingDurationKind::Validator
         } else {
-            Self::bonding_duration()
+            BondingDurationKind::Nominator
+        }
+    }
+
+    fn bonding_duration_of(kind: BondingDurationKind) -> T::BlockNumber {
+        match kind {
+            BondingDurationKind::Nominator => Self::bonding_duration(),
+            BondingDurationKind::Validator => Self::validator_bonding_duration(),
+        }
+    }
+
+    fn bonding_duration_for(who: &T::AccountId, target: &T::AccountId) -> T::BlockNumber {
+        Self::bonding_duration_of(Self::bonding_duration_kind_for(who, target))
+    }
+
+    /// Sets the bonding duration of `kind` and records the change.
+    ///
+    /// The unbonded chunks store the block they are unlocked at, which is computed with
+    /// the bonding duration in force when unbonding, hence the in-flight unbondings are
+    /// never affected by the change.
+    fn apply_set_bonding_duration(kind: BondingDurationKind, new: T::BlockNumber) {
+        let old = Self::bonding_duration_of(kind);
+        match kind {
+            BondingDurationKind::Nominator => BondingDuration::<T>::put(new),
+            BondingDurationKind::Validator => ValidatorBondingDuration::<T>::put(new),
         }
+
+        let at_block = <frame_system::Pallet<T>>::block_number();
+        BondingDurationHistory::<T>::mutate(|history| {
+            if history.len() >= MAXIMUM_BONDING_DURATION_HISTORY {
+                history.remove(0);
+            }
+            history.push(BondingDurationChange {
+                kind,
+                old,
+                new,
+                at_block,
+            });
+        });
+        Self::deposit_event(Event::<T>::BondingDurationChanged(kind, old, new, at_block));
     }
 
     fn apply_unbond(
         who: &T::AccountId,
         target: &T::AccountId,
         value: BalanceOf<T>,
+        memo: Memo,
     ) -> Result<(), Error<T>> {
         debug!(
             target: "runtime::mining::staking",
@@ -1234,21 +2333,64 @@ impl<T: Config> Pallet<T> {
         );
         Self::unbond_reserve(who, value)?;
 
+        // The unlock block is fixed now, the later changes of the bonding duration
+        // do not apply to this unbonding.
         let locked_until =
             <frame_system::Pallet<T>>::block_number() + Self::bonding_duration_for(who, target);
         Self::mutate_unbonded_chunks(who, target, value, locked_until);
 
         Self::update_vote_weight(who, target, Delta::Sub(value));
 
-        Self::deposit_event(Event::<T>::Unbonded(who.clone(), target.clone(), value));
+        Self::deposit_event(Event::<T>::Unbonded(
+            who.clone(),
+            target.clone(),
+            value,
+            memo,
+        ));
 
         Ok(())
     }
 
+    /// Unlocks the unbonded entry of `who` for `target` if it has been due for
+    /// `grace_period` blocks, returns the unlocked balance.
+    fn do_unlock_unbonded_withdrawal(
+        who: &T::AccountId,
+        target: &T::AccountId,
+        unbonded_index: UnbondedIndex,
+        grace_period: T::BlockNumber,
+    ) -> Result<BalanceOf<T>, Error<T>> {
+        // TODO: use try_mutate
+        let mut unbonded_chunks = Self::unbonded_chunks_of(who, target);
+        ensure!(!unbonded_chunks.is_empty(), Error::<T>::EmptyUnbondedChunks);
+        ensure!(
+            unbonded_index < unbonded_chunks.len() as u32,
+            Error::<T>::InvalidUnbondedIndex
+        );
+
+        let Unbonded {
+            value,
+            locked_until,
+        } = unbonded_chunks[unbonded_index as usize];
+        let current_block = <frame_system::Pallet<T>>::block_number();
+        ensure!(
+            current_block > locked_until.saturating_add(grace_period),
+            Error::<T>::UnbondedWithdrawalNotYetDue
+        );
+
+        Self::apply_unlock_unbonded_withdrawal(who, value);
+
+        unbonded_chunks.swap_remove(unbonded_index as usize);
+        Nominations::<T>::mutate(who, target, |nominator| {
+            nominator.unbonded_chunks = unbonded_chunks;
+        });
+
+        Ok(value)
+    }
+
     fn apply_unlock_unbonded_withdrawal(who: &T::AccountId, value: BalanceOf<T>) {
         let new_bonded = Self::total_locked_of(who) - value;
         Self::set_lock(who, new_bonded);
-        Locks::<T>::mutate(who, |locks| {
+        Self::mutate_locks(who, |locks| {
             let old_value = *locks.entry(LockedType::BondedWithdrawal).or_default();
             // All the bonded funds have been withdrawn.
             if old_value == value {
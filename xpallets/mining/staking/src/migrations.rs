@@ -0,0 +1,84 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Storage migrations of the staking pallet, run by `on_runtime_upgrade`.
+
+use frame_support::{
+    log::info,
+    traits::{GetStorageVersion, StorageVersion},
+    weights::Weight,
+};
+
+use super::*;
+
+/// The storage version of the pallet, bumped by each migration below.
+pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
+/// Runs the migrations the on-chain storage version has not reached yet.
+pub fn migrate<T: Config>() -> Weight {
+    let on_chain = Pallet::<T>::on_chain_storage_version();
+    let mut weight = T::DbWeight::get().reads(1);
+    if on_chain >= STORAGE_VERSION {
+        return weight;
+    }
+
+    if on_chain < 1 {
+        weight = weight.saturating_add(index_referral_ids::<T>());
+    }
+    if on_chain < 2 {
+        weight = weight.saturating_add(hold_bonded_balances::<T>());
+    }
+    if on_chain < 3 {
+        weight = weight.saturating_add(seed_jackpot_inflow::<T>());
+    }
+
+    STORAGE_VERSION.put::<Pallet<T>>();
+    weight.saturating_add(T::DbWeight::get().writes(1))
+}
+
+/// v1: builds `ReferralIdHolders` from the existing `ValidatorFor`.
+fn index_referral_ids<T: Config>() -> Weight {
+    let mut count: Weight = 0;
+    for (referral_id, validator) in ValidatorFor::<T>::iter() {
+        Pallet::<T>::note_referral_id_holder(&referral_id, validator);
+        count += 1;
+    }
+    info!(
+        target: "runtime::mining::staking",
+        "[migrate] Indexed {} referral identities",
+        count
+    );
+    T::DbWeight::get().reads_writes(count.saturating_mul(2), count)
+}
+
+/// v2: holds the balances locked by Staking in `NativeHolds`.
+fn hold_bonded_balances<T: Config>() -> Weight {
+    let mut count: Weight = 0;
+    for (who, locks) in Locks::<T>::iter() {
+        let total = locks
+            .values()
+            .fold(Zero::zero(), |acc: BalanceOf<T>, x| acc + *x);
+        T::NativeHolds::hold_native(&who, total);
+        count += 1;
+    }
+    info!(
+        target: "runtime::mining::staking",
+        "[migrate] Held the bonded balances of {} accounts",
+        count
+    );
+    T::DbWeight::get().reads_writes(count.saturating_mul(2), count)
+}
+
+/// v3: seeds `CumulativeJackpotInflow` with the balances the reward pots held before the
+/// jackpot accounting was introduced, so that the accounting is balanced since the upgrade.
+fn seed_jackpot_inflow<T: Config>() -> Weight {
+    let count = Validators::<T>::iter_keys().count() as Weight;
+    let held = Pallet::<T>::total_reward_pot_balance();
+    CumulativeJackpotInflow::<T>::put(held.saturating_add(CumulativeJackpotOutflow::<T>::get()));
+    info!(
+        target: "runtime::mining::staking",
+        "[migrate] Seeded the jackpot inflow with {:?} held by {} reward pots",
+        held,
+        count
+    );
+    T::DbWeight::get().reads_writes(count.saturating_mul(2).saturating_add(1), 1)
+}
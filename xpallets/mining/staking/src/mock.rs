@@ -1,13 +1,16 @@
 // Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
 
-use std::{cell::RefCell, collections::HashSet};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+};
 
 use frame_support::{parameter_types, traits::GenesisBuild};
 use sp_core::H256;
 use sp_runtime::{
     testing::{Header, UintAuthorityId},
     traits::{BlakeTwo256, IdentityLookup},
-    Perbill,
+    DispatchError, Perbill,
 };
 use xp_mining_staking::SessionIndex;
 
@@ -61,7 +64,7 @@ impl frame_system::Config for Test {
     type Header = Header;
     type Event = Event;
     type BlockHashCount = BlockHashCount;
-    type DbWeight = ();
+    type DbWeight = frame_support::weights::constants::RocksDbWeight;
     type Version = ();
     type PalletInfo = PalletInfo;
     type AccountData = pallet_balances::AccountData<Balance>;
@@ -166,7 +169,7 @@ impl pallet_session::Config for Test {
     type SessionHandler = (OtherSessionHandler,);
     type Event = Event;
     type ValidatorId = AccountId;
-    type ValidatorIdOf = ();
+    type ValidatorIdOf = sp_runtime::traits::ConvertInto;
     type NextSessionRotation = pallet_session::PeriodicSessions<Period, Offset>;
     type WeightInfo = ();
 }
@@ -179,6 +182,62 @@ impl TreasuryAccount<AccountId> for DummyTreasuryAccount {
     }
 }
 
+pub struct MockNativeHolds;
+
+impl MockNativeHolds {
+    /// Sets the native balance of `who` held by the modules other than Staking.
+    pub fn set(who: AccountId, held: Balance) {
+        NATIVE_HELD.with(|v| v.borrow_mut().insert(who, held));
+    }
+
+    /// Returns the native balance of `who` held by Staking.
+    pub fn staking_held(who: &AccountId) -> Balance {
+        STAKING_HELD.with(|v| v.borrow().get(who).copied().unwrap_or_default())
+    }
+}
+
+impl NativeHolds<AccountId, Balance> for MockNativeHolds {
+    fn native_held(who: &AccountId) -> Balance {
+        NATIVE_HELD.with(|v| v.borrow().get(who).copied().unwrap_or_default())
+            + Self::staking_held(who)
+    }
+
+    fn hold_native(who: &AccountId, value: Balance) {
+        STAKING_HELD.with(|v| *v.borrow_mut().entry(*who).or_default() += value);
+    }
+
+    fn release_native(who: &AccountId, value: Balance) {
+        STAKING_HELD.with(|v| {
+            let mut held = v.borrow_mut();
+            let current = held.entry(*who).or_default();
+            *current = current.saturating_sub(value);
+        });
+    }
+}
+
+/// Only the jackpot paths are allowed to move the balances out of the registered reward pots.
+pub struct MockDerivedAccounts;
+
+impl DerivedAccountRegistry<AccountId> for MockDerivedAccounts {
+    fn note_derived_account(who: &AccountId, kind: DerivedAccountKind<AccountId>) {
+        DERIVED_ACCOUNTS.with(|v| v.borrow_mut().insert(*who, kind));
+    }
+
+    fn ensure_can_move_out(who: &AccountId, caller: MoveCaller) -> DispatchResult {
+        let is_jackpot = DERIVED_ACCOUNTS.with(|v| {
+            matches!(
+                v.borrow().get(who),
+                Some(DerivedAccountKind::IntentionJackpot(_))
+            )
+        });
+        if is_jackpot && caller != MoveCaller::Jackpot {
+            Err(DispatchError::Other("MoveForbidden"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
 pub struct DummyStakingRewardPotAccountDeterminer;
 
 impl xp_mining_common::RewardPotAccountFor<AccountId, AccountId>
@@ -206,11 +265,14 @@ impl Config for Test {
     type Currency = Balances;
     type Event = Event;
     type AssetMining = ();
+    type NativeHolds = MockNativeHolds;
+    type DerivedAccounts = MockDerivedAccounts;
     type SessionDuration = SessionDuration;
     type MinimumReferralId = MinimumReferralId;
     type MaximumReferralId = MaximumReferralId;
     type SessionInterface = Self;
     type TreasuryAccount = DummyTreasuryAccount;
+    type CouncilOrigin = frame_system::EnsureRoot<AccountId>;
     type DetermineRewardPotAccount = DummyStakingRewardPotAccountDeterminer;
     type ValidatorRegistration = Registration;
     type WeightInfo = ();
@@ -223,6 +285,9 @@ thread_local! {
     static ELECTION_LOOKAHEAD: RefCell<BlockNumber> = RefCell::new(0);
     static PERIOD: RefCell<BlockNumber> = RefCell::new(1);
     static MAX_ITERATIONS: RefCell<u32> = RefCell::new(0);
+    static NATIVE_HELD: RefCell<HashMap<AccountId, Balance>> = RefCell::new(Default::default());
+    static STAKING_HELD: RefCell<HashMap<AccountId, Balance>> = RefCell::new(Default::default());
+    static DERIVED_ACCOUNTS: RefCell<HashMap<AccountId, DerivedAccountKind<AccountId>>> = RefCell::new(Default::default());
 }
 
 pub struct ExtBuilder {
@@ -242,6 +307,10 @@ impl Default for ExtBuilder {
 }
 
 impl ExtBuilder {
+    pub fn session_length(mut self, length: BlockNumber) -> Self {
+        self.session_length = length;
+        self
+    }
     pub fn set_associated_constants(&self) {
         SESSION_PER_ERA.with(|v| *v.borrow_mut() = self.session_per_era);
         ELECTION_LOOKAHEAD.with(|v| *v.borrow_mut() = self.election_lookahead);
@@ -320,11 +389,6 @@ impl ExtBuilder {
 }
 
 pub fn t_register(who: AccountId, initial_bond: Balance) -> DispatchResult {
-    let mut referral_id = who.to_string().as_bytes().to_vec();
-
-    if referral_id.len() < 2 {
-        referral_id.extend_from_slice(&[0, 0, 0, who as u8]);
-    }
-
+    let referral_id = format!("{:02}", who).into_bytes();
     XStaking::register(Origin::signed(who), referral_id, initial_bond)
 }
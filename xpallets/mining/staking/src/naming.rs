@@ -0,0 +1,97 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Naming rules of the validator referral identities.
+//!
+//! The new referral identities are restricted to the ASCII alphanumerics and
+//! `-_.`, must not start with any of `ReservedNamePrefixes` and must differ
+//! from the existing ones case-insensitively. The existing identities are kept
+//! as is, `audit_existing_names` reports those violating the rules.
+
+use super::*;
+
+impl<T: Config> Pallet<T> {
+    /// Returns the first naming rule `referral_id` violates.
+    ///
+    /// The referral identity held by `holder` itself is skipped in the collision check.
+    pub(crate) fn referral_id_violation(
+        referral_id: &[u8],
+        holder: Option<&T::AccountId>,
+    ) -> Option<NameViolation> {
+        if !referral_id.iter().all(|c| is_referral_id_char(*c)) {
+            return Some(NameViolation::InvalidCharset);
+        }
+
+        let lowercase = referral_id.to_ascii_lowercase();
+        if Self::reserved_name_prefixes()
+            .iter()
+            .any(|prefix| lowercase.starts_with(prefix))
+        {
+            return Some(NameViolation::ReservedPrefix);
+        }
+
+        let collided = ReferralIdHolders::<T>::get(&lowercase)
+            .iter()
+            .any(|validator| Some(validator) != holder);
+        if collided {
+            return Some(NameViolation::CaseInsensitiveCollision);
+        }
+
+        None
+    }
+
+    /// Returns Ok if `referral_id` can be claimed by a new validator.
+    pub(crate) fn check_new_referral_id(referral_id: &[u8]) -> Result<(), Error<T>> {
+        Self::check_referral_id(referral_id)?;
+        match Self::referral_id_violation(referral_id, None) {
+            Some(NameViolation::InvalidCharset) => Err(Error::<T>::InvalidReferralIdentityCharset),
+            Some(NameViolation::ReservedPrefix) => Err(Error::<T>::ReservedReferralIdentity),
+            Some(NameViolation::CaseInsensitiveCollision) => {
+                Err(Error::<T>::OccupiedReferralIdentity)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Indexes `validator` as a holder of `referral_id` in `ReferralIdHolders`.
+    pub(crate) fn note_referral_id_holder(referral_id: &[u8], validator: T::AccountId) {
+        ReferralIdHolders::<T>::mutate(referral_id.to_ascii_lowercase(), |holders| {
+            if !holders.contains(&validator) {
+                holders.push(validator);
+            }
+        });
+    }
+
+    /// Audits at most `limit` existing referral identities, starting from `NameAuditCursor`.
+    ///
+    /// Returns the number of the audited referral identities.
+    pub(crate) fn audit_referral_ids(limit: u32) -> u32 {
+        let mut iter = match NameAuditCursor::<T>::get() {
+            Some(cursor) => ValidatorFor::<T>::iter_from(cursor),
+            None => ValidatorFor::<T>::iter(),
+        };
+
+        let mut audited = 0;
+        while audited < limit {
+            let (referral_id, validator) = match iter.next() {
+                Some(entry) => entry,
+                None => {
+                    NameAuditCursor::<T>::kill();
+                    Self::deposit_event(Event::<T>::NameAuditCompleted);
+                    return audited;
+                }
+            };
+            audited += 1;
+
+            if let Some(violation) = Self::referral_id_violation(&referral_id, Some(&validator)) {
+                Self::deposit_event(Event::<T>::ReferralIdentityViolated(
+                    validator,
+                    referral_id.clone(),
+                    violation,
+                ));
+            }
+            NameAuditCursor::<T>::put(ValidatorFor::<T>::hashed_key_for(&referral_id));
+        }
+
+        audited
+    }
+}
@@ -0,0 +1,116 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Block production tracking of the validators.
+//!
+//! The authored blocks are counted per session, the staking reward of the
+//! session is then weighted by them if `PerformanceLeniency` is set. The
+//! counters are accumulated into the era records when the session reward
+//! is minted, which are emitted as events and reset once the era ends.
+//!
+//! The counters of the latest `AUTHORED_BLOCKS_HISTORY_DEPTH` sessions are also
+//! kept on chain, for checking the uptime of the validators over several sessions.
+
+use super::*;
+
+impl<T: Config> pallet_authorship::EventHandler<T::AccountId, T::BlockNumber> for Pallet<T> {
+    fn note_author(author: T::AccountId) {
+        SessionBlocks::<T>::mutate(|blocks| *blocks = blocks.saturating_add(1));
+        if T::SessionInterface::validators().contains(&author) {
+            SessionAuthoredBlocks::<T>::mutate(&author, |blocks| {
+                *blocks = blocks.saturating_add(1)
+            });
+        }
+    }
+
+    fn note_uncle(_author: T::AccountId, _age: T::BlockNumber) {}
+}
+
+impl<T: Config> Pallet<T> {
+    /// Returns the even share of the blocks authored in current session.
+    pub(crate) fn expected_session_blocks(validator_count: usize) -> u32 {
+        if validator_count == 0 {
+            return 0;
+        }
+        SessionBlocks::<T>::get() / validator_count.saturated_into::<u32>()
+    }
+
+    /// Returns the part of `reward` the validator deserves given its performance
+    /// in current session.
+    ///
+    /// The reward is unchanged if the performance weighting is disabled.
+    pub(crate) fn performance_weighted_reward(
+        validator: &T::AccountId,
+        reward: BalanceOf<T>,
+        expected_blocks: u32,
+    ) -> BalanceOf<T> {
+        let leniency = match PerformanceLeniency::<T>::get() {
+            Some(leniency) if expected_blocks > 0 => leniency,
+            _ => return reward,
+        };
+
+        let authored =
+            u128::from(SessionAuthoredBlocks::<T>::get(validator)) * u128::from(leniency);
+        let expected = u128::from(expected_blocks) * 100;
+
+        if authored >= expected {
+            reward
+        } else {
+            let weighted = reward.saturated_into::<u128>() * authored / expected;
+            weighted.saturated_into()
+        }
+    }
+
+    /// Moves the block production of the ended session into the era records.
+    pub(crate) fn note_session_performance(ended_session: SessionIndex) {
+        let validators = T::SessionInterface::validators();
+        let expected_blocks = Self::expected_session_blocks(validators.len());
+
+        if let Some(expired) = ended_session.checked_sub(AUTHORED_BLOCKS_HISTORY_DEPTH) {
+            let _ = AuthoredBlocksHistory::<T>::remove_prefix(expired, None);
+        }
+
+        for validator in validators {
+            let authored_blocks = SessionAuthoredBlocks::<T>::take(&validator);
+            if !authored_blocks.is_zero() {
+                AuthoredBlocksHistory::<T>::insert(ended_session, &validator, authored_blocks);
+            }
+            EraPerformance::<T>::mutate(&validator, |performance| {
+                performance.authored_blocks =
+                    performance.authored_blocks.saturating_add(authored_blocks);
+                performance.expected_blocks =
+                    performance.expected_blocks.saturating_add(expected_blocks);
+                if authored_blocks.is_zero() && !expected_blocks.is_zero() {
+                    performance.missed_sessions = performance.missed_sessions.saturating_add(1);
+                }
+            });
+        }
+
+        // Clean up the blocks authored by the validators that have left.
+        let _ = SessionAuthoredBlocks::<T>::remove_all(None);
+        SessionBlocks::<T>::kill();
+    }
+
+    /// Returns the number of blocks `who` authored in the latest `sessions` ended sessions.
+    ///
+    /// At most `AUTHORED_BLOCKS_HISTORY_DEPTH` sessions are taken into account.
+    pub fn recent_authored_blocks(who: &T::AccountId, sessions: u32) -> u32 {
+        let current = T::SessionInterface::current_index();
+        let sessions = sessions.min(AUTHORED_BLOCKS_HISTORY_DEPTH);
+        (current.saturating_sub(sessions)..current).fold(0u32, |authored, session| {
+            authored.saturating_add(AuthoredBlocksHistory::<T>::get(session, who))
+        })
+    }
+
+    /// Emits the block production of all the validators in the ended era.
+    pub(crate) fn record_era_performance(era: EraIndex) {
+        for (validator, performance) in EraPerformance::<T>::drain() {
+            Self::deposit_event(Event::<T>::EraPerformanceRecorded(
+                era,
+                validator,
+                performance.authored_blocks,
+                performance.expected_blocks,
+                performance.missed_sessions,
+            ));
+        }
+    }
+}
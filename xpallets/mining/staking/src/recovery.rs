@@ -0,0 +1,76 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Recovery of the lost validator accounts by the guardians.
+//!
+//! A validator sets M-of-N guardians by `set_guardians`. Any guardian can initiate
+//! the recovery of the validator to a new account, which can be finalized once
+//! enough guardians approve it and the delay elapses, unless the validator cancels
+//! it in the meantime. The new account then operates the validator, i.e., `validate`,
+//! `chill`, `set_metadata`, `set_guardians` and `cancel_recovery` sent by it are
+//! applied to the validator. The balances and the nominations are not moved.
+//!
+//! The session keys stay registered under the validator account, the operator
+//! re-points them to its own node by `set_operated_session_keys`.
+
+use super::*;
+
+impl<T: Config> Pallet<T> {
+    /// Returns the validator operated by `who`, which is `who` itself unless it has
+    /// taken over a recovered validator.
+    pub fn operated_validator(who: T::AccountId) -> T::AccountId {
+        Self::operated_validator_of(&who).unwrap_or(who)
+    }
+
+    /// Returns the sorted `guardians` if they are valid for `validator`.
+    pub(crate) fn check_guardians(
+        validator: &T::AccountId,
+        mut guardians: Vec<T::AccountId>,
+        threshold: u32,
+    ) -> Result<Vec<T::AccountId>, Error<T>> {
+        let count = guardians.len();
+        guardians.sort();
+        guardians.dedup();
+        let valid = guardians.len() == count
+            && count <= MAXIMUM_GUARDIANS as usize
+            && !guardians.contains(validator)
+            && if count == 0 {
+                threshold == 0
+            } else {
+                threshold > 0 && threshold as usize <= count
+            };
+        ensure!(valid, Error::<T>::InvalidGuardians);
+        Ok(guardians)
+    }
+
+    pub(crate) fn ensure_guardian(lost: &T::AccountId, who: &T::AccountId) -> Result<(), Error<T>> {
+        let is_guardian = Self::guardians_of(lost)
+            .map(|config| config.guardians.binary_search(who).is_ok())
+            .unwrap_or(false);
+        ensure!(is_guardian, Error::<T>::NotGuardian);
+        Ok(())
+    }
+
+    /// Ensures `new_account` can take over the `lost` validator.
+    pub(crate) fn check_recovery_account(
+        lost: &T::AccountId,
+        new_account: &T::AccountId,
+    ) -> Result<(), Error<T>> {
+        ensure!(
+            new_account != lost
+                && !Self::is_validator(new_account)
+                && !OperatedValidatorOf::<T>::contains_key(new_account),
+            Error::<T>::InvalidRecoveryAccount
+        );
+        Ok(())
+    }
+
+    /// Makes `new_account` the operator of the `lost` validator, replacing the
+    /// previous operator if any.
+    pub(crate) fn apply_recovery(lost: &T::AccountId, new_account: &T::AccountId) {
+        if let Some(previous) = ValidatorOperatorOf::<T>::take(lost) {
+            OperatedValidatorOf::<T>::remove(previous);
+        }
+        ValidatorOperatorOf::<T>::insert(lost, new_account);
+        OperatedValidatorOf::<T>::insert(new_account, lost);
+    }
+}
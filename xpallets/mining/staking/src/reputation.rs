@@ -0,0 +1,78 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Reputation of the validators.
+//!
+//! The reputation (in basis points) starts at 100%, each slash of a validator
+//! cuts it by a step and each era without any slash recovers it a bit. The
+//! staking reward of a validator is scaled by its reputation when it's accrued,
+//! the withheld part goes to the treasury. The reward pot is then split among
+//! the nominators by their vote weights as usual.
+
+use sp_std::collections::btree_set::BTreeSet;
+
+use super::*;
+
+impl<T: Config> Pallet<T> {
+    /// Returns the part of `reward` the validator deserves given its reputation.
+    ///
+    /// The reward is unchanged if the reputation is disabled.
+    pub(crate) fn reputation_weighted_reward(
+        validator: &T::AccountId,
+        reward: BalanceOf<T>,
+    ) -> BalanceOf<T> {
+        if ReputationConfig::<T>::get().is_none() {
+            return reward;
+        }
+
+        let reputation = u128::from(ReputationOf::<T>::get(validator));
+        let weighted =
+            reward.saturated_into::<u128>() * reputation / u128::from(MAX_REPUTATION_BPS);
+        weighted.saturated_into()
+    }
+
+    /// Cuts the reputation of the slashed validator.
+    pub(crate) fn decay_reputation(offender: &T::AccountId) {
+        if let Some(params) = ReputationConfig::<T>::get() {
+            SlashedInActiveEra::<T>::insert(offender, ());
+            let old = ReputationOf::<T>::get(offender);
+            let new = old
+                .saturating_sub(params.slash_step_bps)
+                .max(params.min_bps);
+            Self::update_reputation(offender, old, new);
+        }
+    }
+
+    /// Recovers the reputation of the validators not slashed in the ended era.
+    pub(crate) fn recover_reputation() {
+        let slashed = SlashedInActiveEra::<T>::drain()
+            .map(|(who, _)| who)
+            .collect::<BTreeSet<_>>();
+        let params = match ReputationConfig::<T>::get() {
+            Some(params) => params,
+            None => return,
+        };
+
+        // Only the reputations below 100% are stored.
+        let recovering = ReputationOf::<T>::iter()
+            .filter(|(who, _)| !slashed.contains(who))
+            .collect::<Vec<_>>();
+        for (who, old) in recovering {
+            let new = old
+                .saturating_add(params.recovery_bps)
+                .clamp(params.min_bps, MAX_REPUTATION_BPS);
+            Self::update_reputation(&who, old, new);
+        }
+    }
+
+    fn update_reputation(who: &T::AccountId, old: u32, new: u32) {
+        if old == new {
+            return;
+        }
+        if new >= MAX_REPUTATION_BPS {
+            ReputationOf::<T>::remove(who);
+        } else {
+            ReputationOf::<T>::insert(who, new);
+        }
+        Self::deposit_event(Event::<T>::ReputationUpdated(who.clone(), old, new));
+    }
+}
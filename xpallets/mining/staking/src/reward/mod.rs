@@ -14,10 +14,15 @@ impl<T: Config> Pallet<T> {
         (1_u32 << n).saturated_into()
     }
 
+    /// Returns the total issuance of PCX, including the session rewards not yet minted.
+    pub fn total_issuance() -> BalanceOf<T> {
+        T::Currency::total_issuance().saturating_add(Self::unsettled_reward_total())
+    }
+
     /// (1/2)^(n+1) < (2100 - x) / 2100 <= (1/2)^n
     /// Returns the total reward for the session, assuming it ends with this block.
     pub(crate) fn this_session_reward() -> BalanceOf<T> {
-        let total_issuance = T::Currency::total_issuance().saturated_into::<u64>(); // x
+        let total_issuance = Self::total_issuance().saturated_into::<u64>(); // x
         let tt = (FIXED_TOTAL / (FIXED_TOTAL - total_issuance)) as f32;
         let halving_epoch = tt.log2().trunc() as u32; // n
 
@@ -31,6 +36,13 @@ impl<T: Config> Pallet<T> {
         Self::deposit_event(Event::<T>::Minted(receiver.clone(), value));
     }
 
+    /// Issue new fresh PCX to the treasury account.
+    #[inline]
+    pub(crate) fn mint_for_treasury(treasury_account: &T::AccountId, value: BalanceOf<T>) {
+        CumulativeTreasuryRewards::<T>::mutate(|rewards| *rewards = rewards.saturating_add(value));
+        Self::mint(treasury_account, value);
+    }
+
     /// Issue new fresh PCX.
     #[inline]
     pub(crate) fn mint_for_validator(
@@ -39,6 +51,7 @@ impl<T: Config> Pallet<T> {
         validator_pot: &T::AccountId,
         reward_pot: BalanceOf<T>,
     ) {
+        CumulativeJackpotInflow::<T>::mutate(|inflow| *inflow = inflow.saturating_add(reward_pot));
         T::Currency::deposit_creating(validator, reward);
         T::Currency::deposit_creating(validator_pot, reward_pot);
 
@@ -52,23 +65,31 @@ impl<T: Config> Pallet<T> {
 
     /// Reward a (potential) validator by a specific amount.
     ///
-    /// Add the reward to their balance, and their reward pot, pro-rata.
-    fn apply_reward_validator(who: &T::AccountId, reward: BalanceOf<T>) {
+    /// Accrue the reward to their balance, and their reward pot, pro-rata.
+    /// The reward is minted by [`Self::settle_reward_of`] later.
+    fn apply_reward_validator(
+        who: &T::AccountId,
+        reward: BalanceOf<T>,
+        session_index: SessionIndex,
+    ) {
         // Validator themselves can only directly gain 20%, the rest 80% is for the reward pot.
         let off_the_table = reward.saturated_into::<BalanceOf<T>>() / 5u32.saturated_into();
 
-        // Issue the rest 80% to validator's reward pot.
-        let to_reward_pot = (reward - off_the_table).saturated_into();
-        let reward_pot = T::DetermineRewardPotAccount::reward_pot_account_for(who);
+        // Accrue the rest 80% to validator's reward pot.
+        let to_reward_pot: BalanceOf<T> = (reward - off_the_table).saturated_into();
 
-        Self::mint_for_validator(who, off_the_table, &reward_pot, to_reward_pot);
+        UnsettledRewards::<T>::mutate(who, |unsettled| {
+            let unsettled = unsettled.get_or_insert_with(Default::default);
+            unsettled.validator = unsettled.validator.saturating_add(off_the_table);
+            unsettled.reward_pot = unsettled.reward_pot.saturating_add(to_reward_pot);
+            unsettled.last_session = session_index;
+        });
 
         frame_support::log::debug!(
             target: "runtime::mining::staking",
-            "� Mint validator({:?}):{:?}, reward_pot({:?}):{:?}",
+            "� Accrue validator({:?}):{:?}, reward_pot:{:?}",
             who,
             off_the_table,
-            reward_pot,
             to_reward_pot
         );
     }
@@ -78,14 +99,45 @@ impl<T: Config> Pallet<T> {
     /// If the slashed validator can't afford that penalty, it will be
     /// removed from the validator list.
     #[inline]
-    fn reward_active_validator(validator: &T::AccountId, reward: BalanceOf<T>) {
-        Self::apply_reward_validator(validator, reward);
+    fn reward_active_validator(
+        validator: &T::AccountId,
+        reward: BalanceOf<T>,
+        session_index: SessionIndex,
+    ) {
+        Self::apply_reward_validator(validator, reward, session_index);
+    }
+
+    /// Mints the session rewards accrued to `validator` and its reward pot so far.
+    pub(crate) fn settle_reward_of(validator: &T::AccountId) {
+        if let Some(unsettled) = UnsettledRewards::<T>::take(validator) {
+            UnsettledRewardTotal::<T>::mutate(|total| {
+                *total = total
+                    .saturating_sub(unsettled.validator)
+                    .saturating_sub(unsettled.reward_pot)
+            });
+            let reward_pot = T::DetermineRewardPotAccount::reward_pot_account_for(validator);
+            Self::mint_for_validator(
+                validator,
+                unsettled.validator,
+                &reward_pot,
+                unsettled.reward_pot,
+            );
+        }
+    }
+
+    /// Returns the balance of the reward pot of `validator`, including the rewards not yet minted.
+    pub fn reward_pot_balance_of(validator: &T::AccountId) -> BalanceOf<T> {
+        let reward_pot = T::DetermineRewardPotAccount::reward_pot_account_for(validator);
+        let unsettled = Self::unsettled_reward_of(validator).unwrap_or_default();
+        Self::free_balance(&reward_pot).saturating_add(unsettled.reward_pot)
     }
 
     /// Distribute the session reward to all the receivers, returns the total reward for validators.
-    pub(crate) fn distribute_session_reward() -> Vec<(T::AccountId, BalanceOf<T>)> {
+    pub(crate) fn distribute_session_reward(
+        session_index: SessionIndex,
+    ) -> Vec<(T::AccountId, BalanceOf<T>)> {
         let session_reward = Self::this_session_reward();
 
-        Self::distribute_session_reward_impl_09(session_reward)
+        Self::distribute_session_reward_impl_09(session_reward, session_index)
     }
 }
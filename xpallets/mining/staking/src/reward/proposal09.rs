@@ -73,39 +73,64 @@ impl<T: Config> Pallet<T> {
     }
 
     /// Reward to all the active validators pro rata.
+    ///
+    /// The part withheld due to the poor performance or reputation goes to the treasury.
     fn distribute_to_active_validators(
         session_reward: BalanceOf<T>,
+        treasury_account: &T::AccountId,
+        session_index: SessionIndex,
     ) -> Vec<(T::AccountId, BalanceOf<T>)> {
-        let current_validators: Vec<(T::AccountId, BalanceOf<T>)> =
-            T::SessionInterface::validators()
-                .into_iter()
-                .filter(|v| Self::is_active(v))
-                .map(|v| {
-                    let total_votes = Self::total_votes_of(&v);
-                    (v, total_votes)
-                })
-                .collect();
+        let session_validators = T::SessionInterface::validators();
+        let expected_blocks = Self::expected_session_blocks(session_validators.len());
+        let current_validators: Vec<(T::AccountId, BalanceOf<T>)> = session_validators
+            .into_iter()
+            .filter(|v| Self::is_active(v))
+            .map(|v| {
+                let total_votes = Self::total_votes_of(&v);
+                (v, total_votes)
+            })
+            .collect();
 
         let mut total_stake = current_validators
             .iter()
             .fold(Zero::zero(), |acc: BalanceOf<T>, (_, x)| acc + *x);
         let mut total_reward = session_reward;
-        current_validators
+        let mut withheld_reward: BalanceOf<T> = Zero::zero();
+        let mut accrued_reward: BalanceOf<T> = Zero::zero();
+        let validator_rewards = current_validators
             .into_iter()
             .filter_map(|(validator, stake)| {
                 // May become zero after meeting the last one.
                 if !total_stake.is_zero() {
                     let reward =
                         Self::calc_individual_staking_reward(total_reward, stake, total_stake);
-                    Self::reward_active_validator(&validator, reward);
+                    let weighted_reward =
+                        Self::performance_weighted_reward(&validator, reward, expected_blocks);
+                    let weighted_reward =
+                        Self::reputation_weighted_reward(&validator, weighted_reward);
+                    Self::reward_active_validator(&validator, weighted_reward, session_index);
                     total_stake -= stake;
                     total_reward -= reward;
-                    Some((validator, reward))
+                    withheld_reward += reward - weighted_reward;
+                    accrued_reward += weighted_reward;
+                    Some((validator, weighted_reward))
                 } else {
                     None
                 }
             })
-            .collect()
+            .collect();
+
+        if !accrued_reward.is_zero() {
+            UnsettledRewardTotal::<T>::mutate(|total| {
+                *total = total.saturating_add(accrued_reward)
+            });
+        }
+
+        if !withheld_reward.is_zero() {
+            Self::mint_for_treasury(treasury_account, withheld_reward);
+        }
+
+        validator_rewards
     }
 
     /// Issue new PCX to the action intentions and cross mining asset entities
@@ -113,17 +138,19 @@ impl<T: Config> Pallet<T> {
     fn distribute_mining_rewards(
         total: BalanceOf<T>,
         treasury_account: &T::AccountId,
+        session_index: SessionIndex,
     ) -> Vec<(T::AccountId, BalanceOf<T>)> {
         let mining_distribution = Self::mining_distribution_ratio();
         let staking_reward = mining_distribution.calc_staking_reward::<T>(total);
         let max_asset_mining_reward = total - staking_reward;
 
-        let validator_rewards = Self::distribute_to_active_validators(staking_reward);
+        let validator_rewards =
+            Self::distribute_to_active_validators(staking_reward, treasury_account, session_index);
 
         let real_asset_mining_reward = if let Some(treasury_extra) =
             mining_distribution.has_treasury_extra::<T>(max_asset_mining_reward)
         {
-            Self::mint(treasury_account, treasury_extra);
+            Self::mint_for_treasury(treasury_account, treasury_extra);
             max_asset_mining_reward - treasury_extra
         } else {
             max_asset_mining_reward
@@ -137,7 +164,7 @@ impl<T: Config> Pallet<T> {
                 "[distribute_mining_rewards] unpaid_asset_mining_reward:{:?}",
                 unpaid_asset_mining_reward
             );
-            Self::mint(treasury_account, unpaid_asset_mining_reward);
+            Self::mint_for_treasury(treasury_account, unpaid_asset_mining_reward);
         }
 
         validator_rewards
@@ -148,6 +175,7 @@ impl<T: Config> Pallet<T> {
     /// Returns the reward balance minted specifically for Staking.
     pub(super) fn distribute_session_reward_impl_09(
         session_reward: BalanceOf<T>,
+        session_index: SessionIndex,
     ) -> Vec<(T::AccountId, BalanceOf<T>)> {
         let global_distribution = Self::global_distribution_ratio();
         let (treasury_reward, mining_reward) =
@@ -157,14 +185,18 @@ impl<T: Config> Pallet<T> {
         let treasury_account =
             T::TreasuryAccount::treasury_account().expect("TreasuryAccount is some; qed");
         if !treasury_reward.is_zero() {
-            Self::mint(&treasury_account, treasury_reward);
+            Self::mint_for_treasury(&treasury_account, treasury_reward);
         }
 
         // -> Mining
         //      |-> XBTC(Asset Mining)
         //      |-> PCX(Staking)
         if !mining_reward.is_zero() {
-            return Self::distribute_mining_rewards(mining_reward, &treasury_account);
+            return Self::distribute_mining_rewards(
+                mining_reward,
+                &treasury_account,
+                session_index,
+            );
         }
 
         Default::default()
@@ -6,13 +6,21 @@ use codec::{Decode, Encode};
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 
-use sp_runtime::RuntimeDebug;
+use sp_runtime::{
+    traits::{CheckedSub, One, Saturating, Zero},
+    RuntimeDebug,
+};
+
+use xp_mining_common::{ComputeMiningWeight, RewardPotAccountFor};
+use xp_mining_staking::SessionIndex;
 
-use xp_mining_common::RewardPotAccountFor;
+use frame_support::traits::Currency;
+use xpallet_support::traits::TreasuryAccount;
 
 use crate::{
-    types::*, BalanceOf, Config, LastRebondOf, Nominations, Pallet, SessionInterface,
-    ValidatorLedgers, Validators,
+    constants::MAX_REPUTATION_BPS, types::*, BalanceOf, ClaimSwitch, Config, EraIndex,
+    EraPerformance, LastRebondOf, Locks, Nominations, Pallet, ReputationOf, SessionInterface,
+    UnsettledRewards, ValidatorLedgers, Validators,
 };
 
 /// Total information about a validator.
@@ -34,6 +42,49 @@ pub struct ValidatorInfo<AccountId, Balance, VoteWeight, BlockNumber> {
     pub reward_pot_account: AccountId,
     /// Balance of the reward pot account.
     pub reward_pot_balance: Balance,
+    /// Metadata published by the validator.
+    pub metadata: Vec<MetadataEntry>,
+    /// Whether the staking reward claims are enabled.
+    pub claim_enabled: bool,
+    /// Block production of the validator in the active era.
+    pub performance: ValidatorPerformance,
+    /// Reputation of the validator in basis points.
+    pub reputation: u32,
+}
+
+/// `ValidatorInfo` without the metadata, the claim switch, the performance and the
+/// reputation, returned by version 4 of `XStakingApi`.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+pub struct ValidatorInfoV4<AccountId, Balance, VoteWeight, BlockNumber> {
+    pub account: AccountId,
+    pub profile: ValidatorProfile<BlockNumber>,
+    pub ledger: ValidatorLedger<Balance, VoteWeight, BlockNumber>,
+    pub is_validating: bool,
+    pub self_bonded: Balance,
+    pub reward_pot_account: AccountId,
+    pub reward_pot_balance: Balance,
+}
+
+impl<AccountId, Balance, VoteWeight, BlockNumber>
+    From<ValidatorInfoV4<AccountId, Balance, VoteWeight, BlockNumber>>
+    for ValidatorInfo<AccountId, Balance, VoteWeight, BlockNumber>
+{
+    fn from(info: ValidatorInfoV4<AccountId, Balance, VoteWeight, BlockNumber>) -> Self {
+        Self {
+            account: info.account,
+            profile: info.profile,
+            ledger: info.ledger,
+            is_validating: info.is_validating,
+            self_bonded: info.self_bonded,
+            reward_pot_account: info.reward_pot_account,
+            reward_pot_balance: info.reward_pot_balance,
+            // Neither the metadata nor the claim switch nor the reputation existed before.
+            metadata: Vec::new(),
+            claim_enabled: true,
+            performance: ValidatorPerformance::default(),
+            reputation: MAX_REPUTATION_BPS,
+        }
+    }
 }
 
 /// Profile of staking nominator.
@@ -45,6 +96,76 @@ pub struct NominatorInfo<BlockNumber> {
     pub last_rebond: Option<BlockNumber>,
 }
 
+/// Progress of the current session and era.
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct EraProgress<BlockNumber> {
+    /// Index of the current session.
+    pub current_session: SessionIndex,
+    /// The length of a staking era in sessions.
+    pub sessions_per_era: SessionIndex,
+    /// Index of the latest planned era.
+    pub current_era: EraIndex,
+    /// Number of blocks elapsed since the current session started.
+    pub blocks_into_session: BlockNumber,
+    /// The average length of a session in blocks.
+    pub session_length: BlockNumber,
+    /// Estimated number of blocks until the next era starts.
+    ///
+    /// `None` if the new era is prevented by `Forcing::ForceNone`.
+    pub blocks_until_next_era: Option<BlockNumber>,
+    /// Whether a new era will be forced at the end of the current session.
+    pub forcing_new_era: bool,
+}
+
+/// Accounting of the session rewards received and the spends of the treasury account.
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct TreasuryAccounting<AccountId, Balance, BlockNumber> {
+    /// AccountId of the treasury.
+    pub account: Option<AccountId>,
+    /// Current free balance of the treasury account.
+    pub balance: Balance,
+    /// Cumulative session rewards minted to the treasury account.
+    pub cumulative_rewards: Balance,
+    /// Cumulative spends recorded by the council.
+    pub cumulative_spends: Balance,
+    /// The latest spends recorded by the council, from the oldest one.
+    pub spends: Vec<TreasurySpend<Balance, BlockNumber>>,
+}
+
+/// Accounting of the balances moved in and out of the reward pots of all the validators.
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct JackpotAccounting<Balance> {
+    /// Current total free balance of the reward pots.
+    pub total_balance: Balance,
+    /// Cumulative session rewards minted to the reward pots.
+    pub cumulative_inflow: Balance,
+    /// Cumulative balances moved out of the reward pots by the claims and slashes.
+    pub cumulative_outflow: Balance,
+    /// The session rewards accrued to the reward pots but not yet minted.
+    pub unsettled: Balance,
+    /// Whether `total_balance` equals `cumulative_inflow - cumulative_outflow`.
+    pub balanced: bool,
+}
+
+/// An unbonded chunk along with the bonding duration it was unbonded under.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct UnbondedInfo<Balance, BlockNumber> {
+    #[cfg_attr(feature = "std", serde(flatten))]
+    pub unbonded: Unbonded<Balance, BlockNumber>,
+    /// The bonding duration in force when the chunk was unbonded.
+    ///
+    /// `None` if none of the recorded bonding durations fits the chunk.
+    pub bonding_duration: Option<BlockNumber>,
+}
+
 impl<T: Config> Pallet<T> {
     pub fn validators_info(
     ) -> Vec<ValidatorInfo<T::AccountId, BalanceOf<T>, VoteWeight, T::BlockNumber>> {
@@ -60,7 +181,13 @@ impl<T: Config> Pallet<T> {
         let self_bonded: BalanceOf<T> = Nominations::<T>::get(&who, &who).nomination;
         let is_validating = T::SessionInterface::validators().contains(&who);
         let reward_pot_account = T::DetermineRewardPotAccount::reward_pot_account_for(&who);
-        let reward_pot_balance: BalanceOf<T> = Self::free_balance(&reward_pot_account);
+        let reward_pot_balance = Self::reward_pot_balance_of(&who);
+        let metadata = Self::validator_metadata_of(&who)
+            .into_iter()
+            .map(|(key, value)| MetadataEntry { key, value })
+            .collect();
+        let performance = EraPerformance::<T>::get(&who);
+        let reputation = ReputationOf::<T>::get(&who);
         ValidatorInfo {
             account: who,
             profile,
@@ -69,6 +196,10 @@ impl<T: Config> Pallet<T> {
             self_bonded,
             reward_pot_account,
             reward_pot_balance,
+            metadata,
+            claim_enabled: ClaimSwitch::<T>::get(ClaimTypeKind::Intention),
+            performance,
+            reputation,
         }
     }
 
@@ -92,8 +223,230 @@ impl<T: Config> Pallet<T> {
             .collect()
     }
 
+    /// Get the nominations of `who` along with the vote weights accrued up to the current
+    /// block, i.e., `(validator, ledger, nominator_vote_weight, validator_total_vote_weight)`.
+    ///
+    /// The vote weights are settled the same way as claiming at the current block.
+    pub fn nomination_records_detailed(
+        who: T::AccountId,
+    ) -> Vec<(
+        T::AccountId,
+        NominatorLedger<BalanceOf<T>, VoteWeight, T::BlockNumber>,
+        VoteWeight,
+        VoteWeight,
+    )> {
+        let current_block = <frame_system::Pallet<T>>::block_number();
+        Nominations::<T>::iter_prefix(&who)
+            .map(|(validator, ledger)| {
+                let vote_weight =
+                    <Self as ComputeMiningWeight<T::AccountId, T::BlockNumber>>::settle_claimer_weight(
+                        &who,
+                        &validator,
+                        current_block,
+                    );
+                let total_vote_weight =
+                    <Self as ComputeMiningWeight<T::AccountId, T::BlockNumber>>::settle_claimee_weight(
+                        &validator,
+                        current_block,
+                    );
+                (validator, ledger, vote_weight, total_vote_weight)
+            })
+            .collect()
+    }
+
+    /// Get the unbonded chunks of `who` given the validators, along with the bonding
+    /// durations they were unbonded under.
+    pub fn unbonded_chunks_of(
+        who: T::AccountId,
+    ) -> BTreeMap<T::AccountId, Vec<UnbondedInfo<BalanceOf<T>, T::BlockNumber>>> {
+        let history = Self::bonding_duration_history();
+        Nominations::<T>::iter_prefix(&who)
+            .filter(|(_, ledger)| !ledger.unbonded_chunks.is_empty())
+            .map(|(validator, ledger)| {
+                let kind = Self::bonding_duration_kind_for(&who, &validator);
+                let chunks = ledger
+                    .unbonded_chunks
+                    .into_iter()
+                    .map(|unbonded| UnbondedInfo {
+                        bonding_duration: Self::bonding_duration_regime_of(
+                            &history,
+                            kind,
+                            unbonded.locked_until,
+                        ),
+                        unbonded,
+                    })
+                    .collect();
+                (validator, chunks)
+            })
+            .collect()
+    }
+
+    /// Returns the bonding duration of `kind` which produced the unlock block `locked_until`.
+    ///
+    /// The bonding duration `d` in force since block `s` until the next change at block `e`
+    /// produced the unlock block if the chunk was unbonded within `[s, e)`, i.e.,
+    /// `s <= locked_until - d < e`, the current one is in force until now. The latest
+    /// fitting bonding duration wins.
+    fn bonding_duration_regime_of(
+        history: &[BondingDurationChange<T::BlockNumber>],
+        kind: BondingDurationKind,
+        locked_until: T::BlockNumber,
+    ) -> Option<T::BlockNumber> {
+        let changes = history
+            .iter()
+            .filter(|change| change.kind == kind)
+            .collect::<Vec<_>>();
+
+        // (since, until, bonding duration), the earliest one is in force since the genesis
+        // as far as the recorded history goes.
+        let mut regimes = Vec::with_capacity(changes.len() + 1);
+        let mut since = T::BlockNumber::zero();
+        let mut duration = changes
+            .first()
+            .map(|change| change.old)
+            .unwrap_or_else(|| Self::bonding_duration_of(kind));
+        for change in changes {
+            regimes.push((since, Some(change.at_block), duration));
+            since = change.at_block;
+            duration = change.new;
+        }
+        regimes.push((since, None, duration));
+
+        let now = <frame_system::Pallet<T>>::block_number();
+        regimes
+            .into_iter()
+            .rev()
+            .find(|&(since, until, duration)| {
+                locked_until
+                    .checked_sub(&duration)
+                    .map_or(false, |unbonded_at| {
+                        unbonded_at >= since
+                            && until.map_or(unbonded_at <= now, |until| unbonded_at < until)
+                    })
+            })
+            .map(|(_, _, duration)| duration)
+    }
+
     pub fn nominator_info_of(who: T::AccountId) -> NominatorInfo<T::BlockNumber> {
         let last_rebond = LastRebondOf::<T>::get(&who);
         NominatorInfo { last_rebond }
     }
+
+    pub fn era_progress() -> EraProgress<T::BlockNumber> {
+        let now = frame_system::Pallet::<T>::block_number();
+        let current_session = T::SessionInterface::current_index();
+        let sessions_per_era = Self::sessions_per_era();
+        let current_era = Self::current_era().unwrap_or_default();
+        let era_start_session = Self::eras_start_session_index(current_era).unwrap_or_default();
+        let force_era = Self::force_era();
+
+        let session_length = T::SessionInterface::average_session_length();
+        let blocks_until_next_session = T::SessionInterface::estimate_next_session_rotation(now)
+            .map(|next| next.saturating_sub(now))
+            .unwrap_or(session_length)
+            .min(session_length);
+        let blocks_into_session = session_length.saturating_sub(blocks_until_next_session);
+
+        // `new_session(current_session + 1)` has already been called when the current session
+        // started, so the earliest era can be planned by `new_session(current_session + 2)`,
+        // unless the next era has already been planned.
+        //
+        // The era planned by `new_session(current_session + 2)` must have been elected in
+        // advance, in a block before the next rotation, otherwise it's planned one session later.
+        let earliest_era_start_session = if !Self::defers_election()
+            || blocks_until_next_session > One::one()
+            || Self::is_election_planned(current_session + 2)
+        {
+            current_session + 2
+        } else {
+            current_session + 3
+        };
+        let next_era_start_session = if era_start_session > current_session {
+            Some(era_start_session)
+        } else {
+            match force_era {
+                Forcing::ForceNew | Forcing::ForceAlways => Some(earliest_era_start_session),
+                Forcing::ForceNone => None,
+                Forcing::NotForcing => Some(
+                    era_start_session
+                        .saturating_add(sessions_per_era)
+                        .max(earliest_era_start_session),
+                ),
+            }
+        };
+        let blocks_until_next_era = next_era_start_session.map(|start| {
+            let full_sessions =
+                T::BlockNumber::from(start.saturating_sub(current_session).saturating_sub(1));
+            blocks_until_next_session.saturating_add(full_sessions.saturating_mul(session_length))
+        });
+
+        EraProgress {
+            current_session,
+            sessions_per_era,
+            current_era,
+            blocks_into_session,
+            session_length,
+            blocks_until_next_era,
+            forcing_new_era: matches!(force_era, Forcing::ForceNew | Forcing::ForceAlways),
+        }
+    }
+
+    /// Get the accounting of the treasury account.
+    pub fn treasury_accounting() -> TreasuryAccounting<T::AccountId, BalanceOf<T>, T::BlockNumber> {
+        let account = T::TreasuryAccount::treasury_account();
+        let balance = account
+            .as_ref()
+            .map(T::Currency::free_balance)
+            .unwrap_or_default();
+        TreasuryAccounting {
+            account,
+            balance,
+            cumulative_rewards: Self::cumulative_treasury_rewards(),
+            cumulative_spends: Self::cumulative_treasury_spends(),
+            spends: Self::treasury_spend_history(),
+        }
+    }
+
+    /// Get the total balances locked in Staking, keyed by the locked type.
+    ///
+    /// NOTE: all the stakers are iterated, which is only affordable off-chain.
+    pub fn total_locked() -> BTreeMap<LockedType, BalanceOf<T>> {
+        let mut total = BTreeMap::new();
+        for locks in Locks::<T>::iter_values() {
+            for (ty, value) in locks {
+                let entry = total.entry(ty).or_insert_with(Zero::zero);
+                *entry = value.saturating_add(*entry);
+            }
+        }
+        total
+    }
+
+    /// Get the total balance of the reward pots of all the validators.
+    pub fn total_reward_pot_balance() -> BalanceOf<T> {
+        Self::validator_set()
+            .map(|validator| T::Currency::free_balance(&Self::reward_pot_for(&validator)))
+            .fold(Zero::zero(), |acc: BalanceOf<T>, x| acc.saturating_add(x))
+    }
+
+    /// Get the accounting of the reward pots, checking their total balance against
+    /// the cumulative inflow minus outflow.
+    ///
+    /// NOTE: on the chains started earlier, the inflow is seeded with the balances held by
+    /// the reward pots at the runtime upgrade introducing it.
+    pub fn jackpot_accounting() -> JackpotAccounting<BalanceOf<T>> {
+        let total_balance = Self::total_reward_pot_balance();
+        let cumulative_inflow = Self::cumulative_jackpot_inflow();
+        let cumulative_outflow = Self::cumulative_jackpot_outflow();
+        let unsettled = UnsettledRewards::<T>::iter_values()
+            .fold(Zero::zero(), |acc: BalanceOf<T>, x| {
+                acc.saturating_add(x.reward_pot)
+            });
+        JackpotAccounting {
+            total_balance,
+            cumulative_inflow,
+            cumulative_outflow,
+            unsettled,
+            balanced: cumulative_inflow.checked_sub(&cumulative_outflow) == Some(total_balance),
+        }
+    }
 }
@@ -48,6 +48,8 @@ impl<T: Config> Pallet<T> {
         offenders
             .into_iter()
             .flat_map(|(offender, slash_fraction)| {
+                Self::settle_reward_of(&offender);
+                Self::decay_reputation(&offender);
                 let base_slash = calc_base_slash(&offender, slash_fraction);
                 let penalty = validator_rewards
                     .get(&offender)
@@ -0,0 +1,268 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Nomination snapshots of the elected validators for the off-chain reward audits.
+//!
+//! At each era rotation the vote weight of every nomination to the elected validators
+//! is committed into a merkle root per validator, so that the auditors can verify the
+//! weights the era rewards are split by without replaying the events. The snapshots of
+//! the latest `ERA_SNAPSHOT_HISTORY_DEPTH` eras are kept on chain.
+//!
+//! The snapshot is taken lazily: the rotation only records the elected validators in
+//! `PendingEraSnapshot`, the nominations are then checked in `on_initialize` at most
+//! `MAX_SNAPSHOT_NOMINATIONS_PER_BLOCK` per block and the weights are settled at the
+//! rotation block. A nomination updated before being checked is recorded right before the
+//! update, so the root is committed once all the nominations have been checked.
+
+use codec::Encode;
+use frame_support::weights::{DispatchClass, Weight};
+use sp_runtime::traits::Hash;
+
+use super::*;
+
+impl<T: Config> Pallet<T> {
+    /// Starts the nomination snapshot of `validators` for the new era `era`.
+    ///
+    /// The unfinished snapshot of the previous era is completed first, the weight consumed
+    /// is registered to the current block since it happens in the session hook.
+    pub(crate) fn note_era_snapshot(era: EraIndex, validators: &[T::AccountId]) {
+        let mut weight = T::DbWeight::get().reads_writes(1, 2);
+        if let Some(expired) = era.checked_sub(ERA_SNAPSHOT_HISTORY_DEPTH) {
+            EraSnapshotRoot::<T>::remove(expired);
+            let _ = EraSnapshotLeaves::<T>::remove_prefix(expired, None);
+            weight = weight.saturating_add(
+                T::DbWeight::get().writes((validators.len() as Weight).saturating_add(1)),
+            );
+        }
+
+        if PendingEraSnapshot::<T>::exists() {
+            weight = weight.saturating_add(Self::progress_era_snapshot(u32::max_value()));
+        }
+
+        let mut validators = validators.to_vec();
+        validators.sort();
+        validators.dedup();
+        PendingEraSnapshot::<T>::put(EraSnapshotProgress {
+            era,
+            taken_at: <frame_system::Pallet<T>>::block_number(),
+            validators,
+            cursor: None,
+        });
+
+        <frame_system::Pallet<T>>::register_extra_weight_unchecked(
+            weight,
+            DispatchClass::Mandatory,
+        );
+    }
+
+    /// Checks at most `limit` nominations for the pending era snapshot, the snapshot is
+    /// committed once all the nominations have been checked.
+    pub(crate) fn progress_era_snapshot(limit: u32) -> Weight {
+        let mut progress = match PendingEraSnapshot::<T>::get() {
+            Some(progress) => progress,
+            None => return T::DbWeight::get().reads(1),
+        };
+
+        let mut iter = match progress.cursor.take() {
+            Some(cursor) => Nominations::<T>::iter_from(cursor),
+            None => Nominations::<T>::iter(),
+        };
+
+        let mut checked: u32 = 0;
+        let mut leaves_written: u32 = 0;
+        let completed = loop {
+            if checked >= limit {
+                break false;
+            }
+            let (nominator, validator, ledger) = match iter.next() {
+                Some(entry) => entry,
+                None => break true,
+            };
+            checked += 1;
+
+            if Self::note_snapshot_leaf(&progress, &nominator, &validator, &ledger) {
+                leaves_written += 1;
+            }
+            progress.cursor = Some(Nominations::<T>::hashed_key_for(&nominator, &validator));
+        };
+
+        let mut weight = T::DbWeight::get().reads_writes(
+            (checked as Weight)
+                .saturating_add(leaves_written as Weight)
+                .saturating_add(1),
+            (leaves_written as Weight).saturating_add(1),
+        );
+
+        if completed {
+            Self::commit_era_snapshot(&progress);
+            PendingEraSnapshot::<T>::kill();
+            let validators = progress.validators.len() as Weight;
+            weight = weight.saturating_add(T::DbWeight::get().reads_writes(validators, 1));
+        } else {
+            PendingEraSnapshot::<T>::put(progress);
+        }
+
+        weight
+    }
+
+    /// Records the nomination of `nominator` to `validator` into the pending era snapshot
+    /// before its vote weight is updated.
+    pub(crate) fn note_snapshot_before_update(nominator: &T::AccountId, validator: &T::AccountId) {
+        if let Some(progress) = PendingEraSnapshot::<T>::get() {
+            if let Ok(ledger) = Nominations::<T>::try_get(nominator, validator) {
+                Self::note_snapshot_leaf(&progress, nominator, validator, &ledger);
+            }
+        }
+    }
+
+    /// Inserts the nomination into the leaves of the pending era snapshot.
+    ///
+    /// The nominations to the validators not elected, updated since the rotation or recorded
+    /// already are skipped. Returns true if the leaf is inserted.
+    fn note_snapshot_leaf(
+        progress: &EraSnapshotProgress<T::AccountId, T::BlockNumber>,
+        nominator: &T::AccountId,
+        validator: &T::AccountId,
+        ledger: &NominatorLedger<BalanceOf<T>, VoteWeight, T::BlockNumber>,
+    ) -> bool {
+        if ledger.last_vote_weight_update > progress.taken_at
+            || progress.validators.binary_search(validator).is_err()
+        {
+            return false;
+        }
+
+        let weight =
+            <Self as ComputeMiningWeight<T::AccountId, T::BlockNumber>>::settle_claimer_weight(
+                nominator,
+                validator,
+                progress.taken_at,
+            );
+        EraSnapshotLeaves::<T>::mutate(progress.era, validator, |leaves| {
+            match leaves.binary_search_by(|(who, _)| who.cmp(nominator)) {
+                Ok(_) => false,
+                Err(index) => {
+                    leaves.insert(index, (nominator.clone(), weight));
+                    true
+                }
+            }
+        })
+    }
+
+    fn commit_era_snapshot(progress: &EraSnapshotProgress<T::AccountId, T::BlockNumber>) {
+        let roots = progress
+            .validators
+            .iter()
+            .map(|validator| {
+                let nominations = EraSnapshotLeaves::<T>::get(progress.era, validator);
+                let total = nominations
+                    .iter()
+                    .fold(0, |total: VoteWeight, (_, weight)| {
+                        total.saturating_add(*weight)
+                    });
+                let root = merkle_root::<T::Hashing>(leaf_hashes::<T>(&nominations));
+                (validator.clone(), total, root)
+            })
+            .collect::<Vec<_>>();
+        EraSnapshotRoot::<T>::insert(progress.era, roots);
+    }
+
+    /// Returns the merkle proof of the nomination of `nominator` to `validator` in the
+    /// snapshot of `era`, `None` if there is no such nomination or the snapshot is still
+    /// being taken.
+    pub fn nomination_proof(
+        era: EraIndex,
+        validator: &T::AccountId,
+        nominator: &T::AccountId,
+    ) -> Option<NominationProof<VoteWeight, T::Hash>> {
+        if Self::pending_era_snapshot().map(|progress| progress.era) == Some(era) {
+            return None;
+        }
+
+        let nominations = EraSnapshotLeaves::<T>::get(era, validator);
+        let index = nominations
+            .binary_search_by(|(who, _)| who.cmp(nominator))
+            .ok()?;
+
+        let mut level = leaf_hashes::<T>(&nominations);
+        let mut position = index;
+        let mut siblings = Vec::new();
+        while level.len() > 1 {
+            let sibling = position ^ 1;
+            if sibling < level.len() {
+                siblings.push(level[sibling]);
+            }
+            level = next_level::<T::Hashing>(level);
+            position /= 2;
+        }
+
+        Some(NominationProof {
+            weight: nominations[index].1,
+            leaf_index: index as u32,
+            leaf_count: nominations.len() as u32,
+            siblings,
+        })
+    }
+}
+
+/// Returns whether the nomination of `nominator` with `proof` is included in `root`.
+pub fn verify_nomination_proof<AccountId: Encode, H: Hash>(
+    root: H::Output,
+    nominator: &AccountId,
+    proof: &NominationProof<VoteWeight, H::Output>,
+) -> bool {
+    if proof.leaf_index >= proof.leaf_count {
+        return false;
+    }
+
+    let mut node = H::hash_of(&(nominator, proof.weight));
+    let mut position = proof.leaf_index as usize;
+    let mut width = proof.leaf_count as usize;
+    let mut siblings = proof.siblings.iter();
+    while width > 1 {
+        // The last node of a level with an odd width has no sibling.
+        if position != width - 1 || width % 2 == 0 {
+            let sibling = match siblings.next() {
+                Some(sibling) => sibling,
+                None => return false,
+            };
+            node = if position % 2 == 0 {
+                H::hash_of(&(node, sibling))
+            } else {
+                H::hash_of(&(sibling, node))
+            };
+        }
+        position /= 2;
+        width = (width + 1) / 2;
+    }
+
+    siblings.next().is_none() && node == root
+}
+
+fn leaf_hashes<T: Config>(nominations: &[(T::AccountId, VoteWeight)]) -> Vec<T::Hash> {
+    nominations
+        .iter()
+        .map(|(nominator, weight)| T::Hashing::hash_of(&(nominator, weight)))
+        .collect()
+}
+
+fn next_level<H: Hash>(level: Vec<H::Output>) -> Vec<H::Output> {
+    level
+        .chunks(2)
+        .map(|pair| {
+            if let [left, right] = pair {
+                H::hash_of(&(left, right))
+            } else {
+                pair[0]
+            }
+        })
+        .collect()
+}
+
+fn merkle_root<H: Hash>(mut level: Vec<H::Output>) -> H::Output {
+    if level.is_empty() {
+        return Default::default();
+    }
+    while level.len() > 1 {
+        level = next_level::<H>(level);
+    }
+    level[0]
+}
@@ -2,19 +2,26 @@
 
 use super::*;
 use crate::mock::*;
-use frame_support::{assert_err, assert_ok, traits::OnInitialize};
+use codec::Encode;
+use frame_support::{
+    assert_err, assert_noop, assert_ok,
+    traits::{GetStorageVersion, OnInitialize, StorageVersion},
+    weights::{DispatchClass, DispatchInfo, GetDispatchInfo},
+};
+use frame_system::CheckWeight;
+use sp_runtime::{
+    testing::UintAuthorityId,
+    traits::SignedExtension,
+    transaction_validity::{InvalidTransaction, TransactionValidityError},
+};
+use xp_mining_common::{WeightFactors, WeightType};
 
 fn t_issue_pcx(to: AccountId, value: Balance) {
     XStaking::mint(&to, value);
 }
 
 fn t_register(who: AccountId, initial_bond: Balance) -> DispatchResult {
-    let mut referral_id = who.to_string().as_bytes().to_vec();
-
-    if referral_id.len() < 2 {
-        referral_id.extend_from_slice(&[0, 0, 0, who as u8]);
-    }
-
+    let referral_id = format!("{:02}", who).into_bytes();
     XStaking::register(Origin::signed(who), referral_id, initial_bond)
 }
 
@@ -64,6 +71,21 @@ fn t_start_session(session_index: SessionIndex) {
     assert_eq!(Session::current_index(), session_index);
 }
 
+fn t_settle_jackpots(validators: &[AccountId]) {
+    for validator in validators {
+        assert_ok!(XStaking::settle_jackpot(Origin::signed(888), *validator));
+    }
+}
+
+fn t_run_to_block(n: BlockNumber) {
+    for b in System::block_number() + 1..=n {
+        System::set_block_number(b);
+        Timestamp::set_timestamp(b * 1000 + INIT_TIMESTAMP);
+        Session::on_initialize(b);
+        XStaking::on_initialize(b);
+    }
+}
+
 fn assert_bonded_locks(who: AccountId, value: Balance) {
     assert_eq!(
         *<Locks<Test>>::get(who)
@@ -498,6 +520,7 @@ fn staking_reward_should_work() {
         // When you start session 1, actually there are 3 session rounds.
         // the session reward has been minted 3 times.
         t_start_session(1);
+        t_settle_jackpots(&[1, 2, 3, 4]);
 
         let sub_total = 2_500_000_000u128;
 
@@ -570,16 +593,14 @@ fn staking_reward_should_work() {
 
         t_start_session(2);
         assert_eq!(
-            Balances::total_issuance(),
+            XStaking::total_issuance(),
             2_500_000_000u128 * 2 + issued_manually + endowed + (FIXED_TOTAL / 2) as u128
         );
     });
 }
 
 fn t_reward_pot_balance(validator: AccountId) -> Balance {
-    XStaking::free_balance(
-        &DummyStakingRewardPotAccountDeterminer::reward_pot_account_for(&validator),
-    )
+    XStaking::reward_pot_balance_of(&validator)
 }
 
 #[test]
@@ -853,6 +874,147 @@ fn referral_id_should_work() {
     });
 }
 
+#[test]
+fn referral_id_naming_rules_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        // ASCII alphanumerics and `-_.` only.
+        assert_err!(
+            XStaking::register(Origin::signed(111), b"bad name".to_vec(), 0),
+            Error::<Test>::InvalidReferralIdentityCharset
+        );
+        // "vаlidator" with a Cyrillic `а`.
+        assert_err!(
+            XStaking::register(Origin::signed(111), "v\u{430}lidator".into(), 0),
+            Error::<Test>::InvalidReferralIdentityCharset
+        );
+
+        assert_noop!(
+            XStaking::set_reserved_name_prefixes(Origin::signed(1), vec![b"chainx".to_vec()]),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            XStaking::set_reserved_name_prefixes(Origin::root(), vec![vec![]]),
+            Error::<Test>::InvalidReservedNamePrefixes
+        );
+        assert_ok!(XStaking::set_reserved_name_prefixes(
+            Origin::root(),
+            vec![b"ChainX".to_vec(), b"official".to_vec()]
+        ));
+        assert_eq!(
+            XStaking::reserved_name_prefixes(),
+            vec![b"chainx".to_vec(), b"official".to_vec()]
+        );
+        assert_err!(
+            XStaking::register(Origin::signed(111), b"chainx-team".to_vec(), 0),
+            Error::<Test>::ReservedReferralIdentity
+        );
+        assert_err!(
+            XStaking::register(Origin::signed(111), b"OFFICIAL.1".to_vec(), 0),
+            Error::<Test>::ReservedReferralIdentity
+        );
+
+        assert_ok!(XStaking::register(
+            Origin::signed(111),
+            b"Referral_1".to_vec(),
+            0
+        ));
+        assert_err!(
+            XStaking::register(Origin::signed(112), b"referral_1".to_vec(), 0),
+            Error::<Test>::OccupiedReferralIdentity
+        );
+    });
+}
+
+#[test]
+fn audit_existing_names_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        let violations = || {
+            System::events()
+                .into_iter()
+                .filter_map(|record| match record.event {
+                    mock::Event::XStaking(crate::Event::ReferralIdentityViolated(
+                        who,
+                        _,
+                        violation,
+                    )) => Some((who, violation)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_ok!(XStaking::register(
+            Origin::signed(111),
+            b"referral".to_vec(),
+            0
+        ));
+        // Registered before the naming rules.
+        ValidatorFor::<Test>::insert(b"REFERRAL".to_vec(), 112);
+        XStaking::note_referral_id_holder(b"REFERRAL", 112);
+        System::reset_events();
+
+        assert_noop!(
+            XStaking::audit_existing_names(Origin::signed(1), 3),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(XStaking::audit_existing_names(Origin::root(), 3));
+        assert!(NameAuditCursor::<Test>::get().is_some());
+        assert!(violations().len() <= 3);
+
+        assert_ok!(XStaking::audit_existing_names(Origin::root(), 10));
+        assert!(NameAuditCursor::<Test>::get().is_none());
+        System::assert_last_event(mock::Event::XStaking(crate::Event::NameAuditCompleted));
+
+        // The genesis referral identities contain a space.
+        let mut violations = violations();
+        violations.sort_by_key(|(who, _)| *who);
+        assert_eq!(
+            violations,
+            vec![
+                (1, NameViolation::InvalidCharset),
+                (2, NameViolation::InvalidCharset),
+                (3, NameViolation::InvalidCharset),
+                (4, NameViolation::InvalidCharset),
+                (111, NameViolation::CaseInsensitiveCollision),
+                (112, NameViolation::CaseInsensitiveCollision),
+            ]
+        );
+    });
+}
+
+#[test]
+fn referral_id_holders_should_be_migrated() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XStaking::register(
+            Origin::signed(111),
+            b"referral".to_vec(),
+            0
+        ));
+        assert_eq!(
+            ReferralIdHolders::<Test>::get(b"referral".to_vec()),
+            vec![111]
+        );
+
+        // Registered before the naming rules and the index.
+        ValidatorFor::<Test>::insert(b"REFERRAL".to_vec(), 112);
+        let _ = ReferralIdHolders::<Test>::remove_all(None);
+        StorageVersion::new(0).put::<XStaking>();
+
+        crate::migrations::migrate::<Test>();
+        assert_eq!(
+            ReferralIdHolders::<Test>::get(b"referral".to_vec()),
+            vec![111, 112]
+        );
+        assert_eq!(
+            XStaking::on_chain_storage_version(),
+            crate::migrations::STORAGE_VERSION
+        );
+        assert_err!(
+            XStaking::register(Origin::signed(113), b"Referral".to_vec(), 0),
+            Error::<Test>::OccupiedReferralIdentity
+        );
+    });
+}
+
 #[test]
 fn migration_session_offset_should_work() {
     ExtBuilder::default().build_and_execute(|| {
@@ -898,3 +1060,2058 @@ fn migration_session_offset_should_work() {
         );
     });
 }
+
+#[test]
+fn set_metadata_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_err!(
+            XStaking::set_metadata(
+                Origin::signed(111),
+                vec![(MetaKey::Website, b"https://chainx.org".to_vec())]
+            ),
+            Error::<Test>::NotValidator
+        );
+
+        assert_ok!(XStaking::set_metadata(
+            Origin::signed(1),
+            vec![
+                (MetaKey::Website, b"https://chainx.org".to_vec()),
+                (MetaKey::Riot, b"@validator:matrix.org".to_vec()),
+            ]
+        ));
+        assert_eq!(
+            XStaking::validator_metadata_of(1),
+            vec![
+                (MetaKey::Website, b"https://chainx.org".to_vec()),
+                (MetaKey::Riot, b"@validator:matrix.org".to_vec()),
+            ]
+        );
+        assert_eq!(
+            XStaking::validator_info_of(1).metadata,
+            vec![
+                MetadataEntry {
+                    key: MetaKey::Website,
+                    value: b"https://chainx.org".to_vec()
+                },
+                MetadataEntry {
+                    key: MetaKey::Riot,
+                    value: b"@validator:matrix.org".to_vec()
+                },
+            ]
+        );
+
+        // The whole set is overwritten.
+        assert_ok!(XStaking::set_metadata(
+            Origin::signed(1),
+            vec![(MetaKey::Email, b"validator@chainx.org".to_vec())]
+        ));
+        assert_eq!(
+            XStaking::validator_metadata_of(1),
+            vec![(MetaKey::Email, b"validator@chainx.org".to_vec())]
+        );
+
+        // An empty set clears the metadata.
+        assert_ok!(XStaking::set_metadata(Origin::signed(1), vec![]));
+        assert!(!ValidatorMetadataOf::<Test>::contains_key(1));
+    });
+}
+
+#[test]
+fn set_invalid_metadata_should_not_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        let set_metadata =
+            |entries: Vec<(MetaKey, Vec<u8>)>| XStaking::set_metadata(Origin::signed(1), entries);
+
+        assert_err!(
+            set_metadata(vec![(MetaKey::Website, b"https://chainx.org/a b".to_vec())]),
+            Error::<Test>::InvalidMetadataValue
+        );
+        assert_err!(
+            set_metadata(vec![(MetaKey::Email, b"chainx.org".to_vec())]),
+            Error::<Test>::InvalidMetadataValue
+        );
+        assert_err!(
+            set_metadata(vec![(MetaKey::Twitter, vec![])]),
+            Error::<Test>::InvalidMetadataValue
+        );
+        assert_err!(
+            set_metadata(vec![(
+                MetaKey::Telemetry,
+                vec![b'a'; MAXIMUM_METADATA_VALUE_LENGTH + 1]
+            )]),
+            Error::<Test>::InvalidMetadataValue
+        );
+        assert_err!(
+            set_metadata(vec![(MetaKey::Riot, b"<script>".to_vec())]),
+            Error::<Test>::XssCheckFailed
+        );
+        assert_err!(
+            set_metadata(vec![
+                (MetaKey::Website, b"https://chainx.org".to_vec()),
+                (MetaKey::Website, b"https://docs.chainx.org".to_vec()),
+            ]),
+            Error::<Test>::DuplicateMetadataKey
+        );
+
+        // Nothing is changed on failure.
+        assert!(!ValidatorMetadataOf::<Test>::contains_key(1));
+    });
+}
+
+#[test]
+fn website_should_map_to_website_metadata() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_eq!(XStaking::website_of(&1), None);
+
+        assert_ok!(XStaking::set_metadata(
+            Origin::signed(1),
+            vec![
+                (MetaKey::Email, b"validator@chainx.org".to_vec()),
+                (MetaKey::Website, b"https://chainx.org".to_vec()),
+            ]
+        ));
+        assert_eq!(
+            XStaking::website_of(&1),
+            Some(b"https://chainx.org".to_vec())
+        );
+
+        assert_ok!(XStaking::set_metadata(
+            Origin::signed(1),
+            vec![(MetaKey::Email, b"validator@chainx.org".to_vec())]
+        ));
+        assert_eq!(XStaking::website_of(&1), None);
+    });
+}
+
+#[test]
+fn era_progress_should_work() {
+    // Session length is 5 and sessions per era is 3, sessions rotate at block 5, 10, 15...
+    ExtBuilder::default()
+        .session_length(5)
+        .build_and_execute(|| {
+            // Genesis: era 0 starts at session 0, era 1 starts at session 3(block 15).
+            assert_eq!(
+                XStaking::era_progress(),
+                EraProgress {
+                    current_session: 0,
+                    sessions_per_era: 3,
+                    current_era: 0,
+                    blocks_into_session: 1,
+                    session_length: 5,
+                    blocks_until_next_era: Some(14),
+                    forcing_new_era: false,
+                }
+            );
+
+            // Mid-era.
+            t_run_to_block(7);
+            assert_eq!(
+                XStaking::era_progress(),
+                EraProgress {
+                    current_session: 1,
+                    sessions_per_era: 3,
+                    current_era: 0,
+                    blocks_into_session: 2,
+                    session_length: 5,
+                    blocks_until_next_era: Some(8),
+                    forcing_new_era: false,
+                }
+            );
+
+            // Boundary: era 1 has been planned at the start of session 2.
+            t_run_to_block(10);
+            assert_eq!(
+                XStaking::era_progress(),
+                EraProgress {
+                    current_session: 2,
+                    sessions_per_era: 3,
+                    current_era: 1,
+                    blocks_into_session: 0,
+                    session_length: 5,
+                    blocks_until_next_era: Some(5),
+                    forcing_new_era: false,
+                }
+            );
+
+            // Era 1 is active, era 2 starts at session 6(block 30).
+            t_run_to_block(16);
+            let progress = XStaking::era_progress();
+            assert_eq!(progress.current_session, 3);
+            assert_eq!(progress.blocks_into_session, 1);
+            assert_eq!(progress.blocks_until_next_era, Some(14));
+
+            // Forced new era is planned by the next rotation and starts at session 5(block 25).
+            ForceEra::<Test>::put(Forcing::ForceNew);
+            let progress = XStaking::era_progress();
+            assert!(progress.forcing_new_era);
+            assert_eq!(progress.blocks_until_next_era, Some(9));
+
+            // No new era at all.
+            ForceEra::<Test>::put(Forcing::ForceNone);
+            let progress = XStaking::era_progress();
+            assert!(!progress.forcing_new_era);
+            assert_eq!(progress.blocks_until_next_era, None);
+        });
+}
+
+#[test]
+fn staking_with_memo_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        let t_1 = 1111;
+        t_issue_pcx(t_1, 100);
+        XStaking::mint(&888, (FIXED_TOTAL / 2) as u128);
+
+        let memo = Memo::from(b"acct:2021-0042".to_vec());
+        let staking_event = |event: crate::Event<Test>| crate::mock::Event::XStaking(event);
+
+        assert_ok!(XStaking::bond_with_memo(
+            Origin::signed(t_1),
+            1,
+            20,
+            memo.clone()
+        ));
+        System::assert_last_event(staking_event(crate::Event::Bonded(
+            t_1,
+            1,
+            20,
+            memo.clone(),
+        )));
+
+        assert_ok!(XStaking::unbond_with_memo(
+            Origin::signed(t_1),
+            1,
+            5,
+            memo.clone()
+        ));
+        System::assert_last_event(staking_event(crate::Event::Unbonded(
+            t_1,
+            1,
+            5,
+            memo.clone(),
+        )));
+
+        t_start_session(2);
+
+        let dividend = XStaking::compute_dividend_at(&t_1, &1, System::block_number()).unwrap();
+        assert!(dividend > 0);
+        assert_ok!(XStaking::claim_with_memo(
+            Origin::signed(t_1),
+            1,
+            memo.clone()
+        ));
+        System::assert_last_event(staking_event(crate::Event::Claimed(t_1, 1, dividend, memo)));
+
+        // The calls without memo emit an empty memo.
+        assert_ok!(t_bond(t_1, 1, 5));
+        System::assert_last_event(staking_event(crate::Event::Bonded(
+            t_1,
+            1,
+            5,
+            Memo::default(),
+        )));
+    });
+}
+
+#[test]
+fn bond_inactive_validator_should_be_acknowledged() {
+    ExtBuilder::default().build_and_execute(|| {
+        let t_1 = 1111;
+        t_issue_pcx(t_1, 100);
+        let staking_event = |event: crate::Event<Test>| crate::mock::Event::XStaking(event);
+
+        assert_ok!(XStaking::chill(Origin::signed(4)));
+        assert_noop!(t_bond(t_1, 4, 10), Error::<Test>::InactiveValidator);
+        assert_noop!(
+            XStaking::bond_with_memo(Origin::signed(t_1), 4, 10, Memo::default()),
+            Error::<Test>::InactiveValidator
+        );
+
+        assert_ok!(XStaking::bond_inactive(Origin::signed(t_1), 4, 10));
+        System::assert_last_event(staking_event(crate::Event::Bonded(
+            t_1,
+            4,
+            10,
+            Memo::default(),
+        )));
+        assert_eq!(XStaking::bonded_to(&t_1, &4), 10);
+
+        // The chilled validator can still bond to itself.
+        t_issue_pcx(4, 10);
+        assert_ok!(t_bond(4, 4, 10));
+
+        // Nor can the nominations be moved to the chilled validator.
+        assert_ok!(t_bond(t_1, 1, 10));
+        assert_noop!(t_rebond(t_1, 1, 4, 5), Error::<Test>::InactiveValidator);
+        assert_eq!(XStaking::bonded_to(&t_1, &1), 10);
+    });
+}
+
+#[test]
+fn bond_non_elected_validator_should_emit_event() {
+    ExtBuilder::default().build_and_execute(|| {
+        let t_1 = 1111;
+        t_issue_pcx(t_1, 100);
+        let staking_event = |event: crate::Event<Test>| crate::mock::Event::XStaking(event);
+
+        // No warning for the elected validators.
+        assert_ok!(t_bond(t_1, 1, 10));
+        System::assert_last_event(staking_event(crate::Event::Bonded(
+            t_1,
+            1,
+            10,
+            Memo::default(),
+        )));
+
+        t_make_a_validator_candidate(123, 100);
+        assert!(!Session::validators().contains(&123));
+        assert_ok!(t_bond(t_1, 123, 10));
+        System::assert_last_event(staking_event(crate::Event::NonElectedValidatorBonded(
+            t_1, 123,
+        )));
+    });
+}
+
+#[test]
+fn invalid_memo_should_be_rejected() {
+    ExtBuilder::default().build_and_execute(|| {
+        let t_1 = 1111;
+        t_issue_pcx(t_1, 100);
+        assert_ok!(t_bond(t_1, 1, 20));
+
+        for memo in [
+            Memo::from(vec![b'a'; 129]),
+            Memo::from(b"<script>".to_vec()),
+        ] {
+            let err = memo.check_validity().unwrap_err();
+            assert_noop!(
+                XStaking::bond_with_memo(Origin::signed(t_1), 1, 10, memo.clone()),
+                err
+            );
+            assert_noop!(
+                XStaking::unbond_with_memo(Origin::signed(t_1), 1, 10, memo.clone()),
+                err
+            );
+            assert_noop!(
+                XStaking::claim_with_memo(Origin::signed(t_1), 1, memo.clone()),
+                err
+            );
+        }
+    });
+}
+
+#[test]
+fn treasury_accounting_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        XStaking::mint(&888, (FIXED_TOTAL / 2) as u128);
+
+        t_start_session(1);
+        let treasury_balance = Balances::free_balance(&TREASURY_ACCOUNT);
+        assert!(treasury_balance > 0);
+        assert_eq!(XStaking::cumulative_treasury_rewards(), treasury_balance);
+
+        t_start_session(2);
+        assert!(Balances::free_balance(&TREASURY_ACCOUNT) > treasury_balance);
+        assert_eq!(
+            XStaking::cumulative_treasury_rewards(),
+            Balances::free_balance(&TREASURY_ACCOUNT)
+        );
+
+        let accounting = XStaking::treasury_accounting();
+        assert_eq!(accounting.account, Some(TREASURY_ACCOUNT));
+        assert_eq!(
+            accounting.balance,
+            Balances::free_balance(&TREASURY_ACCOUNT)
+        );
+        assert_eq!(
+            accounting.cumulative_rewards,
+            XStaking::cumulative_treasury_rewards()
+        );
+        assert_eq!(accounting.cumulative_spends, 0);
+        assert!(accounting.spends.is_empty());
+    });
+}
+
+#[test]
+fn record_treasury_spend_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_noop!(
+            XStaking::record_treasury_spend(Origin::signed(1), 10, b"spend".to_vec().into()),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            XStaking::record_treasury_spend(Origin::root(), 0, b"spend".to_vec().into()),
+            Error::<Test>::ZeroBalance
+        );
+
+        let total = MAXIMUM_TREASURY_SPEND_HISTORY + 5;
+        for i in 1..=total {
+            assert_ok!(XStaking::record_treasury_spend(
+                Origin::root(),
+                i as Balance,
+                format!("spend {}", i).into_bytes().into()
+            ));
+        }
+        System::assert_last_event(crate::mock::Event::XStaking(
+            crate::Event::TreasurySpendRecorded(
+                total as Balance,
+                format!("spend {}", total).into_bytes().into(),
+            ),
+        ));
+
+        // Only the latest spends are kept in the history.
+        let history = XStaking::treasury_spend_history();
+        assert_eq!(history.len(), MAXIMUM_TREASURY_SPEND_HISTORY);
+        assert_eq!(history[0].amount, 6);
+        assert_eq!(history.last().unwrap().amount, total as Balance);
+
+        let sum = (1..=total).map(|i| i as Balance).sum::<Balance>();
+        assert_eq!(XStaking::cumulative_treasury_spends(), sum);
+        assert_eq!(XStaking::treasury_accounting().cumulative_spends, sum);
+        assert_eq!(XStaking::treasury_accounting().spends, history);
+    });
+}
+
+#[test]
+fn claim_switch_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        let t_1 = 1111;
+        t_issue_pcx(t_1, 100);
+        XStaking::mint(&888, (FIXED_TOTAL / 2) as u128);
+        assert_ok!(t_bond(t_1, 1, 20));
+
+        let staking_event = |event: crate::Event<Test>| crate::mock::Event::XStaking(event);
+
+        assert!(XStaking::claim_switch(ClaimTypeKind::Intention));
+        assert_noop!(
+            XStaking::set_claim_switch(Origin::signed(t_1), ClaimTypeKind::Intention, false),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(XStaking::set_claim_switch(
+            Origin::root(),
+            ClaimTypeKind::Intention,
+            false
+        ));
+        System::assert_last_event(staking_event(crate::Event::ClaimSwitchUpdated(
+            ClaimTypeKind::Intention,
+            false,
+        )));
+        assert!(!XStaking::validator_info_of(1).claim_enabled);
+        // The asset mining claims are switched separately.
+        assert!(XStaking::claim_switch(ClaimTypeKind::PseduIntention));
+
+        t_start_session(2);
+
+        let paused_dividend =
+            XStaking::compute_dividend_at(&t_1, &1, System::block_number()).unwrap();
+        assert!(paused_dividend > 0);
+        assert_noop!(
+            XStaking::claim(Origin::signed(t_1), 1),
+            Error::<Test>::ClaimsDisabled
+        );
+        assert_noop!(
+            XStaking::claim_with_memo(Origin::signed(t_1), 1, Memo::default()),
+            Error::<Test>::ClaimsDisabled
+        );
+
+        // The vote weight keeps accruing while the claims are disabled.
+        t_start_session(3);
+        let dividend = XStaking::compute_dividend_at(&t_1, &1, System::block_number()).unwrap();
+        assert!(dividend > paused_dividend);
+
+        assert_ok!(XStaking::set_claim_switch(
+            Origin::root(),
+            ClaimTypeKind::Intention,
+            true
+        ));
+        assert!(XStaking::validator_info_of(1).claim_enabled);
+        assert_ok!(XStaking::claim(Origin::signed(t_1), 1));
+        System::assert_last_event(staking_event(crate::Event::Claimed(
+            t_1,
+            1,
+            dividend,
+            Memo::default(),
+        )));
+    });
+}
+
+#[test]
+fn performance_weighted_reward_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        XStaking::mint(&888, (FIXED_TOTAL / 2) as u128);
+
+        assert_noop!(
+            XStaking::set_performance_leniency(Origin::signed(1), Some(100)),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            XStaking::set_performance_leniency(Origin::root(), Some(99)),
+            Error::<Test>::InvalidPerformanceLeniency
+        );
+        assert_ok!(XStaking::set_performance_leniency(
+            Origin::root(),
+            Some(100)
+        ));
+        System::assert_last_event(crate::mock::Event::XStaking(
+            crate::Event::PerformanceLeniencyUpdated(Some(100)),
+        ));
+
+        // Era 1 is planned by the next rotation and starts at session 2.
+        ForceEra::<Test>::put(Forcing::ForceNew);
+
+        let t_note_author = |validator: AccountId, blocks: u32| {
+            for _ in 0..blocks {
+                <XStaking as pallet_authorship::EventHandler<_, _>>::note_author(validator);
+            }
+        };
+
+        // 17 blocks in session 0, each of the 4 validators is expected to author 4 blocks.
+        t_note_author(1, 4);
+        t_note_author(2, 2);
+        t_note_author(3, 4);
+        t_note_author(4, 6);
+        // Not a validator.
+        t_note_author(888, 1);
+        assert_eq!(XStaking::session_blocks(), 17);
+        assert_eq!(XStaking::session_authored_blocks(888), 0);
+        assert_eq!(XStaking::session_authored_blocks(2), 2);
+
+        t_start_session(1);
+        t_settle_jackpots(&[1]);
+
+        // Staking reward: 2_500_000_000 * 88% * 90% = 1_980_000_000, split by the votes 1:2:3:4.
+        let staking_reward = 1_980_000_000u128;
+        let reward_of_1 = staking_reward * 10 / 100;
+        // Validator 2 authored only half of the expected blocks.
+        let reward_of_2 = staking_reward * 20 / 100 / 2;
+        assert_eq!(Balances::free_balance(&1), 100 + reward_of_1 / 5);
+        assert_eq!(t_reward_pot_balance(1), reward_of_1 - reward_of_1 / 5);
+        assert_eq!(Balances::free_balance(&2), 200 + reward_of_2 / 5);
+        assert_eq!(t_reward_pot_balance(2), reward_of_2 - reward_of_2 / 5);
+
+        // treasury_reward + asset_mining_reward + withheld reward of validator 2.
+        let withheld = staking_reward * 20 / 100 - reward_of_2;
+        assert_eq!(
+            Balances::free_balance(&TREASURY_ACCOUNT),
+            300_000_000 + 220_000_000 + withheld
+        );
+
+        assert_eq!(XStaking::session_blocks(), 0);
+        assert_eq!(XStaking::session_authored_blocks(2), 0);
+        assert_eq!(
+            XStaking::validator_info_of(2).performance,
+            ValidatorPerformance {
+                authored_blocks: 2,
+                expected_blocks: 4,
+                missed_sessions: 0,
+            }
+        );
+
+        // Validator 2 authors nothing in session 1.
+        t_note_author(1, 2);
+        t_note_author(3, 2);
+        t_note_author(4, 2);
+
+        t_start_session(2);
+        assert_eq!(XStaking::active_era().unwrap().index, 1);
+
+        let performance_event = |validator: AccountId, authored, expected, missed| {
+            crate::mock::Event::XStaking(crate::Event::EraPerformanceRecorded(
+                0, validator, authored, expected, missed,
+            ))
+        };
+        let events = System::events()
+            .into_iter()
+            .map(|record| record.event)
+            .collect::<Vec<_>>();
+        assert!(events.contains(&performance_event(1, 6, 5, 0)));
+        assert!(events.contains(&performance_event(2, 2, 5, 1)));
+        assert!(events.contains(&performance_event(4, 8, 5, 0)));
+
+        // The era records are reset for the new era.
+        assert_eq!(
+            XStaking::validator_info_of(1).performance,
+            ValidatorPerformance::default()
+        );
+    });
+}
+
+#[test]
+fn performance_weighting_is_disabled_by_default() {
+    ExtBuilder::default().build_and_execute(|| {
+        XStaking::mint(&888, (FIXED_TOTAL / 2) as u128);
+        assert_eq!(XStaking::performance_leniency(), None);
+
+        for _ in 0..4 {
+            <XStaking as pallet_authorship::EventHandler<_, _>>::note_author(1);
+        }
+        t_start_session(1);
+        t_settle_jackpots(&[2]);
+
+        // Validators 2, 3 and 4 authored nothing but are rewarded by the votes only.
+        let reward_of_2 = 1_980_000_000u128 * 20 / 100;
+        assert_eq!(Balances::free_balance(&2), 200 + reward_of_2 / 5);
+        assert_eq!(
+            Balances::free_balance(&TREASURY_ACCOUNT),
+            300_000_000 + 220_000_000
+        );
+        // The block production is still tracked.
+        assert_eq!(
+            XStaking::validator_info_of(2).performance.missed_sessions,
+            1
+        );
+    });
+}
+
+/// Returns all the storage entries at the moment.
+fn t_storage_snapshot() -> BTreeMap<Vec<u8>, Vec<u8>> {
+    let mut snapshot = BTreeMap::new();
+    let mut key = Vec::new();
+    while let Some(next_key) = sp_io::storage::next_key(&key) {
+        let value = sp_io::storage::get(&next_key).unwrap_or_default();
+        snapshot.insert(next_key.clone(), value);
+        key = next_key;
+    }
+    snapshot
+}
+
+fn t_written_keys(
+    before: &BTreeMap<Vec<u8>, Vec<u8>>,
+    after: &BTreeMap<Vec<u8>, Vec<u8>>,
+) -> Vec<Vec<u8>> {
+    before
+        .keys()
+        .chain(after.keys())
+        .filter(|key| before.get(*key) != after.get(*key))
+        .cloned()
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+#[test]
+fn session_reward_should_be_accrued_lazily() {
+    ExtBuilder::default().build_and_execute(|| {
+        XStaking::mint(&888, (FIXED_TOTAL / 2) as u128);
+        let validators = vec![1, 2, 3, 4];
+        let account_keys = validators
+            .iter()
+            .flat_map(|validator| {
+                let pot = DummyStakingRewardPotAccountDeterminer::reward_pot_account_for(validator);
+                vec![
+                    frame_system::Account::<Test>::hashed_key_for(validator),
+                    frame_system::Account::<Test>::hashed_key_for(&pot),
+                ]
+            })
+            .collect::<Vec<_>>();
+
+        let before = t_storage_snapshot();
+        t_start_session(1);
+        let written = t_written_keys(&before, &t_storage_snapshot());
+
+        // One accumulator write per validator, no balance of the validators is touched.
+        for validator in &validators {
+            assert!(written.contains(&UnsettledRewards::<Test>::hashed_key_for(validator)));
+            assert_eq!(
+                XStaking::unsettled_reward_of(validator)
+                    .unwrap()
+                    .last_session,
+                1
+            );
+        }
+        assert!(account_keys.iter().all(|key| !written.contains(key)));
+        let lazy_writes = written.len();
+
+        // The eager distribution is equivalent to settling all the jackpots in every session.
+        let before = t_storage_snapshot();
+        t_start_session(2);
+        t_settle_jackpots(&validators);
+        let written = t_written_keys(&before, &t_storage_snapshot());
+        assert!(account_keys.iter().all(|key| written.contains(key)));
+        assert!(written.len() > lazy_writes);
+
+        assert_eq!(XStaking::unsettled_reward_total(), 0);
+        assert_eq!(XStaking::total_issuance(), Balances::total_issuance());
+    });
+}
+
+/// Returns the balances of the nominator of validator 1, validator 1 itself, its reward pot
+/// and the total issuance after claiming the rewards of 3 sessions.
+fn t_claim_after_sessions(settle_every_session: bool) -> (Balance, Balance, Balance, Balance) {
+    let mut outcome = Default::default();
+    ExtBuilder::default().build_and_execute(|| {
+        let validators = [1, 2, 3, 4];
+        let nominator = 1111;
+        let pot = DummyStakingRewardPotAccountDeterminer::reward_pot_account_for(&1);
+
+        XStaking::mint(&888, (FIXED_TOTAL / 2) as u128);
+        t_issue_pcx(nominator, 100);
+        assert_ok!(t_bond(nominator, 1, 10));
+
+        for session in 1..=3 {
+            t_start_session(session);
+            if settle_every_session {
+                t_settle_jackpots(&validators);
+            }
+        }
+
+        if !settle_every_session {
+            assert_eq!(Balances::free_balance(&pot), 0);
+            assert_eq!(
+                XStaking::total_issuance(),
+                Balances::total_issuance() + XStaking::unsettled_reward_total()
+            );
+        }
+
+        assert_ok!(XStaking::claim(Origin::signed(nominator), 1));
+        assert!(XStaking::unsettled_reward_of(1).is_none());
+        t_settle_jackpots(&validators);
+        assert_eq!(XStaking::unsettled_reward_total(), 0);
+
+        outcome = (
+            Balances::free_balance(&nominator),
+            Balances::free_balance(&1),
+            Balances::free_balance(&pot),
+            Balances::total_issuance(),
+        );
+    });
+    outcome
+}
+
+#[test]
+fn lazy_session_reward_should_pay_as_eager_distribution() {
+    let eager = t_claim_after_sessions(true);
+    let lazy = t_claim_after_sessions(false);
+    assert!(eager.0 > 100);
+    assert_eq!(lazy, eager);
+}
+
+#[test]
+fn settle_jackpot_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        XStaking::mint(&888, (FIXED_TOTAL / 2) as u128);
+        t_start_session(1);
+
+        assert_noop!(
+            XStaking::settle_jackpot(Origin::signed(888), 888),
+            Error::<Test>::NotValidator
+        );
+
+        let unsettled = XStaking::unsettled_reward_of(2).unwrap();
+        let pot = DummyStakingRewardPotAccountDeterminer::reward_pot_account_for(&2);
+        let total = XStaking::unsettled_reward_total();
+        assert_ok!(XStaking::settle_jackpot(Origin::signed(888), 2));
+        System::assert_last_event(crate::mock::Event::XStaking(
+            crate::Event::MintedForValidator(2, unsettled.validator, pot, unsettled.reward_pot),
+        ));
+        assert_eq!(Balances::free_balance(&2), 200 + unsettled.validator);
+        assert_eq!(Balances::free_balance(&pot), unsettled.reward_pot);
+        assert_eq!(
+            XStaking::unsettled_reward_total(),
+            total - unsettled.validator - unsettled.reward_pot
+        );
+
+        // Nothing left to settle.
+        assert_ok!(XStaking::settle_jackpot(Origin::signed(888), 2));
+        assert_eq!(Balances::free_balance(&pot), unsettled.reward_pot);
+    });
+}
+
+#[test]
+fn set_bonding_duration_should_keep_in_flight_unbondings() {
+    ExtBuilder::default().build_and_execute(|| {
+        // Block 2
+        t_system_block_number_inc(1);
+        assert_ok!(t_bond(1, 2, 10));
+
+        // Block 3
+        t_system_block_number_inc(1);
+        assert_ok!(t_unbond(1, 2, 5));
+
+        // Block 4
+        t_system_block_number_inc(1);
+        assert_noop!(
+            XStaking::set_bonding_duration(Origin::signed(1), 100),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_ok!(XStaking::set_bonding_duration(Origin::root(), 100));
+        System::assert_last_event(crate::mock::Event::XStaking(
+            crate::Event::BondingDurationChanged(
+                BondingDurationKind::Nominator,
+                DEFAULT_BONDING_DURATION,
+                100,
+                4,
+            ),
+        ));
+        assert_eq!(XStaking::bonding_duration(), 100);
+
+        // The in-flight unbonding is unlocked as scheduled.
+        assert_eq!(
+            <Nominations<Test>>::get(1, 2).unbonded_chunks,
+            vec![Unbonded {
+                value: 5,
+                locked_until: DEFAULT_BONDING_DURATION + 3
+            }]
+        );
+
+        // Block 5
+        t_system_block_number_inc(1);
+        assert_ok!(t_unbond(1, 2, 2));
+        assert_eq!(
+            <Nominations<Test>>::get(1, 2).unbonded_chunks,
+            vec![
+                Unbonded {
+                    value: 5,
+                    locked_until: DEFAULT_BONDING_DURATION + 3
+                },
+                Unbonded {
+                    value: 2,
+                    locked_until: 100 + 5
+                }
+            ]
+        );
+        assert_eq!(
+            XStaking::unbonded_chunks_of(1).get(&2).map(|chunks| chunks
+                .iter()
+                .map(|chunk| chunk.bonding_duration)
+                .collect::<Vec<_>>()),
+            Some(vec![Some(DEFAULT_BONDING_DURATION), Some(100)])
+        );
+
+        assert_ok!(XStaking::set_validator_bonding_duration(
+            Origin::root(),
+            1000
+        ));
+        assert_eq!(XStaking::validator_bonding_duration(), 1000);
+        assert_eq!(
+            XStaking::bonding_duration_history(),
+            vec![
+                BondingDurationChange {
+                    kind: BondingDurationKind::Nominator,
+                    old: DEFAULT_BONDING_DURATION,
+                    new: 100,
+                    at_block: 4,
+                },
+                BondingDurationChange {
+                    kind: BondingDurationKind::Validator,
+                    old: DEFAULT_VALIDATOR_BONDING_DURATION,
+                    new: 1000,
+                    at_block: 5,
+                }
+            ]
+        );
+    });
+}
+
+fn t_reputation_event(validator: AccountId, old: u32, new: u32) -> crate::mock::Event {
+    crate::mock::Event::XStaking(crate::Event::ReputationUpdated(validator, old, new))
+}
+
+/// Slashes `offender` at the start of `session_index`, its reward pot is funded
+/// so that it won't be chilled.
+fn t_slash_in_session(offender: AccountId, session_index: SessionIndex) {
+    XStaking::mint(&XStaking::reward_pot_for(&offender), 1_000_000_000);
+    SessionOffenders::<Test>::put(
+        vec![(offender, Perbill::zero())]
+            .into_iter()
+            .collect::<BTreeMap<_, _>>(),
+    );
+    t_start_session(session_index);
+}
+
+#[test]
+fn reputation_should_decay_on_slash_and_recover() {
+    ExtBuilder::default().build_and_execute(|| {
+        XStaking::mint(&888, (FIXED_TOTAL / 2) as u128);
+
+        let params = ReputationParams {
+            slash_step_bps: 3_000,
+            recovery_bps: 1_000,
+            min_bps: 5_000,
+        };
+        assert_noop!(
+            XStaking::set_reputation_params(Origin::signed(1), Some(params)),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            XStaking::set_reputation_params(
+                Origin::root(),
+                Some(ReputationParams {
+                    min_bps: MAX_REPUTATION_BPS + 1,
+                    ..params
+                })
+            ),
+            Error::<Test>::InvalidReputationParams
+        );
+        assert_ok!(XStaking::set_reputation_params(
+            Origin::root(),
+            Some(params)
+        ));
+        System::assert_last_event(crate::mock::Event::XStaking(
+            crate::Event::ReputationParamsUpdated(Some(params)),
+        ));
+
+        // Each session starts a new era from session 2 on.
+        ForceEra::<Test>::put(Forcing::ForceAlways);
+
+        t_slash_in_session(2, 1);
+        assert_eq!(XStaking::reputation_of(2), 7_000);
+        assert_eq!(XStaking::validator_info_of(2).reputation, 7_000);
+        assert_eq!(XStaking::reputation_of(1), MAX_REPUTATION_BPS);
+        System::assert_has_event(t_reputation_event(2, 10_000, 7_000));
+
+        // Era 0 ends at session 2, validator 2 was slashed in it.
+        let treasury_balance = Balances::free_balance(&TREASURY_ACCOUNT);
+        t_start_session(2);
+        assert_eq!(XStaking::active_era().unwrap().index, 1);
+        assert_eq!(XStaking::reputation_of(2), 7_000);
+
+        // Staking reward: 1_980_000_000 split by the votes 1:2:3:4, validator 2 receives 70%.
+        let reward_of_2 = 1_980_000_000u128 * 20 / 100;
+        let unsettled = XStaking::unsettled_reward_of(2).unwrap();
+        assert_eq!(
+            unsettled.validator + unsettled.reward_pot,
+            reward_of_2 * 7 / 10
+        );
+        // The other validators are not affected.
+        let reward_of_1 = 1_980_000_000u128 * 10 / 100;
+        let unsettled = XStaking::unsettled_reward_of(1).unwrap();
+        assert_eq!(unsettled.validator + unsettled.reward_pot, reward_of_1);
+        // treasury_reward + asset_mining_reward + withheld reward of validator 2.
+        assert_eq!(
+            Balances::free_balance(&TREASURY_ACCOUNT),
+            treasury_balance + 300_000_000 + 220_000_000 + reward_of_2 * 3 / 10
+        );
+
+        // Several clean eras recover the reputation.
+        t_start_session(3);
+        assert_eq!(XStaking::reputation_of(2), 8_000);
+        System::assert_has_event(t_reputation_event(2, 7_000, 8_000));
+        t_start_session(5);
+        assert_eq!(XStaking::reputation_of(2), MAX_REPUTATION_BPS);
+        assert!(!ReputationOf::<Test>::contains_key(2));
+    });
+}
+
+#[test]
+fn reputation_should_be_clamped() {
+    ExtBuilder::default().build_and_execute(|| {
+        XStaking::mint(&888, (FIXED_TOTAL / 2) as u128);
+        assert_ok!(XStaking::set_reputation_params(
+            Origin::root(),
+            Some(ReputationParams {
+                slash_step_bps: 3_000,
+                recovery_bps: 4_000,
+                min_bps: 5_000,
+            })
+        ));
+        ForceEra::<Test>::put(Forcing::ForceAlways);
+
+        t_slash_in_session(2, 1);
+        assert_eq!(XStaking::reputation_of(2), 7_000);
+        t_slash_in_session(2, 2);
+        assert_eq!(XStaking::reputation_of(2), 5_000);
+        System::assert_has_event(t_reputation_event(2, 7_000, 5_000));
+
+        // No change at the lower bound.
+        System::reset_events();
+        t_slash_in_session(2, 3);
+        assert_eq!(XStaking::reputation_of(2), 5_000);
+        assert!(!System::events().into_iter().any(|record| matches!(
+            record.event,
+            crate::mock::Event::XStaking(crate::Event::ReputationUpdated(..))
+        )));
+
+        t_start_session(4);
+        assert_eq!(XStaking::reputation_of(2), 9_000);
+        t_start_session(5);
+        assert_eq!(XStaking::reputation_of(2), MAX_REPUTATION_BPS);
+        System::assert_has_event(t_reputation_event(2, 9_000, 10_000));
+    });
+}
+
+#[test]
+fn reputation_is_disabled_by_default() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_eq!(XStaking::reputation_params(), None);
+        t_slash_in_session(2, 1);
+        assert_eq!(XStaking::reputation_of(2), MAX_REPUTATION_BPS);
+        assert!(!SlashedInActiveEra::<Test>::contains_key(2));
+    });
+}
+
+#[test]
+fn recent_authored_blocks_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        let t_note_author = |validator: AccountId, blocks: u32| {
+            for _ in 0..blocks {
+                <XStaking as pallet_authorship::EventHandler<_, _>>::note_author(validator);
+            }
+        };
+
+        t_note_author(1, 3);
+        t_note_author(2, 1);
+        t_start_session(1);
+        assert_eq!(XStaking::authored_blocks_history(0, 1), 3);
+        assert_eq!(XStaking::recent_authored_blocks(&1, 1), 3);
+
+        t_note_author(1, 2);
+        t_start_session(2);
+        assert_eq!(XStaking::recent_authored_blocks(&1, 1), 2);
+        assert_eq!(XStaking::recent_authored_blocks(&1, 2), 5);
+        assert_eq!(XStaking::recent_authored_blocks(&2, 1), 0);
+        assert_eq!(XStaking::recent_authored_blocks(&2, 2), 1);
+        // The blocks of the current session are not counted until it ends.
+        t_note_author(3, 1);
+        assert_eq!(XStaking::recent_authored_blocks(&3, 2), 0);
+    });
+}
+
+#[test]
+fn unlock_unbonded_withdrawal_for_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        t_system_block_number_inc(1);
+
+        let owner = 10;
+        let caller = 20;
+        t_issue_pcx(owner, 200);
+        assert_ok!(t_bond(owner, 4, 200));
+        assert_ok!(t_unbond(owner, 4, 100));
+        assert_ok!(t_unbond(owner, 4, 100));
+        let locked_until = DEFAULT_BONDING_DURATION + 2;
+
+        let params = UnlockForParams {
+            grace_period: 100,
+            incentive_bps: 100,
+        };
+        assert_noop!(
+            XStaking::set_unlock_for_params(
+                Origin::root(),
+                Some(UnlockForParams {
+                    incentive_bps: MAX_UNLOCK_INCENTIVE_BPS + 1,
+                    ..params
+                })
+            ),
+            Error::<Test>::InvalidUnlockForParams
+        );
+
+        t_system_block_number_inc(locked_until);
+        assert_noop!(
+            XStaking::unlock_unbonded_withdrawal_for(Origin::signed(caller), owner, 4, 0),
+            Error::<Test>::UnlockForDisabled
+        );
+
+        assert_ok!(XStaking::set_unlock_for_params(
+            Origin::root(),
+            Some(params)
+        ));
+        System::assert_last_event(crate::mock::Event::XStaking(
+            crate::Event::UnlockForParamsUpdated(Some(params)),
+        ));
+
+        // Within the grace period only the owner can unlock.
+        assert_noop!(
+            XStaking::unlock_unbonded_withdrawal_for(Origin::signed(caller), owner, 4, 0),
+            Error::<Test>::UnbondedWithdrawalNotYetDue
+        );
+        assert_ok!(XStaking::unlock_unbonded_withdrawal_for(
+            Origin::signed(owner),
+            owner,
+            4,
+            0
+        ));
+        System::assert_last_event(crate::mock::Event::XStaking(crate::Event::Withdrawn(
+            owner, 100,
+        )));
+        assert_eq!(Balances::usable_balance(&owner), 100);
+
+        t_system_block_number_inc(100);
+        assert_ok!(XStaking::unlock_unbonded_withdrawal_for(
+            Origin::signed(caller),
+            owner,
+            4,
+            0
+        ));
+        System::assert_last_event(crate::mock::Event::XStaking(
+            crate::Event::UnbondedWithdrawalUnlockedFor(caller, owner, 100, 1),
+        ));
+        assert_eq!(Balances::usable_balance(&owner), 200 - 1);
+        assert_eq!(Balances::free_balance(&caller), 1);
+        assert_bonded_withdrawal_locks(owner, 0);
+    });
+}
+
+#[test]
+fn era_snapshot_should_prove_nominations() {
+    use sp_runtime::traits::BlakeTwo256;
+
+    ExtBuilder::default().build_and_execute(|| {
+        for (nominator, value) in [(11, 10), (12, 20), (13, 30)] {
+            t_issue_pcx(nominator, 100);
+            assert_ok!(t_bond(nominator, 4, value));
+        }
+        t_system_block_number_inc(5);
+
+        XStaking::note_era_snapshot(7, &[1, 2, 3, 4]);
+        assert!(XStaking::era_snapshot_root(7).is_empty());
+        assert_eq!(XStaking::nomination_proof(7, &4, &11), None);
+        XStaking::progress_era_snapshot(u32::max_value());
+        assert_eq!(XStaking::pending_era_snapshot(), None);
+
+        let roots = XStaking::era_snapshot_root(7);
+        assert_eq!(roots.len(), 4);
+        let (_, total, root) = roots.into_iter().find(|(v, _, _)| *v == 4).unwrap();
+        assert_eq!(
+            total,
+            <XStaking as ComputeMiningWeight<AccountId, BlockNumber>>::settle_claimee_weight(
+                &4,
+                System::block_number()
+            )
+        );
+
+        // The validator itself and the three nominators.
+        for nominator in [4, 11, 12, 13] {
+            let proof = XStaking::nomination_proof(7, &4, &nominator).unwrap();
+            assert_eq!(proof.leaf_count, 4);
+            assert!(verify_nomination_proof::<_, BlakeTwo256>(
+                root, &nominator, &proof
+            ));
+
+            let mut forged = proof.clone();
+            forged.weight += 1;
+            assert!(!verify_nomination_proof::<_, BlakeTwo256>(
+                root, &nominator, &forged
+            ));
+            assert!(!verify_nomination_proof::<_, BlakeTwo256>(root, &1, &proof));
+        }
+        assert_eq!(XStaking::nomination_proof(7, &4, &1), None);
+        assert_eq!(XStaking::nomination_proof(7, &3, &11), None);
+    });
+}
+
+#[test]
+fn era_snapshot_should_settle_weights_at_era_rotation() {
+    ExtBuilder::default().build_and_execute(|| {
+        for (nominator, value) in [(11, 10), (12, 20), (13, 30)] {
+            t_issue_pcx(nominator, 100);
+            assert_ok!(t_bond(nominator, 4, value));
+        }
+        t_system_block_number_inc(5);
+
+        let taken_at = System::block_number();
+        let expected =
+            <XStaking as ComputeMiningWeight<AccountId, BlockNumber>>::settle_claimee_weight(
+                &4, taken_at,
+            );
+        XStaking::note_era_snapshot(7, &[4]);
+
+        // Only a part of the nominations is checked per block.
+        t_system_block_number_inc(1);
+        XStaking::progress_era_snapshot(1);
+        assert!(XStaking::pending_era_snapshot().is_some());
+
+        // The nominations updated before being checked are recorded as of the rotation.
+        t_system_block_number_inc(3);
+        for nominator in [11, 12, 13] {
+            assert_ok!(t_bond(nominator, 4, 10));
+        }
+        t_system_block_number_inc(1);
+        XStaking::progress_era_snapshot(u32::max_value());
+        assert_eq!(XStaking::pending_era_snapshot(), None);
+
+        let roots = XStaking::era_snapshot_root(7);
+        assert_eq!(roots.len(), 1);
+        assert_eq!(roots[0].1, expected);
+        assert_eq!(XStaking::era_snapshot_leaves(7, 4).len(), 4);
+    });
+}
+
+#[test]
+fn era_snapshot_should_be_taken_at_era_rotation_and_pruned() {
+    ExtBuilder::default().build_and_execute(|| {
+        t_start_session(3);
+        let era = XStaking::current_era().unwrap();
+        assert!(era > 0);
+        XStaking::progress_era_snapshot(u32::max_value());
+        assert!(XStaking::era_snapshot_root(era)
+            .iter()
+            .any(|(validator, _, _)| *validator == 4));
+
+        XStaking::note_era_snapshot(100, &[4]);
+        XStaking::note_era_snapshot(100 + ERA_SNAPSHOT_HISTORY_DEPTH - 1, &[4]);
+        assert!(!XStaking::era_snapshot_root(100).is_empty());
+        XStaking::note_era_snapshot(100 + ERA_SNAPSHOT_HISTORY_DEPTH, &[4]);
+        XStaking::progress_era_snapshot(u32::max_value());
+        assert!(XStaking::era_snapshot_root(100).is_empty());
+        assert!(XStaking::era_snapshot_leaves(100, 4).is_empty());
+        assert!(!XStaking::era_snapshot_root(100 + ERA_SNAPSHOT_HISTORY_DEPTH).is_empty());
+    });
+}
+
+#[test]
+fn claim_to_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        let t_1 = 1111;
+        let dest = 2222;
+        t_issue_pcx(t_1, 100);
+        assert_ok!(t_bond(t_1, 1, 10));
+
+        t_start_session(1);
+        assert_noop!(
+            XStaking::claim_to(Origin::signed(t_1), 1, XStaking::reward_pot_for(&2)),
+            Error::<Test>::SystemAccountDestination
+        );
+        assert_noop!(
+            XStaking::claim_to(Origin::signed(t_1), 1, TREASURY_ACCOUNT),
+            Error::<Test>::SystemAccountDestination
+        );
+
+        t_start_session(2);
+        let dividend = XStaking::compute_dividend_at(&t_1, &1, System::block_number()).unwrap();
+        assert!(dividend > 0);
+        assert_ok!(XStaking::claim_to(Origin::signed(t_1), 1, dest));
+        System::assert_last_event(crate::mock::Event::XStaking(crate::Event::ClaimedTo(
+            t_1, dest, 1, dividend,
+        )));
+
+        // The dividend is paid to dest, the vote weight of the claimer is settled.
+        assert_eq!(XStaking::free_balance(&dest), dividend);
+        assert_eq!(XStaking::free_balance(&t_1), 100);
+        let ledger = <Nominations<Test>>::get(t_1, 1);
+        assert_eq!(ledger.last_vote_weight, 0);
+        assert_eq!(ledger.last_vote_weight_update, System::block_number());
+        assert_eq!(
+            XStaking::compute_dividend_at(&t_1, &1, System::block_number()).unwrap(),
+            0
+        );
+    });
+}
+
+#[test]
+fn nomination_records_should_accrue_vote_weight() {
+    ExtBuilder::default().build_and_execute(|| {
+        let t_1 = 1111;
+        t_issue_pcx(t_1, 100);
+        assert_ok!(t_bond(t_1, 1, 10));
+
+        t_start_session(1);
+        t_start_session(2);
+        let now = System::block_number();
+        let (_, source_weight, target_weight, _) =
+            XStaking::calculate_dividend_on_claim(&t_1, &1, now).unwrap();
+        assert!(source_weight > 0);
+
+        // The stored ledger is unchanged since bonding, the accrued weights are computed on the fly.
+        let records = XStaking::nomination_records_detailed(t_1);
+        assert_eq!(records.len(), 1);
+        let (validator, ledger, vote_weight, total_vote_weight) = records[0].clone();
+        assert_eq!(validator, 1);
+        assert_eq!(ledger.last_vote_weight, 0);
+        assert_eq!(vote_weight, source_weight);
+        assert_eq!(total_vote_weight, target_weight);
+
+        assert_ok!(XStaking::claim(Origin::signed(t_1), 1));
+        let (_, _, vote_weight, total_vote_weight) =
+            XStaking::nomination_records_detailed(t_1)[0].clone();
+        assert_eq!(vote_weight, 0);
+        assert_eq!(total_vote_weight, target_weight - source_weight);
+    });
+}
+
+#[test]
+fn bond_should_exclude_held_balance() {
+    ExtBuilder::default().build_and_execute(|| {
+        let t_1 = 1111;
+        t_issue_pcx(t_1, 100);
+
+        // Another module holds 60 of the free balance in the same block, which used to be
+        // bondable since the free balance is unchanged until the hold is applied.
+        MockNativeHolds::set(t_1, 60);
+        assert_eq!(XStaking::free_balance(&t_1), 100);
+        assert_noop!(t_bond(t_1, 1, 50), Error::<Test>::InsufficientBalance);
+        assert_ok!(t_bond(t_1, 1, 40));
+        assert_noop!(t_bond(t_1, 1, 1), Error::<Test>::InsufficientBalance);
+
+        MockNativeHolds::set(t_1, 0);
+        assert_ok!(t_bond(t_1, 1, 60));
+    });
+}
+
+#[test]
+fn bonded_balance_should_be_held() {
+    ExtBuilder::default().build_and_execute(|| {
+        let t_1 = 1111;
+        t_issue_pcx(t_1, 100);
+        assert_ok!(t_bond(t_1, 1, 60));
+        assert_eq!(MockNativeHolds::staking_held(&t_1), 60);
+
+        // The unbonded balance is held until it's unlocked.
+        assert_ok!(t_unbond(t_1, 1, 20));
+        assert_eq!(MockNativeHolds::staking_held(&t_1), 60);
+        assert_ok!(XStaking::force_unlock_bonded_withdrawal(
+            Origin::root(),
+            t_1
+        ));
+        assert_eq!(MockNativeHolds::staking_held(&t_1), 40);
+
+        // The bonded balances before the holds are held by the migration.
+        <MockNativeHolds as NativeHolds<_, _>>::release_native(&t_1, 40);
+        StorageVersion::new(1).put::<XStaking>();
+        crate::migrations::migrate::<Test>();
+        assert_eq!(MockNativeHolds::staking_held(&t_1), 40);
+    });
+}
+
+#[test]
+fn pallet_docs_should_cover_all_variants() {
+    use xpallet_support::docs::undocumented_variants;
+
+    assert_eq!(crate::PALLET_DOCS.name, "XStaking");
+    let (missing, unknown) = undocumented_variants::<crate::Event<Test>>(crate::PALLET_DOCS.events);
+    assert!(missing.is_empty(), "undocumented events: {:?}", missing);
+    assert!(unknown.is_empty(), "unknown events: {:?}", unknown);
+    let (missing, unknown) = undocumented_variants::<crate::Error<Test>>(crate::PALLET_DOCS.errors);
+    assert!(missing.is_empty(), "undocumented errors: {:?}", missing);
+    assert!(unknown.is_empty(), "unknown errors: {:?}", unknown);
+}
+
+/// Returns true if the validators of a new era were elected in the last block.
+fn t_elected(before: &BTreeMap<Vec<u8>, Vec<u8>>, after: &BTreeMap<Vec<u8>, Vec<u8>>) -> bool {
+    let key = PlannedValidators::<Test>::hashed_key().to_vec();
+    after.contains_key(&key) && before.get(&key) != after.get(&key)
+}
+
+fn t_sorted_session_validators() -> Vec<AccountId> {
+    let mut validators = Session::validators();
+    validators.sort_unstable();
+    validators
+}
+
+#[test]
+fn election_should_not_coincide_with_reward_distribution() {
+    // Session length is 5 and sessions per era is 3, sessions rotate at block 5, 10, 15...
+    ExtBuilder::default()
+        .session_length(5)
+        .build_and_execute(|| {
+            XStaking::mint(&888, (FIXED_TOTAL / 2) as u128);
+            t_make_a_validator_candidate(5, 500);
+            t_make_a_validator_candidate(6, 600);
+
+            let mut reward_blocks = Vec::new();
+            let mut election_blocks = Vec::new();
+            for block in 2..=31 {
+                let before = t_storage_snapshot();
+                t_run_to_block(block);
+                let after = t_storage_snapshot();
+                let written = t_written_keys(&before, &after);
+
+                let rewarded = (1..=6).any(|validator| {
+                    written.contains(&UnsettledRewards::<Test>::hashed_key_for(validator))
+                });
+                let elected = t_elected(&before, &after);
+                assert!(
+                    !(rewarded && elected),
+                    "Block {} distributes the reward and elects the validators",
+                    block
+                );
+                if rewarded {
+                    reward_blocks.push(block);
+                }
+                if elected {
+                    election_blocks.push(block);
+                }
+            }
+
+            assert_eq!(reward_blocks, vec![5, 10, 15, 20, 25, 30]);
+            // Era 1 is elected in the block after the rotation of session 1, era 2 after
+            // the rotation of session 4.
+            assert_eq!(election_blocks, vec![6, 21]);
+        });
+}
+
+#[test]
+fn deferred_election_should_take_effect_at_the_planned_session() {
+    ExtBuilder::default()
+        .session_length(5)
+        .build_and_execute(|| {
+            XStaking::mint(&888, (FIXED_TOTAL / 2) as u128);
+            t_make_a_validator_candidate(5, 500);
+            t_make_a_validator_candidate(6, 600);
+
+            // Era 1 is due at session 3, elected at block 6.
+            t_run_to_block(5);
+            assert_eq!(XStaking::pending_election(), Some(3));
+            assert_eq!(XStaking::planned_validators(), None);
+            t_run_to_block(6);
+            assert_eq!(XStaking::pending_election(), None);
+            assert_eq!(
+                XStaking::planned_validators(),
+                Some((3, Some(vec![6, 5, 4, 3, 2, 1])))
+            );
+
+            // Planned by the rotation at block 10.
+            t_run_to_block(10);
+            assert_eq!(XStaking::planned_validators(), None);
+            assert_eq!(XStaking::current_era(), Some(1));
+            assert_eq!(XStaking::eras_start_session_index(1), Some(3));
+            assert_eq!(XStaking::active_era().unwrap().index, 0);
+
+            // Takes effect at session 3.
+            t_run_to_block(14);
+            assert_eq!(Session::current_index(), 2);
+            assert_eq!(t_sorted_session_validators(), vec![1, 2, 3, 4]);
+            t_run_to_block(15);
+            assert_eq!(Session::current_index(), 3);
+            assert_eq!(XStaking::active_era().unwrap().index, 1);
+            assert_eq!(t_sorted_session_validators(), vec![1, 2, 3, 4, 5, 6]);
+        });
+}
+
+#[test]
+fn forced_era_should_be_elected_in_the_next_block() {
+    ExtBuilder::default()
+        .session_length(5)
+        .build_and_execute(|| {
+            XStaking::mint(&888, (FIXED_TOTAL / 2) as u128);
+
+            t_run_to_block(16);
+            assert_eq!(XStaking::current_era(), Some(1));
+            assert_eq!(XStaking::eras_start_session_index(1), Some(3));
+
+            // Era 2 is due at session 6 regularly, the forced one starts at session 5.
+            t_make_a_validator_candidate(5, 500);
+            ForceEra::<Test>::put(Forcing::ForceNew);
+            let before = t_storage_snapshot();
+            t_run_to_block(17);
+            assert!(t_elected(&before, &t_storage_snapshot()));
+            assert_eq!(
+                XStaking::planned_validators(),
+                Some((5, Some(vec![5, 4, 3, 2, 1])))
+            );
+
+            t_run_to_block(20);
+            assert_eq!(XStaking::current_era(), Some(2));
+            assert_eq!(XStaking::eras_start_session_index(2), Some(5));
+            assert_eq!(XStaking::force_era(), Forcing::NotForcing);
+
+            t_run_to_block(25);
+            assert_eq!(Session::current_index(), 5);
+            assert_eq!(XStaking::active_era().unwrap().index, 2);
+            assert_eq!(t_sorted_session_validators(), vec![1, 2, 3, 4, 5]);
+        });
+}
+
+#[test]
+fn stale_planned_validators_should_be_re_elected() {
+    ExtBuilder::default()
+        .session_length(5)
+        .build_and_execute(|| {
+            XStaking::mint(&888, (FIXED_TOTAL / 2) as u128);
+            t_make_a_validator_candidate(5, 500);
+            t_make_a_validator_candidate(6, 600);
+
+            t_run_to_block(6);
+            assert_eq!(
+                XStaking::planned_validators(),
+                Some((3, Some(vec![6, 5, 4, 3, 2, 1])))
+            );
+
+            // Validator 6 is chilled in the final session of era 0, after the election.
+            t_run_to_block(8);
+            assert_ok!(XStaking::chill(Origin::signed(6)));
+
+            t_run_to_block(10);
+            assert_eq!(XStaking::planned_validators(), None);
+            assert_eq!(XStaking::current_era(), Some(1));
+            assert_eq!(XStaking::eras_start_session_index(1), Some(3));
+
+            t_run_to_block(15);
+            assert_eq!(XStaking::active_era().unwrap().index, 1);
+            assert_eq!(t_sorted_session_validators(), vec![1, 2, 3, 4, 5]);
+        });
+}
+
+fn t_set_guardians(validator: AccountId, guardians: Vec<AccountId>, threshold: u32) {
+    assert_ok!(XStaking::set_guardians(
+        Origin::signed(validator),
+        guardians,
+        threshold,
+        10
+    ));
+}
+
+#[test]
+fn guardian_recovery_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        let (g1, g2, g3, new_account) = (101, 102, 103, 200);
+        t_set_guardians(1, vec![g3, g1, g2], 2);
+        assert_eq!(
+            XStaking::guardians_of(1).unwrap().guardians,
+            vec![g1, g2, g3]
+        );
+
+        let balance_before = XStaking::free_balance(&1);
+        assert_ok!(XStaking::initiate_recovery(
+            Origin::signed(g1),
+            1,
+            new_account
+        ));
+        System::assert_last_event(crate::mock::Event::XStaking(
+            crate::Event::RecoveryInitiated(1, new_account, g1),
+        ));
+        // a recovery is already in progress
+        assert_noop!(
+            XStaking::initiate_recovery(Origin::signed(g2), 1, 201),
+            Error::<Test>::RecoveryInProgress
+        );
+        assert_ok!(XStaking::approve_recovery(Origin::signed(g2), 1));
+        System::assert_last_event(crate::mock::Event::XStaking(
+            crate::Event::RecoveryApproved(1, g2, 2),
+        ));
+        assert_noop!(
+            XStaking::approve_recovery(Origin::signed(g2), 1),
+            Error::<Test>::AlreadyApprovedRecovery
+        );
+
+        assert_noop!(
+            XStaking::finalize_recovery(Origin::signed(new_account), 1),
+            Error::<Test>::RecoveryDelayNotElapsed
+        );
+        t_system_block_number_inc(10);
+        assert_ok!(XStaking::finalize_recovery(Origin::signed(new_account), 1));
+        System::assert_last_event(crate::mock::Event::XStaking(
+            crate::Event::RecoveryFinalized(1, new_account),
+        ));
+        assert_eq!(XStaking::active_recoveries(1), None);
+        assert_eq!(XStaking::validator_operator_of(1), Some(new_account));
+        assert_eq!(XStaking::operated_validator_of(new_account), Some(1));
+
+        // the new account operates the validator, the balances are not moved
+        assert_ok!(XStaking::set_metadata(
+            Origin::signed(new_account),
+            vec![(MetaKey::Website, b"https://chainx.org".to_vec())]
+        ));
+        assert_eq!(XStaking::validator_metadata_of(1).len(), 1);
+        assert_ok!(XStaking::chill(Origin::signed(new_account)));
+        assert!(XStaking::is_chilled(&1));
+        assert_ok!(XStaking::validate(Origin::signed(new_account)));
+        assert!(!XStaking::is_chilled(&1));
+        assert!(!XStaking::is_validator(&new_account));
+        assert_eq!(XStaking::free_balance(&1), balance_before);
+        assert_eq!(XStaking::free_balance(&new_account), 0);
+
+        // the operator re-points the session keys of the validator
+        let keys = SessionKeys {
+            other: UintAuthorityId(1001),
+        };
+        assert_noop!(
+            XStaking::set_operated_session_keys(Origin::signed(g1), keys.encode(), vec![]),
+            Error::<Test>::NotValidator
+        );
+        assert_noop!(
+            XStaking::set_operated_session_keys(Origin::signed(new_account), vec![1], vec![]),
+            Error::<Test>::InvalidSessionKeys
+        );
+        assert_ok!(XStaking::set_operated_session_keys(
+            Origin::signed(new_account),
+            keys.encode(),
+            vec![]
+        ));
+        assert_eq!(pallet_session::NextKeys::<Test>::get(1), Some(keys));
+        System::assert_last_event(crate::mock::Event::XStaking(
+            crate::Event::OperatedSessionKeysSet(1, new_account),
+        ));
+
+        // the operator can not take over another validator
+        t_set_guardians(2, vec![g1], 1);
+        assert_noop!(
+            XStaking::initiate_recovery(Origin::signed(g1), 2, new_account),
+            Error::<Test>::InvalidRecoveryAccount
+        );
+    });
+}
+
+#[test]
+fn owner_should_cancel_recovery() {
+    ExtBuilder::default().build_and_execute(|| {
+        let (g1, g2, new_account) = (101, 102, 200);
+        t_set_guardians(1, vec![g1, g2], 1);
+        assert_ok!(XStaking::initiate_recovery(
+            Origin::signed(g1),
+            1,
+            new_account
+        ));
+
+        // the guardians can not be changed during the recovery
+        assert_noop!(
+            XStaking::set_guardians(Origin::signed(1), vec![g1], 1, 10),
+            Error::<Test>::RecoveryInProgress
+        );
+        assert_noop!(
+            XStaking::cancel_recovery(Origin::signed(g1)),
+            Error::<Test>::NoActiveRecovery
+        );
+        assert_ok!(XStaking::cancel_recovery(Origin::signed(1)));
+        System::assert_last_event(crate::mock::Event::XStaking(
+            crate::Event::RecoveryCancelled(1, new_account),
+        ));
+
+        t_system_block_number_inc(10);
+        assert_noop!(
+            XStaking::finalize_recovery(Origin::signed(new_account), 1),
+            Error::<Test>::NoActiveRecovery
+        );
+        assert_eq!(XStaking::operated_validator_of(new_account), None);
+    });
+}
+
+#[test]
+fn recovery_should_need_enough_approvals() {
+    ExtBuilder::default().build_and_execute(|| {
+        let (g1, g2, g3, new_account) = (101, 102, 103, 200);
+        t_set_guardians(1, vec![g1, g2, g3], 3);
+        assert_ok!(XStaking::initiate_recovery(
+            Origin::signed(g1),
+            1,
+            new_account
+        ));
+        assert_ok!(XStaking::approve_recovery(Origin::signed(g2), 1));
+
+        t_system_block_number_inc(10);
+        assert_noop!(
+            XStaking::finalize_recovery(Origin::signed(new_account), 1),
+            Error::<Test>::InsufficientRecoveryApprovals
+        );
+        assert_ok!(XStaking::approve_recovery(Origin::signed(g3), 1));
+        assert_ok!(XStaking::finalize_recovery(Origin::signed(new_account), 1));
+    });
+}
+
+#[test]
+fn non_guardian_should_be_rejected() {
+    ExtBuilder::default().build_and_execute(|| {
+        let (g1, g2, stranger, new_account) = (101, 102, 300, 200);
+
+        // the guardians of a non-validator, or an invalid set of guardians
+        assert_noop!(
+            XStaking::set_guardians(Origin::signed(stranger), vec![g1], 1, 10),
+            Error::<Test>::NotValidator
+        );
+        for (guardians, threshold) in [
+            (vec![g1, g1], 1),
+            (vec![g1, 1], 1),
+            (vec![g1, g2], 0),
+            (vec![g1, g2], 3),
+            (
+                (0..=MAXIMUM_GUARDIANS as u64).map(|i| 1000 + i).collect(),
+                1,
+            ),
+        ] {
+            assert_noop!(
+                XStaking::set_guardians(Origin::signed(1), guardians, threshold, 10),
+                Error::<Test>::InvalidGuardians
+            );
+        }
+
+        assert_noop!(
+            XStaking::initiate_recovery(Origin::signed(g1), 1, new_account),
+            Error::<Test>::NotGuardian
+        );
+        t_set_guardians(1, vec![g1, g2], 2);
+        assert_noop!(
+            XStaking::initiate_recovery(Origin::signed(stranger), 1, new_account),
+            Error::<Test>::NotGuardian
+        );
+        assert_noop!(
+            XStaking::initiate_recovery(Origin::signed(g1), 1, 2),
+            Error::<Test>::InvalidRecoveryAccount
+        );
+        assert_ok!(XStaking::initiate_recovery(
+            Origin::signed(g1),
+            1,
+            new_account
+        ));
+        assert_noop!(
+            XStaking::approve_recovery(Origin::signed(stranger), 1),
+            Error::<Test>::NotGuardian
+        );
+
+        // clearing the guardians disables the recovery
+        assert_ok!(XStaking::cancel_recovery(Origin::signed(1)));
+        t_set_guardians(1, vec![], 0);
+        assert_eq!(XStaking::guardians_of(1), None);
+        assert_noop!(
+            XStaking::initiate_recovery(Origin::signed(g1), 1, new_account),
+            Error::<Test>::NotGuardian
+        );
+    });
+}
+
+#[test]
+fn supply_totals_should_track_locks_and_reward_pots() {
+    ExtBuilder::default().build_and_execute(|| {
+        let locked_of = |ty| {
+            XStaking::total_locked()
+                .get(&ty)
+                .copied()
+                .unwrap_or_default()
+        };
+        let total_nomination = XStaking::validator_set()
+            .map(|validator| XStaking::validator_ledgers(validator).total_nomination)
+            .sum::<Balance>();
+        assert_eq!(locked_of(LockedType::Bonded), total_nomination);
+        assert_eq!(locked_of(LockedType::BondedWithdrawal), 0);
+
+        t_system_block_number_inc(1);
+        assert_ok!(t_bond(1, 2, 10));
+        t_system_block_number_inc(1);
+        assert_ok!(t_unbond(1, 2, 5));
+        assert_eq!(locked_of(LockedType::Bonded), total_nomination + 5);
+        assert_eq!(locked_of(LockedType::BondedWithdrawal), 5);
+
+        let before = XStaking::total_reward_pot_balance();
+        t_issue_pcx(XStaking::reward_pot_for(&2), 30);
+        t_issue_pcx(XStaking::reward_pot_for(&3), 70);
+        assert_eq!(XStaking::total_reward_pot_balance(), before + 100);
+    });
+}
+
+#[test]
+fn nomination_events_should_be_indexed_by_validator_topic() {
+    ExtBuilder::default().build_and_execute(|| {
+        let t_1 = 1111;
+        t_issue_pcx(t_1, 100);
+        XStaking::mint(&888, (FIXED_TOTAL / 2) as u128);
+        assert_ok!(t_bond(t_1, 1, 20));
+        t_start_session(2);
+        assert_ok!(XStaking::claim(Origin::signed(t_1), 1));
+
+        let topic =
+            chainx_primitives::account_event_topic::<sp_runtime::traits::BlakeTwo256, _>(&1u64);
+        let indexed = System::events()
+            .into_iter()
+            .filter(|record| match &record.event {
+                crate::mock::Event::XStaking(crate::Event::Bonded(who, ..))
+                | crate::mock::Event::XStaking(crate::Event::Claimed(who, ..)) => *who == t_1,
+                _ => false,
+            })
+            .map(|record| record.topics)
+            .collect::<Vec<_>>();
+        assert_eq!(indexed, vec![vec![topic]; 2]);
+    });
+}
+
+/// A minimal claimer/claimee ledger implementing the vote weight settlement out of any pallet.
+struct ToySettlement;
+
+#[derive(Debug, PartialEq)]
+struct ToyZeroWeight;
+
+impl From<ZeroMiningWeightError> for ToyZeroWeight {
+    fn from(_: ZeroMiningWeightError) -> Self {
+        Self
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+struct ToyLedger {
+    amount: Balance,
+    weight: WeightType,
+    updated_at: BlockNumber,
+}
+
+std::thread_local! {
+    static TOY_CLAIMERS: std::cell::RefCell<BTreeMap<(AccountId, AccountId), ToyLedger>> =
+        Default::default();
+    static TOY_CLAIMEES: std::cell::RefCell<BTreeMap<AccountId, ToyLedger>> = Default::default();
+    static TOY_JACKPOT: std::cell::RefCell<Balance> = std::cell::RefCell::new(0);
+}
+
+fn toy_weight_factors(ledger: ToyLedger, current_block: BlockNumber) -> WeightFactors {
+    (
+        ledger.weight,
+        ledger.amount,
+        (current_block - ledger.updated_at).into(),
+    )
+}
+
+impl ComputeMiningWeight<AccountId, BlockNumber> for ToySettlement {
+    type Claimee = AccountId;
+    type Error = ToyZeroWeight;
+
+    fn claimer_weight_factors(
+        who: &AccountId,
+        target: &AccountId,
+        current_block: BlockNumber,
+    ) -> WeightFactors {
+        let ledger = TOY_CLAIMERS.with(|l| l.borrow().get(&(*who, *target)).copied());
+        toy_weight_factors(ledger.unwrap_or_default(), current_block)
+    }
+
+    fn claimee_weight_factors(target: &AccountId, current_block: BlockNumber) -> WeightFactors {
+        let ledger = TOY_CLAIMEES.with(|l| l.borrow().get(target).copied());
+        toy_weight_factors(ledger.unwrap_or_default(), current_block)
+    }
+}
+
+impl VoteWeightSettlement<AccountId, BlockNumber> for ToySettlement {
+    type Balance = Balance;
+
+    fn jackpot_of(_: &AccountId) -> (AccountId, Balance) {
+        (0, TOY_JACKPOT.with(|j| *j.borrow()))
+    }
+
+    fn set_claimer_weight(
+        claimer: &AccountId,
+        claimee: &AccountId,
+        new_weight: WeightType,
+        current_block: BlockNumber,
+        delta: Delta<Balance>,
+    ) {
+        TOY_CLAIMERS.with(|l| {
+            let mut ledgers = l.borrow_mut();
+            let ledger = ledgers.entry((*claimer, *claimee)).or_default();
+            ledger.amount = delta.calculate(ledger.amount);
+            ledger.weight = new_weight;
+            ledger.updated_at = current_block;
+        });
+    }
+
+    fn set_claimee_weight(
+        claimee: &AccountId,
+        new_weight: WeightType,
+        current_block: BlockNumber,
+        delta: Delta<Balance>,
+    ) {
+        TOY_CLAIMEES.with(|l| {
+            let mut ledgers = l.borrow_mut();
+            let ledger = ledgers.entry(*claimee).or_default();
+            ledger.amount = delta.calculate(ledger.amount);
+            ledger.weight = new_weight;
+            ledger.updated_at = current_block;
+        });
+    }
+}
+
+fn toy_claim(claimer: AccountId, current_block: BlockNumber) -> Result<Balance, ToyZeroWeight> {
+    ToySettlement::claim_against_jackpot(&claimer, &100, current_block, |_, dividend| {
+        TOY_JACKPOT.with(|j| *j.borrow_mut() -= dividend);
+        Ok(())
+    })
+}
+
+#[test]
+fn vote_weight_settlement_should_be_reusable_by_other_ledgers() {
+    ToySettlement::settle(&1, &100, 0, Delta::Add(10));
+    ToySettlement::settle(&2, &100, 0, Delta::Add(30));
+    TOY_JACKPOT.with(|j| *j.borrow_mut() = 1000);
+
+    assert_eq!(ToySettlement::accrue(&1, &100, 10), (100, 400));
+    assert_eq!(ToySettlement::accrue(&2, &100, 10), (300, 400));
+
+    // A failed payout leaves the weights untouched.
+    assert_eq!(
+        ToySettlement::claim_against_jackpot(&1, &100, 10, |_, _| Err(ToyZeroWeight)),
+        Err(ToyZeroWeight)
+    );
+    assert_eq!(ToySettlement::accrue(&1, &100, 10), (100, 400));
+
+    assert_eq!(toy_claim(1, 10), Ok(250));
+    assert_eq!(ToySettlement::accrue(&1, &100, 10), (0, 300));
+    assert_eq!(toy_claim(1, 10), Err(ToyZeroWeight));
+
+    assert_eq!(toy_claim(2, 10), Ok(750));
+    assert_eq!(ToySettlement::accrue(&2, &100, 10), (0, 0));
+    assert_eq!(TOY_JACKPOT.with(|j| *j.borrow()), 0);
+
+    // The amounts keep accruing after the claims.
+    ToySettlement::settle_claimer(&1, &100, 20);
+    assert_eq!(ToySettlement::accrue(&1, &100, 20), (100, 400));
+}
+
+#[test]
+fn emergency_set_validators_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        t_start_session(1);
+        t_make_a_validator_candidate(5, 500);
+        t_make_a_validator_candidate(6, 600);
+
+        assert_ok!(XStaking::emergency_set_validators(
+            Origin::root(),
+            vec![(5, 1), (6, 2), (2, 0)]
+        ));
+        // No session is forced, the emergency set is planned at the next rotation.
+        assert_eq!(Session::current_index(), 1);
+        assert_eq!(XStaking::emergency_governance(), Some(3));
+        System::assert_has_event(crate::mock::Event::XStaking(
+            crate::Event::EmergencyValidatorsSet(vec![6, 5, 2], 3),
+        ));
+
+        t_start_session(2);
+        assert_ne!(Session::validators(), vec![6, 5, 2]);
+
+        t_start_session(3);
+        assert_eq!(Session::validators(), vec![6, 5, 2]);
+        assert_eq!(XStaking::emergency_governance(), Some(3));
+
+        // The normal era rotation goes on and clears the emergency governance state.
+        let era = XStaking::active_era().unwrap().index;
+        let mut session = 3;
+        while XStaking::emergency_governance().is_some() {
+            session += 1;
+            assert!(
+                session <= 10,
+                "the emergency governance state is never cleared"
+            );
+            t_start_session(session);
+        }
+        let new_era = XStaking::active_era().unwrap().index;
+        assert!(new_era > era);
+        System::assert_has_event(crate::mock::Event::XStaking(
+            crate::Event::EmergencyGovernanceCleared(new_era),
+        ));
+        let validators = Session::validators();
+        assert!(validators.contains(&1) && validators.contains(&4));
+    });
+}
+
+#[test]
+fn emergency_set_validators_should_reject_invalid_set() {
+    ExtBuilder::default().build_and_execute(|| {
+        t_start_session(1);
+        let validators = Session::validators();
+
+        assert_noop!(
+            XStaking::emergency_set_validators(Origin::signed(1), vec![(1, 0)]),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            XStaking::emergency_set_validators(Origin::root(), vec![]),
+            Error::<Test>::InvalidEmergencyValidators
+        );
+        assert_noop!(
+            XStaking::emergency_set_validators(Origin::root(), vec![(1, 0), (1, 1)]),
+            Error::<Test>::InvalidEmergencyValidators
+        );
+        // Account 5 is not a registered validator, the whole set is rejected.
+        assert_noop!(
+            XStaking::emergency_set_validators(Origin::root(), vec![(1, 0), (5, 1), (2, 2)]),
+            Error::<Test>::NotValidator
+        );
+
+        assert_eq!(Session::current_index(), 1);
+        assert_eq!(Session::validators(), validators);
+        assert_eq!(XStaking::emergency_governance(), None);
+    });
+}
+
+#[test]
+fn jackpot_outflow_should_only_be_allowed_by_jackpot_paths() {
+    ExtBuilder::default().build_and_execute(|| {
+        let t_1 = 1111;
+        t_issue_pcx(t_1, 100);
+        assert_ok!(t_bond(t_1, 1, 10));
+        t_start_session(1);
+        t_start_session(2);
+        t_settle_jackpots(&[1]);
+
+        let pot = XStaking::reward_pot_for(&1);
+        let pot_balance = Balances::free_balance(&pot);
+        assert!(pot_balance > 0);
+
+        // Any module moving the balance out of the reward pot on behalf of others is rejected.
+        assert_err!(
+            XStaking::move_from_jackpot(&pot, &t_1, 1, MoveCaller::User),
+            Error::<Test>::JackpotOutflowForbidden
+        );
+        System::assert_last_event(crate::mock::Event::XStaking(
+            crate::Event::JackpotOutflowRejected(pot, t_1, 1, MoveCaller::User),
+        ));
+        assert_eq!(Balances::free_balance(&pot), pot_balance);
+        assert_eq!(XStaking::cumulative_jackpot_outflow(), 0);
+
+        // The claim path still works.
+        let dividend = XStaking::compute_dividend_at(&t_1, &1, System::block_number()).unwrap();
+        assert!(dividend > 0);
+        assert_ok!(XStaking::claim(Origin::signed(t_1), 1));
+        assert_eq!(XStaking::free_balance(&t_1), 100 + dividend);
+        assert_eq!(XStaking::cumulative_jackpot_outflow(), dividend);
+
+        let accounting = XStaking::jackpot_accounting();
+        assert_eq!(
+            accounting.total_balance,
+            XStaking::total_reward_pot_balance()
+        );
+        assert_eq!(
+            accounting.cumulative_inflow - accounting.cumulative_outflow,
+            accounting.total_balance
+        );
+        assert!(accounting.balanced);
+    });
+}
+
+#[test]
+fn migration_should_seed_jackpot_inflow() {
+    ExtBuilder::default().build_and_execute(|| {
+        t_start_session(1);
+        t_start_session(2);
+        t_settle_jackpots(&[1]);
+        let held = XStaking::total_reward_pot_balance();
+        assert!(held > 0);
+
+        // The reward pots were funded before the accounting was introduced.
+        CumulativeJackpotInflow::<Test>::kill();
+        assert!(!XStaking::jackpot_accounting().balanced);
+        StorageVersion::new(2).put::<XStaking>();
+
+        crate::migrations::migrate::<Test>();
+        assert_eq!(XStaking::cumulative_jackpot_inflow(), held);
+        assert!(XStaking::jackpot_accounting().balanced);
+        assert_eq!(
+            XStaking::on_chain_storage_version(),
+            crate::migrations::STORAGE_VERSION
+        );
+    });
+}
+
+fn force_set_lock_call(n: u64) -> Call {
+    Call::XStaking(crate::Call::force_set_lock {
+        new_locks: (0..n).map(|who| (who, 10)).collect(),
+    })
+}
+
+fn check_block_weight(call: &Call, info: &DispatchInfo) -> Result<(), TransactionValidityError> {
+    CheckWeight::<Test>::new().pre_dispatch(&1, call, info, 0)
+}
+
+#[test]
+fn heavy_calls_should_exhaust_the_block_weight() {
+    ExtBuilder::default().build_and_execute(|| {
+        let per_lock = force_set_lock_call(1).get_dispatch_info().weight
+            - force_set_lock_call(0).get_dispatch_info().weight;
+        assert!(per_lock > 0);
+
+        // Size the heavy call to 30% of the room left, so that 3 of them fit in the block.
+        let max_total = <Test as frame_system::Config>::BlockWeights::get()
+            .get(DispatchClass::Normal)
+            .max_total
+            .unwrap();
+        let room = max_total - System::block_weight().get(DispatchClass::Normal);
+        let heavy = force_set_lock_call(room * 3 / 10 / per_lock);
+        let heavy_info = heavy.get_dispatch_info();
+        let light = force_set_lock_call(1);
+        let light_info = light.get_dispatch_info();
+
+        let mut fitted = 0;
+        while check_block_weight(&heavy, &heavy_info).is_ok() {
+            fitted += 1;
+        }
+        assert_eq!(fitted, 3);
+        assert_eq!(
+            check_block_weight(&heavy, &heavy_info),
+            Err(InvalidTransaction::ExhaustsResources.into())
+        );
+
+        // The lighter calls still fit in the rest of the block.
+        assert_ok!(check_block_weight(&light, &light_info));
+    });
+}
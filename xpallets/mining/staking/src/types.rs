@@ -15,9 +15,14 @@ use sp_runtime::{
 use chainx_primitives::{AssetId, ReferralId};
 use frame_support::log::debug;
 use xp_mining_common::{RewardPotAccountFor, WeightType};
-use xp_mining_staking::MiningPower;
+use xp_mining_staking::{MiningPower, SessionIndex};
+use xp_runtime::Memo;
+use xpallet_assets::MoveCaller;
 
-use crate::{AssetMining, BalanceOf, Config, EraIndex, Event, Pallet};
+use crate::{
+    constants::MAXIMUM_METADATA_VALUE_LENGTH, AssetMining, BalanceOf, Config, EraIndex, Event,
+    Pallet,
+};
 
 pub type VoteWeight = WeightType;
 
@@ -111,6 +116,111 @@ pub struct ValidatorProfile<BlockNumber> {
     pub referral_id: ReferralId,
 }
 
+/// Key of the validator metadata entry.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum MetaKey {
+    Website,
+    Email,
+    Riot,
+    Twitter,
+    Telemetry,
+}
+
+impl MetaKey {
+    /// Returns true if the `value` is acceptable for this key.
+    pub fn is_valid_value(&self, value: &[u8]) -> bool {
+        if value.is_empty() || value.len() > MAXIMUM_METADATA_VALUE_LENGTH {
+            return false;
+        }
+        match self {
+            Self::Website => value.iter().all(|&c| is_url_char(c)),
+            Self::Email => value.iter().filter(|&&c| c == b'@').count() == 1,
+            Self::Riot | Self::Twitter | Self::Telemetry => true,
+        }
+    }
+}
+
+/// Kind of the mining claim that can be switched off globally.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum ClaimTypeKind {
+    /// Staking reward claims from the validator reward pots.
+    Intention,
+    /// Asset mining reward claims from the asset reward pots.
+    PseduIntention,
+}
+
+/// Naming rule violated by a validator referral identity.
+#[derive(Copy, Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum NameViolation {
+    /// Contains the characters other than the ASCII alphanumerics and `-_.`.
+    InvalidCharset,
+    /// Starts with one of the reserved prefixes.
+    ReservedPrefix,
+    /// Equals the referral identity of another validator case-insensitively.
+    CaseInsensitiveCollision,
+}
+
+/// Returns true if `c` is allowed in the referral identity of a new validator.
+pub fn is_referral_id_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || b"-_.".contains(&c)
+}
+
+/// Returns true if `c` is allowed in the URL, as defined in RFC 3986.
+fn is_url_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || b"-._~:/?#[]@!$&'()*+,;=%".contains(&c)
+}
+
+/// An entry of the validator metadata.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct MetadataEntry {
+    pub key: MetaKey,
+    #[cfg_attr(feature = "std", serde(with = "xp_rpc::serde_text"))]
+    pub value: Vec<u8>,
+}
+
+/// Kind of the bonding duration.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum BondingDurationKind {
+    /// `BondingDuration`, applied to the nominators.
+    Nominator,
+    /// `ValidatorBondingDuration`, applied to the self-bonded balances of validators.
+    Validator,
+}
+
+/// A change of the bonding duration made by root.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct BondingDurationChange<BlockNumber> {
+    /// Which bonding duration was changed.
+    pub kind: BondingDurationKind,
+    /// The bonding duration before the change.
+    pub old: BlockNumber,
+    /// The bonding duration after the change.
+    pub new: BlockNumber,
+    /// Block number at which the change was made.
+    pub at_block: BlockNumber,
+}
+
+/// An outgoing spend of the treasury account recorded by the council.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct TreasurySpend<Balance, BlockNumber> {
+    /// The amount spent from the treasury account.
+    pub amount: Balance,
+    /// What the spend is for.
+    pub purpose: Memo,
+    /// Block number at which the spend was recorded.
+    pub recorded_at: BlockNumber,
+}
+
 /// Information regarding the active era (era in used in session).
 #[derive(Encode, Decode, RuntimeDebug, TypeInfo)]
 pub struct ActiveEraInfo {
@@ -224,6 +334,81 @@ impl MiningDistribution {
     }
 }
 
+/// Block production of a validator in the active era.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ValidatorPerformance {
+    /// Blocks authored by the validator.
+    pub authored_blocks: u32,
+    /// Blocks the validator was expected to author, i.e., its even share of the blocks.
+    pub expected_blocks: u32,
+    /// Sessions in which the validator authored no block at all.
+    pub missed_sessions: u32,
+}
+
+/// Decay and recovery of the validator reputations, in basis points.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ReputationParams {
+    /// Reputation lost on every slash.
+    pub slash_step_bps: u32,
+    /// Reputation recovered after each era without any slash.
+    pub recovery_bps: u32,
+    /// The lowest reputation of a validator.
+    pub min_bps: u32,
+}
+
+/// Unlocking the unbonded balances of others.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct UnlockForParams<BlockNumber> {
+    /// Blocks after the unbonded balances are due before anyone else can unlock them.
+    pub grace_period: BlockNumber,
+    /// Part of the unlocked balances paid to the caller, in basis points.
+    pub incentive_bps: u32,
+}
+
+/// The guardians who can recover a lost validator account.
+#[derive(Clone, PartialEq, Eq, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct GuardianConfig<AccountId, BlockNumber> {
+    /// The guardian accounts, sorted and deduplicated.
+    pub guardians: Vec<AccountId>,
+    /// The number of the guardian approvals required for a recovery.
+    pub threshold: u32,
+    /// Blocks after the initiation before a recovery can be finalized.
+    pub delay: BlockNumber,
+}
+
+/// An ongoing recovery of a validator account.
+#[derive(Clone, PartialEq, Eq, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct RecoveryProcess<AccountId, BlockNumber> {
+    /// The account taking over the operation of the validator.
+    pub new_account: AccountId,
+    pub initiated_at: BlockNumber,
+    /// The guardians approving the recovery, including the initiator.
+    pub approvals: Vec<AccountId>,
+}
+
+/// Session rewards of a validator accrued but not yet minted.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct UnsettledReward<Balance> {
+    /// The part of the rewards paid to the validator itself.
+    pub validator: Balance,
+    /// The part of the rewards paid to the reward pot of the validator.
+    pub reward_pot: Balance,
+    /// The last session in which the rewards were accrued.
+    pub last_session: SessionIndex,
+}
+
 /// Result of performing a slash operation.
 #[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
 pub enum SlashOutcome<Balance> {
@@ -286,6 +471,37 @@ impl<T: Config> Slasher<T> {
 
     /// Actually slash the account being punished, all slashed balance will go to the treasury.
     fn do_slash(&self, reward_pot: &T::AccountId, value: BalanceOf<T>) -> DispatchResult {
-        Pallet::<T>::transfer(reward_pot, &self.0, value)
+        Pallet::<T>::move_from_jackpot(reward_pot, &self.0, value, MoveCaller::Jackpot)
     }
 }
+
+/// The merkle proof of a nomination in the era snapshot of a validator.
+///
+/// The leaves are `T::Hashing::hash_of(&(nominator, weight))` ordered by the nominator,
+/// each node is `T::Hashing::hash_of(&(left, right))` and the last node of a level with an
+/// odd number of nodes is promoted to the next level as is.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct NominationProof<VoteWeight, Hash> {
+    /// The vote weight of the nomination at the era boundary.
+    pub weight: VoteWeight,
+    /// The position of the leaf of the nomination.
+    pub leaf_index: u32,
+    /// The number of the nominations of the validator.
+    pub leaf_count: u32,
+    /// The sibling nodes from the leaf up to the root, the promoted levels are skipped.
+    pub siblings: Vec<Hash>,
+}
+
+/// The nomination snapshot of an era being taken across the blocks.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct EraSnapshotProgress<AccountId, BlockNumber> {
+    pub era: EraIndex,
+    /// The vote weights are measured at this block, i.e., the era boundary.
+    pub taken_at: BlockNumber,
+    /// The elected validators of the era.
+    pub validators: Vec<AccountId>,
+    /// The storage key of the last nomination checked.
+    pub cursor: Option<Vec<u8>>,
+}
@@ -0,0 +1,132 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! The vote weight settlement shared by the mining modules.
+//!
+//! Both Staking and Asset Mining split the reward pot(jackpot) of a claimee among its
+//! claimers by the proportion of the time-sensitive weight, [`VoteWeightSettlement`]
+//! captures the whole lifecycle so that any module with a claimer/claimee ledger can
+//! reuse it by providing the storage accessors only.
+//!
+//! Invariants kept by the implementations:
+//!
+//! 1. The weight of a claimee is never less than the weight of any of its claimers,
+//!    since the amount of a claimee is the sum of the amounts of its claimers.
+//! 2. The claimed dividend never exceeds the balance of the jackpot.
+//! 3. The weights never go negative, the claimed weight is deducted from the claimee
+//!    with saturation after being checked against invariant 1.
+
+use sp_arithmetic::traits::BaseArithmetic;
+
+use xp_mining_common::{compute_dividend, ComputeMiningWeight, Delta, WeightType};
+
+/// General logic for settling the vote weights and claiming against the jackpot.
+pub trait VoteWeightSettlement<AccountId, BlockNumber: Copy>:
+    ComputeMiningWeight<AccountId, BlockNumber>
+{
+    /// The balance type of the jackpot.
+    type Balance: BaseArithmetic + Copy;
+
+    /// Returns the jackpot account of `claimee` and its balance.
+    fn jackpot_of(claimee: &Self::Claimee) -> (AccountId, Self::Balance);
+
+    /// Writes the settled weight of `claimer` to `claimee`, applying `delta` to its amount.
+    fn set_claimer_weight(
+        claimer: &AccountId,
+        claimee: &Self::Claimee,
+        new_weight: WeightType,
+        current_block: BlockNumber,
+        delta: Delta<Self::Balance>,
+    );
+
+    /// Writes the settled weight of `claimee`, applying `delta` to its amount.
+    fn set_claimee_weight(
+        claimee: &Self::Claimee,
+        new_weight: WeightType,
+        current_block: BlockNumber,
+        delta: Delta<Self::Balance>,
+    );
+
+    /// Returns the latest weights of (claimer, claimee) at `current_block` without
+    /// touching the storage.
+    fn accrue(
+        claimer: &AccountId,
+        claimee: &Self::Claimee,
+        current_block: BlockNumber,
+    ) -> (WeightType, WeightType) {
+        (
+            Self::settle_claimer_weight(claimer, claimee, current_block),
+            Self::settle_claimee_weight(claimee, current_block),
+        )
+    }
+
+    /// Settles the weight of `claimer` only up to `current_block`.
+    ///
+    /// Used when the amount moves between the claimers of `claimee` while the amount
+    /// of `claimee` stays unchanged.
+    fn settle_claimer(claimer: &AccountId, claimee: &Self::Claimee, current_block: BlockNumber) {
+        let claimer_weight = Self::settle_claimer_weight(claimer, claimee, current_block);
+        Self::set_claimer_weight(claimer, claimee, claimer_weight, current_block, Delta::Zero);
+    }
+
+    /// Settles the weights of both `claimer` and `claimee` up to `current_block`.
+    ///
+    /// Must be called before the amount of `claimer` changes, `delta` is applied to
+    /// the amount of both sides if the implementation tracks it.
+    fn settle(
+        claimer: &AccountId,
+        claimee: &Self::Claimee,
+        current_block: BlockNumber,
+        delta: Delta<Self::Balance>,
+    ) {
+        let (claimer_weight, claimee_weight) = Self::accrue(claimer, claimee, current_block);
+        Self::set_claimer_weight(claimer, claimee, claimer_weight, current_block, delta);
+        Self::set_claimee_weight(claimee, claimee_weight, current_block, delta);
+    }
+
+    /// Claims the dividend of `claimer` from the jackpot of `claimee`.
+    ///
+    /// `payout` is given the jackpot account and the dividend, the weights are only
+    /// settled once it succeeds. The claimer weight is reset to zero and the claimee
+    /// weight is deducted by the claimed weight.
+    ///
+    /// Returns the dividend claimed.
+    fn claim_against_jackpot<F>(
+        claimer: &AccountId,
+        claimee: &Self::Claimee,
+        current_block: BlockNumber,
+        payout: F,
+    ) -> Result<Self::Balance, Self::Error>
+    where
+        F: FnOnce(&AccountId, Self::Balance) -> Result<(), Self::Error>,
+    {
+        let (jackpot, jackpot_balance) = Self::jackpot_of(claimee);
+
+        let (claimer_weight, claimee_weight) =
+            Self::settle_weight_on_claim(claimer, claimee, current_block)?;
+        debug_assert!(
+            claimer_weight <= claimee_weight,
+            "claimer weight {} exceeds the claimee weight {}",
+            claimer_weight,
+            claimee_weight
+        );
+
+        let dividend =
+            compute_dividend::<AccountId, _>(claimer_weight, claimee_weight, jackpot_balance);
+        debug_assert!(
+            dividend <= jackpot_balance,
+            "claimed dividend exceeds the jackpot"
+        );
+
+        payout(&jackpot, dividend)?;
+
+        Self::set_claimer_weight(claimer, claimee, 0, current_block, Delta::Zero);
+        Self::set_claimee_weight(
+            claimee,
+            claimee_weight.saturating_sub(claimer_weight),
+            current_block,
+            Delta::Zero,
+        );
+
+        Ok(dividend)
+    }
+}
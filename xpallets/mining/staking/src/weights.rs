@@ -45,6 +45,23 @@ pub trait WeightInfo {
     fn set_validator_bonding_duration() -> Weight;
     fn set_minimum_penalty() -> Weight;
     fn set_sessions_per_era() -> Weight;
+    fn set_metadata() -> Weight;
+    fn record_treasury_spend() -> Weight;
+    fn set_claim_switch() -> Weight;
+    fn set_performance_leniency() -> Weight;
+    fn set_reserved_name_prefixes() -> Weight;
+    fn audit_existing_names(n: u32) -> Weight;
+    fn settle_jackpot() -> Weight;
+    fn set_reputation_params() -> Weight;
+    fn unlock_unbonded_withdrawal_for() -> Weight;
+    fn set_unlock_for_params() -> Weight;
+    fn claim_to() -> Weight;
+    fn set_guardians(n: u32) -> Weight;
+    fn initiate_recovery() -> Weight;
+    fn approve_recovery() -> Weight;
+    fn cancel_recovery() -> Weight;
+    fn finalize_recovery() -> Weight;
+    fn emergency_set_validators(n: u32) -> Weight;
 }
 
 /// Weights for xpallet_mining_staking using the Substrate node and recommended hardware.
@@ -52,42 +69,42 @@ pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
     fn register() -> Weight {
         (1_897_927_000 as Weight)
-            .saturating_add(T::DbWeight::get().reads(149 as Weight))
-            .saturating_add(T::DbWeight::get().writes(7 as Weight))
+            .saturating_add(T::DbWeight::get().reads(151 as Weight))
+            .saturating_add(T::DbWeight::get().writes(9 as Weight))
     }
     fn bond() -> Weight {
         (111_353_000 as Weight)
-            .saturating_add(T::DbWeight::get().reads(9 as Weight))
-            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+            .saturating_add(T::DbWeight::get().reads(12 as Weight))
+            .saturating_add(T::DbWeight::get().writes(7 as Weight))
     }
     fn unbond() -> Weight {
         (88_401_000 as Weight)
-            .saturating_add(T::DbWeight::get().reads(6 as Weight))
-            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+            .saturating_add(T::DbWeight::get().reads(8 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
     }
     fn unlock_unbonded_withdrawal() -> Weight {
         (78_701_000 as Weight)
-            .saturating_add(T::DbWeight::get().reads(4 as Weight))
-            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
     }
     fn rebond() -> Weight {
         (111_922_000 as Weight)
-            .saturating_add(T::DbWeight::get().reads(10 as Weight))
-            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+            .saturating_add(T::DbWeight::get().reads(14 as Weight))
+            .saturating_add(T::DbWeight::get().writes(7 as Weight))
     }
     fn claim() -> Weight {
         (96_268_000 as Weight)
-            .saturating_add(T::DbWeight::get().reads(5 as Weight))
-            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+            .saturating_add(T::DbWeight::get().reads(7 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
     }
     fn chill() -> Weight {
         (1_141_804_000 as Weight)
-            .saturating_add(T::DbWeight::get().reads(95 as Weight))
+            .saturating_add(T::DbWeight::get().reads(96 as Weight))
             .saturating_add(T::DbWeight::get().writes(1 as Weight))
     }
     fn validate() -> Weight {
         (22_891_000 as Weight)
-            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
             .saturating_add(T::DbWeight::get().writes(1 as Weight))
     }
     fn set_validator_count() -> Weight {
@@ -97,10 +114,14 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
         (2_355_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
     }
     fn set_bonding_duration() -> Weight {
-        (2_287_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+        (2_287_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
     }
     fn set_validator_bonding_duration() -> Weight {
-        (2_400_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+        (2_400_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
     }
     fn set_minimum_penalty() -> Weight {
         (2_469_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
@@ -108,48 +129,162 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
     fn set_sessions_per_era() -> Weight {
         (2_275_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
     }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_metadata` benchmark.
+    fn set_metadata() -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `record_treasury_spend` benchmark.
+    fn record_treasury_spend() -> Weight {
+        (18_400_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_claim_switch` benchmark.
+    fn set_claim_switch() -> Weight {
+        (2_301_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_performance_leniency` benchmark.
+    fn set_performance_leniency() -> Weight {
+        (2_284_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_reserved_name_prefixes` benchmark.
+    fn set_reserved_name_prefixes() -> Weight {
+        (4_926_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `audit_existing_names` benchmark.
+    fn audit_existing_names(n: u32) -> Weight {
+        (8_512_000 as Weight)
+            .saturating_add((96_730_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `settle_jackpot` benchmark.
+    fn settle_jackpot() -> Weight {
+        (41_870_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_reputation_params` benchmark.
+    fn set_reputation_params() -> Weight {
+        (2_296_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `unlock_unbonded_withdrawal_for` benchmark.
+    fn unlock_unbonded_withdrawal_for() -> Weight {
+        (97_415_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(7 as Weight))
+            .saturating_add(T::DbWeight::get().writes(7 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_unlock_for_params` benchmark.
+    fn set_unlock_for_params() -> Weight {
+        (2_271_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `claim_to` benchmark.
+    fn claim_to() -> Weight {
+        (104_512_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(8 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_guardians` benchmark.
+    fn set_guardians(n: u32) -> Weight {
+        (21_307_000 as Weight)
+            .saturating_add((412_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `initiate_recovery` benchmark.
+    fn initiate_recovery() -> Weight {
+        (27_846_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `approve_recovery` benchmark.
+    fn approve_recovery() -> Weight {
+        (20_915_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `cancel_recovery` benchmark.
+    fn cancel_recovery() -> Weight {
+        (17_482_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `finalize_recovery` benchmark.
+    fn finalize_recovery() -> Weight {
+        (31_209_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `emergency_set_validators` benchmark.
+    fn emergency_set_validators(n: u32) -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add((5_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
     fn register() -> Weight {
         (1_897_927_000 as Weight)
-            .saturating_add(RocksDbWeight::get().reads(149 as Weight))
-            .saturating_add(RocksDbWeight::get().writes(7 as Weight))
+            .saturating_add(RocksDbWeight::get().reads(151 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(9 as Weight))
     }
     fn bond() -> Weight {
         (111_353_000 as Weight)
-            .saturating_add(RocksDbWeight::get().reads(9 as Weight))
-            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+            .saturating_add(RocksDbWeight::get().reads(12 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(7 as Weight))
     }
     fn unbond() -> Weight {
         (88_401_000 as Weight)
-            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
-            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+            .saturating_add(RocksDbWeight::get().reads(8 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
     }
     fn unlock_unbonded_withdrawal() -> Weight {
         (78_701_000 as Weight)
-            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
-            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
     }
     fn rebond() -> Weight {
         (111_922_000 as Weight)
-            .saturating_add(RocksDbWeight::get().reads(10 as Weight))
-            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+            .saturating_add(RocksDbWeight::get().reads(14 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(7 as Weight))
     }
     fn claim() -> Weight {
         (96_268_000 as Weight)
-            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
-            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+            .saturating_add(RocksDbWeight::get().reads(7 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
     }
     fn chill() -> Weight {
         (1_141_804_000 as Weight)
-            .saturating_add(RocksDbWeight::get().reads(95 as Weight))
+            .saturating_add(RocksDbWeight::get().reads(96 as Weight))
             .saturating_add(RocksDbWeight::get().writes(1 as Weight))
     }
     fn validate() -> Weight {
         (22_891_000 as Weight)
-            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
             .saturating_add(RocksDbWeight::get().writes(1 as Weight))
     }
     fn set_validator_count() -> Weight {
@@ -159,10 +294,14 @@ impl WeightInfo for () {
         (2_355_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
     }
     fn set_bonding_duration() -> Weight {
-        (2_287_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+        (2_287_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
     }
     fn set_validator_bonding_duration() -> Weight {
-        (2_400_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+        (2_400_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
     }
     fn set_minimum_penalty() -> Weight {
         (2_469_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
@@ -170,4 +309,118 @@ impl WeightInfo for () {
     fn set_sessions_per_era() -> Weight {
         (2_275_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
     }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_metadata` benchmark.
+    fn set_metadata() -> Weight {
+        (25_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `record_treasury_spend` benchmark.
+    fn record_treasury_spend() -> Weight {
+        (18_400_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_claim_switch` benchmark.
+    fn set_claim_switch() -> Weight {
+        (2_301_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_performance_leniency` benchmark.
+    fn set_performance_leniency() -> Weight {
+        (2_284_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_reserved_name_prefixes` benchmark.
+    fn set_reserved_name_prefixes() -> Weight {
+        (4_926_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `audit_existing_names` benchmark.
+    fn audit_existing_names(n: u32) -> Weight {
+        (8_512_000 as Weight)
+            .saturating_add((96_730_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `settle_jackpot` benchmark.
+    fn settle_jackpot() -> Weight {
+        (41_870_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_reputation_params` benchmark.
+    fn set_reputation_params() -> Weight {
+        (2_296_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `unlock_unbonded_withdrawal_for` benchmark.
+    fn unlock_unbonded_withdrawal_for() -> Weight {
+        (97_415_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(7 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(7 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_unlock_for_params` benchmark.
+    fn set_unlock_for_params() -> Weight {
+        (2_271_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `claim_to` benchmark.
+    fn claim_to() -> Weight {
+        (104_512_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(8 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `set_guardians` benchmark.
+    fn set_guardians(n: u32) -> Weight {
+        (21_307_000 as Weight)
+            .saturating_add((412_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `initiate_recovery` benchmark.
+    fn initiate_recovery() -> Weight {
+        (27_846_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `approve_recovery` benchmark.
+    fn approve_recovery() -> Weight {
+        (20_915_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `cancel_recovery` benchmark.
+    fn cancel_recovery() -> Weight {
+        (17_482_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `finalize_recovery` benchmark.
+    fn finalize_recovery() -> Weight {
+        (31_209_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    // Estimated from the storage accesses, it must be regenerated by the
+    // `emergency_set_validators` benchmark.
+    fn emergency_set_validators(n: u32) -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add((5_000_000 as Weight).saturating_mul(n as Weight))
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(n as Weight)))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
 }
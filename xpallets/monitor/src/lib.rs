@@ -0,0 +1,229 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! # Supply Monitor Module
+//!
+//! This module watches the issuance of the cross-chain tokens, an issuance far beyond
+//! the usual one is a possible sign of a compromised bridge.
+//!
+//! The issued and destroyed amounts of each token are accumulated in the fixed windows
+//! of `WindowBlocks` blocks. Once the issuance within a window exceeds the threshold of
+//! the token, `SupplyAnomaly` is emitted, at most once per window, and the bridge of the
+//! token is halted if the auto halt is enabled for it.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+mod types;
+
+use sp_std::prelude::*;
+
+use sp_runtime::traits::{Saturating, Zero};
+
+use frame_support::{dispatch::DispatchResult, log::warn, traits::Get};
+use frame_system::ensure_root;
+
+use chainx_primitives::AssetId;
+use xpallet_assets::{BalanceOf, OnAssetChanged};
+
+pub use self::types::SupplyWindow;
+pub use pallet::*;
+
+/// The maximum number of the past windows kept for each token.
+pub const MAXIMUM_WINDOW_HISTORY: usize = 16;
+
+/// Halts the bridge of a token.
+pub trait HaltBridge {
+    fn halt(token: &AssetId);
+}
+
+impl HaltBridge for () {
+    fn halt(_token: &AssetId) {}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+    use frame_support::pallet_prelude::*;
+    use frame_system::pallet_prelude::*;
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config + xpallet_assets::Config {
+        /// The overarching event type.
+        type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+        /// The number of blocks of each supply window.
+        #[pallet::constant]
+        type WindowBlocks: Get<Self::BlockNumber>;
+
+        /// Halts the bridge of a token on a supply anomaly if its auto halt is enabled.
+        type HaltBridge: HaltBridge;
+    }
+
+    #[pallet::pallet]
+    #[pallet::generate_store(pub(crate) trait Store)]
+    #[pallet::without_storage_info]
+    pub struct Pallet<T>(PhantomData<T>);
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Set the issuance threshold of a token per window, `None` to stop the alerts.
+        ///
+        /// This is a root-only operation.
+        #[pallet::weight(0)]
+        pub fn set_threshold(
+            origin: OriginFor<T>,
+            #[pallet::compact] token: AssetId,
+            threshold: Option<BalanceOf<T>>,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            match threshold {
+                Some(threshold) => Thresholds::<T>::insert(token, threshold),
+                None => Thresholds::<T>::remove(token),
+            }
+            Self::deposit_event(Event::<T>::ThresholdSet(token, threshold));
+            Ok(())
+        }
+
+        /// Set whether to halt the bridge of a token on a supply anomaly.
+        ///
+        /// This is a root-only operation.
+        #[pallet::weight(0)]
+        pub fn set_auto_halt(
+            origin: OriginFor<T>,
+            #[pallet::compact] token: AssetId,
+            auto_halt: bool,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            if auto_halt {
+                AutoHalt::<T>::insert(token, true);
+            } else {
+                AutoHalt::<T>::remove(token);
+            }
+            Self::deposit_event(Event::<T>::AutoHaltSet(token, auto_halt));
+            Ok(())
+        }
+    }
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(crate) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// The issuance of a token within the current window exceeded its threshold.
+        /// [token, window_issued, threshold]
+        SupplyAnomaly(AssetId, BalanceOf<T>, BalanceOf<T>),
+        /// The bridge of a token was halted on a supply anomaly. [token]
+        BridgeHalted(AssetId),
+        /// The issuance threshold of a token was set. [token, threshold]
+        ThresholdSet(AssetId, Option<BalanceOf<T>>),
+        /// The auto halt of a token was set. [token, auto_halt]
+        AutoHaltSet(AssetId, bool),
+    }
+
+    /// The issuance threshold of each token per window.
+    #[pallet::storage]
+    #[pallet::getter(fn threshold_of)]
+    pub type Thresholds<T: Config> = StorageMap<_, Twox64Concat, AssetId, BalanceOf<T>>;
+
+    /// Whether to halt the bridge of each token on a supply anomaly.
+    #[pallet::storage]
+    #[pallet::getter(fn auto_halt_of)]
+    pub type AutoHalt<T: Config> = StorageMap<_, Twox64Concat, AssetId, bool, ValueQuery>;
+
+    /// The current supply window of each token.
+    #[pallet::storage]
+    #[pallet::getter(fn current_window_of)]
+    pub type CurrentWindow<T: Config> =
+        StorageMap<_, Twox64Concat, AssetId, SupplyWindow<BalanceOf<T>, T::BlockNumber>>;
+
+    /// The latest past supply windows of each token, from the oldest one.
+    #[pallet::storage]
+    #[pallet::getter(fn window_history_of)]
+    pub type WindowHistory<T: Config> = StorageMap<
+        _,
+        Twox64Concat,
+        AssetId,
+        Vec<SupplyWindow<BalanceOf<T>, T::BlockNumber>>,
+        ValueQuery,
+    >;
+}
+
+impl<T: Config> Pallet<T> {
+    /// Returns the first block of the window `now` belongs to.
+    fn window_start_of(now: T::BlockNumber) -> T::BlockNumber {
+        let window_blocks = T::WindowBlocks::get();
+        if window_blocks.is_zero() {
+            now
+        } else {
+            now - now % window_blocks
+        }
+    }
+
+    /// Accumulates the supply change of `token` in its current window, the previous
+    /// window is moved into the history once a new window begins.
+    fn note_supply_changed(token: &AssetId, issued: BalanceOf<T>, destroyed: BalanceOf<T>) {
+        let start = Self::window_start_of(<frame_system::Pallet<T>>::block_number());
+        let mut window = match Self::current_window_of(token) {
+            Some(window) if window.start == start => window,
+            previous => {
+                if let Some(previous) = previous {
+                    WindowHistory::<T>::mutate(token, |history| {
+                        if history.len() >= MAXIMUM_WINDOW_HISTORY {
+                            history.remove(0);
+                        }
+                        history.push(previous);
+                    });
+                }
+                SupplyWindow {
+                    start,
+                    ..Default::default()
+                }
+            }
+        };
+        window.issued = window.issued.saturating_add(issued);
+        window.destroyed = window.destroyed.saturating_add(destroyed);
+
+        if !window.alerted {
+            if let Some(threshold) = Self::threshold_of(token) {
+                if window.issued > threshold {
+                    window.alerted = true;
+                    warn!(
+                        target: "runtime::monitor",
+                        "[note_supply_changed] Supply anomaly, token:{}, window_start:{:?}, issued:{:?}, threshold:{:?}",
+                        token, start, window.issued, threshold
+                    );
+                    Self::deposit_event(Event::<T>::SupplyAnomaly(
+                        *token,
+                        window.issued,
+                        threshold,
+                    ));
+                    if Self::auto_halt_of(token) {
+                        T::HaltBridge::halt(token);
+                        Self::deposit_event(Event::<T>::BridgeHalted(*token));
+                    }
+                }
+            }
+        }
+
+        CurrentWindow::<T>::insert(token, window);
+    }
+}
+
+impl<T: Config> OnAssetChanged<T::AccountId, BalanceOf<T>> for Pallet<T> {
+    fn on_issue_post(token: &AssetId, _who: &T::AccountId, value: BalanceOf<T>) -> DispatchResult {
+        Self::note_supply_changed(token, value, Zero::zero());
+        Ok(())
+    }
+
+    fn on_destroy_post(
+        token: &AssetId,
+        _who: &T::AccountId,
+        value: BalanceOf<T>,
+    ) -> DispatchResult {
+        Self::note_supply_changed(token, Zero::zero(), value);
+        Ok(())
+    }
+}
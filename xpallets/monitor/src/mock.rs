@@ -0,0 +1,203 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+use std::{cell::RefCell, collections::BTreeMap};
+
+use sp_core::H256;
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentityLookup},
+};
+
+use frame_support::{parameter_types, sp_io, traits::GenesisBuild};
+
+use chainx_primitives::AssetId;
+pub use xp_protocol::{PCX, X_BTC};
+use xpallet_assets::{AssetInfo, AssetRestrictions, Chain};
+use xpallet_support::traits::TreasuryAccount;
+
+use crate::{self as xpallet_monitor, Config, HaltBridge};
+
+/// The AccountId alias in this test module.
+pub(crate) type AccountId = u64;
+pub(crate) type BlockNumber = u64;
+pub(crate) type Balance = u128;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+        Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+        XAssetsRegistrar: xpallet_assets_registrar::{Pallet, Call, Config, Storage, Event<T>},
+        XAssets: xpallet_assets::{Pallet, Call, Config<T>, Storage, Event<T>},
+        XMonitor: xpallet_monitor::{Pallet, Call, Storage, Event<T>},
+    }
+);
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+    pub const SS58Prefix: u8 = 42;
+}
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = frame_support::traits::Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type Origin = Origin;
+    type Call = Call;
+    type Index = u64;
+    type BlockNumber = BlockNumber;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type Event = Event;
+    type BlockHashCount = BlockHashCount;
+    type DbWeight = ();
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = pallet_balances::AccountData<Balance>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = SS58Prefix;
+    type OnSetCode = ();
+    type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+parameter_types! {
+    pub const ExistentialDeposit: u64 = 1;
+    pub const MaxReserves: u32 = 50;
+}
+
+impl pallet_balances::Config for Test {
+    type MaxLocks = ();
+    type Balance = Balance;
+    type DustRemoval = ();
+    type Event = Event;
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = System;
+    type WeightInfo = ();
+    type ReserveIdentifier = [u8; 8];
+    type MaxReserves = MaxReserves;
+}
+
+parameter_types! {
+    pub const ChainXAssetId: AssetId = PCX;
+}
+
+impl xpallet_assets_registrar::Config for Test {
+    type Event = Event;
+    type NativeAssetId = ChainXAssetId;
+    type RegistrarHandler = ();
+    type WeightInfo = ();
+}
+
+pub struct SimpleTreasuryAccount;
+impl TreasuryAccount<AccountId> for SimpleTreasuryAccount {
+    fn treasury_account() -> Option<AccountId> {
+        Some(TREASURY)
+    }
+}
+
+impl xpallet_assets::Config for Test {
+    type Event = Event;
+    type Currency = Balances;
+    type TreasuryAccount = SimpleTreasuryAccount;
+    type OnCreatedAccount = frame_system::Provider<Test>;
+    type OnAssetChanged = XMonitor;
+    type OnReceived = ();
+    type MoveGuard = ();
+    type QuarantineOrigin = frame_system::EnsureRoot<AccountId>;
+    type WeightInfo = ();
+}
+
+thread_local! {
+    pub static HALTED: RefCell<Vec<AssetId>> = RefCell::new(Vec::new());
+}
+
+pub struct MockHaltBridge;
+impl HaltBridge for MockHaltBridge {
+    fn halt(token: &AssetId) {
+        HALTED.with(|halted| halted.borrow_mut().push(*token));
+    }
+}
+
+pub fn halted() -> Vec<AssetId> {
+    HALTED.with(|halted| halted.borrow().clone())
+}
+
+parameter_types! {
+    pub const WindowBlocks: BlockNumber = 600;
+}
+
+impl Config for Test {
+    type Event = Event;
+    type WindowBlocks = WindowBlocks;
+    type HaltBridge = MockHaltBridge;
+}
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+pub const TREASURY: AccountId = 100;
+
+fn btc() -> (AssetId, AssetInfo, AssetRestrictions) {
+    (
+        X_BTC,
+        AssetInfo::new::<Test>(
+            b"X-BTC".to_vec(),
+            b"X-BTC".to_vec(),
+            Chain::Bitcoin,
+            8,
+            b"ChainX's cross-chain Bitcoin".to_vec(),
+        )
+        .unwrap(),
+        AssetRestrictions::empty(),
+    )
+}
+
+#[derive(Default)]
+pub struct ExtBuilder;
+
+impl ExtBuilder {
+    pub fn build(self) -> sp_io::TestExternalities {
+        let mut storage = frame_system::GenesisConfig::default()
+            .build_storage::<Test>()
+            .unwrap();
+
+        let _ = pallet_balances::GenesisConfig::<Test> {
+            balances: vec![(ALICE, 1000), (BOB, 2000)],
+        }
+        .assimilate_storage(&mut storage);
+
+        let (id, info, restrictions) = btc();
+        GenesisBuild::<Test>::assimilate_storage(
+            &xpallet_assets_registrar::GenesisConfig {
+                assets: vec![(id, info, true, true)],
+            },
+            &mut storage,
+        )
+        .unwrap();
+
+        let _ = xpallet_assets::GenesisConfig::<Test> {
+            assets_restrictions: vec![(id, restrictions)],
+            endowed: BTreeMap::new(),
+        }
+        .assimilate_storage(&mut storage);
+
+        let mut ext = sp_io::TestExternalities::new(storage);
+        ext.execute_with(|| System::set_block_number(1));
+        ext
+    }
+
+    pub fn build_and_execute(self, test: impl FnOnce()) {
+        let mut ext = self.build();
+        ext.execute_with(test);
+    }
+}
@@ -0,0 +1,157 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::DispatchError;
+
+use crate::mock::*;
+use crate::*;
+
+fn xmonitor_events() -> Vec<crate::Event<Test>> {
+    System::events()
+        .into_iter()
+        .filter_map(|record| match record.event {
+            crate::mock::Event::XMonitor(event) => Some(event),
+            _ => None,
+        })
+        .collect()
+}
+
+fn anomalies() -> Vec<crate::Event<Test>> {
+    xmonitor_events()
+        .into_iter()
+        .filter(|event| matches!(event, crate::Event::SupplyAnomaly(..)))
+        .collect()
+}
+
+fn t_issue(who: AccountId, value: Balance) {
+    assert_ok!(XAssets::issue(&X_BTC, &who, value, true));
+}
+
+#[test]
+fn set_threshold_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_noop!(
+            XMonitor::set_threshold(Origin::signed(ALICE), X_BTC, Some(100)),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            XMonitor::set_auto_halt(Origin::signed(ALICE), X_BTC, true),
+            DispatchError::BadOrigin
+        );
+
+        assert_ok!(XMonitor::set_threshold(Origin::root(), X_BTC, Some(100)));
+        assert_ok!(XMonitor::set_auto_halt(Origin::root(), X_BTC, true));
+        assert_eq!(XMonitor::threshold_of(X_BTC), Some(100));
+        assert!(XMonitor::auto_halt_of(X_BTC));
+
+        assert_ok!(XMonitor::set_threshold(Origin::root(), X_BTC, None));
+        assert_ok!(XMonitor::set_auto_halt(Origin::root(), X_BTC, false));
+        assert_eq!(XMonitor::threshold_of(X_BTC), None);
+        assert!(!XMonitor::auto_halt_of(X_BTC));
+    });
+}
+
+#[test]
+fn issuance_below_threshold_should_be_silent() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XMonitor::set_threshold(Origin::root(), X_BTC, Some(100)));
+
+        t_issue(ALICE, 60);
+        t_issue(BOB, 40);
+        assert_ok!(XAssets::destroy_usable(&X_BTC, &BOB, 10));
+
+        assert!(anomalies().is_empty());
+        assert_eq!(
+            XMonitor::current_window_of(X_BTC),
+            Some(SupplyWindow {
+                start: 0,
+                issued: 100,
+                destroyed: 10,
+                alerted: false,
+            })
+        );
+    });
+}
+
+#[test]
+fn issuance_above_threshold_should_alert_once_per_window() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XMonitor::set_threshold(Origin::root(), X_BTC, Some(100)));
+
+        t_issue(ALICE, 60);
+        t_issue(BOB, 50);
+        assert_eq!(
+            anomalies(),
+            vec![crate::Event::SupplyAnomaly(X_BTC, 110, 100)]
+        );
+
+        // No more alerts within the same window.
+        System::set_block_number(599);
+        t_issue(ALICE, 100);
+        assert_eq!(anomalies().len(), 1);
+
+        // A new window.
+        System::set_block_number(600);
+        t_issue(ALICE, 60);
+        assert_eq!(anomalies().len(), 1);
+        t_issue(ALICE, 41);
+        assert_eq!(
+            anomalies(),
+            vec![
+                crate::Event::SupplyAnomaly(X_BTC, 110, 100),
+                crate::Event::SupplyAnomaly(X_BTC, 101, 100)
+            ]
+        );
+        assert_eq!(
+            XMonitor::window_history_of(X_BTC),
+            vec![SupplyWindow {
+                start: 0,
+                issued: 210,
+                destroyed: 0,
+                alerted: true,
+            }]
+        );
+
+        // Without the auto halt, the bridge is never halted.
+        assert!(halted().is_empty());
+    });
+}
+
+#[test]
+fn auto_halt_should_halt_the_bridge() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XMonitor::set_threshold(Origin::root(), X_BTC, Some(100)));
+        assert_ok!(XMonitor::set_auto_halt(Origin::root(), X_BTC, true));
+
+        t_issue(ALICE, 100);
+        assert!(halted().is_empty());
+
+        t_issue(ALICE, 1);
+        assert_eq!(halted(), vec![X_BTC]);
+        assert_eq!(
+            xmonitor_events()[2..],
+            [
+                crate::Event::SupplyAnomaly(X_BTC, 101, 100),
+                crate::Event::BridgeHalted(X_BTC)
+            ]
+        );
+    });
+}
+
+#[test]
+fn window_history_should_be_bounded() {
+    ExtBuilder::default().build_and_execute(|| {
+        for window in 0..(MAXIMUM_WINDOW_HISTORY as u64 + 2) {
+            System::set_block_number(window * 600);
+            t_issue(ALICE, 1);
+        }
+
+        let history = XMonitor::window_history_of(X_BTC);
+        assert_eq!(history.len(), MAXIMUM_WINDOW_HISTORY);
+        assert_eq!(history[0].start, 600);
+        assert_eq!(
+            XMonitor::current_window_of(X_BTC).map(|window| window.start),
+            Some((MAXIMUM_WINDOW_HISTORY as u64 + 1) * 600)
+        );
+    });
+}
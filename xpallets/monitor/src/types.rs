@@ -0,0 +1,21 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+use sp_runtime::RuntimeDebug;
+
+/// The amounts of a token issued and destroyed within a window of blocks.
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct SupplyWindow<Balance, BlockNumber> {
+    /// The first block of the window.
+    pub start: BlockNumber,
+    pub issued: Balance,
+    pub destroyed: Balance,
+    /// Whether the issuance threshold has been exceeded within the window.
+    pub alerted: bool,
+}
@@ -0,0 +1,152 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+use codec::Encode;
+use frame_benchmarking::{account, benchmarks};
+use frame_system::RawOrigin;
+
+use super::*;
+
+/// Deploys a normal multisig of `n` owners with the threshold 2, returns the multisig and
+/// its owners.
+fn deploy_multisig<T: Config>(n: u32) -> (T::AccountId, Vec<T::AccountId>) {
+    let owners = (0..n)
+        .map(|i| account::<T::AccountId>("owner", i, 0))
+        .collect::<Vec<_>>();
+    let multisig = Pallet::<T>::do_deploy(owners.clone(), 2, MultisigKind::Normal).unwrap();
+    (multisig, owners)
+}
+
+fn remark_call<T: Config>(index: u32) -> <T as Config>::Call {
+    frame_system::Call::<T>::remark {
+        remark: index.encode(),
+    }
+    .into()
+}
+
+/// Proposes `p` distinct calls to `multisig` by `proposer`.
+fn propose_calls<T: Config>(multisig: &T::AccountId, proposer: &T::AccountId, p: u32) {
+    for i in 0..p {
+        Pallet::<T>::propose(
+            RawOrigin::Signed(proposer.clone()).into(),
+            multisig.clone(),
+            Box::new(remark_call::<T>(i)),
+        )
+        .unwrap();
+    }
+}
+
+benchmarks! {
+    deploy {
+        let o in 1 .. T::MaxOwners::get();
+        let owners = (0..o)
+            .map(|i| account::<T::AccountId>("owner", i, 0))
+            .collect::<Vec<_>>();
+        let count = Pallet::<T>::multisig_count();
+    }: _(RawOrigin::Root, owners, 1, MultisigKind::Normal)
+    verify {
+        assert_eq!(Pallet::<T>::multisig_count(), count + 1);
+    }
+
+    propose {
+        let (multisig, owners) = deploy_multisig::<T>(3);
+        propose_calls::<T>(&multisig, &owners[0], T::MaxProposals::get() - 1);
+        let call = remark_call::<T>(T::MaxProposals::get());
+    }: _(RawOrigin::Signed(owners[0].clone()), multisig.clone(), Box::new(call))
+    verify {
+        assert_eq!(Pallet::<T>::proposal_count_of(&multisig), T::MaxProposals::get());
+    }
+
+    confirm {
+        let (multisig, owners) = deploy_multisig::<T>(3);
+        propose_calls::<T>(&multisig, &owners[0], 1);
+        let proposal_hash = T::Hashing::hash_of(&remark_call::<T>(0));
+    }: _(RawOrigin::Signed(owners[1].clone()), multisig.clone(), proposal_hash, Weight::max_value())
+    verify {
+        assert!(Pallet::<T>::proposal_of(&multisig, proposal_hash).is_none());
+    }
+
+    revoke {
+        let (multisig, owners) = deploy_multisig::<T>(3);
+        propose_calls::<T>(&multisig, &owners[0], 1);
+        let proposal_hash = T::Hashing::hash_of(&remark_call::<T>(0));
+    }: _(RawOrigin::Signed(owners[0].clone()), multisig.clone(), proposal_hash)
+    verify {
+        assert_eq!(Pallet::<T>::proposal_count_of(&multisig), 0);
+    }
+
+    replace_owner {
+        let (multisig, owners) = deploy_multisig::<T>(3);
+        let new = account::<T::AccountId>("new", 0, 0);
+    }: _(RawOrigin::Signed(multisig.clone()), owners[0].clone(), new)
+    verify {
+        assert!(Pallet::<T>::pending_owner_change_of(&multisig).is_some());
+    }
+
+    add_owner {
+        let (multisig, _) = deploy_multisig::<T>(3);
+        let new = account::<T::AccountId>("new", 0, 0);
+    }: _(RawOrigin::Signed(multisig.clone()), new)
+    verify {
+        assert!(Pallet::<T>::pending_owner_change_of(&multisig).is_some());
+    }
+
+    remove_owner {
+        let (multisig, owners) = deploy_multisig::<T>(3);
+    }: _(RawOrigin::Signed(multisig.clone()), owners[0].clone())
+    verify {
+        assert!(Pallet::<T>::pending_owner_change_of(&multisig).is_some());
+    }
+
+    change_threshold {
+        let (multisig, _) = deploy_multisig::<T>(3);
+    }: _(RawOrigin::Signed(multisig.clone()), 3)
+    verify {
+        assert!(Pallet::<T>::pending_owner_change_of(&multisig).is_some());
+    }
+
+    veto_owner_change {
+        let (multisig, owners) = deploy_multisig::<T>(3);
+        Pallet::<T>::remove_owner(RawOrigin::Signed(multisig.clone()).into(), owners[0].clone())
+            .unwrap();
+    }: _(RawOrigin::Signed(owners[1].clone()), multisig.clone())
+    verify {
+        assert!(Pallet::<T>::pending_owner_change_of(&multisig).is_none());
+    }
+
+    enact_owner_change {
+        let p in 0 .. T::MaxProposals::get();
+        let (multisig, owners) = deploy_multisig::<T>(3);
+        propose_calls::<T>(&multisig, &owners[0], p);
+        Pallet::<T>::change_threshold(RawOrigin::Signed(multisig.clone()).into(), 3).unwrap();
+        let enact_at = Pallet::<T>::pending_owner_change_of(&multisig).unwrap().enact_at;
+    }: {
+        Pallet::<T>::enact_owner_change(multisig.clone(), enact_at);
+    }
+    verify {
+        assert_eq!(Pallet::<T>::multisig_of(&multisig).unwrap().threshold, 3);
+        assert_eq!(Proposals::<T>::iter_prefix(&multisig).count(), 0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{ExtBuilder, Test};
+    use frame_support::assert_ok;
+
+    #[test]
+    fn test_benchmarks() {
+        ExtBuilder::default().build().execute_with(|| {
+            assert_ok!(Pallet::<Test>::test_benchmark_deploy());
+            assert_ok!(Pallet::<Test>::test_benchmark_propose());
+            assert_ok!(Pallet::<Test>::test_benchmark_confirm());
+            assert_ok!(Pallet::<Test>::test_benchmark_revoke());
+            assert_ok!(Pallet::<Test>::test_benchmark_replace_owner());
+            assert_ok!(Pallet::<Test>::test_benchmark_add_owner());
+            assert_ok!(Pallet::<Test>::test_benchmark_remove_owner());
+            assert_ok!(Pallet::<Test>::test_benchmark_change_threshold());
+            assert_ok!(Pallet::<Test>::test_benchmark_veto_owner_change());
+            assert_ok!(Pallet::<Test>::test_benchmark_enact_owner_change());
+        });
+    }
+}
@@ -0,0 +1,590 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! # Multisig Module
+//!
+//! This module provides the multisigs whose accounts stay the same when the owners change,
+//! e.g., the council multisig deployed at genesis.
+//!
+//! An owner proposes a call, which is dispatched from the multisig account once `threshold`
+//! owners confirmed it. The owners and the threshold are changed by the multisig itself,
+//! through the proposals of `replace_owner`, `add_owner`, `remove_owner` and
+//! `change_threshold`. An approved change is enacted after `OwnerChangeDelay` blocks, during
+//! which any owner not removed by the change can veto it. All the pending proposals of the
+//! multisig are discarded once the change is enacted, a multisig has at most `MaxProposals`
+//! pending proposals so that the discard is bounded.
+//!
+//! The owner operations are rejected for a `Trustee` multisig, its owners and threshold stay
+//! as deployed.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+mod types;
+pub mod weights;
+
+use sp_std::prelude::*;
+
+use codec::Decode;
+use sp_runtime::traits::{Dispatchable, Hash, Saturating, TrailingZeroInput};
+
+use frame_support::{
+    dispatch::DispatchResult,
+    ensure,
+    traits::Get,
+    weights::{GetDispatchInfo, Weight},
+};
+use frame_system::{ensure_root, ensure_signed};
+
+pub use self::types::{MultisigInfo, MultisigKind, OwnerOperation, PendingOwnerChange, Proposal};
+pub use self::weights::WeightInfo;
+pub use pallet::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+    use frame_support::pallet_prelude::*;
+    use frame_system::pallet_prelude::*;
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        /// The overarching event type.
+        type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+        /// The overarching call type.
+        type Call: Parameter
+            + Dispatchable<Origin = Self::Origin>
+            + GetDispatchInfo
+            + From<frame_system::Call<Self>>;
+
+        /// The maximum number of the owners of a multisig.
+        #[pallet::constant]
+        type MaxOwners: Get<u32>;
+
+        /// The number of blocks between the approval and the enactment of an owner change.
+        #[pallet::constant]
+        type OwnerChangeDelay: Get<Self::BlockNumber>;
+
+        /// The maximum number of the pending proposals of a multisig.
+        #[pallet::constant]
+        type MaxProposals: Get<u32>;
+
+        /// Weight information for extrinsics in this pallet.
+        type WeightInfo: WeightInfo;
+    }
+
+    #[pallet::pallet]
+    #[pallet::generate_store(pub(crate) trait Store)]
+    #[pallet::without_storage_info]
+    pub struct Pallet<T>(PhantomData<T>);
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(now: T::BlockNumber) -> Weight {
+            let multisigs = OwnerChangeSchedule::<T>::take(now);
+            let mut weight = T::DbWeight::get().reads_writes(1, 1);
+            for multisig in multisigs {
+                let discarded = Self::enact_owner_change(multisig, now);
+                weight =
+                    weight.saturating_add(<T as Config>::WeightInfo::enact_owner_change(discarded));
+            }
+            weight
+        }
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Deploy a multisig.
+        ///
+        /// This is a root-only operation.
+        #[pallet::weight(<T as Config>::WeightInfo::deploy(owners.len() as u32))]
+        pub fn deploy(
+            origin: OriginFor<T>,
+            owners: Vec<T::AccountId>,
+            threshold: u16,
+            kind: MultisigKind,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+            Self::do_deploy(owners, threshold, kind)?;
+            Ok(())
+        }
+
+        /// Propose a call to be dispatched from the multisig account.
+        ///
+        /// The proposer confirms the proposal at the same time.
+        #[pallet::weight(
+            <T as Config>::WeightInfo::propose()
+                .saturating_add(call.get_dispatch_info().weight)
+        )]
+        pub fn propose(
+            origin: OriginFor<T>,
+            multisig: T::AccountId,
+            call: Box<<T as Config>::Call>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let info = Self::ensure_owner(&multisig, &who)?;
+
+            let proposal_hash = T::Hashing::hash_of(&call);
+            ensure!(
+                !Proposals::<T>::contains_key(&multisig, proposal_hash),
+                Error::<T>::ProposalExists
+            );
+
+            if info.threshold > 1 {
+                ProposalCount::<T>::try_mutate(&multisig, |count| {
+                    ensure!(
+                        *count < T::MaxProposals::get(),
+                        Error::<T>::TooManyProposals
+                    );
+                    *count += 1;
+                    Ok::<_, Error<T>>(())
+                })?;
+            }
+
+            Self::deposit_event(Event::<T>::Proposed(
+                multisig.clone(),
+                who.clone(),
+                proposal_hash,
+            ));
+            if info.threshold <= 1 {
+                Self::execute(multisig, proposal_hash, *call);
+            } else {
+                Proposals::<T>::insert(
+                    &multisig,
+                    proposal_hash,
+                    Proposal {
+                        call: *call,
+                        proposer: who.clone(),
+                        confirmations: vec![who],
+                    },
+                );
+            }
+            Ok(())
+        }
+
+        /// Confirm a proposal of the multisig.
+        ///
+        /// The proposal is dispatched once it is confirmed by `threshold` owners, `max_weight`
+        /// must cover the weight of the proposed call in that case.
+        #[pallet::weight(<T as Config>::WeightInfo::confirm().saturating_add(*max_weight))]
+        pub fn confirm(
+            origin: OriginFor<T>,
+            multisig: T::AccountId,
+            proposal_hash: T::Hash,
+            #[pallet::compact] max_weight: Weight,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let info = Self::ensure_owner(&multisig, &who)?;
+
+            let mut proposal =
+                Self::proposal_of(&multisig, proposal_hash).ok_or(Error::<T>::ProposalNotFound)?;
+            ensure!(
+                !proposal.confirmations.contains(&who),
+                Error::<T>::AlreadyConfirmed
+            );
+            proposal.confirmations.push(who.clone());
+
+            let approved = proposal.confirmations.len() >= info.threshold as usize;
+            if approved {
+                ensure!(
+                    proposal.call.get_dispatch_info().weight <= max_weight,
+                    Error::<T>::MaxWeightTooLow
+                );
+            }
+
+            Self::deposit_event(Event::<T>::Confirmed(multisig.clone(), who, proposal_hash));
+            if approved {
+                Self::remove_proposal(&multisig, proposal_hash);
+                Self::execute(multisig, proposal_hash, proposal.call);
+            } else {
+                Proposals::<T>::insert(&multisig, proposal_hash, proposal);
+            }
+            Ok(())
+        }
+
+        /// Revoke a proposal of the multisig.
+        ///
+        /// Only the proposer can revoke the proposal.
+        #[pallet::weight(<T as Config>::WeightInfo::revoke())]
+        pub fn revoke(
+            origin: OriginFor<T>,
+            multisig: T::AccountId,
+            proposal_hash: T::Hash,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let proposal =
+                Self::proposal_of(&multisig, proposal_hash).ok_or(Error::<T>::ProposalNotFound)?;
+            ensure!(proposal.proposer == who, Error::<T>::NotProposer);
+
+            Self::remove_proposal(&multisig, proposal_hash);
+            Self::deposit_event(Event::<T>::ProposalRevoked(multisig, proposal_hash));
+            Ok(())
+        }
+
+        /// Replace the owner `old` of the multisig with `new`.
+        ///
+        /// The origin must be the multisig itself.
+        #[pallet::weight(<T as Config>::WeightInfo::replace_owner())]
+        pub fn replace_owner(
+            origin: OriginFor<T>,
+            old: T::AccountId,
+            new: T::AccountId,
+        ) -> DispatchResult {
+            let multisig = ensure_signed(origin)?;
+            Self::schedule_owner_change(multisig, OwnerOperation::ReplaceOwner(old, new))
+        }
+
+        /// Add a new owner to the multisig.
+        ///
+        /// The origin must be the multisig itself.
+        #[pallet::weight(<T as Config>::WeightInfo::add_owner())]
+        pub fn add_owner(origin: OriginFor<T>, new: T::AccountId) -> DispatchResult {
+            let multisig = ensure_signed(origin)?;
+            Self::schedule_owner_change(multisig, OwnerOperation::AddOwner(new))
+        }
+
+        /// Remove an owner of the multisig.
+        ///
+        /// The origin must be the multisig itself.
+        #[pallet::weight(<T as Config>::WeightInfo::remove_owner())]
+        pub fn remove_owner(origin: OriginFor<T>, old: T::AccountId) -> DispatchResult {
+            let multisig = ensure_signed(origin)?;
+            Self::schedule_owner_change(multisig, OwnerOperation::RemoveOwner(old))
+        }
+
+        /// Change the threshold of the multisig.
+        ///
+        /// The origin must be the multisig itself.
+        #[pallet::weight(<T as Config>::WeightInfo::change_threshold())]
+        pub fn change_threshold(origin: OriginFor<T>, threshold: u16) -> DispatchResult {
+            let multisig = ensure_signed(origin)?;
+            Self::schedule_owner_change(multisig, OwnerOperation::ChangeThreshold(threshold))
+        }
+
+        /// Veto the pending owner change of the multisig.
+        ///
+        /// The origin must be an owner of the multisig not removed by the change.
+        #[pallet::weight(<T as Config>::WeightInfo::veto_owner_change())]
+        pub fn veto_owner_change(origin: OriginFor<T>, multisig: T::AccountId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::ensure_owner(&multisig, &who)?;
+
+            let pending =
+                Self::pending_owner_change_of(&multisig).ok_or(Error::<T>::NoPendingOwnerChange)?;
+            let removed = match &pending.operation {
+                OwnerOperation::ReplaceOwner(old, _) | OwnerOperation::RemoveOwner(old) => {
+                    Some(old)
+                }
+                OwnerOperation::AddOwner(_) | OwnerOperation::ChangeThreshold(_) => None,
+            };
+            ensure!(removed != Some(&who), Error::<T>::CannotVetoOwnRemoval);
+
+            PendingOwnerChanges::<T>::remove(&multisig);
+            Self::deposit_event(Event::<T>::OwnerChangeVetoed(multisig, who));
+            Ok(())
+        }
+    }
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(crate) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// A multisig was deployed. [multisig, owners, threshold, kind]
+        MultisigDeployed(T::AccountId, Vec<T::AccountId>, u16, MultisigKind),
+        /// A call was proposed. [multisig, proposer, proposal_hash]
+        Proposed(T::AccountId, T::AccountId, T::Hash),
+        /// A proposal was confirmed by an owner. [multisig, owner, proposal_hash]
+        Confirmed(T::AccountId, T::AccountId, T::Hash),
+        /// An approved proposal was dispatched. [multisig, proposal_hash, result]
+        Executed(T::AccountId, T::Hash, DispatchResult),
+        /// A proposal was revoked by the proposer. [multisig, proposal_hash]
+        ProposalRevoked(T::AccountId, T::Hash),
+        /// An owner change was approved by the multisig. [multisig, operation, enact_at]
+        OwnerChangeScheduled(T::AccountId, OwnerOperation<T::AccountId>, T::BlockNumber),
+        /// The pending owner change was vetoed. [multisig, owner]
+        OwnerChangeVetoed(T::AccountId, T::AccountId),
+        /// The pending owner change was enacted. [multisig, owners, threshold]
+        OwnerChangeEnacted(T::AccountId, Vec<T::AccountId>, u16),
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// The account is not a multisig.
+        NotMultisig,
+        /// The sender is not an owner of the multisig.
+        NotOwner,
+        /// A multisig must have at least one owner.
+        EmptyOwners,
+        /// The number of the owners exceeds `MaxOwners`.
+        TooManyOwners,
+        /// The account is already an owner of the multisig.
+        DuplicateOwner,
+        /// The account to remove or replace is not an owner of the multisig.
+        OwnerNotFound,
+        /// The threshold can not be zero.
+        ZeroThreshold,
+        /// The threshold can not exceed the number of the owners.
+        ThresholdTooHigh,
+        /// The same call has already been proposed.
+        ProposalExists,
+        /// The multisig already has `MaxProposals` pending proposals.
+        TooManyProposals,
+        /// The proposal does not exist.
+        ProposalNotFound,
+        /// The owner has already confirmed the proposal.
+        AlreadyConfirmed,
+        /// Only the proposer can revoke the proposal.
+        NotProposer,
+        /// `max_weight` is lower than the weight of the proposed call.
+        MaxWeightTooLow,
+        /// The owners and the threshold of a trustee multisig can not be changed.
+        TrusteeMultisig,
+        /// The multisig already has a pending owner change.
+        OwnerChangePending,
+        /// The multisig has no pending owner change.
+        NoPendingOwnerChange,
+        /// An owner can not veto the change removing itself.
+        CannotVetoOwnRemoval,
+    }
+
+    /// The number of the deployed multisigs, used to derive the multisig accounts.
+    #[pallet::storage]
+    #[pallet::getter(fn multisig_count)]
+    pub type MultisigCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+    /// The owners and the threshold of each multisig.
+    #[pallet::storage]
+    #[pallet::getter(fn multisig_of)]
+    pub type Multisigs<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, MultisigInfo<T::AccountId>>;
+
+    /// The proposals of each multisig waiting for the confirmations.
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_of)]
+    pub type Proposals<T: Config> = StorageDoubleMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        Identity,
+        T::Hash,
+        Proposal<T::AccountId, <T as Config>::Call>,
+    >;
+
+    /// The number of the pending proposals of each multisig.
+    #[pallet::storage]
+    #[pallet::getter(fn proposal_count_of)]
+    pub type ProposalCount<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    /// The approved owner change of each multisig waiting for the enactment.
+    #[pallet::storage]
+    #[pallet::getter(fn pending_owner_change_of)]
+    pub type PendingOwnerChanges<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        PendingOwnerChange<T::AccountId, T::BlockNumber>,
+    >;
+
+    /// The multisigs whose pending owner change is enacted at the block.
+    #[pallet::storage]
+    pub type OwnerChangeSchedule<T: Config> =
+        StorageMap<_, Twox64Concat, T::BlockNumber, Vec<T::AccountId>, ValueQuery>;
+
+    #[pallet::genesis_config]
+    pub struct GenesisConfig<T: Config> {
+        /// The multisigs deployed at genesis, i.e., `(owners, threshold, kind)`.
+        pub multisigs: Vec<(Vec<T::AccountId>, u16, MultisigKind)>,
+    }
+
+    #[cfg(feature = "std")]
+    impl<T: Config> Default for GenesisConfig<T> {
+        fn default() -> Self {
+            Self {
+                multisigs: Default::default(),
+            }
+        }
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+        fn build(&self) {
+            for (owners, threshold, kind) in &self.multisigs {
+                Pallet::<T>::do_deploy(owners.clone(), *threshold, *kind)
+                    .expect("Invalid genesis multisig");
+            }
+        }
+    }
+}
+
+impl<T: Config> Pallet<T> {
+    /// Returns the account of the multisig deployed as the `index`-th one.
+    pub fn multisig_account_id(index: u32) -> T::AccountId {
+        let entropy = T::Hashing::hash_of(&(b"xmultisig", index));
+        Decode::decode(&mut TrailingZeroInput::new(entropy.as_ref()))
+            .expect("infinite length input; no invalid inputs for type; qed")
+    }
+
+    fn do_deploy(
+        owners: Vec<T::AccountId>,
+        threshold: u16,
+        kind: MultisigKind,
+    ) -> Result<T::AccountId, Error<T>> {
+        let info = MultisigInfo {
+            owners,
+            threshold,
+            kind,
+        };
+        Self::validate(&info)?;
+
+        let index = Self::multisig_count();
+        let multisig = Self::multisig_account_id(index);
+        MultisigCount::<T>::put(index + 1);
+        Multisigs::<T>::insert(&multisig, &info);
+        Self::deposit_event(Event::<T>::MultisigDeployed(
+            multisig.clone(),
+            info.owners,
+            info.threshold,
+            kind,
+        ));
+        Ok(multisig)
+    }
+
+    fn validate(info: &MultisigInfo<T::AccountId>) -> Result<(), Error<T>> {
+        let owners = &info.owners;
+        ensure!(!owners.is_empty(), Error::<T>::EmptyOwners);
+        ensure!(
+            owners.len() <= T::MaxOwners::get() as usize,
+            Error::<T>::TooManyOwners
+        );
+        ensure!(
+            owners
+                .iter()
+                .enumerate()
+                .all(|(i, owner)| !owners[..i].contains(owner)),
+            Error::<T>::DuplicateOwner
+        );
+        ensure!(info.threshold > 0, Error::<T>::ZeroThreshold);
+        ensure!(
+            info.threshold as usize <= owners.len(),
+            Error::<T>::ThresholdTooHigh
+        );
+        Ok(())
+    }
+
+    /// Returns the multisig info with `operation` applied.
+    fn apply_operation(
+        info: &MultisigInfo<T::AccountId>,
+        operation: &OwnerOperation<T::AccountId>,
+    ) -> Result<MultisigInfo<T::AccountId>, Error<T>> {
+        let mut new_info = info.clone();
+        let position_of = |who: &T::AccountId| {
+            info.owners
+                .iter()
+                .position(|owner| owner == who)
+                .ok_or(Error::<T>::OwnerNotFound)
+        };
+        match operation {
+            OwnerOperation::ReplaceOwner(old, new) => {
+                let position = position_of(old)?;
+                new_info.owners[position] = new.clone();
+            }
+            OwnerOperation::AddOwner(new) => new_info.owners.push(new.clone()),
+            OwnerOperation::RemoveOwner(old) => {
+                let position = position_of(old)?;
+                new_info.owners.remove(position);
+            }
+            OwnerOperation::ChangeThreshold(threshold) => new_info.threshold = *threshold,
+        }
+        Self::validate(&new_info)?;
+        Ok(new_info)
+    }
+
+    fn ensure_owner(
+        multisig: &T::AccountId,
+        who: &T::AccountId,
+    ) -> Result<MultisigInfo<T::AccountId>, Error<T>> {
+        let info = Self::multisig_of(multisig).ok_or(Error::<T>::NotMultisig)?;
+        ensure!(info.owners.contains(who), Error::<T>::NotOwner);
+        Ok(info)
+    }
+
+    fn remove_proposal(multisig: &T::AccountId, proposal_hash: T::Hash) {
+        Proposals::<T>::remove(multisig, proposal_hash);
+        ProposalCount::<T>::mutate(multisig, |count| *count = count.saturating_sub(1));
+    }
+
+    fn execute(multisig: T::AccountId, proposal_hash: T::Hash, call: <T as Config>::Call) {
+        let origin = frame_system::RawOrigin::Signed(multisig.clone()).into();
+        let result = call.dispatch(origin).map(|_| ()).map_err(|err| err.error);
+        Self::deposit_event(Event::<T>::Executed(multisig, proposal_hash, result));
+    }
+
+    fn schedule_owner_change(
+        multisig: T::AccountId,
+        operation: OwnerOperation<T::AccountId>,
+    ) -> DispatchResult {
+        let info = Self::multisig_of(&multisig).ok_or(Error::<T>::NotMultisig)?;
+        ensure!(
+            info.kind == MultisigKind::Normal,
+            Error::<T>::TrusteeMultisig
+        );
+        ensure!(
+            !PendingOwnerChanges::<T>::contains_key(&multisig),
+            Error::<T>::OwnerChangePending
+        );
+        Self::apply_operation(&info, &operation)?;
+
+        let now = <frame_system::Pallet<T>>::block_number();
+        let enact_at = now.saturating_add(T::OwnerChangeDelay::get());
+        PendingOwnerChanges::<T>::insert(
+            &multisig,
+            PendingOwnerChange {
+                operation: operation.clone(),
+                enact_at,
+            },
+        );
+        OwnerChangeSchedule::<T>::append(enact_at, &multisig);
+        Self::deposit_event(Event::<T>::OwnerChangeScheduled(
+            multisig, operation, enact_at,
+        ));
+        Ok(())
+    }
+
+    /// Enacts the pending owner change of `multisig` scheduled at `now`.
+    ///
+    /// Returns the number of the discarded proposals.
+    fn enact_owner_change(multisig: T::AccountId, now: T::BlockNumber) -> u32 {
+        let pending = match Self::pending_owner_change_of(&multisig) {
+            // The change was vetoed, or vetoed and scheduled again at a later block.
+            Some(pending) if pending.enact_at == now => pending,
+            _ => return 0,
+        };
+        PendingOwnerChanges::<T>::remove(&multisig);
+
+        // The operation was validated when it was scheduled and the owners can not change
+        // in between, so this never fails in practice.
+        let new_info = match Self::multisig_of(&multisig)
+            .ok_or(Error::<T>::NotMultisig)
+            .and_then(|info| Self::apply_operation(&info, &pending.operation))
+        {
+            Ok(new_info) => new_info,
+            Err(_) => return 0,
+        };
+
+        Multisigs::<T>::insert(&multisig, &new_info);
+        // The confirmations of the previous owners are no longer valid, there are at most
+        // `MaxProposals` of them.
+        let discarded = ProposalCount::<T>::take(&multisig);
+        let _ = Proposals::<T>::remove_prefix(&multisig, Some(T::MaxProposals::get()));
+        Self::deposit_event(Event::<T>::OwnerChangeEnacted(
+            multisig,
+            new_info.owners,
+            new_info.threshold,
+        ));
+        discarded
+    }
+}
@@ -0,0 +1,121 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+use sp_core::H256;
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentityLookup},
+};
+
+use frame_support::{parameter_types, sp_io, traits::GenesisBuild};
+
+use crate::{self as xpallet_multisig, Config, MultisigKind};
+
+/// The AccountId alias in this test module.
+pub(crate) type AccountId = u64;
+pub(crate) type BlockNumber = u64;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+    pub enum Test where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+        XMultisig: xpallet_multisig::{Pallet, Call, Storage, Config<T>, Event<T>},
+    }
+);
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+    pub const SS58Prefix: u8 = 42;
+}
+
+impl frame_system::Config for Test {
+    type BaseCallFilter = frame_support::traits::Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type Origin = Origin;
+    type Call = Call;
+    type Index = u64;
+    type BlockNumber = BlockNumber;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type Event = Event;
+    type BlockHashCount = BlockHashCount;
+    type DbWeight = ();
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = ();
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = SS58Prefix;
+    type OnSetCode = ();
+    type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+parameter_types! {
+    pub const MaxOwners: u32 = 5;
+    pub const OwnerChangeDelay: BlockNumber = 10;
+    pub const MaxProposals: u32 = 3;
+}
+
+impl Config for Test {
+    type Event = Event;
+    type Call = Call;
+    type MaxOwners = MaxOwners;
+    type OwnerChangeDelay = OwnerChangeDelay;
+    type MaxProposals = MaxProposals;
+    type WeightInfo = ();
+}
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+pub const CHARLIE: AccountId = 3;
+pub const DAVE: AccountId = 4;
+pub const EVE: AccountId = 5;
+
+/// The normal multisig deployed at genesis.
+pub fn council() -> AccountId {
+    XMultisig::multisig_account_id(0)
+}
+
+/// The trustee multisig deployed at genesis.
+pub fn trustee() -> AccountId {
+    XMultisig::multisig_account_id(1)
+}
+
+#[derive(Default)]
+pub struct ExtBuilder;
+
+impl ExtBuilder {
+    pub fn build(self) -> sp_io::TestExternalities {
+        let mut storage = frame_system::GenesisConfig::default()
+            .build_storage::<Test>()
+            .unwrap();
+
+        xpallet_multisig::GenesisConfig::<Test> {
+            multisigs: vec![
+                (vec![ALICE, BOB, CHARLIE], 2, MultisigKind::Normal),
+                (vec![ALICE, BOB, CHARLIE], 2, MultisigKind::Trustee),
+            ],
+        }
+        .assimilate_storage(&mut storage)
+        .unwrap();
+
+        let mut ext = sp_io::TestExternalities::new(storage);
+        ext.execute_with(|| System::set_block_number(1));
+        ext
+    }
+
+    pub fn build_and_execute(self, test: impl FnOnce()) {
+        let mut ext = self.build();
+        ext.execute_with(test);
+    }
+}
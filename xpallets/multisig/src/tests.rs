@@ -0,0 +1,343 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+use frame_support::{assert_noop, assert_ok, traits::Hooks};
+use sp_core::H256;
+use sp_runtime::{
+    traits::{BlakeTwo256, Hash},
+    DispatchError,
+};
+
+use crate::mock::*;
+use crate::*;
+
+fn t_run_to_block(n: BlockNumber) {
+    for b in System::block_number() + 1..=n {
+        System::set_block_number(b);
+        XMultisig::on_initialize(b);
+    }
+}
+
+/// Proposes `call` by `proposer` and confirms it by `confirmer`, returns the proposal hash.
+fn t_approve(proposer: AccountId, confirmer: AccountId, call: Call) -> H256 {
+    let proposal_hash = BlakeTwo256::hash_of(&call);
+    assert_ok!(XMultisig::propose(
+        Origin::signed(proposer),
+        council(),
+        Box::new(call)
+    ));
+    assert_ok!(XMultisig::confirm(
+        Origin::signed(confirmer),
+        council(),
+        proposal_hash,
+        u64::MAX
+    ));
+    proposal_hash
+}
+
+fn t_owners() -> (Vec<AccountId>, u16) {
+    let info = XMultisig::multisig_of(council()).unwrap();
+    (info.owners, info.threshold)
+}
+
+#[test]
+fn genesis_multisigs_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_eq!(XMultisig::multisig_count(), 2);
+        assert_ne!(council(), trustee());
+        assert_eq!(t_owners(), (vec![ALICE, BOB, CHARLIE], 2));
+        assert_eq!(
+            XMultisig::multisig_of(trustee()).unwrap().kind,
+            MultisigKind::Trustee
+        );
+    });
+}
+
+#[test]
+fn deploy_should_validate_owners_and_threshold() {
+    ExtBuilder::default().build_and_execute(|| {
+        let deploy = |owners: Vec<AccountId>, threshold| {
+            XMultisig::deploy(Origin::root(), owners, threshold, MultisigKind::Normal)
+        };
+        assert_noop!(
+            XMultisig::deploy(Origin::signed(ALICE), vec![ALICE], 1, MultisigKind::Normal),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(deploy(vec![], 1), Error::<Test>::EmptyOwners);
+        assert_noop!(
+            deploy(vec![1, 2, 3, 4, 5, 6], 1),
+            Error::<Test>::TooManyOwners
+        );
+        assert_noop!(deploy(vec![ALICE, ALICE], 1), Error::<Test>::DuplicateOwner);
+        assert_noop!(deploy(vec![ALICE, BOB], 0), Error::<Test>::ZeroThreshold);
+        assert_noop!(deploy(vec![ALICE, BOB], 3), Error::<Test>::ThresholdTooHigh);
+
+        assert_ok!(deploy(vec![ALICE, BOB], 2));
+        let multisig = XMultisig::multisig_account_id(2);
+        assert_eq!(
+            XMultisig::multisig_of(multisig),
+            Some(MultisigInfo {
+                owners: vec![ALICE, BOB],
+                threshold: 2,
+                kind: MultisigKind::Normal,
+            })
+        );
+    });
+}
+
+#[test]
+fn proposal_should_be_executed_once_confirmed() {
+    ExtBuilder::default().build_and_execute(|| {
+        let call = Call::System(frame_system::Call::remark_with_event { remark: vec![1] });
+        let proposal_hash = BlakeTwo256::hash_of(&call);
+
+        assert_noop!(
+            XMultisig::propose(Origin::signed(DAVE), council(), Box::new(call.clone())),
+            Error::<Test>::NotOwner
+        );
+        assert_ok!(XMultisig::propose(
+            Origin::signed(ALICE),
+            council(),
+            Box::new(call.clone())
+        ));
+        assert_noop!(
+            XMultisig::propose(Origin::signed(BOB), council(), Box::new(call)),
+            Error::<Test>::ProposalExists
+        );
+        assert_noop!(
+            XMultisig::confirm(Origin::signed(ALICE), council(), proposal_hash, u64::MAX),
+            Error::<Test>::AlreadyConfirmed
+        );
+        assert_noop!(
+            XMultisig::confirm(Origin::signed(BOB), council(), proposal_hash, 0),
+            Error::<Test>::MaxWeightTooLow
+        );
+        assert_noop!(
+            XMultisig::revoke(Origin::signed(BOB), council(), proposal_hash),
+            Error::<Test>::NotProposer
+        );
+
+        assert_ok!(XMultisig::confirm(
+            Origin::signed(BOB),
+            council(),
+            proposal_hash,
+            u64::MAX
+        ));
+        assert!(XMultisig::proposal_of(council(), proposal_hash).is_none());
+        System::assert_has_event(Event::System(frame_system::Event::Remarked {
+            sender: council(),
+            hash: BlakeTwo256::hash(&[1]),
+        }));
+        System::assert_last_event(Event::XMultisig(crate::Event::Executed(
+            council(),
+            proposal_hash,
+            Ok(()),
+        )));
+    });
+}
+
+#[test]
+fn pending_proposals_should_be_limited() {
+    ExtBuilder::default().build_and_execute(|| {
+        let remark = |i: u8| Call::System(frame_system::Call::remark { remark: vec![i] });
+        for i in 0..MaxProposals::get() as u8 {
+            assert_ok!(XMultisig::propose(
+                Origin::signed(ALICE),
+                council(),
+                Box::new(remark(i))
+            ));
+        }
+        assert_eq!(XMultisig::proposal_count_of(council()), MaxProposals::get());
+        assert_noop!(
+            XMultisig::propose(Origin::signed(BOB), council(), Box::new(remark(10))),
+            Error::<Test>::TooManyProposals
+        );
+
+        // The revoked and the executed proposals free up the slots.
+        assert_ok!(XMultisig::revoke(
+            Origin::signed(ALICE),
+            council(),
+            BlakeTwo256::hash_of(&remark(0))
+        ));
+        assert_ok!(XMultisig::confirm(
+            Origin::signed(BOB),
+            council(),
+            BlakeTwo256::hash_of(&remark(1)),
+            u64::MAX
+        ));
+        assert_eq!(XMultisig::proposal_count_of(council()), 1);
+        assert_ok!(XMultisig::propose(
+            Origin::signed(BOB),
+            council(),
+            Box::new(remark(10))
+        ));
+        assert_eq!(XMultisig::proposal_count_of(council()), 2);
+    });
+}
+
+#[test]
+fn rotate_owner_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        let call = Call::XMultisig(crate::Call::replace_owner {
+            old: CHARLIE,
+            new: DAVE,
+        });
+        let remark = Call::System(frame_system::Call::remark { remark: vec![] });
+        assert_ok!(XMultisig::propose(
+            Origin::signed(CHARLIE),
+            council(),
+            Box::new(remark.clone())
+        ));
+        t_approve(ALICE, BOB, call);
+
+        let enact_at = 1 + OwnerChangeDelay::get();
+        assert_eq!(
+            XMultisig::pending_owner_change_of(council()),
+            Some(PendingOwnerChange {
+                operation: OwnerOperation::ReplaceOwner(CHARLIE, DAVE),
+                enact_at,
+            })
+        );
+        System::assert_has_event(Event::XMultisig(crate::Event::OwnerChangeScheduled(
+            council(),
+            OwnerOperation::ReplaceOwner(CHARLIE, DAVE),
+            enact_at,
+        )));
+
+        // Only one owner change can be pending at a time.
+        assert_noop!(
+            XMultisig::add_owner(Origin::signed(council()), EVE),
+            Error::<Test>::OwnerChangePending
+        );
+        // The owner being replaced can not veto it.
+        assert_noop!(
+            XMultisig::veto_owner_change(Origin::signed(CHARLIE), council()),
+            Error::<Test>::CannotVetoOwnRemoval
+        );
+
+        t_run_to_block(enact_at - 1);
+        assert_eq!(t_owners(), (vec![ALICE, BOB, CHARLIE], 2));
+
+        t_run_to_block(enact_at);
+        assert_eq!(t_owners(), (vec![ALICE, BOB, DAVE], 2));
+        assert!(XMultisig::pending_owner_change_of(council()).is_none());
+        System::assert_last_event(Event::XMultisig(crate::Event::OwnerChangeEnacted(
+            council(),
+            vec![ALICE, BOB, DAVE],
+            2,
+        )));
+
+        // The proposals confirmed by the previous owners are discarded.
+        let remark_hash = BlakeTwo256::hash_of(&remark);
+        assert!(XMultisig::proposal_of(council(), remark_hash).is_none());
+        assert_eq!(XMultisig::proposal_count_of(council()), 0);
+
+        // The new owner takes over.
+        assert_noop!(
+            XMultisig::propose(Origin::signed(CHARLIE), council(), Box::new(remark.clone())),
+            Error::<Test>::NotOwner
+        );
+        assert_ok!(XMultisig::propose(
+            Origin::signed(DAVE),
+            council(),
+            Box::new(remark)
+        ));
+    });
+}
+
+#[test]
+fn veto_should_cancel_owner_change() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_noop!(
+            XMultisig::veto_owner_change(Origin::signed(ALICE), council()),
+            Error::<Test>::NoPendingOwnerChange
+        );
+
+        t_approve(
+            ALICE,
+            BOB,
+            Call::XMultisig(crate::Call::remove_owner { old: CHARLIE }),
+        );
+        assert_noop!(
+            XMultisig::veto_owner_change(Origin::signed(DAVE), council()),
+            Error::<Test>::NotOwner
+        );
+        assert_ok!(XMultisig::veto_owner_change(Origin::signed(BOB), council()));
+        System::assert_last_event(Event::XMultisig(crate::Event::OwnerChangeVetoed(
+            council(),
+            BOB,
+        )));
+        assert!(XMultisig::pending_owner_change_of(council()).is_none());
+
+        t_run_to_block(1 + OwnerChangeDelay::get());
+        assert_eq!(t_owners(), (vec![ALICE, BOB, CHARLIE], 2));
+
+        // Another change can be proposed once vetoed.
+        assert_ok!(XMultisig::add_owner(Origin::signed(council()), DAVE));
+        t_run_to_block(System::block_number() + OwnerChangeDelay::get());
+        assert_eq!(t_owners(), (vec![ALICE, BOB, CHARLIE, DAVE], 2));
+    });
+}
+
+#[test]
+fn owner_change_should_enforce_threshold_bounds() {
+    ExtBuilder::default().build_and_execute(|| {
+        let multisig = Origin::signed(council());
+        assert_noop!(
+            XMultisig::change_threshold(multisig.clone(), 0),
+            Error::<Test>::ZeroThreshold
+        );
+        assert_noop!(
+            XMultisig::change_threshold(multisig.clone(), 4),
+            Error::<Test>::ThresholdTooHigh
+        );
+        assert_noop!(
+            XMultisig::add_owner(multisig.clone(), ALICE),
+            Error::<Test>::DuplicateOwner
+        );
+        assert_noop!(
+            XMultisig::replace_owner(multisig.clone(), ALICE, BOB),
+            Error::<Test>::DuplicateOwner
+        );
+        assert_noop!(
+            XMultisig::remove_owner(multisig.clone(), DAVE),
+            Error::<Test>::OwnerNotFound
+        );
+        assert_noop!(
+            XMultisig::change_threshold(Origin::signed(ALICE), 1),
+            Error::<Test>::NotMultisig
+        );
+
+        // The rejected operation proposed through the multisig fails on execution.
+        let call = Call::XMultisig(crate::Call::change_threshold { threshold: 4 });
+        let proposal_hash = t_approve(ALICE, BOB, call);
+        System::assert_last_event(Event::XMultisig(crate::Event::Executed(
+            council(),
+            proposal_hash,
+            Err(Error::<Test>::ThresholdTooHigh.into()),
+        )));
+        assert!(XMultisig::pending_owner_change_of(council()).is_none());
+
+        // Removing an owner can not leave fewer owners than the threshold.
+        assert_ok!(XMultisig::change_threshold(multisig.clone(), 3));
+        t_run_to_block(1 + OwnerChangeDelay::get());
+        assert_eq!(t_owners(), (vec![ALICE, BOB, CHARLIE], 3));
+        assert_noop!(
+            XMultisig::remove_owner(multisig, CHARLIE),
+            Error::<Test>::ThresholdTooHigh
+        );
+    });
+}
+
+#[test]
+fn trustee_multisig_owners_should_not_be_changed() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_noop!(
+            XMultisig::replace_owner(Origin::signed(trustee()), CHARLIE, DAVE),
+            Error::<Test>::TrusteeMultisig
+        );
+        assert_noop!(
+            XMultisig::change_threshold(Origin::signed(trustee()), 3),
+            Error::<Test>::TrusteeMultisig
+        );
+    });
+}
@@ -0,0 +1,72 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+use sp_runtime::RuntimeDebug;
+use sp_std::prelude::*;
+
+/// The kind of a multisig.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum MultisigKind {
+    /// A general multisig, e.g., the council multisig, whose owners are managed by itself.
+    Normal,
+    /// A multisig of the bridge trustees, whose owners and threshold can not be changed,
+    /// a new one is deployed for the next trustees instead.
+    Trustee,
+}
+
+impl Default for MultisigKind {
+    fn default() -> Self {
+        Self::Normal
+    }
+}
+
+/// The owners and the threshold of a multisig.
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct MultisigInfo<AccountId> {
+    pub owners: Vec<AccountId>,
+    /// The number of the confirmations required to execute a proposal.
+    pub threshold: u16,
+    pub kind: MultisigKind,
+}
+
+/// A proposal of a multisig waiting for the confirmations.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct Proposal<AccountId, Call> {
+    pub call: Call,
+    pub proposer: AccountId,
+    /// The owners who confirmed the proposal, including the proposer.
+    pub confirmations: Vec<AccountId>,
+}
+
+/// An operation changing the owners or the threshold of a multisig.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub enum OwnerOperation<AccountId> {
+    /// Replace the owner `old` with `new`.
+    ReplaceOwner(AccountId, AccountId),
+    /// Add a new owner.
+    AddOwner(AccountId),
+    /// Remove an owner.
+    RemoveOwner(AccountId),
+    /// Change the threshold.
+    ChangeThreshold(u16),
+}
+
+/// An owner operation approved by a multisig, waiting for the veto period to elapse.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct PendingOwnerChange<AccountId, BlockNumber> {
+    pub operation: OwnerOperation<AccountId>,
+    /// The block at which the operation is enacted unless vetoed.
+    pub enact_at: BlockNumber,
+}
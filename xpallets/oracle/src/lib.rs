@@ -0,0 +1,299 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! # Price Oracle Module
+//!
+//! This module provides the prices of the tokens that have no other price source on chain,
+//! e.g., the newly listed tokens without any liquidity in the spot market.
+//!
+//! A set of feeders managed by root submits the price observations of the tokens, only the
+//! latest observation of each feeder is kept. The oracle price of a token is the median of
+//! the observations submitted by the current feeders within the latest `StaleBlocks` blocks.
+//!
+//! The price is measured in PCX, in the same scale as the fixed mining power of the assets.
+//!
+//! The observations out of the `ObservationRetention` window, or submitted by the accounts
+//! no longer feeders, are removed gradually in `on_initialize`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+mod types;
+
+use sp_std::prelude::*;
+
+use sp_runtime::{traits::Saturating, Percent};
+
+use frame_support::{dispatch::DispatchResult, ensure, traits::Get, weights::Weight};
+use frame_system::{ensure_root, ensure_signed};
+
+use chainx_primitives::AssetId;
+
+pub use self::types::PriceObservation;
+pub use pallet::*;
+
+/// The price of a token measured in PCX.
+pub type OraclePrice = u128;
+
+/// The maximum number of the observations checked for removal in a block.
+pub const MAX_OBSERVATIONS_CHECKED_PER_BLOCK: u32 = 32;
+
+#[frame_support::pallet]
+pub mod pallet {
+    use super::*;
+    use frame_support::pallet_prelude::*;
+    use frame_system::pallet_prelude::*;
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        /// The overarching event type.
+        type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+        /// The maximum number of the feeders.
+        #[pallet::constant]
+        type MaxFeeders: Get<u32>;
+
+        /// The observations older than this number of blocks are discarded.
+        #[pallet::constant]
+        type StaleBlocks: Get<Self::BlockNumber>;
+
+        /// The observations older than this number of blocks are removed from the storage,
+        /// which should be no less than `StaleBlocks`.
+        #[pallet::constant]
+        type ObservationRetention: Get<Self::BlockNumber>;
+
+        /// `OraclePriceChanged` is emitted once the median moves beyond this ratio of the
+        /// previously reported one.
+        #[pallet::constant]
+        type PriceChangeThreshold: Get<Percent>;
+    }
+
+    #[pallet::pallet]
+    #[pallet::generate_store(pub(crate) trait Store)]
+    #[pallet::without_storage_info]
+    pub struct Pallet<T>(PhantomData<T>);
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
+            Self::prune_observations(MAX_OBSERVATIONS_CHECKED_PER_BLOCK)
+        }
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Submit the price of a token.
+        ///
+        /// The origin must be one of the feeders, the previous observation of the feeder
+        /// is replaced.
+        #[pallet::weight(T::DbWeight::get().reads_writes(
+            T::MaxFeeders::get().saturating_add(3).into(),
+            2,
+        ))]
+        pub fn feed_price(
+            origin: OriginFor<T>,
+            #[pallet::compact] token: AssetId,
+            #[pallet::compact] price: OraclePrice,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(Self::feeders().contains(&who), Error::<T>::NotFeeder);
+            ensure!(price > 0, Error::<T>::ZeroPrice);
+
+            Observations::<T>::insert(
+                token,
+                &who,
+                PriceObservation {
+                    price,
+                    fed_at: <frame_system::Pallet<T>>::block_number(),
+                },
+            );
+            Self::deposit_event(Event::<T>::PriceFed(who, token, price));
+
+            Self::note_oracle_price(token);
+            Ok(())
+        }
+
+        /// Set the feeders.
+        ///
+        /// This is a root-only operation.
+        #[pallet::weight(
+            T::DbWeight::get()
+                .writes(1)
+                .saturating_add((1_000_000 as Weight).saturating_mul(feeders.len() as Weight))
+        )]
+        pub fn set_feeders(origin: OriginFor<T>, feeders: Vec<T::AccountId>) -> DispatchResult {
+            ensure_root(origin)?;
+            ensure!(
+                feeders.len() <= T::MaxFeeders::get() as usize,
+                Error::<T>::TooManyFeeders
+            );
+            ensure!(
+                feeders
+                    .iter()
+                    .enumerate()
+                    .all(|(i, feeder)| !feeders[..i].contains(feeder)),
+                Error::<T>::DuplicateFeeder
+            );
+
+            Feeders::<T>::put(&feeders);
+            Self::deposit_event(Event::<T>::FeedersUpdated(feeders));
+            Ok(())
+        }
+    }
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(crate) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// A feeder submitted the price of a token. [feeder, token, price]
+        PriceFed(T::AccountId, AssetId, OraclePrice),
+        /// The oracle price of a token moved beyond the threshold. [token, previous, current]
+        OraclePriceChanged(AssetId, Option<OraclePrice>, OraclePrice),
+        /// The feeders were updated. [feeders]
+        FeedersUpdated(Vec<T::AccountId>),
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// The sender is not a feeder.
+        NotFeeder,
+        /// The price can not be zero.
+        ZeroPrice,
+        /// The number of the feeders exceeds `MaxFeeders`.
+        TooManyFeeders,
+        /// The feeders contain duplicate accounts.
+        DuplicateFeeder,
+    }
+
+    /// The accounts allowed to submit the prices.
+    #[pallet::storage]
+    #[pallet::getter(fn feeders)]
+    pub type Feeders<T: Config> = StorageValue<_, Vec<T::AccountId>, ValueQuery>;
+
+    /// The latest price observation of each token submitted by each feeder.
+    #[pallet::storage]
+    #[pallet::getter(fn observation_of)]
+    pub type Observations<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        AssetId,
+        Blake2_128Concat,
+        T::AccountId,
+        PriceObservation<OraclePrice, T::BlockNumber>,
+    >;
+
+    /// The oracle price of each token reported by the latest `OraclePriceChanged`.
+    #[pallet::storage]
+    #[pallet::getter(fn reported_price_of)]
+    pub type ReportedPrices<T: Config> = StorageMap<_, Twox64Concat, AssetId, OraclePrice>;
+
+    /// The storage key of the last observation checked for removal.
+    #[pallet::storage]
+    pub(crate) type PruneCursor<T: Config> = StorageValue<_, Vec<u8>>;
+}
+
+impl<T: Config> Pallet<T> {
+    /// Returns the median of the fresh observations of `token` submitted by the current
+    /// feeders, `None` if there is no such observation.
+    ///
+    /// The median of an even number of observations is the mean of the middle two.
+    pub fn oracle_price(token: AssetId) -> Option<OraclePrice> {
+        let now = <frame_system::Pallet<T>>::block_number();
+        let stale_blocks = T::StaleBlocks::get();
+        let prices = Self::feeders()
+            .iter()
+            .filter_map(|feeder| Self::observation_of(token, feeder))
+            .filter(|observation| now.saturating_sub(observation.fed_at) <= stale_blocks)
+            .map(|observation| observation.price)
+            .collect::<Vec<_>>();
+        median(prices)
+    }
+
+    /// Removes the observations out of the retention window or submitted by the accounts
+    /// no longer feeders, checking at most `limit` observations from `PruneCursor`.
+    ///
+    /// Returns the consumed weight.
+    pub(crate) fn prune_observations(limit: u32) -> Weight {
+        let now = <frame_system::Pallet<T>>::block_number();
+        let retention = T::ObservationRetention::get();
+        let feeders = Self::feeders();
+        let cursor = PruneCursor::<T>::get();
+        let mut iter = match cursor {
+            Some(ref cursor) => Observations::<T>::iter_from(cursor.clone()),
+            None => Observations::<T>::iter(),
+        };
+
+        let mut checked = 0u32;
+        let mut outdated = Vec::new();
+        let mut next_cursor = None;
+        while checked < limit {
+            let (token, feeder, observation) = match iter.next() {
+                Some(entry) => entry,
+                None => {
+                    next_cursor = None;
+                    break;
+                }
+            };
+            checked += 1;
+            next_cursor = Some(Observations::<T>::hashed_key_for(token, &feeder));
+            if !feeders.contains(&feeder) || now.saturating_sub(observation.fed_at) > retention {
+                outdated.push((token, feeder));
+            }
+        }
+
+        let removed = outdated.len() as u64;
+        for (token, feeder) in outdated {
+            Observations::<T>::remove(token, feeder);
+        }
+        let cursor_write = match (cursor, next_cursor) {
+            (None, None) => 0,
+            (_, Some(next_cursor)) => {
+                PruneCursor::<T>::put(next_cursor);
+                1
+            }
+            (Some(_), None) => {
+                PruneCursor::<T>::kill();
+                1
+            }
+        };
+
+        T::DbWeight::get().reads_writes(2 + u64::from(checked), removed + cursor_write)
+    }
+
+    /// Emits `OraclePriceChanged` if the oracle price of `token` moved beyond the threshold
+    /// since it was reported last time.
+    fn note_oracle_price(token: AssetId) {
+        let current = match Self::oracle_price(token) {
+            Some(price) => price,
+            None => return,
+        };
+        let previous = Self::reported_price_of(token);
+        let changed = match previous {
+            Some(previous) => {
+                let delta = current.max(previous) - current.min(previous);
+                delta > T::PriceChangeThreshold::get() * previous
+            }
+            None => true,
+        };
+        if changed {
+            ReportedPrices::<T>::insert(token, current);
+            Self::deposit_event(Event::<T>::OraclePriceChanged(token, previous, current));
+        }
+    }
+}
+
+/// Returns the median of `prices`.
+fn median(mut prices: Vec<OraclePrice>) -> Option<OraclePrice> {
+    if prices.is_empty() {
+        return None;
+    }
+    prices.sort_unstable();
+    let mid = prices.len() / 2;
+    if prices.len() % 2 == 1 {
+        Some(prices[mid])
+    } else {
+        let (low, high) = (prices[mid - 1], prices[mid]);
+        Some(low + (high - low) / 2)
+    }
+}
@@ -0,0 +1,180 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+use frame_support::{assert_noop, assert_ok, traits::OnInitialize};
+use sp_runtime::DispatchError;
+
+use crate::mock::*;
+use crate::*;
+
+fn price_changes() -> Vec<(AssetId, Option<OraclePrice>, OraclePrice)> {
+    System::events()
+        .into_iter()
+        .filter_map(|record| match record.event {
+            crate::mock::Event::XOracle(crate::Event::OraclePriceChanged(
+                token,
+                previous,
+                current,
+            )) => Some((token, previous, current)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn t_feed(who: AccountId, price: OraclePrice) {
+    assert_ok!(XOracle::feed_price(Origin::signed(who), X_BTC, price));
+}
+
+#[test]
+fn set_feeders_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_noop!(
+            XOracle::set_feeders(Origin::signed(ALICE), vec![ALICE]),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            XOracle::set_feeders(Origin::root(), vec![ALICE, BOB, ALICE]),
+            Error::<Test>::DuplicateFeeder
+        );
+        assert_noop!(
+            XOracle::set_feeders(Origin::root(), vec![1, 2, 3, 4, 5]),
+            Error::<Test>::TooManyFeeders
+        );
+
+        assert_ok!(XOracle::set_feeders(Origin::root(), vec![ALICE, BOB]));
+        assert_eq!(XOracle::feeders(), vec![ALICE, BOB]);
+    });
+}
+
+#[test]
+fn feed_price_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XOracle::set_feeders(Origin::root(), vec![ALICE]));
+
+        assert_noop!(
+            XOracle::feed_price(Origin::signed(BOB), X_BTC, 100),
+            Error::<Test>::NotFeeder
+        );
+        assert_noop!(
+            XOracle::feed_price(Origin::signed(ALICE), X_BTC, 0),
+            Error::<Test>::ZeroPrice
+        );
+
+        t_feed(ALICE, 100);
+        assert_eq!(
+            XOracle::observation_of(X_BTC, ALICE),
+            Some(PriceObservation {
+                price: 100,
+                fed_at: 1
+            })
+        );
+        assert_eq!(XOracle::oracle_price(X_BTC), Some(100));
+        assert_eq!(XOracle::oracle_price(X_BTC + 1), None);
+    });
+}
+
+#[test]
+fn oracle_price_should_be_the_median() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XOracle::set_feeders(
+            Origin::root(),
+            vec![ALICE, BOB, CHARLIE, DAVE]
+        ));
+
+        t_feed(ALICE, 100);
+        t_feed(BOB, 300);
+        t_feed(CHARLIE, 110);
+        assert_eq!(XOracle::oracle_price(X_BTC), Some(110));
+
+        t_feed(DAVE, 121);
+        assert_eq!(XOracle::oracle_price(X_BTC), Some(115));
+
+        // The observations of the removed feeders are ignored.
+        assert_ok!(XOracle::set_feeders(Origin::root(), vec![ALICE, BOB]));
+        assert_eq!(XOracle::oracle_price(X_BTC), Some(200));
+    });
+}
+
+#[test]
+fn stale_observations_should_be_discarded() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XOracle::set_feeders(Origin::root(), vec![ALICE, BOB]));
+
+        t_feed(ALICE, 100);
+        System::set_block_number(6);
+        t_feed(BOB, 200);
+        assert_eq!(XOracle::oracle_price(X_BTC), Some(150));
+
+        System::set_block_number(11);
+        assert_eq!(XOracle::oracle_price(X_BTC), Some(150));
+
+        System::set_block_number(12);
+        assert_eq!(XOracle::oracle_price(X_BTC), Some(200));
+
+        System::set_block_number(17);
+        assert_eq!(XOracle::oracle_price(X_BTC), None);
+    });
+}
+
+#[test]
+fn oracle_price_changed_should_respect_threshold() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XOracle::set_feeders(Origin::root(), vec![ALICE]));
+
+        t_feed(ALICE, 1000);
+        assert_eq!(price_changes(), vec![(X_BTC, None, 1000)]);
+
+        // Moving by exactly 5% is not reported.
+        t_feed(ALICE, 1050);
+        t_feed(ALICE, 950);
+        assert_eq!(price_changes().len(), 1);
+        assert_eq!(XOracle::reported_price_of(X_BTC), Some(1000));
+
+        t_feed(ALICE, 1051);
+        assert_eq!(price_changes()[1], (X_BTC, Some(1000), 1051));
+        assert_eq!(XOracle::reported_price_of(X_BTC), Some(1051));
+    });
+}
+
+#[test]
+fn outdated_observations_should_be_pruned() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XOracle::set_feeders(Origin::root(), vec![ALICE, BOB]));
+        t_feed(ALICE, 100);
+        t_feed(BOB, 200);
+
+        // The observations of the removed feeders are pruned.
+        assert_ok!(XOracle::set_feeders(Origin::root(), vec![ALICE]));
+        XOracle::on_initialize(System::block_number());
+        assert!(XOracle::observation_of(X_BTC, ALICE).is_some());
+        assert!(XOracle::observation_of(X_BTC, BOB).is_none());
+
+        // The observations are kept within the retention window.
+        System::set_block_number(21);
+        XOracle::on_initialize(System::block_number());
+        assert!(XOracle::observation_of(X_BTC, ALICE).is_some());
+
+        System::set_block_number(22);
+        XOracle::on_initialize(System::block_number());
+        assert!(XOracle::observation_of(X_BTC, ALICE).is_none());
+        assert!(PruneCursor::<Test>::get().is_none());
+    });
+}
+
+#[test]
+fn pruning_should_resume_from_cursor() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XOracle::set_feeders(Origin::root(), vec![ALICE]));
+        for token in 0..3 {
+            assert_ok!(XOracle::feed_price(Origin::signed(ALICE), token, 100));
+        }
+        assert_ok!(XOracle::set_feeders(Origin::root(), vec![]));
+
+        XOracle::prune_observations(2);
+        assert_eq!(Observations::<Test>::iter().count(), 1);
+        assert!(PruneCursor::<Test>::get().is_some());
+
+        XOracle::prune_observations(2);
+        assert_eq!(Observations::<Test>::iter().count(), 0);
+        assert!(PruneCursor::<Test>::get().is_none());
+    });
+}
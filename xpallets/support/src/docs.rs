@@ -0,0 +1,71 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Static documentation of the pallet events and errors.
+//!
+//! Each pallet declares its registry with [`pallet_docs!`](crate::pallet_docs), the runtime
+//! collects them and serves the result via the `chainx_getRuntimeDocs` rpc.
+
+/// The documentation of the events and errors of a pallet, as `(variant, doc)` pairs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PalletDocs {
+    /// The name of the pallet in the runtime.
+    pub name: &'static str,
+    pub events: &'static [(&'static str, &'static str)],
+    pub errors: &'static [(&'static str, &'static str)],
+}
+
+/// Declares the `PALLET_DOCS` constant of a pallet.
+///
+/// ```ignore
+/// xpallet_support::pallet_docs! {
+///     "XAssets",
+///     events {
+///         Issued => "New balances of an asset were issued. [asset_id, receiver, amount]",
+///     }
+///     errors {
+///         InvalidAsset => "Got and Invalid Asset",
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! pallet_docs {
+    (
+        $name:literal,
+        events { $( $event:ident => $event_doc:literal ),* $(,)? }
+        errors { $( $error:ident => $error_doc:literal ),* $(,)? }
+    ) => {
+        /// The documentation of the events and errors of this pallet.
+        pub const PALLET_DOCS: $crate::docs::PalletDocs = $crate::docs::PalletDocs {
+            name: $name,
+            events: &[ $( (stringify!($event), $event_doc) ),* ],
+            errors: &[ $( (stringify!($error), $error_doc) ),* ],
+        };
+    };
+}
+
+/// Returns the variants of the enum `T` that are missing in `docs`, and the entries of `docs`
+/// that are not variants of `T`.
+#[cfg(feature = "std")]
+pub fn undocumented_variants<T: scale_info::TypeInfo>(
+    docs: &[(&'static str, &'static str)],
+) -> (Vec<String>, Vec<String>) {
+    let variants = match T::type_info().type_def() {
+        scale_info::TypeDef::Variant(def) => def
+            .variants()
+            .iter()
+            .map(|v| v.name().to_string())
+            .collect::<Vec<_>>(),
+        _ => Vec::new(),
+    };
+    let missing = variants
+        .iter()
+        .filter(|v| !docs.iter().any(|(name, _)| name == v))
+        .cloned()
+        .collect();
+    let unknown = docs
+        .iter()
+        .filter(|(name, _)| !variants.iter().any(|v| v == name))
+        .map(|(name, _)| name.to_string())
+        .collect();
+    (missing, unknown)
+}
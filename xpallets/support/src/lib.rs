@@ -7,6 +7,7 @@ extern crate alloc;
 #[cfg(not(feature = "std"))]
 use alloc::{format, string::String};
 
+pub mod docs;
 pub mod traits;
 
 /// Try to convert a slice of bytes to a string.
@@ -51,6 +52,24 @@ pub fn try_addr<S: AsRef<[u8]>>(src: S) -> String {
     }
 }
 
+/// The number of the leading and trailing bytes kept by [`hash_strip`].
+const HASH_STRIP_BYTES: usize = 4;
+
+/// Converts a hash to a short hex string keeping only its first and last 4 bytes,
+/// e.g. `0x01020304…1d1e1f20`, the slices no longer than 8 bytes are converted as a whole.
+#[inline]
+pub fn hash_strip<S: AsRef<[u8]>>(src: S) -> String {
+    let src = src.as_ref();
+    if src.len() <= 2 * HASH_STRIP_BYTES {
+        return hex(src);
+    }
+    format!(
+        "0x{}…{}",
+        hex::encode(&src[..HASH_STRIP_BYTES]),
+        hex::encode(&src[src.len() - HASH_STRIP_BYTES..])
+    )
+}
+
 /// Converts a slice of bytes to a string.
 #[inline]
 fn str(s: &[u8]) -> String {
@@ -62,3 +81,29 @@ fn str(s: &[u8]) -> String {
 fn hex(s: &[u8]) -> String {
     format!("0x{}", hex::encode(s))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_strip_should_keep_both_ends() {
+        let hash = (1u8..=32).collect::<Vec<_>>();
+        assert_eq!(hash_strip(&hash), "0x01020304…1d1e1f20");
+
+        // Same as the full hex string with the middle omitted.
+        let full = hex(&hash);
+        assert_eq!(
+            hash_strip(&hash),
+            format!("{}…{}", &full[..10], &full[full.len() - 8..])
+        );
+
+        // The short slices are kept as a whole.
+        assert_eq!(hash_strip([1u8, 2, 3]), "0x010203");
+        assert_eq!(
+            hash_strip((1u8..=8).collect::<Vec<_>>()),
+            "0x0102030405060708"
+        );
+        assert_eq!(hash_strip([]), "0x");
+    }
+}
@@ -1,5 +1,7 @@
 // Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
 
+use sp_std::collections::btree_map::BTreeMap;
+
 pub trait MultisigAddressFor<AccountId> {
     fn calc_multisig(accounts: &[AccountId], threshold: u16) -> AccountId;
 }
@@ -18,6 +20,9 @@ pub trait Validator<AccountId> {
     fn is_validator(who: &AccountId) -> bool;
 
     fn validator_for(name: &[u8]) -> Option<AccountId>;
+
+    /// Returns the number of blocks `who` authored in the latest `sessions` ended sessions.
+    fn recent_authored_blocks(who: &AccountId, sessions: u32) -> u32;
 }
 
 impl<AccountId> Validator<AccountId> for () {
@@ -28,6 +33,10 @@ impl<AccountId> Validator<AccountId> for () {
     fn validator_for(_: &[u8]) -> Option<AccountId> {
         None
     }
+
+    fn recent_authored_blocks(_: &AccountId, _: u32) -> u32 {
+        0
+    }
 }
 
 /// This trait provides a simple way to get the treasury account.
@@ -40,3 +49,37 @@ impl<AccountId> TreasuryAccount<AccountId> for () {
         None
     }
 }
+
+/// The native balance of an account held by the modules, which is still in the free
+/// balance but not spendable.
+pub trait NativeHolds<AccountId, Balance> {
+    fn native_held(who: &AccountId) -> Balance;
+
+    /// Holds `value` of the free balance of `who`, which has been checked by the caller.
+    fn hold_native(who: &AccountId, value: Balance);
+
+    /// Releases at most `value` of the native balance of `who` held before.
+    fn release_native(who: &AccountId, value: Balance);
+}
+
+impl<AccountId, Balance: Default> NativeHolds<AccountId, Balance> for () {
+    fn native_held(_: &AccountId) -> Balance {
+        Default::default()
+    }
+
+    fn hold_native(_: &AccountId, _: Balance) {}
+
+    fn release_native(_: &AccountId, _: Balance) {}
+}
+
+/// Balance maps keyed by the type of the balances, where an absent key means zero.
+pub trait BalanceMap<K, V> {
+    /// Returns the balance of `key`, or zero if it's absent.
+    fn get_or_zero(&self, key: &K) -> V;
+}
+
+impl<K: Ord, V: Copy + Default> BalanceMap<K, V> for BTreeMap<K, V> {
+    fn get_or_zero(&self, key: &K) -> V {
+        self.get(key).copied().unwrap_or_default()
+    }
+}
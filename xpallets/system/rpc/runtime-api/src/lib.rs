@@ -0,0 +1,150 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Runtime API definition for the chain level information of ChainX.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::too_many_arguments, clippy::unnecessary_mut_passed)]
+
+use codec::{Decode, Encode};
+use sp_runtime::{traits::Block as BlockT, RuntimeDebug};
+use sp_std::vec::Vec;
+
+pub use chainx_primitives::{AccountId, Balance, BlockNumber, Decimals};
+pub use xp_assets_registrar::Chain;
+pub use xp_protocol::NetworkType;
+pub use xp_type_registry::TypeRegistry;
+pub use xpallet_system::activity::{
+    collect_account_activity, AccountActivity, BlockExtrinsic, Involvement,
+};
+
+use xpallet_support::docs::PalletDocs;
+
+/// The chain properties read from the runtime state.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+pub struct RuntimeProperties {
+    /// Decimals of PCX in the asset registry.
+    pub pcx_precision: Decimals,
+    /// The chains having registered assets, along with the number of their assets.
+    pub chains: Vec<(Chain, u32)>,
+    /// The bitcoin network the bridge is targeting.
+    pub bitcoin_network: NetworkType,
+    /// The confirmations required by the bitcoin bridge.
+    pub bitcoin_confirmation_number: u32,
+}
+
+/// The documentation of the events and errors of a runtime module.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+pub struct ModuleDocs {
+    /// The name of the module in the runtime.
+    pub module: Vec<u8>,
+    /// The `(variant, doc)` pairs of the events.
+    pub events: Vec<(Vec<u8>, Vec<u8>)>,
+    /// The `(variant, doc)` pairs of the errors.
+    pub errors: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl From<PalletDocs> for ModuleDocs {
+    fn from(docs: PalletDocs) -> Self {
+        let to_vec = |items: &[(&str, &str)]| -> Vec<(Vec<u8>, Vec<u8>)> {
+            items
+                .iter()
+                .map(|(name, doc)| (name.as_bytes().to_vec(), doc.as_bytes().to_vec()))
+                .collect()
+        };
+        Self {
+            module: docs.name.as_bytes().to_vec(),
+            events: to_vec(docs.events),
+            errors: to_vec(docs.errors),
+        }
+    }
+}
+
+/// The breakdown of the total issuance of PCX.
+///
+/// The jackpots and the council are free balances of the specific accounts, which
+/// are not counted in `free`.
+#[derive(PartialEq, Eq, Clone, Default, Encode, Decode, RuntimeDebug)]
+pub struct SupplyBreakdown {
+    pub total_issuance: Balance,
+    /// The remainder of the total issuance, including the reserves not tracked here,
+    /// e.g. the deposits of the governance modules.
+    pub free: Balance,
+    /// Locked by the bonds in Staking.
+    pub reserved_staking: Balance,
+    /// Locked by the unbonded chunks pending withdrawal in Staking.
+    pub reserved_revocation: Balance,
+    /// Reserved by the open orders in Spot.
+    pub reserved_dex_spot: Balance,
+    /// Reserved by the pending withdrawals.
+    pub reserved_withdrawal: Balance,
+    /// The reward pots of the validators and the mining assets.
+    pub jackpots_total: Balance,
+    /// The treasury account managed by the council.
+    pub council: Balance,
+}
+
+impl SupplyBreakdown {
+    /// Creates the breakdown given the total issuance and the buckets, `free` is the remainder.
+    pub fn new(
+        total_issuance: Balance,
+        reserved_staking: Balance,
+        reserved_revocation: Balance,
+        reserved_dex_spot: Balance,
+        reserved_withdrawal: Balance,
+        jackpots_total: Balance,
+        council: Balance,
+    ) -> Self {
+        let mut breakdown = Self {
+            total_issuance,
+            free: 0,
+            reserved_staking,
+            reserved_revocation,
+            reserved_dex_spot,
+            reserved_withdrawal,
+            jackpots_total,
+            council,
+        };
+        breakdown.free = total_issuance.saturating_sub(breakdown.buckets_total());
+        breakdown
+    }
+
+    /// Returns the sum of all the buckets except `free`.
+    pub fn buckets_total(&self) -> Balance {
+        [
+            self.reserved_staking,
+            self.reserved_revocation,
+            self.reserved_dex_spot,
+            self.reserved_withdrawal,
+            self.jackpots_total,
+            self.council,
+        ]
+        .iter()
+        .fold(0, |acc: Balance, x| acc.saturating_add(*x))
+    }
+}
+
+sp_api::decl_runtime_apis! {
+    /// Version 2 adds `chain_properties`.
+    /// Version 3 adds `docs_registry` and `pcx_supply_breakdown`.
+    /// Version 4 adds `account_activity`.
+    #[api_version(4)]
+    pub trait XSystemApi {
+        /// Returns the SCALE encoded `TypeRegistry` of the ChainX specific types.
+        fn chainx_type_registry() -> Vec<u8>;
+
+        /// Returns the chain properties stored on chain.
+        fn chain_properties() -> RuntimeProperties;
+
+        /// Returns the documentation of the events and errors of the documented modules.
+        fn docs_registry() -> Vec<ModuleDocs>;
+
+        /// Returns the breakdown of the total issuance of PCX.
+        ///
+        /// NOTE: all the stakers and the spot reservations are iterated.
+        fn pcx_supply_breakdown() -> SupplyBreakdown;
+
+        /// Returns the activity of `who` in the block it's called at, given the extrinsics
+        /// of that block, see `xpallet_system::Pallet::account_activity`.
+        fn account_activity(who: AccountId, extrinsics: Vec<<Block as BlockT>::Extrinsic>) -> Vec<AccountActivity>;
+    }
+}
@@ -0,0 +1,675 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! RPC interface for the chain level information of ChainX.
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use codec::Decode;
+use jsonrpc_derive::rpc;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use sc_client_api::BlockBackend;
+use sc_rpc_api::DenyUnsafe;
+use sp_api::{ApiExt, Core, ProvideRuntimeApi, RuntimeVersion};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{
+    generic::BlockId,
+    traits::{Block as BlockT, Header as HeaderT, UniqueSaturatedInto, Zero},
+};
+
+use xp_rpc::{
+    decode_error_into_rpc_err, runtime_error_into_rpc_err, unsupported_api_rpc_err, Result,
+    RpcBalance,
+};
+
+use xpallet_system_rpc_runtime_api::{
+    AccountActivity, AccountId, Balance, BlockNumber, Chain, Decimals, Involvement, ModuleDocs,
+    NetworkType, RuntimeProperties, SupplyBreakdown, TypeRegistry, XSystemApi as XSystemRuntimeApi,
+};
+
+/// The maximum number of the blocks scanned by `chainx_getRecentActivity`.
+pub const MAX_ACTIVITY_BLOCKS_BACK: u32 = 2048;
+
+/// The maximum number of the entries returned by `chainx_getRecentActivity`.
+pub const MAX_ACTIVITY_ENTRIES: u32 = 256;
+
+/// The properties of the chain spec, e.g. `ss58Format` and `network`.
+pub type Properties = Map<String, Value>;
+
+/// The number of the registered assets of a chain.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainAssets {
+    pub chain: Chain,
+    pub asset_count: u32,
+}
+
+/// The chain properties the wallets need to know.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainProperties<BlockHash> {
+    pub genesis_hash: BlockHash,
+    /// The network type of the chain spec, `None` if not specified.
+    pub network_type: Option<NetworkType>,
+    /// The ss58 address type of the chain spec, `None` if not specified.
+    pub address_type: Option<u16>,
+    /// The bitcoin network the bridge is targeting.
+    pub bitcoin_type: NetworkType,
+    pub bitcoin_confirmation_number: u32,
+    pub pcx_precision: Decimals,
+    pub chains: Vec<ChainAssets>,
+    pub spec_version: u32,
+    pub impl_version: u32,
+}
+
+impl<BlockHash> ChainProperties<BlockHash> {
+    /// Assembles the chain properties from the chain spec and the runtime.
+    pub fn new(
+        genesis_hash: BlockHash,
+        properties: &Properties,
+        version: &RuntimeVersion,
+        runtime_properties: RuntimeProperties,
+    ) -> Self {
+        Self {
+            genesis_hash,
+            network_type: properties
+                .get("network")
+                .and_then(|network| serde_json::from_value(network.clone()).ok()),
+            address_type: properties
+                .get("ss58Format")
+                .and_then(Value::as_u64)
+                .map(|address_type| address_type as u16),
+            bitcoin_type: runtime_properties.bitcoin_network,
+            bitcoin_confirmation_number: runtime_properties.bitcoin_confirmation_number,
+            pcx_precision: runtime_properties.pcx_precision,
+            chains: runtime_properties
+                .chains
+                .into_iter()
+                .map(|(chain, asset_count)| ChainAssets { chain, asset_count })
+                .collect(),
+            spec_version: version.spec_version,
+            impl_version: version.impl_version,
+        }
+    }
+}
+
+/// The documentation of the events and errors of a module, keyed by the variant name.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuntimeModuleDocs {
+    pub events: BTreeMap<String, String>,
+    pub errors: BTreeMap<String, String>,
+}
+
+/// The documentation of the runtime, keyed by the module name.
+pub type RuntimeDocs = BTreeMap<String, RuntimeModuleDocs>;
+
+/// Groups the documentation returned by the runtime by module.
+pub fn group_runtime_docs(docs: Vec<ModuleDocs>) -> RuntimeDocs {
+    let to_map = |items: Vec<(Vec<u8>, Vec<u8>)>| -> BTreeMap<String, String> {
+        items
+            .into_iter()
+            .map(|(name, doc)| {
+                (
+                    String::from_utf8_lossy(&name).into_owned(),
+                    String::from_utf8_lossy(&doc).into_owned(),
+                )
+            })
+            .collect()
+    };
+    docs.into_iter()
+        .map(|module| {
+            (
+                String::from_utf8_lossy(&module.module).into_owned(),
+                RuntimeModuleDocs {
+                    events: to_map(module.events),
+                    errors: to_map(module.errors),
+                },
+            )
+        })
+        .collect()
+}
+
+/// The breakdown of the total issuance of PCX, see [`SupplyBreakdown`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PcxSupply {
+    pub total_issuance: RpcBalance<Balance>,
+    pub free: RpcBalance<Balance>,
+    pub reserved_staking: RpcBalance<Balance>,
+    pub reserved_revocation: RpcBalance<Balance>,
+    pub reserved_dex_spot: RpcBalance<Balance>,
+    pub reserved_withdrawal: RpcBalance<Balance>,
+    pub jackpots_total: RpcBalance<Balance>,
+    pub council: RpcBalance<Balance>,
+}
+
+impl From<SupplyBreakdown> for PcxSupply {
+    fn from(breakdown: SupplyBreakdown) -> Self {
+        Self {
+            total_issuance: breakdown.total_issuance.into(),
+            free: breakdown.free.into(),
+            reserved_staking: breakdown.reserved_staking.into(),
+            reserved_revocation: breakdown.reserved_revocation.into(),
+            reserved_dex_spot: breakdown.reserved_dex_spot.into(),
+            reserved_withdrawal: breakdown.reserved_withdrawal.into(),
+            jackpots_total: breakdown.jackpots_total.into(),
+            council: breakdown.council.into(),
+        }
+    }
+}
+
+/// An extrinsic, or the block hooks, involving an account.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityEntry<BlockHash> {
+    pub block_number: BlockNumber,
+    pub block_hash: BlockHash,
+    /// `None` for the events emitted by the block hooks.
+    pub extrinsic_index: Option<u32>,
+    /// The `Pallet.call` name of the extrinsic, empty for the block hooks.
+    pub call: String,
+    /// One of `signer`, `counterparty` and `eventParticipant`.
+    pub involvement: String,
+    pub summary: String,
+}
+
+impl<BlockHash> ActivityEntry<BlockHash> {
+    /// Renders the activity in the block `block_number`.
+    pub fn new(
+        block_number: BlockNumber,
+        block_hash: BlockHash,
+        activity: AccountActivity,
+    ) -> Self {
+        let involvement = match activity.involvement {
+            Involvement::Signer => "signer",
+            Involvement::Counterparty => "counterparty",
+            Involvement::EventParticipant => "eventParticipant",
+        };
+        let call = String::from_utf8_lossy(&activity.call).into_owned();
+        let status = match activity.success {
+            Some(true) => "succeeded",
+            Some(false) => "failed",
+            None => "block hooks",
+        };
+        let summary = format!(
+            "{} ({}), {} event(s) involving the account",
+            if call.is_empty() { "-" } else { &call },
+            status,
+            activity.events
+        );
+        Self {
+            block_number,
+            block_hash,
+            extrinsic_index: activity.extrinsic_index,
+            call,
+            involvement: involvement.into(),
+            summary,
+        }
+    }
+}
+
+/// The best-effort activity of an account in the latest blocks.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentActivity<BlockHash> {
+    /// The entries from the latest to the earliest.
+    pub entries: Vec<ActivityEntry<BlockHash>>,
+    pub scanned_blocks: u32,
+    /// True if the scan stopped early, due to the `limit`, or a block whose body is pruned
+    /// or whose runtime can not report the activity.
+    pub truncated: bool,
+}
+
+/// XSystem RPC methods.
+#[rpc]
+pub trait XSystemApi<BlockHash> {
+    /// Get the registry of the ChainX specific types for the client codegen.
+    #[rpc(name = "chainx_getTypeRegistry")]
+    fn type_registry(&self, at: Option<BlockHash>) -> Result<TypeRegistry>;
+
+    /// Get the properties of the chain, e.g. the address type and the bitcoin network.
+    #[rpc(name = "chainx_getChainProperties")]
+    fn chain_properties(&self, at: Option<BlockHash>) -> Result<ChainProperties<BlockHash>>;
+
+    /// Get the documentation of the events and errors, grouped by module.
+    #[rpc(name = "chainx_getRuntimeDocs")]
+    fn runtime_docs(&self, at: Option<BlockHash>) -> Result<RuntimeDocs>;
+
+    /// Get the breakdown of the total issuance of PCX, e.g. the staked and the reserved.
+    #[rpc(name = "chainx_getPCXSupply")]
+    fn pcx_supply(&self, at: Option<BlockHash>) -> Result<PcxSupply>;
+
+    /// Get the activity of an account by walking backwards from the best block.
+    ///
+    /// This is a best-effort view for the wallets without an indexer, at most
+    /// `MAX_ACTIVITY_BLOCKS_BACK` blocks are scanned and `MAX_ACTIVITY_ENTRIES` entries
+    /// are returned whatever is requested.
+    ///
+    /// As each scanned block costs a runtime call, this is an unsafe RPC method.
+    #[rpc(name = "chainx_getRecentActivity")]
+    fn recent_activity(
+        &self,
+        who: AccountId,
+        max_blocks_back: u32,
+        limit: u32,
+    ) -> Result<RecentActivity<BlockHash>>;
+}
+
+/// A struct that implements the [`XSystemApi`].
+pub struct XSystem<C, B> {
+    client: Arc<C>,
+    properties: Properties,
+    deny_unsafe: DenyUnsafe,
+    _marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> XSystem<C, B> {
+    /// Create new `XSystem` with the given reference to the client and the
+    /// properties of the chain spec.
+    pub fn new(client: Arc<C>, properties: Properties, deny_unsafe: DenyUnsafe) -> Self {
+        Self {
+            client,
+            properties,
+            deny_unsafe,
+            _marker: Default::default(),
+        }
+    }
+}
+
+impl<C, Block> XSystem<C, Block>
+where
+    Block: BlockT,
+    C: ProvideRuntimeApi<Block>,
+    C::Api: XSystemRuntimeApi<Block>,
+{
+    /// Returns true if the runtime at `at` provides an `XSystemApi` of at least `version`.
+    fn has_api_version(&self, at: &BlockId<Block>, version: u32) -> Result<bool> {
+        self.client
+            .runtime_api()
+            .has_api_with::<dyn XSystemRuntimeApi<Block>, _>(at, |v| v >= version)
+            .map_err(runtime_error_into_rpc_err)
+    }
+
+    /// Returns an error if the runtime at `at` provides an `XSystemApi` older than `version`.
+    fn ensure_api_version(&self, at: &BlockId<Block>, version: u32) -> Result<()> {
+        if self.has_api_version(at, version)? {
+            Ok(())
+        } else {
+            Err(unsupported_api_rpc_err("XSystemApi", version))
+        }
+    }
+}
+
+impl<C, Block> XSystemApi<<Block as BlockT>::Hash> for XSystem<C, Block>
+where
+    Block: BlockT,
+    C: Send
+        + Sync
+        + 'static
+        + ProvideRuntimeApi<Block>
+        + HeaderBackend<Block>
+        + BlockBackend<Block>,
+    C::Api: XSystemRuntimeApi<Block> + Core<Block>,
+{
+    fn type_registry(&self, at: Option<<Block as BlockT>::Hash>) -> Result<TypeRegistry> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        let encoded = api
+            .chainx_type_registry(&at)
+            .map_err(runtime_error_into_rpc_err)?;
+        TypeRegistry::decode(&mut encoded.as_slice()).map_err(decode_error_into_rpc_err)
+    }
+
+    fn chain_properties(
+        &self,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<ChainProperties<<Block as BlockT>::Hash>> {
+        let api = self.client.runtime_api();
+        let info = self.client.info();
+        let at = BlockId::hash(at.unwrap_or(info.best_hash));
+        self.ensure_api_version(&at, 2)?;
+        let version = api.version(&at).map_err(runtime_error_into_rpc_err)?;
+        let runtime_properties = api
+            .chain_properties(&at)
+            .map_err(runtime_error_into_rpc_err)?;
+        Ok(ChainProperties::new(
+            info.genesis_hash,
+            &self.properties,
+            &version,
+            runtime_properties,
+        ))
+    }
+
+    fn runtime_docs(&self, at: Option<<Block as BlockT>::Hash>) -> Result<RuntimeDocs> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        self.ensure_api_version(&at, 3)?;
+        let docs = api.docs_registry(&at).map_err(runtime_error_into_rpc_err)?;
+        Ok(group_runtime_docs(docs))
+    }
+
+    fn pcx_supply(&self, at: Option<<Block as BlockT>::Hash>) -> Result<PcxSupply> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        self.ensure_api_version(&at, 3)?;
+        api.pcx_supply_breakdown(&at)
+            .map(Into::into)
+            .map_err(runtime_error_into_rpc_err)
+    }
+
+    fn recent_activity(
+        &self,
+        who: AccountId,
+        max_blocks_back: u32,
+        limit: u32,
+    ) -> Result<RecentActivity<<Block as BlockT>::Hash>> {
+        self.deny_unsafe.check_if_safe()?;
+
+        let best_hash = self.client.info().best_hash;
+        self.ensure_api_version(&BlockId::hash(best_hash), 4)?;
+        let max_blocks_back = max_blocks_back.min(MAX_ACTIVITY_BLOCKS_BACK);
+        let limit = limit.min(MAX_ACTIVITY_ENTRIES) as usize;
+
+        let mut hash = best_hash;
+        let mut entries = Vec::new();
+        let mut scanned_blocks = 0;
+        let mut truncated = false;
+        let mut reached_genesis = false;
+        while scanned_blocks < max_blocks_back && entries.len() < limit {
+            let at = BlockId::hash(hash);
+            let header = self
+                .client
+                .header(BlockId::hash(hash))
+                .map_err(runtime_error_into_rpc_err)?;
+            let extrinsics = self
+                .client
+                .block_body(&at)
+                .map_err(runtime_error_into_rpc_err)?;
+            let (header, extrinsics) = match (header, extrinsics) {
+                (Some(header), Some(extrinsics)) => (header, extrinsics),
+                _ => {
+                    truncated = true;
+                    break;
+                }
+            };
+            // The blocks before the runtime api was introduced can not be scanned.
+            if !self.has_api_version(&at, 4)? {
+                truncated = true;
+                break;
+            }
+            let activity = self
+                .client
+                .runtime_api()
+                .account_activity(&at, who.clone(), extrinsics)
+                .map_err(runtime_error_into_rpc_err)?;
+            scanned_blocks += 1;
+
+            let block_number: BlockNumber = (*header.number()).unique_saturated_into();
+            entries.extend(
+                activity
+                    .into_iter()
+                    .rev()
+                    .map(|activity| ActivityEntry::new(block_number, hash, activity)),
+            );
+            if header.number().is_zero() {
+                reached_genesis = true;
+                break;
+            }
+            hash = *header.parent_hash();
+        }
+        if entries.len() >= limit {
+            truncated =
+                entries.len() > limit || (scanned_blocks < max_blocks_back && !reached_genesis);
+            entries.truncate(limit);
+        }
+
+        Ok(RecentActivity {
+            entries,
+            scanned_blocks,
+            truncated,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn dev_chain_properties_should_be_assembled() {
+        // Same as the properties of the development chain spec.
+        let properties = json!({
+            "ss58Format": 42,
+            "network": NetworkType::Testnet,
+            "tokenDecimals": 8,
+            "tokenSymbol": "PCX"
+        })
+        .as_object()
+        .unwrap()
+        .to_owned();
+        let version = RuntimeVersion {
+            spec_version: 34,
+            impl_version: 1,
+            ..Default::default()
+        };
+        let runtime_properties = RuntimeProperties {
+            pcx_precision: 8,
+            chains: vec![(Chain::ChainX, 1), (Chain::Bitcoin, 1)],
+            bitcoin_network: NetworkType::Testnet,
+            bitcoin_confirmation_number: 4,
+        };
+
+        let chain_properties =
+            ChainProperties::new("0x1234", &properties, &version, runtime_properties);
+        assert_eq!(
+            serde_json::to_value(&chain_properties).unwrap(),
+            json!({
+                "genesisHash": "0x1234",
+                "networkType": "Testnet",
+                "addressType": 42,
+                "bitcoinType": "Testnet",
+                "bitcoinConfirmationNumber": 4,
+                "pcxPrecision": 8,
+                "chains": [
+                    { "chain": "ChainX", "assetCount": 1 },
+                    { "chain": "Bitcoin", "assetCount": 1 }
+                ],
+                "specVersion": 34,
+                "implVersion": 1
+            })
+        );
+
+        // The chain spec properties are optional.
+        let chain_properties = ChainProperties::new(
+            "0x1234",
+            &Properties::new(),
+            &version,
+            RuntimeProperties {
+                pcx_precision: 8,
+                chains: vec![],
+                bitcoin_network: NetworkType::Mainnet,
+                bitcoin_confirmation_number: 4,
+            },
+        );
+        assert_eq!(chain_properties.network_type, None);
+        assert_eq!(chain_properties.address_type, None);
+        assert_eq!(chain_properties.bitcoin_type, NetworkType::Mainnet);
+    }
+
+    #[test]
+    fn runtime_docs_should_be_grouped_by_module() {
+        let docs = vec![ModuleDocs {
+            module: b"XAssets".to_vec(),
+            events: vec![(
+                b"Issued".to_vec(),
+                b"New balances of an asset were issued. [asset_id, receiver, amount]".to_vec(),
+            )],
+            errors: vec![(b"InvalidAsset".to_vec(), b"Got and Invalid Asset".to_vec())],
+        }];
+        assert_eq!(
+            serde_json::to_value(&group_runtime_docs(docs)).unwrap(),
+            json!({
+                "XAssets": {
+                    "events": {
+                        "Issued": "New balances of an asset were issued. [asset_id, receiver, amount]"
+                    },
+                    "errors": {
+                        "InvalidAsset": "Got and Invalid Asset"
+                    }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn pcx_supply_buckets_should_add_up_to_total_issuance() {
+        let breakdown = SupplyBreakdown::new(1_000, 300, 50, 20, 5, 100, 25);
+        assert_eq!(breakdown.free, 500);
+        assert_eq!(
+            breakdown.free + breakdown.buckets_total(),
+            breakdown.total_issuance
+        );
+
+        assert_eq!(
+            serde_json::to_value(&PcxSupply::from(breakdown)).unwrap(),
+            json!({
+                "totalIssuance": "1000",
+                "free": "500",
+                "reservedStaking": "300",
+                "reservedRevocation": "50",
+                "reservedDexSpot": "20",
+                "reservedWithdrawal": "5",
+                "jackpotsTotal": "100",
+                "council": "25"
+            })
+        );
+
+        // The buckets never exceed the total issuance.
+        assert_eq!(SupplyBreakdown::new(10, 20, 0, 0, 0, 0, 0).free, 0);
+    }
+
+    #[test]
+    fn recent_activity_should_cover_transfers_across_blocks() {
+        use codec::Encode;
+        use xpallet_system_rpc_runtime_api::{collect_account_activity, BlockExtrinsic};
+
+        let alice = AccountId::new([1; 32]);
+        let bob = AccountId::new([2; 32]);
+        let charlie = AccountId::new([3; 32]);
+        let transfer = |from: &AccountId, to: &AccountId, success| {
+            BlockExtrinsic::new(
+                Some(from.clone()),
+                "Balances",
+                "transfer",
+                [&[0x06, 0x00][..], &to.encode()].concat(),
+                success,
+            )
+        };
+        let timestamp = BlockExtrinsic::new(None, "Timestamp", "set", vec![0x04, 0x00], true);
+        let event = |index: Option<u32>, who: &[&AccountId]| {
+            let mut encoded = vec![0x06, 0x02];
+            who.iter().for_each(|who| encoded.extend(who.encode()));
+            (index, encoded)
+        };
+
+        // Block 1: alice pays bob, charlie pays bob.
+        let block1 = collect_account_activity(
+            &alice,
+            &[
+                timestamp.clone(),
+                transfer(&alice, &bob, true),
+                transfer(&charlie, &bob, true),
+            ],
+            &[
+                event(Some(1), &[&alice, &bob]),
+                event(Some(2), &[&charlie, &bob]),
+            ],
+        );
+        // Block 2: bob pays alice and a failed transfer of alice, alice is rewarded on
+        // initialization.
+        let block2 = collect_account_activity(
+            &alice,
+            &[
+                timestamp.clone(),
+                transfer(&bob, &alice, true),
+                transfer(&alice, &charlie, false),
+            ],
+            &[
+                event(None, &[&alice]),
+                event(Some(1), &[&bob, &alice]),
+                event(Some(1), &[&alice]),
+            ],
+        );
+        // Block 3: nothing involving alice.
+        let block3 = collect_account_activity(
+            &alice,
+            &[timestamp, transfer(&bob, &charlie, true)],
+            &[event(Some(1), &[&bob, &charlie])],
+        );
+        assert!(block3.is_empty());
+
+        let entries = [(2, block2), (1, block1)]
+            .into_iter()
+            .flat_map(|(number, activity)| {
+                activity
+                    .into_iter()
+                    .rev()
+                    .map(move |activity| ActivityEntry::new(number, "0x1234", activity))
+            })
+            .collect::<Vec<_>>();
+        let summary = entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.block_number,
+                    entry.extrinsic_index,
+                    entry.involvement.as_str(),
+                    entry.summary.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    2,
+                    None,
+                    "eventParticipant",
+                    "- (block hooks), 1 event(s) involving the account"
+                ),
+                (
+                    2,
+                    Some(2),
+                    "signer",
+                    "Balances.transfer (failed), 0 event(s) involving the account"
+                ),
+                (
+                    2,
+                    Some(1),
+                    "counterparty",
+                    "Balances.transfer (succeeded), 2 event(s) involving the account"
+                ),
+                (
+                    1,
+                    Some(1),
+                    "signer",
+                    "Balances.transfer (succeeded), 1 event(s) involving the account"
+                ),
+            ]
+        );
+
+        assert_eq!(
+            serde_json::to_value(&entries[3]).unwrap(),
+            json!({
+                "blockNumber": 1,
+                "blockHash": "0x1234",
+                "extrinsicIndex": 1,
+                "call": "Balances.transfer",
+                "involvement": "signer",
+                "summary": "Balances.transfer (succeeded), 1 event(s) involving the account"
+            })
+        );
+    }
+}
@@ -0,0 +1,183 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Best-effort activity of an account in a block, which backs `chainx_getRecentActivity`.
+
+use codec::{Decode, Encode};
+use sp_runtime::{
+    generic::UncheckedExtrinsic,
+    traits::{SignedExtension, StaticLookup},
+    RuntimeDebug,
+};
+use sp_std::{collections::btree_map::BTreeMap, prelude::*};
+
+use frame_support::dispatch::GetCallMetadata;
+
+use crate::{Config, Pallet};
+
+/// How an account is involved in an extrinsic or in the block hooks.
+#[derive(PartialEq, Eq, Clone, Copy, Encode, Decode, RuntimeDebug)]
+pub enum Involvement {
+    /// The account signed the extrinsic.
+    Signer,
+    /// The account is an argument of the call, e.g. the destination of a transfer.
+    Counterparty,
+    /// The account only appears in the events.
+    EventParticipant,
+}
+
+/// The activity of an account in an extrinsic, or in the block hooks if
+/// `extrinsic_index` is `None`.
+#[derive(PartialEq, Eq, Clone, Encode, Decode, RuntimeDebug)]
+pub struct AccountActivity {
+    pub extrinsic_index: Option<u32>,
+    /// The `Pallet.call` name of the extrinsic, empty for the block hooks.
+    pub call: Vec<u8>,
+    pub involvement: Involvement,
+    /// The number of the events involving the account.
+    pub events: u32,
+    /// Whether the extrinsic succeeded, `None` for the block hooks.
+    pub success: Option<bool>,
+}
+
+/// An extrinsic of a block prepared for [`collect_account_activity`].
+#[derive(PartialEq, Eq, Clone, RuntimeDebug)]
+pub struct BlockExtrinsic<AccountId> {
+    /// The resolved signer, `None` if the extrinsic is unsigned.
+    pub signer: Option<AccountId>,
+    /// The `Pallet.call` name.
+    pub call: Vec<u8>,
+    /// The SCALE encoded call.
+    pub encoded_call: Vec<u8>,
+    pub success: bool,
+}
+
+impl<AccountId> BlockExtrinsic<AccountId> {
+    pub fn new(
+        signer: Option<AccountId>,
+        pallet_name: &str,
+        function_name: &str,
+        encoded_call: Vec<u8>,
+        success: bool,
+    ) -> Self {
+        let mut call = pallet_name.as_bytes().to_vec();
+        call.push(b'.');
+        call.extend_from_slice(function_name.as_bytes());
+        Self {
+            signer,
+            call,
+            encoded_call,
+            success,
+        }
+    }
+}
+
+/// Collects the activity of `who` in a block given its extrinsics and its SCALE encoded
+/// events, along with the index of the extrinsic emitting each of them.
+///
+/// An account is considered involved in a call or an event if its SCALE encoding shows
+/// up in the encoded bytes, which covers any module without decoding its types but is
+/// best-effort by nature.
+pub fn collect_account_activity<AccountId: Encode + PartialEq>(
+    who: &AccountId,
+    extrinsics: &[BlockExtrinsic<AccountId>],
+    events: &[(Option<u32>, Vec<u8>)],
+) -> Vec<AccountActivity> {
+    let who_encoded = who.encode();
+    let involves = |encoded: &[u8]| {
+        encoded
+            .windows(who_encoded.len())
+            .any(|window| window == who_encoded.as_slice())
+    };
+
+    let mut event_counts = BTreeMap::<Option<u32>, u32>::new();
+    for (extrinsic_index, event) in events {
+        if involves(event) {
+            *event_counts.entry(*extrinsic_index).or_default() += 1;
+        }
+    }
+
+    let mut activity = extrinsics
+        .iter()
+        .enumerate()
+        .filter_map(|(index, extrinsic)| {
+            let index = index as u32;
+            let events = event_counts.remove(&Some(index)).unwrap_or_default();
+            let involvement = if extrinsic.signer.as_ref() == Some(who) {
+                Involvement::Signer
+            } else if involves(&extrinsic.encoded_call) {
+                Involvement::Counterparty
+            } else if events > 0 {
+                Involvement::EventParticipant
+            } else {
+                return None;
+            };
+            Some(AccountActivity {
+                extrinsic_index: Some(index),
+                call: extrinsic.call.clone(),
+                involvement,
+                events,
+                success: Some(extrinsic.success),
+            })
+        })
+        .collect::<Vec<_>>();
+    if let Some(events) = event_counts.remove(&None) {
+        activity.push(AccountActivity {
+            extrinsic_index: None,
+            call: Vec::new(),
+            involvement: Involvement::EventParticipant,
+            events,
+            success: None,
+        });
+    }
+    activity
+}
+
+impl<T: Config> Pallet<T> {
+    /// Returns the activity of `who` in current block, given the extrinsics of the block.
+    ///
+    /// Meant to be called by the runtime api at the block being inspected, whose events
+    /// are still in `frame_system::Events`.
+    pub fn account_activity<Call, Signature, Extra>(
+        who: &T::AccountId,
+        extrinsics: Vec<
+            UncheckedExtrinsic<<T::Lookup as StaticLookup>::Source, Call, Signature, Extra>,
+        >,
+    ) -> Vec<AccountActivity>
+    where
+        Call: Encode + GetCallMetadata,
+        Extra: SignedExtension,
+        <T as frame_system::Config>::Event: TryInto<frame_system::Event<T>>,
+    {
+        let mut failed = Vec::new();
+        let events = frame_system::Pallet::<T>::events()
+            .into_iter()
+            .map(|record| {
+                let extrinsic_index = match record.phase {
+                    frame_system::Phase::ApplyExtrinsic(index) => Some(index),
+                    _ => None,
+                };
+                let encoded = record.event.encode();
+                if let Ok(frame_system::Event::ExtrinsicFailed { .. }) = record.event.try_into() {
+                    failed.extend(extrinsic_index);
+                }
+                (extrinsic_index, encoded)
+            })
+            .collect::<Vec<_>>();
+        let extrinsics = extrinsics
+            .into_iter()
+            .enumerate()
+            .map(|(index, xt)| {
+                let call = xt.function.get_call_metadata();
+                BlockExtrinsic::new(
+                    xt.signature
+                        .and_then(|(address, _, _)| T::Lookup::lookup(address).ok()),
+                    call.pallet_name,
+                    call.function_name,
+                    xt.function.encode(),
+                    !failed.contains(&(index as u32)),
+                )
+            })
+            .collect::<Vec<_>>();
+        collect_account_activity(who, &extrinsics, &events)
+    }
+}
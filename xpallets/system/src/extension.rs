@@ -0,0 +1,156 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! The signed extension applying the paused calls, the blacklist, the extrinsic quota
+//! and the burn account to the signed extrinsics.
+
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sp_runtime::{
+    traits::{DispatchInfoOf, PostDispatchInfoOf, SignedExtension, StaticLookup},
+    transaction_validity::{
+        InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+    },
+};
+use sp_std::marker::PhantomData;
+
+use frame_support::{
+    dispatch::GetCallMetadata,
+    traits::{Contains, Get, IsSubType},
+};
+
+use crate::{Config, Pallet, QuotaCalls};
+
+pub const FORBIDDEN_CALL: u8 = 255;
+pub const FORBIDDEN_ACCOUNT: u8 = 254;
+
+/// Rejects the paused calls, the blacklisted senders and the senders beyond their
+/// extrinsic quota, and destroys the native transfers to the burn account.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct BaseFilter<T>(PhantomData<T>);
+
+impl<T> BaseFilter<T> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T> Default for BaseFilter<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> sp_std::fmt::Debug for BaseFilter<T> {
+    #[cfg(feature = "std")]
+    fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+        write!(f, "BaseFilter")
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+        Ok(())
+    }
+}
+
+impl<T: Config> Contains<<T as frame_system::Config>::Call> for BaseFilter<T>
+where
+    <T as frame_system::Config>::Call: GetCallMetadata,
+{
+    fn contains(call: &<T as frame_system::Config>::Call) -> bool {
+        !Pallet::<T>::is_paused(call.get_call_metadata())
+    }
+}
+
+impl<T> BaseFilter<T>
+where
+    T: Config + pallet_balances::Config,
+    <T as frame_system::Config>::Call: IsSubType<pallet_balances::Call<T>>,
+{
+    /// Returns true if `call` is a native transfer to the burn account.
+    fn is_burn_transfer(call: &<T as frame_system::Config>::Call) -> bool {
+        let dest = match call.is_sub_type() {
+            Some(pallet_balances::Call::transfer { dest, .. })
+            | Some(pallet_balances::Call::transfer_keep_alive { dest, .. })
+            | Some(pallet_balances::Call::transfer_all { dest, .. }) => dest,
+            _ => return false,
+        };
+        match (Pallet::<T>::burn_account(), T::Lookup::lookup(dest.clone())) {
+            (Some(burn_account), Ok(dest)) => burn_account == dest,
+            _ => false,
+        }
+    }
+}
+
+impl<T> SignedExtension for BaseFilter<T>
+where
+    T: Config + pallet_balances::Config + Send + Sync,
+    <T as frame_system::Config>::Call: GetCallMetadata + IsSubType<pallet_balances::Call<T>>,
+{
+    const IDENTIFIER: &'static str = "BaseFilter";
+    type AccountId = T::AccountId;
+    type Call = <T as frame_system::Config>::Call;
+    type AdditionalSigned = ();
+    /// The sender of a native transfer to the burn account.
+    type Pre = Option<T::AccountId>;
+    fn additional_signed(&self) -> sp_std::result::Result<(), TransactionValidityError> {
+        Ok(())
+    }
+
+    fn pre_dispatch(
+        self,
+        who: &Self::AccountId,
+        call: &Self::Call,
+        info: &DispatchInfoOf<Self::Call>,
+        len: usize,
+    ) -> Result<Self::Pre, TransactionValidityError> {
+        self.validate(who, call, info, len)?;
+        let calls = T::QuotaCalls::quota_calls(call);
+        if calls > 0 {
+            Pallet::<T>::note_extrinsic(who, calls);
+            // The read of the quota in `validate`, its update here and its removal in
+            // `on_finalize` are not covered by the weight of the call.
+            frame_system::Pallet::<T>::register_extra_weight_unchecked(
+                <T as frame_system::Config>::DbWeight::get().reads_writes(2, 2),
+                info.class,
+            );
+        }
+        Ok(Self::is_burn_transfer(call).then(|| who.clone()))
+    }
+
+    fn post_dispatch(
+        pre: Option<Self::Pre>,
+        _info: &DispatchInfoOf<Self::Call>,
+        _post_info: &PostDispatchInfoOf<Self::Call>,
+        _len: usize,
+        result: &sp_runtime::DispatchResult,
+    ) -> Result<(), TransactionValidityError> {
+        // Destroy the native coins sent to the burn account right away, the ones
+        // reaching it through the other calls are swept at the end of the block.
+        if let (Some(Some(sender)), Ok(())) = (pre, result) {
+            Pallet::<T>::burn_native_received(&sender);
+        }
+        Ok(())
+    }
+
+    fn validate(
+        &self,
+        who: &Self::AccountId,
+        call: &Self::Call,
+        _info: &DispatchInfoOf<Self::Call>,
+        _len: usize,
+    ) -> TransactionValidity {
+        if !Self::contains(call) {
+            return Err(InvalidTransaction::Custom(FORBIDDEN_CALL).into());
+        }
+        if Pallet::<T>::blacklist(who) {
+            return Err(InvalidTransaction::Custom(FORBIDDEN_ACCOUNT).into());
+        }
+        // The extrinsics beyond the quota are skipped by the block author but kept
+        // in the pool, they will be included in the following blocks.
+        if Pallet::<T>::exceeds_extrinsic_quota(who, T::QuotaCalls::quota_calls(call)) {
+            return Err(InvalidTransaction::ExhaustsResources.into());
+        }
+        Ok(ValidTransaction::default())
+    }
+}
@@ -2,22 +2,55 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub mod activity;
+mod extension;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
 use sp_std::{collections::btree_map::BTreeMap, prelude::*};
 
-use sp_runtime::traits::StaticLookup;
+use sp_runtime::traits::{Saturating, StaticLookup, Zero};
 
 use frame_support::{
     dispatch::{CallMetadata, DispatchResult},
-    traits::Currency,
+    log::{error, info},
+    traits::{Currency, Get, Imbalance},
 };
 
 use frame_system::ensure_root;
+
+use chainx_primitives::AssetId;
 use xp_protocol::NetworkType;
+use xpallet_assets::{AssetBalance, AssetType, BalanceOf};
+use xpallet_support::traits::TreasuryAccount;
 
+pub use self::extension::{BaseFilter, FORBIDDEN_ACCOUNT, FORBIDDEN_CALL};
 pub use pallet::*;
 
+/// Counts the calls of a signed extrinsic in the extrinsic quota of its sender.
+///
+/// The default implementation `()` counts every extrinsic as one call.
+pub trait QuotaCalls<Call> {
+    /// Returns the number of the calls in `call` counted in the quota, zero if exempt.
+    ///
+    /// The calls wrapping the others, e.g. `Utility.batch`, are expected to count
+    /// their inner calls.
+    fn quota_calls(call: &Call) -> u32;
+}
+
+impl<Call> QuotaCalls<Call> for () {
+    fn quota_calls(_call: &Call) -> u32 {
+        1
+    }
+}
+
 const PALLET_MARK: &[u8; 1] = b"#";
 const ALWAYS_ALLOW: [&str; 1] = ["Sudo"];
+/// The maximum number of the foreign assets swept from the burn account in a block,
+/// the rest are left to the following blocks.
+const MAX_BURN_SWEEP_ASSETS: u32 = 8;
 
 /// The pallet's config trait.
 ///
@@ -32,12 +65,19 @@ pub mod pallet {
     ///
     /// `frame_system::Config` should always be included in our implied traits.
     #[pallet::config]
-    pub trait Config: frame_system::Config {
+    pub trait Config: frame_system::Config + xpallet_assets::Config {
         /// The overarching event type.
         type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 
         /// The currency mechanism.
         type Currency: Currency<Self::AccountId>;
+
+        /// The maximum number of calls of the signed extrinsics of a sender in a block.
+        #[pallet::constant]
+        type MaxExtrinsicsPerSenderPerBlock: Get<u32>;
+
+        /// Counts the calls of the signed extrinsics in the extrinsic quota.
+        type QuotaCalls: QuotaCalls<<Self as frame_system::Config>::Call>;
     }
 
     #[pallet::pallet]
@@ -45,6 +85,32 @@ pub mod pallet {
     #[pallet::without_storage_info]
     pub struct Pallet<T>(PhantomData<T>);
 
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn on_initialize(_n: BlockNumberFor<T>) -> Weight {
+            // Reserve the weight of sweeping the native and foreign asset balances
+            // left in the burn account in `on_finalize`, each asset swept costs a
+            // read and the updates of its balances.
+            if Self::burn_account().is_some() {
+                let assets = u64::from(MAX_BURN_SWEEP_ASSETS);
+                T::DbWeight::get()
+                    .reads_writes(3, 2)
+                    .saturating_add(T::DbWeight::get().reads_writes(2 * assets, 2 * assets))
+            } else {
+                T::DbWeight::get().reads(1)
+            }
+        }
+
+        fn on_finalize(_n: BlockNumberFor<T>) {
+            // The transfers to the burn account are destroyed on receipt, only the
+            // balances reaching it through other paths are left to be swept here.
+            if let Some(burn_account) = Self::burn_account() {
+                Self::burn_all(&burn_account);
+            }
+            let _ = SenderExtrinsicCount::<T>::remove_all(None);
+        }
+    }
+
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         /// Modify the paused status of the given pallet call.
@@ -58,30 +124,7 @@ pub mod pallet {
             should_paused: bool,
         ) -> DispatchResult {
             ensure_root(origin)?;
-
-            let mut paused = Self::paused(&pallet);
-
-            if should_paused {
-                if let Some(c) = call {
-                    // pause the call of the pallet
-                    paused.insert(c, ());
-                } else {
-                    // pause the whole calls of the pallet
-                    paused.insert(PALLET_MARK.to_vec(), ());
-                }
-            } else if let Some(c) = call {
-                // revoke the paused status of the call in the pallet
-                paused.remove(&c[..]);
-            } else {
-                // revoke the paused status of the whole calls in the pallet.
-                paused.remove(&PALLET_MARK[..]);
-            }
-
-            if paused.is_empty() {
-                Paused::<T>::remove(&pallet);
-            } else {
-                Paused::<T>::insert(pallet, paused);
-            }
+            Self::modify_paused_impl(pallet, call, should_paused);
             Ok(())
         }
 
@@ -106,6 +149,32 @@ pub mod pallet {
             }
             Ok(())
         }
+
+        /// Set the burn account, all the balances sent to which will be destroyed.
+        ///
+        /// This is a root-only operation.
+        #[pallet::weight(0)]
+        pub fn set_burn_account(origin: OriginFor<T>, new: Option<T::AccountId>) -> DispatchResult {
+            ensure_root(origin)?;
+
+            if let Some(who) = &new {
+                ensure!(
+                    <T as xpallet_assets::Config>::TreasuryAccount::treasury_account().as_ref()
+                        != Some(who),
+                    Error::<T>::BurnAccountIsFeeDestination
+                );
+            }
+
+            if let Some(old) = Self::burn_account() {
+                xpallet_assets::Pallet::<T>::set_transfer_notify_of(&old, false);
+            }
+            if let Some(who) = &new {
+                xpallet_assets::Pallet::<T>::set_transfer_notify_of(who, true);
+            }
+            BurnAccount::<T>::set(new.clone());
+            Self::deposit_event(Event::<T>::BurnAccountSet(new));
+            Ok(())
+        }
     }
 
     /// Event for the XSystem Pallet
@@ -116,6 +185,17 @@ pub mod pallet {
         Blacklisted(T::AccountId),
         /// An account was removed from the blacklist. [who]
         Unblacklisted(T::AccountId),
+        /// The burn account was set. [burn_account]
+        BurnAccountSet(Option<T::AccountId>),
+        /// Some balances sent to the burn account were destroyed. [asset_id, from, amount]
+        Burned(AssetId, T::AccountId, BalanceOf<T>),
+    }
+
+    /// Error for the XSystem Pallet
+    #[pallet::error]
+    pub enum Error<T> {
+        /// The fee destination can not be used as the burn account.
+        BurnAccountIsFeeDestination,
     }
 
     /// Network property (Mainnet / Testnet).
@@ -133,6 +213,19 @@ pub mod pallet {
     #[pallet::getter(fn blacklist)]
     pub type Blacklist<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, bool, ValueQuery>;
 
+    /// The account whose received balances will be destroyed.
+    #[pallet::storage]
+    #[pallet::getter(fn burn_account)]
+    pub type BurnAccount<T: Config> = StorageValue<_, T::AccountId>;
+
+    /// The number of calls of the signed extrinsics of each sender in current block.
+    ///
+    /// Cleared at the end of each block.
+    #[pallet::storage]
+    #[pallet::getter(fn sender_extrinsic_count)]
+    pub type SenderExtrinsicCount<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
     #[pallet::genesis_config]
     #[cfg_attr(feature = "std", derive(Default))]
     pub struct GenesisConfig {
@@ -148,6 +241,33 @@ pub mod pallet {
 }
 
 impl<T: Config> Pallet<T> {
+    /// Modifies the paused status of the given pallet call.
+    pub fn modify_paused_impl(pallet: Vec<u8>, call: Option<Vec<u8>>, should_paused: bool) {
+        let mut paused = Self::paused(&pallet);
+
+        if should_paused {
+            if let Some(c) = call {
+                // pause the call of the pallet
+                paused.insert(c, ());
+            } else {
+                // pause the whole calls of the pallet
+                paused.insert(PALLET_MARK.to_vec(), ());
+            }
+        } else if let Some(c) = call {
+            // revoke the paused status of the call in the pallet
+            paused.remove(&c[..]);
+        } else {
+            // revoke the paused status of the whole calls in the pallet.
+            paused.remove(&PALLET_MARK[..]);
+        }
+
+        if paused.is_empty() {
+            Paused::<T>::remove(&pallet);
+        } else {
+            Paused::<T>::insert(pallet, paused);
+        }
+    }
+
     /// Returns true if the given pallet call has been paused.
     pub fn is_paused(metadata: CallMetadata) -> bool {
         if ALWAYS_ALLOW.contains(&metadata.pallet_name) {
@@ -167,10 +287,122 @@ impl<T: Config> Pallet<T> {
         false
     }
 
+    /// Returns true if `calls` more would exceed the extrinsic quota of `who` in current block.
+    ///
+    /// The exempt extrinsics, counting no call, are always allowed.
+    pub fn exceeds_extrinsic_quota(who: &T::AccountId, calls: u32) -> bool {
+        calls > 0
+            && Self::sender_extrinsic_count(who).saturating_add(calls)
+                > T::MaxExtrinsicsPerSenderPerBlock::get()
+    }
+
+    /// Counts `calls` of a signed extrinsic of `who` into its quota of current block.
+    pub fn note_extrinsic(who: &T::AccountId, calls: u32) {
+        if calls > 0 {
+            SenderExtrinsicCount::<T>::mutate(who, |count| *count = count.saturating_add(calls));
+        }
+    }
+
     /// Returns the blocked account id list.
     pub fn get_blacklist() -> Vec<T::AccountId> {
         Blacklist::<T>::iter()
             .filter_map(|(account, blocked)| if blocked { Some(account) } else { None })
             .collect()
     }
+
+    /// Destroys the native coins received by the burn account from `from`.
+    ///
+    /// Called by [`BaseFilter`] right after a native transfer of `from` to the burn
+    /// account is dispatched, which makes the burn immediate.
+    pub fn burn_native_received(from: &T::AccountId) {
+        if let Some(burn_account) = Self::burn_account() {
+            Self::burn_native(&burn_account, from);
+        }
+    }
+
+    /// Destroys the whole free native balance of the burn account, the burn is
+    /// attributed to `from`.
+    fn burn_native(burn_account: &T::AccountId, from: &T::AccountId) {
+        let native = <T as xpallet_assets::Config>::Currency::free_balance(burn_account);
+        if native.is_zero() {
+            return;
+        }
+        let (imbalance, _) = <T as xpallet_assets::Config>::Currency::slash(burn_account, native);
+        let burned = imbalance.peek();
+        // Dropping the negative imbalance decreases the total issuance.
+        drop(imbalance);
+        info!(
+            target: "runtime::system",
+            "[burn_native] Burned native coin, from:{:?}, value:{:?}",
+            from, burned
+        );
+        Self::deposit_event(Event::<T>::Burned(
+            <T as xpallet_assets_registrar::Config>::NativeAssetId::get(),
+            from.clone(),
+            burned,
+        ));
+    }
+
+    /// Destroys `value` of the usable asset `id` received by the burn account from `from`,
+    /// which reduces the total asset balance accordingly.
+    fn burn_asset(
+        burn_account: &T::AccountId,
+        from: &T::AccountId,
+        id: &AssetId,
+        value: BalanceOf<T>,
+    ) -> DispatchResult {
+        let usable = xpallet_assets::Pallet::<T>::usable_balance(burn_account, id);
+        let mut balances = BTreeMap::new();
+        balances.insert(AssetType::Usable, usable.saturating_sub(value));
+        xpallet_assets::Pallet::<T>::set_balance_impl(burn_account, id, balances)?;
+        Self::deposit_event(Event::<T>::Burned(*id, from.clone(), value.min(usable)));
+        Ok(())
+    }
+
+    /// Destroys the native and the usable asset balances left in the burn account,
+    /// which reduces the total issuance accordingly.
+    ///
+    /// At most `MAX_BURN_SWEEP_ASSETS` assets are visited, the swept ones are removed
+    /// from the burn account so that the rest are visited in the following blocks.
+    /// The balances reaching the burn account without a transfer of a known sender
+    /// are attributed to the burn account itself.
+    fn burn_all(burn_account: &T::AccountId) {
+        Self::burn_native(burn_account, burn_account);
+
+        let usable_assets = AssetBalance::<T>::iter_prefix(burn_account)
+            .take(MAX_BURN_SWEEP_ASSETS as usize)
+            .filter_map(|(asset_id, balances)| {
+                balances
+                    .get(&AssetType::Usable)
+                    .copied()
+                    .filter(|usable| !usable.is_zero())
+                    .map(|usable| (asset_id, usable))
+            })
+            .collect::<Vec<_>>();
+        for (asset_id, usable) in usable_assets {
+            if let Err(e) = Self::burn_asset(burn_account, burn_account, &asset_id, usable) {
+                error!(
+                    target: "runtime::system",
+                    "[burn_all] Failed to burn asset:{}, who:{:?}, value:{:?}, err:{:?}",
+                    asset_id, burn_account, usable, e
+                );
+            }
+        }
+    }
+}
+
+impl<T: Config> xpallet_assets::OnReceived<T::AccountId, BalanceOf<T>> for Pallet<T> {
+    fn on_received(
+        id: &AssetId,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        value: BalanceOf<T>,
+    ) -> DispatchResult {
+        match Self::burn_account() {
+            Some(burn_account) if &burn_account == to => {
+                Self::burn_asset(&burn_account, from, id, value)
+            }
+            _ => Ok(()),
+        }
+    }
 }
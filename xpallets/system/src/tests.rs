@@ -0,0 +1,235 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+use frame_support::{
+    assert_noop, assert_ok,
+    traits::{Currency, Hooks},
+    weights::{GetDispatchInfo, PostDispatchInfo},
+};
+use sp_runtime::{
+    generic::CheckedExtrinsic, traits::Applyable, transaction_validity::InvalidTransaction,
+    ApplyExtrinsicResultWithInfo, DispatchError,
+};
+
+use crate::mock::*;
+use crate::*;
+
+fn xsystem_events() -> Vec<crate::Event<Test>> {
+    System::events()
+        .into_iter()
+        .filter_map(|record| match record.event {
+            crate::mock::Event::XSystem(event) => Some(event),
+            _ => None,
+        })
+        .collect()
+}
+
+fn t_finalize() {
+    XSystem::on_finalize(System::block_number());
+}
+
+/// Applies `call` as a signed extrinsic of `who` checked by `BaseFilter`.
+fn apply_signed(
+    who: AccountId,
+    call: crate::mock::Call,
+) -> ApplyExtrinsicResultWithInfo<PostDispatchInfo> {
+    let info = call.get_dispatch_info();
+    let xt = CheckedExtrinsic {
+        signed: Some((who, BaseFilter::<Test>::new())),
+        function: call,
+    };
+    xt.apply::<Test>(&info, 0)
+}
+
+#[test]
+fn set_burn_account_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_noop!(
+            XSystem::set_burn_account(Origin::signed(ALICE), Some(BURN)),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            XSystem::set_burn_account(Origin::root(), Some(TREASURY)),
+            Error::<Test>::BurnAccountIsFeeDestination
+        );
+
+        assert_ok!(XSystem::set_burn_account(Origin::root(), Some(BURN)));
+        assert_eq!(XSystem::burn_account(), Some(BURN));
+        assert!(XAssets::transfer_notify_of(BURN));
+
+        assert_ok!(XSystem::set_burn_account(Origin::root(), None));
+        assert_eq!(XSystem::burn_account(), None);
+        assert!(!XAssets::transfer_notify_of(BURN));
+        assert_eq!(
+            xsystem_events(),
+            vec![
+                crate::Event::BurnAccountSet(Some(BURN)),
+                crate::Event::BurnAccountSet(None)
+            ]
+        );
+    });
+}
+
+#[test]
+fn transfer_pcx_to_burn_account_should_reduce_total_issuance() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XSystem::set_burn_account(Origin::root(), Some(BURN)));
+        assert_eq!(Balances::total_issuance(), 3000);
+
+        // The native transfers to the burn account are burned right after they
+        // are dispatched.
+        let transfer = pallet_balances::Call::<Test>::transfer {
+            dest: BURN,
+            value: 100,
+        };
+        assert_ok!(apply_signed(ALICE, transfer.into()).unwrap());
+
+        assert_eq!(Balances::free_balance(ALICE), 900);
+        assert_eq!(Balances::free_balance(BURN), 0);
+        assert_eq!(Balances::total_issuance(), 2900);
+        assert!(xsystem_events().contains(&crate::Event::Burned(PCX, ALICE, 100)));
+    });
+}
+
+#[test]
+fn pcx_left_in_burn_account_should_be_swept_at_the_end_of_block() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XSystem::set_burn_account(Origin::root(), Some(BURN)));
+
+        // A transfer not seen by the runtime, e.g. nested in a batch.
+        assert_ok!(Balances::transfer(Origin::signed(ALICE), BURN, 100));
+        assert_eq!(Balances::free_balance(BURN), 100);
+        t_finalize();
+
+        assert_eq!(Balances::free_balance(BURN), 0);
+        assert_eq!(Balances::total_issuance(), 2900);
+        assert!(xsystem_events().contains(&crate::Event::Burned(PCX, BURN, 100)));
+    });
+}
+
+#[test]
+fn transfer_xbtc_to_burn_account_should_reduce_total_asset_balance() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XSystem::set_burn_account(Origin::root(), Some(BURN)));
+        assert_eq!(
+            XAssets::total_asset_balance_of(&X_BTC, AssetType::Usable),
+            300
+        );
+
+        // Burned on receipt, without waiting for the end of the block.
+        assert_ok!(XAssets::transfer(Origin::signed(BOB), BURN, X_BTC, 50));
+
+        assert_eq!(XAssets::usable_balance(&BOB, &X_BTC), 150);
+        assert_eq!(XAssets::usable_balance(&BURN, &X_BTC), 0);
+        assert_eq!(
+            XAssets::total_asset_balance_of(&X_BTC, AssetType::Usable),
+            250
+        );
+        assert_eq!(XAssets::total_issuance(&X_BTC), 250);
+        assert!(xsystem_events().contains(&crate::Event::Burned(X_BTC, BOB, 50)));
+    });
+}
+
+#[test]
+fn nothing_should_be_burned_without_burn_account() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(Balances::transfer(Origin::signed(ALICE), BURN, 100));
+        assert_ok!(XAssets::transfer(Origin::signed(BOB), BURN, X_BTC, 50));
+        t_finalize();
+
+        assert_eq!(Balances::free_balance(BURN), 100);
+        assert_eq!(Balances::total_issuance(), 3000);
+        assert_eq!(XAssets::usable_balance(&BURN, &X_BTC), 50);
+        assert_eq!(XAssets::total_issuance(&X_BTC), 300);
+        assert!(xsystem_events().is_empty());
+    });
+}
+
+#[test]
+fn extrinsic_quota_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        let transfer = |dest| -> crate::mock::Call {
+            pallet_balances::Call::<Test>::transfer { dest, value: 1 }.into()
+        };
+        let batch =
+            |calls| -> crate::mock::Call { pallet_utility::Call::<Test>::batch { calls }.into() };
+        let remark = || -> crate::mock::Call {
+            frame_system::Call::<Test>::remark { remark: vec![] }.into()
+        };
+        let exhausted: ApplyExtrinsicResultWithInfo<PostDispatchInfo> =
+            Err(InvalidTransaction::ExhaustsResources.into());
+
+        // ALICE can submit 2 calls in a block, a batch counts its inner calls.
+        assert_eq!(
+            apply_signed(
+                ALICE,
+                batch(vec![transfer(BOB), transfer(BOB), transfer(BOB)])
+            ),
+            exhausted
+        );
+        assert_ok!(apply_signed(ALICE, transfer(BOB)).unwrap());
+        assert_eq!(
+            apply_signed(ALICE, batch(vec![transfer(BOB), transfer(BOB)])),
+            exhausted
+        );
+        assert_ok!(apply_signed(ALICE, batch(vec![transfer(BOB)])).unwrap());
+        assert_eq!(XSystem::sender_extrinsic_count(ALICE), 2);
+        assert_eq!(apply_signed(ALICE, transfer(BOB)), exhausted);
+        assert_ok!(apply_signed(BOB, transfer(ALICE)).unwrap());
+
+        // The exempt calls are always allowed.
+        assert_ok!(apply_signed(ALICE, remark()).unwrap());
+        assert_ok!(apply_signed(ALICE, batch(vec![remark(), remark()])).unwrap());
+        assert_eq!(XSystem::sender_extrinsic_count(ALICE), 2);
+
+        // The quota is reset in the next block.
+        t_finalize();
+        assert_eq!(XSystem::sender_extrinsic_count(ALICE), 0);
+        assert_ok!(apply_signed(ALICE, transfer(BOB)).unwrap());
+    });
+}
+
+#[test]
+fn account_activity_should_cover_dispatched_transfers() {
+    use crate::activity::{AccountActivity, Involvement};
+    use frame_support::{dispatch::Dispatchable, weights::DispatchInfo};
+    use sp_runtime::generic::UncheckedExtrinsic;
+
+    ExtBuilder::default().build_and_execute(|| {
+        let transfer = |dest, value| {
+            crate::mock::Call::Balances(pallet_balances::Call::transfer { dest, value })
+        };
+        let extrinsics = vec![
+            UncheckedExtrinsic::new_signed(transfer(BOB, 10), ALICE, (), ()),
+            // Fails due to the insufficient balance of BOB.
+            UncheckedExtrinsic::new_signed(transfer(ALICE, 5000), BOB, (), ()),
+            UncheckedExtrinsic::new_signed(transfer(TREASURY, 10), BOB, (), ()),
+        ];
+
+        System::note_finished_initialize();
+        for xt in extrinsics.iter() {
+            let (who, _, _) = xt.signature.clone().unwrap();
+            let result = xt.function.clone().dispatch(Origin::signed(who));
+            System::note_applied_extrinsic(&result, DispatchInfo::default());
+        }
+
+        assert_eq!(
+            XSystem::account_activity(&ALICE, extrinsics),
+            vec![
+                AccountActivity {
+                    extrinsic_index: Some(0),
+                    call: b"Balances.transfer".to_vec(),
+                    involvement: Involvement::Signer,
+                    events: 1,
+                    success: Some(true),
+                },
+                AccountActivity {
+                    extrinsic_index: Some(1),
+                    call: b"Balances.transfer".to_vec(),
+                    involvement: Involvement::Counterparty,
+                    events: 0,
+                    success: Some(false),
+                },
+            ]
+        );
+    });
+}
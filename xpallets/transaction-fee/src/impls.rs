@@ -0,0 +1,139 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+use sp_std::marker::PhantomData;
+
+use frame_support::traits::{
+    Currency, ExistenceRequirement, Imbalance, OnUnbalanced, WithdrawReasons,
+};
+use sp_runtime::{
+    traits::{DispatchInfoOf, PostDispatchInfoOf, Saturating, Zero},
+    transaction_validity::{InvalidTransaction, TransactionValidityError},
+};
+
+use pallet_transaction_payment::OnChargeTransaction;
+
+use crate::{Config, CreditBalanceOf, Event, FeeCredit, Pallet};
+
+type PositiveImbalanceOf<T> = <<T as Config>::Currency as Currency<
+    <T as frame_system::Config>::AccountId,
+>>::PositiveImbalance;
+type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+    <T as frame_system::Config>::AccountId,
+>>::NegativeImbalance;
+
+/// Implements the transaction payment for a pallet implementing the `Currency`
+/// trait (eg. the pallet_balances), drawing from the fee credit of the payer first.
+///
+/// Same as `pallet_transaction_payment::CurrencyAdapter` except the fee credit part,
+/// the final fee and the tip are passed to `OU`.
+pub struct FeeCreditAdapter<OU>(PhantomData<OU>);
+
+impl<T, OU> OnChargeTransaction<T> for FeeCreditAdapter<OU>
+where
+    T: Config,
+    OU: OnUnbalanced<NegativeImbalanceOf<T>>,
+{
+    /// The withdrawn fee and the part of it drawn from the fee credit.
+    type LiquidityInfo = Option<(NegativeImbalanceOf<T>, CreditBalanceOf<T>)>;
+    type Balance = CreditBalanceOf<T>;
+
+    fn withdraw_fee(
+        who: &T::AccountId,
+        _call: &T::Call,
+        _info: &DispatchInfoOf<T::Call>,
+        fee: Self::Balance,
+        tip: Self::Balance,
+    ) -> Result<Self::LiquidityInfo, TransactionValidityError> {
+        if fee.is_zero() {
+            return Ok(None);
+        }
+
+        let credit = Pallet::<T>::fee_credit_of(who).min(fee);
+        let from_balance = fee - credit;
+
+        let imbalance = if from_balance.is_zero() {
+            NegativeImbalanceOf::<T>::zero()
+        } else {
+            let withdraw_reason = if tip.is_zero() {
+                WithdrawReasons::TRANSACTION_PAYMENT
+            } else {
+                WithdrawReasons::TRANSACTION_PAYMENT | WithdrawReasons::TIP
+            };
+            T::Currency::withdraw(
+                who,
+                from_balance,
+                withdraw_reason,
+                ExistenceRequirement::KeepAlive,
+            )
+            .map_err(|_| InvalidTransaction::Payment)?
+        };
+
+        if !credit.is_zero() {
+            Pallet::<T>::consume_fee_credit(who, credit);
+        }
+
+        // The fee credit was burned when granted, issue it again for paying the fee.
+        Ok(Some((imbalance.merge(T::Currency::issue(credit)), credit)))
+    }
+
+    fn correct_and_deposit_fee(
+        who: &T::AccountId,
+        _dispatch_info: &DispatchInfoOf<T::Call>,
+        _post_info: &PostDispatchInfoOf<T::Call>,
+        corrected_fee: Self::Balance,
+        tip: Self::Balance,
+        already_withdrawn: Self::LiquidityInfo,
+    ) -> Result<(), TransactionValidityError> {
+        if let Some((paid, credit)) = already_withdrawn {
+            // Calculate how much refund we should return, the fee credit is always
+            // consumed first, so the refund goes back to the free balance first.
+            let refund_amount = paid.peek().saturating_sub(corrected_fee);
+            let balance_refund = refund_amount.min(paid.peek().saturating_sub(credit));
+            let credit_refund = refund_amount - balance_refund;
+
+            // refund to the account that paid the fees. If this fails, the
+            // account might have dropped below the existential balance. In
+            // that case we don't refund anything.
+            let refund_imbalance = T::Currency::deposit_into_existing(who, balance_refund)
+                .unwrap_or_else(|_| PositiveImbalanceOf::<T>::zero());
+            // merge the imbalance caused by paying the fees and refunding parts of it again.
+            let adjusted_paid = paid
+                .offset(refund_imbalance)
+                .same()
+                .map_err(|_| TransactionValidityError::Invalid(InvalidTransaction::Payment))?;
+
+            // The refunded fee credit is burned again and returned to the fee credit.
+            let (_, adjusted_paid) = adjusted_paid.split(credit_refund);
+            if !credit_refund.is_zero() {
+                Pallet::<T>::restore_fee_credit(who, credit_refund);
+            }
+
+            let consumed = credit.saturating_sub(credit_refund);
+            if !consumed.is_zero() {
+                Pallet::<T>::deposit_event(Event::<T>::FeeCreditConsumed(who.clone(), consumed));
+            }
+
+            // Call someone else to handle the imbalance (fee and tip separately)
+            let (tip, fee) = adjusted_paid.split(tip);
+            OU::on_unbalanceds(Some(fee).into_iter().chain(Some(tip)));
+        }
+        Ok(())
+    }
+}
+
+impl<T: Config> Pallet<T> {
+    fn consume_fee_credit(who: &T::AccountId, value: CreditBalanceOf<T>) {
+        FeeCredit::<T>::mutate_exists(who, |maybe_credit| {
+            let remaining = maybe_credit.unwrap_or_default().saturating_sub(value);
+            *maybe_credit = if remaining.is_zero() {
+                None
+            } else {
+                Some(remaining)
+            };
+        });
+    }
+
+    fn restore_fee_credit(who: &T::AccountId, value: CreditBalanceOf<T>) {
+        FeeCredit::<T>::mutate(who, |credit| *credit = credit.saturating_add(value));
+    }
+}
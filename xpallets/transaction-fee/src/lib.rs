@@ -1,22 +1,42 @@
 // Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
 
 //! # Transaction Fee Module
+//!
+//! Besides the fee related events, this module maintains a per-account fee credit
+//! which can be topped up by any third party (the "gas station" pattern). The
+//! credit is consumed before the free balance when charging transaction fees and
+//! can not be transferred.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+mod impls;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
 mod types;
+pub mod weights;
 
+use frame_support::traits::{Currency, ExistenceRequirement, WithdrawReasons};
+use sp_runtime::traits::{Saturating, StaticLookup, Zero};
+
+pub use self::impls::FeeCreditAdapter;
 pub use self::types::FeeDetails;
+pub use self::weights::WeightInfo;
 pub use pallet_transaction_payment::InclusionFee;
 
 type BalanceOf<T> = <<T as pallet_transaction_payment::Config>::OnChargeTransaction as pallet_transaction_payment::OnChargeTransaction<T>>::Balance;
 
+pub(crate) type CreditBalanceOf<T> =
+    <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
 pub use pallet::*;
 
 #[frame_support::pallet]
 pub mod pallet {
     use super::*;
     use frame_support::pallet_prelude::*;
+    use frame_system::pallet_prelude::*;
 
     #[pallet::pallet]
     #[pallet::generate_store(pub(crate) trait Store)]
@@ -26,14 +46,66 @@ pub mod pallet {
     #[pallet::config]
     pub trait Config: frame_system::Config + pallet_transaction_payment::Config {
         type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+        /// The native currency used for paying the transaction fee.
+        type Currency: Currency<Self::AccountId>;
+
+        /// Weight information for extrinsics in this pallet.
+        type WeightInfo: WeightInfo;
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Grant some fee credit to `who`, paid by burning the native token of the granter.
+        ///
+        /// The credit can only be used for paying the transaction fee of `who`.
+        #[pallet::weight(<T as Config>::WeightInfo::grant_fee_credit())]
+        pub fn grant_fee_credit(
+            origin: OriginFor<T>,
+            who: <T::Lookup as StaticLookup>::Source,
+            #[pallet::compact] value: CreditBalanceOf<T>,
+        ) -> DispatchResult {
+            let granter = ensure_signed(origin)?;
+            let who = T::Lookup::lookup(who)?;
+            ensure!(!value.is_zero(), Error::<T>::ZeroFeeCredit);
+
+            // The withdrawn imbalance is dropped, i.e., burned.
+            let _ = T::Currency::withdraw(
+                &granter,
+                value,
+                WithdrawReasons::TRANSFER,
+                ExistenceRequirement::KeepAlive,
+            )?;
+            FeeCredit::<T>::mutate(&who, |credit| *credit = credit.saturating_add(value));
+
+            Self::deposit_event(Event::<T>::FeeCreditGranted(granter, who, value));
+            Ok(())
+        }
     }
 
     #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
         /// Transaction fee was paid to the block author and its reward pot in 1:9.
         /// [author, author_fee, reward_pot, reward_pot_fee]
         FeePaid(T::AccountId, BalanceOf<T>, T::AccountId, BalanceOf<T>),
         /// Transaction BTC fee
         BTCFeePaid(T::AccountId, u128),
+        /// Some fee credit was granted to an account. [granter, who, value]
+        FeeCreditGranted(T::AccountId, T::AccountId, CreditBalanceOf<T>),
+        /// Some fee credit was consumed by the transaction fee. [who, value]
+        FeeCreditConsumed(T::AccountId, CreditBalanceOf<T>),
     }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// The fee credit to grant can not be zero.
+        ZeroFeeCredit,
+    }
+
+    /// The fee credit of an account, consumed before the free balance when paying fees.
+    #[pallet::storage]
+    #[pallet::getter(fn fee_credit_of)]
+    pub(crate) type FeeCredit<T: Config> =
+        StorageMap<_, Twox64Concat, T::AccountId, CreditBalanceOf<T>, ValueQuery>;
 }
@@ -0,0 +1,153 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+use super::mock::*;
+use super::*;
+
+use frame_support::{
+    assert_noop, assert_ok,
+    weights::{DispatchInfo, Pays, PostDispatchInfo},
+};
+use sp_runtime::traits::SignedExtension;
+
+use pallet_transaction_payment::ChargeTransactionPayment;
+
+const CALL: &<Test as frame_system::Config>::Call =
+    &Call::Balances(pallet_balances::Call::transfer { dest: 2, value: 69 });
+
+/// Charges the fee of a transaction with length `len` and (dispatch weight, actual weight).
+fn charge_fee(who: AccountId, len: usize, weight: u64, actual_weight: u64) {
+    let info = DispatchInfo {
+        weight,
+        ..Default::default()
+    };
+    let post_info = PostDispatchInfo {
+        actual_weight: Some(actual_weight),
+        pays_fee: Pays::Yes,
+    };
+    let pre = ChargeTransactionPayment::<Test>::from(0)
+        .pre_dispatch(&who, CALL, &info, len)
+        .unwrap();
+    assert_ok!(ChargeTransactionPayment::<Test>::post_dispatch(
+        pre,
+        &info,
+        &post_info,
+        len,
+        &Ok(())
+    ));
+}
+
+fn last_event() -> Event {
+    System::events().pop().expect("Event expected").event
+}
+
+fn has_event(event: crate::Event<Test>) -> bool {
+    System::events()
+        .iter()
+        .any(|record| record.event == Event::XTransactionFee(event.clone()))
+}
+
+#[test]
+fn grant_fee_credit_should_work() {
+    ExtBuilder::default().build_and_execute(|| {
+        let total_issuance = Balances::total_issuance();
+
+        assert_noop!(
+            XTransactionFee::grant_fee_credit(Origin::signed(BOB), ALICE, 0),
+            Error::<Test>::ZeroFeeCredit
+        );
+        assert_ok!(XTransactionFee::grant_fee_credit(
+            Origin::signed(BOB),
+            ALICE,
+            20
+        ));
+        assert_eq!(
+            last_event(),
+            Event::XTransactionFee(crate::Event::FeeCreditGranted(BOB, ALICE, 20))
+        );
+
+        // The granted value is burned.
+        assert_eq!(Balances::free_balance(BOB), 80);
+        assert_eq!(Balances::total_issuance(), total_issuance - 20);
+        assert_eq!(XTransactionFee::fee_credit_of(ALICE), 20);
+        assert_eq!(Balances::free_balance(ALICE), 0);
+    });
+}
+
+#[test]
+fn sponsored_account_should_pay_fee_by_credit_only() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XTransactionFee::grant_fee_credit(
+            Origin::signed(BOB),
+            ALICE,
+            20
+        ));
+
+        // fee = len + weight = 5 + 10, 5 of it is refunded by the actual weight.
+        charge_fee(ALICE, 5, 10, 5);
+        assert!(has_event(crate::Event::FeeCreditConsumed(ALICE, 10)));
+        assert_eq!(XTransactionFee::fee_credit_of(ALICE), 10);
+        assert_eq!(Balances::free_balance(ALICE), 0);
+        assert_eq!(Balances::free_balance(FEE_RECEIVER), 10);
+
+        // The credit is not enough and ALICE has no native token.
+        let info = DispatchInfo {
+            weight: 10,
+            ..Default::default()
+        };
+        assert!(ChargeTransactionPayment::<Test>::from(0)
+            .pre_dispatch(&ALICE, CALL, &info, 5)
+            .is_err());
+        assert_eq!(XTransactionFee::fee_credit_of(ALICE), 10);
+    });
+}
+
+#[test]
+fn exhausted_credit_should_fall_back_to_balance() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XTransactionFee::grant_fee_credit(
+            Origin::signed(BOB),
+            CHARLIE,
+            5
+        ));
+
+        // 5 from the credit and 10 from the free balance.
+        charge_fee(CHARLIE, 5, 10, 10);
+        assert!(has_event(crate::Event::FeeCreditConsumed(CHARLIE, 5)));
+        assert_eq!(XTransactionFee::fee_credit_of(CHARLIE), 0);
+        assert_eq!(Balances::free_balance(CHARLIE), 90);
+        assert_eq!(Balances::free_balance(FEE_RECEIVER), 15);
+
+        // The credit is used up, the fee is paid by the free balance entirely.
+        charge_fee(CHARLIE, 5, 10, 10);
+        assert_eq!(Balances::free_balance(CHARLIE), 75);
+        assert_eq!(Balances::free_balance(FEE_RECEIVER), 30);
+    });
+}
+
+#[test]
+fn refund_should_go_back_to_balance_first() {
+    ExtBuilder::default().build_and_execute(|| {
+        assert_ok!(XTransactionFee::grant_fee_credit(
+            Origin::signed(BOB),
+            CHARLIE,
+            5
+        ));
+
+        // 5 from the credit and 10 from the free balance, 8 is refunded.
+        charge_fee(CHARLIE, 5, 10, 2);
+        assert_eq!(XTransactionFee::fee_credit_of(CHARLIE), 0);
+        assert_eq!(Balances::free_balance(CHARLIE), 98);
+        assert_eq!(Balances::free_balance(FEE_RECEIVER), 7);
+
+        // Refund more than the part paid by the free balance.
+        assert_ok!(XTransactionFee::grant_fee_credit(
+            Origin::signed(BOB),
+            CHARLIE,
+            10
+        ));
+        charge_fee(CHARLIE, 5, 10, 0);
+        assert_eq!(XTransactionFee::fee_credit_of(CHARLIE), 5);
+        assert_eq!(Balances::free_balance(CHARLIE), 98);
+        assert_eq!(Balances::free_balance(FEE_RECEIVER), 12);
+    });
+}
@@ -0,0 +1,41 @@
+// Copyright 2019-2023 ChainX Project Authors. Licensed under GPL-3.0.
+
+//! Weights for xpallet_transaction_fee
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(clippy::unnecessary_cast)]
+
+use frame_support::{
+    traits::Get,
+    weights::{constants::RocksDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for xpallet_transaction_fee.
+pub trait WeightInfo {
+    fn grant_fee_credit() -> Weight;
+}
+
+/// Weights for xpallet_transaction_fee using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+    // Estimated from the storage accesses, it must be regenerated once `grant_fee_credit` is
+    // benchmarked.
+    fn grant_fee_credit() -> Weight {
+        (42_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+    // Estimated from the storage accesses, it must be regenerated once `grant_fee_credit` is
+    // benchmarked.
+    fn grant_fee_credit() -> Weight {
+        (42_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+}